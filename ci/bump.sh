@@ -62,12 +62,17 @@ function msg {
 	fi
 }
 
+# NOTE: `ethcontract-core` is versioned independently from the rest of the
+#   workspace, so neither its manifest nor dependencies on it are bumped.
 msg "Updating Cargo manifests with new version '$version':"
 for manifest in ethcontract*/Cargo.toml; do
+	if [[ $manifest == ethcontract-core/Cargo.toml ]]; then
+		continue
+	fi
 	msg "  - $manifest"
 	if [[ $(uname) == Darwin ]]; then
-		sed -i '' -E -e 's/^((ethcontract(-[a-z]+)? = \{ )?version) = "[0-9\.]+"/\1 = "'"$version"'"/g' "$manifest"
+		sed -i '' -E -e 's/^((ethcontract(-(common|derive|generate|mock))? = \{ )?version) = "[0-9\.]+"/\1 = "'"$version"'"/g' "$manifest"
 	else
-		sed -i -E -e 's/^((ethcontract(-[a-z]+)? = \{ )?version) = "[0-9\.]+"/\1 = "'"$version"'"/g' "$manifest"
+		sed -i -E -e 's/^((ethcontract(-(common|derive|generate|mock))? = \{ )?version) = "[0-9\.]+"/\1 = "'"$version"'"/g' "$manifest"
 	fi
 done
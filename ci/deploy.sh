@@ -71,6 +71,12 @@ function cargo_publish {
 	fi
 }
 
+# NOTE: `ethcontract-core` is versioned independently, so only publish it when
+#   its current version is not yet available on `crates.io`.
+core_version=$(cat ethcontract-core/Cargo.toml | grep '^version' | sed -n 's/version = "\(.*\)"/\1/p')
+if ! curl -Ifs "https://crates.io/api/v1/crates/ethcontract-core/$core_version/download" > /dev/null; then
+	(cd ethcontract-core; cargo publish $options)
+fi
 cargo_publish ethcontract-common
 cargo_publish ethcontract-generate
 cargo_publish ethcontract-derive
@@ -1,9 +1,11 @@
 //! This module implements extensions to the `ethabi` API.
 
-use crate::abi::{Event, Function, ParamType};
+use crate::abi::{Event, Function, Param, ParamType};
 use crate::errors::ParseParamTypeError;
 use crate::hash::{self, H32};
+use crate::Abi;
 use serde_json::json;
+use web3::types::H256;
 
 /// Extension trait for `ethabi::Function`.
 pub trait FunctionExt {
@@ -36,21 +38,102 @@ pub trait EventExt {
     /// hash of this value is the actual event signature that is used as topic0
     /// in the transaction logs.
     fn abi_signature(&self) -> String;
+
+    /// Computes the canonical event signature, that is the event name followed
+    /// by its parameter types. Unlike [`abi_signature`](Self::abi_signature),
+    /// this never includes an `anonymous` suffix.
+    fn canonical_signature(&self) -> String;
+
+    /// Computes the topic0 hash used to identify logs for this event, or
+    /// `None` for anonymous events which don't have one.
+    fn topic0(&self) -> Option<H256>;
+
+    /// Returns true if the event has the specified name and parameter types.
+    fn matches_signature(&self, name: &str, types: &[ParamType]) -> bool;
 }
 
 impl EventExt for Event {
     fn abi_signature(&self) -> String {
         format!(
-            "{}({}){}",
+            "{}{}",
+            self.canonical_signature(),
+            if self.anonymous { " anonymous" } else { "" },
+        )
+    }
+
+    fn canonical_signature(&self) -> String {
+        format!(
+            "{}({})",
             self.name,
             self.inputs
                 .iter()
                 .map(|input| input.kind.to_string())
                 .collect::<Vec<_>>()
                 .join(","),
-            if self.anonymous { " anonymous" } else { "" },
         )
     }
+
+    fn topic0(&self) -> Option<H256> {
+        if self.anonymous {
+            None
+        } else {
+            Some(H256(hash::keccak256(self.canonical_signature())))
+        }
+    }
+
+    fn matches_signature(&self, name: &str, types: &[ParamType]) -> bool {
+        self.name == name
+            && self.inputs.len() == types.len()
+            && self
+                .inputs
+                .iter()
+                .zip(types)
+                .all(|(input, kind)| input.kind == *kind)
+    }
+}
+
+/// Extension trait for `ethabi::Contract`.
+pub trait AbiExt {
+    /// Finds an event by name and parameter types. This resolves overloaded
+    /// events which can't be looked up by name alone.
+    fn event_by_signature(&self, name: &str, types: &[ParamType]) -> Option<&Event>;
+
+    /// Finds the non-anonymous event with the specified topic0 hash.
+    fn event_by_topic0(&self, topic: H256) -> Option<&Event>;
+
+    /// Computes the ERC-165 interface ID of the ABI, that is the XOR of the
+    /// selectors of all its functions.
+    ///
+    /// The ERC-165 `supportsInterface(bytes4)` function is excluded, which
+    /// matches Solidity's `type(I).interfaceId` for interfaces inheriting from
+    /// `IERC165`.
+    fn interface_id(&self) -> H32;
+}
+
+impl AbiExt for Abi {
+    fn event_by_signature(&self, name: &str, types: &[ParamType]) -> Option<&Event> {
+        self.events
+            .get(name)?
+            .iter()
+            .find(|event| event.matches_signature(name, types))
+    }
+
+    fn event_by_topic0(&self, topic: H256) -> Option<&Event> {
+        self.events().find(|event| event.topic0() == Some(topic))
+    }
+
+    fn interface_id(&self) -> H32 {
+        let erc165 = hash::function_selector("supportsInterface(bytes4)");
+        self.functions()
+            .map(FunctionExt::selector)
+            .filter(|selector| *selector != erc165)
+            .fold([0; 4], |mut id, selector| {
+                for (byte, selector_byte) in id.iter_mut().zip(selector) {
+                    *byte ^= selector_byte;
+                }
+                id
+            })
+    }
 }
 
 /// An extension trait for Solidity parameter types.
@@ -63,6 +146,27 @@ pub trait ParamTypeExt {
 
 impl ParamTypeExt for ParamType {}
 
+/// Extension trait for `ethabi::Param`.
+pub trait ParamExt {
+    /// Returns the qualified name of the Solidity struct this parameter is
+    /// declared with, based on its internal type. For example, a parameter
+    /// with internal type `struct Lib.Order[]` returns `Lib.Order`.
+    fn struct_name(&self) -> Option<&str>;
+}
+
+impl ParamExt for Param {
+    fn struct_name(&self) -> Option<&str> {
+        struct_name(self.internal_type.as_deref()?)
+    }
+}
+
+/// Extracts the qualified struct name from an internal type, stripping any
+/// array suffixes.
+pub(crate) fn struct_name(internal_type: &str) -> Option<&str> {
+    let name = internal_type.strip_prefix("struct ")?;
+    Some(name.split('[').next().unwrap_or(name))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -112,4 +216,91 @@ mod tests {
             assert_eq!(signature, *expected);
         }
     }
+
+    #[test]
+    fn event_topics() {
+        let event: Event = serde_json::from_str(
+            r#"{"name":"Transfer","inputs":[{"name":"from","type":"address","indexed":true},{"name":"to","type":"address","indexed":true},{"name":"value","type":"uint256","indexed":false}],"anonymous":false}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            event.canonical_signature(),
+            "Transfer(address,address,uint256)"
+        );
+        assert_eq!(event.topic0(), Some(event.signature()));
+        assert_eq!(
+            event.topic0().unwrap(),
+            crate::parse_h256("0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef")
+                .unwrap()
+        );
+
+        let anonymous = Event {
+            anonymous: true,
+            ..event
+        };
+        assert_eq!(
+            anonymous.canonical_signature(),
+            "Transfer(address,address,uint256)"
+        );
+        assert_eq!(anonymous.topic0(), None);
+    }
+
+    #[test]
+    fn find_overloaded_events() {
+        let abi: Abi = serde_json::from_str(
+            r#"[
+                {"type":"event","name":"Foo","inputs":[{"name":"a","type":"uint256","indexed":false}],"anonymous":false},
+                {"type":"event","name":"Foo","inputs":[{"name":"a","type":"bool","indexed":false}],"anonymous":false},
+                {"type":"event","name":"Bar","inputs":[],"anonymous":true}
+            ]"#,
+        )
+        .unwrap();
+
+        let foo = abi.event_by_signature("Foo", &[ParamType::Bool]).unwrap();
+        assert_eq!(foo.canonical_signature(), "Foo(bool)");
+        assert_eq!(abi.event_by_topic0(foo.topic0().unwrap()), Some(foo));
+        assert!(abi
+            .event_by_signature("Foo", &[ParamType::Address])
+            .is_none());
+        assert!(abi.event_by_signature("Bar", &[]).is_some());
+        assert!(abi.event_by_signature("Baz", &[]).is_none());
+    }
+
+    #[test]
+    fn param_struct_name() {
+        let param = |internal_type: Option<&str>| Param {
+            name: "order".into(),
+            kind: ParamType::Tuple(vec![ParamType::Address]),
+            internal_type: internal_type.map(String::from),
+        };
+
+        assert_eq!(param(Some("struct Order")).struct_name(), Some("Order"));
+        assert_eq!(
+            param(Some("struct Lib.Order[][2]")).struct_name(),
+            Some("Lib.Order")
+        );
+        assert_eq!(param(Some("address")).struct_name(), None);
+        assert_eq!(param(None).struct_name(), None);
+    }
+
+    #[test]
+    fn erc721_interface_id() {
+        let abi: Abi = serde_json::from_str(
+            r#"[
+                {"type":"function","name":"balanceOf","inputs":[{"name":"","type":"address"}],"outputs":[]},
+                {"type":"function","name":"ownerOf","inputs":[{"name":"","type":"uint256"}],"outputs":[]},
+                {"type":"function","name":"safeTransferFrom","inputs":[{"name":"","type":"address"},{"name":"","type":"address"},{"name":"","type":"uint256"},{"name":"","type":"bytes"}],"outputs":[]},
+                {"type":"function","name":"safeTransferFrom","inputs":[{"name":"","type":"address"},{"name":"","type":"address"},{"name":"","type":"uint256"}],"outputs":[]},
+                {"type":"function","name":"transferFrom","inputs":[{"name":"","type":"address"},{"name":"","type":"address"},{"name":"","type":"uint256"}],"outputs":[]},
+                {"type":"function","name":"approve","inputs":[{"name":"","type":"address"},{"name":"","type":"uint256"}],"outputs":[]},
+                {"type":"function","name":"setApprovalForAll","inputs":[{"name":"","type":"address"},{"name":"","type":"bool"}],"outputs":[]},
+                {"type":"function","name":"getApproved","inputs":[{"name":"","type":"uint256"}],"outputs":[]},
+                {"type":"function","name":"isApprovedForAll","inputs":[{"name":"","type":"address"},{"name":"","type":"address"}],"outputs":[]},
+                {"type":"function","name":"supportsInterface","inputs":[{"name":"","type":"bytes4"}],"outputs":[]}
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(abi.interface_id(), [0x80, 0xac, 0x58, 0xcd]);
+    }
 }
@@ -9,12 +9,14 @@
 //! sources, and parse them using different formats.
 
 use crate::contract::{Documentation, Interface, Network};
+use crate::errors::ArtifactError;
 use crate::{Abi, Bytecode, Contract};
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 use std::ops::Deref;
 use std::sync::Arc;
 
+pub mod abi;
 pub mod hardhat;
 pub mod truffle;
 
@@ -128,6 +130,96 @@ impl Artifact {
     pub fn drain(&mut self) -> impl Iterator<Item = Contract> + '_ {
         self.contracts.drain().map(|(_, contract)| contract)
     }
+
+    /// Merges contracts from another artifact into this one.
+    ///
+    /// Contracts with the same name, ABI and bytecode are deduplicated,
+    /// and their deployments on different networks are combined. Contracts
+    /// with the same name but a different ABI or bytecode, as well as
+    /// deployments of the same contract at different addresses on the same
+    /// network, are conflicts that are resolved with the given policy.
+    ///
+    /// With [`MergePolicy::ErrorOnConflict`], this artifact is left unchanged
+    /// if the artifacts conflict.
+    pub fn merge(&mut self, mut other: Artifact, policy: MergePolicy) -> Result<(), ArtifactError> {
+        if policy == MergePolicy::ErrorOnConflict {
+            if let Some(name) = other
+                .iter()
+                .find(|contract| self.conflicts_with(contract))
+                .map(|contract| contract.name.clone())
+            {
+                return Err(ArtifactError::MergeConflict(name));
+            }
+        }
+
+        for contract in other.drain() {
+            let existing = match self.contracts.get_mut(&contract.name) {
+                Some(existing) => existing,
+                None => {
+                    self.insert(contract);
+                    continue;
+                }
+            };
+
+            if !same_code(existing, &contract) {
+                if policy == MergePolicy::Overwrite {
+                    *existing = contract;
+                }
+                continue;
+            }
+
+            for (chain_id, network) in contract.networks {
+                match existing.networks.entry(chain_id) {
+                    Entry::Vacant(entry) => {
+                        entry.insert(network);
+                    }
+                    Entry::Occupied(mut entry) => {
+                        if policy == MergePolicy::Overwrite {
+                            entry.insert(network);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns `true` if a contract conflicts with the contract of the same
+    /// name in this artifact.
+    fn conflicts_with(&self, contract: &Contract) -> bool {
+        let existing = match self.get(&contract.name) {
+            Some(existing) => existing,
+            None => return false,
+        };
+
+        !same_code(existing, contract)
+            || contract.networks.iter().any(|(chain_id, network)| {
+                matches!(
+                    existing.networks.get(chain_id),
+                    Some(existing) if existing.address != network.address
+                )
+            })
+    }
+}
+
+/// Returns `true` if two contracts have the same ABI and bytecode.
+fn same_code(a: &Contract, b: &Contract) -> bool {
+    a.interface.abi == b.interface.abi
+        && a.bytecode == b.bytecode
+        && a.deployed_bytecode == b.deployed_bytecode
+}
+
+/// How conflicting contracts are resolved when merging artifacts, see
+/// [`Artifact::merge`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MergePolicy {
+    /// Keep the contract or deployment of the artifact being merged into.
+    KeepExisting,
+    /// Replace the existing contract or deployment with the merged one.
+    Overwrite,
+    /// Fail the merge.
+    ErrorOnConflict,
 }
 
 impl Default for Artifact {
@@ -204,6 +296,7 @@ impl Drop for ContractMut<'_> {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::Address;
 
     fn make_contract(name: &str) -> Contract {
         let mut contract = Contract::empty();
@@ -274,4 +367,115 @@ mod test {
         assert!(!artifact.contains("C1"));
         assert!(artifact.contains("C2"));
     }
+
+    fn make_deployed_contract(name: &str, code: &str, networks: &[(&str, u8)]) -> Contract {
+        let mut contract = make_contract(name);
+        contract.bytecode = Bytecode::from_hex_str(code).unwrap();
+        for (chain_id, address) in networks {
+            contract.networks.insert(
+                chain_id.to_string(),
+                Network {
+                    address: Address::repeat_byte(*address),
+                    deployment_information: None,
+                },
+            );
+        }
+        contract
+    }
+
+    fn make_artifact(contracts: Vec<Contract>) -> Artifact {
+        let mut artifact = Artifact::new();
+        for contract in contracts {
+            artifact.insert(contract);
+        }
+        artifact
+    }
+
+    fn address(artifact: &Artifact, name: &str, chain_id: &str) -> Option<Address> {
+        Some(artifact.get(name)?.networks.get(chain_id)?.address)
+    }
+
+    #[test]
+    fn merge_dedups_identical_contracts() {
+        let mut artifact = make_artifact(vec![
+            make_deployed_contract("C1", "0x01", &[("1", 1)]),
+            make_deployed_contract("C2", "0x02", &[]),
+        ]);
+        let other = make_artifact(vec![
+            make_deployed_contract("C1", "0x01", &[("1", 1), ("5", 5)]),
+            make_deployed_contract("C3", "0x03", &[]),
+        ]);
+
+        artifact.merge(other, MergePolicy::ErrorOnConflict).unwrap();
+
+        assert_eq!(artifact.len(), 3);
+        assert_eq!(address(&artifact, "C1", "1"), Some(Address::repeat_byte(1)));
+        assert_eq!(address(&artifact, "C1", "5"), Some(Address::repeat_byte(5)));
+        assert!(artifact.contains("C2"));
+        assert!(artifact.contains("C3"));
+    }
+
+    #[test]
+    fn merge_keep_existing() {
+        let mut artifact = make_artifact(vec![
+            make_deployed_contract("C1", "0x01", &[]),
+            make_deployed_contract("C2", "0x02", &[("1", 1)]),
+        ]);
+        let other = make_artifact(vec![
+            make_deployed_contract("C1", "0x11", &[]),
+            make_deployed_contract("C2", "0x02", &[("1", 2), ("5", 5)]),
+        ]);
+
+        artifact.merge(other, MergePolicy::KeepExisting).unwrap();
+
+        assert_eq!(
+            artifact.get("C1").unwrap().bytecode,
+            Bytecode::from_hex_str("0x01").unwrap()
+        );
+        assert_eq!(address(&artifact, "C2", "1"), Some(Address::repeat_byte(1)));
+        assert_eq!(address(&artifact, "C2", "5"), Some(Address::repeat_byte(5)));
+    }
+
+    #[test]
+    fn merge_overwrite() {
+        let mut artifact = make_artifact(vec![
+            make_deployed_contract("C1", "0x01", &[("1", 1)]),
+            make_deployed_contract("C2", "0x02", &[("1", 1)]),
+        ]);
+        let other = make_artifact(vec![
+            make_deployed_contract("C1", "0x11", &[]),
+            make_deployed_contract("C2", "0x02", &[("1", 2)]),
+        ]);
+
+        artifact.merge(other, MergePolicy::Overwrite).unwrap();
+
+        let c1 = artifact.get("C1").unwrap();
+        assert_eq!(c1.bytecode, Bytecode::from_hex_str("0x11").unwrap());
+        assert!(c1.networks.is_empty());
+        assert_eq!(address(&artifact, "C2", "1"), Some(Address::repeat_byte(2)));
+    }
+
+    #[test]
+    fn merge_error_on_conflict() {
+        let mut artifact = make_artifact(vec![make_deployed_contract("C1", "0x01", &[("1", 1)])]);
+
+        let different_code = make_artifact(vec![
+            make_deployed_contract("C0", "0x00", &[]),
+            make_deployed_contract("C1", "0x11", &[]),
+        ]);
+        let err = artifact
+            .merge(different_code, MergePolicy::ErrorOnConflict)
+            .unwrap_err();
+        assert!(matches!(err, ArtifactError::MergeConflict(name) if name == "C1"));
+
+        let different_address =
+            make_artifact(vec![make_deployed_contract("C1", "0x01", &[("1", 2)])]);
+        let err = artifact
+            .merge(different_address, MergePolicy::ErrorOnConflict)
+            .unwrap_err();
+        assert!(matches!(err, ArtifactError::MergeConflict(name) if name == "C1"));
+
+        assert_eq!(artifact.len(), 1);
+        assert_eq!(address(&artifact, "C1", "1"), Some(Address::repeat_byte(1)));
+    }
 }
@@ -0,0 +1,206 @@
+//! Implements loading of bare ABI files, as emitted by `solc --abi` and many
+//! other tools.
+//!
+//! These files contain just the JSON array describing the contract's
+//! interface, without a contract name, bytecode or deployment information.
+//! Loaded contracts are therefore unnamed unless a name is provided, and
+//! have empty bytecode.
+
+use crate::artifact::Artifact;
+use crate::contract::Interface;
+use crate::errors::ArtifactError;
+use crate::Contract;
+use serde_json::{from_reader, from_slice, from_str, from_value, Value};
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Loads bare ABI files.
+#[must_use = "abi loaders do nothing unless you load them"]
+pub struct AbiLoader {
+    /// Override for artifact's origin.
+    ///
+    /// If empty, origin will be derived automatically.
+    pub origin: Option<String>,
+
+    /// Name of the loaded contract.
+    ///
+    /// ABI files don't contain contract names, so contracts are unnamed
+    /// unless this is set.
+    pub name: Option<String>,
+}
+
+impl AbiLoader {
+    /// Creates a new ABI loader.
+    pub fn new() -> Self {
+        AbiLoader {
+            origin: None,
+            name: None,
+        }
+    }
+
+    /// Creates a new ABI loader and sets an override for artifact's origins.
+    pub fn with_origin(origin: impl Into<String>) -> Self {
+        AbiLoader {
+            origin: Some(origin.into()),
+            name: None,
+        }
+    }
+
+    /// Sets new override for artifact's origin. See [`origin`] for more info.
+    ///
+    /// [`origin`]: #structfield.origin
+    pub fn origin(mut self, origin: impl Into<String>) -> Self {
+        self.origin = Some(origin.into());
+        self
+    }
+
+    /// Sets the name of the loaded contract. See [`name`] for more info.
+    ///
+    /// [`name`]: #structfield.name
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Loads an artifact from an [`io::Read`](std::io::Read) of JSON text,
+    /// such as an open ABI file.
+    pub fn load_from_reader(&self, v: impl Read) -> Result<Artifact, ArtifactError> {
+        self.load_artifact("<unknown>", v, from_reader)
+    }
+
+    /// Loads an artifact from bytes of JSON text.
+    pub fn load_from_slice(&self, v: &[u8]) -> Result<Artifact, ArtifactError> {
+        self.load_artifact("<unknown>", v, from_slice)
+    }
+
+    /// Loads an artifact from string of JSON text.
+    pub fn load_from_str(&self, v: &str) -> Result<Artifact, ArtifactError> {
+        self.load_artifact("<unknown>", v, from_str)
+    }
+
+    /// Loads an artifact from a loaded JSON value.
+    pub fn load_from_value(&self, v: Value) -> Result<Artifact, ArtifactError> {
+        self.load_artifact("<unknown>", v, from_value)
+    }
+
+    /// Loads an artifact from disk.
+    pub fn load_from_file(&self, p: impl AsRef<Path>) -> Result<Artifact, ArtifactError> {
+        let path = p.as_ref();
+        let file = File::open(path)?;
+        let reader = BufReader::new(file);
+        self.load_artifact(path.display(), reader, from_reader)
+    }
+
+    /// Loads a contract from a loaded JSON value.
+    pub fn load_contract_from_reader(&self, v: impl Read) -> Result<Contract, ArtifactError> {
+        self.load_contract(v, from_reader)
+    }
+
+    /// Loads a contract from bytes of JSON text.
+    pub fn load_contract_from_slice(&self, v: &[u8]) -> Result<Contract, ArtifactError> {
+        self.load_contract(v, from_slice)
+    }
+
+    /// Loads a contract from string of JSON text.
+    pub fn load_contract_from_str(&self, v: &str) -> Result<Contract, ArtifactError> {
+        self.load_contract(v, from_str)
+    }
+
+    /// Loads a contract from a loaded JSON value.
+    pub fn load_contract_from_value(&self, v: Value) -> Result<Contract, ArtifactError> {
+        self.load_contract(v, from_value)
+    }
+
+    /// Loads a contract from disk.
+    pub fn load_contract_from_file(&self, p: impl AsRef<Path>) -> Result<Contract, ArtifactError> {
+        let path = p.as_ref();
+        let file = File::open(path)?;
+        let reader = BufReader::new(file);
+        self.load_contract(reader, from_reader)
+    }
+
+    fn load_artifact<T>(
+        &self,
+        origin: impl ToString,
+        source: T,
+        loader: impl FnOnce(T) -> serde_json::Result<Interface>,
+    ) -> Result<Artifact, ArtifactError> {
+        let origin = self.origin.clone().unwrap_or_else(|| origin.to_string());
+        let mut artifact = Artifact::with_origin(origin);
+        artifact.insert(self.load_contract(source, loader)?);
+        Ok(artifact)
+    }
+
+    fn load_contract<T>(
+        &self,
+        source: T,
+        loader: impl FnOnce(T) -> serde_json::Result<Interface>,
+    ) -> Result<Contract, ArtifactError> {
+        let mut contract = Contract::with_name(self.name.clone().unwrap_or_default());
+        contract.interface = Arc::new(loader(source)?);
+        Ok(contract)
+    }
+}
+
+impl Default for AbiLoader {
+    fn default() -> Self {
+        AbiLoader::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ABI: &str = r#"[
+        {
+            "type": "function",
+            "name": "balanceOf",
+            "inputs": [{ "name": "owner", "type": "address" }],
+            "outputs": [{ "name": "", "type": "uint256" }],
+            "stateMutability": "view"
+        },
+        {
+            "type": "event",
+            "name": "Transfer",
+            "inputs": [
+                { "name": "from", "type": "address", "indexed": true },
+                { "name": "to", "type": "address", "indexed": true },
+                { "name": "value", "type": "uint256", "indexed": false }
+            ],
+            "anonymous": false
+        }
+    ]"#;
+
+    #[test]
+    fn load_contract() {
+        let contract = AbiLoader::new()
+            .name("Token")
+            .load_contract_from_str(ABI)
+            .unwrap();
+
+        assert_eq!(contract.name, "Token");
+        assert!(contract.interface.abi.function("balanceOf").is_ok());
+        assert!(contract.interface.abi.event("Transfer").is_ok());
+        assert!(contract.bytecode.is_empty());
+        assert!(contract.deployed_bytecode.is_empty());
+        assert!(contract.networks.is_empty());
+    }
+
+    #[test]
+    fn load_artifact() {
+        let artifact = AbiLoader::new().load_from_str(ABI).unwrap();
+
+        assert_eq!(artifact.origin(), "<unknown>");
+        assert_eq!(artifact.len(), 1);
+        assert!(artifact.get("").is_some());
+    }
+
+    #[test]
+    fn load_artifact_wrapper_error() {
+        let err = AbiLoader::new().load_contract_from_str(r#"{"abi": []}"#);
+        assert!(matches!(err, Err(ArtifactError::Json(_))));
+    }
+}
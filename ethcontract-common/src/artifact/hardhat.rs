@@ -28,9 +28,11 @@ use crate::artifact::Artifact;
 use crate::contract::Network;
 use crate::errors::ArtifactError;
 use crate::{Address, Contract, DeploymentInformation, TransactionHash};
+use serde::de::{DeserializeSeed, Deserializer, Error as _, IgnoredAny, MapAccess, Visitor};
 use serde::Deserialize;
-use serde_json::{from_reader, from_slice, from_str, from_value, Value};
-use std::collections::HashMap;
+use serde_json::de::{IoRead, SliceRead, StrRead};
+use serde_json::Value;
+use std::fmt::{Formatter, Result as FmtResult};
 use std::fs::File;
 use std::io::{BufReader, Read};
 use std::path::Path;
@@ -52,6 +54,15 @@ use std::path::Path;
 /// have to filter such networks. See [#545] for more info.
 ///
 /// [#545]: https://github.com/gnosis/ethcontract-rs/issues/545.
+///
+/// # Performance
+///
+/// Exports are parsed in a streaming fashion. Contracts and networks that
+/// are filtered out by the allow and deny lists are skipped without parsing
+/// their ABIs and bytecode, so use [`allow_contract`] when only a few
+/// contracts are needed from a large export.
+///
+/// [`allow_contract`]: HardHatLoader::allow_contract
 #[must_use = "hardhat loaders do nothing unless you load them"]
 pub struct HardHatLoader {
     /// Override for artifact's origin. If `None`, origin
@@ -184,22 +195,23 @@ impl HardHatLoader {
 
     /// Loads an artifact from a JSON value.
     pub fn load_from_reader(&self, f: Format, v: impl Read) -> Result<Artifact, ArtifactError> {
-        self.load_artifact(f, "<unknown>", v, from_reader, from_reader)
+        self.load_artifact(f, "<unknown>", IoRead::new(v))
     }
 
     /// Loads an artifact from bytes of JSON text.
     pub fn load_from_slice(&self, f: Format, v: &[u8]) -> Result<Artifact, ArtifactError> {
-        self.load_artifact(f, "<unknown>", v, from_slice, from_slice)
+        self.load_artifact(f, "<unknown>", SliceRead::new(v))
     }
 
     /// Loads an artifact from string of JSON text.
     pub fn load_from_str(&self, f: Format, v: &str) -> Result<Artifact, ArtifactError> {
-        self.load_artifact(f, "<unknown>", v, from_str, from_str)
+        self.load_artifact(f, "<unknown>", StrRead::new(v))
     }
 
     /// Loads an artifact from a loaded JSON value.
     pub fn load_from_value(&self, f: Format, v: Value) -> Result<Artifact, ArtifactError> {
-        self.load_artifact(f, "<unknown>", v, from_value, from_value)
+        let exports = ExportsSeed::new(self, f).deserialize(v)?;
+        self.fill_artifact_from_exports("<unknown>", exports)
     }
 
     /// Loads an artifact from disk.
@@ -211,7 +223,7 @@ impl HardHatLoader {
         let path = p.as_ref();
         let file = File::open(path)?;
         let reader = BufReader::new(file);
-        self.load_artifact(f, path.display(), reader, from_reader, from_reader)
+        self.load_artifact(f, path.display(), IoRead::new(reader))
     }
 
     /// Loads an artifact from `deployments` directory.
@@ -328,7 +340,7 @@ impl HardHatLoader {
                 } = {
                     let file = File::open(contract_path)?;
                     let reader = BufReader::new(file);
-                    from_reader(reader)?
+                    serde_json::from_reader(reader)?
                 };
 
                 contract.name = contract_name;
@@ -346,27 +358,38 @@ impl HardHatLoader {
         Ok(artifact)
     }
 
-    fn load_artifact<T>(
+    /// Loads an artifact from JSON text.
+    ///
+    /// The JSON is parsed in a streaming fashion, so that contracts and
+    /// networks that are filtered out by the loader are skipped without being
+    /// fully parsed. This makes loading a few contracts from large exports
+    /// considerably cheaper.
+    fn load_artifact<'de, R>(
         &self,
         format: Format,
         origin: impl ToString,
-        source: T,
-        single_loader: impl FnOnce(T) -> serde_json::Result<HardHatExport>,
-        multi_loader: impl FnOnce(T) -> serde_json::Result<HardHatMultiExport>,
+        read: R,
+    ) -> Result<Artifact, ArtifactError>
+    where
+        R: serde_json::de::Read<'de>,
+    {
+        let mut deserializer = serde_json::Deserializer::new(read);
+        let exports = ExportsSeed::new(self, format).deserialize(&mut deserializer)?;
+        deserializer.end()?;
+
+        self.fill_artifact_from_exports(origin, exports)
+    }
+
+    fn fill_artifact_from_exports(
+        &self,
+        origin: impl ToString,
+        exports: Vec<HardHatExport>,
     ) -> Result<Artifact, ArtifactError> {
         let origin = self.origin.clone().unwrap_or_else(|| origin.to_string());
 
         let mut artifact = Artifact::with_origin(origin);
-
-        match format {
-            Format::SingleExport => {
-                let loaded = single_loader(source)?;
-                self.fill_artifact(&mut artifact, loaded)?
-            }
-            Format::MultiExport => {
-                let loaded = multi_loader(source)?;
-                self.fill_artifact_multi(&mut artifact, loaded)?
-            }
+        for export in exports {
+            self.fill_artifact(&mut artifact, export)?;
         }
 
         Ok(artifact)
@@ -404,20 +427,6 @@ impl HardHatLoader {
         Ok(())
     }
 
-    fn fill_artifact_multi(
-        &self,
-        artifact: &mut Artifact,
-        export: HardHatMultiExport,
-    ) -> Result<(), ArtifactError> {
-        for (_, export) in export.networks {
-            for (_, export) in export {
-                self.fill_artifact(artifact, export)?;
-            }
-        }
-
-        Ok(())
-    }
-
     fn add_contract_to_artifact(
         &self,
         artifact: &mut Artifact,
@@ -524,23 +533,195 @@ impl NetworkEntry {
     }
 }
 
-#[derive(Deserialize)]
-struct HardHatMultiExport {
-    #[serde(flatten)]
-    networks: HashMap<String, HashMap<String, HardHatExport>>,
-}
-
-#[derive(Deserialize)]
+/// A single network export with the contracts allowed by the loader.
 struct HardHatExport {
-    #[serde(rename = "name")]
     chain_name: String,
-    #[serde(rename = "chainId")]
     chain_id: String,
-    contracts: HashMap<String, HardHatContract>,
+    contracts: Vec<(String, HardHatContract)>,
+}
+
+/// Deserializes the exports of an artifact in the specified format.
+///
+/// Networks and contracts that are not allowed by the loader are skipped
+/// with [`IgnoredAny`], avoiding the cost of parsing their ABIs and bytecode.
+#[derive(Clone, Copy)]
+struct ExportsSeed<'a> {
+    loader: &'a HardHatLoader,
+    format: Format,
+}
+
+impl<'a> ExportsSeed<'a> {
+    fn new(loader: &'a HardHatLoader, format: Format) -> Self {
+        ExportsSeed { loader, format }
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for ExportsSeed<'_> {
+    type Value = Vec<HardHatExport>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match self.format {
+            Format::SingleExport => Ok(vec![ExportSeed(self.loader).deserialize(deserializer)?]),
+            Format::MultiExport => deserializer.deserialize_map(self),
+        }
+    }
+}
+
+impl<'de> Visitor<'de> for ExportsSeed<'_> {
+    type Value = Vec<HardHatExport>;
+
+    fn expecting(&self, f: &mut Formatter) -> FmtResult {
+        f.write_str("a map of chain IDs to hardhat exports")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut exports = Vec::new();
+        while let Some(chain_id) = map.next_key::<String>()? {
+            let networks = map.next_value_seed(NetworksSeed {
+                loader: self.loader,
+                chain_id: &chain_id,
+            })?;
+            exports.extend(networks);
+        }
+
+        Ok(exports)
+    }
+}
+
+/// Deserializes the network exports for a single chain ID of a multi-export.
+struct NetworksSeed<'a> {
+    loader: &'a HardHatLoader,
+    chain_id: &'a str,
+}
+
+impl<'de> DeserializeSeed<'de> for NetworksSeed<'_> {
+    type Value = Vec<HardHatExport>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for NetworksSeed<'_> {
+    type Value = Vec<HardHatExport>;
+
+    fn expecting(&self, f: &mut Formatter) -> FmtResult {
+        f.write_str("a map of network names to hardhat exports")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut exports = Vec::new();
+        while let Some(chain_name) = map.next_key::<String>()? {
+            if self.loader.network_allowed(self.chain_id, &chain_name) {
+                exports.push(map.next_value_seed(ExportSeed(self.loader))?);
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+
+        Ok(exports)
+    }
+}
+
+/// Deserializes a single network export.
+struct ExportSeed<'a>(&'a HardHatLoader);
+
+impl<'de> DeserializeSeed<'de> for ExportSeed<'_> {
+    type Value = HardHatExport;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for ExportSeed<'_> {
+    type Value = HardHatExport;
+
+    fn expecting(&self, f: &mut Formatter) -> FmtResult {
+        f.write_str("a hardhat export")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let (mut chain_name, mut chain_id, mut contracts) = (None, None, None);
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "name" => chain_name = Some(map.next_value()?),
+                "chainId" => chain_id = Some(map.next_value()?),
+                "contracts" => contracts = Some(map.next_value_seed(ContractsSeed(self.0))?),
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+
+        Ok(HardHatExport {
+            chain_name: chain_name.ok_or_else(|| A::Error::missing_field("name"))?,
+            chain_id: chain_id.ok_or_else(|| A::Error::missing_field("chainId"))?,
+            contracts: contracts.ok_or_else(|| A::Error::missing_field("contracts"))?,
+        })
+    }
+}
+
+/// Deserializes the contracts of a network export, skipping contracts that
+/// are not allowed by the loader.
+struct ContractsSeed<'a>(&'a HardHatLoader);
+
+impl<'de> DeserializeSeed<'de> for ContractsSeed<'_> {
+    type Value = Vec<(String, HardHatContract)>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for ContractsSeed<'_> {
+    type Value = Vec<(String, HardHatContract)>;
+
+    fn expecting(&self, f: &mut Formatter) -> FmtResult {
+        f.write_str("a map of contract names to contracts")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut contracts = Vec::new();
+        while let Some(name) = map.next_key::<String>()? {
+            if self.0.contract_allowed(&name) {
+                contracts.push((name, map.next_value()?));
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+
+        Ok(contracts)
+    }
 }
 
 #[derive(Deserialize)]
 struct HardHatContract {
+    #[serde(deserialize_with = "crate::parse::deserialize_address")]
     address: Address,
     #[serde(rename = "transactionHash")]
     transaction_hash: Option<TransactionHash>,
@@ -916,6 +1097,50 @@ mod test {
         assert_eq!(artifact.len(), 0);
     }
 
+    #[test]
+    fn load_multi_skips_filtered_entries() {
+        let json = r#"
+          {
+            "1": {
+              "mainnet": {
+                "name": "mainnet",
+                "chainId": "1",
+                "contracts": {
+                  "A": {
+                    "address": "0x000000000000000000000000000000000000000A"
+                  },
+                  "B": {
+                    "address": "not an address",
+                    "abi": "not an abi"
+                  }
+                }
+              }
+            },
+            "4": {
+              "rinkeby": {
+                "contracts": null
+              }
+            }
+          }
+        "#;
+
+        let artifact = HardHatLoader::new()
+            .allow_contract("A")
+            .deny_network_by_chain_id("4")
+            .load_from_str(Format::MultiExport, json)
+            .unwrap();
+
+        assert_eq!(artifact.len(), 1);
+        assert_eq!(
+            artifact.get("A").unwrap().networks["1"].address,
+            address(0xA)
+        );
+
+        assert!(HardHatLoader::new()
+            .load_from_str(Format::MultiExport, json)
+            .is_err());
+    }
+
     #[test]
     fn load_multi_deny_contract_name() {
         let artifact = HardHatLoader::new()
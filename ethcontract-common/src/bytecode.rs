@@ -13,7 +13,7 @@ use web3::types::{Address, Bytes};
 /// The string representation of the byte code. Note that this must be a
 /// `String` since `solc` linking requires string manipulation of the
 /// bytecode string representation.
-#[derive(Clone, Debug, Default, Serialize)]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
 pub struct Bytecode(String);
 
 impl Bytecode {
@@ -93,6 +93,14 @@ impl Bytecode {
         self.undefined_libraries().next().is_some()
     }
 
+    /// Returns the length of the bytecode in bytes.
+    ///
+    /// Library placeholders have the same length as the addresses they are
+    /// replaced with, so this is also the length of the linked bytecode.
+    pub fn len(&self) -> usize {
+        self.0.len() / 2
+    }
+
     /// Returns true if the bytecode is an empty bytecode.
     pub fn is_empty(&self) -> bool {
         self.0.is_empty()
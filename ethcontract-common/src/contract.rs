@@ -1,14 +1,19 @@
 //! Module for reading and examining data produced by truffle.
 
-use crate::abiext::FunctionExt;
+use crate::abi::{
+    AbiError, Constructor, Event, EventParam, Function, Param, ParamType, StateMutability,
+};
+use crate::abiext::{self, FunctionExt};
 use crate::hash::H32;
 use crate::Abi;
 use crate::{bytecode::Bytecode, DeploymentInformation};
 use ethabi::ethereum_types::H256;
+use serde::de::{Error as _, SeqAccess, Visitor};
 use serde::Deserializer;
 use serde::Serializer;
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashMap};
+use std::fmt;
 use std::hash::Hash;
 use std::sync::Arc;
 use web3::types::Address;
@@ -48,6 +53,25 @@ pub struct Interface {
     /// A mapping from event signature to a name-index pair for resolving
     /// events in the contract ABI.
     pub events: HashMap<H256, (String, usize)>,
+    /// The fields of Solidity structs used as tuple parameters in the ABI,
+    /// keyed by their qualified name, such as `Order` or `Lib.Order`. Since
+    /// the parsed ABI does not retain tuple component names, this is only
+    /// available when the interface is deserialized from a JSON ABI with
+    /// internal types.
+    pub structs: BTreeMap<String, Vec<Param>>,
+}
+
+impl Interface {
+    /// Creates a new interface from a contract ABI and the fields of the
+    /// Solidity structs it uses, computing the signature mappings.
+    pub fn new(abi: Abi, structs: BTreeMap<String, Vec<Param>>) -> Self {
+        Self {
+            methods: create_mapping(&abi.functions, |function| function.selector()),
+            events: create_mapping(&abi.events, |event| event.signature()),
+            structs,
+            abi,
+        }
+    }
 }
 
 impl<'de> Deserialize<'de> for Interface {
@@ -55,11 +79,194 @@ impl<'de> Deserialize<'de> for Interface {
     where
         D: Deserializer<'de>,
     {
-        let abi = Abi::deserialize(deserializer)?;
-        Ok(abi.into())
+        deserializer.deserialize_seq(InterfaceVisitor)
     }
 }
 
+/// A visitor that builds the ABI and collects the Solidity structs used by
+/// its parameters in a single pass over the JSON ABI items.
+struct InterfaceVisitor;
+
+impl<'de> Visitor<'de> for InterfaceVisitor {
+    type Value = Interface;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a JSON ABI")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut abi = Abi::default();
+        let mut structs = BTreeMap::new();
+        while let Some(item) = seq.next_element::<RawAbiItem>()? {
+            let mut params = |params: Vec<RawParam>| {
+                params
+                    .into_iter()
+                    .map(|param| param.into_param(&mut structs))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(A::Error::custom)
+            };
+            match item {
+                RawAbiItem::Constructor { inputs } => {
+                    abi.constructor = Some(Constructor {
+                        inputs: params(inputs)?,
+                    });
+                }
+                RawAbiItem::Function {
+                    name,
+                    inputs,
+                    outputs,
+                    constant,
+                    state_mutability,
+                } => {
+                    #[allow(deprecated)]
+                    let function = Function {
+                        name: sanitize_name(name),
+                        inputs: params(inputs)?,
+                        outputs: params(outputs)?,
+                        constant,
+                        state_mutability,
+                    };
+                    abi.functions
+                        .entry(function.name.clone())
+                        .or_default()
+                        .push(function);
+                }
+                RawAbiItem::Event {
+                    name,
+                    inputs,
+                    anonymous,
+                } => {
+                    let indexed = inputs.iter().map(|param| param.indexed).collect::<Vec<_>>();
+                    let event = Event {
+                        name: sanitize_name(name),
+                        inputs: params(inputs)?
+                            .into_iter()
+                            .zip(indexed)
+                            .map(|(param, indexed)| EventParam {
+                                name: param.name,
+                                kind: param.kind,
+                                indexed,
+                            })
+                            .collect(),
+                        anonymous,
+                    };
+                    abi.events
+                        .entry(event.name.clone())
+                        .or_default()
+                        .push(event);
+                }
+                RawAbiItem::Error { name, inputs } => {
+                    let error = AbiError {
+                        name: sanitize_name(name),
+                        inputs: params(inputs)?,
+                    };
+                    abi.errors
+                        .entry(error.name.clone())
+                        .or_default()
+                        .push(error);
+                }
+                RawAbiItem::Fallback => abi.fallback = true,
+                RawAbiItem::Receive => abi.receive = true,
+            }
+        }
+
+        Ok(Interface::new(abi, structs))
+    }
+}
+
+/// A JSON ABI item. This mirrors the representation `ethabi` deserializes
+/// ABI items from, except that tuple parameters keep their components so
+/// that struct fields can be collected along the way.
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum RawAbiItem {
+    Constructor {
+        inputs: Vec<RawParam>,
+    },
+    Function {
+        name: String,
+        inputs: Vec<RawParam>,
+        outputs: Vec<RawParam>,
+        constant: Option<bool>,
+        #[serde(rename = "stateMutability", default)]
+        state_mutability: StateMutability,
+    },
+    Event {
+        name: String,
+        inputs: Vec<RawParam>,
+        anonymous: bool,
+    },
+    Error {
+        name: String,
+        inputs: Vec<RawParam>,
+    },
+    Fallback,
+    Receive,
+}
+
+/// A JSON ABI parameter with its tuple components.
+#[derive(Deserialize)]
+struct RawParam {
+    #[serde(default)]
+    name: String,
+    #[serde(rename = "type")]
+    kind: ParamType,
+    #[serde(rename = "internalType")]
+    internal_type: Option<String>,
+    components: Option<Vec<RawParam>>,
+    #[serde(default)]
+    indexed: bool,
+}
+
+impl RawParam {
+    /// Converts the raw parameter into an ABI parameter, recording the fields
+    /// of the Solidity structs used by it and its components.
+    fn into_param(self, structs: &mut BTreeMap<String, Vec<Param>>) -> Result<Param, String> {
+        let mut kind = self.kind;
+        if let Some(tuple) = tuple_components_mut(&mut kind) {
+            let components = self
+                .components
+                .ok_or_else(|| format!("missing components for tuple parameter '{}'", self.name))?
+                .into_iter()
+                .map(|component| component.into_param(structs))
+                .collect::<Result<Vec<_>, _>>()?;
+            tuple.extend(components.iter().map(|component| component.kind.clone()));
+
+            if let Some(name) = self.internal_type.as_deref().and_then(abiext::struct_name) {
+                structs.entry(name.to_owned()).or_insert(components);
+            }
+        }
+
+        Ok(Param {
+            name: self.name,
+            kind,
+            internal_type: self.internal_type,
+        })
+    }
+}
+
+/// Returns the component types of a possibly nested array of tuples.
+fn tuple_components_mut(mut kind: &mut ParamType) -> Option<&mut Vec<ParamType>> {
+    loop {
+        match kind {
+            ParamType::Array(inner) | ParamType::FixedArray(inner, _) => kind = inner,
+            ParamType::Tuple(components) => return Some(components),
+            _ => return None,
+        }
+    }
+}
+
+/// Strips the parameter list that some compilers include in ABI item names.
+fn sanitize_name(mut name: String) -> String {
+    if let Some(index) = name.find('(') {
+        name.truncate(index);
+    }
+    name
+}
+
 impl Serialize for Interface {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -71,11 +278,7 @@ impl Serialize for Interface {
 
 impl From<Abi> for Interface {
     fn from(abi: Abi) -> Self {
-        Self {
-            methods: create_mapping(&abi.functions, |function| function.selector()),
-            events: create_mapping(&abi.events, |event| event.signature()),
-            abi,
-        }
+        Interface::new(abi, BTreeMap::new())
     }
 }
 
@@ -125,6 +328,7 @@ impl Contract {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Network {
     /// The address at which the contract is deployed on this network.
+    #[serde(deserialize_with = "crate::parse::deserialize_address")]
     pub address: Address,
     /// The hash of the transaction that deployed the contract on this network.
     #[serde(rename = "transactionHash")]
@@ -146,3 +350,126 @@ pub struct DocEntry {
     /// The documentation details for this entry.
     pub details: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::abi::ParamType;
+
+    #[test]
+    fn interface_collects_structs() {
+        let interface: Interface = serde_json::from_str(
+            r#"[
+                {
+                    "type": "function",
+                    "name": "settle",
+                    "inputs": [
+                        {
+                            "name": "orders",
+                            "type": "tuple[]",
+                            "internalType": "struct Lib.Order[]",
+                            "components": [
+                                {"name": "owner", "type": "address", "internalType": "address"},
+                                {
+                                    "name": "fee",
+                                    "type": "tuple",
+                                    "internalType": "struct Fee",
+                                    "components": [
+                                        {"name": "amount", "type": "uint256", "internalType": "uint256"}
+                                    ]
+                                }
+                            ]
+                        },
+                        {"name": "", "type": "tuple", "components": [{"name": "", "type": "bool"}]}
+                    ],
+                    "outputs": [],
+                    "stateMutability": "nonpayable"
+                }
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            interface.structs.keys().collect::<Vec<_>>(),
+            ["Fee", "Lib.Order"]
+        );
+        assert_eq!(
+            interface.structs["Lib.Order"],
+            [
+                Param {
+                    name: "owner".into(),
+                    kind: ParamType::Address,
+                    internal_type: Some("address".into()),
+                },
+                Param {
+                    name: "fee".into(),
+                    kind: ParamType::Tuple(vec![ParamType::Uint(256)]),
+                    internal_type: Some("struct Fee".into()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn interface_abi_matches_ethabi() {
+        let json = r#"[
+            {
+                "type": "constructor",
+                "inputs": [{"name": "owner", "type": "address"}],
+                "stateMutability": "nonpayable"
+            },
+            {
+                "type": "function",
+                "name": "get(uint256)",
+                "inputs": [{"name": "key", "type": "uint256"}],
+                "outputs": [
+                    {
+                        "name": "",
+                        "type": "tuple[2]",
+                        "components": [
+                            {"name": "a", "type": "bytes32"},
+                            {"name": "b", "type": "tuple[]", "components": [{"type": "bool"}]}
+                        ]
+                    }
+                ],
+                "stateMutability": "view"
+            },
+            {
+                "type": "event",
+                "name": "Set",
+                "inputs": [
+                    {"name": "key", "type": "uint256", "indexed": true},
+                    {"name": "value", "type": "tuple", "indexed": false, "components": [{"name": "v", "type": "int8"}]}
+                ],
+                "anonymous": false
+            },
+            {
+                "type": "error",
+                "name": "Missing",
+                "inputs": [{"name": "key", "type": "uint256"}]
+            },
+            {"type": "fallback", "stateMutability": "payable"},
+            {"type": "receive", "stateMutability": "payable"}
+        ]"#;
+
+        let interface: Interface = serde_json::from_str(json).unwrap();
+        let abi: Abi = serde_json::from_str(json).unwrap();
+        assert_eq!(interface, Interface::from(abi));
+    }
+
+    #[test]
+    fn network_address_errors() {
+        let network: Network =
+            serde_json::from_str(r#"{"address": "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD"}"#)
+                .unwrap();
+        assert_eq!(
+            network.address,
+            "5aaeb6053f3e94c9b9a09f33669435e7ef1beaed".parse().unwrap()
+        );
+
+        let err = serde_json::from_str::<Network>(r#"{"address": "0x0001"}"#).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("invalid address '0x0001': expected '0x' followed by 40 hex digits"));
+    }
+}
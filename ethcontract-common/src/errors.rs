@@ -22,6 +22,10 @@ pub enum ArtifactError {
     /// Contract have multiple deployment addresses on the same chain.
     #[error("chain with id {0} appears several times in the artifact")]
     DuplicateChain(String),
+
+    /// Merged artifacts contain different contracts with the same name.
+    #[error("contract {0} differs between merged artifacts")]
+    MergeConflict(String),
 }
 
 /// An error reading bytecode string representation.
@@ -40,6 +44,18 @@ pub enum BytecodeError {
     InvalidHexDigit(char),
 }
 
+/// An error extracting `solc` metadata from runtime bytecode.
+#[derive(Clone, Debug, Eq, PartialEq, Error)]
+pub enum MetadataError {
+    /// The bytecode does not end with metadata.
+    #[error("bytecode does not contain metadata")]
+    Missing,
+
+    /// The metadata is not valid CBOR or contains unexpected values.
+    #[error("invalid CBOR encoded metadata")]
+    InvalidCbor,
+}
+
 /// An error linking a library to bytecode.
 #[derive(Debug, Error)]
 pub enum LinkError {
@@ -59,3 +75,55 @@ pub enum LinkError {
 #[derive(Clone, Debug, Error)]
 #[error("'{0}' is not a valid Solidity type")]
 pub struct ParseParamTypeError(pub String);
+
+/// An error parsing a hex encoded value such as an address or a hash.
+#[derive(Clone, Debug, Eq, PartialEq, Error)]
+pub enum ParseHexError {
+    /// The value is missing the `0x` prefix.
+    #[error("invalid {kind} '{value}': missing '0x' prefix, expected '0x' followed by {digits} hex digits")]
+    MissingPrefix {
+        /// The kind of value that was parsed.
+        kind: &'static str,
+        /// The offending string.
+        value: String,
+        /// The expected number of hex digits.
+        digits: usize,
+    },
+
+    /// The value has the wrong number of hex digits.
+    #[error("invalid {kind} '{value}': expected '0x' followed by {expected} hex digits but found {found}")]
+    InvalidLength {
+        /// The kind of value that was parsed.
+        kind: &'static str,
+        /// The offending string.
+        value: String,
+        /// The expected number of hex digits.
+        expected: usize,
+        /// The actual number of hex digits.
+        found: usize,
+    },
+
+    /// The value contains a character that is not a hex digit.
+    #[error("invalid {kind} '{value}': invalid hex digit '{digit}' at position {position}")]
+    InvalidHexDigit {
+        /// The kind of value that was parsed.
+        kind: &'static str,
+        /// The offending string.
+        value: String,
+        /// The invalid character.
+        digit: char,
+        /// The position of the invalid character in the string.
+        position: usize,
+    },
+
+    /// A mixed-case address does not match its EIP-55 checksum, which usually
+    /// indicates a typo. Only returned by
+    /// [`parse_checksummed_address`](crate::parse_checksummed_address).
+    #[error("invalid address '{value}': checksum mismatch, did you mean '{checksummed}'?")]
+    InvalidChecksum {
+        /// The offending string.
+        value: String,
+        /// The address with the correct checksum.
+        checksummed: String,
+    },
+}
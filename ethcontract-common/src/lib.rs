@@ -9,10 +9,14 @@ pub mod bytecode;
 pub mod contract;
 pub mod errors;
 pub mod hash;
+pub mod metadata;
+pub mod parse;
 
-pub use crate::abiext::FunctionExt;
+pub use crate::abiext::{AbiExt, EventExt, FunctionExt, ParamExt};
 pub use crate::bytecode::Bytecode;
 pub use crate::contract::Contract;
+pub use crate::metadata::BytecodeMetadata;
+pub use crate::parse::{parse_address, parse_checksummed_address, parse_h256};
 pub use ethabi::{self as abi, Contract as Abi};
 use serde::{Deserialize, Serialize};
 pub use web3::types::Address;
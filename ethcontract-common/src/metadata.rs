@@ -0,0 +1,357 @@
+//! This module implements decoding of the CBOR encoded metadata that `solc`
+//! appends to the end of runtime bytecode. The metadata contains the hash of
+//! the contract's metadata JSON file, which can be used to look up its sources,
+//! as well as the version of the compiler that produced the bytecode.
+
+use crate::errors::MetadataError;
+use std::fmt::{self, Display, Formatter};
+use web3::types::H256;
+
+/// Metadata appended by `solc` to the end of runtime bytecode.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BytecodeMetadata {
+    /// The hash of the contract metadata JSON file, if it was included.
+    pub hash: Option<MetadataHash>,
+    /// The version of the compiler that produced the bytecode, if it was
+    /// included.
+    pub solc: Option<SolcVersion>,
+    /// Whether the contract was compiled with experimental features.
+    pub experimental: bool,
+}
+
+impl BytecodeMetadata {
+    /// Extracts the metadata from the end of runtime bytecode, for example as
+    /// returned by `eth_getCode`.
+    ///
+    /// The bytecode is expected to end with the CBOR encoded metadata followed
+    /// by its length as a 2-byte big-endian integer.
+    pub fn from_runtime_code(code: &[u8]) -> Result<Self, MetadataError> {
+        let len = match code.len().checked_sub(2) {
+            Some(end) => u16::from_be_bytes([code[end], code[end + 1]]) as usize,
+            None => return Err(MetadataError::Missing),
+        };
+        let start = code
+            .len()
+            .checked_sub(2 + len)
+            .ok_or(MetadataError::Missing)?;
+
+        let mut decoder = Decoder(&code[start..code.len() - 2]);
+        let entries = match decoder.read_header()? {
+            (MAJOR_MAP, entries) => entries,
+            _ => return Err(MetadataError::Missing),
+        };
+
+        let mut metadata = BytecodeMetadata {
+            hash: None,
+            solc: None,
+            experimental: false,
+        };
+        for _ in 0..entries {
+            let key = decoder.read_text()?;
+            match key {
+                "ipfs" => metadata.hash = Some(MetadataHash::Ipfs(decoder.read_bytes()?.to_vec())),
+                "bzzr0" => metadata.hash = Some(MetadataHash::Bzzr0(decoder.read_h256()?)),
+                "bzzr1" => metadata.hash = Some(MetadataHash::Bzzr1(decoder.read_h256()?)),
+                "solc" => metadata.solc = Some(decoder.read_solc_version()?),
+                "experimental" => metadata.experimental = decoder.read_bool()?,
+                _ => decoder.skip()?,
+            }
+        }
+        if !decoder.0.is_empty() {
+            return Err(MetadataError::InvalidCbor);
+        }
+
+        Ok(metadata)
+    }
+}
+
+/// The hash of a contract metadata JSON file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MetadataHash {
+    /// An IPFS multihash of the metadata file.
+    Ipfs(Vec<u8>),
+    /// A legacy Swarm hash of the metadata file, used by `solc` before 0.5.9.
+    Bzzr0(H256),
+    /// A Swarm hash of the metadata file, used by `solc` 0.5.9 to 0.6.x.
+    Bzzr1(H256),
+}
+
+impl MetadataHash {
+    /// Returns a URL for the metadata file, such as `ipfs://Qm...` or
+    /// `bzz-raw://...`.
+    pub fn url(&self) -> String {
+        match self {
+            MetadataHash::Ipfs(hash) => format!("ipfs://{}", base58(hash)),
+            MetadataHash::Bzzr0(hash) | MetadataHash::Bzzr1(hash) => {
+                format!("bzz-raw://{}", hex::encode(hash))
+            }
+        }
+    }
+}
+
+/// The version of the `solc` compiler that produced bytecode.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SolcVersion {
+    /// A release version of the compiler.
+    Release {
+        /// The major version.
+        major: u8,
+        /// The minor version.
+        minor: u8,
+        /// The patch version.
+        patch: u8,
+    },
+    /// A full version string of a pre-release compiler, for example
+    /// `0.8.20-nightly.2023.4.13+commit.5d42bb5e`.
+    Prerelease(String),
+}
+
+impl Display for SolcVersion {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            SolcVersion::Release {
+                major,
+                minor,
+                patch,
+            } => write!(f, "{}.{}.{}", major, minor, patch),
+            SolcVersion::Prerelease(version) => f.write_str(version),
+        }
+    }
+}
+
+const MAJOR_UINT: u8 = 0;
+const MAJOR_BYTES: u8 = 2;
+const MAJOR_TEXT: u8 = 3;
+const MAJOR_ARRAY: u8 = 4;
+const MAJOR_MAP: u8 = 5;
+const MAJOR_SIMPLE: u8 = 7;
+
+/// A minimal CBOR decoder that supports the subset of CBOR emitted by `solc`.
+struct Decoder<'a>(&'a [u8]);
+
+impl<'a> Decoder<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], MetadataError> {
+        if self.0.len() < len {
+            return Err(MetadataError::InvalidCbor);
+        }
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    /// Reads the header of a data item, returning its major type and
+    /// argument. Indefinite length items are not supported.
+    fn read_header(&mut self) -> Result<(u8, u64), MetadataError> {
+        let initial = self.take(1)?[0];
+        let major = initial >> 5;
+        let argument = match initial & 0x1f {
+            info @ 0..=23 => info as u64,
+            24 => self.take(1)?[0] as u64,
+            25 => u16::from_be_bytes(self.take(2)?.try_into().unwrap()) as u64,
+            26 => u32::from_be_bytes(self.take(4)?.try_into().unwrap()) as u64,
+            27 => u64::from_be_bytes(self.take(8)?.try_into().unwrap()),
+            _ => return Err(MetadataError::InvalidCbor),
+        };
+        Ok((major, argument))
+    }
+
+    fn read_string(&mut self, expected: u8) -> Result<&'a [u8], MetadataError> {
+        match self.read_header()? {
+            (major, len) if major == expected => self.take(len as usize),
+            _ => Err(MetadataError::InvalidCbor),
+        }
+    }
+
+    fn read_bytes(&mut self) -> Result<&'a [u8], MetadataError> {
+        self.read_string(MAJOR_BYTES)
+    }
+
+    fn read_text(&mut self) -> Result<&'a str, MetadataError> {
+        let text = self.read_string(MAJOR_TEXT)?;
+        std::str::from_utf8(text).map_err(|_| MetadataError::InvalidCbor)
+    }
+
+    fn read_h256(&mut self) -> Result<H256, MetadataError> {
+        match self.read_bytes()? {
+            hash if hash.len() == 32 => Ok(H256::from_slice(hash)),
+            _ => Err(MetadataError::InvalidCbor),
+        }
+    }
+
+    fn read_bool(&mut self) -> Result<bool, MetadataError> {
+        match self.read_header()? {
+            (MAJOR_SIMPLE, 20) => Ok(false),
+            (MAJOR_SIMPLE, 21) => Ok(true),
+            _ => Err(MetadataError::InvalidCbor),
+        }
+    }
+
+    /// Reads a compiler version, which is encoded as 3 bytes for release
+    /// versions and as a string for pre-release versions.
+    fn read_solc_version(&mut self) -> Result<SolcVersion, MetadataError> {
+        match self.read_header()? {
+            (MAJOR_BYTES, 3) => {
+                let version = self.take(3)?;
+                Ok(SolcVersion::Release {
+                    major: version[0],
+                    minor: version[1],
+                    patch: version[2],
+                })
+            }
+            (MAJOR_TEXT, len) => {
+                let version = self.take(len as usize)?;
+                String::from_utf8(version.to_vec())
+                    .map(SolcVersion::Prerelease)
+                    .map_err(|_| MetadataError::InvalidCbor)
+            }
+            _ => Err(MetadataError::InvalidCbor),
+        }
+    }
+
+    /// Skips a data item of any type.
+    fn skip(&mut self) -> Result<(), MetadataError> {
+        match self.read_header()? {
+            (MAJOR_BYTES | MAJOR_TEXT, len) => {
+                self.take(len as usize)?;
+            }
+            (MAJOR_ARRAY, len) => {
+                for _ in 0..len {
+                    self.skip()?;
+                }
+            }
+            (MAJOR_MAP, len) => {
+                for _ in 0..len * 2 {
+                    self.skip()?;
+                }
+            }
+            (MAJOR_UINT | 1 | MAJOR_SIMPLE, _) => {}
+            _ => return Err(MetadataError::InvalidCbor),
+        }
+        Ok(())
+    }
+}
+
+/// Encodes bytes with the Bitcoin base58 alphabet used for IPFS hashes.
+fn base58(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+    let mut digits = Vec::<u8>::new();
+    for &byte in bytes {
+        let mut carry = byte as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+
+    let zeros = bytes.iter().take_while(|&&byte| byte == 0).count();
+    std::iter::repeat(b'1')
+        .take(zeros)
+        .chain(digits.iter().rev().map(|&digit| ALPHABET[digit as usize]))
+        .map(char::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_ipfs_metadata() {
+        // metadata of a contract compiled with solc 0.8.19
+        let code = hex::decode(
+            "6080604052\
+             a2646970667358221220\
+             1b7ce5f3d9ab08c6b1d1c2a1f0e9d6b5c4a3f2e1d0c9b8a7f6e5d4c3b2a19080\
+             64736f6c63430008130033",
+        )
+        .unwrap();
+
+        let metadata = BytecodeMetadata::from_runtime_code(&code).unwrap();
+        assert_eq!(
+            metadata.solc,
+            Some(SolcVersion::Release {
+                major: 0,
+                minor: 8,
+                patch: 19
+            })
+        );
+        assert_eq!(metadata.solc.unwrap().to_string(), "0.8.19");
+        assert!(!metadata.experimental);
+
+        let hash = metadata.hash.unwrap();
+        assert!(matches!(&hash, MetadataHash::Ipfs(hash) if hash.len() == 34));
+        assert!(hash.url().starts_with("ipfs://Qm"));
+    }
+
+    #[test]
+    fn decode_bzzr_metadata() {
+        // metadata of a contract compiled with solc 0.5.0
+        let code = hex::decode(
+            "6080604052\
+             a165627a7a72305820\
+             4242424242424242424242424242424242424242424242424242424242424242\
+             0029",
+        )
+        .unwrap();
+
+        let metadata = BytecodeMetadata::from_runtime_code(&code).unwrap();
+        assert_eq!(
+            metadata.hash,
+            Some(MetadataHash::Bzzr0(H256::repeat_byte(0x42)))
+        );
+        assert_eq!(metadata.solc, None);
+        assert_eq!(
+            metadata.hash.unwrap().url(),
+            format!("bzz-raw://{}", "42".repeat(32)),
+        );
+    }
+
+    #[test]
+    fn decode_prerelease_version() {
+        let code = hex::decode(
+            "00\
+             a2\
+             64736f6c63\
+             6d302e382e302d6e696768746c79\
+             6c6578706572696d656e74616cf5\
+             0022",
+        )
+        .unwrap();
+
+        let metadata = BytecodeMetadata::from_runtime_code(&code).unwrap();
+        assert_eq!(
+            metadata.solc,
+            Some(SolcVersion::Prerelease("0.8.0-nightly".to_owned()))
+        );
+        assert!(metadata.experimental);
+    }
+
+    #[test]
+    fn missing_metadata() {
+        assert!(matches!(
+            BytecodeMetadata::from_runtime_code(&[]),
+            Err(MetadataError::Missing)
+        ));
+        assert!(matches!(
+            BytecodeMetadata::from_runtime_code(&[0x60, 0x80, 0x60, 0x40]),
+            Err(MetadataError::Missing)
+        ));
+        assert!(matches!(
+            BytecodeMetadata::from_runtime_code(&[0xa1, 0x64, 0x73, 0x00, 0x03]),
+            Err(MetadataError::InvalidCbor)
+        ));
+    }
+
+    #[test]
+    fn base58_encoding() {
+        assert_eq!(base58(b""), "");
+        assert_eq!(base58(&[0, 0, 1]), "112");
+        assert_eq!(base58(b"hello world"), "StV1DL6CwTryKyV");
+    }
+}
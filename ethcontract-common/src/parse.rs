@@ -0,0 +1,193 @@
+//! Parsing of hex encoded addresses and hashes with descriptive errors.
+//!
+//! These utilities are shared by the procedural macro, the code generation
+//! builders and the artifact loaders, so that invalid values are reported
+//! consistently with the offending string and the expected format. The
+//! runtime only deals with already parsed addresses and hashes.
+
+use crate::errors::ParseHexError;
+use crate::hash;
+use serde::{Deserialize, Deserializer};
+use std::borrow::Cow;
+use web3::types::{Address, H256};
+
+/// Parses a `0x`-prefixed hex string into an address.
+///
+/// Addresses are accepted regardless of the case of their hex digits, use
+/// [`parse_checksummed_address`] to additionally verify the [EIP-55]
+/// checksum of mixed-case addresses.
+///
+/// [EIP-55]: https://eips.ethereum.org/EIPS/eip-55
+pub fn parse_address(value: &str) -> Result<Address, ParseHexError> {
+    Ok(Address::from_slice(&parse_hex("address", value, 20)?))
+}
+
+/// Parses a `0x`-prefixed hex string into an address, verifying the
+/// [EIP-55] checksum of addresses that contain both upper and lower case hex
+/// digits. The error includes the correctly checksummed address to help spot
+/// typos.
+///
+/// [EIP-55]: https://eips.ethereum.org/EIPS/eip-55
+pub fn parse_checksummed_address(value: &str) -> Result<Address, ParseHexError> {
+    let address = parse_address(value)?;
+
+    let digits = &value[2..];
+    let mixed_case = digits.chars().any(|c| c.is_ascii_uppercase())
+        && digits.chars().any(|c| c.is_ascii_lowercase());
+    if mixed_case {
+        let checksummed = to_checksum_address(&address);
+        if checksummed != value {
+            return Err(ParseHexError::InvalidChecksum {
+                value: value.to_string(),
+                checksummed,
+            });
+        }
+    }
+
+    Ok(address)
+}
+
+/// Parses a `0x`-prefixed hex string into a 32-byte hash.
+pub fn parse_h256(value: &str) -> Result<H256, ParseHexError> {
+    Ok(H256::from_slice(&parse_hex("hash", value, 32)?))
+}
+
+/// Formats an address with its [EIP-55] mixed-case checksum.
+///
+/// [EIP-55]: https://eips.ethereum.org/EIPS/eip-55
+pub fn to_checksum_address(address: &Address) -> String {
+    let digits = hex::encode(address.as_bytes());
+    let hash = hash::keccak256(&digits);
+
+    let checksummed = digits
+        .char_indices()
+        .map(|(i, c)| {
+            let nibble = (hash[i / 2] >> (if i % 2 == 0 { 4 } else { 0 })) & 0xf;
+            if nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect::<String>();
+    format!("0x{}", checksummed)
+}
+
+/// Deserializes an address with [`parse_address`], so that artifacts with
+/// invalid addresses fail to load with a descriptive error.
+pub(crate) fn deserialize_address<'de, D>(deserializer: D) -> Result<Address, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = Cow::<str>::deserialize(deserializer)?;
+    parse_address(&value).map_err(serde::de::Error::custom)
+}
+
+/// Parses a `0x`-prefixed hex string of exactly `len` bytes.
+fn parse_hex(kind: &'static str, value: &str, len: usize) -> Result<Vec<u8>, ParseHexError> {
+    let digits = value
+        .strip_prefix("0x")
+        .ok_or_else(|| ParseHexError::MissingPrefix {
+            kind,
+            value: value.to_string(),
+            digits: len * 2,
+        })?;
+
+    if let Some((position, digit)) = digits.char_indices().find(|(_, c)| !c.is_ascii_hexdigit()) {
+        return Err(ParseHexError::InvalidHexDigit {
+            kind,
+            value: value.to_string(),
+            digit,
+            position: position + 2,
+        });
+    }
+    if digits.len() != len * 2 {
+        return Err(ParseHexError::InvalidLength {
+            kind,
+            value: value.to_string(),
+            expected: len * 2,
+            found: digits.len(),
+        });
+    }
+
+    Ok(hex::decode(digits).expect("valid hex"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_valid_values() {
+        let address = Address::from([
+            0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19,
+        ]);
+        assert_eq!(
+            parse_address("0x000102030405060708090a0b0c0d0e0f10111213").unwrap(),
+            address
+        );
+        assert_eq!(
+            parse_address("0x000102030405060708090A0B0C0D0E0F10111213").unwrap(),
+            address
+        );
+        assert_eq!(
+            parse_address("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed").unwrap(),
+            "5aaeb6053f3e94c9b9a09f33669435e7ef1beaed".parse().unwrap()
+        );
+        assert_eq!(
+            parse_address("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD").unwrap(),
+            "5aaeb6053f3e94c9b9a09f33669435e7ef1beaed".parse().unwrap()
+        );
+        assert_eq!(
+            parse_checksummed_address("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed").unwrap(),
+            "5aaeb6053f3e94c9b9a09f33669435e7ef1beaed".parse().unwrap()
+        );
+        assert_eq!(
+            parse_checksummed_address("0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed").unwrap(),
+            "5aaeb6053f3e94c9b9a09f33669435e7ef1beaed".parse().unwrap()
+        );
+        assert_eq!(
+            parse_h256(&format!("0x{}", "42".repeat(32))).unwrap(),
+            H256::repeat_byte(0x42)
+        );
+    }
+
+    #[test]
+    fn checksum_address() {
+        let address = "5aaeb6053f3e94c9b9a09f33669435e7ef1beaed".parse().unwrap();
+        assert_eq!(
+            to_checksum_address(&address),
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            parse_address("000102030405060708090a0b0c0d0e0f10111213")
+                .unwrap_err()
+                .to_string(),
+            "invalid address '000102030405060708090a0b0c0d0e0f10111213': \
+             missing '0x' prefix, expected '0x' followed by 40 hex digits"
+        );
+        assert_eq!(
+            parse_h256("0x0001").unwrap_err().to_string(),
+            "invalid hash '0x0001': expected '0x' followed by 64 hex digits \
+             but found 4"
+        );
+        assert_eq!(
+            parse_address("0x00010203040506070809g")
+                .unwrap_err()
+                .to_string(),
+            "invalid address '0x00010203040506070809g': invalid hex digit 'g' \
+             at position 22"
+        );
+        assert_eq!(
+            parse_checksummed_address("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD")
+                .unwrap_err()
+                .to_string(),
+            "invalid address '0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD': \
+             checksum mismatch, did you mean '0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed'?"
+        );
+    }
+}
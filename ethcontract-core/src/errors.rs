@@ -0,0 +1,32 @@
+//! Error types for the core types.
+
+use thiserror::Error;
+use uint::FromDecStrErr;
+
+/// The error type that is returned when conversion to or from a 256-bit integer
+/// fails.
+#[derive(Clone, Copy, Debug, Error)]
+#[error("output of range integer conversion attempted")]
+pub struct TryFromBigIntError;
+
+/// The error type that is returned when parsing a 256-bit signed integer.
+#[derive(Clone, Copy, Debug, Error)]
+pub enum ParseI256Error {
+    /// Error that occurs when an invalid digit is encountered while parsing.
+    #[error("invalid digit found in string")]
+    InvalidDigit,
+
+    /// Error that occurs when the number is too large or too small (negative)
+    /// and does not fit in a 256-bit signed integer.
+    #[error("number does not fit in 256-bit integer")]
+    IntegerOverflow,
+}
+
+impl From<FromDecStrErr> for ParseI256Error {
+    fn from(err: FromDecStrErr) -> Self {
+        match err {
+            FromDecStrErr::InvalidCharacter => ParseI256Error::InvalidDigit,
+            FromDecStrErr::InvalidLength => ParseI256Error::IntegerOverflow,
+        }
+    }
+}
@@ -1,13 +1,13 @@
 //! This module contains an 256-bit signed integer implementation.
 
 use crate::errors::{ParseI256Error, TryFromBigIntError};
+use ethabi::ethereum_types::U256;
 use serde::{Deserialize, Serialize};
 use std::cmp;
 use std::fmt;
 use std::iter;
 use std::ops;
 use std::str;
-use web3::types::U256;
 
 /// Compute the two's complement of a U256.
 fn twos_complement(u: U256) -> U256 {
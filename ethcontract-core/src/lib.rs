@@ -0,0 +1,19 @@
+#![deny(missing_docs, unsafe_code)]
+
+//! Core types shared between the `ethcontract` runtime crate and the
+//! `ethcontract-mock` crate.
+//!
+//! This crate only contains method signatures, the `Tokenize` trait and the
+//! types that it is implemented for. It is versioned independently from the
+//! rest of the `ethcontract` crates and changes rarely, so that the runtime
+//! and mocking crates can be upgraded separately as long as they depend on a
+//! compatible version of this crate.
+
+pub mod errors;
+mod int;
+mod signature;
+pub mod tokens;
+
+pub use crate::int::I256;
+pub use crate::signature::{Signature, H32};
+pub use ethabi as abi;
@@ -0,0 +1,34 @@
+//! Typed method signatures.
+
+use std::marker::PhantomData;
+
+/// A 32-bit prefix of a standard 256-bit Keccak hash.
+///
+/// This 32-bit prefix is generally used as the first 4 bytes of transaction
+/// data in order to select which Solidity method will be called.
+pub type H32 = [u8; 4];
+
+/// Method signature with additional info about method's input and output types.
+///
+/// Additional type parameters are used to help with type inference
+/// for instance's `method` and `view_method` functions.
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+pub struct Signature<P, R>(pub H32, pub PhantomData<(P, R)>);
+
+impl<P, R> Signature<P, R> {
+    /// Wraps raw signature.
+    pub fn new(signature: H32) -> Self {
+        Signature(signature, PhantomData)
+    }
+
+    /// Unwraps raw signature.
+    pub fn into_inner(self) -> H32 {
+        self.0
+    }
+}
+
+impl<P, R> From<H32> for Signature<P, R> {
+    fn from(signature: H32) -> Self {
+        Signature::new(signature)
+    }
+}
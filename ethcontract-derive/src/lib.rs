@@ -8,14 +8,15 @@ extern crate proc_macro;
 mod spanned;
 
 use crate::spanned::{ParseInner, Spanned};
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context as _, Result};
 use ethcontract_common::abi::{Function, Param, ParamType};
 use ethcontract_common::abiext::{FunctionExt, ParamTypeExt};
+use ethcontract_common::artifact::abi::AbiLoader;
 use ethcontract_common::artifact::truffle::TruffleLoader;
 use ethcontract_common::contract::Network;
 use ethcontract_common::Address;
 use ethcontract_generate::loaders::{HardHatFormat, HardHatLoader};
-use ethcontract_generate::{parse_address, ContractBuilder, Source};
+use ethcontract_generate::{parse_address, ContractBuilder, NumberFormat, Source};
 use proc_macro::TokenStream;
 use proc_macro2::{Span, TokenStream as TokenStream2};
 use quote::{quote, ToTokens as _};
@@ -23,8 +24,8 @@ use std::collections::HashSet;
 use syn::ext::IdentExt;
 use syn::parse::{Error as ParseError, Parse, ParseStream, Result as ParseResult};
 use syn::{
-    braced, parenthesized, parse_macro_input, Error as SynError, Ident, LitInt, LitStr, Path,
-    Token, Visibility,
+    braced, bracketed, parenthesized, parse_macro_input, Error as SynError, Ident, LitBool, LitInt,
+    LitStr, Path, Token, Visibility,
 };
 
 /// Proc macro to generate type-safe bindings to a contract.
@@ -46,13 +47,38 @@ use syn::{
 /// // Etherscan source
 /// contract!("etherscan:0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2");
 ///
+/// // Block explorer sources for other chains
+/// contract!("etherscan:10:0x4200000000000000000000000000000000000006");
+/// contract!("arbiscan:0x82aF49447D8a07e3bd95BD0d56f35241523fBab1");
+///
+/// // Sourcify source, with a chain ID and a contract address
+/// contract!("sourcify:100:0xe91D153E0b41518A2Ce8Dd3D7944Fa863463a97d");
+///
 /// // NPM package source
 /// contract!("npm:@openzeppelin/contracts@4.2.0/build/contracts/IERC20.json")
 /// ```
 ///
 /// Note that Etherscan rate-limits requests to their API, to avoid this an
 /// `ETHERSCAN_API_KEY` environment variable can be set. If it is, it will use
-/// that API key when retrieving the contract ABI.
+/// that API key when retrieving the contract ABI. Other explorers read their
+/// API keys from `ARBISCAN_API_KEY`, `POLYGONSCAN_API_KEY`, `BSCSCAN_API_KEY`
+/// and `BASESCAN_API_KEY` respectively.
+///
+/// Contracts that define Solidity custom errors additionally get a
+/// `ContractError` enum with a variant for each error. It can be used to
+/// decode the error that a method call reverted with:
+///
+/// ```ignore
+/// match vault.withdraw(amount).send().await {
+///     Err(err) => match vault::ContractError::from_method_error(&err) {
+///         Some(vault::ContractError::InsufficientBalance { available, .. }) => {
+///             println!("only {} available", available);
+///         }
+///         _ => return Err(err.into()),
+///     },
+///     Ok(_) => {}
+/// }
+/// ```
 ///
 /// Currently, the proc macro accepts additional parameters to configure some
 /// aspects of the code generation. Specifically it accepts the following.
@@ -63,15 +89,20 @@ use syn::{
 ///
 ///   - `truffle` (default) to use [truffle loader];
 ///   - `hardhat` to use [hardhat loader] in [single export mode];
-///   - `hardhat_multi` to use hardhat loader in [multi export mode].
+///   - `hardhat_multi` to use hardhat loader in [multi export mode];
+///   - `abi` to use [abi loader] for bare ABI JSON arrays.
 ///
 ///   Note that hardhat artifacts export multiple contracts. You'll have to use
 ///   `contract` parameter to specify which contract to generate bindings to.
+///   Bare ABI files don't contain a contract name, so it must be specified
+///   with the `contract` parameter as well. Contracts loaded from them have
+///   empty bytecode and can't be deployed.
 ///
 ///   [truffle loader]: ethcontract_common::artifact::truffle::TruffleLoader
 ///   [hardhat loader]: ethcontract_common::artifact::hardhat::HardHatLoader
 ///   [single export mode]: ethcontract_common::artifact::hardhat::Format::SingleExport
 ///   [multi export mode]: ethcontract_common::artifact::hardhat::Format::MultiExport
+///   [abi loader]: ethcontract_common::artifact::abi::AbiLoader
 ///
 /// - `contract`: name of the contract we're generating bindings to.
 ///
@@ -158,6 +189,24 @@ use syn::{
 ///   );
 ///   ```
 ///
+/// - `deprecated`: a list of mappings from method signatures to deprecation
+///   notes, marking the generated bindings for these methods with a
+///   `#[deprecated]` attribute.
+///
+///   This steers downstream code away from legacy contract methods, as using
+///   them produces compiler warnings.
+///
+///   Example:
+///
+///   ```ignore
+///   contract!(
+///       "build/contracts/WETH9.json",
+///       deprecated {
+///           approve(address,uint256) => "use `increase_allowance` instead",
+///       },
+///   );
+///   ```
+///
 /// - `event_derives`: a list of additional derives that should be added to
 ///   contract event structs and enums.
 ///
@@ -170,6 +219,124 @@ use syn::{
 ///   );
 ///   ```
 ///
+/// - `event_number_format`: the serialization format, either `hex` or
+///   `decimal`, for `U256` and `I256` fields of contract event structs. This
+///   requires serde derives to be added with `event_derives`.
+///
+///   Example:
+///
+///   ```ignore
+///   contract!(
+///       "build/contracts/WETH9.json",
+///       event_derives (serde::Deserialize, serde::Serialize),
+///       event_number_format = decimal,
+///   );
+///   ```
+///
+/// - `non_exhaustive_events`: whether the generated event enum should be
+///   marked as `#[non_exhaustive]` with an additional `Unknown(RawLog)`
+///   variant. Logs that don't match any of the contract events are parsed
+///   into this variant instead of producing an error.
+///
+///   Example:
+///
+///   ```ignore
+///   contract!(
+///       "build/contracts/WETH9.json",
+///       non_exhaustive_events = true,
+///   );
+///   ```
+///
+/// - `no_events`: whether to skip generating all event code, such as event
+///   data structs, the event enum and event stream builders. This reduces
+///   compile times for crates that only use contract methods.
+///
+///   Example:
+///
+///   ```ignore
+///   contract!(
+///       "build/contracts/WETH9.json",
+///       no_events = true,
+///   );
+///   ```
+///
+/// - `event_subsets`: a list of named subsets of the contract events.
+///
+///   An enum with a variant for each of the listed events is generated for
+///   every subset, which can be streamed with the generated
+///   `events_filtered` method. Only logs of the selected events are
+///   requested from the node.
+///
+///   Example:
+///
+///   ```ignore
+///   contract!(
+///       "build/contracts/WETH9.json",
+///       event_subsets {
+///           TokenFlow(Transfer, Deposit, Withdrawal),
+///       },
+///   );
+///
+///   let stream = weth.events_filtered::<weth9::TokenFlow>().stream();
+///   ```
+///
+/// - `facets`: a list of additional artifacts whose functions and events are
+///   merged into the generated binding.
+///
+///   This is useful for contracts that dispatch calls to multiple
+///   implementations from a single address, such as EIP-2535 diamonds.
+///   Facet artifacts accept the same sources as the main artifact, and must
+///   contain a single contract in the truffle format (note that per-contract
+///   hardhat artifacts use this format as well). Code generation fails if
+///   two different functions have the same selector.
+///
+///   Example:
+///
+///   ```ignore
+///   contract!(
+///       "build/contracts/Diamond.json",
+///       facets = ["build/contracts/TokenFacet.json", "build/contracts/VoteFacet.json"],
+///   );
+///   ```
+///
+/// - `mock_feature`: name of a Cargo feature that enables a generated `mock`
+///   module with typed `ethcontract-mock` helpers.
+///
+///   The module contains a `MockContract` type that wraps a mocked contract
+///   and has an `expect_*` method for setting up expectations for each
+///   contract method. The crate invoking the macro must have an optional
+///   dependency on `ethcontract-mock` that is enabled by the feature.
+///
+///   Example:
+///
+///   ```ignore
+///   contract!("build/contracts/WETH9.json", mock_feature = "mock");
+///
+///   let mock = weth9::mock::MockContract::deploy(&ethcontract_mock::Mock::new(1));
+///   mock.expect_balance_of().returns(42.into());
+///   ```
+///
+/// - `path_env`: name of an environment variable containing the directory
+///   in which artifact paths are rooted.
+///
+///   This is useful in workspaces where artifacts are not located at a fixed
+///   path relative to the crate. Relative artifact and facet paths are
+///   resolved against the value of the variable, and code generation fails if
+///   the variable is not set. Absolute paths and sources such as URLs or
+///   `etherscan:` addresses are not affected. Artifact paths may also reference environment
+///   variables directly using the `${NAME}` syntax.
+///
+///   Note that cargo does not track environment variables read by procedural
+///   macros, so changing the variable's value requires a rebuild.
+///
+///   Example:
+///
+///   ```ignore
+///   contract!("WETH9.json", path_env = "CONTRACTS_DIR");
+///   // equivalent to
+///   contract!("${CONTRACTS_DIR}/WETH9.json");
+///   ```
+///
 /// - `crate`: the name of the `ethcontract` crate. This is useful if the crate
 ///   was renamed in the `Cargo.toml` for whatever reason.
 ///
@@ -194,7 +361,18 @@ use syn::{
 ///     methods {
 ///         myMethod(uint256,bool) as my_renamed_method;
 ///     },
+///     deprecated {
+///         myLegacyMethod() => "use `my_renamed_method` instead",
+///     },
 ///     event_derives (serde::Deserialize, serde::Serialize),
+///     event_number_format = decimal,
+///     non_exhaustive_events = false,
+///     no_events = false,
+///     event_subsets {
+///         Approvals(Approval),
+///     },
+///     facets = ["build/contracts/Facet.json"],
+///     mock_feature = "mock",
 ///     crate = ethcontract_renamed,
 /// );
 /// ```
@@ -205,24 +383,39 @@ use syn::{
 pub fn contract(input: TokenStream) -> TokenStream {
     let args = parse_macro_input!(input as Spanned<ContractArgs>);
     let span = args.span();
-    generate(args.into_inner())
-        .unwrap_or_else(|e| SynError::new(span, format!("{:?}", e)).to_compile_error())
+    generate(span, args.into_inner())
+        .unwrap_or_else(|e| e.to_compile_error())
         .into()
 }
 
-fn generate(args: ContractArgs) -> Result<TokenStream2> {
+fn generate(artifact_span: Span, args: ContractArgs) -> Result<TokenStream2, SynError> {
     let mut artifact_format = Format::Truffle;
     let mut contract_name = None;
+    let mut methods_span = None;
+    let mut deprecated_span = None;
 
     let mut builder = ContractBuilder::new();
     builder.visibility_modifier = args.visibility;
 
+    let mut path_env = None;
+    for parameter in &args.parameters {
+        if let Parameter::PathEnv(name) = &**parameter {
+            if std::env::var_os(name).is_none() {
+                return Err(anyhow!("environment variable '{}' is not set", name))
+                    .at(parameter.span());
+            }
+            path_env = Some(name.clone());
+        }
+    }
+    let resolve = |path: &str| resolve_path(path_env.as_deref(), path);
+
     for parameter in args.parameters.into_iter() {
-        match parameter {
+        let span = parameter.span();
+        match parameter.into_inner() {
             Parameter::Mod(name) => builder.contract_mod_override = Some(name),
             Parameter::Contract(name, alias) => {
                 builder.contract_name_override = alias.or_else(|| Some(name.clone()));
-                contract_name = Some(name);
+                contract_name = Some((span, name));
             }
             Parameter::Crate(name) => builder.runtime_crate_name = name,
             Parameter::Deployments(deployments) => {
@@ -237,70 +430,229 @@ fn generate(args: ContractArgs) -> Result<TokenStream2> {
                 }
             }
             Parameter::Methods(methods) => {
+                methods_span = Some(span);
                 for method in methods {
                     builder
                         .method_aliases
                         .insert(method.signature, method.alias);
                 }
             }
+            Parameter::Deprecated(methods) => {
+                deprecated_span = Some(span);
+                for method in methods {
+                    builder
+                        .deprecated_methods
+                        .insert(method.signature, method.note);
+                }
+            }
             Parameter::EventDerives(derives) => {
                 builder.event_derives.extend(derives);
             }
+            Parameter::EventSubsets(subsets) => {
+                for subset in subsets {
+                    builder = builder.add_event_subset(subset.name, subset.events);
+                }
+            }
             Parameter::Format(format) => artifact_format = format,
+            Parameter::PathEnv(_) => {}
+            Parameter::NonExhaustiveEvents(value) => builder.non_exhaustive_events = value,
+            Parameter::NoEvents(value) => builder.no_events = value,
+            Parameter::EventNumberFormat(format) => builder.event_number_format = Some(format),
+            Parameter::MockFeature(feature) => builder.mock_feature = Some(feature),
+            Parameter::Facets(facets) => {
+                for facet in facets {
+                    let json = load_artifact_json(&resolve(&facet)).at(span)?;
+                    let contract = TruffleLoader::new()
+                        .load_contract_from_str(&json)
+                        .with_context(|| format!("failed to parse facet artifact '{}'", facet))
+                        .at(span)?;
+                    builder.facets.push(contract);
+                }
+            }
         };
     }
 
-    let source = Source::parse(&args.artifact_path)?;
-    let json = source.artifact_json()?;
+    let json = match artifact_format {
+        Format::Abi => load_json(&resolve(&args.artifact_path)),
+        _ => load_artifact_json(&resolve(&args.artifact_path)),
+    }
+    .at(artifact_span)?;
+    let parse_context = || format!("failed to parse artifact '{}'", args.artifact_path);
 
-    match artifact_format {
+    let contract = match artifact_format {
         Format::Truffle => {
-            let mut contract = TruffleLoader::new().load_contract_from_str(&json)?;
+            let mut contract = TruffleLoader::new()
+                .load_contract_from_str(&json)
+                .with_context(parse_context)
+                .at(artifact_span)?;
 
-            if let Some(contract_name) = contract_name {
+            if let Some((span, contract_name)) = contract_name {
                 if contract.name.is_empty() {
                     contract.name = contract_name;
                 } else if contract.name != contract_name {
                     return Err(anyhow!(
-                        "there is no contract '{}' in artifact '{}'",
+                        "there is no contract '{}' in artifact '{}', it contains '{}'",
                         contract_name,
-                        args.artifact_path
-                    ));
+                        args.artifact_path,
+                        contract.name,
+                    ))
+                    .at(span);
                 }
             }
 
-            Ok(builder.generate(&contract)?.into_tokens())
+            contract
+        }
+
+        Format::Abi => {
+            let mut loader = AbiLoader::new();
+            if let Some((_, contract_name)) = contract_name {
+                loader = loader.name(contract_name);
+            }
+            loader
+                .load_contract_from_str(&json)
+                .with_context(parse_context)
+                .at(artifact_span)?
         }
 
         Format::HardHat(format) => {
-            let artifact = HardHatLoader::new().load_from_str(format, &json)?;
+            // NOTE: Only load the requested contract, so that other contracts
+            //   in large exports are skipped without being fully parsed.
+            let mut loader = HardHatLoader::new();
+            if let Some((_, contract_name)) = &contract_name {
+                loader = loader.allow_contract(contract_name);
+            }
+            let artifact = loader
+                .load_from_str(format, &json)
+                .with_context(parse_context)
+                .at(artifact_span)?;
 
-            if let Some(contract_name) = contract_name {
-                if let Some(contract) = artifact.get(&contract_name) {
-                    Ok(builder.generate(contract)?.into_tokens())
-                } else {
-                    Err(anyhow!(
-                        "there is no contract '{}' in artifact '{}'",
-                        contract_name,
-                        args.artifact_path
-                    ))
+            if let Some((span, contract_name)) = contract_name {
+                match artifact.get(&contract_name) {
+                    Some(contract) => contract.clone(),
+                    None => {
+                        return Err(anyhow!(
+                            "there is no contract '{}' in artifact '{}'",
+                            contract_name,
+                            args.artifact_path
+                        ))
+                        .at(span)
+                    }
                 }
             } else {
-                Err(anyhow!(
+                return Err(anyhow!(
                     "when using hardhat artifacts, you should specify \
                      contract name using 'contract' parameter"
                 ))
+                .at(artifact_span);
             }
         }
+    };
+
+    let abi = &contract.interface.abi;
+    let facets = builder.facets.iter().map(|facet| &facet.interface.abi);
+    let signatures = std::iter::once(abi)
+        .chain(facets)
+        .flat_map(|abi| abi.functions())
+        .map(|function| function.abi_signature())
+        .collect::<HashSet<_>>();
+    if let Some(span) = methods_span {
+        for signature in builder.method_aliases.keys() {
+            if !signatures.contains(signature) {
+                return Err(anyhow!(
+                    "a manual method alias for '{}' was specified but this method does not exist",
+                    signature,
+                ))
+                .at(span);
+            }
+        }
+    }
+    if let Some(span) = deprecated_span {
+        for signature in builder.deprecated_methods.keys() {
+            if !signatures.contains(signature) {
+                return Err(anyhow!(
+                    "method '{}' was marked as deprecated but this method does not exist",
+                    signature,
+                ))
+                .at(span);
+            }
+        }
+    }
+
+    Ok(builder.generate(&contract).at(artifact_span)?.into_tokens())
+}
+
+/// Loads artifact JSON from a source string.
+fn load_artifact_json(source: &str) -> Result<String> {
+    Source::parse(source)
+        .and_then(|source| source.artifact_json())
+        .with_context(|| format!("failed to load artifact '{}'", source))
+}
+
+/// Roots a relative artifact path in the directory specified by the `path_env`
+/// environment variable. Sources with a scheme, such as URLs or `etherscan:`
+/// sources, absolute paths and paths starting with an environment variable
+/// are returned as is.
+fn resolve_path(path_env: Option<&str>, path: &str) -> String {
+    match path_env {
+        Some(name) if is_relative_path(path) => format!("${{{}}}/{}", name, path),
+        _ => path.to_owned(),
+    }
+}
+
+/// Returns `true` if an artifact source is a relative file system path.
+fn is_relative_path(source: &str) -> bool {
+    let has_scheme = match source.split_once(':') {
+        Some((scheme, _)) => {
+            scheme.len() > 1
+                && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        }
+        None => false,
+    };
+    !has_scheme && !source.starts_with('$') && std::path::Path::new(source).is_relative()
+}
+
+/// Loads the JSON document from the specified source without wrapping bare
+/// ABIs into artifacts.
+fn load_json(source: &str) -> Result<String> {
+    Source::parse(source)
+        .and_then(|source| source.json())
+        .with_context(|| format!("failed to load artifact '{}'", source))
+}
+
+/// Extension trait for attaching a span to errors.
+trait ResultExt<T> {
+    /// Converts the error into a compile error at the specified span, with
+    /// its whole chain of causes rendered in a human-readable form.
+    fn at(self, span: Span) -> Result<T, SynError>;
+}
+
+impl<T, E> ResultExt<T> for Result<T, E>
+where
+    E: Into<anyhow::Error>,
+{
+    fn at(self, span: Span) -> Result<T, SynError> {
+        self.map_err(|err| SynError::new(span, render_error(&err.into())))
     }
 }
 
+/// Renders an error message along with all of its causes, one per line.
+fn render_error(err: &anyhow::Error) -> String {
+    let mut message = err.to_string();
+    for cause in err.chain().skip(1) {
+        message.push_str(&format!("\n  caused by: {}", cause));
+    }
+    message
+}
+
 /// Contract procedural macro arguments.
 #[cfg_attr(test, derive(Debug, Eq, PartialEq))]
 struct ContractArgs {
     visibility: Option<String>,
     artifact_path: String,
-    parameters: Vec<Parameter>,
+    parameters: Vec<Spanned<Parameter>>,
 }
 
 impl ParseInner for ContractArgs {
@@ -324,7 +676,7 @@ impl ParseInner for ContractArgs {
             input.parse::<Token![,]>()?;
         }
         let parameters = input
-            .parse_terminated(Parameter::parse, Token![,])?
+            .parse_terminated(Spanned::<Parameter>::parse, Token![,])?
             .into_iter()
             .collect();
 
@@ -343,6 +695,7 @@ impl ParseInner for ContractArgs {
 #[cfg_attr(test, derive(Debug, Eq, PartialEq))]
 enum Format {
     Truffle,
+    Abi,
     HardHat(HardHatFormat),
 }
 
@@ -354,8 +707,16 @@ enum Parameter {
     Crate(String),
     Deployments(Vec<Deployment>),
     Methods(Vec<Method>),
+    Deprecated(Vec<DeprecatedMethod>),
     EventDerives(Vec<String>),
+    EventSubsets(Vec<EventSubset>),
     Format(Format),
+    Facets(Vec<String>),
+    PathEnv(String),
+    NonExhaustiveEvents(bool),
+    NoEvents(bool),
+    EventNumberFormat(NumberFormat),
+    MockFeature(String),
 }
 
 impl Parse for Parameter {
@@ -379,6 +740,7 @@ impl Parse for Parameter {
                     "truffle" => Format::Truffle,
                     "hardhat" => Format::HardHat(HardHatFormat::SingleExport),
                     "hardhat_multi" => Format::HardHat(HardHatFormat::MultiExport),
+                    "abi" => Format::Abi,
                     format => {
                         return Err(ParseError::new(
                             token.span(),
@@ -453,6 +815,30 @@ impl Parse for Parameter {
 
                 Parameter::Methods(methods)
             }
+            "deprecated" => {
+                let content;
+                braced!(content in input);
+                let methods = {
+                    let parsed =
+                        content.parse_terminated(Spanned::<DeprecatedMethod>::parse, Token![,])?;
+
+                    let mut methods = Vec::with_capacity(parsed.len());
+                    let mut signatures = HashSet::new();
+                    for method in parsed {
+                        if !signatures.insert(method.signature.clone()) {
+                            return Err(ParseError::new(
+                                method.span(),
+                                "duplicate deprecated method signature in `ethcontract::contract!` macro invocation",
+                            ));
+                        }
+                        methods.push(method.into_inner())
+                    }
+
+                    methods
+                };
+
+                Parameter::Deprecated(methods)
+            }
             "event_derives" => {
                 let content;
                 parenthesized!(content in input);
@@ -463,6 +849,76 @@ impl Parse for Parameter {
                     .collect();
                 Parameter::EventDerives(derives)
             }
+            "event_subsets" => {
+                let content;
+                braced!(content in input);
+                let subsets = {
+                    let parsed =
+                        content.parse_terminated(Spanned::<EventSubset>::parse, Token![,])?;
+
+                    let mut subsets = Vec::with_capacity(parsed.len());
+                    let mut names = HashSet::new();
+                    for subset in parsed {
+                        if !names.insert(subset.name.clone()) {
+                            return Err(ParseError::new(
+                                subset.span(),
+                                "duplicate event subset name in `ethcontract::contract!` macro invocation",
+                            ));
+                        }
+                        subsets.push(subset.into_inner())
+                    }
+
+                    subsets
+                };
+
+                Parameter::EventSubsets(subsets)
+            }
+            "facets" => {
+                input.parse::<Token![=]>()?;
+                let content;
+                bracketed!(content in input);
+                let facets = content
+                    .parse_terminated(<LitStr as Parse>::parse, Token![,])?
+                    .into_iter()
+                    .map(|path| path.value())
+                    .collect();
+                Parameter::Facets(facets)
+            }
+            "path_env" => {
+                input.parse::<Token![=]>()?;
+                let name = input.parse::<LitStr>()?.value();
+                Parameter::PathEnv(name)
+            }
+            "event_number_format" => {
+                input.parse::<Token![=]>()?;
+                let token = input.parse::<Ident>()?;
+                let format = match token.to_string().as_str() {
+                    "hex" => NumberFormat::Hex,
+                    "decimal" => NumberFormat::Decimal,
+                    format => {
+                        return Err(ParseError::new(
+                            token.span(),
+                            format!("unknown number format {}", format),
+                        ))
+                    }
+                };
+                Parameter::EventNumberFormat(format)
+            }
+            "non_exhaustive_events" => {
+                input.parse::<Token![=]>()?;
+                let value = input.parse::<LitBool>()?.value;
+                Parameter::NonExhaustiveEvents(value)
+            }
+            "no_events" => {
+                input.parse::<Token![=]>()?;
+                let value = input.parse::<LitBool>()?.value;
+                Parameter::NoEvents(value)
+            }
+            "mock_feature" => {
+                input.parse::<Token![=]>()?;
+                let feature = input.parse::<LitStr>()?.value();
+                Parameter::MockFeature(feature)
+            }
             _ => {
                 return Err(ParseError::new(
                     name.span(),
@@ -498,6 +954,28 @@ impl Parse for Deployment {
     }
 }
 
+/// A named subset of contract events.
+#[cfg_attr(test, derive(Debug, Eq, PartialEq))]
+struct EventSubset {
+    name: String,
+    events: Vec<String>,
+}
+
+impl Parse for EventSubset {
+    fn parse(input: ParseStream) -> ParseResult<Self> {
+        let name = input.parse::<Ident>()?.to_string();
+        let content;
+        parenthesized!(content in input);
+        let events = content
+            .parse_terminated(Ident::parse, Token![,])?
+            .into_iter()
+            .map(|event| event.to_string())
+            .collect();
+
+        Ok(EventSubset { name, events })
+    }
+}
+
 /// An explicitely named contract method.
 #[cfg_attr(test, derive(Debug, Eq, PartialEq))]
 struct Method {
@@ -507,38 +985,7 @@ struct Method {
 
 impl Parse for Method {
     fn parse(input: ParseStream) -> ParseResult<Self> {
-        let function = {
-            let name = input.parse::<Ident>()?.to_string();
-
-            let content;
-            parenthesized!(content in input);
-            let inputs = content
-                .parse_terminated(Ident::parse, Token![,])?
-                .iter()
-                .map(|ident| {
-                    let kind = ParamType::from_str(&ident.to_string())
-                        .map_err(|err| ParseError::new(ident.span(), err))?;
-                    Ok(Param {
-                        name: "".into(),
-                        kind,
-                        internal_type: None,
-                    })
-                })
-                .collect::<ParseResult<Vec<_>>>()?;
-
-            #[allow(deprecated)]
-            Function {
-                name,
-                inputs,
-
-                // NOTE: The output types and const-ness of the function do not
-                //   affect its signature.
-                outputs: vec![],
-                constant: None,
-                state_mutability: Default::default(),
-            }
-        };
-        let signature = function.abi_signature();
+        let signature = parse_signature(input)?;
         input.parse::<Token![as]>()?;
         let alias = {
             let ident = input.parse::<Ident>()?;
@@ -549,6 +996,60 @@ impl Parse for Method {
     }
 }
 
+/// A contract method marked as deprecated.
+#[cfg_attr(test, derive(Debug, Eq, PartialEq))]
+struct DeprecatedMethod {
+    signature: String,
+    note: String,
+}
+
+impl Parse for DeprecatedMethod {
+    fn parse(input: ParseStream) -> ParseResult<Self> {
+        let signature = parse_signature(input)?;
+        input.parse::<Token![=>]>()?;
+        let note = input.parse::<LitStr>()?.value();
+
+        Ok(DeprecatedMethod { signature, note })
+    }
+}
+
+/// Parses a method signature of the form `name(type,...)`.
+fn parse_signature(input: ParseStream) -> ParseResult<String> {
+    let function = {
+        let name = input.parse::<Ident>()?.to_string();
+
+        let content;
+        parenthesized!(content in input);
+        let inputs = content
+            .parse_terminated(Ident::parse, Token![,])?
+            .iter()
+            .map(|ident| {
+                let kind = ParamType::from_str(&ident.to_string())
+                    .map_err(|err| ParseError::new(ident.span(), err))?;
+                Ok(Param {
+                    name: "".into(),
+                    kind,
+                    internal_type: None,
+                })
+            })
+            .collect::<ParseResult<Vec<_>>>()?;
+
+        #[allow(deprecated)]
+        Function {
+            name,
+            inputs,
+
+            // NOTE: The output types and const-ness of the function do not
+            //   affect its signature.
+            outputs: vec![],
+            constant: None,
+            state_mutability: Default::default(),
+        }
+    };
+
+    Ok(function.abi_signature())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -581,6 +1082,10 @@ mod tests {
         }
     }
 
+    fn spanned(parameters: Vec<Parameter>) -> Vec<Spanned<Parameter>> {
+        parameters.into_iter().map(Spanned::from).collect()
+    }
+
     fn method(signature: &str, alias: &str) -> Method {
         Method {
             signature: signature.into(),
@@ -628,14 +1133,15 @@ mod tests {
                 myMethod(uint256, bool) as my_renamed_method;
                 myOtherMethod() as my_other_renamed_method;
             },
-            event_derives (Asdf, a::B, a::b::c::D)
+            event_derives (Asdf, a::B, a::b::c::D),
+            facets = ["a.json", "b.json"],
         );
         assert_eq!(
             args,
             ContractArgs {
                 visibility: Some(quote!(pub(crate)).to_string()),
                 artifact_path: "artifact.json".into(),
-                parameters: vec![
+                parameters: spanned(vec![
                     Parameter::Crate("foobar".into()),
                     Parameter::Mod("contract".into()),
                     Parameter::Contract("Contract".into(), None),
@@ -651,8 +1157,9 @@ mod tests {
                         "Asdf".into(),
                         "a :: B".into(),
                         "a :: b :: c :: D".into()
-                    ])
-                ],
+                    ]),
+                    Parameter::Facets(vec!["a.json".into(), "b.json".into()]),
+                ]),
             },
         );
     }
@@ -665,13 +1172,25 @@ mod tests {
             ContractArgs {
                 visibility: None,
                 artifact_path: "artifact.json".into(),
-                parameters: vec![Parameter::Format(Format::HardHat(
+                parameters: spanned(vec![Parameter::Format(Format::HardHat(
                     HardHatFormat::MultiExport
-                ))],
+                ))]),
             },
         );
     }
 
+    #[test]
+    fn parse_contract_args_abi_format() {
+        let args = contract_args!("abi.json", format = abi, contract = Token);
+        assert_eq!(
+            args.parameters,
+            &[
+                Parameter::Format(Format::Abi),
+                Parameter::Contract("Token".into(), None),
+            ]
+        );
+    }
+
     #[test]
     fn parse_contract_args_rename() {
         let args = contract_args!("artifact.json", contract = Contract as Renamed);
@@ -680,14 +1199,121 @@ mod tests {
             ContractArgs {
                 visibility: None,
                 artifact_path: "artifact.json".into(),
-                parameters: vec![Parameter::Contract(
+                parameters: spanned(vec![Parameter::Contract(
                     "Contract".into(),
                     Some("Renamed".into())
-                )],
+                )]),
             },
         );
     }
 
+    #[test]
+    fn parse_contract_args_path_env() {
+        let args = contract_args!("artifact.json", path_env = "CONTRACTS_DIR");
+        assert_eq!(
+            args.parameters,
+            &[Parameter::PathEnv("CONTRACTS_DIR".into())]
+        );
+    }
+
+    #[test]
+    fn resolve_path_env() {
+        let env = Some("CONTRACTS_DIR");
+        assert_eq!(
+            resolve_path(env, "build/WETH9.json"),
+            "${CONTRACTS_DIR}/build/WETH9.json"
+        );
+        assert_eq!(resolve_path(None, "build/WETH9.json"), "build/WETH9.json");
+
+        for source in [
+            "/abs/path/WETH9.json",
+            "${OTHER_DIR}/WETH9.json",
+            "file:///abs/path/WETH9.json",
+            "https://example.com/WETH9.json",
+            "etherscan:0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2",
+            "etherscan:1:0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2",
+            "sourcify:0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2",
+            "npm:@openzeppelin/contracts@4.9.0/build/contracts/ERC20.json",
+        ] {
+            assert_eq!(resolve_path(env, source), source);
+        }
+    }
+
+    #[test]
+    fn parse_contract_args_non_exhaustive_events() {
+        let args = contract_args!("artifact.json", non_exhaustive_events = true);
+        assert_eq!(args.parameters, &[Parameter::NonExhaustiveEvents(true)]);
+    }
+
+    #[test]
+    fn parse_contract_args_no_events() {
+        let args = contract_args!("artifact.json", no_events = true);
+        assert_eq!(args.parameters, &[Parameter::NoEvents(true)]);
+    }
+
+    #[test]
+    fn parse_contract_args_event_subsets() {
+        let args = contract_args!(
+            "artifact.json",
+            event_subsets {
+                TokenFlow(Transfer, Approval),
+                Mints(Mint),
+            },
+        );
+        assert_eq!(
+            args.parameters,
+            &[Parameter::EventSubsets(vec![
+                EventSubset {
+                    name: "TokenFlow".into(),
+                    events: vec!["Transfer".into(), "Approval".into()],
+                },
+                EventSubset {
+                    name: "Mints".into(),
+                    events: vec!["Mint".into()],
+                },
+            ])]
+        );
+    }
+
+    #[test]
+    fn parse_contract_args_mock_feature() {
+        let args = contract_args!("artifact.json", mock_feature = "mock");
+        assert_eq!(args.parameters, &[Parameter::MockFeature("mock".into())]);
+    }
+
+    #[test]
+    fn parse_contract_args_event_number_format() {
+        let args = contract_args!("artifact.json", event_number_format = hex);
+        assert_eq!(
+            args.parameters,
+            &[Parameter::EventNumberFormat(NumberFormat::Hex)]
+        );
+    }
+
+    #[test]
+    fn parse_contract_args_deprecated() {
+        let args = contract_args!(
+            "artifact.json",
+            deprecated {
+                myMethod(uint256, bool) => "use `my_other_method`",
+                myOtherMethod() => "",
+            },
+        );
+        assert_eq!(
+            args.parameters,
+            &[Parameter::Deprecated(vec![
+                DeprecatedMethod {
+                    signature: "myMethod(uint256,bool)".into(),
+                    note: "use `my_other_method`".into(),
+                },
+                DeprecatedMethod {
+                    signature: "myOtherMethod()".into(),
+                    note: "".into(),
+                },
+            ])]
+        );
+    }
+
     #[test]
     fn unsupported_format_error() {
         contract_args_err!("artifact.json", format = yaml);
@@ -704,6 +1330,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn duplicate_event_subset_error() {
+        contract_args_err!(
+            "artifact.json",
+            event_subsets {
+                Tokens(Transfer),
+                Tokens(Approval),
+            }
+        );
+    }
+
     #[test]
     fn duplicate_method_rename_error() {
         contract_args_err!(
@@ -722,6 +1359,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn duplicate_deprecated_method_error() {
+        contract_args_err!(
+            "artifact.json",
+            deprecated {
+                myMethod(uint256) => "first",
+                myMethod(uint256) => "second",
+            }
+        );
+    }
+
+    #[test]
+    fn render_error_with_causes() {
+        let err = anyhow!("file not found")
+            .context("failed to read artifact JSON file")
+            .context("failed to load artifact 'artifact.json'");
+        assert_eq!(
+            render_error(&err),
+            "failed to load artifact 'artifact.json'\n  \
+             caused by: failed to read artifact JSON file\n  \
+             caused by: file not found",
+        );
+    }
+
     #[test]
     fn method_invalid_method_parameter_type() {
         contract_args_err!(
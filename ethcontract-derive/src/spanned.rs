@@ -41,6 +41,30 @@ impl<T> Spanned<T> {
     }
 }
 
+#[cfg(test)]
+impl<T> From<T> for Spanned<T> {
+    fn from(value: T) -> Self {
+        Spanned(Span::call_site(), value)
+    }
+}
+
+#[cfg(test)]
+impl<T: PartialEq> PartialEq for Spanned<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.1 == other.1
+    }
+}
+
+#[cfg(test)]
+impl<T: Eq> Eq for Spanned<T> {}
+
+#[cfg(test)]
+impl<T: PartialEq> PartialEq<T> for Spanned<T> {
+    fn eq(&self, other: &T) -> bool {
+        self.1 == *other
+    }
+}
+
 impl<T> Deref for Spanned<T> {
     type Target = T;
 
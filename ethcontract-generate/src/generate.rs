@@ -2,13 +2,23 @@
 //! crate is intended to be used either indirectly with the `ethcontract`
 //! crate's `contract` procedural macro or directly from a build script.
 
+mod calls;
 mod common;
 mod deployment;
+mod errors;
 mod events;
+mod facets;
+#[cfg(test)]
+mod fixtures;
+mod interface;
 mod methods;
+mod mocks;
+mod selectors;
+mod smoke_tests;
+mod structs;
 mod types;
 
-use crate::{util, ContractBuilder};
+use crate::{util, ContractBuilder, NumberFormat};
 use anyhow::{anyhow, Context as _, Result};
 use ethcontract_common::contract::Network;
 use ethcontract_common::Contract;
@@ -31,6 +41,13 @@ pub(crate) struct Context<'a> {
     /// The visibility for the generated module and re-exported contract type.
     visibility: Visibility,
 
+    /// The visibility for the generated inner items, such as the methods
+    /// struct and the event modules.
+    item_visibility: Visibility,
+
+    /// Whether to re-export the contract type in the root.
+    reexport: bool,
+
     /// The name of the module as an identifier in which to place the contract
     /// implementation. Note that the main contract type gets re-exported in the
     /// root.
@@ -45,8 +62,36 @@ pub(crate) struct Context<'a> {
     /// Manually specified method aliases.
     method_aliases: HashMap<String, Ident>,
 
+    /// Identifiers of the generated struct types, keyed by qualified
+    /// Solidity struct name.
+    structs: HashMap<String, Ident>,
+
+    /// Deprecation notes for methods, keyed by method signature.
+    deprecated_methods: HashMap<String, String>,
+
     /// Derives added to event structs and enums.
     event_derives: Vec<Path>,
+
+    /// Serialization format for 256-bit integer event fields.
+    event_number_format: Option<NumberFormat>,
+
+    /// Whether the event enum is non-exhaustive with an `Unknown` variant.
+    non_exhaustive_events: bool,
+
+    /// Named subsets of events for which narrowed event enums are generated.
+    event_subsets: Vec<(String, Vec<String>)>,
+
+    /// Whether to skip generating event code.
+    no_events: bool,
+
+    /// Whether to generate mock-based smoke tests for contract methods.
+    generate_tests: bool,
+
+    /// The Cargo feature gating the generated mock module, if any.
+    mock_feature: Option<String>,
+
+    /// Whether to generate a trait abstracting the contract methods.
+    generate_interface_trait: bool,
 }
 
 impl<'a> Context<'a> {
@@ -70,6 +115,10 @@ impl<'a> Context<'a> {
             Some(vis) => syn::parse_str(vis)?,
             None => Visibility::Inherited,
         };
+        let item_visibility = match &builder.item_visibility_modifier {
+            Some(vis) => syn::parse_str(vis)?,
+            None => syn::parse_quote!(pub),
+        };
         let contract_mod = if let Some(name) = &builder.contract_mod_override {
             util::ident(name)
         } else {
@@ -102,16 +151,36 @@ impl<'a> Context<'a> {
             contract,
             runtime_crate,
             visibility,
+            item_visibility,
+            reexport: builder.reexport,
             contract_mod,
             contract_name,
             networks: builder.networks,
             method_aliases,
+            structs: structs::names(&contract.interface.structs),
+            deprecated_methods: builder.deprecated_methods,
             event_derives,
+            event_number_format: builder.event_number_format,
+            non_exhaustive_events: builder.non_exhaustive_events,
+            event_subsets: builder.event_subsets,
+            no_events: builder.no_events,
+            generate_tests: builder.generate_tests,
+            mock_feature: builder.mock_feature,
+            generate_interface_trait: builder.generate_interface_trait,
         })
     }
 }
 
-pub(crate) fn expand(contract: &Contract, builder: ContractBuilder) -> Result<TokenStream> {
+pub(crate) fn expand(contract: &Contract, mut builder: ContractBuilder) -> Result<TokenStream> {
+    let merged;
+    let contract = if builder.facets.is_empty() {
+        contract
+    } else {
+        let facets = std::mem::take(&mut builder.facets);
+        merged = facets::merge(contract, &facets).context("error merging contract facets")?;
+        &merged
+    };
+
     let cx = Context::from_builder(contract, builder)?;
     let contract = expand_contract(&cx).context("error expanding contract from its ABI")?;
 
@@ -125,21 +194,55 @@ fn expand_contract(cx: &Context) -> Result<TokenStream> {
     let contract_name = &cx.contract_name;
 
     let common = common::expand(cx);
+    let structs = structs::expand(cx)?;
     let deployment = deployment::expand(cx)?;
     let methods = methods::expand(cx)?;
     let events = events::expand(cx)?;
+    let selectors = selectors::expand(cx);
+    let errors = errors::expand(cx)?;
+    let calls = calls::expand(cx)?;
+    let interface = interface::expand(cx)?;
+    let mocks = mocks::expand(cx)?;
+    let smoke_tests = smoke_tests::expand(cx);
+
+    // NOTE: Generated code refers to deprecated methods, for example in mock
+    //   helpers, which should not produce warnings in the user's crate.
+    let allow_deprecated = if cx.deprecated_methods.is_empty() {
+        quote! {}
+    } else {
+        quote! { deprecated, }
+    };
+    // NOTE: Public contract methods return the inner items, which is fine
+    //   when their visibility is restricted on purpose.
+    let allow_private = if let Visibility::Public(_) = cx.item_visibility {
+        quote! {}
+    } else {
+        quote! { private_interfaces, }
+    };
+    let reexport = if cx.reexport {
+        quote! { #vis use self::#contract_mod::Contract as #contract_name; }
+    } else {
+        quote! {}
+    };
 
     Ok(quote! {
-        #[allow(dead_code, clippy::type_complexity, clippy::large_enum_variant)]
+        #[allow(#allow_deprecated #allow_private dead_code, clippy::type_complexity, clippy::large_enum_variant)]
         #vis mod #contract_mod {
             #[rustfmt::skip]
             use #runtime_crate as ethcontract;
 
             #common
+            #structs
             #deployment
             #methods
             #events
+            #selectors
+            #errors
+            #calls
+            #interface
+            #mocks
+            #smoke_tests
         }
-        #vis use self::#contract_mod::Contract as #contract_name;
+        #reexport
     })
 }
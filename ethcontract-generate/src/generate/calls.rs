@@ -0,0 +1,237 @@
+use crate::generate::{types, Context};
+use crate::util;
+use anyhow::{Context as _, Result};
+use ethcontract_common::abi::Function;
+use ethcontract_common::abiext::FunctionExt;
+use inflector::Inflector;
+use proc_macro2::{Literal, TokenStream};
+use quote::quote;
+
+/// Expands the functions of a contract ABI into a `Call` enum that can be
+/// decoded from transaction calldata, along with a helper for retrieving
+/// transactions and decoding their calls. Expands to nothing for contracts
+/// without functions.
+pub(crate) fn expand(cx: &Context) -> Result<TokenStream> {
+    let abi = &cx.contract.interface.abi;
+    if abi.functions.is_empty() {
+        return Ok(quote! {});
+    }
+
+    let calls = abi
+        .functions()
+        .map(|function| {
+            let signature = function.abi_signature();
+            let name = cx
+                .method_aliases
+                .get(&signature)
+                .map(|alias| alias.to_string())
+                .unwrap_or_else(|| function.name.clone());
+            let variant = util::ident(&name.trim_start_matches("r#").to_pascal_case());
+            expand_call(cx, function, variant)
+                .with_context(|| format!("error expanding function '{}'", signature))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let variants = calls.iter().map(|(variant, _)| variant);
+    let decoders = calls.iter().map(|(_, decoder)| decoder);
+
+    Ok(quote! {
+        /// Calls to the contract's methods that can be decoded from
+        /// transaction calldata.
+        #[derive(Clone, Debug, Eq, PartialEq)]
+        pub enum Call {
+            #( #variants, )*
+        }
+
+        impl self::ethcontract::contract::ParseCall for Call {
+            fn parse_call(data: &[u8]) -> Result<Self, self::ethcontract::errors::ExecutionError> {
+                let abi = &Contract::raw_contract().interface.abi;
+                match data.get(..4) {
+                    #( #decoders )*
+                    _ => Err(self::ethcontract::common::abi::Error::InvalidData.into()),
+                }
+            }
+        }
+
+        impl Contract {
+            /// Retrieves a transaction by hash and decodes its calldata as a
+            /// call to one of the contract's methods. Returns `None` if the
+            /// transaction does not exist.
+            pub async fn transaction_call(
+                &self,
+                hash: self::ethcontract::H256,
+            ) -> Result<
+                Option<self::ethcontract::contract::TransactionCall<Call>>,
+                self::ethcontract::errors::ExecutionError,
+            > {
+                self::ethcontract::contract::transaction_call(&self.raw_instance().web3(), hash)
+                    .await
+            }
+        }
+    })
+}
+
+/// Expands a function into an enum variant and the match arm for decoding it
+/// from calldata. Variants have named fields if all function parameters are
+/// named, and unnamed fields otherwise.
+fn expand_call(
+    cx: &Context,
+    function: &Function,
+    variant: syn::Ident,
+) -> Result<(TokenStream, TokenStream)> {
+    let doc = util::expand_doc(&format!("`{}`", function.abi_signature()));
+    let selector = function
+        .short_signature()
+        .iter()
+        .copied()
+        .map(Literal::u8_unsuffixed)
+        .collect::<Vec<_>>();
+
+    let types = function
+        .inputs
+        .iter()
+        .map(|input| types::expand_param(cx, input))
+        .collect::<Result<Vec<_>>>()?;
+    let indices = (0..types.len()).map(Literal::usize_unsuffixed);
+
+    let (definition, construction) = if types.is_empty() {
+        (quote! { #variant }, quote! { |()| Call::#variant })
+    } else if function.inputs.iter().all(|input| !input.name.is_empty()) {
+        let names = function
+            .inputs
+            .iter()
+            .enumerate()
+            .map(|(i, input)| util::expand_input_name(i, &input.name))
+            .collect::<Vec<_>>();
+        (
+            quote! { #variant { #( #names: #types ),* } },
+            quote! { |params| Call::#variant { #( #names: params.#indices ),* } },
+        )
+    } else {
+        (
+            quote! { #variant( #( #types ),* ) },
+            quote! { |params| Call::#variant( #( params.#indices ),* ) },
+        )
+    };
+
+    Ok((
+        quote! {
+            #doc
+            #definition
+        },
+        quote! {
+            Some([#( #selector ),*]) => self::ethcontract::private::decode_call::<(#( #types, )*)>(
+                abi,
+                data,
+            )
+            .map(#construction),
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::fixtures::{self, function};
+    use crate::ContractBuilder;
+    use ethcontract_common::abi::ParamType;
+    use ethcontract_common::Contract;
+
+    fn contract(functions: Vec<Function>) -> Contract {
+        fixtures::contract("Token", functions, vec![], vec![])
+    }
+
+    #[test]
+    fn expand_no_calls() {
+        let contract = contract(vec![]);
+        let cx = Context::from_builder(&contract, ContractBuilder::new()).unwrap();
+        assert_quote!(expand(&cx).unwrap(), {});
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn expand_calls() {
+        let contract = contract(vec![
+            function(
+                "transfer",
+                &[("to", ParamType::Address), ("value", ParamType::Uint(256))],
+            ),
+            function("pause", &[]),
+        ]);
+        let cx = Context::from_builder(&contract, ContractBuilder::new()).unwrap();
+
+        assert_quote!(expand(&cx).unwrap(), {
+            /// Calls to the contract's methods that can be decoded from
+            /// transaction calldata.
+            #[derive(Clone, Debug, Eq, PartialEq)]
+            pub enum Call {
+                #[doc = "`pause()`"]
+                Pause,
+                #[doc = "`transfer(address,uint256)`"]
+                Transfer {
+                    to: self::ethcontract::Address,
+                    value: self::ethcontract::U256
+                },
+            }
+
+            impl self::ethcontract::contract::ParseCall for Call {
+                fn parse_call(data: &[u8]) -> Result<Self, self::ethcontract::errors::ExecutionError> {
+                    let abi = &Contract::raw_contract().interface.abi;
+                    match data.get(..4) {
+                        Some([132, 86, 203, 89]) => self::ethcontract::private::decode_call::<()>(
+                            abi,
+                            data,
+                        )
+                        .map(|()| Call::Pause),
+                        Some([169, 5, 156, 187]) => self::ethcontract::private::decode_call::<(
+                            self::ethcontract::Address,
+                            self::ethcontract::U256,
+                        )>(
+                            abi,
+                            data,
+                        )
+                        .map(|params| Call::Transfer {
+                            to: params.0,
+                            value: params.1
+                        }),
+                        _ => Err(self::ethcontract::common::abi::Error::InvalidData.into()),
+                    }
+                }
+            }
+
+            impl Contract {
+                /// Retrieves a transaction by hash and decodes its calldata as a
+                /// call to one of the contract's methods. Returns `None` if the
+                /// transaction does not exist.
+                pub async fn transaction_call(
+                    &self,
+                    hash: self::ethcontract::H256,
+                ) -> Result<
+                    Option<self::ethcontract::contract::TransactionCall<Call>>,
+                    self::ethcontract::errors::ExecutionError,
+                > {
+                    self::ethcontract::contract::transaction_call(&self.raw_instance().web3(), hash)
+                        .await
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn expand_aliased_overloads() {
+        let contract = contract(vec![
+            function("mint", &[("", ParamType::Uint(256))]),
+            function("mint", &[("to", ParamType::Address)]),
+        ]);
+        let cx = Context::from_builder(
+            &contract,
+            ContractBuilder::new()
+                .add_method_alias("mint(uint256)", "mint_amount")
+                .add_method_alias("mint(address)", "mint_to"),
+        )
+        .unwrap();
+
+        let tokens = expand(&cx).unwrap().to_string();
+        assert!(tokens.contains(&quote! { MintAmount(self::ethcontract::U256) }.to_string()));
+        assert!(tokens.contains(&quote! { MintTo { to: self::ethcontract::Address } }.to_string()));
+    }
+}
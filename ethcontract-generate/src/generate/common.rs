@@ -68,35 +68,10 @@ pub(crate) fn expand(cx: &Context) -> TokenStream {
             ///
             /// Note that this does not verify that a contract with a matching
             /// `Abi` is actually deployed at the given address.
-            pub fn at<F, B, T>(
+            pub fn at<T: self::ethcontract::transport::EthTransport>(
                 web3: &self::ethcontract::web3::api::Web3<T>,
                 address: self::ethcontract::Address,
             ) -> Self
-            where
-                F: std::future::Future<
-                        Output = Result<
-                            self::ethcontract::json::Value,
-                            self::ethcontract::web3::Error,
-                        >,
-                    > + Send
-                    + 'static,
-                B: std::future::Future<
-                        Output = Result<
-                            Vec<
-                                Result<
-                                    self::ethcontract::json::Value,
-                                    self::ethcontract::web3::Error,
-                                >,
-                            >,
-                            self::ethcontract::web3::Error,
-                        >,
-                    > + Send
-                    + 'static,
-                T: self::ethcontract::web3::Transport<Out = F>
-                    + self::ethcontract::web3::BatchTransport<Batch = B>
-                    + Send
-                    + Sync
-                    + 'static,
             {
                 Contract::with_deployment_info(web3, address, None)
             }
@@ -109,42 +84,16 @@ pub(crate) fn expand(cx: &Context) -> TokenStream {
             /// Note that this does not verify that a contract with a matching `Abi` is
             /// actually deployed at the given address nor that the transaction hash,
             /// when provided, is actually for this contract deployment.
-            pub fn with_deployment_info<F, B, T>(
+            pub fn with_deployment_info<T: self::ethcontract::transport::EthTransport>(
                 web3: &self::ethcontract::web3::api::Web3<T>,
                 address: self::ethcontract::Address,
                 deployment_information: Option<ethcontract::common::DeploymentInformation>,
             ) -> Self
-            where
-                F: std::future::Future<
-                        Output = Result<
-                            self::ethcontract::json::Value,
-                            self::ethcontract::web3::Error,
-                        >,
-                    > + Send
-                    + 'static,
-                B: std::future::Future<
-                        Output = Result<
-                            Vec<
-                                Result<
-                                    self::ethcontract::json::Value,
-                                    self::ethcontract::web3::Error,
-                                >,
-                            >,
-                            self::ethcontract::web3::Error,
-                        >,
-                    > + Send
-                    + 'static,
-                T: self::ethcontract::web3::Transport<Out = F>
-                    + self::ethcontract::web3::BatchTransport<Batch = B>
-                    + Send
-                    + Sync
-                    + 'static,
             {
                 use self::ethcontract::Instance;
-                use self::ethcontract::transport::DynTransport;
                 use self::ethcontract::web3::api::Web3;
 
-                let transport = DynTransport::new(web3.transport().clone());
+                let transport = web3.transport().clone().into_dyn();
                 let web3 = Web3::new(transport);
                 let interface = Self::raw_contract().interface.clone();
                 let instance = Instance::with_deployment_info(web3, interface, address, deployment_information);
@@ -152,6 +101,35 @@ pub(crate) fn expand(cx: &Context) -> TokenStream {
                 Contract::from_raw(instance)
             }
 
+            /// Creates a registry of contract instances for each of the
+            /// specified per-chain `web3` providers, using the deployments
+            /// from the contract artifact's networks.
+            ///
+            /// Note that this does not verify that a contract with a matching
+            /// `Abi` is actually deployed on each of the chains.
+            pub fn multichain<T, I>(
+                web3s: I,
+            ) -> Result<
+                self::ethcontract::contract::MultichainInstance<Self>,
+                self::ethcontract::errors::DeployError,
+            >
+            where
+                T: self::ethcontract::transport::EthTransport,
+                I: IntoIterator<Item = (u64, self::ethcontract::web3::api::Web3<T>)>,
+            {
+                self::ethcontract::contract::MultichainInstance::from_networks(
+                    &Self::raw_contract().networks,
+                    web3s,
+                    |web3, network| {
+                        Contract::with_deployment_info(
+                            &web3,
+                            network.address,
+                            network.deployment_information,
+                        )
+                    },
+                )
+            }
+
             /// Creates a contract from a raw instance.
             fn from_raw(instance: self::ethcontract::dyns::DynInstance) -> Self {
                 let methods = Methods { instance };
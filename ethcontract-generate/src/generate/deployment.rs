@@ -1,4 +1,4 @@
-use crate::generate::{methods, Context};
+use crate::generate::{methods, types, Context};
 use crate::util;
 use anyhow::{Context as _, Result};
 use inflector::Inflector;
@@ -28,39 +28,13 @@ fn expand_deployed(cx: &Context) -> TokenStream {
             ///
             /// Note that this does not verify that a contract with a matching
             /// `Abi` is actually deployed at the given address.
-            pub async fn deployed<F, B, T>(
+            pub async fn deployed<T: self::ethcontract::transport::EthTransport>(
                 web3: &self::ethcontract::web3::api::Web3<T>,
             ) -> Result<Self, self::ethcontract::errors::DeployError>
-            where
-                F: std::future::Future<
-                        Output = Result<
-                            self::ethcontract::json::Value,
-                            self::ethcontract::web3::Error,
-                        >,
-                    > + Send
-                    + 'static,
-                B: std::future::Future<
-                        Output = Result<
-                            Vec<
-                                Result<
-                                    self::ethcontract::json::Value,
-                                    self::ethcontract::web3::Error,
-                                >,
-                            >,
-                            self::ethcontract::web3::Error,
-                        >,
-                    > + Send
-                    + 'static,
-                T: self::ethcontract::web3::Transport<Out = F>
-                    + self::ethcontract::web3::BatchTransport<Batch = B>
-                    + Send
-                    + Sync
-                    + 'static,
             {
                 use self::ethcontract::{Instance, Web3};
-                use self::ethcontract::transport::DynTransport;
 
-                let transport = DynTransport::new(web3.transport().clone());
+                let transport = web3.transport().clone().into_dyn();
                 let web3 = Web3::new(transport);
                 let instance = Instance::deployed(web3, Contract::raw_contract().clone()).await?;
 
@@ -80,12 +54,13 @@ fn expand_deploy(cx: &Context) -> Result<TokenStream> {
     //   can't seem to get truffle to output it
     let doc = util::expand_doc("Generated by `ethcontract`");
 
-    let (input, arg) = match cx.contract.interface.abi.constructor() {
+    let (input, arg, arg_types) = match cx.contract.interface.abi.constructor() {
         Some(constructor) => (
-            methods::expand_inputs(&constructor.inputs)?,
+            methods::expand_inputs(cx, &constructor.inputs)?,
             methods::expand_inputs_call_arg(&constructor.inputs),
+            methods::expand_input_types(cx, &constructor.inputs)?,
         ),
-        None => (quote! {}, quote! {()}),
+        None => (quote! {}, quote! {()}, quote! {()}),
     };
 
     let libs: Vec<_> = cx
@@ -94,7 +69,7 @@ fn expand_deploy(cx: &Context) -> Result<TokenStream> {
         .undefined_libraries()
         .map(|name| (name, util::safe_ident(&name.to_snake_case())))
         .collect();
-    let (lib_struct, lib_input, link) = if !libs.is_empty() {
+    let (lib_struct, lib_input, link, lib_param) = if !libs.is_empty() {
         let lib_struct = {
             let lib_struct_fields = libs.iter().map(|(name, field)| {
                 let doc = util::expand_doc(&format!("Address of the `{}` library.", name));
@@ -128,51 +103,55 @@ fn expand_deploy(cx: &Context) -> Result<TokenStream> {
             }
         };
 
-        (lib_struct, quote! { , libs: Libraries }, link)
+        (
+            lib_struct,
+            quote! { , libs: Libraries },
+            link,
+            Some(quote! { libs: Libraries }),
+        )
     } else {
         Default::default()
     };
 
+    let constructor_inputs = cx
+        .contract
+        .interface
+        .abi
+        .constructor()
+        .map(|constructor| &constructor.inputs[..])
+        .unwrap_or_default();
+    let ctor_params = lib_param
+        .iter()
+        .cloned()
+        .chain(
+            constructor_inputs
+                .iter()
+                .enumerate()
+                .map(|(i, param)| {
+                    let name = util::expand_input_name(i, &param.name);
+                    let kind = types::expand_param(cx, param)?;
+                    Ok(quote! { #name: #kind })
+                })
+                .collect::<Result<Vec<_>>>()?,
+        )
+        .collect::<Vec<_>>();
+    let ctor_struct = expand_constructor_struct(cx, constructor_inputs, lib_param.is_some())?;
+
     Ok(quote! {
         #lib_struct
+        #ctor_struct
 
         impl Contract {
             #doc
             #[allow(clippy::too_many_arguments)]
-            pub fn builder<F, B, T>(
+            pub fn builder<T: self::ethcontract::transport::EthTransport>(
                 web3: &self::ethcontract::web3::api::Web3<T> #lib_input #input ,
             ) -> self::ethcontract::dyns::DynDeployBuilder<Self>
-            where
-                F: std::future::Future<
-                        Output = Result<
-                            self::ethcontract::json::Value,
-                            self::ethcontract::web3::Error,
-                        >,
-                    > + Send
-                    + 'static,
-                B: std::future::Future<
-                        Output = Result<
-                            Vec<
-                                Result<
-                                    self::ethcontract::json::Value,
-                                    self::ethcontract::web3::Error,
-                                >,
-                            >,
-                            self::ethcontract::web3::Error,
-                        >,
-                    > + Send
-                    + 'static,
-                T: self::ethcontract::web3::Transport<Out = F>
-                    + self::ethcontract::web3::BatchTransport<Batch = B>
-                    + Send
-                    + Sync
-                    + 'static,
             {
-                use self::ethcontract::dyns::DynTransport;
                 use self::ethcontract::contract::DeployBuilder;
                 use self::ethcontract::web3::api::Web3;
 
-                let transport = DynTransport::new(web3.transport().clone());
+                let transport = web3.transport().clone().into_dyn();
                 let web3 = Web3::new(transport);
 
                 let bytecode = Self::raw_contract().bytecode.clone();
@@ -180,6 +159,38 @@ fn expand_deploy(cx: &Context) -> Result<TokenStream> {
 
                 DeployBuilder::new(web3, bytecode, #arg).expect("valid deployment args")
             }
+
+            /// Returns the input data of a transaction deploying this contract
+            /// with the specified constructor arguments, for deploying it with
+            /// external deployers such as `CREATE2` factories.
+            #[allow(clippy::too_many_arguments)]
+            pub fn constructor_calldata(
+                #( #ctor_params ),*
+            ) -> self::ethcontract::types::Bytes {
+                let contract = Self::raw_contract();
+                let bytecode = contract.bytecode.clone();
+                #link
+
+                self::ethcontract::contract::constructor_calldata(
+                    &contract.interface.abi,
+                    &bytecode,
+                    #arg,
+                )
+                .expect("valid deployment args")
+            }
+
+            /// Decodes the constructor arguments from the input data of a
+            /// transaction that created an instance of this contract.
+            pub fn decode_constructor(
+                data: &[u8],
+            ) -> Result<#arg_types, self::ethcontract::errors::DeployError> {
+                let contract = Self::raw_contract();
+                self::ethcontract::contract::decode_constructor_args(
+                    &contract.interface.abi,
+                    &contract.bytecode,
+                    data,
+                )
+            }
         }
 
         impl self::ethcontract::contract::Deploy<self::ethcontract::dyns::DynTransport> for Contract {
@@ -204,3 +215,93 @@ fn expand_deploy(cx: &Context) -> Result<TokenStream> {
         }
     })
 }
+
+/// Expands a struct with the named constructor arguments of the contract,
+/// for introspecting and encoding deployments separately from deploying them.
+fn expand_constructor_struct(
+    cx: &Context,
+    inputs: &[ethcontract_common::abi::Param],
+    linked: bool,
+) -> Result<TokenStream> {
+    if cx.contract.interface.abi.constructor().is_none() {
+        return Ok(quote! {});
+    }
+
+    let names = inputs
+        .iter()
+        .enumerate()
+        .map(|(i, param)| util::expand_input_name(i, &param.name))
+        .collect::<Vec<_>>();
+    let fields = inputs
+        .iter()
+        .zip(&names)
+        .map(|(param, name)| {
+            let doc = util::expand_doc(&format!("The `{}` constructor argument.", name));
+            let kind = types::expand_param(cx, param)?;
+            Ok(quote! { #doc pub #name: #kind })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let (lib_param, lib_arg) = if linked {
+        (quote! { , libs: Libraries }, quote! { libs, })
+    } else {
+        Default::default()
+    };
+
+    Ok(quote! {
+        /// The constructor arguments of the contract.
+        #[derive(Clone, Debug, Eq, PartialEq)]
+        pub struct Constructor {
+            #( #fields, )*
+        }
+
+        impl Constructor {
+            /// Returns the input data of a transaction deploying the contract
+            /// with these constructor arguments.
+            pub fn calldata(self #lib_param) -> self::ethcontract::types::Bytes {
+                Contract::constructor_calldata(#lib_arg #( self.#names ),*)
+            }
+
+            /// Decodes the constructor arguments from the input data of a
+            /// transaction that created an instance of the contract.
+            pub fn decode(data: &[u8]) -> Result<Self, self::ethcontract::errors::DeployError> {
+                let ( #( #names, )* ) = Contract::decode_constructor(data)?;
+                Ok(Constructor { #( #names ),* })
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ContractBuilder;
+    use ethcontract_common::artifact::truffle::TruffleLoader;
+
+    #[test]
+    fn expand_deploy_with_struct_arguments() {
+        let contract = TruffleLoader::new()
+            .load_contract_from_file(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/../examples/truffle/build/contracts/StructConstructor.json",
+            ))
+            .unwrap();
+        let cx = Context::from_builder(&contract, ContractBuilder::new()).unwrap();
+
+        let expanded = expand_deploy(&cx).unwrap().to_string();
+        let builder = quote! {
+            pub fn builder<T: self::ethcontract::transport::EthTransport>(
+                web3: &self::ethcontract::web3::api::Web3<T>,
+                order: self::structs::Order,
+                extra_fees: Vec<self::structs::Fee>,
+            )
+        };
+        let decode = quote! {
+            Result<
+                (self::structs::Order, Vec<self::structs::Fee>,),
+                self::ethcontract::errors::DeployError
+            >
+        };
+        assert!(expanded.contains(&builder.to_string()), "{}", expanded);
+        assert!(expanded.contains(&decode.to_string()), "{}", expanded);
+    }
+}
@@ -0,0 +1,248 @@
+use crate::generate::{types, Context};
+use crate::util;
+use anyhow::{Context as _, Result};
+use ethcontract_common::abi::AbiError;
+use inflector::Inflector;
+use proc_macro2::{Literal, TokenStream};
+use quote::{format_ident, quote};
+
+/// Expands the custom errors of a contract ABI into a `ContractError` enum
+/// that can be decoded from the revert data of failed calls. Expands to
+/// nothing for contracts without custom errors.
+pub(crate) fn expand(cx: &Context) -> Result<TokenStream> {
+    let abi = &cx.contract.interface.abi;
+    if abi.errors.is_empty() {
+        return Ok(quote! {});
+    }
+
+    let errors = abi
+        .errors
+        .values()
+        .flat_map(|overloads| {
+            overloads.iter().enumerate().map(move |(i, error)| {
+                let name = error.name.to_pascal_case();
+                let variant = if overloads.len() > 1 {
+                    format_ident!("{}{}", name, i)
+                } else {
+                    util::ident(&name)
+                };
+                expand_error(cx, error, variant)
+                    .with_context(|| format!("error expanding custom error '{}'", error.name))
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let variants = errors.iter().map(|(variant, _)| variant);
+    let decoders = errors.iter().map(|(_, decoder)| decoder);
+
+    Ok(quote! {
+        /// Custom errors defined by the contract that can be decoded from
+        /// the revert data of failed method calls.
+        #[derive(Clone, Debug, Eq, PartialEq)]
+        pub enum ContractError {
+            #( #variants, )*
+        }
+
+        impl ContractError {
+            /// Decodes a custom error from ABI encoded revert data starting
+            /// with the 4-byte error selector. Returns `None` if the data
+            /// does not encode one of the contract's errors.
+            pub fn from_revert_data(data: &[u8]) -> Option<Self> {
+                let abi = &Contract::raw_contract().interface.abi;
+                match data.get(..4)? {
+                    #( #decoders )*
+                    _ => None,
+                }
+            }
+
+            /// Decodes the custom error that a contract method call reverted
+            /// with. Returns `None` if the call failed for another reason.
+            pub fn from_method_error(
+                error: &self::ethcontract::errors::MethodError,
+            ) -> Option<Self> {
+                Self::from_revert_data(&error.custom_error()?.data)
+            }
+        }
+
+        impl self::ethcontract::errors::DecodeCustomError for ContractError {
+            fn decode_custom_error(
+                error: &self::ethcontract::errors::CustomError,
+            ) -> Option<Self> {
+                Self::from_revert_data(&error.data)
+            }
+        }
+    })
+}
+
+/// Expands a custom error into an enum variant and the match arm for decoding
+/// it from revert data. Variants have named fields if all error parameters
+/// are named, and unnamed fields otherwise.
+fn expand_error(
+    cx: &Context,
+    error: &AbiError,
+    variant: syn::Ident,
+) -> Result<(TokenStream, TokenStream)> {
+    let signature = format!(
+        "{}({})",
+        error.name,
+        error
+            .inputs
+            .iter()
+            .map(|input| input.kind.to_string())
+            .collect::<Vec<_>>()
+            .join(","),
+    );
+    let doc = util::expand_doc(&format!("`{}`", signature));
+    let selector = error.signature()[..4]
+        .iter()
+        .copied()
+        .map(Literal::u8_unsuffixed)
+        .collect::<Vec<_>>();
+
+    let types = error
+        .inputs
+        .iter()
+        .map(|input| types::expand_param(cx, input))
+        .collect::<Result<Vec<_>>>()?;
+    let indices = (0..types.len()).map(Literal::usize_unsuffixed);
+
+    let (definition, construction) = if types.is_empty() {
+        (quote! { #variant }, quote! { |()| ContractError::#variant })
+    } else if error.inputs.iter().all(|input| !input.name.is_empty()) {
+        let names = error
+            .inputs
+            .iter()
+            .enumerate()
+            .map(|(i, input)| util::expand_input_name(i, &input.name))
+            .collect::<Vec<_>>();
+        (
+            quote! { #variant { #( #names: #types ),* } },
+            quote! { |params| ContractError::#variant { #( #names: params.#indices ),* } },
+        )
+    } else {
+        (
+            quote! { #variant( #( #types ),* ) },
+            quote! { |params| ContractError::#variant( #( params.#indices ),* ) },
+        )
+    };
+
+    Ok((
+        quote! {
+            #doc
+            #definition
+        },
+        quote! {
+            [#( #selector ),*] => self::ethcontract::private::decode_error::<(#( #types, )*)>(
+                abi,
+                data,
+            )
+            .map(#construction),
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::fixtures::{self, error};
+    use crate::ContractBuilder;
+    use ethcontract_common::abi::ParamType;
+    use ethcontract_common::Contract;
+
+    fn contract(errors: Vec<AbiError>) -> Contract {
+        fixtures::contract("Vault", vec![], vec![], errors)
+    }
+
+    #[test]
+    fn expand_no_errors() {
+        let contract = contract(vec![]);
+        let cx = Context::from_builder(&contract, ContractBuilder::new()).unwrap();
+        assert_quote!(expand(&cx).unwrap(), {});
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn expand_custom_errors() {
+        let contract = contract(vec![
+            error(
+                "InsufficientBalance",
+                &[("available", ParamType::Uint(256)), ("required", ParamType::Uint(256))],
+            ),
+            error("Unauthorized", &[]),
+        ]);
+        let cx = Context::from_builder(&contract, ContractBuilder::new()).unwrap();
+
+        assert_quote!(expand(&cx).unwrap(), {
+            /// Custom errors defined by the contract that can be decoded from
+            /// the revert data of failed method calls.
+            #[derive(Clone, Debug, Eq, PartialEq)]
+            pub enum ContractError {
+                #[doc = "`InsufficientBalance(uint256,uint256)`"]
+                InsufficientBalance {
+                    available: self::ethcontract::U256,
+                    required: self::ethcontract::U256
+                },
+                #[doc = "`Unauthorized()`"]
+                Unauthorized,
+            }
+
+            impl ContractError {
+                /// Decodes a custom error from ABI encoded revert data starting
+                /// with the 4-byte error selector. Returns `None` if the data
+                /// does not encode one of the contract's errors.
+                pub fn from_revert_data(data: &[u8]) -> Option<Self> {
+                    let abi = &Contract::raw_contract().interface.abi;
+                    match data.get(..4)? {
+                        [207, 71, 145, 129] => self::ethcontract::private::decode_error::<(
+                            self::ethcontract::U256,
+                            self::ethcontract::U256,
+                        )>(
+                            abi,
+                            data,
+                        )
+                        .map(|params| ContractError::InsufficientBalance {
+                            available: params.0,
+                            required: params.1
+                        }),
+                        [130, 180, 41, 0] => self::ethcontract::private::decode_error::<()>(
+                            abi,
+                            data,
+                        )
+                        .map(|()| ContractError::Unauthorized),
+                        _ => None,
+                    }
+                }
+
+                /// Decodes the custom error that a contract method call reverted
+                /// with. Returns `None` if the call failed for another reason.
+                pub fn from_method_error(
+                    error: &self::ethcontract::errors::MethodError,
+                ) -> Option<Self> {
+                    Self::from_revert_data(&error.custom_error()?.data)
+                }
+            }
+
+            impl self::ethcontract::errors::DecodeCustomError for ContractError {
+                fn decode_custom_error(
+                    error: &self::ethcontract::errors::CustomError,
+                ) -> Option<Self> {
+                    Self::from_revert_data(&error.data)
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn expand_overloaded_errors() {
+        let contract = contract(vec![
+            error("Failed", &[("", ParamType::Bool)]),
+            error("Failed", &[("who", ParamType::Address)]),
+        ]);
+        let cx = Context::from_builder(&contract, ContractBuilder::new()).unwrap();
+
+        let tokens = expand(&cx).unwrap().to_string();
+        assert!(tokens.contains(&quote! { Failed0(bool) }.to_string()));
+        assert!(
+            tokens.contains(&quote! { Failed1 { who: self::ethcontract::Address } }.to_string())
+        );
+    }
+}
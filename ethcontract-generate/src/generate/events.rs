@@ -1,22 +1,28 @@
-use crate::generate::{types, Context};
-use crate::util;
-use anyhow::Result;
+use crate::generate::{structs, types, Context};
+use crate::{util, NumberFormat};
+use anyhow::{anyhow, Result};
 use ethcontract_common::abi::{Event, EventParam, Hash, ParamType};
 use ethcontract_common::abiext::EventExt;
 use inflector::Inflector;
-use proc_macro2::{Literal, TokenStream};
+use proc_macro2::{Ident, Literal, TokenStream};
 use quote::quote;
 use syn::Path;
 
 pub(crate) fn expand(cx: &Context) -> Result<TokenStream> {
+    if cx.no_events {
+        return Ok(quote! {});
+    }
+
     let structs_mod = expand_structs_mod(cx)?;
     let filters = expand_filters(cx)?;
     let all_events = expand_all_events(cx);
+    let event_subsets = expand_event_subsets(cx)?;
 
     Ok(quote! {
         #structs_mod
         #filters
         #all_events
+        #event_subsets
     })
 }
 
@@ -27,84 +33,67 @@ fn expand_structs_mod(cx: &Context) -> Result<TokenStream> {
         .interface
         .abi
         .events()
-        .map(|event| expand_data_type(event, &cx.event_derives))
+        .map(|event| expand_data_type(cx, event))
         .collect::<Result<Vec<_>>>()?;
     if data_types.is_empty() {
         return Ok(quote! {});
     }
+    let use_structs = structs::expand_use(cx);
+    let vis = &cx.item_visibility;
 
     Ok(quote! {
         /// Module containing all generated data models for this contract's
         /// events.
-        pub mod event_data {
+        #vis mod event_data {
             use super::ethcontract;
+            #use_structs
 
             #( #data_types )*
         }
     })
 }
 
-fn expand_derives(derives: &[Path]) -> TokenStream {
+pub(crate) fn expand_derives(derives: &[Path]) -> TokenStream {
     quote! {#(#derives),*}
 }
 
 /// Expands an ABI event into a single event data type. This can expand either
 /// into a structure or a tuple in the case where all event parameters (topics
 /// and data) are anonymous.
-fn expand_data_type(event: &Event, event_derives: &[Path]) -> Result<TokenStream> {
+fn expand_data_type(cx: &Context, event: &Event) -> Result<TokenStream> {
     let event_name = expand_struct_name(event);
 
     let signature = expand_hash(event.signature());
 
-    let abi_signature = event.abi_signature();
-    let abi_signature_lit = Literal::string(&abi_signature);
-    let abi_signature_doc = util::expand_doc(&format!("`{}`", abi_signature));
+    let abi_signature_lit = Literal::string(&event.abi_signature());
 
-    let params = expand_params(event)?;
+    let params = expand_params(cx, event)?;
     let param_names = params.iter().map(|param| &param.0);
+    let field_attrs = event
+        .inputs
+        .iter()
+        .map(|input| expand_field_attr(cx, input))
+        .collect::<Result<Vec<_>>>()?;
 
     let all_anonymous_fields = event.inputs.iter().all(|input| input.name.is_empty());
     let (data_type_definition, data_type_construction) = if all_anonymous_fields {
-        expand_data_tuple(&event_name, &params)
+        expand_data_tuple(&event_name, &params, &field_attrs)
     } else {
-        expand_data_struct(&event_name, &params)
+        expand_data_struct(&event_name, &params, &field_attrs)
     };
 
-    let derives = expand_derives(event_derives);
+    let derives = expand_derives(&cx.event_derives);
 
     Ok(quote! {
         #[derive(Clone, Debug, Default, Eq, PartialEq, #derives)]
         pub #data_type_definition
 
-        impl #event_name {
-            /// Retrieves the signature for the event this data corresponds to.
-            /// This signature is the Keccak-256 hash of the ABI signature of
-            /// this event.
-            pub fn signature() -> self::ethcontract::H256 {
-                #signature
-            }
-
-            /// Retrieves the ABI signature for the event this data corresponds
-            /// to. For this event the value should always be:
-            ///
-            #abi_signature_doc
-            pub fn abi_signature() -> &'static str {
-                #abi_signature_lit
-            }
-        }
-
-        impl self::ethcontract::tokens::Tokenize for #event_name {
-            fn from_token(
-                token: self::ethcontract::common::abi::Token,
-            ) -> Result<Self, self::ethcontract::tokens::Error> {
-                let (#(#param_names,)*) = self::ethcontract::tokens::Tokenize::from_token(token)?;
-                Ok(#data_type_construction)
-            }
-
-            fn into_token(self) -> self::ethcontract::common::abi::Token {
-                unimplemented!("events are only decoded, not encoded")
-            }
-        }
+        self::ethcontract::private::event_data!(
+            #event_name,
+            #signature,
+            #abi_signature_lit,
+            (#( #param_names ),*) => #data_type_construction,
+        );
     })
 }
 
@@ -115,7 +104,7 @@ fn expand_struct_name(event: &Event) -> TokenStream {
 }
 
 /// Expands an ABI event into name-type pairs for each of its parameters.
-fn expand_params(event: &Event) -> Result<Vec<(TokenStream, TokenStream)>> {
+fn expand_params(cx: &Context, event: &Event) -> Result<Vec<(TokenStream, TokenStream)>> {
     event
         .inputs
         .iter()
@@ -123,23 +112,62 @@ fn expand_params(event: &Event) -> Result<Vec<(TokenStream, TokenStream)>> {
         .map(|(i, input)| {
             // NOTE: Events can contain nameless values.
             let name = util::expand_input_name(i, &input.name);
-            let ty = expand_input_type(input)?;
+            let ty = expand_input_type(cx, input)?;
 
             Ok((name, ty))
         })
         .collect()
 }
 
+/// Expands the serde attribute for an event field holding a 256-bit integer if
+/// a number format is configured. Expands to nothing otherwise.
+///
+/// Returns an error if a number format is configured without serde derives,
+/// as the generated attribute would not be accepted by the compiler.
+fn expand_field_attr(cx: &Context, input: &EventParam) -> Result<TokenStream> {
+    let adapter = match cx.event_number_format {
+        Some(NumberFormat::Hex) => "hex",
+        Some(NumberFormat::Decimal) => "decimal",
+        None => return Ok(quote! {}),
+    };
+
+    let has_serde_derive = cx.event_derives.iter().any(|derive| {
+        derive
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Serialize" || segment.ident == "Deserialize")
+    });
+    if !has_serde_derive {
+        return Err(anyhow!(
+            "event number format requires a `Serialize` or `Deserialize` event derive"
+        ));
+    }
+
+    match input.kind {
+        // NOTE: Integers wider than 128 bits are expanded to `U256`/`I256`.
+        ParamType::Int(n) | ParamType::Uint(n) if n > 128 => {
+            let path = format!("self::ethcontract::serialization::{}", adapter);
+            Ok(quote! { #[serde(with = #path)] })
+        }
+        _ => Ok(quote! {}),
+    }
+}
+
 /// Expands an event data structure from its name-type parameter pairs. Returns
 /// a tuple with the type definition (i.e. the struct declaration) and
 /// construction (i.e. code for creating an instance of the event data).
 fn expand_data_struct(
     name: &TokenStream,
     params: &[(TokenStream, TokenStream)],
+    field_attrs: &[TokenStream],
 ) -> (TokenStream, TokenStream) {
     let fields = params
         .iter()
-        .map(|(name, ty)| quote! { pub #name: #ty })
+        .enumerate()
+        .map(|(i, (name, ty))| {
+            let attr = field_attrs.get(i);
+            quote! { #attr pub #name: #ty }
+        })
         .collect::<Vec<_>>();
 
     let param_names = params
@@ -159,10 +187,15 @@ fn expand_data_struct(
 fn expand_data_tuple(
     name: &TokenStream,
     params: &[(TokenStream, TokenStream)],
+    field_attrs: &[TokenStream],
 ) -> (TokenStream, TokenStream) {
     let fields = params
         .iter()
-        .map(|(_, ty)| quote! { pub #ty })
+        .enumerate()
+        .map(|(i, (_, ty))| {
+            let attr = field_attrs.get(i);
+            quote! { #attr pub #ty }
+        })
         .collect::<Vec<_>>();
 
     let param_names = params
@@ -197,8 +230,9 @@ fn expand_filters(cx: &Context) -> Result<TokenStream> {
         .collect::<Vec<_>>();
     let builders = standard_events
         .iter()
-        .map(|event| expand_builder_type(event))
+        .map(|event| expand_builder_type(cx, event))
         .collect::<Result<Vec<_>>>()?;
+    let vis = &cx.item_visibility;
 
     Ok(quote! {
         impl Contract {
@@ -211,7 +245,7 @@ fn expand_filters(cx: &Context) -> Result<TokenStream> {
             }
         }
 
-        pub struct Events<'a> {
+        #vis struct Events<'a> {
             instance: &'a self::ethcontract::dyns::DynInstance,
         }
 
@@ -221,7 +255,7 @@ fn expand_filters(cx: &Context) -> Result<TokenStream> {
 
         /// Module containing the generated event stream builders with type safe
         /// filter methods for this contract's events.
-        pub mod event_builders {
+        #vis mod event_builders {
             use super::ethcontract;
             use super::event_data;
 
@@ -234,103 +268,57 @@ fn expand_filters(cx: &Context) -> Result<TokenStream> {
 fn expand_filter(event: &Event) -> TokenStream {
     let name = util::safe_ident(&event.name.to_snake_case());
     let builder_name = expand_builder_name(event);
-    let signature = expand_hash(event.signature());
+    let event_name = expand_struct_name(event);
 
     quote! {
         /// Generated by `ethcontract`.
         pub fn #name(&self) -> self::event_builders::#builder_name {
-            self::event_builders::#builder_name(
-                self.instance.event(#signature)
-                    .expect("generated event filter"),
-            )
+            self::event_builders::#builder_name(self::ethcontract::private::event(
+                self.instance,
+                self::event_data::#event_name::signature(),
+            ))
         }
     }
 }
 
 /// Expands an ABI event into a wrapped `EventBuilder` type with type-safe
 /// filter methods.
-fn expand_builder_type(event: &Event) -> Result<TokenStream> {
+fn expand_builder_type(cx: &Context, event: &Event) -> Result<TokenStream> {
     let event_name = expand_struct_name(event);
     let builder_doc = util::expand_doc(&format!(
         "A builder for creating a filtered stream of `{}` events.",
         event_name
     ));
     let builder_name = expand_builder_name(event);
-    let topic_filters = expand_builder_topic_filters(event)?;
-
-    Ok(quote! {
-        #builder_doc
-        pub struct #builder_name(
-            /// The inner event builder.
-            pub self::ethcontract::dyns::DynEventBuilder<self::event_data::#event_name>,
-        );
-
-        impl #builder_name {
-            /// Sets the starting block from which to stream logs for.
-            ///
-            /// If left unset defaults to the latest block.
-            #[allow(clippy::wrong_self_convention)]
-            pub fn from_block(mut self, block: self::ethcontract::BlockNumber) -> Self {
-                self.0 = (self.0).from_block(block);
-                self
-            }
-
-            /// Sets the last block from which to stream logs for.
-            ///
-            /// If left unset defaults to the streaming until the end of days.
-            #[allow(clippy::wrong_self_convention)]
-            pub fn to_block(mut self, block: self::ethcontract::BlockNumber) -> Self {
-                self.0 = (self.0).to_block(block);
-                self
-            }
-
-            /// Limits the number of events that can be retrieved by this filter.
-            ///
-            /// Note that this parameter is non-standard.
-            pub fn limit(mut self, value: usize) -> Self {
-                self.0 = (self.0).limit(value);
-                self
-            }
-
-            /// Sets the polling interval. This is used as the interval between
-            /// consecutive `eth_getFilterChanges` calls to get filter updates.
-            pub fn poll_interval(mut self, value: std::time::Duration) -> Self {
-                self.0 = (self.0).poll_interval(value);
-                self
-            }
-
-            #topic_filters
-
-            /// Returns a future that resolves with a collection of all existing
-            /// logs matching the builder parameters.
-            pub async fn query(self) -> std::result::Result<
-                std::vec::Vec<self::ethcontract::Event<self::event_data::#event_name>>,
-                self::ethcontract::errors::EventError,
-            > {
-                (self.0).query().await
+    let topic_filters = if event.inputs.iter().any(|input| input.indexed) {
+        let topic_filters = expand_builder_topic_filters(cx, event)?;
+        quote! {
+            impl #builder_name {
+                #topic_filters
             }
+        }
+    } else {
+        quote! {}
+    };
 
-            /// Creates an event stream from the current event builder.
-            pub fn stream(self) -> impl self::ethcontract::futures::stream::Stream<
-                Item = std::result::Result<
-                    self::ethcontract::StreamEvent<self::event_data::#event_name>,
-                    self::ethcontract::errors::EventError,
-                >,
-            > {
-                (self.0).stream()
-            }
+    Ok(quote! {
+        self::ethcontract::private::event_builder! {
+            #builder_doc
+            #builder_name(self::event_data::#event_name)
         }
+
+        #topic_filters
     })
 }
 
 /// Expands an ABI event into filter methods for its indexed parameters.
-fn expand_builder_topic_filters(event: &Event) -> Result<TokenStream> {
+fn expand_builder_topic_filters(cx: &Context, event: &Event) -> Result<TokenStream> {
     let topic_filters = event
         .inputs
         .iter()
         .filter(|input| input.indexed)
         .enumerate()
-        .map(|(topic_index, input)| expand_builder_topic_filter(topic_index, input))
+        .map(|(topic_index, input)| expand_builder_topic_filter(cx, topic_index, input))
         .collect::<Result<Vec<_>>>()?;
 
     Ok(quote! {
@@ -338,27 +326,42 @@ fn expand_builder_topic_filters(event: &Event) -> Result<TokenStream> {
     })
 }
 
-/// Expands a event parameter into an event builder filter method for the
+/// Expands a event parameter into event builder filter methods for the
 /// specified topic index.
-fn expand_builder_topic_filter(topic_index: usize, param: &EventParam) -> Result<TokenStream> {
+fn expand_builder_topic_filter(
+    cx: &Context,
+    topic_index: usize,
+    param: &EventParam,
+) -> Result<TokenStream> {
     let doc = util::expand_doc(&format!(
-        "Adds a filter for the {} event parameter.",
+        "Adds a filter for the {} event parameter. Accepts a single value, a \
+         `Vec` of values of which any must match, or a `Topic`.",
+        param.name,
+    ));
+    let any_doc = util::expand_doc(&format!(
+        "Removes the filter for the {} event parameter, matching any value.",
         param.name,
     ));
     let topic = util::ident(&format!("topic{}", topic_index));
-    let name = if param.name.is_empty() {
-        // Clippy (v1.70) is complaining that this clone isn't needed (despite `topic` clearly being used later)
-        #[allow(clippy::redundant_clone)]
-        topic.clone()
+    let base_name = if param.name.is_empty() {
+        format!("topic{}", topic_index)
     } else {
-        util::safe_ident(&param.name.to_snake_case())
+        param.name.to_snake_case()
     };
-    let ty = expand_input_type(param)?;
+    let name = util::safe_ident(&base_name);
+    let any_name = util::ident(&format!("{}_any", base_name));
+    let ty = expand_input_type(cx, param)?;
 
     Ok(quote! {
         #doc
-        pub fn #name(mut self, topic: self::ethcontract::Topic<#ty>) -> Self {
-            self.0 = (self.0).#topic(topic);
+        pub fn #name(mut self, topic: impl Into<self::ethcontract::Topic<#ty>>) -> Self {
+            self.0 = (self.0).#topic(topic.into());
+            self
+        }
+
+        #any_doc
+        pub fn #any_name(mut self) -> Self {
+            self.0 = (self.0).#topic(self::ethcontract::Topic::<#ty>::Any);
             self
         }
     })
@@ -390,6 +393,22 @@ fn expand_all_events(cx: &Context) -> TokenStream {
                     self.deployment_information(),
                 )
             }
+
+            /// Decodes the events emitted by this contract from a transaction
+            /// receipt, without querying the node for logs. Logs emitted by
+            /// other contracts or with an unknown topic are skipped, while
+            /// logs of known events that fail to decode are errors.
+            pub fn parse_logs(
+                &self,
+                receipt: &self::ethcontract::web3::types::TransactionReceipt,
+            ) -> Result<
+                Vec<self::ethcontract::Event<Event>>,
+                self::ethcontract::errors::ExecutionError,
+            > {
+                self::ethcontract::Event::from_logs(
+                    receipt.logs.iter().filter(|log| log.address == self.address()),
+                )
+            }
         }
 
         #event_enum
@@ -407,20 +426,25 @@ fn expand_event_enum(cx: &Context) -> TokenStream {
         //   consistent. This also facilitates testing as so that the same ABI
         //   yields consistent code.
         events.sort_unstable_by_key(|event| &event.name);
-
-        events
-            .into_iter()
-            .map(|event| {
-                let struct_name = expand_struct_name(event);
-                quote! {
-                    #struct_name(self::event_data::#struct_name)
-                }
-            })
-            .collect::<Vec<_>>()
+        expand_event_variants(&events)
     };
 
     let derives = expand_derives(&cx.event_derives);
 
+    if cx.non_exhaustive_events {
+        return quote! {
+            /// A contract event.
+            #[derive(Clone, Debug, Eq, PartialEq, #derives)]
+            #[non_exhaustive]
+            pub enum Event {
+                #( #variants, )*
+                /// A log that could not be decoded into any of the contract
+                /// events.
+                Unknown(self::ethcontract::RawLog),
+            }
+        };
+    }
+
     quote! {
         /// A contract event.
         #[derive(Clone, Debug, Eq, PartialEq, #derives)]
@@ -430,14 +454,32 @@ fn expand_event_enum(cx: &Context) -> TokenStream {
     }
 }
 
+/// Expands the variants of an event enum for the specified events.
+fn expand_event_variants(events: &[&Event]) -> Vec<TokenStream> {
+    events
+        .iter()
+        .map(|event| {
+            let struct_name = expand_struct_name(event);
+            quote! {
+                #struct_name(self::event_data::#struct_name)
+            }
+        })
+        .collect()
+}
+
 /// Expands the `ParseLog` implementation for the event enum.
 fn expand_event_parse_log(cx: &Context) -> TokenStream {
+    let events = cx.contract.interface.abi.events().collect::<Vec<_>>();
+    expand_parse_log(&util::ident("Event"), &events, cx.non_exhaustive_events)
+}
+
+/// Expands the `ParseLog` implementation for an event enum with a variant for
+/// each of the specified events. Logs that don't match any of the events are
+/// an error, or are parsed into the `Unknown` variant if the enum has one.
+fn expand_parse_log(enum_name: &Ident, events: &[&Event], unknown: bool) -> TokenStream {
     let all_events = {
-        let mut all_events = cx
-            .contract
-            .interface
-            .abi
-            .events()
+        let mut all_events = events
+            .iter()
             .map(|event| {
                 let struct_name = expand_struct_name(event);
 
@@ -476,7 +518,7 @@ fn expand_event_parse_log(cx: &Context) -> TokenStream {
 
             let signature = expand_hash(event.signature());
             quote! {
-                #signature => Ok(Event::#struct_name(#decode_event?)),
+                #signature => Ok(#enum_name::#struct_name(#decode_event?)),
             }
         })
         .collect::<Vec<_>>();
@@ -490,7 +532,7 @@ fn expand_event_parse_log(cx: &Context) -> TokenStream {
 
             quote! {
                 if let Ok(data) = #decode_event {
-                    return Ok(Event::#struct_name(data));
+                    return Ok(#enum_name::#struct_name(data));
                 }
             }
         })
@@ -498,8 +540,66 @@ fn expand_event_parse_log(cx: &Context) -> TokenStream {
 
     let invalid_data = expand_invalid_data();
 
+    let known_signatures = all_events
+        .iter()
+        .filter(|(event, _, _)| !event.anonymous)
+        .map(|(event, _, _)| expand_hash(event.signature()))
+        .collect::<Vec<_>>();
+    let is_known_topic = if known_signatures.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            fn is_known_topic(topic: &self::ethcontract::H256) -> bool {
+                matches!(*topic, #( #known_signatures )|*)
+            }
+        }
+    };
+
+    if unknown {
+        // NOTE: Only logs with an unknown topic 0 are parsed as unknown
+        //   events, logs of known events that fail to decode are errors.
+        let standard_event_match_arms = all_events
+            .iter()
+            .filter(|(event, _, _)| !event.anonymous)
+            .map(|(event, struct_name, decode_event)| {
+                let signature = expand_hash(event.signature());
+                quote! {
+                    #signature => return Ok(#enum_name::#struct_name(#decode_event?)),
+                }
+            })
+            .collect::<Vec<_>>();
+        let standard_event = if standard_event_match_arms.is_empty() {
+            quote! {}
+        } else {
+            quote! {
+                if let Some(topic) = log.topics.first().copied() {
+                    match topic {
+                        #( #standard_event_match_arms )*
+                        _ => {}
+                    }
+                }
+            }
+        };
+
+        return quote! {
+            impl self::ethcontract::contract::ParseLog for #enum_name {
+                fn parse_log(
+                    log: self::ethcontract::RawLog,
+                ) -> Result<Self, self::ethcontract::errors::ExecutionError> {
+                    #standard_event
+
+                    #( #anonymous_event_try_decode )*
+
+                    Ok(#enum_name::Unknown(log))
+                }
+
+                #is_known_topic
+            }
+        };
+    }
+
     quote! {
-        impl self::ethcontract::contract::ParseLog for Event {
+        impl self::ethcontract::contract::ParseLog for #enum_name {
             fn parse_log(
                 log: self::ethcontract::RawLog,
             ) -> Result<Self, self::ethcontract::errors::ExecutionError> {
@@ -519,6 +619,110 @@ fn expand_event_parse_log(cx: &Context) -> TokenStream {
 
                 #invalid_data
             }
+
+            #is_known_topic
+        }
+    }
+}
+
+/// Expands the configured event subsets into narrowed event enums, along with
+/// an `events_filtered` method on the root contract type for streaming them.
+/// Expands to nothing if no event subsets were configured.
+fn expand_event_subsets(cx: &Context) -> Result<TokenStream> {
+    if cx.event_subsets.is_empty() {
+        return Ok(quote! {});
+    }
+
+    let abi = &cx.contract.interface.abi;
+    let subsets = cx
+        .event_subsets
+        .iter()
+        .map(|(name, event_names)| {
+            let mut events = Vec::with_capacity(event_names.len());
+            for event_name in event_names {
+                let overloads = abi.events.get(event_name).ok_or_else(|| {
+                    anyhow!(
+                        "event subset '{}' contains unknown event '{}'",
+                        name,
+                        event_name,
+                    )
+                })?;
+                // NOTE: Subset variants are named after their events, so
+                //   overloads would need to be disambiguated.
+                if overloads.len() > 1 {
+                    return Err(anyhow!(
+                        "event subset '{}' contains overloaded event '{}', which is not supported",
+                        name,
+                        event_name,
+                    ));
+                }
+                events.extend(overloads);
+            }
+            // NOTE: Only events listed more than once in the subset are
+            //   removed here, as overloaded events are rejected above.
+            events.sort_unstable_by_key(|event| &event.name);
+            events.dedup_by_key(|event| &event.name);
+
+            Ok(expand_event_subset(cx, &util::ident(name), &events))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(quote! {
+        impl Contract {
+            /// Returns a log stream with only the events of a subset of the
+            /// contract events.
+            pub fn events_filtered<E>(&self) -> self::ethcontract::dyns::DynAllEventsBuilder<E>
+            where
+                E: self::ethcontract::contract::EventSubset,
+            {
+                self::ethcontract::dyns::DynAllEventsBuilder::new(
+                    self.raw_instance().web3(),
+                    self.address(),
+                    self.deployment_information(),
+                )
+                .topic0(E::topic0())
+            }
+        }
+
+        #( #subsets )*
+    })
+}
+
+/// Expands an event subset into an event enum with a variant for each of the
+/// selected events.
+fn expand_event_subset(cx: &Context, name: &Ident, events: &[&Event]) -> TokenStream {
+    let doc = util::expand_doc(&format!(
+        "A subset of the contract events: {}.",
+        events
+            .iter()
+            .map(|event| format!("`{}`", event.name))
+            .collect::<Vec<_>>()
+            .join(", "),
+    ));
+    let variants = expand_event_variants(events);
+    let derives = expand_derives(&cx.event_derives);
+    let parse_log = expand_parse_log(name, events, false);
+
+    let topic0 = if events.iter().any(|event| event.anonymous) {
+        quote! { self::ethcontract::Topic::Any }
+    } else {
+        let signatures = events.iter().map(|event| expand_hash(event.signature()));
+        quote! { self::ethcontract::Topic::OneOf(vec![#( #signatures ),*]) }
+    };
+
+    quote! {
+        #doc
+        #[derive(Clone, Debug, Eq, PartialEq, #derives)]
+        pub enum #name {
+            #( #variants, )*
+        }
+
+        #parse_log
+
+        impl self::ethcontract::contract::EventSubset for #name {
+            fn topic0() -> self::ethcontract::Topic<self::ethcontract::H256> {
+                #topic0
+            }
         }
     }
 }
@@ -526,24 +730,25 @@ fn expand_event_parse_log(cx: &Context) -> TokenStream {
 /// Expands an event property type.
 ///
 /// Note that this is slightly different than an expanding a Solidity type as
-/// complex types like arrays and strings get emitted as hashes when they are
-/// indexed.
-fn expand_input_type(input: &EventParam) -> Result<TokenStream> {
+/// complex types like arrays and strings only have their hashes emitted when
+/// they are indexed, so they are wrapped in a `Hashed` type.
+fn expand_input_type(cx: &Context, input: &EventParam) -> Result<TokenStream> {
+    let ty = types::expand_with_structs(cx, &input.kind, None)?;
     Ok(match (&input.kind, input.indexed) {
         (ParamType::Array(..), true)
         | (ParamType::Bytes, true)
         | (ParamType::FixedArray(..), true)
         | (ParamType::String, true)
         | (ParamType::Tuple(..), true) => {
-            quote! { self::ethcontract::H256 }
+            quote! { self::ethcontract::contract::Hashed<#ty> }
         }
-        (kind, _) => types::expand(kind)?,
+        _ => ty,
     })
 }
 
 /// Expands a 256-bit `Hash` into a literal representation that can be used with
 /// quasi-quoting for code generation.
-fn expand_hash(hash: Hash) -> TokenStream {
+pub(crate) fn expand_hash(hash: Hash) -> TokenStream {
     let bytes = hash.as_bytes().iter().copied().map(Literal::u8_unsuffixed);
 
     quote! {
@@ -563,6 +768,7 @@ fn expand_invalid_data() -> TokenStream {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::generate::fixtures;
     use crate::ContractBuilder;
     use ethcontract_common::{Abi, Contract};
     use std::{collections::BTreeMap, sync::Arc};
@@ -597,15 +803,14 @@ mod tests {
             ],
             anonymous: false,
         };
-        let signature = expand_hash(event.signature());
 
         assert_quote!(expand_filter(&event), {
             /// Generated by `ethcontract`.
             pub fn transfer(&self) -> self::event_builders::TransferBuilder {
-                self::event_builders::TransferBuilder(
-                    self.instance.event(#signature)
-                        .expect("generated event filter"),
-                )
+                self::event_builders::TransferBuilder(self::ethcontract::private::event(
+                    self.instance,
+                    self::event_data::Transfer::signature(),
+                ))
             }
         });
     }
@@ -634,22 +839,150 @@ mod tests {
             anonymous: false,
         };
 
+        let contract = Contract::with_name("Contract");
+        let cx = Context::from_builder(&contract, ContractBuilder::new()).unwrap();
+
         #[rustfmt::skip]
-        assert_quote!(expand_builder_topic_filters(&event).unwrap(), {
-            #[doc = "Adds a filter for the from event parameter."]
-            pub fn from(mut self, topic: self::ethcontract::Topic<self::ethcontract::Address>) -> Self {
-                self.0 = (self.0).topic0(topic);
+        assert_quote!(expand_builder_topic_filters(&cx, &event).unwrap(), {
+            #[doc = "Adds a filter for the from event parameter. Accepts a single value, a `Vec` of values of which any must match, or a `Topic`."]
+            pub fn from(mut self, topic: impl Into<self::ethcontract::Topic<self::ethcontract::Address>>) -> Self {
+                self.0 = (self.0).topic0(topic.into());
+                self
+            }
+
+            #[doc = "Removes the filter for the from event parameter, matching any value."]
+            pub fn from_any(mut self) -> Self {
+                self.0 = (self.0).topic0(self::ethcontract::Topic::<self::ethcontract::Address>::Any);
                 self
             }
 
-            #[doc = "Adds a filter for the to event parameter."]
-            pub fn to(mut self, topic: self::ethcontract::Topic<self::ethcontract::Address>) -> Self {
-                self.0 = (self.0).topic1(topic);
+            #[doc = "Adds a filter for the to event parameter. Accepts a single value, a `Vec` of values of which any must match, or a `Topic`."]
+            pub fn to(mut self, topic: impl Into<self::ethcontract::Topic<self::ethcontract::Address>>) -> Self {
+                self.0 = (self.0).topic1(topic.into());
+                self
+            }
+
+            #[doc = "Removes the filter for the to event parameter, matching any value."]
+            pub fn to_any(mut self) -> Self {
+                self.0 = (self.0).topic1(self::ethcontract::Topic::<self::ethcontract::Address>::Any);
                 self
             }
         });
     }
 
+    #[test]
+    fn expand_builder_type_without_topics() {
+        let event = Event {
+            name: "Deposit".into(),
+            inputs: vec![EventParam {
+                name: "amount".into(),
+                kind: ParamType::Uint(256),
+                indexed: false,
+            }],
+            anonymous: false,
+        };
+
+        let contract = Contract::with_name("Contract");
+        let cx = Context::from_builder(&contract, ContractBuilder::new()).unwrap();
+
+        assert_quote!(expand_builder_type(&cx, &event).unwrap(), {
+            self::ethcontract::private::event_builder! {
+                #[doc = "A builder for creating a filtered stream of `Deposit` events."]
+                DepositBuilder(self::event_data::Deposit)
+            }
+        });
+    }
+
+    #[test]
+    fn expand_data_type_uses_shared_impls() {
+        let event = Event {
+            name: "Deposit".into(),
+            inputs: vec![EventParam {
+                name: "amount".into(),
+                kind: ParamType::Uint(256),
+                indexed: false,
+            }],
+            anonymous: false,
+        };
+        let signature = expand_hash(event.signature());
+        let derives = expand_derives(&[]);
+
+        let contract = Contract::with_name("Contract");
+        let cx = Context::from_builder(&contract, ContractBuilder::new()).unwrap();
+
+        assert_quote!(expand_data_type(&cx, &event).unwrap(), {
+            #[derive(Clone, Debug, Default, Eq, PartialEq, #derives)]
+            pub struct Deposit {
+                pub amount: self::ethcontract::U256,
+            }
+
+            self::ethcontract::private::event_data!(
+                Deposit,
+                #signature,
+                "Deposit(uint256)",
+                (amount) => Deposit { amount },
+            );
+        });
+    }
+
+    #[test]
+    fn expand_hashed_builder_topic_filters() {
+        let event = Event {
+            name: "Registered".into(),
+            inputs: vec![
+                EventParam {
+                    name: "name".into(),
+                    kind: ParamType::String,
+                    indexed: true,
+                },
+                EventParam {
+                    name: "ids".into(),
+                    kind: ParamType::Array(Box::new(ParamType::Uint(256))),
+                    indexed: true,
+                },
+                EventParam {
+                    name: "owner".into(),
+                    kind: ParamType::String,
+                    indexed: false,
+                },
+            ],
+            anonymous: false,
+        };
+
+        let contract = Contract::with_name("Contract");
+        let cx = Context::from_builder(&contract, ContractBuilder::new()).unwrap();
+
+        #[rustfmt::skip]
+        assert_quote!(expand_builder_topic_filters(&cx, &event).unwrap(), {
+            #[doc = "Adds a filter for the name event parameter. Accepts a single value, a `Vec` of values of which any must match, or a `Topic`."]
+            pub fn name(mut self, topic: impl Into<self::ethcontract::Topic<self::ethcontract::contract::Hashed<String> >>) -> Self {
+                self.0 = (self.0).topic0(topic.into());
+                self
+            }
+
+            #[doc = "Removes the filter for the name event parameter, matching any value."]
+            pub fn name_any(mut self) -> Self {
+                self.0 = (self.0).topic0(self::ethcontract::Topic::<self::ethcontract::contract::Hashed<String> >::Any);
+                self
+            }
+
+            #[doc = "Adds a filter for the ids event parameter. Accepts a single value, a `Vec` of values of which any must match, or a `Topic`."]
+            pub fn ids(mut self, topic: impl Into<self::ethcontract::Topic<self::ethcontract::contract::Hashed<Vec<self::ethcontract::U256> > >>) -> Self {
+                self.0 = (self.0).topic1(topic.into());
+                self
+            }
+
+            #[doc = "Removes the filter for the ids event parameter, matching any value."]
+            pub fn ids_any(mut self) -> Self {
+                self.0 = (self.0).topic1(self::ethcontract::Topic::<self::ethcontract::contract::Hashed<Vec<self::ethcontract::U256> > >::Any);
+                self
+            }
+        });
+        assert_quote!(expand_input_type(&cx, &event.inputs[2]).unwrap(), {
+            String
+        });
+    }
+
     #[test]
     fn expand_data_struct_value() {
         let event = Event {
@@ -669,9 +1002,11 @@ mod tests {
             anonymous: false,
         };
 
+        let contract = Contract::with_name("Contract");
+        let cx = Context::from_builder(&contract, ContractBuilder::new()).unwrap();
         let name = expand_struct_name(&event);
-        let params = expand_params(&event).unwrap();
-        let (definition, construction) = expand_data_struct(&name, &params);
+        let params = expand_params(&cx, &event).unwrap();
+        let (definition, construction) = expand_data_struct(&name, &params, &[]);
 
         assert_quote!(definition, {
             struct Foo {
@@ -682,6 +1017,75 @@ mod tests {
         assert_quote!(construction, { Foo { a, p1 } });
     }
 
+    #[test]
+    fn expand_data_struct_number_format() {
+        let event = Event {
+            name: "Foo".into(),
+            inputs: vec![
+                EventParam {
+                    name: "a".into(),
+                    kind: ParamType::Uint(256),
+                    indexed: false,
+                },
+                EventParam {
+                    name: "b".into(),
+                    kind: ParamType::Uint(64),
+                    indexed: false,
+                },
+            ],
+            anonymous: false,
+        };
+
+        let contract = Contract::with_name("Contract");
+        let cx = Context::from_builder(
+            &contract,
+            ContractBuilder::new()
+                .add_event_derive("serde::Serialize")
+                .event_number_format(NumberFormat::Decimal),
+        )
+        .unwrap();
+        let name = expand_struct_name(&event);
+        let params = expand_params(&cx, &event).unwrap();
+        let field_attrs = event
+            .inputs
+            .iter()
+            .map(|input| expand_field_attr(&cx, input).unwrap())
+            .collect::<Vec<_>>();
+        let (definition, _) = expand_data_struct(&name, &params, &field_attrs);
+
+        assert_quote!(definition, {
+            struct Foo {
+                #[serde(with = "self::ethcontract::serialization::decimal")]
+                pub a: self::ethcontract::U256,
+                pub b: u64,
+            }
+        });
+    }
+
+    #[test]
+    fn expand_number_format_requires_serde_derives() {
+        let event = Event {
+            name: "Foo".into(),
+            inputs: vec![EventParam {
+                name: "a".into(),
+                kind: ParamType::Uint(256),
+                indexed: false,
+            }],
+            anonymous: false,
+        };
+
+        let contract = Contract::with_name("Contract");
+        let cx = Context::from_builder(
+            &contract,
+            ContractBuilder::new()
+                .add_event_derive("std::hash::Hash")
+                .event_number_format(NumberFormat::Hex),
+        )
+        .unwrap();
+
+        assert!(expand_field_attr(&cx, &event.inputs[0]).is_err());
+    }
+
     #[test]
     fn expand_data_tuple_value() {
         let event = Event {
@@ -701,9 +1105,11 @@ mod tests {
             anonymous: false,
         };
 
+        let contract = Contract::with_name("Contract");
+        let cx = Context::from_builder(&contract, ContractBuilder::new()).unwrap();
         let name = expand_struct_name(&event);
-        let params = expand_params(&event).unwrap();
-        let (definition, construction) = expand_data_tuple(&name, &params);
+        let params = expand_params(&cx, &event).unwrap();
+        let (definition, construction) = expand_data_tuple(&name, &params, &[]);
 
         assert_quote!(definition, {
             struct Foo(pub bool, pub self::ethcontract::Address);
@@ -762,6 +1168,139 @@ mod tests {
         });
     }
 
+    fn subset_contract(anonymous_mint: bool) -> Contract {
+        let events = [
+            ("Transfer", false),
+            ("Approval", false),
+            ("Mint", anonymous_mint),
+        ]
+        .into_iter()
+        .map(|(name, anonymous)| Event {
+            anonymous,
+            ..fixtures::event(name, &[("", ParamType::Address, true)])
+        })
+        .collect();
+
+        fixtures::contract("Contract", vec![], events, vec![])
+    }
+
+    #[test]
+    fn expand_no_event_subsets() {
+        let contract = subset_contract(false);
+        let context = Context::from_builder(&contract, ContractBuilder::new()).unwrap();
+        assert_quote!(expand_event_subsets(&context).unwrap(), {});
+    }
+
+    #[test]
+    fn expand_event_subset_enum() {
+        let contract = subset_contract(false);
+        let context = Context::from_builder(
+            &contract,
+            ContractBuilder::new().add_event_subset("TokenFlow", ["Transfer", "Approval"]),
+        )
+        .unwrap();
+
+        let abi = &context.contract.interface.abi;
+        let approval_signature = expand_hash(abi.event("Approval").unwrap().signature());
+        let transfer_signature = expand_hash(abi.event("Transfer").unwrap().signature());
+        let events = [
+            abi.event("Approval").unwrap(),
+            abi.event("Transfer").unwrap(),
+        ];
+        let parse_log = expand_parse_log(&util::ident("TokenFlow"), &events, false);
+
+        assert_quote!(expand_event_subsets(&context).unwrap(), {
+            impl Contract {
+                /// Returns a log stream with only the events of a subset of the
+                /// contract events.
+                pub fn events_filtered<E>(&self) -> self::ethcontract::dyns::DynAllEventsBuilder<E>
+                where
+                    E: self::ethcontract::contract::EventSubset,
+                {
+                    self::ethcontract::dyns::DynAllEventsBuilder::new(
+                        self.raw_instance().web3(),
+                        self.address(),
+                        self.deployment_information(),
+                    )
+                    .topic0(E::topic0())
+                }
+            }
+
+            #[doc = "A subset of the contract events: `Approval`, `Transfer`."]
+            #[derive(Clone, Debug, Eq, PartialEq,)]
+            pub enum TokenFlow {
+                Approval(self::event_data::Approval),
+                Transfer(self::event_data::Transfer),
+            }
+
+            #parse_log
+
+            impl self::ethcontract::contract::EventSubset for TokenFlow {
+                fn topic0() -> self::ethcontract::Topic<self::ethcontract::H256> {
+                    self::ethcontract::Topic::OneOf(vec![#approval_signature, #transfer_signature])
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn expand_event_subset_with_anonymous_event() {
+        let contract = subset_contract(true);
+        let context = Context::from_builder(
+            &contract,
+            ContractBuilder::new().add_event_subset("Mints", ["Mint", "Transfer"]),
+        )
+        .unwrap();
+
+        let tokens = expand_event_subsets(&context).unwrap().to_string();
+        assert!(tokens.contains(
+            &quote! {
+                fn topic0() -> self::ethcontract::Topic<self::ethcontract::H256> {
+                    self::ethcontract::Topic::Any
+                }
+            }
+            .to_string()
+        ));
+    }
+
+    #[test]
+    fn expand_event_subset_unknown_event_error() {
+        let contract = subset_contract(false);
+        let context = Context::from_builder(
+            &contract,
+            ContractBuilder::new().add_event_subset("TokenFlow", ["Transfer", "Burn"]),
+        )
+        .unwrap();
+
+        assert!(expand_event_subsets(&context).is_err());
+    }
+
+    #[test]
+    fn expand_event_subset_overloaded_event_error() {
+        let contract = fixtures::contract(
+            "Contract",
+            vec![],
+            vec![
+                fixtures::event("Transfer", &[("", ParamType::Address, true)]),
+                fixtures::event(
+                    "Transfer",
+                    &[
+                        ("", ParamType::Address, true),
+                        ("", ParamType::Uint(256), false),
+                    ],
+                ),
+            ],
+            vec![],
+        );
+        let context = Context::from_builder(
+            &contract,
+            ContractBuilder::new().add_event_subset("Transfers", ["Transfer"]),
+        )
+        .unwrap();
+
+        assert!(expand_event_subsets(&context).is_err());
+    }
+
     #[test]
     fn expand_parse_log_impl_for_all_events() {
         let mut events = BTreeMap::<String, _>::default();
@@ -847,6 +1386,90 @@ mod tests {
 
                     #invalid_data
                 }
+
+                fn is_known_topic(topic: &self::ethcontract::H256) -> bool {
+                    matches!(*topic, #foo_signature)
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn expand_no_events() {
+        let contract = fixtures::contract(
+            "Contract",
+            vec![],
+            vec![fixtures::event("Foo", &[])],
+            vec![],
+        );
+        let context =
+            Context::from_builder(&contract, ContractBuilder::new().no_events(true)).unwrap();
+
+        assert_quote!(expand(&context).unwrap(), {});
+    }
+
+    #[test]
+    fn expand_non_exhaustive_events() {
+        let contract = fixtures::contract(
+            "Contract",
+            vec![],
+            vec![fixtures::event("Foo", &[])],
+            vec![],
+        );
+        let context = Context::from_builder(
+            &contract,
+            ContractBuilder::new().non_exhaustive_events(true),
+        )
+        .unwrap();
+
+        let derives = expand_derives(&[]);
+        assert_quote!(expand_event_enum(&context), {
+            /// A contract event.
+            #[derive(Clone, Debug, Eq, PartialEq, #derives)]
+            #[non_exhaustive]
+            pub enum Event {
+                Foo(self::event_data::Foo),
+                /// A log that could not be decoded into any of the contract
+                /// events.
+                Unknown(self::ethcontract::RawLog),
+            }
+        });
+
+        let foo_signature = expand_hash(
+            context
+                .contract
+                .interface
+                .abi
+                .event("Foo")
+                .unwrap()
+                .signature(),
+        );
+        assert_quote!(expand_event_parse_log(&context), {
+            impl self::ethcontract::contract::ParseLog for Event {
+                fn parse_log(
+                    log: self::ethcontract::RawLog,
+                ) -> Result<Self, self::ethcontract::errors::ExecutionError> {
+                    if let Some(topic) = log.topics.first().copied() {
+                        match topic {
+                            #foo_signature => return Ok(Event::Foo(
+                                log.clone().decode(
+                                    Contract::raw_contract()
+                                        .interface
+                                        .abi
+                                        .event("Foo")
+                                        .expect("generated event decode")
+                                )?
+                            )),
+                            _ => {}
+                        }
+                    }
+
+                    Ok(Event::Unknown(log))
+                }
+
+                fn is_known_topic(topic: &self::ethcontract::H256) -> bool {
+                    matches!(*topic, #foo_signature)
+                }
             }
         });
     }
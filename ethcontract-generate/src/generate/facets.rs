@@ -0,0 +1,177 @@
+//! Merging of multiple contract ABIs into a single facade binding, for
+//! example for EIP-2535 diamonds or proxies that dispatch calls to facets.
+
+use anyhow::{anyhow, Result};
+use ethcontract_common::abi::Function;
+use ethcontract_common::abiext::FunctionExt;
+use ethcontract_common::hash::H32;
+use ethcontract_common::Contract;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Merges functions, events and errors of the facets into the main contract.
+///
+/// The name, bytecode and deployments of the main contract are kept. Functions
+/// that are identical in multiple contracts are only included once, any other
+/// function selector collision is reported as an error.
+pub(crate) fn merge(contract: &Contract, facets: &[Contract]) -> Result<Contract> {
+    let mut merged = contract.clone();
+    let mut abi = contract.interface.abi.clone();
+
+    let mut selectors = HashMap::new();
+    check_selectors(&mut selectors, contract)?;
+
+    for facet in facets {
+        for function in check_selectors(&mut selectors, facet)? {
+            abi.functions
+                .entry(function.name.clone())
+                .or_default()
+                .push(function.clone());
+        }
+        for event in facet.interface.abi.events() {
+            let events = abi.events.entry(event.name.clone()).or_default();
+            if !events.contains(event) {
+                events.push(event.clone());
+            }
+        }
+        for error in facet.interface.abi.errors() {
+            let errors = abi.errors.entry(error.name.clone()).or_default();
+            if !errors.contains(error) {
+                errors.push(error.clone());
+            }
+        }
+        abi.fallback |= facet.interface.abi.fallback;
+        abi.receive |= facet.interface.abi.receive;
+
+        for (signature, entry) in &facet.devdoc.methods {
+            merged
+                .devdoc
+                .methods
+                .entry(signature.clone())
+                .or_insert_with(|| entry.clone());
+        }
+        for (signature, entry) in &facet.userdoc.methods {
+            merged
+                .userdoc
+                .methods
+                .entry(signature.clone())
+                .or_insert_with(|| entry.clone());
+        }
+    }
+
+    merged.interface = Arc::new(abi.into());
+    Ok(merged)
+}
+
+/// Registers function selectors of a contract, returning functions that were
+/// not registered by a previous contract.
+fn check_selectors<'a>(
+    selectors: &mut HashMap<H32, (&'a Contract, &'a Function)>,
+    contract: &'a Contract,
+) -> Result<Vec<&'a Function>> {
+    let mut functions = Vec::new();
+    for function in contract.interface.abi.functions() {
+        match selectors.get(&function.selector()) {
+            Some((_, existing)) if *existing == function => continue,
+            Some((source, existing)) => {
+                return Err(anyhow!(
+                    "function selector 0x{} collision between '{}' in '{}' and '{}' in '{}'",
+                    hex(function.selector()),
+                    existing.abi_signature(),
+                    source.name,
+                    function.abi_signature(),
+                    contract.name,
+                ));
+            }
+            None => {
+                selectors.insert(function.selector(), (contract, function));
+                functions.push(function);
+            }
+        }
+    }
+
+    Ok(functions)
+}
+
+fn hex(selector: H32) -> String {
+    selector
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::fixtures::{self, event, function};
+    use ethcontract_common::abi::ParamType;
+
+    fn contract(name: &str, functions: Vec<Function>, events: &[&str]) -> Contract {
+        let events = events.iter().map(|name| event(name, &[])).collect();
+        fixtures::contract(name, functions, events, vec![])
+    }
+
+    #[test]
+    fn merge_facets() {
+        let diamond = contract(
+            "Diamond",
+            vec![function("facets", &[]), function("owner", &[])],
+            &["DiamondCut"],
+        );
+        let facets = [
+            contract(
+                "Token",
+                vec![function("transfer", &[]), function("owner", &[])],
+                &["Transfer"],
+            ),
+            contract(
+                "Governance",
+                vec![function("vote", &[])],
+                &["Transfer", "Voted"],
+            ),
+        ];
+
+        let merged = merge(&diamond, &facets).unwrap();
+        let abi = &merged.interface.abi;
+
+        assert_eq!(merged.name, "Diamond");
+        assert_eq!(
+            abi.functions().map(|f| f.name.as_str()).collect::<Vec<_>>(),
+            ["facets", "owner", "transfer", "vote"],
+        );
+        assert_eq!(
+            abi.events().map(|e| e.name.as_str()).collect::<Vec<_>>(),
+            ["DiamondCut", "Transfer", "Voted"],
+        );
+        assert_eq!(merged.interface.methods.len(), 4);
+    }
+
+    #[test]
+    fn merge_selector_collision() {
+        // `burn(uint256)` and `collate_propagate_storage(bytes16)` share the
+        // `0x42966c68` selector.
+        let diamond = contract("Diamond", vec![], &[]);
+        let facets = [
+            contract(
+                "Token",
+                vec![function("burn", &[("", ParamType::Uint(256))])],
+                &[],
+            ),
+            contract(
+                "Governance",
+                vec![function(
+                    "collate_propagate_storage",
+                    &[("", ParamType::FixedBytes(16))],
+                )],
+                &[],
+            ),
+        ];
+
+        let err = merge(&diamond, &facets).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "function selector 0x42966c68 collision between 'burn(uint256)' in 'Token' \
+             and 'collate_propagate_storage(bytes16)' in 'Governance'",
+        );
+    }
+}
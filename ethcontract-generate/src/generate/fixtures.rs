@@ -0,0 +1,89 @@
+//! Factories for the ABI items and contracts used by the code generation unit
+//! tests.
+
+use ethcontract_common::abi::{
+    AbiError, Event, EventParam, Function, Param, ParamType, StateMutability,
+};
+use ethcontract_common::{Abi, Contract};
+use std::sync::Arc;
+
+/// Creates function or error parameters from their names and types.
+pub fn params(params: &[(&str, ParamType)]) -> Vec<Param> {
+    params
+        .iter()
+        .map(|(name, kind)| Param {
+            name: name.to_string(),
+            kind: kind.clone(),
+            internal_type: None,
+        })
+        .collect()
+}
+
+/// Creates a non-payable function without outputs.
+pub fn function(name: &str, inputs: &[(&str, ParamType)]) -> Function {
+    #[allow(deprecated)]
+    Function {
+        name: name.into(),
+        inputs: params(inputs),
+        outputs: vec![],
+        constant: None,
+        state_mutability: StateMutability::NonPayable,
+    }
+}
+
+/// Creates a non-anonymous event from its inputs' names, types and whether
+/// they are indexed.
+pub fn event(name: &str, inputs: &[(&str, ParamType, bool)]) -> Event {
+    Event {
+        name: name.into(),
+        inputs: inputs
+            .iter()
+            .map(|(name, kind, indexed)| EventParam {
+                name: name.to_string(),
+                kind: kind.clone(),
+                indexed: *indexed,
+            })
+            .collect(),
+        anonymous: false,
+    }
+}
+
+/// Creates a custom error.
+pub fn error(name: &str, inputs: &[(&str, ParamType)]) -> AbiError {
+    AbiError {
+        name: name.into(),
+        inputs: params(inputs),
+    }
+}
+
+/// Creates a contract with the specified ABI items.
+pub fn contract(
+    name: &str,
+    functions: Vec<Function>,
+    events: Vec<Event>,
+    errors: Vec<AbiError>,
+) -> Contract {
+    let mut abi = Abi::default();
+    for function in functions {
+        abi.functions
+            .entry(function.name.clone())
+            .or_default()
+            .push(function);
+    }
+    for event in events {
+        abi.events
+            .entry(event.name.clone())
+            .or_default()
+            .push(event);
+    }
+    for error in errors {
+        abi.errors
+            .entry(error.name.clone())
+            .or_default()
+            .push(error);
+    }
+
+    let mut contract = Contract::with_name(name);
+    contract.interface = Arc::new(abi.into());
+    contract
+}
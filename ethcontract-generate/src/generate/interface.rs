@@ -0,0 +1,192 @@
+use crate::generate::{methods, Context};
+use crate::util;
+use anyhow::{Context as _, Result};
+use ethcontract_common::abi::StateMutability;
+use ethcontract_common::abiext::FunctionExt;
+use inflector::Inflector;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// Expands a context into a `ContractInterface` trait with an async method
+/// for each contract method, and its implementation for the generated
+/// contract type, when interface trait generation is enabled. Returns an
+/// empty token stream otherwise.
+///
+/// View methods are called and resolve to their return values, while other
+/// methods are sent as transactions and resolve to the transaction result.
+/// Futures are boxed so that the trait can be used as a trait object.
+pub(crate) fn expand(cx: &Context) -> Result<TokenStream> {
+    if !cx.generate_interface_trait {
+        return Ok(quote! {});
+    }
+
+    let methods = cx
+        .contract
+        .interface
+        .abi
+        .functions()
+        .map(|function| {
+            let signature = function.abi_signature();
+            let name = cx
+                .method_aliases
+                .get(&signature)
+                .cloned()
+                .unwrap_or_else(|| util::safe_ident(&function.name.to_snake_case()));
+            let inputs = methods::expand_inputs(cx, &function.inputs)
+                .with_context(|| format!("error expanding function '{}'", signature))?;
+            let args = function
+                .inputs
+                .iter()
+                .enumerate()
+                .map(|(i, param)| util::expand_input_name(i, &param.name));
+
+            let (output, doc, execute) = match function.state_mutability {
+                StateMutability::Pure | StateMutability::View => (
+                    methods::expand_outputs(cx, &function.outputs)
+                        .with_context(|| format!("error expanding function '{}'", signature))?,
+                    format!("Calls method `{}`.", signature),
+                    quote! { call },
+                ),
+                _ => (
+                    quote! { self::ethcontract::transaction::TransactionResult },
+                    format!("Sends a transaction calling method `{}`.", signature),
+                    quote! { send },
+                ),
+            };
+            let doc = util::expand_doc(&doc);
+            let deprecated = methods::expand_deprecated(cx, function);
+            let result = quote! {
+                self::ethcontract::futures::future::BoxFuture<
+                    'static,
+                    Result<#output, self::ethcontract::errors::MethodError>,
+                >
+            };
+
+            Ok((
+                quote! {
+                    #doc
+                    #deprecated
+                    fn #name(&self #inputs) -> #result;
+                },
+                quote! {
+                    fn #name(&self #inputs) -> #result {
+                        let method = self.methods.#name(#( #args ),*);
+                        Box::pin(async move { method.#execute().await })
+                    }
+                },
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let (declarations, implementations): (Vec<_>, Vec<_>) = methods.into_iter().unzip();
+
+    Ok(quote! {
+        /// An abstraction over the contract methods, for injecting contract
+        /// dependencies into application code and replacing them with test
+        /// doubles.
+        ///
+        /// Note that the trait methods share their names with the method
+        /// builders of the contract type, so they should be called explicitly
+        /// with `ContractInterface::method(&contract, ..)` or through a
+        /// `dyn ContractInterface` object. Use [`Contract::methods`] to access
+        /// the method builders unambiguously.
+        #[allow(clippy::too_many_arguments, clippy::type_complexity)]
+        pub trait ContractInterface: Send + Sync {
+            #( #declarations )*
+        }
+
+        #[allow(clippy::too_many_arguments, clippy::type_complexity)]
+        impl ContractInterface for Contract {
+            #( #implementations )*
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::fixtures::{self, params};
+    use crate::ContractBuilder;
+    use ethcontract_common::abi::ParamType;
+    use ethcontract_common::Contract;
+
+    fn contract() -> Contract {
+        let function = |name: &str, state_mutability| {
+            let mut function = fixtures::function(name, &[("value", ParamType::Uint(256))]);
+            function.outputs = params(&[("", ParamType::Bool)]);
+            function.state_mutability = state_mutability;
+            function
+        };
+
+        fixtures::contract(
+            "Poker",
+            vec![
+                function("check", StateMutability::View),
+                function("poke", StateMutability::NonPayable),
+            ],
+            vec![],
+            vec![],
+        )
+    }
+
+    #[test]
+    fn expand_interface_trait_disabled() {
+        let contract = contract();
+        let cx = Context::from_builder(&contract, ContractBuilder::new()).unwrap();
+
+        assert_quote!(expand(&cx).unwrap(), {});
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn expand_interface_trait() {
+        let contract = contract();
+        let cx = Context::from_builder(
+            &contract,
+            ContractBuilder::new().generate_interface_trait(true),
+        )
+        .unwrap();
+
+        assert_quote!(expand(&cx).unwrap(), {
+            /// An abstraction over the contract methods, for injecting contract
+            /// dependencies into application code and replacing them with test
+            /// doubles.
+            ///
+            /// Note that the trait methods share their names with the method
+            /// builders of the contract type, so they should be called explicitly
+            /// with `ContractInterface::method(&contract, ..)` or through a
+            /// `dyn ContractInterface` object. Use [`Contract::methods`] to access
+            /// the method builders unambiguously.
+            #[allow(clippy::too_many_arguments, clippy::type_complexity)]
+            pub trait ContractInterface: Send + Sync {
+                #[doc = "Calls method `check(uint256)`."]
+                fn check(&self, value: self::ethcontract::U256) -> self::ethcontract::futures::future::BoxFuture<
+                    'static,
+                    Result<bool, self::ethcontract::errors::MethodError>,
+                >;
+                #[doc = "Sends a transaction calling method `poke(uint256)`."]
+                fn poke(&self, value: self::ethcontract::U256) -> self::ethcontract::futures::future::BoxFuture<
+                    'static,
+                    Result<self::ethcontract::transaction::TransactionResult, self::ethcontract::errors::MethodError>,
+                >;
+            }
+
+            #[allow(clippy::too_many_arguments, clippy::type_complexity)]
+            impl ContractInterface for Contract {
+                fn check(&self, value: self::ethcontract::U256) -> self::ethcontract::futures::future::BoxFuture<
+                    'static,
+                    Result<bool, self::ethcontract::errors::MethodError>,
+                > {
+                    let method = self.methods.check(value);
+                    Box::pin(async move { method.call().await })
+                }
+                fn poke(&self, value: self::ethcontract::U256) -> self::ethcontract::futures::future::BoxFuture<
+                    'static,
+                    Result<self::ethcontract::transaction::TransactionResult, self::ethcontract::errors::MethodError>,
+                > {
+                    let method = self.methods.poke(value);
+                    Box::pin(async move { method.send().await })
+                }
+            }
+        });
+    }
+}
@@ -2,11 +2,12 @@ use crate::generate::{types, Context};
 use crate::util;
 use anyhow::{anyhow, Context as _, Result};
 use ethcontract_common::abi::{Function, Param, StateMutability};
-use ethcontract_common::abiext::FunctionExt;
+use ethcontract_common::abiext::{AbiExt, FunctionExt};
 use ethcontract_common::hash::H32;
 use inflector::Inflector;
 use proc_macro2::{Literal, TokenStream};
 use quote::quote;
+use std::collections::HashSet;
 use syn::Ident;
 
 pub(crate) fn expand(cx: &Context) -> Result<TokenStream> {
@@ -23,6 +24,7 @@ pub(crate) fn expand(cx: &Context) -> Result<TokenStream> {
 /// to the Solidity contract methods.
 fn expand_functions(cx: &Context) -> Result<TokenStream> {
     let mut aliases = cx.method_aliases.clone();
+    let mut deprecated_methods = cx.deprecated_methods.keys().collect::<HashSet<_>>();
     let functions = cx
         .contract
         .interface
@@ -32,14 +34,15 @@ fn expand_functions(cx: &Context) -> Result<TokenStream> {
             let signature = function.abi_signature();
 
             let alias = aliases.remove(&signature);
+            deprecated_methods.remove(&signature);
             let name = alias.unwrap_or_else(|| util::safe_ident(&function.name.to_snake_case()));
             let signature = function.abi_signature();
             let selector = expand_selector(function.selector());
-            let inputs = expand_inputs(&function.inputs)
+            let inputs = expand_inputs(cx, &function.inputs)
                 .with_context(|| format!("error expanding function '{}'", signature))?;
-            let input_types = expand_input_types(&function.inputs)
+            let input_types = expand_input_types(cx, &function.inputs)
                 .with_context(|| format!("error expanding function '{}'", signature))?;
-            let outputs = expand_outputs(&function.outputs)
+            let outputs = expand_outputs(cx, &function.outputs)
                 .with_context(|| format!("error expanding function '{}'", signature))?;
 
             Ok((function, name, selector, inputs, input_types, outputs))
@@ -51,12 +54,38 @@ fn expand_functions(cx: &Context) -> Result<TokenStream> {
             unused,
         ));
     }
+    if let Some(unused) = deprecated_methods.into_iter().next() {
+        return Err(anyhow!(
+            "method '{}' was marked as deprecated but this method does not exist",
+            unused,
+        ));
+    }
 
     let methods = functions
         .iter()
         .map(|(function, name, selector, inputs, _, outputs)| {
-            expand_function(cx, function, name, selector, inputs, outputs)
+            let deprecated = expand_deprecated(cx, function);
+            let method = expand_function(cx, function, name, selector, inputs, outputs);
+            quote! { #deprecated #method }
         });
+    let calldata_builders = functions
+        .iter()
+        .map(|(function, name, selector, inputs, _, _)| {
+            let deprecated = expand_deprecated(cx, function);
+            let builder = expand_calldata_builder(function, name, selector, inputs);
+            quote! { #deprecated #builder }
+        });
+    let selector_constants = functions.iter().map(|(function, name, selector, _, _, _)| {
+        let deprecated = expand_deprecated(cx, function);
+        let constant = expand_selector_constant(function, name, selector);
+        quote! { #deprecated #constant }
+    });
+
+    let interface_id = expand_selector(cx.contract.interface.abi.interface_id());
+    // NOTE: The methods struct is always public, since it is the `Deref`
+    //   target of the contract type and trait implementations can't expose
+    //   private types.
+    let vis = &cx.item_visibility;
 
     let methods_attrs = quote! { #[derive(Clone)] };
     let methods_struct = quote! {
@@ -69,7 +98,10 @@ fn expand_functions(cx: &Context) -> Result<TokenStream> {
         functions
             .iter()
             .map(|(function, name, selector, _, input_types, outputs)| {
-                expand_signature_accessor(function, name, selector, input_types, outputs)
+                let deprecated = expand_deprecated(cx, function);
+                let accessor =
+                    expand_signature_accessor(function, name, selector, input_types, outputs);
+                quote! { #deprecated #accessor }
             });
 
     let signatures_attrs = quote! { #[derive(Clone, Copy)] };
@@ -93,6 +125,24 @@ fn expand_functions(cx: &Context) -> Result<TokenStream> {
 
     Ok(quote! {
         impl Contract {
+            #( #selector_constants )*
+
+            /// The ERC-165 interface ID of the contract, that is the XOR of
+            /// the selectors of all its methods except `supportsInterface`.
+            pub const INTERFACE_ID: [u8; 4] = #interface_id;
+
+            /// Checks whether the contract supports the interface identified
+            /// by `INTERFACE_ID` using ERC-165 interface detection. Contracts
+            /// that don't implement ERC-165 are reported as not supporting it.
+            ///
+            /// This is not named `supports_interface`, so that it does not
+            /// shadow the `supportsInterface(bytes4)` method of contracts
+            /// implementing ERC-165.
+            pub async fn supports_own_interface(&self) -> Result<bool, self::ethcontract::errors::MethodError> {
+                let web3 = self.raw_instance().web3();
+                self::ethcontract::erc165::supports_interface(&web3, self.address(), Self::INTERFACE_ID).await
+            }
+
             /// Returns an object that allows accessing typed method signatures.
             pub fn signatures() -> Signatures {
                 Signatures
@@ -108,7 +158,7 @@ fn expand_functions(cx: &Context) -> Result<TokenStream> {
 
         /// Type containing signatures for all methods for generated contract type.
         #signatures_attrs
-        pub #signatures_struct
+        #vis #signatures_struct
 
         impl Signatures {
             #( #signature_accessors )*
@@ -121,6 +171,8 @@ fn expand_functions(cx: &Context) -> Result<TokenStream> {
         #[allow(clippy::too_many_arguments, clippy::type_complexity)]
         impl Methods {
             #( #methods )*
+
+            #( #calldata_builders )*
         }
 
         impl std::ops::Deref for Contract {
@@ -165,12 +217,60 @@ fn expand_function(
     quote! {
         #doc
         pub fn #name(&self #inputs) -> #result {
-            self.instance.#method(#selector, #arg)
-                .expect("generated call")
+            self::ethcontract::private::#method(&self.instance, #selector, #arg)
         }
     }
 }
 
+/// Expands a function into a method that encodes calldata for calling it,
+/// without going through a method builder.
+fn expand_calldata_builder(
+    function: &Function,
+    name: &Ident,
+    selector: &TokenStream,
+    inputs: &TokenStream,
+) -> TokenStream {
+    let doc = util::expand_doc(&format!(
+        "Returns the ABI encoded calldata for a call to method `{}`.",
+        function.abi_signature()
+    ));
+    let calldata_name = util::ident(&format!(
+        "{}_calldata",
+        name.to_string().trim_start_matches("r#")
+    ));
+    let arg = expand_inputs_call_arg(&function.inputs);
+
+    quote! {
+        #doc
+        pub fn #calldata_name(&self #inputs) -> self::ethcontract::types::Bytes {
+            self::ethcontract::private::calldata(#selector, #arg)
+        }
+    }
+}
+
+/// Expands a function into a constant for its 4-byte selector.
+fn expand_selector_constant(
+    function: &Function,
+    name: &Ident,
+    selector: &TokenStream,
+) -> TokenStream {
+    let doc = util::expand_doc(&format!(
+        "The selector of method `{}`.",
+        function.abi_signature()
+    ));
+    let constant_name = util::ident(&format!(
+        "{}_SELECTOR",
+        name.to_string()
+            .trim_start_matches("r#")
+            .to_screaming_snake_case()
+    ));
+
+    quote! {
+        #doc
+        pub const #constant_name: [u8; 4] = #selector;
+    }
+}
+
 fn expand_signature_accessor(
     function: &Function,
     name: &Ident,
@@ -191,23 +291,32 @@ fn expand_signature_accessor(
     }
 }
 
-pub(crate) fn expand_inputs(inputs: &[Param]) -> Result<TokenStream> {
+/// Expands a `#[deprecated]` attribute for a function if it was marked as
+/// deprecated, and an empty token stream otherwise.
+pub(crate) fn expand_deprecated(cx: &Context, function: &Function) -> TokenStream {
+    match cx.deprecated_methods.get(&function.abi_signature()) {
+        Some(note) => quote! { #[deprecated(note = #note)] },
+        None => quote! {},
+    }
+}
+
+pub(crate) fn expand_inputs(cx: &Context, inputs: &[Param]) -> Result<TokenStream> {
     let params = inputs
         .iter()
         .enumerate()
         .map(|(i, param)| {
             let name = util::expand_input_name(i, &param.name);
-            let kind = types::expand(&param.kind)?;
+            let kind = types::expand_param(cx, param)?;
             Ok(quote! { #name: #kind })
         })
         .collect::<Result<Vec<_>>>()?;
     Ok(quote! { #( , #params )* })
 }
 
-pub(crate) fn expand_input_types(inputs: &[Param]) -> Result<TokenStream> {
+pub(crate) fn expand_input_types(cx: &Context, inputs: &[Param]) -> Result<TokenStream> {
     let params = inputs
         .iter()
-        .map(|param| types::expand(&param.kind))
+        .map(|param| types::expand_param(cx, param))
         .collect::<Result<Vec<_>>>()?;
     Ok(quote! { ( #( #params ,)* ) })
 }
@@ -220,21 +329,21 @@ pub(crate) fn expand_inputs_call_arg(inputs: &[Param]) -> TokenStream {
     quote! { ( #( #names ,)* ) }
 }
 
-fn expand_outputs(outputs: &[Param]) -> Result<TokenStream> {
+pub(crate) fn expand_outputs(cx: &Context, outputs: &[Param]) -> Result<TokenStream> {
     match outputs.len() {
         0 => Ok(quote! { () }),
-        1 => types::expand(&outputs[0].kind),
+        1 => types::expand_param(cx, &outputs[0]),
         _ => {
             let types = outputs
                 .iter()
-                .map(|param| types::expand(&param.kind))
+                .map(|param| types::expand_param(cx, param))
                 .collect::<Result<Vec<_>>>()?;
             Ok(quote! { (#( #types ),*) })
         }
     }
 }
 
-fn expand_selector(selector: H32) -> TokenStream {
+pub(crate) fn expand_selector(selector: H32) -> TokenStream {
     let bytes = selector.iter().copied().map(Literal::u8_unsuffixed);
     quote! { [#( #bytes ),*] }
 }
@@ -264,18 +373,23 @@ fn expand_fallback(cx: &Context) -> TokenStream {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::generate::fixtures;
     use ethcontract_common::abi::ParamType;
 
     #[test]
     fn expand_inputs_empty() {
-        assert_quote!(expand_inputs(&[]).unwrap().to_string(), {},);
+        let contract = ethcontract_common::Contract::with_name("Contract");
+        let cx = Context::from_builder(&contract, crate::ContractBuilder::new()).unwrap();
+        assert_quote!(expand_inputs(&cx, &[]).unwrap().to_string(), {},);
     }
 
     #[test]
     fn expand_inputs_() {
+        let contract = ethcontract_common::Contract::with_name("Contract");
+        let cx = Context::from_builder(&contract, crate::ContractBuilder::new()).unwrap();
         assert_quote!(
             expand_inputs(
-
+                &cx,
                 &[
                     Param {
                         name: "a".to_string(),
@@ -294,19 +408,143 @@ mod tests {
         );
     }
 
+    #[test]
+    #[rustfmt::skip]
+    fn expand_calldata_builder_and_selector() {
+        let function = fixtures::function(
+            "transferFrom",
+            &[("from", ParamType::Address), ("value", ParamType::Uint(256))],
+        );
+        let name = util::ident("transfer_from");
+        let selector = expand_selector(function.selector());
+        let contract = ethcontract_common::Contract::with_name("Contract");
+        let cx = Context::from_builder(&contract, crate::ContractBuilder::new()).unwrap();
+        let inputs = expand_inputs(&cx, &function.inputs).unwrap();
+
+        assert_quote!(expand_calldata_builder(&function, &name, &selector, &inputs), {
+            #[doc = "Returns the ABI encoded calldata for a call to method `transferFrom(address,uint256)`."]
+            pub fn transfer_from_calldata(
+                &self,
+                from: self::ethcontract::Address,
+                value: self::ethcontract::U256
+            ) -> self::ethcontract::types::Bytes {
+                self::ethcontract::private::calldata(#selector, (from, value,))
+            }
+        });
+        assert_quote!(expand_selector_constant(&function, &name, &selector), {
+            #[doc = "The selector of method `transferFrom(address,uint256)`."]
+            pub const TRANSFER_FROM_SELECTOR: [u8; 4] = #selector;
+        });
+    }
+
+    fn deprecated_contract() -> (Function, ethcontract_common::Contract) {
+        let function = fixtures::function("poke", &[]);
+        let contract = fixtures::contract("Poker", vec![function.clone()], vec![], vec![]);
+
+        (function, contract)
+    }
+
+    #[test]
+    fn expand_deprecated_method() {
+        let (function, contract) = deprecated_contract();
+        let builder = crate::ContractBuilder::new().add_deprecated_method("poke()", "use `push`");
+        let cx = Context::from_builder(&contract, builder).unwrap();
+
+        assert_quote!(expand_deprecated(&cx, &function), {
+            #[deprecated(note = "use `push`")]
+        });
+        assert!(expand(&cx).unwrap().to_string().contains(
+            &quote! {
+                #[deprecated(note = "use `push`")]
+                #[doc = "Generated by `ethcontract`"]
+                pub fn poke
+            }
+            .to_string()
+        ));
+    }
+
+    #[test]
+    fn expand_methods_item_visibility() {
+        let (_, contract) = deprecated_contract();
+        let builder = crate::ContractBuilder::new().item_visibility_modifier("pub(crate)");
+        let cx = Context::from_builder(&contract, builder).unwrap();
+        let methods = expand(&cx).unwrap().to_string();
+        assert!(methods.contains(&quote! { pub(crate) struct Signatures; }.to_string()));
+        assert!(methods.contains(&quote! { pub struct Methods }.to_string()));
+
+        let builder = crate::ContractBuilder::new().item_visibility_modifier("");
+        let cx = Context::from_builder(&contract, builder).unwrap();
+        let methods = expand(&cx).unwrap().to_string();
+        assert!(methods.contains(&quote! { #[derive(Clone, Copy)] struct Signatures; }.to_string()));
+    }
+
+    #[test]
+    fn expand_erc165_contract_methods() {
+        let contract = fixtures::contract(
+            "Token",
+            vec![
+                fixtures::function(
+                    "supportsInterface",
+                    &[("interfaceId", ParamType::FixedBytes(4))],
+                ),
+                fixtures::function("transfer", &[("to", ParamType::Address)]),
+            ],
+            vec![],
+            vec![],
+        );
+        let cx = Context::from_builder(&contract, crate::ContractBuilder::new()).unwrap();
+        let methods = expand(&cx).unwrap().to_string();
+
+        assert!(
+            methods.contains(&quote! { pub async fn supports_own_interface(&self) }.to_string())
+        );
+        let method = quote! {
+            pub fn supports_interface(
+                &self,
+                interface_id: self::ethcontract::tokens::Bytes<[u8; 4]>
+            ) -> self::ethcontract::dyns::DynMethodBuilder<()>
+        };
+        assert!(methods.contains(&method.to_string()));
+        assert!(!methods.contains(&quote! { pub async fn supports_interface }.to_string()));
+    }
+
+    #[test]
+    fn expand_not_deprecated_method() {
+        let (function, contract) = deprecated_contract();
+        let cx = Context::from_builder(&contract, crate::ContractBuilder::new()).unwrap();
+
+        assert_quote!(expand_deprecated(&cx, &function), {});
+    }
+
+    #[test]
+    fn expand_deprecated_missing_method() {
+        let (_, contract) = deprecated_contract();
+        let builder = crate::ContractBuilder::new().add_deprecated_method("push()", "gone");
+        let cx = Context::from_builder(&contract, builder).unwrap();
+
+        assert!(expand(&cx).is_err());
+    }
+
     #[test]
     fn expand_outputs_empty() {
-        assert_quote!(expand_outputs(&[],).unwrap(), { () });
+        let contract = ethcontract_common::Contract::with_name("Contract");
+        let cx = Context::from_builder(&contract, crate::ContractBuilder::new()).unwrap();
+        assert_quote!(expand_outputs(&cx, &[],).unwrap(), { () });
     }
 
     #[test]
     fn expand_outputs_single() {
+        let contract = ethcontract_common::Contract::with_name("Contract");
+        let cx = Context::from_builder(&contract, crate::ContractBuilder::new()).unwrap();
         assert_quote!(
-            expand_outputs(&[Param {
-                name: "a".to_string(),
-                kind: ParamType::Bool,
-                internal_type: None,
-            }])
+            expand_outputs(
+                &cx,
+                &[Param {
+                    name: "a".to_string(),
+                    kind: ParamType::Bool,
+                    internal_type: None,
+                }]
+            )
             .unwrap(),
             { bool },
         );
@@ -314,21 +552,61 @@ mod tests {
 
     #[test]
     fn expand_outputs_multiple() {
+        let contract = ethcontract_common::Contract::with_name("Contract");
+        let cx = Context::from_builder(&contract, crate::ContractBuilder::new()).unwrap();
         assert_quote!(
-            expand_outputs(&[
-                Param {
-                    name: "a".to_string(),
-                    kind: ParamType::Bool,
-                    internal_type: None,
-                },
-                Param {
-                    name: "b".to_string(),
-                    kind: ParamType::Address,
-                    internal_type: None,
-                },
-            ],)
+            expand_outputs(
+                &cx,
+                &[
+                    Param {
+                        name: "a".to_string(),
+                        kind: ParamType::Bool,
+                        internal_type: None,
+                    },
+                    Param {
+                        name: "b".to_string(),
+                        kind: ParamType::Address,
+                        internal_type: None,
+                    },
+                ],
+            )
             .unwrap(),
             { (bool, self::ethcontract::Address) },
         );
     }
+
+    #[test]
+    fn expand_struct_inputs_and_outputs() {
+        let mut contract = ethcontract_common::Contract::with_name("Contract");
+        let mut interface = (*contract.interface).clone();
+        interface.structs.insert(
+            "Lib.Fee".into(),
+            vec![Param {
+                name: "amount".to_string(),
+                kind: ParamType::Uint(256),
+                internal_type: Some("uint256".to_string()),
+            }],
+        );
+        contract.interface = std::sync::Arc::new(interface);
+        let cx = Context::from_builder(&contract, crate::ContractBuilder::new()).unwrap();
+
+        let fees = Param {
+            name: "fees".to_string(),
+            kind: ParamType::Array(Box::new(ParamType::Tuple(vec![ParamType::Uint(256)]))),
+            internal_type: Some("struct Lib.Fee[]".to_string()),
+        };
+        let pair = Param {
+            name: "pair".to_string(),
+            kind: ParamType::Tuple(vec![ParamType::Bool, ParamType::Bool]),
+            internal_type: Some("struct Pair".to_string()),
+        };
+
+        assert_quote!(
+            expand_inputs(&cx, &[fees.clone(), pair]).unwrap(),
+            { , fees: Vec<self::structs::Fee>, pair: (bool, bool,) },
+        );
+        assert_quote!(expand_outputs(&cx, &[fees]).unwrap(), {
+            Vec<self::structs::Fee>
+        });
+    }
 }
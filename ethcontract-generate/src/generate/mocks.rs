@@ -0,0 +1,167 @@
+use crate::generate::{methods, structs, Context};
+use crate::util;
+use anyhow::{Context as _, Result};
+use ethcontract_common::abiext::FunctionExt;
+use inflector::Inflector;
+use proc_macro2::{Literal, TokenStream};
+use quote::{format_ident, quote};
+
+/// Expands a context into a feature gated `mock` module with a typed wrapper
+/// around an `ethcontract-mock` contract when a mock feature is configured,
+/// and an empty token stream otherwise.
+///
+/// The wrapper has an `expect_*` method for each contract method that sets up
+/// an expectation with the method's typed signature, so that tests don't
+/// need to deal with `Signature`s manually.
+pub(crate) fn expand(cx: &Context) -> Result<TokenStream> {
+    let feature = match &cx.mock_feature {
+        Some(feature) => Literal::string(feature),
+        None => return Ok(quote! {}),
+    };
+
+    let expectations = cx
+        .contract
+        .interface
+        .abi
+        .functions()
+        .map(|function| {
+            let signature = function.abi_signature();
+            let name = cx
+                .method_aliases
+                .get(&signature)
+                .cloned()
+                .unwrap_or_else(|| util::safe_ident(&function.name.to_snake_case()));
+            let expect_name = format_ident!("expect_{}", name.to_string().trim_start_matches("r#"));
+            let input_types = methods::expand_input_types(cx, &function.inputs)
+                .with_context(|| format!("error expanding function '{}'", signature))?;
+            let outputs = methods::expand_outputs(cx, &function.outputs)
+                .with_context(|| format!("error expanding function '{}'", signature))?;
+            let doc = util::expand_doc(&format!(
+                "Adds a new expectation for method `{}`.",
+                function.signature()
+            ));
+
+            Ok(quote! {
+                #doc
+                #[allow(clippy::type_complexity)]
+                pub fn #expect_name(&self) -> ethcontract_mock::Expectation<#input_types, #outputs> {
+                    self.contract.expect(super::Contract::signatures().#name())
+                }
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let doc = util::expand_doc(&format!(
+        "A mocked `{}` contract with typed helpers for setting up method \
+         expectations.",
+        cx.contract_name,
+    ));
+
+    let use_structs = structs::expand_use(cx);
+
+    Ok(quote! {
+        /// Typed helpers for mocking the contract with `ethcontract-mock`.
+        #[cfg(feature = #feature)]
+        pub mod mock {
+            use super::ethcontract;
+            #use_structs
+
+            #doc
+            pub struct MockContract {
+                contract: ethcontract_mock::Contract,
+            }
+
+            impl MockContract {
+                /// Deploys the contract to the mock node.
+                pub fn deploy(mock: &ethcontract_mock::Mock) -> Self {
+                    let abi = super::Contract::raw_contract().interface.abi.clone();
+                    Self::from_contract(mock.deploy(abi))
+                }
+
+                /// Deploys the contract to the mock node at the specified
+                /// address.
+                pub fn deploy_with_address(
+                    mock: &ethcontract_mock::Mock,
+                    address: ethcontract::Address,
+                ) -> Self {
+                    let abi = super::Contract::raw_contract().interface.abi.clone();
+                    Self::from_contract(mock.deploy_with_address(abi, address))
+                }
+
+                /// Wraps an already deployed mocked contract.
+                pub fn from_contract(contract: ethcontract_mock::Contract) -> Self {
+                    MockContract { contract }
+                }
+
+                /// Returns the underlying mocked contract.
+                pub fn contract(&self) -> &ethcontract_mock::Contract {
+                    &self.contract
+                }
+
+                /// Returns the address of the mocked contract.
+                pub fn address(&self) -> ethcontract::Address {
+                    self.contract.address()
+                }
+
+                /// Creates generated contract bindings for interacting with
+                /// the mocked contract.
+                pub fn instance(&self) -> super::Contract {
+                    super::Contract::at(&self.contract.web3(), self.contract.address())
+                }
+
+                /// Verifies that all expectations on this contract have been
+                /// met, then clears all expectations.
+                pub fn checkpoint(&self) {
+                    self.contract.checkpoint()
+                }
+
+                #( #expectations )*
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::fixtures::{self, function, params};
+    use crate::ContractBuilder;
+    use ethcontract_common::abi::{ParamType, StateMutability};
+    use ethcontract_common::Contract;
+
+    fn contract() -> Contract {
+        let mut function = function("balanceOf", &[("owner", ParamType::Address)]);
+        function.outputs = params(&[("", ParamType::Uint(256))]);
+        function.state_mutability = StateMutability::View;
+
+        fixtures::contract("Token", vec![function], vec![], vec![])
+    }
+
+    #[test]
+    fn expand_disabled() {
+        let contract = contract();
+        let cx = Context::from_builder(&contract, ContractBuilder::new()).unwrap();
+        assert_quote!(expand(&cx).unwrap(), {});
+    }
+
+    #[test]
+    fn expand_expectation_helpers() {
+        let contract = contract();
+        let builder = ContractBuilder::new().mock_feature("mock");
+        let cx = Context::from_builder(&contract, builder).unwrap();
+
+        let tokens = expand(&cx).unwrap().to_string();
+        assert!(tokens.contains(&quote! { #[cfg(feature = "mock")] }.to_string()));
+        assert!(tokens.contains(
+            &quote! {
+                pub fn expect_balance_of(&self) -> ethcontract_mock::Expectation<
+                    (self::ethcontract::Address,),
+                    self::ethcontract::U256
+                > {
+                    self.contract.expect(super::Contract::signatures().balance_of())
+                }
+            }
+            .to_string()
+        ));
+    }
+}
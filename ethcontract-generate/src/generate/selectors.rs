@@ -0,0 +1,324 @@
+use crate::generate::{events, methods, Context};
+use crate::util;
+use ethcontract_common::abiext::{EventExt, FunctionExt};
+use inflector::Inflector;
+use proc_macro2::{Literal, TokenStream};
+use quote::quote;
+
+/// Expands a context into `MethodSelector` and `EventSelector` enums that
+/// identify contract methods and events symbolically, and convert to and
+/// from their canonical ABI signatures with `Display` and `FromStr`.
+pub(crate) fn expand(cx: &Context) -> TokenStream {
+    let methods = expand_method_selector(cx);
+    let events = expand_event_selector(cx);
+
+    quote! {
+        #methods
+        #events
+    }
+}
+
+/// Expands into the `MethodSelector` enum if the contract has any methods.
+fn expand_method_selector(cx: &Context) -> TokenStream {
+    let methods = cx
+        .contract
+        .interface
+        .abi
+        .functions()
+        .map(|function| {
+            let signature = function.abi_signature();
+            let name = cx
+                .method_aliases
+                .get(&signature)
+                .cloned()
+                .unwrap_or_else(|| util::safe_ident(&function.name.to_snake_case()));
+            let variant = expand_variant(&name.to_string());
+            let selector = methods::expand_selector(function.selector());
+
+            (variant, signature, selector)
+        })
+        .collect::<Vec<_>>();
+    if methods.is_empty() {
+        return quote! {};
+    }
+
+    let variants = methods
+        .iter()
+        .map(|(variant, _, _)| variant.clone())
+        .collect::<Vec<_>>();
+    let docs = methods
+        .iter()
+        .map(|(_, signature, _)| util::expand_doc(&format!("Method `{}`.", signature)));
+    let signatures = methods
+        .iter()
+        .map(|(_, signature, _)| Literal::string(signature));
+    let selectors = methods.iter().map(|(_, _, selector)| selector);
+    let body = expand_selector_impls(quote! { MethodSelector }, &variants, signatures.collect());
+
+    quote! {
+        /// A contract method, identified by its selector. This can be
+        /// formatted as the canonical ABI signature of the method and parsed
+        /// back from it.
+        #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+        pub enum MethodSelector {
+            #( #docs #variants, )*
+        }
+
+        impl MethodSelector {
+            /// Returns the 4-byte selector of the method.
+            pub fn selector(self) -> [u8; 4] {
+                match self {
+                    #( Self::#variants => #selectors, )*
+                }
+            }
+
+            /// Returns the method with the specified selector, if any.
+            pub fn from_selector(selector: [u8; 4]) -> Option<Self> {
+                Self::ALL
+                    .iter()
+                    .copied()
+                    .find(|method| method.selector() == selector)
+            }
+        }
+
+        #body
+    }
+}
+
+/// Expands into the `EventSelector` enum if the contract has any events that
+/// are not anonymous. Anonymous events have no signature topic, so they can't
+/// be identified from a log.
+fn expand_event_selector(cx: &Context) -> TokenStream {
+    let mut events = cx
+        .contract
+        .interface
+        .abi
+        .events()
+        .filter(|event| !event.anonymous)
+        .collect::<Vec<_>>();
+    if events.is_empty() || cx.no_events {
+        return quote! {};
+    }
+
+    // NOTE: Sort the events by name to match the order of the event enum.
+    events.sort_unstable_by_key(|event| &event.name);
+
+    let variants = events
+        .iter()
+        .map(|event| expand_variant(&event.name))
+        .collect::<Vec<_>>();
+    let docs = events
+        .iter()
+        .map(|event| util::expand_doc(&format!("Event `{}`.", event.abi_signature())));
+    let signatures = events
+        .iter()
+        .map(|event| Literal::string(&event.abi_signature()))
+        .collect();
+    let topics = events
+        .iter()
+        .map(|event| events::expand_hash(event.signature()));
+    let body = expand_selector_impls(quote! { EventSelector }, &variants, signatures);
+
+    quote! {
+        /// A contract event, identified by its signature topic. This can be
+        /// formatted as the canonical ABI signature of the event and parsed
+        /// back from it.
+        #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+        pub enum EventSelector {
+            #( #docs #variants, )*
+        }
+
+        impl EventSelector {
+            /// Returns the signature topic of the event, which is the
+            /// Keccak-256 hash of its ABI signature.
+            pub fn topic(self) -> self::ethcontract::H256 {
+                match self {
+                    #( Self::#variants => #topics, )*
+                }
+            }
+
+            /// Returns the event with the specified signature topic, if any.
+            pub fn from_topic(topic: self::ethcontract::H256) -> Option<Self> {
+                Self::ALL
+                    .iter()
+                    .copied()
+                    .find(|event| event.topic() == topic)
+            }
+        }
+
+        #body
+    }
+}
+
+/// Expands the items shared by selector enums: the list of all variants, the
+/// ABI signature accessor, and the `Display` and `FromStr` implementations.
+fn expand_selector_impls(
+    name: TokenStream,
+    variants: &[TokenStream],
+    signatures: Vec<Literal>,
+) -> TokenStream {
+    quote! {
+        impl #name {
+            /// All variants, in the order they are declared in.
+            pub const ALL: &'static [Self] = &[ #( Self::#variants, )* ];
+
+            /// Returns the canonical ABI signature.
+            pub fn abi_signature(self) -> &'static str {
+                match self {
+                    #( Self::#variants => #signatures, )*
+                }
+            }
+        }
+
+        impl std::fmt::Display for #name {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str(self.abi_signature())
+            }
+        }
+
+        impl std::str::FromStr for #name {
+            type Err = self::ethcontract::errors::UnknownSignature;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Self::ALL
+                    .iter()
+                    .copied()
+                    .find(|value| value.abi_signature() == s)
+                    .ok_or_else(|| self::ethcontract::errors::UnknownSignature(s.to_owned()))
+            }
+        }
+    }
+}
+
+/// Expands a method or event name into a selector enum variant.
+fn expand_variant(name: &str) -> TokenStream {
+    let variant = util::safe_ident(&name.to_pascal_case());
+    quote! { #variant }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::fixtures::{self, event, function};
+    use crate::ContractBuilder;
+    use ethcontract_common::abi::ParamType;
+    use ethcontract_common::Contract;
+
+    fn contract() -> Contract {
+        let function = function(
+            "transferFrom",
+            &[
+                ("from", ParamType::Address),
+                ("value", ParamType::Uint(256)),
+            ],
+        );
+        let paid = event("Paid", &[("value", ParamType::Uint(256), false)]);
+        let mut hidden = event("Hidden", &[("value", ParamType::Uint(256), false)]);
+        hidden.anonymous = true;
+
+        fixtures::contract("Token", vec![function], vec![paid, hidden], vec![])
+    }
+
+    #[test]
+    fn expand_empty_selectors() {
+        let contract = Contract::with_name("Contract");
+        let cx = Context::from_builder(&contract, ContractBuilder::new()).unwrap();
+
+        assert_quote!(expand(&cx), {});
+    }
+
+    #[test]
+    fn expand_method_selector_with_alias() {
+        let contract = contract();
+        let cx = Context::from_builder(
+            &contract,
+            ContractBuilder::new().add_method_alias("transferFrom(address,uint256)", "pull"),
+        )
+        .unwrap();
+
+        let selector = methods::expand_selector(
+            contract
+                .interface
+                .abi
+                .functions()
+                .next()
+                .unwrap()
+                .selector(),
+        );
+        assert_quote!(expand_method_selector(&cx), {
+            /// A contract method, identified by its selector. This can be
+            /// formatted as the canonical ABI signature of the method and parsed
+            /// back from it.
+            #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+            pub enum MethodSelector {
+                #[doc = "Method `transferFrom(address,uint256)`."]
+                Pull,
+            }
+
+            impl MethodSelector {
+                /// Returns the 4-byte selector of the method.
+                pub fn selector(self) -> [u8; 4] {
+                    match self {
+                        Self::Pull => #selector,
+                    }
+                }
+
+                /// Returns the method with the specified selector, if any.
+                pub fn from_selector(selector: [u8; 4]) -> Option<Self> {
+                    Self::ALL
+                        .iter()
+                        .copied()
+                        .find(|method| method.selector() == selector)
+                }
+            }
+
+            impl MethodSelector {
+                /// All variants, in the order they are declared in.
+                pub const ALL: &'static [Self] = &[Self::Pull,];
+
+                /// Returns the canonical ABI signature.
+                pub fn abi_signature(self) -> &'static str {
+                    match self {
+                        Self::Pull => "transferFrom(address,uint256)",
+                    }
+                }
+            }
+
+            impl std::fmt::Display for MethodSelector {
+                fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                    f.write_str(self.abi_signature())
+                }
+            }
+
+            impl std::str::FromStr for MethodSelector {
+                type Err = self::ethcontract::errors::UnknownSignature;
+
+                fn from_str(s: &str) -> Result<Self, Self::Err> {
+                    Self::ALL
+                        .iter()
+                        .copied()
+                        .find(|value| value.abi_signature() == s)
+                        .ok_or_else(|| self::ethcontract::errors::UnknownSignature(s.to_owned()))
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn expand_event_selector_skips_anonymous_events() {
+        let contract = contract();
+        let cx = Context::from_builder(&contract, ContractBuilder::new()).unwrap();
+
+        let expanded = expand_event_selector(&cx).to_string();
+        assert!(expanded.contains("Paid"));
+        assert!(!expanded.contains("Hidden"));
+    }
+
+    #[test]
+    fn expand_event_selector_skipped_without_events() {
+        let contract = contract();
+        let cx = Context::from_builder(&contract, ContractBuilder::new().no_events(true)).unwrap();
+
+        assert!(expand_event_selector(&cx).is_empty());
+    }
+}
@@ -0,0 +1,178 @@
+use crate::generate::Context;
+use crate::util;
+use ethcontract_common::abi::ParamType;
+use ethcontract_common::abiext::FunctionExt;
+use inflector::Inflector;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// Expands a context into a `#[cfg(test)]` module with smoke tests for all
+/// contract methods when test generation is enabled, and an empty token
+/// stream otherwise.
+///
+/// Each generated test deploys the contract ABI to an `ethcontract-mock` node
+/// and calls the method with zero arguments through the generated bindings.
+/// This round-trips the method parameters and return values through the ABI
+/// encoder and decoder, so crates using these tests need `ethcontract-mock` as
+/// a dev-dependency.
+pub(crate) fn expand(cx: &Context) -> TokenStream {
+    if !cx.generate_tests {
+        return quote! {};
+    }
+
+    let tests = cx.contract.interface.abi.functions().map(|function| {
+        let signature = function.abi_signature();
+        let name = cx
+            .method_aliases
+            .get(&signature)
+            .cloned()
+            .unwrap_or_else(|| util::safe_ident(&function.name.to_snake_case()));
+        let args = function.inputs.iter().map(|input| {
+            let token = expand_zero_token(&input.kind);
+            quote! { ethcontract::tokens::Tokenize::from_token(#token).unwrap() }
+        });
+        let message = format!("call to `{}` failed", signature);
+
+        quote! {
+            #[test]
+            fn #name() {
+                let mock = ethcontract_mock::Mock::new(1337);
+                let contract = mock.deploy(super::Contract::raw_contract().interface.abi.clone());
+                contract
+                    .expect(super::Contract::signatures().#name())
+                    .once()
+                    .returns_default();
+
+                let instance = super::Contract::at(&mock.web3(), contract.address());
+                let call = instance.methods().#name(#( #args ),*).call();
+                ethcontract::futures::executor::block_on(call).expect(#message);
+
+                mock.checkpoint();
+            }
+        }
+    });
+
+    quote! {
+        #[cfg(test)]
+        mod smoke_tests {
+            use super::ethcontract;
+
+            #( #tests )*
+        }
+    }
+}
+
+/// Expands a token expression holding the zero value for the specified
+/// parameter type. Arguments are built from tokens rather than with
+/// `Default::default()`, since the Rust types of some parameters (such as
+/// fixed arrays with more than 32 elements) do not implement `Default`.
+fn expand_zero_token(kind: &ParamType) -> TokenStream {
+    match kind {
+        ParamType::Address => {
+            quote! { ethcontract::common::abi::Token::Address(ethcontract::Address::zero()) }
+        }
+        ParamType::Bytes => quote! { ethcontract::common::abi::Token::Bytes(Vec::new()) },
+        ParamType::Int(_) => {
+            quote! { ethcontract::common::abi::Token::Int(ethcontract::U256::zero()) }
+        }
+        ParamType::Uint(_) => {
+            quote! { ethcontract::common::abi::Token::Uint(ethcontract::U256::zero()) }
+        }
+        ParamType::Bool => quote! { ethcontract::common::abi::Token::Bool(false) },
+        ParamType::String => quote! { ethcontract::common::abi::Token::String(String::new()) },
+        ParamType::Array(_) => quote! { ethcontract::common::abi::Token::Array(Vec::new()) },
+        ParamType::FixedBytes(n) => {
+            quote! { ethcontract::common::abi::Token::FixedBytes(vec![0; #n]) }
+        }
+        ParamType::FixedArray(kind, n) => {
+            let token = expand_zero_token(kind);
+            quote! { ethcontract::common::abi::Token::FixedArray(vec![#token; #n]) }
+        }
+        ParamType::Tuple(kinds) => {
+            let tokens = kinds.iter().map(expand_zero_token);
+            quote! { ethcontract::common::abi::Token::Tuple(vec![#( #tokens ),*]) }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::fixtures::{self, function};
+    use crate::ContractBuilder;
+    use ethcontract_common::Contract;
+
+    fn contract() -> Contract {
+        let function = function("setValue", &[("value", ParamType::Uint(256))]);
+        fixtures::contract("Store", vec![function], vec![], vec![])
+    }
+
+    #[test]
+    fn expand_disabled() {
+        let contract = contract();
+        let cx = Context::from_builder(&contract, ContractBuilder::new()).unwrap();
+        assert_quote!(expand(&cx), {});
+    }
+
+    #[test]
+    fn expand_method_tests() {
+        let contract = contract();
+        let builder = ContractBuilder::new()
+            .generate_tests(true)
+            .add_method_alias("setValue(uint256)", "store");
+        let cx = Context::from_builder(&contract, builder).unwrap();
+        let value = quote! {
+            ethcontract::tokens::Tokenize::from_token(
+                ethcontract::common::abi::Token::Uint(ethcontract::U256::zero())
+            ).unwrap()
+        };
+
+        assert_quote!(expand(&cx), {
+            #[cfg(test)]
+            mod smoke_tests {
+                use super::ethcontract;
+
+                #[test]
+                fn store() {
+                    let mock = ethcontract_mock::Mock::new(1337);
+                    let contract =
+                        mock.deploy(super::Contract::raw_contract().interface.abi.clone());
+                    contract
+                        .expect(super::Contract::signatures().store())
+                        .once()
+                        .returns_default();
+
+                    let instance = super::Contract::at(&mock.web3(), contract.address());
+                    let call = instance.methods().store(#value).call();
+                    ethcontract::futures::executor::block_on(call)
+                        .expect("call to `setValue(uint256)` failed");
+
+                    mock.checkpoint();
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn expand_zero_token_for_large_fixed_arrays() {
+        let kind = ParamType::FixedArray(
+            Box::new(ParamType::Tuple(vec![
+                ParamType::Address,
+                ParamType::FixedBytes(4),
+            ])),
+            33,
+        );
+
+        assert_quote!(expand_zero_token(&kind), {
+            ethcontract::common::abi::Token::FixedArray(vec![
+                ethcontract::common::abi::Token::Tuple(
+                    vec![
+                        ethcontract::common::abi::Token::Address(ethcontract::Address::zero()),
+                        ethcontract::common::abi::Token::FixedBytes(vec![0; 4usize])
+                    ]
+                );
+                33usize
+            ])
+        });
+    }
+}
@@ -0,0 +1,295 @@
+use crate::generate::{events, types, Context};
+use crate::util;
+use anyhow::{Context as _, Result};
+use ethcontract_common::abi::{Param, ParamType};
+use proc_macro2::{Literal, TokenStream};
+use quote::quote;
+use std::collections::{BTreeMap, HashMap};
+use syn::{Ident, Visibility};
+
+/// Computes the identifiers of the generated struct types from the qualified
+/// Solidity struct names.
+///
+/// Structs are named after their unqualified name, unless multiple structs
+/// share it, for example `LibA.Order` and `LibB.Order`, in which case the
+/// qualified name without dots is used instead.
+pub(crate) fn names(structs: &BTreeMap<String, Vec<Param>>) -> HashMap<String, Ident> {
+    let short_name = |name: &str| name.rsplit('.').next().unwrap_or(name).to_owned();
+
+    let mut counts = HashMap::new();
+    for name in structs.keys() {
+        *counts.entry(short_name(name)).or_insert(0) += 1;
+    }
+
+    structs
+        .keys()
+        .map(|name| {
+            let short_name = short_name(name);
+            let ident = if counts[&short_name] > 1 {
+                name.replace('.', "")
+            } else {
+                short_name
+            };
+            (name.clone(), util::safe_ident(&ident))
+        })
+        .collect()
+}
+
+/// Finds the generated struct type for a tuple without a struct name. This is
+/// only successful when exactly one struct has the same layout.
+pub(crate) fn find_by_layout<'a>(cx: &'a Context, components: &[ParamType]) -> Option<&'a Ident> {
+    let mut matches = cx
+        .contract
+        .interface
+        .structs
+        .iter()
+        .filter(|(_, fields)| fields.iter().map(|field| &field.kind).eq(components));
+
+    match (matches.next(), matches.next()) {
+        (Some((name, _)), None) => cx.structs.get(name),
+        _ => None,
+    }
+}
+
+/// Expands into an import of the structs module for generated modules that
+/// may refer to struct types. Expands to nothing if there are no structs.
+pub(crate) fn expand_use(cx: &Context) -> TokenStream {
+    if cx.structs.is_empty() {
+        return quote! {};
+    }
+
+    quote! {
+        #[allow(unused_imports)]
+        use super::structs;
+    }
+}
+
+/// Expands into a module containing a data type for each Solidity struct used
+/// by the contract ABI.
+pub(crate) fn expand(cx: &Context) -> Result<TokenStream> {
+    let structs = &cx.contract.interface.structs;
+    if structs.is_empty() {
+        return Ok(quote! {});
+    }
+
+    let definitions = structs
+        .iter()
+        .map(|(name, fields)| {
+            expand_struct(cx, name, fields)
+                .with_context(|| format!("error expanding struct '{}'", name))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let use_structs = expand_use(cx);
+
+    // NOTE: The structs module is nested in the contract module, so a private
+    //   contract module needs `pub(super)` for the structs to be reachable
+    //   wherever the contract module is.
+    let vis = match &cx.visibility {
+        Visibility::Inherited => quote! { pub(super) },
+        vis => quote! { #vis },
+    };
+
+    Ok(quote! {
+        /// Module containing the generated data types for Solidity structs
+        /// used by this contract.
+        #vis mod structs {
+            use super::ethcontract;
+            #use_structs
+
+            #( #definitions )*
+        }
+    })
+}
+
+/// Expands a Solidity struct into a data type with a `Tokenize`
+/// implementation.
+fn expand_struct(cx: &Context, name: &str, fields: &[Param]) -> Result<TokenStream> {
+    let ident = &cx.structs[name];
+    let doc = util::expand_doc(&format!("Solidity struct `{}`.", name));
+    let derives = events::expand_derives(&cx.event_derives);
+
+    let field_names = fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| util::expand_input_name(i, &field.name))
+        .collect::<Vec<_>>();
+    let field_types = fields
+        .iter()
+        .map(|field| types::expand_param(cx, field))
+        .collect::<Result<Vec<_>>>()?;
+    let count = Literal::usize_unsuffixed(fields.len());
+
+    Ok(quote! {
+        #doc
+        #[derive(Clone, Debug, Default, Eq, PartialEq, #derives)]
+        pub struct #ident {
+            #( pub #field_names: #field_types, )*
+        }
+
+        impl self::ethcontract::tokens::Tokenize for #ident {
+            fn from_token(
+                token: self::ethcontract::common::abi::Token,
+            ) -> Result<Self, self::ethcontract::tokens::Error> {
+                let tokens = match token {
+                    self::ethcontract::common::abi::Token::Tuple(tokens) => tokens,
+                    _ => return Err(self::ethcontract::tokens::Error::TypeMismatch),
+                };
+                if tokens.len() != #count {
+                    return Err(self::ethcontract::tokens::Error::TupleLengthMismatch);
+                }
+
+                let mut tokens = tokens.into_iter();
+                Ok(#ident {
+                    #(
+                        #field_names: self::ethcontract::tokens::Tokenize::from_token(
+                            tokens.next().unwrap(),
+                        )?,
+                    )*
+                })
+            }
+
+            fn into_token(self) -> self::ethcontract::common::abi::Token {
+                self::ethcontract::common::abi::Token::Tuple(vec![
+                    #( self::ethcontract::tokens::Tokenize::into_token(self.#field_names), )*
+                ])
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ContractBuilder;
+    use ethcontract_common::Contract;
+
+    fn field(name: &str, kind: ParamType, internal_type: Option<&str>) -> Param {
+        Param {
+            name: name.into(),
+            kind,
+            internal_type: internal_type.map(String::from),
+        }
+    }
+
+    fn contract() -> Contract {
+        let mut contract = Contract::with_name("Settlement");
+        let mut interface = (*contract.interface).clone();
+        interface.structs.insert(
+            "Fee".into(),
+            vec![field("amount", ParamType::Uint(256), Some("uint256"))],
+        );
+        interface.structs.insert(
+            "Lib.Order".into(),
+            vec![
+                field("owner", ParamType::Address, Some("address")),
+                field(
+                    "fees",
+                    ParamType::Array(Box::new(ParamType::Tuple(vec![ParamType::Uint(256)]))),
+                    Some("struct Fee[]"),
+                ),
+            ],
+        );
+        contract.interface = std::sync::Arc::new(interface);
+        contract
+    }
+
+    #[test]
+    fn struct_names() {
+        let mut structs = BTreeMap::new();
+        structs.insert("Fee".to_owned(), vec![]);
+        structs.insert("LibA.Order".to_owned(), vec![]);
+        structs.insert("LibB.Order".to_owned(), vec![]);
+        structs.insert("Lib.type".to_owned(), vec![]);
+
+        let names = names(&structs);
+        assert_eq!(names["Fee"], "Fee");
+        assert_eq!(names["LibA.Order"], "LibAOrder");
+        assert_eq!(names["LibB.Order"], "LibBOrder");
+        assert_eq!(names["Lib.type"], "type_");
+    }
+
+    #[test]
+    fn find_struct_by_layout() {
+        let contract = contract();
+        let cx = Context::from_builder(&contract, ContractBuilder::new()).unwrap();
+
+        assert_eq!(find_by_layout(&cx, &[ParamType::Uint(256)]).unwrap(), "Fee");
+        assert!(find_by_layout(&cx, &[ParamType::Bool]).is_none());
+    }
+
+    #[test]
+    fn expand_empty_structs() {
+        let contract = Contract::with_name("Contract");
+        let cx = Context::from_builder(&contract, ContractBuilder::new()).unwrap();
+
+        assert_quote!(expand(&cx).unwrap(), {});
+        assert_quote!(expand_use(&cx), {});
+    }
+
+    #[test]
+    fn expand_structs_mod_visibility() {
+        let contract = contract();
+        let expand_mod = |builder: ContractBuilder| {
+            let cx = Context::from_builder(&contract, builder).unwrap();
+            expand(&cx).unwrap().to_string()
+        };
+
+        assert!(expand_mod(ContractBuilder::new())
+            .contains(&quote! { pub(super) mod structs }.to_string()));
+        assert!(
+            expand_mod(ContractBuilder::new().visibility_modifier("pub(crate)"))
+                .contains(&quote! { pub(crate) mod structs }.to_string())
+        );
+        assert!(
+            expand_mod(ContractBuilder::new().visibility_modifier("pub"))
+                .contains(&quote! { pub mod structs }.to_string())
+        );
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn expand_structs() {
+        let contract = contract();
+        let cx = Context::from_builder(&contract, ContractBuilder::new()).unwrap();
+
+        assert_quote!(expand_struct(&cx, "Lib.Order", &contract.interface.structs["Lib.Order"]).unwrap(), {
+            #[doc = "Solidity struct `Lib.Order`."]
+            #[derive(Clone, Debug, Default, Eq, PartialEq,)]
+            pub struct Order {
+                pub owner: self::ethcontract::Address,
+                pub fees: Vec<self::structs::Fee>,
+            }
+
+            impl self::ethcontract::tokens::Tokenize for Order {
+                fn from_token(
+                    token: self::ethcontract::common::abi::Token,
+                ) -> Result<Self, self::ethcontract::tokens::Error> {
+                    let tokens = match token {
+                        self::ethcontract::common::abi::Token::Tuple(tokens) => tokens,
+                        _ => return Err(self::ethcontract::tokens::Error::TypeMismatch),
+                    };
+                    if tokens.len() != 2 {
+                        return Err(self::ethcontract::tokens::Error::TupleLengthMismatch);
+                    }
+
+                    let mut tokens = tokens.into_iter();
+                    Ok(Order {
+                        owner: self::ethcontract::tokens::Tokenize::from_token(
+                            tokens.next().unwrap(),
+                        )?,
+                        fees: self::ethcontract::tokens::Tokenize::from_token(
+                            tokens.next().unwrap(),
+                        )?,
+                    })
+                }
+
+                fn into_token(self) -> self::ethcontract::common::abi::Token {
+                    self::ethcontract::common::abi::Token::Tuple(vec![
+                        self::ethcontract::tokens::Tokenize::into_token(self.owner),
+                        self::ethcontract::tokens::Tokenize::into_token(self.fees),
+                    ])
+                }
+            }
+        });
+    }
+}
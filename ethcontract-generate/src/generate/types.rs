@@ -1,8 +1,56 @@
+use crate::generate::{structs, Context};
 use anyhow::{anyhow, Result};
-use ethcontract_common::abi::ParamType;
+use ethcontract_common::abi::{Param, ParamType};
+use ethcontract_common::abiext::ParamExt;
 use proc_macro2::{Literal, TokenStream};
 use quote::quote;
 
+/// Expands a parameter into its Rust type, using the generated struct types
+/// for tuples declared as Solidity structs.
+pub(crate) fn expand_param(cx: &Context, param: &Param) -> Result<TokenStream> {
+    expand_with_structs(cx, &param.kind, param.struct_name())
+}
+
+/// Expands a Solidity type into its Rust type, using the generated struct
+/// type for tuples with the specified struct name.
+///
+/// Tuples without a struct name, such as event parameters for which the ABI
+/// does not include internal types, use a struct type if exactly one struct
+/// has the same layout.
+pub(crate) fn expand_with_structs(
+    cx: &Context,
+    kind: &ParamType,
+    struct_name: Option<&str>,
+) -> Result<TokenStream> {
+    match kind {
+        ParamType::Tuple(components) => {
+            let name = match struct_name {
+                Some(name) => cx.structs.get(name),
+                None => structs::find_by_layout(cx, components),
+            };
+            if let Some(name) = name {
+                return Ok(quote! { self::structs::#name });
+            }
+
+            let inner = components
+                .iter()
+                .map(|kind| expand_with_structs(cx, kind, None))
+                .collect::<Result<Vec<_>>>()?;
+            Ok(quote! { (#(#inner,)*) })
+        }
+        ParamType::Array(t) => {
+            let inner = expand_with_structs(cx, t, struct_name)?;
+            Ok(quote! { Vec<#inner> })
+        }
+        ParamType::FixedArray(t, n) => {
+            let inner = expand_with_structs(cx, t, struct_name)?;
+            let size = Literal::usize_unsuffixed(*n);
+            Ok(quote! { [#inner; #size] })
+        }
+        _ => expand(kind),
+    }
+}
+
 pub(crate) fn expand(kind: &ParamType) -> Result<TokenStream> {
     match kind {
         ParamType::Address => Ok(quote! { self::ethcontract::Address }),
@@ -10,6 +10,7 @@
 #[path = "test/macros.rs"]
 mod test_macros;
 
+pub mod snapshot;
 pub mod source;
 
 mod generate;
@@ -24,23 +25,27 @@ pub use ethcontract_common::artifact::{Artifact, ContractMut, InsertResult};
 /// Convenience re-imports so that you don't have to add `ethcontract-common`
 /// as a dependency.
 pub mod loaders {
+    pub use ethcontract_common::artifact::abi::AbiLoader;
     pub use ethcontract_common::artifact::hardhat::{
         Format as HardHatFormat, HardHatLoader, NetworkEntry,
     };
     pub use ethcontract_common::artifact::truffle::TruffleLoader;
 }
 
-use anyhow::Result;
+use anyhow::{anyhow, Context as _, Result};
+use ethcontract_common::artifact::truffle::TruffleLoader;
 use ethcontract_common::contract::Network;
 use ethcontract_common::Contract;
+use inflector::Inflector;
 use proc_macro2::TokenStream;
-use std::collections::HashMap;
-use std::fs::File;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
 use std::io::{BufWriter, Write};
 use std::path::Path;
 
 /// Builder for generating contract code. Note that no code is generated until
 /// the builder is finalized with `generate` or `output`.
+#[derive(Clone)]
 #[must_use = "contract builders do nothing unless you generate bindings"]
 pub struct ContractBuilder {
     /// The runtime crate name to use.
@@ -50,6 +55,14 @@ pub struct ContractBuilder {
     /// re-export.
     pub visibility_modifier: Option<String>,
 
+    /// The visibility modifier to use for the generated inner items, such as
+    /// the method signatures struct and the event modules. Defaults to `pub`.
+    pub item_visibility_modifier: Option<String>,
+
+    /// Re-export the generated contract type in the root, next to the
+    /// generated module.
+    pub reexport: bool,
+
     /// Override the contract module name that contains the generated code.
     pub contract_mod_override: Option<String>,
 
@@ -62,9 +75,43 @@ pub struct ContractBuilder {
     /// Manually specified contract method aliases.
     pub method_aliases: HashMap<String, String>,
 
+    /// Deprecation notes for contract methods, keyed by method signature.
+    pub deprecated_methods: HashMap<String, String>,
+
     /// Derives added to event structs and enums.
     pub event_derives: Vec<String>,
 
+    /// Serialization format for 256-bit integer fields of event structs.
+    pub event_number_format: Option<NumberFormat>,
+
+    /// Mark the generated event enum as `#[non_exhaustive]` and add an
+    /// `Unknown` variant for logs that don't match any contract event.
+    pub non_exhaustive_events: bool,
+
+    /// Named subsets of contract events for which narrowed event enums are
+    /// generated.
+    pub event_subsets: Vec<(String, Vec<String>)>,
+
+    /// Skip generating all event related code, such as event data structs,
+    /// the event enum and event stream builders.
+    pub no_events: bool,
+
+    /// Additional contracts whose functions, events and errors are merged
+    /// into the generated binding.
+    pub facets: Vec<Contract>,
+
+    /// Generate a `#[cfg(test)]` module with mock-based smoke tests for all
+    /// contract methods.
+    pub generate_tests: bool,
+
+    /// Generate a `mock` module with typed `ethcontract-mock` helpers, gated
+    /// behind the specified Cargo feature.
+    pub mock_feature: Option<String>,
+
+    /// Generate a `ContractInterface` trait with an async method for each
+    /// contract method, implemented by the generated contract type.
+    pub generate_interface_trait: bool,
+
     /// Format generated code sing locally installed copy of `rustfmt`.
     pub rustfmt: bool,
 }
@@ -75,11 +122,22 @@ impl ContractBuilder {
         ContractBuilder {
             runtime_crate_name: "ethcontract".to_string(),
             visibility_modifier: None,
+            item_visibility_modifier: None,
+            reexport: true,
             contract_mod_override: None,
             contract_name_override: None,
             networks: Default::default(),
             method_aliases: Default::default(),
+            deprecated_methods: Default::default(),
             event_derives: vec![],
+            event_number_format: None,
+            non_exhaustive_events: false,
+            event_subsets: vec![],
+            no_events: false,
+            facets: vec![],
+            generate_tests: false,
+            mock_feature: None,
+            generate_interface_trait: false,
             rustfmt: true,
         }
     }
@@ -98,6 +156,29 @@ impl ContractBuilder {
         self
     }
 
+    /// Sets the visibility modifier for the generated inner items, that is the
+    /// `Signatures` and `Events` structs and the `event_data` and
+    /// `event_builders` modules, independently of the visibility of the
+    /// generated module. An empty string makes the items private to the
+    /// generated module.
+    ///
+    /// Note that the generated contract type still exposes these items in its
+    /// methods, so restricting their visibility only prevents naming them.
+    /// The `Methods` struct is always public, as the contract type
+    /// dereferences to it.
+    pub fn item_visibility_modifier(mut self, vis: impl Into<String>) -> Self {
+        self.item_visibility_modifier = Some(vis.into());
+        self
+    }
+
+    /// Specifies whether or not to re-export the generated contract type in
+    /// the root, next to the generated module. When disabled, the contract
+    /// type is only available at `{contract_mod}::Contract`.
+    pub fn reexport(mut self, reexport: bool) -> Self {
+        self.reexport = reexport;
+        self
+    }
+
     /// Sets the optional contract module name override.
     pub fn contract_mod_override(mut self, name: impl Into<String>) -> Self {
         self.contract_mod_override = Some(name.into());
@@ -153,6 +234,20 @@ impl ContractBuilder {
         self
     }
 
+    /// Marks a solidity method as deprecated, so that the generated bindings
+    /// for it are annotated with a `#[deprecated]` attribute with the
+    /// specified note. This steers downstream code away from legacy contract
+    /// methods with compiler warnings.
+    pub fn add_deprecated_method(
+        mut self,
+        signature: impl Into<String>,
+        note: impl Into<String>,
+    ) -> Self {
+        self.deprecated_methods
+            .insert(signature.into(), note.into());
+        self
+    }
+
     /// Specifies whether or not to format the code using a locally installed
     /// copy of `rustfmt`.
     ///
@@ -181,6 +276,130 @@ impl ContractBuilder {
         self
     }
 
+    /// Sets the serialization format for `U256` and `I256` fields of event
+    /// structs.
+    ///
+    /// The fields are annotated with `#[serde(with = ...)]` using the adapters
+    /// from `ethcontract::serialization`, so this requires adding serde
+    /// derives with [`add_event_derive`](Self::add_event_derive).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ethcontract_generate::{ContractBuilder, NumberFormat};
+    /// let builder = ContractBuilder::new()
+    ///     .add_event_derive("serde::Serialize")
+    ///     .event_number_format(NumberFormat::Decimal);
+    /// ```
+    pub fn event_number_format(mut self, format: NumberFormat) -> Self {
+        self.event_number_format = Some(format);
+        self
+    }
+
+    /// Specifies whether or not the generated event enum should be marked as
+    /// `#[non_exhaustive]` with an additional `Unknown(RawLog)` variant.
+    ///
+    /// This allows adding events to a contract without breaking downstream
+    /// code that matches on the event enum. Logs that can't be decoded into
+    /// any of the contract events are parsed into the `Unknown` variant
+    /// instead of producing an error. Note that additional event derives must
+    /// also be implemented by `RawLog`.
+    pub fn non_exhaustive_events(mut self, non_exhaustive_events: bool) -> Self {
+        self.non_exhaustive_events = non_exhaustive_events;
+        self
+    }
+
+    /// Adds a named subset of contract events, for which an event enum with
+    /// only the selected events is generated.
+    ///
+    /// The enum can be passed to the generated `events_filtered` method in
+    /// order to only stream the selected events. Logs are filtered by the
+    /// node, so that streams don't pay the cost of retrieving and decoding
+    /// irrelevant events on busy contracts.
+    ///
+    /// Events are selected by name, so overloaded events can't be part of a
+    /// subset and cause code generation to fail.
+    pub fn add_event_subset<S>(
+        mut self,
+        name: impl Into<String>,
+        events: impl IntoIterator<Item = S>,
+    ) -> Self
+    where
+        S: Into<String>,
+    {
+        self.event_subsets
+            .push((name.into(), events.into_iter().map(Into::into).collect()));
+        self
+    }
+
+    /// Specifies whether or not to skip generating event code.
+    ///
+    /// Contracts with many events generate a lot of code for event data
+    /// structs, enums and stream builders, which slows down compilation for
+    /// crates that only use contract methods. Note that this also skips the
+    /// `EventSelector` enum, and any configured event derives and subsets are
+    /// ignored.
+    pub fn no_events(mut self, no_events: bool) -> Self {
+        self.no_events = no_events;
+        self
+    }
+
+    /// Adds a facet contract whose functions, events and errors are merged
+    /// into the generated binding.
+    ///
+    /// This makes it possible to generate a single facade binding for
+    /// contracts that dispatch calls to multiple implementations at one
+    /// address, such as EIP-2535 diamonds. The name, bytecode and deployments
+    /// of the contract passed to [`generate`](Self::generate) are used for the
+    /// binding.
+    ///
+    /// Functions that are identical in several contracts are only included
+    /// once. Generation fails if two different functions have the same
+    /// 4-byte selector, since a call to such a selector is ambiguous.
+    pub fn add_facet(mut self, facet: Contract) -> Self {
+        self.facets.push(facet);
+        self
+    }
+
+    /// Specifies whether or not to generate a `#[cfg(test)]` module with smoke
+    /// tests for all contract methods.
+    ///
+    /// Each test deploys the contract to an `ethcontract-mock` node and calls
+    /// a method with default arguments, checking that its parameters and
+    /// return values survive an ABI encoding and decoding roundtrip. The
+    /// crate that includes the generated bindings must have `ethcontract-mock`
+    /// as a dev-dependency.
+    pub fn generate_tests(mut self, generate_tests: bool) -> Self {
+        self.generate_tests = generate_tests;
+        self
+    }
+
+    /// Generates a `mock` module with a typed wrapper around an
+    /// `ethcontract-mock` contract, with an `expect_*` helper method for each
+    /// contract method.
+    ///
+    /// The module is only compiled when the specified Cargo feature of the
+    /// crate that includes the generated bindings is enabled. That crate must
+    /// have an optional dependency on `ethcontract-mock` enabled by this
+    /// feature.
+    pub fn mock_feature(mut self, feature: impl Into<String>) -> Self {
+        self.mock_feature = Some(feature.into());
+        self
+    }
+
+    /// Specifies whether or not to generate a `ContractInterface` trait with
+    /// an async method for each contract method, and its implementation for
+    /// the generated contract type.
+    ///
+    /// View methods are called and return their decoded return values, while
+    /// other methods are sent as transactions. Application code can depend on
+    /// the trait instead of the contract type, so that contract interactions
+    /// can be replaced with test doubles without going through a mock node.
+    pub fn generate_interface_trait(mut self, generate_interface_trait: bool) -> Self {
+        self.generate_interface_trait = generate_interface_trait;
+        self
+    }
+
     /// Generates the contract bindings.
     pub fn generate(self, contract: &Contract) -> Result<ContractBindings> {
         let rustfmt = self.rustfmt;
@@ -189,6 +408,49 @@ impl ContractBuilder {
             rustfmt,
         })
     }
+
+    /// Generates bindings for every Truffle artifact in a directory, such as
+    /// `build/contracts`, with the settings of this builder.
+    ///
+    /// Each `.json` file in the directory is loaded as a contract, and its
+    /// bindings are added to the module under the snake case contract name.
+    /// Contracts are added in file name order, and subdirectories are not
+    /// scanned. The module can then be written with
+    /// [`ModuleBindings::write_to_dir`].
+    ///
+    /// Since the contract name and module overrides would apply to every
+    /// contract, they must not be set.
+    pub fn generate_dir(self, dir: impl AsRef<Path>) -> Result<ModuleBindings> {
+        if self.contract_name_override.is_some() || self.contract_mod_override.is_some() {
+            return Err(anyhow!(
+                "contract name and module overrides can't be used when \
+                 generating bindings for a directory"
+            ));
+        }
+
+        let dir = dir.as_ref();
+        let mut paths = fs::read_dir(dir)
+            .with_context(|| format!("failed to read directory {}", dir.display()))?
+            .map(|entry| Ok(entry?.path()))
+            .collect::<Result<Vec<_>>>()?;
+        paths.retain(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "json"));
+        paths.sort();
+
+        let loader = TruffleLoader::new();
+        let mut module = ModuleBindings::new();
+        for path in paths {
+            let contract = loader
+                .load_contract_from_file(&path)
+                .with_context(|| format!("failed to load artifact {}", path.display()))?;
+            let bindings = self
+                .clone()
+                .generate(&contract)
+                .with_context(|| format!("failed to generate bindings for {}", path.display()))?;
+            module = module.add(contract.name.to_snake_case(), bindings);
+        }
+
+        Ok(module)
+    }
 }
 
 impl Default for ContractBuilder {
@@ -197,6 +459,15 @@ impl Default for ContractBuilder {
     }
 }
 
+/// Serialization format for 256-bit integers in generated code.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum NumberFormat {
+    /// `0x`-prefixed hexadecimal strings, for example `"0x2a"`.
+    Hex,
+    /// Decimal strings, for example `"42"`.
+    Decimal,
+}
+
 /// Type-safe contract bindings generated by a `Builder`. This type can be
 /// either written to file or into a token stream for use in a procedural macro.
 pub struct ContractBindings {
@@ -221,17 +492,7 @@ impl ContractBindings {
 
     /// Writes the bindings to a given `Write`.
     pub fn write(&self, mut w: impl Write) -> Result<()> {
-        let source = {
-            let raw = self.tokens.to_string();
-
-            if self.rustfmt {
-                rustfmt::format(&raw).unwrap_or(raw)
-            } else {
-                raw
-            }
-        };
-
-        w.write_all(source.as_bytes())?;
+        w.write_all(self.source().as_bytes())?;
         Ok(())
     }
 
@@ -242,9 +503,248 @@ impl ContractBindings {
         self.write(writer)
     }
 
+    /// Renders the bindings into a normalized string for snapshot testing.
+    /// See the [`snapshot`] module for more details.
+    pub fn snapshot(&self) -> String {
+        snapshot::render(&self.tokens)
+    }
+
     /// Converts the bindings into its underlying token stream. This allows it
     /// to be used within a procedural macro.
     pub fn into_tokens(self) -> TokenStream {
         self.tokens
     }
+
+    /// Renders the bindings into source code, formatted if enabled.
+    fn source(&self) -> String {
+        let raw = self.tokens.to_string();
+
+        if self.rustfmt {
+            rustfmt::format(&raw).unwrap_or(raw)
+        } else {
+            raw
+        }
+    }
+}
+
+/// Type-safe bindings for multiple contracts. These can be either written as a
+/// single concatenated source file, or into a directory with one file per
+/// contract and a `mod.rs` index.
+#[derive(Default)]
+#[must_use = "module bindings do nothing unless you write them"]
+pub struct ModuleBindings {
+    contracts: Vec<(String, ContractBindings)>,
+}
+
+impl ModuleBindings {
+    /// Creates new empty module bindings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the bindings of a contract. When writing to a directory, they are
+    /// written to a file named `{name}.rs`.
+    pub fn add(mut self, name: impl Into<String>, bindings: ContractBindings) -> Self {
+        self.contracts.push((name.into(), bindings));
+        self
+    }
+
+    /// Writes the bindings of all contracts to a given `Write`.
+    pub fn write(&self, mut w: impl Write) -> Result<()> {
+        for (_, bindings) in &self.contracts {
+            bindings.write(&mut w)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the bindings of all contracts to the specified file.
+    pub fn write_to_file(&self, path: impl AsRef<Path>) -> Result<()> {
+        let file = File::create(path)?;
+        let writer = BufWriter::new(file);
+        self.write(writer)
+    }
+
+    /// Writes the bindings of each contract to its own file in the specified
+    /// directory, creating it if it doesn't exist, along with a `mod.rs` index
+    /// file that includes all of them.
+    ///
+    /// The index uses `include!` instead of `mod` declarations, so the
+    /// generated types are at the same paths as when writing to a single file.
+    /// This allows including the index from a build script's output directory
+    /// with `include!(concat!(env!("OUT_DIR"), "/contracts/mod.rs"))`, as well
+    /// as declaring the directory as a module.
+    ///
+    /// Files whose contents did not change are not rewritten, so that only
+    /// the bindings of contracts that changed are considered modified.
+    pub fn write_to_dir(&self, dir: impl AsRef<Path>) -> Result<()> {
+        let dir = dir.as_ref();
+        let mut names = HashSet::new();
+        for (name, _) in &self.contracts {
+            if name.is_empty() || name == "mod" || name.contains(['/', '\\', '.']) {
+                return Err(anyhow!("invalid contract file name '{}'", name));
+            }
+            if !names.insert(name) {
+                return Err(anyhow!("duplicate contract file name '{}'", name));
+            }
+        }
+
+        fs::create_dir_all(dir)?;
+        let mut index = String::new();
+        for (name, bindings) in &self.contracts {
+            let file_name = format!("{}.rs", name);
+            write_if_changed(&dir.join(&file_name), &bindings.source())?;
+            index.push_str(&format!("include!({:?});\n", file_name));
+        }
+        write_if_changed(&dir.join("mod.rs"), &index)
+    }
+}
+
+/// Writes contents to a file, unless it already has the same contents.
+fn write_if_changed(path: &Path, contents: &str) -> Result<()> {
+    if fs::read_to_string(path).ok().as_deref() == Some(contents) {
+        return Ok(());
+    }
+    fs::write(path, contents)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::quote;
+    use std::path::PathBuf;
+
+    fn bindings(tokens: TokenStream) -> ContractBindings {
+        ContractBindings {
+            tokens,
+            rustfmt: false,
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "ethcontract-generate-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn write_module_to_single_file() {
+        let module = ModuleBindings::new()
+            .add("foo", bindings(quote! { mod foo {} }))
+            .add("bar", bindings(quote! { mod bar {} }));
+
+        let mut source = Vec::new();
+        module.write(&mut source).unwrap();
+
+        assert_eq!(String::from_utf8(source).unwrap(), "mod foo { }mod bar { }");
+    }
+
+    #[test]
+    fn write_module_to_dir() {
+        let dir = temp_dir("write-module-to-dir");
+        let module = ModuleBindings::new()
+            .add("foo", bindings(quote! { mod foo {} }))
+            .add("bar", bindings(quote! { mod bar {} }));
+
+        module.write_to_dir(&dir).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(dir.join("mod.rs")).unwrap(),
+            "include!(\"foo.rs\");\ninclude!(\"bar.rs\");\n",
+        );
+        assert_eq!(
+            fs::read_to_string(dir.join("foo.rs")).unwrap(),
+            "mod foo { }"
+        );
+        assert_eq!(
+            fs::read_to_string(dir.join("bar.rs")).unwrap(),
+            "mod bar { }"
+        );
+
+        let modified = fs::metadata(dir.join("foo.rs"))
+            .unwrap()
+            .modified()
+            .unwrap();
+        ModuleBindings::new()
+            .add("foo", bindings(quote! { mod foo {} }))
+            .add("bar", bindings(quote! { mod bar { struct Bar; } }))
+            .write_to_dir(&dir)
+            .unwrap();
+        assert_eq!(
+            fs::metadata(dir.join("foo.rs"))
+                .unwrap()
+                .modified()
+                .unwrap(),
+            modified,
+        );
+        assert_eq!(
+            fs::read_to_string(dir.join("bar.rs")).unwrap(),
+            "mod bar { struct Bar ; }",
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn write_module_to_dir_rejects_invalid_names() {
+        let dir = temp_dir("invalid-names");
+        for names in [&["mod"][..], &["../foo"], &["foo", "foo"]] {
+            let module = names.iter().fold(ModuleBindings::new(), |module, name| {
+                module.add(*name, bindings(quote! {}))
+            });
+            assert!(module.write_to_dir(&dir).is_err());
+        }
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn generate_dir() {
+        let dir = temp_dir("generate-dir");
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        for name in ["SimpleToken", "Owned"] {
+            let contract = Contract::with_name(name);
+            fs::write(
+                dir.join(format!("{}.json", name)),
+                TruffleLoader::save_to_string(&contract).unwrap(),
+            )
+            .unwrap();
+        }
+        fs::write(dir.join("README.md"), "not an artifact").unwrap();
+
+        let module = ContractBuilder::new().generate_dir(&dir).unwrap();
+        let names = module
+            .contracts
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["owned", "simple_token"]);
+
+        fs::write(dir.join("Broken.json"), "{").unwrap();
+        assert!(ContractBuilder::new().generate_dir(&dir).is_err());
+        assert!(ContractBuilder::new()
+            .contract_name_override("Token")
+            .generate_dir(&dir)
+            .is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn generate_without_reexport() {
+        let contract = Contract::with_name("Token");
+        let reexport = quote! { use self::token::Contract as Token; }.to_string();
+
+        let bindings = ContractBuilder::new().generate(&contract).unwrap();
+        assert!(bindings.tokens.to_string().contains(&reexport));
+
+        let bindings = ContractBuilder::new()
+            .reexport(false)
+            .generate(&contract)
+            .unwrap();
+        assert!(!bindings.tokens.to_string().contains(&reexport));
+    }
 }
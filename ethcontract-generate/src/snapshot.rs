@@ -0,0 +1,234 @@
+//! Helpers for snapshot testing generated contract bindings.
+//!
+//! Crates that post-process or depend on the exact shape of generated code
+//! can render their bindings into a normalized string and compare it with a
+//! golden file checked into their repository, so that unintended codegen
+//! changes are caught when upgrading `ethcontract`:
+//!
+//! ```no_run
+//! # use ethcontract_generate::{snapshot, ContractBuilder};
+//! # use ethcontract_generate::loaders::TruffleLoader;
+//! let contract = TruffleLoader::new().load_contract_from_file("WETH9.json").unwrap();
+//! let bindings = ContractBuilder::new().generate(&contract).unwrap();
+//! snapshot::assert_snapshot("tests/snapshots/weth9.rs.snap", &bindings.snapshot());
+//! ```
+//!
+//! Golden files are created when they don't exist yet, and can be updated by
+//! running the tests with the `ETHCONTRACT_UPDATE_SNAPSHOTS` environment
+//! variable set.
+
+use proc_macro2::{Delimiter, Spacing, TokenStream, TokenTree};
+use std::fs;
+use std::path::Path;
+
+/// Environment variable that causes [`assert_snapshot`] to overwrite golden
+/// files with the actual output instead of comparing them.
+pub const UPDATE_SNAPSHOTS_ENV: &str = "ETHCONTRACT_UPDATE_SNAPSHOTS";
+
+/// Renders a token stream into a normalized string with one item or statement
+/// per line.
+///
+/// Unlike formatting with `rustfmt`, the output only depends on the tokens
+/// and not on the locally installed toolchain, which makes it suitable for
+/// comparing against golden files.
+pub fn render(tokens: &TokenStream) -> String {
+    let mut printer = Printer::default();
+    printer.print(tokens.clone(), true);
+    printer.newline();
+    printer.out
+}
+
+/// Asserts that the rendered bindings match the golden file at `path`.
+///
+/// The golden file is written instead if it does not exist yet or if the
+/// [`UPDATE_SNAPSHOTS_ENV`] environment variable is set.
+///
+/// # Panics
+///
+/// If the golden file does not match the actual output, or if it can't be
+/// read or written.
+pub fn assert_snapshot(path: impl AsRef<Path>, actual: &str) {
+    let path = path.as_ref();
+    if std::env::var_os(UPDATE_SNAPSHOTS_ENV).is_some() || !path.exists() {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .unwrap_or_else(|err| panic!("failed to create {}: {}", parent.display(), err));
+        }
+        fs::write(path, actual)
+            .unwrap_or_else(|err| panic!("failed to write {}: {}", path.display(), err));
+        return;
+    }
+
+    let expected = fs::read_to_string(path)
+        .unwrap_or_else(|err| panic!("failed to read {}: {}", path.display(), err));
+    if let Some((line, (expected, actual))) = expected
+        .lines()
+        .chain(std::iter::repeat(""))
+        .zip(actual.lines().chain(std::iter::repeat("")))
+        .take(expected.lines().count().max(actual.lines().count()))
+        .enumerate()
+        .find(|(_, (expected, actual))| expected != actual)
+    {
+        panic!(
+            "generated code does not match snapshot {} at line {}:\n  expected: {}\n    actual: {}\n\
+             rerun with {} set to update the snapshot",
+            path.display(),
+            line + 1,
+            expected,
+            actual,
+            UPDATE_SNAPSHOTS_ENV,
+        );
+    }
+}
+
+/// Simple token printer that puts items and statements on separate lines,
+/// indented by brace depth.
+#[derive(Default)]
+struct Printer {
+    out: String,
+    indent: usize,
+    line_start: bool,
+    space: bool,
+}
+
+impl Printer {
+    fn print(&mut self, tokens: TokenStream, braced: bool) {
+        let mut tokens = tokens.into_iter().peekable();
+        let mut attribute = false;
+        while let Some(token) = tokens.next() {
+            let hash = matches!(&token, TokenTree::Punct(punct) if punct.as_char() == '#');
+            match token {
+                TokenTree::Group(group) => match group.delimiter() {
+                    Delimiter::Brace => {
+                        self.word("{");
+                        self.indent += 1;
+                        self.newline();
+                        self.print(group.stream(), true);
+                        self.indent -= 1;
+                        self.newline();
+                        self.word("}");
+                        match tokens.peek() {
+                            Some(TokenTree::Punct(_)) | None => {}
+                            Some(_) => self.newline(),
+                        }
+                    }
+                    delimiter => {
+                        let (open, close) = match delimiter {
+                            Delimiter::Parenthesis => ("(", ")"),
+                            Delimiter::Bracket => ("[", "]"),
+                            _ => ("", ""),
+                        };
+                        self.word(open);
+                        self.space = false;
+                        self.print(group.stream(), false);
+                        self.space = false;
+                        self.word(close);
+                        if attribute && braced && delimiter == Delimiter::Bracket {
+                            self.newline();
+                        }
+                    }
+                },
+                TokenTree::Punct(punct) => {
+                    let ch = punct.as_char();
+                    if matches!(ch, ',' | ';' | '.' | '?') {
+                        self.space = false;
+                    }
+                    self.word(&ch.to_string());
+                    let joint = punct.spacing() == Spacing::Joint
+                        && matches!(tokens.peek(), Some(TokenTree::Punct(_)));
+                    if joint || matches!(ch, '.' | '#' | '&' | '\'') {
+                        self.space = false;
+                    }
+                    if ch == ';' || (ch == ',' && braced) {
+                        self.newline();
+                    }
+                }
+                token => self.word(&token.to_string()),
+            }
+            attribute = hash;
+        }
+    }
+
+    fn word(&mut self, word: &str) {
+        if self.line_start {
+            self.out.push_str(&"    ".repeat(self.indent));
+            self.line_start = false;
+        } else if self.space && !self.out.is_empty() {
+            self.out.push(' ');
+        }
+        self.out.push_str(word);
+        self.space = true;
+    }
+
+    fn newline(&mut self) {
+        if !self.line_start && !self.out.is_empty() {
+            self.out.push('\n');
+            self.line_start = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::quote;
+
+    #[test]
+    fn render_tokens() {
+        let tokens = quote! {
+            #[derive(Clone)]
+            pub struct Foo {
+                a: u32,
+                b: Vec<u8>,
+            }
+
+            impl Foo {
+                pub fn bar (&self) -> Option<u32> {
+                    let a = self.a;
+                    Some(a + 1)
+                }
+            }
+        };
+
+        assert_eq!(
+            render(&tokens),
+            "#[derive (Clone)]\n\
+             pub struct Foo {\n    \
+                a : u32,\n    \
+                b : Vec < u8 >,\n\
+             }\n\
+             impl Foo {\n    \
+                pub fn bar (&self) -> Option < u32 > {\n        \
+                    let a = self.a;\n        \
+                    Some (a + 1)\n    \
+                }\n\
+             }\n",
+        );
+    }
+
+    #[test]
+    fn render_is_deterministic() {
+        let tokens = quote! { mod a { fn b() { c(); } } };
+        assert_eq!(
+            render(&tokens),
+            render(&tokens.to_string().parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn assert_snapshot_golden_file() {
+        let path = std::env::temp_dir()
+            .join(format!("ethcontract-snapshot-{}", std::process::id()))
+            .join("bindings.rs.snap");
+        let _ = fs::remove_file(&path);
+
+        assert_snapshot(&path, "fn foo() {}\n");
+        assert_eq!(fs::read_to_string(&path).unwrap(), "fn foo() {}\n");
+        assert_snapshot(&path, "fn foo() {}\n");
+
+        let mismatch = std::panic::catch_unwind(|| assert_snapshot(&path, "fn bar() {}\n"));
+        assert!(mismatch.is_err());
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -59,6 +59,29 @@ pub enum Source {
     #[cfg(feature = "http")]
     Etherscan(Address),
 
+    /// An address of a contract verified on a block explorer with an
+    /// Etherscan compatible API, for contracts deployed on L2s and
+    /// sidechains.
+    ///
+    /// Artifacts loaded from block explorers can be parsed using
+    /// the [truffle loader].
+    ///
+    /// [truffle loader]: ethcontract_common::artifact::truffle::TruffleLoader
+    #[cfg(feature = "http")]
+    Explorer(BlockExplorer, Address),
+
+    /// A chain ID and an address of a contract with verified sources on
+    /// [Sourcify].
+    ///
+    /// Sourcify is an open alternative to Etherscan that does not require an
+    /// API key and supports many networks. Artifacts loaded from Sourcify
+    /// can be parsed using the [truffle loader].
+    ///
+    /// [Sourcify]: sourcify.dev
+    /// [truffle loader]: ethcontract_common::artifact::truffle::TruffleLoader
+    #[cfg(feature = "http")]
+    Sourcify(u64, Address),
+
     /// The package identifier of an NPM package with a path to an artifact
     /// or ABI to be retrieved from [unpkg].
     ///
@@ -88,12 +111,32 @@ impl Source {
     ///   an [etherscan] URL: `https://etherscan.io/address/0xC02AA...`.
     ///   The contract artifact or ABI will be retrieved through [`Etherscan`];
     ///
+    /// - a URL with `etherscan` scheme, a chain ID and a contract address, for
+    ///   contracts on other chains indexed by Etherscan. For example
+    ///   `etherscan:10:0x4200...`;
+    ///
+    /// - a URL with `arbiscan`, `polygonscan`, `bscscan` or `basescan` scheme
+    ///   and a contract address, for example `arbiscan:0x82aF4...`.
+    ///   Alternatively, specify the explorer URL:
+    ///   `https://arbiscan.io/address/0x82aF4...`. The contract ABI will be
+    ///   retrieved through the respective [`BlockExplorer`];
+    ///
+    /// - a URL with `sourcify` scheme, a chain ID and a contract address.
+    ///   For example `sourcify:100:0xe91D1...`. The contract ABI will be
+    ///   retrieved from the verified contract metadata on [`Sourcify`];
+    ///
     /// - a URL with `npm` scheme, NPM package name, an optional version
     ///   and a path (defaulting to the latest version and `index.js`).
     ///   For example `npm:@openzeppelin/contracts/build/contracts/IERC20.json`.
     ///   The contract artifact or ABI will be retrieved through [`unpkg`].
     ///
+    /// Sources can reference environment variables using the `${NAME}`
+    /// syntax, for example `${CONTRACTS_DIR}/IERC20.json`. Variables are
+    /// expanded before the source is parsed, and an error is returned if any
+    /// of them is not set.
+    ///
     /// [Etherscan]: etherscan.io
+    /// [Sourcify]: sourcify.dev
     /// [unpkg]: unpkg.io
     pub fn parse(source: &str) -> Result<Self> {
         let root = env::current_dir()?.canonicalize()?;
@@ -104,6 +147,7 @@ impl Source {
     /// directory for resolving relative paths. See [`parse`] for more details
     /// on supported source strings.
     pub fn with_root(root: impl AsRef<Path>, source: &str) -> Result<Self> {
+        let source = &*interpolate_env(source, |name| env::var(name).ok())?;
         let root = root.as_ref();
         let base = Url::from_directory_path(root)
             .map_err(|_| anyhow!("root path '{}' is not absolute", root.display()))?;
@@ -112,17 +156,53 @@ impl Source {
         match url.scheme() {
             "file" => Ok(Source::local(root.join(source))),
             #[cfg(feature = "http")]
-            "http" | "https" => match url.host_str() {
-                Some("etherscan.io") => Source::etherscan(
-                    url.path()
-                        .rsplit('/')
-                        .next()
-                        .ok_or_else(|| anyhow!("HTTP URL does not have a path"))?,
-                ),
-                _ => Ok(Source::Http(url)),
+            "http" | "https" => {
+                let explorer = match url.host_str() {
+                    Some("etherscan.io") => None,
+                    Some(host) => match BlockExplorer::from_host(host) {
+                        Some(explorer) => Some(explorer),
+                        None => return Ok(Source::Http(url)),
+                    },
+                    None => return Ok(Source::Http(url)),
+                };
+                let address = url
+                    .path()
+                    .rsplit('/')
+                    .next()
+                    .ok_or_else(|| anyhow!("HTTP URL does not have a path"))?;
+                match explorer {
+                    Some(explorer) => Source::explorer(explorer, address),
+                    None => Source::etherscan(address),
+                }
+            }
+            #[cfg(feature = "http")]
+            "etherscan" => match url.path().split_once(':') {
+                Some((chain_id, address)) => {
+                    let chain_id = chain_id.parse().with_context(|| {
+                        format!(
+                            "failed to parse chain ID '{}' for Etherscan source",
+                            chain_id
+                        )
+                    })?;
+                    Source::explorer(BlockExplorer::Etherscan(chain_id), address)
+                }
+                None => Source::etherscan(url.path()),
             },
             #[cfg(feature = "http")]
-            "etherscan" => Source::etherscan(url.path()),
+            scheme if BlockExplorer::from_scheme(scheme).is_some() => {
+                let explorer = BlockExplorer::from_scheme(scheme).unwrap();
+                Source::explorer(explorer, url.path())
+            }
+            #[cfg(feature = "http")]
+            "sourcify" => {
+                let (chain_id, address) = url.path().split_once(':').ok_or_else(|| {
+                    anyhow!(
+                        "Sourcify URL '{}' must have the form sourcify:<chain_id>:<address>",
+                        url
+                    )
+                })?;
+                Source::sourcify(chain_id, address)
+            }
             #[cfg(feature = "http")]
             "npm" => Ok(Source::npm(url.path())),
             _ => Err(anyhow!("unsupported URL '{}'", url)),
@@ -150,6 +230,31 @@ impl Source {
             .map(Source::Etherscan)
     }
 
+    /// Creates a source for a contract verified on the specified block
+    /// explorer.
+    #[cfg(feature = "http")]
+    pub fn explorer(explorer: BlockExplorer, address: &str) -> Result<Self> {
+        util::parse_address(address)
+            .with_context(|| format!("failed to parse address for {} source", explorer))
+            .map(|address| Source::Explorer(explorer, address))
+    }
+
+    /// Creates a [Sourcify] source from a chain ID and contract address.
+    ///
+    /// [Sourcify]: sourcify.dev
+    #[cfg(feature = "http")]
+    pub fn sourcify(chain_id: &str, address: &str) -> Result<Self> {
+        let chain_id = chain_id.parse().with_context(|| {
+            format!(
+                "failed to parse chain ID '{}' for Sourcify source",
+                chain_id
+            )
+        })?;
+        let address =
+            util::parse_address(address).context("failed to parse address for Sourcify source")?;
+        Ok(Source::Sourcify(chain_id, address))
+    }
+
     /// Creates an NPM source from a package path.
     #[cfg(feature = "http")]
     pub fn npm(package_path: impl Into<String>) -> Self {
@@ -166,14 +271,180 @@ impl Source {
     ///
     /// [truffle loader]: ethcontract_common::artifact::truffle::TruffleLoader
     pub fn artifact_json(&self) -> Result<String> {
+        let json = self.json()?;
+        Ok(match self {
+            #[cfg(feature = "http")]
+            Source::Etherscan(address) => deployed_artifact(json, 1, *address),
+            #[cfg(feature = "http")]
+            Source::Explorer(explorer, address) => {
+                deployed_artifact(json, explorer.chain_id(), *address)
+            }
+            #[cfg(feature = "http")]
+            Source::Sourcify(chain_id, address) => deployed_artifact(json, *chain_id, *address),
+            _ => abi_or_artifact(json),
+        })
+    }
+
+    /// Retrieves the source JSON document as is.
+    ///
+    /// Unlike [`artifact_json`](Self::artifact_json), contract ABIs are not
+    /// wrapped into a JSON object, so that they can be loaded using the
+    /// [abi loader]. Block explorer and Sourcify sources always return a bare
+    /// contract ABI.
+    ///
+    /// [abi loader]: ethcontract_common::artifact::abi::AbiLoader
+    pub fn json(&self) -> Result<String> {
         match self {
-            Source::Local(path) => get_local_contract(path),
+            Source::Local(path) => get_local_json(path),
+            #[cfg(feature = "http")]
+            Source::Http(url) => get_http_json(url),
             #[cfg(feature = "http")]
-            Source::Http(url) => get_http_contract(url),
+            Source::Etherscan(address) => get_etherscan_abi(*address),
             #[cfg(feature = "http")]
-            Source::Etherscan(address) => get_etherscan_contract(*address),
+            Source::Explorer(explorer, address) => get_explorer_abi(*explorer, *address),
             #[cfg(feature = "http")]
-            Source::Npm(package) => get_npm_contract(package),
+            Source::Sourcify(chain_id, address) => get_sourcify_abi(*chain_id, *address),
+            #[cfg(feature = "http")]
+            Source::Npm(package) => get_npm_json(package),
+        }
+    }
+}
+
+/// A block explorer with an Etherscan compatible API.
+#[cfg(feature = "http")]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BlockExplorer {
+    /// The unified [Etherscan] API for the chain with the specified ID.
+    ///
+    /// Requests use the `ETHERSCAN_API_KEY` environment variable as API key.
+    ///
+    /// [Etherscan]: etherscan.io
+    Etherscan(u64),
+
+    /// [Arbiscan] for Arbitrum One, using the `ARBISCAN_API_KEY` environment
+    /// variable as API key.
+    ///
+    /// [Arbiscan]: arbiscan.io
+    Arbiscan,
+
+    /// [Polygonscan] for Polygon PoS, using the `POLYGONSCAN_API_KEY`
+    /// environment variable as API key.
+    ///
+    /// [Polygonscan]: polygonscan.com
+    Polygonscan,
+
+    /// [BscScan] for BNB Smart Chain, using the `BSCSCAN_API_KEY` environment
+    /// variable as API key.
+    ///
+    /// [BscScan]: bscscan.com
+    BscScan,
+
+    /// [Basescan] for Base, using the `BASESCAN_API_KEY` environment variable
+    /// as API key.
+    ///
+    /// [Basescan]: basescan.org
+    Basescan,
+}
+
+#[cfg(feature = "http")]
+impl BlockExplorer {
+    const NAMED: [BlockExplorer; 4] = [
+        BlockExplorer::Arbiscan,
+        BlockExplorer::Polygonscan,
+        BlockExplorer::BscScan,
+        BlockExplorer::Basescan,
+    ];
+
+    /// Returns the named block explorer for a source URL scheme, such as
+    /// `arbiscan`.
+    pub fn from_scheme(scheme: &str) -> Option<Self> {
+        Self::NAMED
+            .into_iter()
+            .find(|explorer| explorer.scheme() == Some(scheme))
+    }
+
+    /// Returns the named block explorer for a website host, such as
+    /// `arbiscan.io`.
+    pub fn from_host(host: &str) -> Option<Self> {
+        Self::NAMED
+            .into_iter()
+            .find(|explorer| explorer.host() == Some(host))
+    }
+
+    /// Returns the ID of the chain indexed by this explorer.
+    pub fn chain_id(&self) -> u64 {
+        match self {
+            BlockExplorer::Etherscan(chain_id) => *chain_id,
+            BlockExplorer::Arbiscan => 42161,
+            BlockExplorer::Polygonscan => 137,
+            BlockExplorer::BscScan => 56,
+            BlockExplorer::Basescan => 8453,
+        }
+    }
+
+    /// Returns the URL of the explorer's API. All explorers are queried
+    /// through the multichain Etherscan V2 API, with the chain selected by
+    /// the `chainid` parameter.
+    pub fn api_url(&self) -> &'static str {
+        "https://api.etherscan.io/v2/api"
+    }
+
+    /// Returns the name of the environment variable holding the API key for
+    /// requests to this explorer.
+    pub fn api_key_env(&self) -> &'static str {
+        match self {
+            BlockExplorer::Etherscan(_) => "ETHERSCAN_API_KEY",
+            BlockExplorer::Arbiscan => "ARBISCAN_API_KEY",
+            BlockExplorer::Polygonscan => "POLYGONSCAN_API_KEY",
+            BlockExplorer::BscScan => "BSCSCAN_API_KEY",
+            BlockExplorer::Basescan => "BASESCAN_API_KEY",
+        }
+    }
+
+    /// Returns the URL for retrieving the ABI of a verified contract.
+    pub fn abi_url(&self, address: Address, api_key: Option<&str>) -> String {
+        let mut url = format!(
+            "{}?module=contract&action=getabi&address={:?}&format=raw",
+            self.api_url(),
+            address,
+        );
+        url.push_str(&format!("&chainid={}", self.chain_id()));
+        if let Some(api_key) = api_key {
+            url.push_str(&format!("&apikey={}", api_key));
+        }
+        url
+    }
+
+    fn scheme(&self) -> Option<&'static str> {
+        match self {
+            BlockExplorer::Etherscan(_) => None,
+            BlockExplorer::Arbiscan => Some("arbiscan"),
+            BlockExplorer::Polygonscan => Some("polygonscan"),
+            BlockExplorer::BscScan => Some("bscscan"),
+            BlockExplorer::Basescan => Some("basescan"),
+        }
+    }
+
+    fn host(&self) -> Option<&'static str> {
+        match self {
+            BlockExplorer::Etherscan(_) => None,
+            BlockExplorer::Arbiscan => Some("arbiscan.io"),
+            BlockExplorer::Polygonscan => Some("polygonscan.com"),
+            BlockExplorer::BscScan => Some("bscscan.com"),
+            BlockExplorer::Basescan => Some("basescan.org"),
+        }
+    }
+}
+
+#[cfg(feature = "http")]
+impl std::fmt::Display for BlockExplorer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BlockExplorer::Etherscan(chain_id) => write!(f, "Etherscan (chain {})", chain_id),
+            BlockExplorer::Arbiscan => f.write_str("Arbiscan"),
+            BlockExplorer::Polygonscan => f.write_str("Polygonscan"),
+            BlockExplorer::BscScan => f.write_str("BscScan"),
+            BlockExplorer::Basescan => f.write_str("Basescan"),
         }
     }
 }
@@ -186,7 +457,39 @@ impl FromStr for Source {
     }
 }
 
-fn get_local_contract(path: &Path) -> Result<String> {
+/// Expands `${NAME}` environment variable references in a source string.
+fn interpolate_env(source: &str, lookup: impl Fn(&str) -> Option<String>) -> Result<Cow<'_, str>> {
+    if !source.contains("${") {
+        return Ok(Cow::Borrowed(source));
+    }
+
+    let mut result = String::with_capacity(source.len());
+    let mut rest = source;
+    while let Some(start) = rest.find("${") {
+        result.push_str(&rest[..start]);
+        let end = rest[start..].find('}').ok_or_else(|| {
+            anyhow!(
+                "unterminated environment variable reference in artifact source '{}'",
+                source,
+            )
+        })?;
+        let name = &rest[start + 2..start + end];
+        let value = lookup(name).ok_or_else(|| {
+            anyhow!(
+                "environment variable '{}' used in artifact source '{}' is not set",
+                name,
+                source,
+            )
+        })?;
+        result.push_str(&value);
+        rest = &rest[start + end + 1..];
+    }
+    result.push_str(rest);
+
+    Ok(Cow::Owned(result))
+}
+
+fn get_local_json(path: &Path) -> Result<String> {
     let path = if path.is_relative() {
         let absolute_path = path.canonicalize().with_context(|| {
             format!(
@@ -202,19 +505,16 @@ fn get_local_contract(path: &Path) -> Result<String> {
         Cow::Borrowed(path)
     };
 
-    let json = fs::read_to_string(path).context("failed to read artifact JSON file")?;
-    Ok(abi_or_artifact(json))
+    fs::read_to_string(path).context("failed to read artifact JSON file")
 }
 
 #[cfg(feature = "http")]
-fn get_http_contract(url: &Url) -> Result<String> {
-    let json = util::http_get(url.as_str())
-        .with_context(|| format!("failed to retrieve JSON from {}", url))?;
-    Ok(abi_or_artifact(json))
+fn get_http_json(url: &Url) -> Result<String> {
+    util::http_get(url.as_str()).with_context(|| format!("failed to retrieve JSON from {}", url))
 }
 
 #[cfg(feature = "http")]
-fn get_etherscan_contract(address: Address) -> Result<String> {
+fn get_etherscan_abi(address: Address) -> Result<String> {
     // NOTE: We do not retrieve the bytecode since deploying contracts with the
     //   same bytecode is unreliable as the libraries have already linked and
     //   probably don't reference anything when deploying on other networks.
@@ -228,26 +528,68 @@ fn get_etherscan_contract(address: Address) -> Result<String> {
          ?module=contract&action=getabi&address={:?}&format=raw{}",
         address, api_key,
     );
-    let abi = util::http_get(&abi_url).context("failed to retrieve ABI from Etherscan.io")?;
-
-    // NOTE: Wrap the retrieved ABI in an empty contract, this is because
-    //   currently, the code generation infrastructure depends on having an
-    //   `Artifact` instance.
-    let json = format!(
-        r#"{{"abi":{},"networks":{{"1":{{"address":"{:?}"}}}}}}"#,
-        abi, address,
-    );
+    util::http_get(&abi_url).context("failed to retrieve ABI from Etherscan.io")
+}
+
+#[cfg(feature = "http")]
+fn get_explorer_abi(explorer: BlockExplorer, address: Address) -> Result<String> {
+    let api_key = env::var(explorer.api_key_env()).ok();
+    let abi_url = explorer.abi_url(address, api_key.as_deref());
+    util::http_get(&abi_url).with_context(|| format!("failed to retrieve ABI from {}", explorer))
+}
 
-    Ok(json)
+#[cfg(feature = "http")]
+fn get_sourcify_abi(chain_id: u64, address: Address) -> Result<String> {
+    // NOTE: Contracts can be verified with either a full match, where the
+    //   metadata hash embedded in the bytecode also matches, or a partial
+    //   match. Both provide the same ABI, so try the full match first.
+
+    let metadata = ["full_match", "partial_match"]
+        .iter()
+        .find_map(|kind| {
+            let metadata_url = format!(
+                "https://repo.sourcify.dev/contracts/{}/{}/{:?}/metadata.json",
+                kind, chain_id, address,
+            );
+            util::http_get(&metadata_url).ok()
+        })
+        .ok_or_else(|| {
+            anyhow!(
+                "contract {:?} on chain {} is not verified on Sourcify",
+                address,
+                chain_id,
+            )
+        })?;
+    sourcify_abi(&metadata)
 }
 
+/// Extracts the ABI from a Solidity metadata JSON document, as served by
+/// Sourcify.
 #[cfg(feature = "http")]
-fn get_npm_contract(package: &str) -> Result<String> {
-    let unpkg_url = format!("https://unpkg.com/{}", package);
-    let json = util::http_get(&unpkg_url)
-        .with_context(|| format!("failed to retrieve JSON from for npm package {}", package))?;
+fn sourcify_abi(metadata: &str) -> Result<String> {
+    let metadata: serde_json::Value =
+        serde_json::from_str(metadata).context("failed to parse Sourcify contract metadata")?;
+    let abi = metadata
+        .pointer("/output/abi")
+        .ok_or_else(|| anyhow!("Sourcify contract metadata does not contain an ABI"))?;
+    Ok(abi.to_string())
+}
 
-    Ok(abi_or_artifact(json))
+/// Wraps an ABI retrieved from a block explorer in an artifact for
+/// a contract deployed on the specified chain at the specified address.
+#[cfg(feature = "http")]
+fn deployed_artifact(abi: String, chain_id: u64, address: Address) -> String {
+    format!(
+        r#"{{"abi":{},"networks":{{"{}":{{"address":"{:?}"}}}}}}"#,
+        abi, chain_id, address,
+    )
+}
+
+#[cfg(feature = "http")]
+fn get_npm_json(package: &str) -> Result<String> {
+    let unpkg_url = format!("https://unpkg.com/{}", package);
+    util::http_get(&unpkg_url)
+        .with_context(|| format!("failed to retrieve JSON from for npm package {}", package))
 }
 
 /// A best-effort coercion of an ABI or an artifact JSON document into an
@@ -258,9 +600,8 @@ fn get_npm_contract(package: &str) -> Result<String> {
 /// validation is done at this point as the document gets parsed and validated
 /// at generation time.
 ///
-/// This needs to be done as currently the contract generation infrastructure
-/// depends on having an artifact.
-// TODO(taminomara): add loader for plain ABIs?
+/// This allows bare ABIs to be loaded with the truffle loader, which is the
+/// default for the `contract!` macro.
 fn abi_or_artifact(json: String) -> String {
     if json.trim().starts_with('[') {
         format!(r#"{{"abi":{}}}"#, json.trim())
@@ -301,6 +642,38 @@ mod tests {
                 Source::etherscan("0x0001020304050607080910111213141516171819").unwrap(),
             ),
             #[cfg(feature = "http")]
+            (
+                "etherscan:10:0x0001020304050607080910111213141516171819",
+                Source::explorer(
+                    BlockExplorer::Etherscan(10),
+                    "0x0001020304050607080910111213141516171819",
+                )
+                .unwrap(),
+            ),
+            #[cfg(feature = "http")]
+            (
+                "arbiscan:0x0001020304050607080910111213141516171819",
+                Source::explorer(
+                    BlockExplorer::Arbiscan,
+                    "0x0001020304050607080910111213141516171819",
+                )
+                .unwrap(),
+            ),
+            #[cfg(feature = "http")]
+            (
+                "https://basescan.org/address/0x0001020304050607080910111213141516171819",
+                Source::explorer(
+                    BlockExplorer::Basescan,
+                    "0x0001020304050607080910111213141516171819",
+                )
+                .unwrap(),
+            ),
+            #[cfg(feature = "http")]
+            (
+                "sourcify:100:0x0001020304050607080910111213141516171819",
+                Source::sourcify("100", "0x0001020304050607080910111213141516171819").unwrap(),
+            ),
+            #[cfg(feature = "http")]
             (
                 "npm:@openzeppelin/contracts@2.5.0/build/contracts/IERC20.json",
                 Source::npm("@openzeppelin/contracts@2.5.0/build/contracts/IERC20.json"),
@@ -310,4 +683,95 @@ mod tests {
             assert_eq!(source, *expected);
         }
     }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn block_explorer_abi_url() {
+        let address = Address::from_low_u64_be(1);
+        assert_eq!(
+            BlockExplorer::Polygonscan.abi_url(address, Some("KEY")),
+            "https://api.etherscan.io/v2/api?module=contract&action=getabi\
+             &address=0x0000000000000000000000000000000000000001&format=raw&chainid=137\
+             &apikey=KEY",
+        );
+        assert_eq!(
+            BlockExplorer::Etherscan(10).abi_url(address, None),
+            "https://api.etherscan.io/v2/api?module=contract&action=getabi\
+             &address=0x0000000000000000000000000000000000000001&format=raw&chainid=10",
+        );
+        assert_eq!(BlockExplorer::BscScan.chain_id(), 56);
+        assert_eq!(BlockExplorer::BscScan.api_key_env(), "BSCSCAN_API_KEY");
+        assert!(Source::with_root(
+            "/rooted",
+            "etherscan:optimism:0x0001020304050607080910111213141516171819"
+        )
+        .is_err());
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn parse_invalid_sourcify_source() {
+        assert!(Source::with_root(
+            "/rooted",
+            "sourcify:0x0001020304050607080910111213141516171819"
+        )
+        .is_err());
+        assert!(Source::with_root(
+            "/rooted",
+            "sourcify:gnosis:0x0001020304050607080910111213141516171819"
+        )
+        .is_err());
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn sourcify_artifact() {
+        let metadata = r#"{
+            "compiler": { "version": "0.8.19+commit.7dd6d404" },
+            "language": "Solidity",
+            "output": {
+                "abi": [{ "type": "fallback", "stateMutability": "payable" }],
+                "devdoc": {},
+                "userdoc": {}
+            },
+            "version": 1
+        }"#;
+
+        let address = Address::from_low_u64_be(42);
+        let abi = sourcify_abi(metadata).unwrap();
+        let artifact = deployed_artifact(abi, 100, address);
+        let contract = ethcontract_common::artifact::truffle::TruffleLoader::new()
+            .load_contract_from_str(&artifact)
+            .unwrap();
+
+        assert!(contract.interface.abi.fallback);
+        assert_eq!(contract.networks["100"].address, address);
+        assert!(sourcify_abi(r#"{"output":{}}"#).is_err());
+    }
+
+    #[test]
+    fn interpolate_env_vars() {
+        let lookup = |name: &str| match name {
+            "CONTRACTS_DIR" => Some("/contracts".to_string()),
+            "NAME" => Some("IERC20".to_string()),
+            _ => None,
+        };
+
+        assert_eq!(
+            interpolate_env("build/IERC20.json", lookup).unwrap(),
+            "build/IERC20.json",
+        );
+        assert_eq!(
+            interpolate_env("${CONTRACTS_DIR}/${NAME}.json", lookup).unwrap(),
+            "/contracts/IERC20.json",
+        );
+        assert_eq!(
+            interpolate_env("${MISSING}/IERC20.json", lookup)
+                .unwrap_err()
+                .to_string(),
+            "environment variable 'MISSING' used in artifact source \
+             '${MISSING}/IERC20.json' is not set",
+        );
+        assert!(interpolate_env("${CONTRACTS_DIR/IERC20.json", lookup).is_err());
+    }
 }
@@ -1,4 +1,4 @@
-use anyhow::{anyhow, Result};
+use anyhow::Result;
 #[cfg(feature = "http")]
 use curl::easy::Easy;
 use ethcontract_common::Address;
@@ -42,16 +42,13 @@ pub fn expand_doc(s: &str) -> TokenStream {
     }
 }
 
-/// Parses the given address string
+/// Parses the given address string. See
+/// [`ethcontract_common::parse_address`] for details on the accepted format.
 pub fn parse_address<S>(address_str: S) -> Result<Address>
 where
     S: AsRef<str>,
 {
-    let address_str = address_str.as_ref();
-    if !address_str.starts_with("0x") {
-        return Err(anyhow!("address must start with '0x'"));
-    }
-    Ok(address_str[2..].parse()?)
+    Ok(ethcontract_common::parse_address(address_str.as_ref())?)
 }
 
 /// Performs an HTTP GET request and return the contents of the response.
@@ -0,0 +1,132 @@
+//! Catch-all expectations that match calls to any method of a contract.
+
+use crate::details::transaction::TransactionResult;
+use crate::sequence::CallCounter;
+use crate::CallContext;
+use ethcontract_core::H32;
+
+/// Expectation that is not tied to a specific method signature and
+/// operates on raw calldata and return data.
+pub struct AnyExpectation {
+    /// Call count limits and sequence of this expectation.
+    pub counter: CallCounter,
+
+    /// Indicates that this expectation has been checked against a call
+    /// at least once. Expectations shouldn't be changed after that happened.
+    pub checked: bool,
+
+    /// How many blocks should node skip for confirmation to be successful.
+    pub confirmations: u64,
+
+    /// Only consider calls to the method with this selector.
+    pub selector: Option<H32>,
+
+    /// Only consider this expectation if predicate returns `true`.
+    pub predicate: AnyPredicate,
+
+    /// Should this expectation match view calls?
+    pub allow_calls: bool,
+
+    /// Should this expectation match transactions?
+    pub allow_transactions: bool,
+
+    /// Function to generate raw return data.
+    pub returns: AnyReturns,
+
+    /// Gas consumed by calls matching this expectation.
+    pub gas: Option<u64>,
+}
+
+impl AnyExpectation {
+    pub fn new() -> Self {
+        AnyExpectation {
+            counter: CallCounter::new(),
+            checked: false,
+            confirmations: 0,
+            selector: None,
+            predicate: AnyPredicate::None,
+            allow_calls: true,
+            allow_transactions: true,
+            returns: AnyReturns::Default,
+            gas: None,
+        }
+    }
+
+    /// Checks if a call with the given raw calldata matches this expectation.
+    pub fn matches(&mut self, tx: &CallContext, data: &[u8]) -> bool {
+        self.checked = true;
+
+        if tx.is_view_call && !self.allow_calls || !tx.is_view_call && !self.allow_transactions {
+            return false;
+        }
+
+        if !self.counter.is_active() {
+            return false;
+        }
+
+        if matches!(self.selector, Some(selector) if data.get(..4) != Some(&selector[..])) {
+            return false;
+        }
+
+        self.predicate.can_call(tx, data)
+    }
+
+    /// Matches and processes a call with the given raw calldata.
+    pub fn process_tx(
+        &mut self,
+        tx: &CallContext,
+        description: &str,
+        data: &[u8],
+    ) -> Option<TransactionResult> {
+        if !self.matches(tx, data) {
+            return None;
+        }
+
+        self.counter.record_call(description);
+
+        Some(TransactionResult {
+            result: self.returns.process_tx(tx, data),
+            confirmations: self.confirmations,
+            logs: Vec::new(),
+            gas: self.gas,
+        })
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub enum AnyPredicate {
+    None,
+    Function(Box<dyn Fn(&[u8]) -> bool + Send>),
+    TxFunction(Box<dyn Fn(&CallContext, &[u8]) -> bool + Send>),
+}
+
+impl AnyPredicate {
+    fn can_call(&self, tx: &CallContext, data: &[u8]) -> bool {
+        match self {
+            AnyPredicate::None => true,
+            AnyPredicate::Function(f) => f(data),
+            AnyPredicate::TxFunction(f) => f(tx, data),
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub enum AnyReturns {
+    Default,
+    Error(String),
+    Const(Vec<u8>),
+    Function(Box<dyn Fn(&[u8]) -> Result<Vec<u8>, String> + Send>),
+    TxFunction(Box<dyn Fn(&CallContext, &[u8]) -> Result<Vec<u8>, String> + Send>),
+}
+
+impl AnyReturns {
+    fn process_tx(&self, tx: &CallContext, data: &[u8]) -> Result<Vec<u8>, String> {
+        match self {
+            AnyReturns::Default => Ok(Vec::new()),
+            AnyReturns::Error(error) => Err(error.clone()),
+            AnyReturns::Const(result) => Ok(result.clone()),
+            AnyReturns::Function(f) => f(data),
+            AnyReturns::TxFunction(f) => f(tx, data),
+        }
+    }
+}
@@ -1,7 +1,6 @@
 //! Helpers for building default values for tokens.
 
-use ethcontract::common::abi::{Bytes, Int, ParamType, Token, Uint};
-use ethcontract::Address;
+use ethcontract_core::abi::{Address, Bytes, Int, ParamType, Token, Uint};
 
 /// Builds a default value for the given solidity type.
 pub fn default(ty: &ParamType) -> Token {
@@ -0,0 +1,169 @@
+//! Helpers for emitting event logs and filtering them in `eth_getLogs`.
+
+use ethcontract_core::abi::{self, Event, RawLog, Token};
+use serde::Deserialize;
+use serde_json::{from_value, Value};
+use web3::signing::keccak256;
+use web3::types::{Address, Log, H256, U64};
+
+/// Encodes event parameters into log topics and data according to the
+/// event's ABI.
+pub fn encode_event(event: &Event, params: Token) -> RawLog {
+    let params = match params {
+        Token::Tuple(params) if params.len() == event.inputs.len() => params,
+        _ => panic!(
+            "event {:?} expects {} parameters",
+            event.name,
+            event.inputs.len()
+        ),
+    };
+
+    let mut topics = Vec::new();
+    if !event.anonymous {
+        topics.push(event.signature());
+    }
+
+    let mut data = Vec::new();
+    for (input, param) in event.inputs.iter().zip(params) {
+        assert!(
+            param.type_check(&input.kind),
+            "parameter {:?} of event {:?} should have type {}",
+            input.name,
+            event.name,
+            input.kind,
+        );
+
+        if input.indexed {
+            topics.push(encode_topic(param));
+        } else {
+            data.push(param);
+        }
+    }
+
+    RawLog {
+        topics,
+        data: abi::encode(&data),
+    }
+}
+
+/// Encodes an indexed event parameter into a log topic.
+///
+/// Value types are stored in topics directly, while dynamic types and
+/// composite types are hashed.
+fn encode_topic(token: Token) -> H256 {
+    match token {
+        Token::Bytes(bytes) => H256(keccak256(&bytes)),
+        Token::String(string) => H256(keccak256(string.as_bytes())),
+        Token::Array(tokens) | Token::FixedArray(tokens) | Token::Tuple(tokens) => {
+            let mut data = Vec::new();
+            for token in tokens {
+                encode_in_place(token, &mut data);
+            }
+            H256(keccak256(&data))
+        }
+        token => H256::from_slice(&abi::encode(&[token])),
+    }
+}
+
+/// Encodes a member of an indexed array or tuple. Members are encoded
+/// in-place, without length prefixes and offsets, and padded to 32 bytes.
+fn encode_in_place(token: Token, data: &mut Vec<u8>) {
+    match token {
+        Token::Bytes(bytes) => pad(&bytes, data),
+        Token::String(string) => pad(string.as_bytes(), data),
+        Token::Array(tokens) | Token::FixedArray(tokens) | Token::Tuple(tokens) => {
+            for token in tokens {
+                encode_in_place(token, data);
+            }
+        }
+        token => data.extend(abi::encode(&[token])),
+    }
+}
+
+fn pad(bytes: &[u8], data: &mut Vec<u8>) {
+    data.extend_from_slice(bytes);
+    data.resize(data.len() + (32 - bytes.len() % 32) % 32, 0);
+}
+
+/// A filter for `eth_getLogs` queries.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogFilter {
+    #[serde(default)]
+    from_block: Option<Value>,
+    #[serde(default)]
+    to_block: Option<Value>,
+    #[serde(default)]
+    block_hash: Option<H256>,
+    #[serde(default)]
+    address: Option<ValueOrArray<Address>>,
+    #[serde(default)]
+    topics: Option<Vec<Option<ValueOrArray<H256>>>>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ValueOrArray<T> {
+    Value(T),
+    Array(Vec<T>),
+}
+
+impl<T: PartialEq> ValueOrArray<T> {
+    fn contains(&self, value: &T) -> bool {
+        match self {
+            ValueOrArray::Value(expected) => expected == value,
+            ValueOrArray::Array(expected) => expected.is_empty() || expected.contains(value),
+        }
+    }
+}
+
+impl LogFilter {
+    /// Checks if a log matches this filter, given the current block.
+    pub fn matches(&self, log: &Log, block: u64) -> bool {
+        let log_block = log.block_number.unwrap_or_default().as_u64();
+
+        let in_range = match self.block_hash {
+            Some(hash) => log.block_hash == Some(hash),
+            None => {
+                let from = resolve_block(self.from_block.as_ref(), block);
+                let to = resolve_block(self.to_block.as_ref(), block);
+                from <= log_block && log_block <= to
+            }
+        };
+        if !in_range {
+            return false;
+        }
+
+        if let Some(address) = &self.address {
+            if !address.contains(&log.address) {
+                return false;
+            }
+        }
+
+        if let Some(topics) = &self.topics {
+            for (i, topic) in topics.iter().enumerate() {
+                if let Some(topic) = topic {
+                    match log.topics.get(i) {
+                        Some(log_topic) if topic.contains(log_topic) => (),
+                        _ => return false,
+                    }
+                }
+            }
+        }
+
+        true
+    }
+}
+
+/// Resolves a block number or tag in a log filter, missing blocks default
+/// to the latest block.
+fn resolve_block(value: Option<&Value>, latest: u64) -> u64 {
+    match value.map(|value| (value, value.as_str())) {
+        None => latest,
+        Some((_, Some("earliest"))) => 0,
+        Some((_, Some("latest" | "pending" | "safe" | "finalized"))) => latest,
+        Some((value, _)) => from_value::<U64>(value.clone())
+            .unwrap_or_else(|err| panic!("invalid block number in log filter: {}", err))
+            .as_u64(),
+    }
+}
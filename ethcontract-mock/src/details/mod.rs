@@ -1,30 +1,36 @@
 //! Implementation details of mock node.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::future::ready;
-use std::sync::{Arc, Mutex};
-
-use ethcontract::common::abi::{Function, StateMutability, Token};
-use ethcontract::common::hash::H32;
-use ethcontract::common::{Abi, FunctionExt};
-use ethcontract::jsonrpc::serde::Serialize;
-use ethcontract::jsonrpc::serde_json::to_value;
-use ethcontract::jsonrpc::{Call, MethodCall, Params, Value};
-use ethcontract::tokens::Tokenize;
-use ethcontract::web3::types::{
-    Bytes, CallRequest, TransactionReceipt, TransactionRequest, U256, U64,
-};
-use ethcontract::web3::{helpers, BatchTransport, Error, RequestId, Transport};
-use ethcontract::{Address, BlockNumber, H160, H256};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use ethcontract_core::abi::{Contract as Abi, Event, Function, RawLog, StateMutability, Token};
+use ethcontract_core::tokens::Tokenize;
+use ethcontract_core::H32;
+use jsonrpc_core::{Call, ErrorCode, MethodCall, Params, Value};
+use logs::{encode_event, LogFilter};
 use parse::Parser;
-use sign::verify;
+use serde::Serialize;
+use serde_json::to_value;
+use sign::{sign, verify};
+use web3::signing::{Key, SecretKey, SecretKeyRef};
+use web3::types::{
+    Address, BlockNumber, Bytes, CallRequest, FeeHistory, Log, TransactionReceipt,
+    TransactionRequest, H160, H256, U256, U64,
+};
+use web3::{helpers, BatchTransport, Error, RequestId, Transport};
 
-use crate::details::transaction::TransactionResult;
+use crate::details::transaction::{Transaction, TransactionResult};
 use crate::range::TimesRange;
-use crate::CallContext;
+use crate::sequence::CallCounter;
+use crate::{CallContext, UnexpectedCall};
+pub use any::{AnyExpectation, AnyPredicate, AnyReturns};
 use std::any::Any;
 
+mod any;
 mod default;
+mod logs;
 mod parse;
 mod sign;
 mod transaction;
@@ -44,6 +50,13 @@ struct MockTransportState {
     /// Current gas price.
     gas_price: u64,
 
+    /// Current max priority fee per gas.
+    max_priority_fee_per_gas: u64,
+
+    /// Base fee and priority fee per gas of the most recent blocks,
+    /// oldest first.
+    fee_history: Vec<(u64, u64)>,
+
     /// This counter is used to keep track of prepared calls.
     request_id: RequestId,
 
@@ -56,13 +69,43 @@ struct MockTransportState {
     /// Nonce for account.
     nonce: HashMap<Address, u64>,
 
+    /// Balance for account.
+    balances: HashMap<Address, U256>,
+
+    /// Accounts whose balance was set explicitly, and for which transaction
+    /// values are checked against the balance.
+    funded: HashSet<Address>,
+
+    /// Accounts that mock node can sign transactions for,
+    /// in order of registration.
+    accounts: Vec<(Address, SecretKey)>,
+
     /// Deployed mocked contracts.
     contracts: HashMap<Address, Contract>,
 
+    /// Addresses of mocked contracts that expect to be deployed via
+    /// a contract creation transaction, in order of deployment.
+    deployments: VecDeque<Address>,
+
     /// Receipts for already performed transactions.
     receipts: HashMap<H256, TransactionReceipt>,
+
+    /// Logs emitted by already performed transactions.
+    logs: Vec<Log>,
+
+    /// If `true`, violations are recorded and returned as RPC errors
+    /// instead of panicking.
+    panic_free: bool,
+
+    /// Violations recorded in panic-free mode.
+    violations: Vec<String>,
+
+    /// Handler for calls that don't match any expectation.
+    on_unexpected: Option<UnexpectedCallHandler>,
 }
 
+type UnexpectedCallHandler = Arc<dyn Fn(&UnexpectedCall) + Send + Sync>;
+
 #[allow(clippy::type_complexity)]
 impl MockTransport {
     /// Creates a new transport.
@@ -71,19 +114,37 @@ impl MockTransport {
             state: Arc::new(Mutex::new(MockTransportState {
                 chain_id,
                 gas_price: 1,
+                max_priority_fee_per_gas: 1,
+                fee_history: Vec::new(),
                 request_id: 0,
                 block: 0,
                 address: 0,
                 nonce: HashMap::new(),
+                balances: HashMap::new(),
+                funded: HashSet::new(),
+                accounts: Vec::new(),
                 contracts: HashMap::new(),
+                deployments: VecDeque::new(),
                 receipts: HashMap::new(),
+                logs: Vec::new(),
+                panic_free: false,
+                violations: Vec::new(),
+                on_unexpected: None,
             })),
         }
     }
 
+    /// Locks transport state.
+    ///
+    /// In panic-free mode, the lock can be poisoned by a violation caught
+    /// while processing an RPC call. The state is still usable in this case.
+    fn state(&self) -> MutexGuard<'_, MockTransportState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
     /// Deploys a new contract with the given ABI.
     pub fn deploy(&self, abi: &Abi) -> Address {
-        let mut state = self.state.lock().unwrap();
+        let mut state = self.state();
 
         state.address += 1;
         let address = H160::from_low_u64_be(state.address);
@@ -93,9 +154,17 @@ impl MockTransport {
         address
     }
 
+    /// Registers a new contract with the given ABI that expects to be
+    /// deployed via a contract creation transaction.
+    pub fn expect_deployment(&self, abi: &Abi) -> Address {
+        let address = self.deploy(abi);
+        self.state().deployments.push_back(address);
+        address
+    }
+
     /// Deploys a new contract with the given ABI and address
     pub fn deploy_with_address(&self, abi: &Abi, address: Address) {
-        let mut state = self.state.lock().unwrap();
+        let mut state = self.state();
 
         assert!(
             state
@@ -107,16 +176,71 @@ impl MockTransport {
         );
     }
 
+    /// Registers an account that mock node can sign transactions for.
+    pub fn add_account(&self, key: SecretKey) -> Address {
+        let mut state = self.state();
+
+        let address = SecretKeyRef::new(&key).address();
+        if !state
+            .accounts
+            .iter()
+            .any(|(account, _)| *account == address)
+        {
+            state.accounts.push((address, key));
+        }
+
+        address
+    }
+
+    pub fn set_code(&self, address: Address, code: Vec<u8>) {
+        let mut state = self.state();
+        state.contract(address).code = code;
+    }
+
+    pub fn set_balance(&self, address: Address, balance: U256) {
+        let mut state = self.state();
+        state.balances.insert(address, balance);
+        state.funded.insert(address);
+    }
+
     pub fn update_gas_price(&self, gas_price: u64) {
-        let mut state = self.state.lock().unwrap();
+        let mut state = self.state();
         state.gas_price = gas_price;
     }
 
+    pub fn update_max_priority_fee_per_gas(&self, max_priority_fee_per_gas: u64) {
+        let mut state = self.state();
+        state.max_priority_fee_per_gas = max_priority_fee_per_gas;
+    }
+
+    pub fn set_fee_history(&self, fee_history: Vec<(u64, u64)>) {
+        let mut state = self.state();
+        state.fee_history = fee_history;
+    }
+
+    pub fn set_panic_free(&self, panic_free: bool) {
+        let mut state = self.state();
+        state.panic_free = panic_free;
+    }
+
+    pub fn on_unexpected(&self, handler: impl Fn(&UnexpectedCall) + Send + Sync + 'static) {
+        let mut state = self.state();
+        state.on_unexpected = Some(Arc::new(handler));
+    }
+
     pub fn checkpoint(&self) {
-        let mut state = self.state.lock().unwrap();
-        let contracts = state.contracts.values_mut();
-        for contract in contracts {
-            contract.checkpoint();
+        let mut state = self.state();
+        let violations = state.checkpoint();
+        state.report(violations);
+    }
+
+    pub fn verify(&self) {
+        let mut state = self.state();
+        let mut violations = std::mem::take(&mut state.violations);
+        violations.extend(state.checkpoint());
+        if let Some(report) = violations_report(&violations) {
+            drop(state);
+            panic!("{}", report);
         }
     }
 
@@ -125,15 +249,31 @@ impl MockTransport {
         address: Address,
         signature: H32,
     ) -> (usize, usize) {
-        let mut state = self.state.lock().unwrap();
+        let mut state = self.state();
         let method = state.method(address, signature);
         method.expect::<P, R>()
     }
 
+    pub fn expect_any(&self, address: Address) -> (usize, usize) {
+        let mut state = self.state();
+        state.contract(address).expect_any()
+    }
+
+    pub fn any_expectation(
+        &self,
+        address: Address,
+        index: usize,
+        generation: usize,
+        update: impl FnOnce(&mut AnyExpectation),
+    ) {
+        let mut state = self.state();
+        update(state.contract(address).any_expectation(index, generation));
+    }
+
     pub fn contract_checkpoint(&self, address: Address) {
-        let mut state = self.state.lock().unwrap();
-        let contract = state.contract(address);
-        contract.checkpoint();
+        let mut state = self.state();
+        let violations = state.contract(address).checkpoint();
+        state.report(violations);
     }
 
     pub fn times<P: Tokenize + Send + 'static, R: Tokenize + Send + 'static>(
@@ -144,17 +284,10 @@ impl MockTransport {
         generation: usize,
         times: TimesRange,
     ) {
-        let mut state = self.state.lock().unwrap();
+        let mut state = self.state();
         let expectation = state.expectation::<P, R>(address, signature, index, generation);
 
-        if expectation.sequence.is_some() && !times.is_exact() {
-            panic!("only expectations with an exact call count can be in a sequences")
-        }
-        if expectation.sequence.is_some() && times.lower_bound() == 0 {
-            panic!("expectation in a sequences should be called at least once")
-        }
-
-        expectation.times = times;
+        expectation.counter.set_times(times);
     }
 
     pub fn in_sequence<P: Tokenize + Send + 'static, R: Tokenize + Send + 'static>(
@@ -165,20 +298,10 @@ impl MockTransport {
         generation: usize,
         sequence: &mut mockall::Sequence,
     ) {
-        let mut state = self.state.lock().unwrap();
+        let mut state = self.state();
         let expectation = state.expectation::<P, R>(address, signature, index, generation);
 
-        if !expectation.times.is_exact() {
-            panic!("only expectations with an exact call count can be in a sequences")
-        }
-        if expectation.times.lower_bound() == 0 {
-            panic!("expectation in a sequences should be called at least once")
-        }
-        if expectation.sequence.is_some() {
-            panic!("expectation can't be in multiple sequences")
-        }
-
-        expectation.sequence = Some(sequence.next_handle());
+        expectation.counter.set_sequence(sequence);
     }
 
     pub fn confirmations<P: Tokenize + Send + 'static, R: Tokenize + Send + 'static>(
@@ -189,11 +312,51 @@ impl MockTransport {
         generation: usize,
         confirmations: u64,
     ) {
-        let mut state = self.state.lock().unwrap();
+        let mut state = self.state();
         let expectation = state.expectation::<P, R>(address, signature, index, generation);
         expectation.confirmations = confirmations;
     }
 
+    pub fn consumes_gas<P: Tokenize + Send + 'static, R: Tokenize + Send + 'static>(
+        &self,
+        address: Address,
+        signature: H32,
+        index: usize,
+        generation: usize,
+        gas: u64,
+    ) {
+        let mut state = self.state();
+        let expectation = state.expectation::<P, R>(address, signature, index, generation);
+        expectation.gas = Some(gas);
+    }
+
+    pub fn valid_until_block<P: Tokenize + Send + 'static, R: Tokenize + Send + 'static>(
+        &self,
+        address: Address,
+        signature: H32,
+        index: usize,
+        generation: usize,
+        block: u64,
+    ) {
+        let mut state = self.state();
+        let expectation = state.expectation::<P, R>(address, signature, index, generation);
+        expectation.valid_until = Some(block);
+    }
+
+    pub fn valid_for_blocks<P: Tokenize + Send + 'static, R: Tokenize + Send + 'static>(
+        &self,
+        address: Address,
+        signature: H32,
+        index: usize,
+        generation: usize,
+        blocks: u64,
+    ) {
+        let mut state = self.state();
+        let block = state.block.saturating_add(blocks);
+        let expectation = state.expectation::<P, R>(address, signature, index, generation);
+        expectation.valid_until = Some(block);
+    }
+
     pub fn predicate<P: Tokenize + Send + 'static, R: Tokenize + Send + 'static>(
         &self,
         address: Address,
@@ -202,7 +365,7 @@ impl MockTransport {
         generation: usize,
         pred: Box<dyn predicates::Predicate<P> + Send>,
     ) {
-        let mut state = self.state.lock().unwrap();
+        let mut state = self.state();
         let expectation = state.expectation::<P, R>(address, signature, index, generation);
         expectation.predicate = Predicate::Predicate(pred);
     }
@@ -215,7 +378,7 @@ impl MockTransport {
         generation: usize,
         pred: Box<dyn Fn(&P) -> bool + Send>,
     ) {
-        let mut state = self.state.lock().unwrap();
+        let mut state = self.state();
         let expectation = state.expectation::<P, R>(address, signature, index, generation);
         expectation.predicate = Predicate::Function(pred);
     }
@@ -228,7 +391,7 @@ impl MockTransport {
         generation: usize,
         pred: Box<dyn Fn(&CallContext, &P) -> bool + Send>,
     ) {
-        let mut state = self.state.lock().unwrap();
+        let mut state = self.state();
         let expectation = state.expectation::<P, R>(address, signature, index, generation);
         expectation.predicate = Predicate::TxFunction(pred);
     }
@@ -241,7 +404,7 @@ impl MockTransport {
         generation: usize,
         allow_calls: bool,
     ) {
-        let mut state = self.state.lock().unwrap();
+        let mut state = self.state();
         let expectation = state.expectation::<P, R>(address, signature, index, generation);
         expectation.allow_calls = allow_calls;
     }
@@ -254,7 +417,7 @@ impl MockTransport {
         generation: usize,
         allow_transactions: bool,
     ) {
-        let mut state = self.state.lock().unwrap();
+        let mut state = self.state();
         let expectation = state.expectation::<P, R>(address, signature, index, generation);
         expectation.allow_transactions = allow_transactions;
     }
@@ -270,7 +433,7 @@ impl MockTransport {
         // Convert `R` into `Token` here because `Token` is `Clone` while `R` is not.
         // We need to clone result const if method is called multiple times.
         let token = returns.into_token();
-        let mut state = self.state.lock().unwrap();
+        let mut state = self.state();
         let expectation = state.expectation::<P, R>(address, signature, index, generation);
         expectation.returns = Returns::Const(token);
     }
@@ -283,7 +446,7 @@ impl MockTransport {
         generation: usize,
         returns: Box<dyn Fn(P) -> Result<R, String> + Send>,
     ) {
-        let mut state = self.state.lock().unwrap();
+        let mut state = self.state();
         let expectation = state.expectation::<P, R>(address, signature, index, generation);
         expectation.returns = Returns::Function(returns);
     }
@@ -296,7 +459,7 @@ impl MockTransport {
         generation: usize,
         returns: Box<dyn Fn(&CallContext, P) -> Result<R, String> + Send>,
     ) {
-        let mut state = self.state.lock().unwrap();
+        let mut state = self.state();
         let expectation = state.expectation::<P, R>(address, signature, index, generation);
         expectation.returns = Returns::TxFunction(returns);
     }
@@ -309,7 +472,7 @@ impl MockTransport {
         generation: usize,
         error: String,
     ) {
-        let mut state = self.state.lock().unwrap();
+        let mut state = self.state();
         let expectation = state.expectation::<P, R>(address, signature, index, generation);
         expectation.returns = Returns::Error(error);
     }
@@ -323,13 +486,64 @@ impl MockTransport {
     ) {
         // Convert `R` into `Token` here because `Token` is `Clone` while `R` is not.
         // We need to clone result const if method is called multiple times.
-        let mut state = self.state.lock().unwrap();
+        let mut state = self.state();
         let expectation = state.expectation::<P, R>(address, signature, index, generation);
         expectation.returns = Returns::Default;
     }
+
+    pub fn emits_raw_log<P: Tokenize + Send + 'static, R: Tokenize + Send + 'static>(
+        &self,
+        address: Address,
+        signature: H32,
+        index: usize,
+        generation: usize,
+        log: RawLog,
+    ) {
+        let mut state = self.state();
+        let expectation = state.expectation::<P, R>(address, signature, index, generation);
+        expectation.logs.push(log);
+    }
+
+    pub fn emits_event<P: Tokenize + Send + 'static, R: Tokenize + Send + 'static>(
+        &self,
+        address: Address,
+        signature: H32,
+        index: usize,
+        generation: usize,
+        name: &str,
+        params: Token,
+    ) {
+        let mut state = self.state();
+        let log = encode_event(state.contract(address).event(name), params);
+        let expectation = state.expectation::<P, R>(address, signature, index, generation);
+        expectation.logs.push(log);
+    }
 }
 
 impl MockTransportState {
+    /// Verifies and clears expectations on all contracts, returns
+    /// descriptions of unsatisfied expectations.
+    fn checkpoint(&mut self) -> Vec<String> {
+        let deployments = self.deployments.drain(..).map(|address| {
+            format!(
+                "expected deployment of mocked contract {:#x} was never performed",
+                address
+            )
+        });
+        let expectations = self.contracts.values_mut().flat_map(Contract::checkpoint);
+
+        deployments.chain(expectations).collect()
+    }
+
+    /// Records violations in panic-free mode, panics otherwise.
+    fn report(&mut self, violations: Vec<String>) {
+        if self.panic_free {
+            self.violations.extend(violations);
+        } else if let Some(violation) = violations.into_iter().next() {
+            panic!("{}", violation);
+        }
+    }
+
     /// Returns contract at the given address, panics if contract does not exist.
     fn contract(&mut self, address: Address) -> &mut Contract {
         match self.contracts.get_mut(&address) {
@@ -365,7 +579,7 @@ impl Transport for MockTransport {
     /// We don't have to deal with network issues, so we are relaxed about
     /// request IDs, idempotency checks and so on.
     fn prepare(&self, method: &str, params: Vec<Value>) -> (RequestId, Call) {
-        let mut state = self.state.lock().unwrap();
+        let mut state = self.state();
 
         let id = state.request_id;
         state.request_id += 1;
@@ -399,6 +613,33 @@ impl BatchTransport for MockTransport {
 
 impl MockTransport {
     fn process_call(&self, request: Call) -> Result<Value, Error> {
+        if !self.state().panic_free {
+            return self.dispatch_call(request);
+        }
+
+        match panic::catch_unwind(AssertUnwindSafe(|| self.dispatch_call(request))) {
+            Ok(result) => result,
+            Err(payload) => {
+                let message = match payload.downcast::<String>() {
+                    Ok(message) => *message,
+                    Err(payload) => match payload.downcast::<&str>() {
+                        Ok(message) => message.to_string(),
+                        Err(_) => "mock node panicked".to_string(),
+                    },
+                };
+
+                self.state().violations.push(message.clone());
+
+                Err(Error::Rpc(jsonrpc_core::Error {
+                    code: ErrorCode::InternalError,
+                    message,
+                    data: None,
+                }))
+            }
+        }
+    }
+
+    fn dispatch_call(&self, request: Call) -> Result<Value, Error> {
         let MethodCall { method, params, .. } = match request {
             Call::MethodCall(method_call) => method_call,
             Call::Notification(_) => panic!("rpc notifications are not supported"),
@@ -412,6 +653,10 @@ impl MockTransport {
         };
 
         let result = match method.as_str() {
+            "eth_accounts" => {
+                let name = "eth_accounts";
+                self.eth_accounts(Parser::new(name, params))
+            }
             "eth_blockNumber" => {
                 let name = "eth_blockNumber";
                 self.eth_block_number(Parser::new(name, params))
@@ -424,10 +669,26 @@ impl MockTransport {
                 let name = "eth_getTransactionCount";
                 self.eth_transaction_count(Parser::new(name, params))
             }
+            "eth_getBalance" => {
+                let name = "eth_getBalance";
+                self.eth_get_balance(Parser::new(name, params))
+            }
+            "eth_getCode" => {
+                let name = "eth_getCode";
+                self.eth_get_code(Parser::new(name, params))
+            }
             "eth_gasPrice" => {
                 let name = "eth_gasPrice";
                 self.eth_gas_price(Parser::new(name, params))
             }
+            "eth_maxPriorityFeePerGas" => {
+                let name = "eth_maxPriorityFeePerGas";
+                self.eth_max_priority_fee_per_gas(Parser::new(name, params))
+            }
+            "eth_feeHistory" => {
+                let name = "eth_feeHistory";
+                self.eth_fee_history(Parser::new(name, params))
+            }
             "eth_estimateGas" => {
                 let name = "eth_estimateGas";
                 self.eth_estimate_gas(Parser::new(name, params))
@@ -448,23 +709,40 @@ impl MockTransport {
                 let name = "eth_getTransactionReceipt";
                 self.eth_get_transaction_receipt(Parser::new(name, params))
             }
+            "eth_getLogs" => {
+                let name = "eth_getLogs";
+                self.eth_get_logs(Parser::new(name, params))
+            }
             unsupported => panic!("mock node does not support rpc method {:?}", unsupported),
         };
 
         result
     }
 
+    fn eth_accounts(&self, args: Parser) -> Result<Value, Error> {
+        args.done();
+
+        let state = self.state();
+        Self::ok(
+            state
+                .accounts
+                .iter()
+                .map(|(address, _)| *address)
+                .collect::<Vec<_>>(),
+        )
+    }
+
     fn eth_block_number(&self, args: Parser) -> Result<Value, Error> {
         args.done();
 
-        let state = self.state.lock().unwrap();
+        let state = self.state();
         Self::ok(U64::from(state.block))
     }
 
     fn eth_chain_id(&self, args: Parser) -> Result<Value, Error> {
         args.done();
 
-        let state = self.state.lock().unwrap();
+        let state = self.state();
         Self::ok(U256::from(state.chain_id))
     }
 
@@ -474,7 +752,7 @@ impl MockTransport {
         args.done();
 
         let block = block.unwrap_or(BlockNumber::Pending);
-        let state = self.state.lock().unwrap();
+        let state = self.state();
         let transaction_count = match block {
             BlockNumber::Earliest => 0,
             BlockNumber::Number(n) if n == 0.into() => 0,
@@ -486,19 +764,107 @@ impl MockTransport {
         Self::ok(U256::from(transaction_count))
     }
 
+    fn eth_get_balance(&self, mut args: Parser) -> Result<Value, Error> {
+        let address: Address = args.arg();
+        let block: Option<BlockNumber> = args.block_number_opt();
+        args.done();
+
+        let block = block.unwrap_or(BlockNumber::Latest);
+        let state = self.state();
+        match block {
+            BlockNumber::Number(n) if n == state.block.into() => (),
+            BlockNumber::Latest | BlockNumber::Pending => (),
+            _ => panic!("mock node does not support returning balance for specific block number"),
+        }
+        Self::ok(state.balances.get(&address).copied().unwrap_or_default())
+    }
+
+    fn eth_get_code(&self, mut args: Parser) -> Result<Value, Error> {
+        let address: Address = args.arg();
+        let block: Option<BlockNumber> = args.block_number_opt();
+        args.done();
+
+        let block = block.unwrap_or(BlockNumber::Latest);
+        let state = self.state();
+        match block {
+            BlockNumber::Number(n) if n == state.block.into() => (),
+            BlockNumber::Latest | BlockNumber::Pending => (),
+            _ => panic!("mock node does not support returning code for specific block number"),
+        }
+
+        // Contracts that are still waiting for their deployment
+        // transaction don't exist on chain yet.
+        let code = match state.contracts.get(&address) {
+            Some(contract) if !state.deployments.contains(&address) => contract.code.clone(),
+            _ => Vec::new(),
+        };
+        Self::ok(Bytes(code))
+    }
+
     fn eth_gas_price(&self, args: Parser) -> Result<Value, Error> {
         args.done();
 
-        let state = self.state.lock().unwrap();
+        let state = self.state();
         Self::ok(U256::from(state.gas_price))
     }
 
+    fn eth_max_priority_fee_per_gas(&self, args: Parser) -> Result<Value, Error> {
+        args.done();
+
+        let state = self.state();
+        Self::ok(U256::from(state.max_priority_fee_per_gas))
+    }
+
+    fn eth_fee_history(&self, mut args: Parser) -> Result<Value, Error> {
+        let block_count: U256 = args.arg();
+        let newest_block: Option<BlockNumber> = args.block_number_opt();
+        let reward_percentiles: Option<Vec<f64>> = args.arg_opt().flatten();
+        args.done();
+
+        let state = self.state();
+        let newest_block = match newest_block {
+            Some(BlockNumber::Number(number)) => number.as_u64().min(state.block),
+            Some(BlockNumber::Earliest) => 0,
+            _ => state.block,
+        };
+
+        // By default, all blocks have a base fee equal to the gas price and
+        // no priority fee. Blocks older than the configured history repeat
+        // its oldest entry.
+        let fee_history = if state.fee_history.is_empty() {
+            vec![(state.gas_price, 0)]
+        } else {
+            state.fee_history.clone()
+        };
+        let block_count = block_count.as_u64().min(newest_block + 1) as usize;
+        let fees = (0..block_count)
+            .rev()
+            .map(|age| fee_history[fee_history.len().saturating_sub(age + 1)])
+            .collect::<Vec<_>>();
+        let next_base_fee = fee_history[fee_history.len() - 1].0;
+
+        Self::ok(FeeHistory {
+            oldest_block: BlockNumber::Number((newest_block + 1 - block_count as u64).into()),
+            base_fee_per_gas: fees
+                .iter()
+                .map(|(base_fee, _)| U256::from(*base_fee))
+                .chain(std::iter::once(U256::from(next_base_fee)))
+                .collect(),
+            gas_used_ratio: vec![0.5; block_count],
+            reward: reward_percentiles.map(|percentiles| {
+                fees.iter()
+                    .map(|(_, priority_fee)| vec![U256::from(*priority_fee); percentiles.len()])
+                    .collect()
+            }),
+        })
+    }
+
     fn eth_estimate_gas(&self, mut args: Parser) -> Result<Value, Error> {
         let request: CallRequest = args.arg();
         let block: Option<BlockNumber> = args.block_number_opt();
         args.done();
 
-        let state = self.state.lock().unwrap();
+        let mut state = self.state();
 
         let block = block.unwrap_or(BlockNumber::Pending);
         match block {
@@ -511,50 +877,55 @@ impl MockTransport {
             _ => (),
         }
 
-        match request.to {
-            None => panic!("call's 'to' field is empty"),
-            Some(to) => to,
-        };
+        // We check expectations as if we're executing a transaction,
+        // but we don't mark any expectation as fulfilled. Transactions that
+        // don't match an expectation which specifies gas consumption,
+        // including contract deployments, are estimated to require 1 gas.
 
-        // TODO:
-        //
-        // We could look up contract's method, match an expectation,
-        // and see if the expectation defines gas price.
-        //
-        // So, for example, this code:
-        //
-        // ```
-        // contract
-        //     .expect_method(signature)
-        //     .with(matcher)
-        //     .gas(100);
-        // ```
-        //
-        // Indicates that call to the method with the given signature
-        // requires 100 gas.
-        //
-        // When estimating gas, we'll check all expectation as if we're
-        // executing a method, but we won't mark any expectation as fulfilled.
+        let from = request.from.unwrap_or_default();
+        let nonce = state.nonce.get(&from).copied().unwrap_or(0);
+        let gas_price = state.gas_price;
+        let block = state.block + 1;
+
+        let gas = match request.to {
+            Some(to) => {
+                let context = CallContext {
+                    is_view_call: false,
+                    block,
+                    from,
+                    to,
+                    nonce: U256::from(nonce),
+                    gas: request.gas.unwrap_or_else(|| U256::from(1)),
+                    gas_price: request.gas_price.unwrap_or_else(|| U256::from(gas_price)),
+                    value: request.value.unwrap_or_default(),
+                };
+                let data = request.data.unwrap_or_default();
+
+                state
+                    .contracts
+                    .get_mut(&to)
+                    .and_then(|contract| contract.estimate_gas(context, &data.0))
+            }
+            None => None,
+        };
 
-        Self::ok(U256::from(1))
+        Self::ok(U256::from(gas.unwrap_or(1)))
     }
 
     fn eth_call(&self, mut args: Parser) -> Result<Value, Error> {
         let request: CallRequest = args.arg();
         let block: Option<BlockNumber> = args.block_number_opt();
 
-        let mut state = self.state.lock().unwrap();
+        let mut state = self.state();
 
-        let block = block.unwrap_or(BlockNumber::Pending);
-        match block {
-            BlockNumber::Earliest => {
-                panic!("mock node does not support executing methods on earliest block");
-            }
-            BlockNumber::Number(n) if n != state.block.into() => {
-                panic!("mock node does not support executing methods on non-last block");
+        let block = match block.unwrap_or(BlockNumber::Pending) {
+            BlockNumber::Earliest => 0,
+            BlockNumber::Number(n) if n.as_u64() > state.block => {
+                panic!("mock node does not support executing methods on future blocks");
             }
-            _ => (),
-        }
+            BlockNumber::Number(n) => n.as_u64(),
+            _ => state.block,
+        };
 
         let from = request.from.unwrap_or_default();
         let to = match request.to {
@@ -570,6 +941,7 @@ impl MockTransport {
 
         let context = CallContext {
             is_view_call: true,
+            block,
             from: request.from.unwrap_or_default(),
             to,
             nonce: U256::from(nonce),
@@ -580,12 +952,15 @@ impl MockTransport {
 
         let data = request.data.unwrap_or_default();
 
-        let result = contract.process_tx(context, &data.0);
+        let result = match contract.process_tx(context, &data.0) {
+            Ok(result) => result,
+            Err(call) => Self::unexpected_call(state, call),
+        };
 
         match result.result {
             Ok(data) => Self::ok(Bytes(data)),
-            Err(err) => Err(Error::Rpc(ethcontract::jsonrpc::Error {
-                code: ethcontract::jsonrpc::ErrorCode::ServerError(0),
+            Err(err) => Err(Error::Rpc(jsonrpc_core::Error {
+                code: ErrorCode::ServerError(0),
                 message: format!("execution reverted: {}", err),
                 data: None,
             })),
@@ -593,62 +968,171 @@ impl MockTransport {
     }
 
     fn eth_send_transaction(&self, mut args: Parser) -> Result<Value, Error> {
-        let _request: TransactionRequest = args.arg();
+        let request: TransactionRequest = args.arg();
         args.done();
 
-        // TODO:
-        //
-        // We could support signing if user adds accounts with their private
-        // keys during mock setup.
+        let state = self.state();
 
-        panic!("mock node can't sign transactions, use offline signing with private key");
+        let key = match state
+            .accounts
+            .iter()
+            .find(|(address, _)| *address == request.from)
+        {
+            Some((_, key)) => key,
+            None => panic!(
+                "mock node can't sign transactions for account {:#x}, \
+                 use `Mock::add_account` to register its private key \
+                 or use offline signing",
+                request.from
+            ),
+        };
+
+        let nonce = state.nonce.get(&request.from).copied().unwrap_or(0);
+        let raw_tx = sign(
+            request.nonce.unwrap_or_else(|| nonce.into()),
+            request
+                .gas_price
+                .or(request.max_fee_per_gas)
+                .unwrap_or_else(|| state.gas_price.into()),
+            request.gas.unwrap_or_else(|| U256::from(1)),
+            request.to,
+            request.value.unwrap_or_default(),
+            request.data.unwrap_or_default().0,
+            key,
+            state.chain_id,
+        );
+        let tx = verify(&raw_tx, state.chain_id);
+
+        Self::send_transaction(state, tx)
     }
 
     fn eth_send_raw_transaction(&self, mut args: Parser) -> Result<Value, Error> {
         let raw_tx: Bytes = args.arg();
         args.done();
 
-        let mut state = self.state.lock().unwrap();
+        let state = self.state();
 
         let tx = verify(&raw_tx.0, state.chain_id);
 
-        let nonce = state.nonce.entry(tx.from).or_insert(0);
+        Self::send_transaction(state, tx)
+    }
+
+    /// Executes a verified transaction.
+    fn send_transaction(
+        mut state: MutexGuard<MockTransportState>,
+        tx: Transaction,
+    ) -> Result<Value, Error> {
+        let nonce = state.nonce.get(&tx.from).copied().unwrap_or_default();
         assert!(
-            *nonce == tx.nonce.as_u64(),
+            nonce == tx.nonce.as_u64(),
             "nonce mismatch for account {:#x}: expected {}, actual {}",
             tx.from,
             tx.nonce.as_u64(),
             nonce
         );
-        *nonce += 1;
 
-        let contract = state.contract(tx.to);
+        // Like a real node, reject transactions that the sender can't pay
+        // for, but only for accounts that were funded explicitly.
+        let balance = state.balances.get(&tx.from).copied().unwrap_or_default();
+        if state.funded.contains(&tx.from) && balance < tx.value {
+            return Err(Error::Rpc(jsonrpc_core::Error {
+                code: ErrorCode::ServerError(0),
+                message: format!(
+                    "insufficient funds for account {:#x}: balance {}, value {}",
+                    tx.from, balance, tx.value
+                ),
+                data: None,
+            }));
+        }
 
-        let context = CallContext {
-            is_view_call: false,
-            from: tx.from,
-            to: tx.to,
-            nonce: tx.nonce,
-            gas: tx.gas,
-            gas_price: tx.gas_price,
-            value: tx.value,
+        *state.nonce.entry(tx.from).or_insert(0) += 1;
+
+        let block = state.block + 1;
+
+        let (result, contract_address) = match tx.to {
+            Some(to) => {
+                let contract = state.contract(to);
+
+                let context = CallContext {
+                    is_view_call: false,
+                    block,
+                    from: tx.from,
+                    to,
+                    nonce: tx.nonce,
+                    gas: tx.gas,
+                    gas_price: tx.gas_price,
+                    value: tx.value,
+                };
+
+                match contract.process_tx(context, &tx.data) {
+                    Ok(result) => (result, None),
+                    Err(call) => Self::unexpected_call(state, call),
+                }
+            }
+            None => {
+                let address = state.deployments.pop_front().unwrap_or_else(|| {
+                    panic!(
+                        "unexpected contract deployment from {:#x}, use `Mock::expect_deployment` to mock deployments",
+                        tx.from
+                    )
+                });
+                let result = TransactionResult {
+                    result: Ok(Vec::new()),
+                    confirmations: 0,
+                    logs: Vec::new(),
+                    gas: None,
+                };
+
+                (result, Some(address))
+            }
         };
 
-        let result = contract.process_tx(context, &tx.data);
+        // Gas fees are charged up to the available balance, since mock node
+        // does not require accounts to be funded to send transactions.
+        let fee = U256::from(result.gas.unwrap_or(1)).saturating_mul(tx.gas_price);
+        let mut debit = fee;
+        let recipient = contract_address.or(tx.to);
+        if let (Ok(_), Some(recipient)) = (&result.result, recipient) {
+            debit = debit.saturating_add(tx.value);
+            *state.balances.entry(recipient).or_default() += tx.value;
+        }
+        let balance = state.balances.entry(tx.from).or_default();
+        *balance = balance.saturating_sub(debit);
 
         state.block += 1;
 
+        let block_hash = block_hash(state.block);
+        let logs = result
+            .logs
+            .into_iter()
+            .enumerate()
+            .map(|(index, log)| Log {
+                address: tx.to.unwrap_or_default(),
+                topics: log.topics,
+                data: Bytes(log.data),
+                block_hash: Some(block_hash),
+                block_number: Some(U64::from(state.block)),
+                transaction_hash: Some(tx.hash),
+                transaction_index: Some(U64::from(0)),
+                log_index: Some(U256::from(index)),
+                transaction_log_index: Some(U256::from(index)),
+                log_type: None,
+                removed: Some(false),
+            })
+            .collect::<Vec<_>>();
+        state.logs.extend(logs.iter().cloned());
+
         let receipt = TransactionReceipt {
             transaction_hash: tx.hash,
             transaction_index: U64::from(0),
-            block_hash: None,
+            block_hash: Some(block_hash),
             block_number: Some(U64::from(state.block)),
             from: tx.from,
-            to: Some(tx.to),
-            cumulative_gas_used: U256::from(1),
-            gas_used: None,
-            contract_address: None,
-            logs: vec![],
+            to: tx.to,
+            cumulative_gas_used: U256::from(result.gas.unwrap_or(1)),
+            gas_used: result.gas.map(U256::from),
+            contract_address,
+            logs,
             status: Some(U64::from(result.result.is_ok() as u64)),
             root: None,
             logs_bloom: Default::default(),
@@ -667,18 +1151,98 @@ impl MockTransport {
         let transaction: H256 = args.arg();
         args.done();
 
-        let state = self.state.lock().unwrap();
+        let state = self.state();
 
         Self::ok(state.receipts.get(&transaction).unwrap_or_else(|| {
             panic!("there is no transaction with hash {:#x}", transaction);
         }))
     }
 
+    fn eth_get_logs(&self, mut args: Parser) -> Result<Value, Error> {
+        let filter: LogFilter = args.arg();
+        args.done();
+
+        let state = self.state();
+
+        let logs = state
+            .logs
+            .iter()
+            .filter(|log| filter.matches(log, state.block))
+            .collect::<Vec<_>>();
+        Self::ok(logs)
+    }
+
+    /// Reports a call that doesn't match any expectation to the registered
+    /// handler, then panics.
+    ///
+    /// The state lock is released before invoking the handler, so that the
+    /// handler can interact with the mock node.
+    fn unexpected_call(state: MutexGuard<MockTransportState>, call: Box<UnexpectedCall>) -> ! {
+        let handler = state.on_unexpected.clone();
+        drop(state);
+
+        if let Some(handler) = handler {
+            handler(&call);
+        }
+        panic!("{}", call);
+    }
+
     fn ok<T: Serialize>(t: T) -> Result<Value, Error> {
         Ok(to_value(t).unwrap())
     }
 }
 
+impl Drop for MockTransportState {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            return;
+        }
+
+        let mut violations = std::mem::take(&mut self.violations);
+        violations.extend(self.checkpoint());
+        if let Some(report) = violations_report(&violations) {
+            panic!("{}", report);
+        }
+    }
+}
+
+/// Formats violations into a single report, returns `None` if there are none.
+fn violations_report(violations: &[String]) -> Option<String> {
+    match violations {
+        [] => None,
+        [violation] => Some(violation.clone()),
+        violations => {
+            let mut report = format!("mock node recorded {} violations:", violations.len());
+            for (i, violation) in violations.iter().enumerate() {
+                report.push_str(&format!("\n  {}. {}", i + 1, violation));
+            }
+            Some(report)
+        }
+    }
+}
+
+/// Placeholder bytecode for mocked contracts, consisting of a single
+/// `INVALID` opcode.
+const DEFAULT_CODE: &[u8] = &[0xfe];
+
+/// Computes the method signature in the standard ABI format, without the
+/// output types.
+fn abi_signature(function: &Function) -> String {
+    let mut signature = function.signature();
+    if let Some(colon) = signature.find(':') {
+        signature.truncate(colon);
+    }
+    signature
+}
+
+/// Returns the hash of a mined block.
+///
+/// Mock node does not produce actual blocks, so block hashes are derived from
+/// block numbers.
+fn block_hash(block: u64) -> H256 {
+    H256::from_low_u64_be(block)
+}
+
 impl std::fmt::Debug for MockTransport {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_str("MockTransport")
@@ -688,7 +1252,23 @@ impl std::fmt::Debug for MockTransport {
 /// A mocked contract instance.
 struct Contract {
     address: Address,
+
+    /// Bytecode returned from `eth_getCode`.
+    code: Vec<u8>,
+
     methods: HashMap<H32, Method>,
+    events: HashMap<String, Vec<Event>>,
+
+    /// Description for catch-all expectations.
+    any_description: String,
+
+    /// Incremented whenever `any_expectations` vector is cleared to
+    /// invalidate expectations API handle.
+    any_generation: usize,
+
+    /// Catch-all expectations, checked when a call does not match any
+    /// expectation of the called method.
+    any_expectations: Vec<AnyExpectation>,
 }
 
 impl Contract {
@@ -697,11 +1277,39 @@ impl Contract {
 
         for functions in abi.functions.values() {
             for function in functions {
-                methods.insert(function.selector(), Method::new(address, function.clone()));
+                methods.insert(
+                    function.short_signature(),
+                    Method::new(address, function.clone()),
+                );
             }
         }
 
-        Contract { address, methods }
+        let events = abi
+            .events
+            .iter()
+            .map(|(name, events)| (name.clone(), events.clone()))
+            .collect();
+
+        Contract {
+            address,
+            code: DEFAULT_CODE.to_vec(),
+            methods,
+            events,
+            any_description: format!("any method on contract {:#x}", address),
+            any_generation: 0,
+            any_expectations: Vec::new(),
+        }
+    }
+
+    fn event(&self, name: &str) -> &Event {
+        match self.events.get(name).map(Vec::as_slice) {
+            Some([event]) => event,
+            Some(_) => panic!(
+                "event {:?} on contract {:#x} is overloaded, use `emits_raw_log` instead",
+                name, self.address
+            ),
+            None => panic!("contract {:#x} doesn't have event {:?}", self.address, name),
+        }
     }
 
     fn method(&mut self, signature: H32) -> &mut Method {
@@ -715,31 +1323,114 @@ impl Contract {
         }
     }
 
-    fn process_tx(&mut self, tx: CallContext, data: &[u8]) -> TransactionResult {
+    fn process_tx(
+        &mut self,
+        tx: CallContext,
+        data: &[u8],
+    ) -> Result<TransactionResult, Box<UnexpectedCall>> {
         // TODO:
         //
         // We could support receive/fallback functions if data is empty.
 
-        assert!(data.len() >= 4, "transaction has invalid call data");
+        if data.len() < 4 {
+            return match self.process_any(&tx, data) {
+                Some(result) => Ok(result),
+                None => panic!("transaction has invalid call data"),
+            };
+        }
 
         let signature = H32::try_from(&data[0..4]).unwrap();
-        let method = self.method(signature);
+        let call = match self.methods.get_mut(&signature) {
+            Some(method) => match method.process_tx(tx, data) {
+                Ok(result) => return Ok(result),
+                Err(call) => call,
+            },
+            None => Box::new(UnexpectedCall {
+                address: self.address,
+                selector: signature,
+                signature: None,
+                params: None,
+                data: data.to_vec(),
+                context: tx,
+            }),
+        };
 
-        method.process_tx(tx, data)
+        match self.process_any(&call.context, data) {
+            Some(result) => Ok(result),
+            None => Err(call),
+        }
     }
 
-    fn checkpoint(&mut self) {
-        for method in self.methods.values_mut() {
-            method.checkpoint();
+    /// Returns gas consumption specified by the expectation that would
+    /// match the given transaction, without executing it.
+    fn estimate_gas(&mut self, tx: CallContext, data: &[u8]) -> Option<u64> {
+        let matched = if data.len() < 4 {
+            None
+        } else {
+            let signature = H32::try_from(&data[0..4]).unwrap();
+            self.methods
+                .get_mut(&signature)
+                .and_then(|method| method.estimate_gas(&tx, data))
+        };
+
+        match matched {
+            Some(gas) => gas,
+            None => self
+                .any_expectations
+                .iter_mut()
+                .find_map(|expectation| expectation.matches(&tx, data).then_some(expectation.gas))
+                .flatten(),
         }
     }
-}
 
-impl Drop for Contract {
-    fn drop(&mut self) {
-        if !std::thread::panicking() {
-            self.checkpoint();
+    /// Executes a transaction or a call using catch-all expectations.
+    fn process_any(&mut self, tx: &CallContext, data: &[u8]) -> Option<TransactionResult> {
+        let description = &self.any_description;
+        self.any_expectations
+            .iter_mut()
+            .filter(|expectation| expectation.counter.is_active())
+            .find_map(|expectation| expectation.process_tx(tx, description, data))
+    }
+
+    /// Adds new catch-all expectation.
+    fn expect_any(&mut self) -> (usize, usize) {
+        let index = self.any_expectations.len();
+        self.any_expectations.push(AnyExpectation::new());
+        (index, self.any_generation)
+    }
+
+    /// Returns a catch-all expectation.
+    fn any_expectation(&mut self, index: usize, generation: usize) -> &mut AnyExpectation {
+        assert!(
+            generation == self.any_generation,
+            "old expectations are not valid after checkpoint"
+        );
+
+        let expectation = &mut self.any_expectations[index];
+        if expectation.checked {
+            panic!(
+                "can't modify expectation for {} because it was already in use",
+                self.any_description
+            )
         }
+
+        expectation
+    }
+
+    fn checkpoint(&mut self) -> Vec<String> {
+        let mut violations: Vec<String> = self
+            .methods
+            .values_mut()
+            .flat_map(Method::checkpoint)
+            .collect();
+        violations.extend(
+            self.any_expectations
+                .iter()
+                .filter_map(|expectation| expectation.counter.verify(&self.any_description).err()),
+        );
+        self.any_generation += 1;
+        self.any_expectations.clear();
+        violations
     }
 }
 
@@ -761,7 +1452,7 @@ struct Method {
 impl Method {
     /// Creates new method.
     fn new(address: Address, function: Function) -> Self {
-        let description = format!("{:?} on contract {:#x}", function.abi_signature(), address);
+        let description = format!("{:?} on contract {:#x}", abi_signature(&function), address);
 
         Method {
             description,
@@ -810,7 +1501,25 @@ impl Method {
     }
 
     /// Executes a transaction or a call.
-    fn process_tx(&mut self, tx: CallContext, data: &[u8]) -> TransactionResult {
+    /// Finds an expectation that matches a transaction without executing it,
+    /// and returns its gas consumption.
+    fn estimate_gas(&mut self, tx: &CallContext, data: &[u8]) -> Option<Option<u64>> {
+        let params = self
+            .function
+            .decode_input(&data[4..])
+            .unwrap_or_else(|e| panic!("unable to decode input for {}: {:?}", self.description, e));
+
+        self.expectations
+            .iter_mut()
+            .find_map(|expectation| expectation.estimate_gas(tx, &self.description, params.clone()))
+    }
+
+    /// Executes a transaction or a call.
+    fn process_tx(
+        &mut self,
+        tx: CallContext,
+        data: &[u8],
+    ) -> Result<TransactionResult, Box<UnexpectedCall>> {
         if !tx.value.is_zero() && self.function.state_mutability != StateMutability::Payable {
             panic!(
                 "call to non-payable {} with non-zero value {}",
@@ -832,20 +1541,30 @@ impl Method {
                 if let Some(result) =
                     expectation.process_tx(&tx, &self.description, &self.function, params.clone())
                 {
-                    return result;
+                    return Ok(result);
                 }
             }
         }
 
-        panic!("unexpected call to {}", self.description)
+        Err(Box::new(UnexpectedCall {
+            address: tx.to,
+            selector: self.function.short_signature(),
+            signature: Some(abi_signature(&self.function)),
+            params: Some(params),
+            data: data.to_vec(),
+            context: tx,
+        }))
     }
 
-    fn checkpoint(&mut self) {
-        for expectation in self.expectations.iter_mut() {
-            expectation.verify(&self.description);
-        }
+    fn checkpoint(&mut self) -> Vec<String> {
+        let violations = self
+            .expectations
+            .iter()
+            .filter_map(|expectation| expectation.verify(&self.description).err())
+            .collect();
         self.generation += 1;
         self.expectations.clear();
+        violations
     }
 }
 
@@ -868,16 +1587,25 @@ trait ExpectationApi: Send {
         params: Vec<Token>,
     ) -> Option<TransactionResult>;
 
+    /// Matches a transaction without processing it.
+    ///
+    /// If transaction matches this expectation, returns gas consumption
+    /// specified by it. Otherwise, returns `None`.
+    fn estimate_gas(
+        &mut self,
+        tx: &CallContext,
+        description: &str,
+        params: Vec<Token>,
+    ) -> Option<Option<u64>>;
+
     /// Verifies that this expectation is satisfied.
-    fn verify(&self, description: &str);
+    fn verify(&self, description: &str) -> Result<(), String>;
 }
 
 struct Expectation<P: Tokenize + Send + 'static, R: Tokenize + Send + 'static> {
-    /// How many times should this expectation be called.
-    times: TimesRange,
-
-    /// How many times was it actually called.
-    used: usize,
+    /// How many times should this expectation be called, how many times
+    /// it was actually called, and the sequence it belongs to.
+    counter: CallCounter,
 
     /// Indicates that predicate for this expectation has been called at least
     /// once. Expectations shouldn't be changed after that happened.
@@ -886,6 +1614,9 @@ struct Expectation<P: Tokenize + Send + 'static, R: Tokenize + Send + 'static> {
     /// How many blocks should node skip for confirmation to be successful.
     confirmations: u64,
 
+    /// Last block in which this expectation can be matched.
+    valid_until: Option<u64>,
+
     /// Only consider this expectation if predicate returns `true`.
     predicate: Predicate<P>,
 
@@ -898,23 +1629,54 @@ struct Expectation<P: Tokenize + Send + 'static, R: Tokenize + Send + 'static> {
     /// Function to generate method's return value.
     returns: Returns<P, R>,
 
-    /// Handle for when this expectation belongs to a sequence.
-    sequence: Option<mockall::SeqHandle>,
+    /// Logs emitted by transactions matching this expectation.
+    logs: Vec<RawLog>,
+
+    /// Gas consumed by transactions matching this expectation.
+    gas: Option<u64>,
 }
 
 impl<P: Tokenize + Send + 'static, R: Tokenize + Send + 'static> Expectation<P, R> {
     fn new() -> Self {
         Expectation {
-            times: TimesRange::default(),
-            used: 0,
+            counter: CallCounter::new(),
             checked: false,
             confirmations: 0,
+            valid_until: None,
             predicate: Predicate::None,
             allow_calls: true,
             allow_transactions: true,
             returns: Returns::Default,
-            sequence: None,
+            logs: Vec::new(),
+            gas: None,
+        }
+    }
+
+    /// Checks if a transaction matches this expectation, and decodes
+    /// its parameters if it does.
+    fn matches(&mut self, tx: &CallContext, description: &str, params: Vec<Token>) -> Option<P> {
+        self.checked = true;
+
+        if tx.is_view_call && !self.allow_calls || !tx.is_view_call && !self.allow_transactions {
+            return None;
+        }
+
+        if !self.counter.is_active() {
+            return None;
+        }
+
+        if matches!(self.valid_until, Some(block) if tx.block > block) {
+            return None;
+        }
+
+        let param = P::from_token(Token::Tuple(params))
+            .unwrap_or_else(|e| panic!("unable to decode input for {}: {:?}", description, e));
+
+        if !self.predicate.can_call(tx, &param) {
+            return None;
         }
+
+        Some(param)
     }
 }
 
@@ -926,7 +1688,7 @@ impl<P: Tokenize + Send + 'static, R: Tokenize + Send + 'static> ExpectationApi
     }
 
     fn is_active(&self) -> bool {
-        self.times.can_call(self.used)
+        self.counter.is_active()
     }
 
     fn process_tx(
@@ -936,63 +1698,40 @@ impl<P: Tokenize + Send + 'static, R: Tokenize + Send + 'static> ExpectationApi
         function: &Function,
         params: Vec<Token>,
     ) -> Option<TransactionResult> {
-        self.checked = true;
+        let param = self.matches(tx, description, params)?;
 
-        if tx.is_view_call && !self.allow_calls || !tx.is_view_call && !self.allow_transactions {
-            return None;
-        }
-
-        if !self.times.can_call(self.used) {
-            return None;
-        }
-
-        let param = P::from_token(Token::Tuple(params))
-            .unwrap_or_else(|e| panic!("unable to decode input for {}: {:?}", description, e));
-
-        if !self.predicate.can_call(tx, &param) {
-            return None;
-        }
-
-        self.used += 1;
-        if let Some(sequence) = &self.sequence {
-            sequence.verify(description);
-
-            if self.used == self.times.lower_bound() {
-                sequence.satisfy();
-            }
-        }
+        self.counter.record_call(description);
 
         let result = self
             .returns
             .process_tx(function, tx, param)
-            .map(|result| ethcontract::common::abi::encode(&result));
+            .map(|result| ethcontract_core::abi::encode(&result));
+
+        let logs = match (&result, tx.is_view_call) {
+            (Ok(_), false) => self.logs.clone(),
+            _ => Vec::new(),
+        };
 
         Some(TransactionResult {
             result,
             confirmations: self.confirmations,
+            logs,
+            gas: self.gas,
         })
     }
 
-    fn verify(&self, description: &str) {
-        if !self.times.contains(self.used) {
-            panic!(
-                "{} was called {} {}, but it was expected to be called {} {} {}",
-                description,
-                self.used,
-                if self.used == 1 { "time" } else { "times" },
-                if self.times.is_exact() {
-                    "exactly"
-                } else {
-                    "at least"
-                },
-                self.times.lower_bound(),
-                if self.times.lower_bound() == 1 {
-                    "time"
-                } else {
-                    "times"
-                }
-            )
-        }
+    fn estimate_gas(
+        &mut self,
+        tx: &CallContext,
+        description: &str,
+        params: Vec<Token>,
+    ) -> Option<Option<u64>> {
+        self.matches(tx, description, params)?;
+        Some(self.gas)
+    }
+
+    fn verify(&self, description: &str) -> Result<(), String> {
+        self.counter.verify(description)
     }
 }
 
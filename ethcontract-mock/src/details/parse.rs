@@ -1,9 +1,9 @@
 //! Helpers to parse RPC call arguments.
 
-use ethcontract::json::{from_value, Value};
-use ethcontract::jsonrpc::serde::Deserialize;
-use ethcontract::web3::types::BlockNumber;
+use serde::Deserialize;
+use serde_json::{from_value, Value};
 use std::fmt::Display;
+use web3::types::BlockNumber;
 
 /// A helper to parse RPC call arguments.
 ///
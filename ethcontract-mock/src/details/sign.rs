@@ -1,9 +1,61 @@
 //! Helpers to work with signed transactions.
 
 use crate::details::transaction::Transaction;
-use ethcontract::common::abi::ethereum_types::BigEndianHash;
-use ethcontract::web3::signing;
-use ethcontract::web3::types::{Address, H256, U256};
+use ethcontract_core::abi::ethereum_types::BigEndianHash;
+use web3::signing::{self, Key, SecretKey, SecretKeyRef};
+use web3::types::{Address, H256, U256};
+
+/// Signs a legacy transaction with an EIP-155 signature and returns
+/// its raw RLP encoding.
+#[allow(clippy::too_many_arguments)]
+pub fn sign(
+    nonce: U256,
+    gas_price: U256,
+    gas: U256,
+    to: Option<Address>,
+    value: U256,
+    data: Vec<u8>,
+    key: &SecretKey,
+    chain_id: u64,
+) -> Vec<u8> {
+    let encode = |rlp: &mut rlp::RlpStream| {
+        rlp.append(&nonce);
+        rlp.append(&gas_price);
+        rlp.append(&gas);
+        match &to {
+            Some(to) => rlp.append(to),
+            None => rlp.append_empty_data(),
+        };
+        rlp.append(&value);
+        rlp.append(&data);
+    };
+
+    let msg_hash = {
+        let mut rlp = rlp::RlpStream::new();
+
+        rlp.begin_list(9);
+        encode(&mut rlp);
+        rlp.append(&chain_id);
+        rlp.append(&0u8);
+        rlp.append(&0u8);
+
+        signing::keccak256(rlp.as_raw())
+    };
+
+    let signature = SecretKeyRef::new(key)
+        .sign(&msg_hash, Some(chain_id))
+        .unwrap_or_else(|_| panic!("failed to sign transaction"));
+
+    let mut rlp = rlp::RlpStream::new();
+
+    rlp.begin_list(9);
+    encode(&mut rlp);
+    rlp.append(&signature.v);
+    rlp.append(&signature.r.into_uint());
+    rlp.append(&signature.s.into_uint());
+
+    rlp.out().to_vec()
+}
 
 /// Parses and verifies raw transaction, including chain ID.
 ///
@@ -22,16 +74,14 @@ pub fn verify(raw_tx: &[u8], node_chain_id: u64) -> Transaction {
         err();
     }
 
-    // TODO:
-    //
-    // We could support deployments via RPC calls by introducing
-    // something like `expect_deployment` method to `Mock` struct.
-    assert!(res(rlp.at(3)).size() != 0, "mock client does not support deploying contracts via transaction, use `Mock::deploy` instead");
-
     let nonce: U256 = res(rlp.val_at(0));
     let gas_price: U256 = res(rlp.val_at(1));
     let gas: U256 = res(rlp.val_at(2));
-    let to: Address = res(rlp.val_at(3));
+    // Contract creation transactions have an empty `to` field.
+    let to: Option<Address> = match res(rlp.at(3)).is_empty() {
+        true => None,
+        false => Some(res(rlp.val_at(3))),
+    };
     let value: U256 = res(rlp.val_at(4));
     let data: Vec<u8> = res(rlp.val_at(5));
     let v: u64 = res(rlp.val_at(6));
@@ -56,7 +106,10 @@ pub fn verify(raw_tx: &[u8], node_chain_id: u64) -> Transaction {
         rlp.append(&nonce);
         rlp.append(&gas_price);
         rlp.append(&gas);
-        rlp.append(&to);
+        match &to {
+            Some(to) => rlp.append(to),
+            None => rlp.append_empty_data(),
+        };
         rlp.append(&value);
         rlp.append(&data);
         rlp.append(&chain_id);
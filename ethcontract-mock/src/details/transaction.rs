@@ -1,11 +1,13 @@
 //! Common transaction types.
 
-use ethcontract::{Address, H256, U256};
+use ethcontract_core::abi::RawLog;
+use web3::types::{Address, H256, U256};
 
 /// Basic transaction parameters.
 pub struct Transaction {
     pub from: Address,
-    pub to: Address,
+    /// Recipient of the transaction, `None` for contract creation.
+    pub to: Option<Address>,
     pub nonce: U256,
     pub gas: U256,
     pub gas_price: U256,
@@ -22,4 +24,11 @@ pub struct TransactionResult {
     /// How many blocks should be mined on top of transaction's block
     /// for confirmation to be successful.
     pub confirmations: u64,
+
+    /// Logs emitted by the transaction.
+    pub logs: Vec<RawLog>,
+
+    /// Gas consumed by the transaction, if it was specified
+    /// by the matched expectation.
+    pub gas: Option<u64>,
 }
@@ -11,6 +11,14 @@
 //! Finally, create an ethcontract's [`Instance`] by calling [`Contract::instance`],
 //! then use said instance in your tests.
 //!
+//! Helpers that work with ethcontract's dynamic types, such as
+//! [`Contract::instance`] and [`Mock::web3`], are enabled by the default
+//! `ethcontract` feature. Without it, this crate only depends on the
+//! separately versioned `ethcontract-core` crate, which defines method
+//! signatures and token conversions, and on `web3`, so that it can be
+//! upgraded independently of the runtime. Mock node is then accessed
+//! through [`Mock::raw_web3`] and [`MockTransport`].
+//!
 //! # Example
 //!
 //! Let's mock [voting contract] from solidity examples.
@@ -99,6 +107,9 @@
 //!
 //! See [`Expectation`] for more info and examples.
 //!
+//! Calls that don't match any method expectation can be handled by
+//! catch-all expectations created with [`Contract::expect_any_method`].
+//!
 //! # Interacting with mocked contracts
 //!
 //! After contract's behaviour is programmed, you can call
@@ -108,11 +119,19 @@
 //! through [`web3`].
 //!
 //! Specifically, mock node supports `eth_call`, `eth_sendRawTransaction`,
-//! and `eth_getTransactionReceipt`.
+//! `eth_getTransactionReceipt`, `eth_getLogs`, `eth_getBalance`
+//! and `eth_getCode`. Account balances are set up with [`Mock::set_balance`].
+//! Mocked contracts have placeholder bytecode, so contract existence checks
+//! pass; it can be replaced with [`Contract::set_code`].
+//!
+//! Transactions can emit events set up with [`Expectation::emits_event`].
+//! Emitted events are included in transaction receipts and returned from
+//! `eth_getLogs`, so event queries on contract instances work as usual.
 //!
-//! At the moment, mock node can't sign transactions on its own,
-//! so `eth_sendTransaction` is not supported. Also, deploying contracts
-//! via `eth_sendRawTransaction` is not possible yet.
+//! Mock node can sign transactions sent with `eth_sendTransaction`
+//! only for accounts registered with [`Mock::add_account`]; other
+//! transactions should be signed offline. Deploying contracts
+//! requires setting up a deployment with [`Mock::expect_deployment`].
 //!
 //! # Mocking generated contracts
 //!
@@ -203,11 +222,21 @@
 //! Mock node allows you to customize value returned from `eth_gasPrice`
 //! RPC call. Use [`Mock::update_gas_price`] to set a new gas price.
 //!
-//! Estimating gas consumption with `eth_estimateGas` is not supported at the
-//! moment. For now, calls to `eth_estimateGas` always return `1`.
+//! EIP-1559 fees are mocked as well. [`Mock::set_fee_history`] sets
+//! base fees and priority fees of the most recent blocks, which are reported
+//! by `eth_feeHistory`. This makes fee estimation, such as
+//! [`GasPrice::estimate_eip1559`], deterministic in tests. Value returned
+//! from `eth_maxPriorityFeePerGas` is set with
+//! [`Mock::update_max_priority_fee_per_gas`].
 //!
-//! [`web3-rs`]: ethcontract::web3
-//! [`web3`]: ethcontract::web3
+//! Gas consumption of transactions is set up with
+//! [`Expectation::consumes_gas`]. Calls to `eth_estimateGas` return gas
+//! consumption of the expectation that matches the transaction, and
+//! receipts report it as gas used. Transactions that don't match
+//! an expectation with gas consumption are estimated to require `1` gas.
+//!
+//! [`web3-rs`]: web3
+//! [`web3`]: web3
 //! [`expect_call`]: Contract::expect_call
 //! [`expect_transaction`]: Contract::expect_transaction
 //! [`returns`]: Expectation::returns
@@ -216,23 +245,32 @@
 //! [`Instance`]: ethcontract::Instance
 //! [voting contract]: https://docs.soliditylang.org/en/v0.8.6/solidity-by-example.html#voting
 //! [method signatures]: Signature
+//! [`GasPrice::estimate_eip1559`]: ethcontract::GasPrice::estimate_eip1559
 
 use crate::predicate::TuplePredicate;
-use crate::range::TimesRange;
-use ethcontract::common::hash::H32;
-use ethcontract::common::Abi;
+#[cfg(feature = "ethcontract")]
 use ethcontract::dyns::{DynInstance, DynTransport, DynWeb3};
-use ethcontract::tokens::Tokenize;
-use ethcontract::{Address, U256};
+#[cfg(feature = "ethcontract")]
+use ethcontract::PrivateKey;
+use ethcontract_core::abi::{Contract as Abi, RawLog, Token};
+use ethcontract_core::tokens::Tokenize;
+use ethcontract_core::H32;
+use jsonrpc_core::{Call, Value};
 use std::marker::PhantomData;
+#[cfg(feature = "ethcontract")]
 use std::sync::Arc;
+use web3::signing::SecretKey;
+use web3::types::{Address, U256};
+use web3::{BatchTransport, RequestId, Transport, Web3};
 
 #[doc(no_inline)]
-pub use ethcontract::contract::Signature;
+pub use ethcontract_core::Signature;
+pub use range::TimesRange;
 
 mod details;
 mod predicate;
-mod range;
+pub mod range;
+pub mod sequence;
 pub mod utils;
 
 #[cfg(test)]
@@ -269,16 +307,30 @@ impl Mock {
 
     /// Creates a `Web3` object that can be used to interact with
     /// the mocked chain.
+    #[cfg(feature = "ethcontract")]
     pub fn web3(&self) -> DynWeb3 {
         DynWeb3::new(self.transport())
     }
 
     /// Creates a `Transport` object that can be used to interact with
     /// the mocked chain.
+    #[cfg(feature = "ethcontract")]
     pub fn transport(&self) -> DynTransport {
         DynTransport::new(self.transport.clone())
     }
 
+    /// Creates a `Web3` object with the mock node's own transport, which
+    /// is available without the `ethcontract` feature.
+    pub fn raw_web3(&self) -> Web3<MockTransport> {
+        Web3::new(self.raw_transport())
+    }
+
+    /// Creates the mock node's own `Transport` object, which is available
+    /// without the `ethcontract` feature.
+    pub fn raw_transport(&self) -> MockTransport {
+        MockTransport(self.transport.clone())
+    }
+
     /// Deploys a new mocked contract and returns an object that allows
     /// configuring expectations for contract methods.
     pub fn deploy(&self, abi: Abi) -> Contract {
@@ -301,6 +353,85 @@ impl Mock {
         }
     }
 
+    /// Registers a new mocked contract that expects to be deployed with
+    /// a contract creation transaction, for example by a `DeployBuilder`,
+    /// and returns an object that allows configuring expectations for
+    /// contract methods.
+    ///
+    /// The contract is available at the returned address right away, so
+    /// expectations can be set up before it is deployed. Contract creation
+    /// transactions are matched with expected deployments in the order in
+    /// which they were registered, and their receipts contain the address
+    /// of the mocked contract. Deployment data, including constructor
+    /// arguments, is not verified.
+    ///
+    /// Expected deployments that were not performed are reported
+    /// by [`checkpoint`], and a contract creation transaction that does
+    /// not match an expected deployment causes a panic.
+    ///
+    /// [`checkpoint`]: Mock::checkpoint
+    pub fn expect_deployment(&self, abi: Abi) -> Contract {
+        let address = self.transport.expect_deployment(&abi);
+        Contract {
+            transport: self.transport.clone(),
+            address,
+            abi,
+        }
+    }
+
+    /// Registers an account with the given private key, and returns
+    /// its address.
+    ///
+    /// Mock node signs transactions sent with `eth_sendTransaction` from
+    /// registered accounts, so that tests can use [`Account::Local`]
+    /// instead of offline signing. Registered accounts are also returned
+    /// by `eth_accounts`, in order of registration, which means that
+    /// the first registered account is used to send transactions
+    /// that don't specify a sender.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ethcontract::{Account, PrivateKey};
+    /// # use ethcontract_mock::Mock;
+    /// let mock = Mock::new(1234);
+    /// let key = PrivateKey::from_raw([1; 32]).unwrap();
+    /// let address = mock.add_account(key);
+    ///
+    /// // Transactions from this account are signed by the mock node.
+    /// let account = Account::Local(address, None);
+    /// ```
+    ///
+    /// [`Account::Local`]: ethcontract::Account::Local
+    #[cfg(feature = "ethcontract")]
+    pub fn add_account(&self, key: PrivateKey) -> Address {
+        self.add_secret_key(*key)
+    }
+
+    /// Registers an account with the given `web3` secret key, and returns
+    /// its address. See [`add_account`] for more info.
+    ///
+    /// [`add_account`]: Mock::add_account
+    pub fn add_secret_key(&self, key: SecretKey) -> Address {
+        self.transport.add_account(key)
+    }
+
+    /// Sets ETH balance of the given account, which is returned by RPC call
+    /// `eth_getBalance`.
+    ///
+    /// Mock node tracks balances of all accounts, which start at zero.
+    /// Transactions transfer their value from the sender to the recipient,
+    /// unless they revert. Once an account's balance is set, transactions
+    /// from it with a value that exceeds its balance are rejected with an
+    /// RPC error; accounts whose balance was never set can send any value.
+    /// Gas fees, i.e. gas price multiplied by gas set with
+    /// [`Expectation::consumes_gas`] or `1` if it is not specified, are
+    /// charged from the sender up to its available balance, so that accounts
+    /// don't need to be funded to send transactions.
+    pub fn set_balance(&self, address: Address, balance: U256) {
+        self.transport.set_balance(address, balance);
+    }
+
     /// Updates gas price that is returned by RPC call `eth_gasPrice`.
     ///
     /// Mock node does not simulate gas consumption, so this value does not
@@ -309,6 +440,28 @@ impl Mock {
         self.transport.update_gas_price(gas_price);
     }
 
+    /// Updates max priority fee per gas that is returned by RPC call
+    /// `eth_maxPriorityFeePerGas`.
+    pub fn update_max_priority_fee_per_gas(&self, max_priority_fee_per_gas: u64) {
+        self.transport
+            .update_max_priority_fee_per_gas(max_priority_fee_per_gas);
+    }
+
+    /// Sets fee history that is returned by RPC call `eth_feeHistory`.
+    ///
+    /// Fee history is a list of pairs of base fee per gas and priority
+    /// fee per gas of the most recent blocks, oldest first. Priority fee
+    /// is reported for all requested reward percentiles. The base fee of
+    /// the newest block is also reported as the base fee of the next block.
+    /// Blocks older than the history repeat its oldest entry.
+    ///
+    /// By default, all blocks report current gas price as their base fee,
+    /// and zero priority fee.
+    pub fn set_fee_history(&self, fee_history: impl IntoIterator<Item = (u64, u64)>) {
+        self.transport
+            .set_fee_history(fee_history.into_iter().collect());
+    }
+
     /// Verifies that all expectations on all contracts have been met,
     /// then clears all expectations.
     ///
@@ -323,6 +476,86 @@ impl Mock {
     pub fn checkpoint(&self) {
         self.transport.checkpoint();
     }
+
+    /// Enables or disables panic-free mode.
+    ///
+    /// By default, mock node panics when it encounters an unexpected call,
+    /// an unsupported RPC method, or any other violation. The panic happens
+    /// in whatever task is making the RPC call, which may obscure the actual
+    /// failure in tests that run multiple tasks.
+    ///
+    /// In panic-free mode, violations are returned to the caller
+    /// as JSON-RPC errors instead. They are also recorded, and reported
+    /// by [`verify`]. Unsatisfied expectations found by [`checkpoint`] are
+    /// recorded as well.
+    ///
+    /// Violations that were not reported by [`verify`] will cause a panic
+    /// when the mock node is dropped.
+    ///
+    /// [`verify`]: Mock::verify
+    /// [`checkpoint`]: Mock::checkpoint
+    pub fn set_panic_free(&self, panic_free: bool) {
+        self.transport.set_panic_free(panic_free);
+    }
+
+    /// Registers a handler for calls and transactions that don't match any
+    /// expectation, replacing the previously registered handler.
+    ///
+    /// The handler receives the contract address, method selector, decoded
+    /// arguments (if the contract has a matching method), and the call
+    /// context. This allows test frameworks to turn unexpected calls into
+    /// rich assertion failures, or to record them for authoring
+    /// expectations later.
+    ///
+    /// The handler is invoked before the mock node reports the unexpected
+    /// call. If the handler returns, the call is reported as usual, i.e. the
+    /// mock node panics, or returns an error in panic-free mode. If the
+    /// handler panics, its panic message is reported instead.
+    pub fn on_unexpected(&self, handler: impl Fn(&UnexpectedCall) + Send + Sync + 'static) {
+        self.transport.on_unexpected(handler);
+    }
+
+    /// Verifies that all expectations on all contracts have been met,
+    /// and that no violations were recorded in panic-free mode,
+    /// then clears all expectations and recorded violations.
+    ///
+    /// # Panics
+    ///
+    /// If there are any unsatisfied expectations or recorded violations.
+    /// The panic message contains all of them.
+    pub fn verify(&self) {
+        self.transport.verify();
+    }
+}
+
+/// Transport that sends RPC calls to a mock node.
+///
+/// It implements `web3`'s `Transport` and `BatchTransport`, so it can be
+/// used without the `ethcontract` feature. See [`Mock::raw_transport`].
+#[derive(Clone, Debug)]
+pub struct MockTransport(details::MockTransport);
+
+impl Transport for MockTransport {
+    type Out = <details::MockTransport as Transport>::Out;
+
+    fn prepare(&self, method: &str, params: Vec<Value>) -> (RequestId, Call) {
+        self.0.prepare(method, params)
+    }
+
+    fn send(&self, id: RequestId, request: Call) -> Self::Out {
+        self.0.send(id, request)
+    }
+}
+
+impl BatchTransport for MockTransport {
+    type Batch = <details::MockTransport as BatchTransport>::Batch;
+
+    fn send_batch<T>(&self, requests: T) -> Self::Batch
+    where
+        T: IntoIterator<Item = (RequestId, Call)>,
+    {
+        self.0.send_batch(requests)
+    }
 }
 
 impl std::fmt::Debug for Mock {
@@ -344,28 +577,44 @@ pub struct Contract {
 impl Contract {
     /// Creates a `Web3` object that can be used to interact with
     /// the mocked chain on which this contract is deployed.
+    #[cfg(feature = "ethcontract")]
     pub fn web3(&self) -> DynWeb3 {
         DynWeb3::new(self.transport())
     }
 
     /// Creates a `Transport` object that can be used to interact with
     /// the mocked chain.
+    #[cfg(feature = "ethcontract")]
     pub fn transport(&self) -> DynTransport {
         DynTransport::new(self.transport.clone())
     }
 
     /// Creates a contract `Instance` that can be used to interact with
     /// this contract.
+    #[cfg(feature = "ethcontract")]
     pub fn instance(&self) -> DynInstance {
         DynInstance::at(self.web3(), Arc::new(self.abi.clone().into()), self.address)
     }
 
     /// Consumes this object and transforms it into a contract `Instance`
     /// that can be used to interact with this contract.
+    #[cfg(feature = "ethcontract")]
     pub fn into_instance(self) -> DynInstance {
         DynInstance::at(self.web3(), Arc::new(self.abi.into()), self.address)
     }
 
+    /// Creates a `Web3` object with the mock node's own transport, which
+    /// is available without the `ethcontract` feature.
+    pub fn raw_web3(&self) -> Web3<MockTransport> {
+        Web3::new(self.raw_transport())
+    }
+
+    /// Creates the mock node's own `Transport` object, which is available
+    /// without the `ethcontract` feature.
+    pub fn raw_transport(&self) -> MockTransport {
+        MockTransport(self.transport.clone())
+    }
+
     /// Returns a reference to the contract's ABI.
     pub fn abi(&self) -> &Abi {
         &self.abi
@@ -376,6 +625,20 @@ impl Contract {
         self.address
     }
 
+    /// Sets bytecode that mock node returns from `eth_getCode`
+    /// for this contract.
+    ///
+    /// By default, mocked contracts have non-empty placeholder bytecode,
+    /// so that contract existence checks pass. Contracts that expect
+    /// to be deployed via [`Mock::expect_deployment`] have no code
+    /// until they are deployed.
+    ///
+    /// Note that mock node never executes contract code, this only
+    /// affects the result of `eth_getCode`.
+    pub fn set_code(&self, code: Vec<u8>) {
+        self.transport.set_code(self.address, code);
+    }
+
     /// Adds a new expectation for contract method. See [`Expectation`].
     ///
     /// Generic parameters are used to specify which rust types should be used
@@ -433,6 +696,36 @@ impl Contract {
         self.expect(signature).allow_calls(false)
     }
 
+    /// Adds a new catch-all expectation that is not tied to a specific
+    /// method signature.
+    ///
+    /// Catch-all expectations operate on raw calldata and return data.
+    /// They are only considered for calls that don't match any expectation
+    /// of the called method, including calls with selectors that are not
+    /// a part of contract's ABI. This makes them useful for asserting
+    /// that a contract is not called at all, or that all calls revert:
+    ///
+    /// ```
+    /// # include!("test/doctest/common.rs");
+    /// # fn main() {
+    /// # let contract = contract();
+    /// contract
+    ///     .expect_any_method()
+    ///     .returns_error("paused".to_string());
+    /// # }
+    /// ```
+    ///
+    /// See [`AnyMethodExpectation`] for more info.
+    pub fn expect_any_method(&self) -> AnyMethodExpectation {
+        let (index, generation) = self.transport.expect_any(self.address);
+        AnyMethodExpectation {
+            transport: self.transport.clone(),
+            address: self.address,
+            index,
+            generation,
+        }
+    }
+
     /// Verifies that all expectations on this contract have been met,
     /// then clears all expectations.
     ///
@@ -462,7 +755,8 @@ impl Contract {
 ///
 /// If a suitable expectation is found, it is used to determine method's
 /// return value and other transaction properties. If not, the call
-/// is considered unexpected, and mock node panics.
+/// is considered unexpected, and mock node panics, or returns an error
+/// if it is in [panic-free mode].
 ///
 /// To determine if a particular expectation should be used for the given call,
 /// mock node uses two of the expectation's properties:
@@ -485,6 +779,7 @@ impl Contract {
 /// [`predicate`]: Expectation::predicate
 /// [times limiter]: Expectation::times
 /// [`returns`]: Expectation::returns
+/// [panic-free mode]: Mock::set_panic_free
 pub struct Expectation<P: Tokenize + Send + 'static, R: Tokenize + Send + 'static> {
     transport: details::MockTransport,
     address: Address,
@@ -678,6 +973,90 @@ impl<P: Tokenize + Send + 'static, R: Tokenize + Send + 'static> Expectation<P,
         self
     }
 
+    /// Sets amount of gas consumed by transactions matching
+    /// this expectation.
+    ///
+    /// Mock node returns this value from `eth_estimateGas` for transactions
+    /// that match this expectation, and reports it as `gas_used` in receipts
+    /// of executed transactions. Gas estimation checks expectations in
+    /// the same way as executing a transaction would, but it does not count
+    /// as a call to the matched expectation.
+    ///
+    /// If gas consumption is not specified, gas is estimated to be 1,
+    /// and receipts don't report gas usage.
+    pub fn consumes_gas(self, gas: u64) -> Self {
+        self.transport.consumes_gas::<P, R>(
+            self.address,
+            self.signature,
+            self.index,
+            self.generation,
+            gas,
+        );
+        self
+    }
+
+    /// Limits this expectation to calls and transactions in blocks up to
+    /// and including the given block number.
+    ///
+    /// Once the mock chain advances past this block, the expectation is
+    /// skipped as if its predicate didn't match, and later expectations
+    /// for the method are considered instead. This allows modelling
+    /// contracts whose behaviour changes over time, for example an auction
+    /// that moves to a different phase at some block:
+    ///
+    /// ```
+    /// # include!("test/doctest/common.rs");
+    /// # fn main() {
+    /// # let contract = contract();
+    /// # let signature = signature();
+    /// contract
+    ///     .expect(signature)
+    ///     .valid_until_block(10)
+    ///     .returns(1);
+    /// contract
+    ///     .expect(signature)
+    ///     .returns(2);
+    /// # }
+    /// ```
+    ///
+    /// View calls are executed in the latest block, or in the requested block
+    /// for calls to historical blocks, while transactions are executed in the
+    /// block they get mined in, i.e. the next one. See [`CallContext::block`].
+    ///
+    /// Note that block validity does not affect [`times`] verification:
+    /// an expectation that expired before it was called enough times
+    /// still fails verification.
+    ///
+    /// [`times`]: Expectation::times
+    pub fn valid_until_block(self, block: u64) -> Self {
+        self.transport.valid_until_block::<P, R>(
+            self.address,
+            self.signature,
+            self.index,
+            self.generation,
+            block,
+        );
+        self
+    }
+
+    /// Limits this expectation to calls and transactions in the next `blocks`
+    /// blocks, i.e. until the mock chain advances more than `blocks` blocks
+    /// past its current block.
+    ///
+    /// See [`valid_until_block`] for more info.
+    ///
+    /// [`valid_until_block`]: Expectation::valid_until_block
+    pub fn valid_for_blocks(self, blocks: u64) -> Self {
+        self.transport.valid_for_blocks::<P, R>(
+            self.address,
+            self.signature,
+            self.index,
+            self.generation,
+            blocks,
+        );
+        self
+    }
+
     /// Sets predicate for this expectation.
     ///
     /// If method has multiple expectations, they are checked one-by-one,
@@ -936,13 +1315,320 @@ impl<P: Tokenize + Send + 'static, R: Tokenize + Send + 'static> Expectation<P,
         );
         self
     }
+
+    /// Adds an event that is emitted by transactions matching this
+    /// expectation.
+    ///
+    /// The event is looked up by name in the contract's ABI, and its
+    /// parameters are encoded into log topics and data. Parameters are
+    /// given as a tuple, in the order they are declared in the ABI, which
+    /// means that generated event data types can be used directly:
+    ///
+    /// ```no_run
+    /// # include!("test/doctest/common.rs");
+    /// # fn main() {
+    /// # let contract = contract();
+    /// # let signature = signature();
+    /// contract
+    ///     .expect_transaction(signature)
+    ///     .emits_event("Transfer", (address_for("Alice"), address_for("Bob"), U256::from(1)));
+    /// # }
+    /// ```
+    ///
+    /// Emitted events are included in the transaction receipt and can be
+    /// queried with `eth_getLogs`, so event queries on contract instances
+    /// work with the mock node. Events are not emitted by view calls and
+    /// by reverted transactions. Calling this method multiple times emits
+    /// multiple events, in the order they were added.
+    ///
+    /// Overloaded events can't be looked up by name, use [`emits_raw_log`]
+    /// for them instead.
+    ///
+    /// [`emits_raw_log`]: Expectation::emits_raw_log
+    pub fn emits_event(self, name: &str, params: impl Tokenize) -> Self {
+        self.transport.emits_event::<P, R>(
+            self.address,
+            self.signature,
+            self.index,
+            self.generation,
+            name,
+            params.into_token(),
+        );
+        self
+    }
+
+    /// Adds a log with raw topics and data that is emitted by transactions
+    /// matching this expectation.
+    ///
+    /// See [`emits_event`] for more info.
+    ///
+    /// [`emits_event`]: Expectation::emits_event
+    pub fn emits_raw_log(self, log: impl Into<RawLog>) -> Self {
+        self.transport.emits_raw_log::<P, R>(
+            self.address,
+            self.signature,
+            self.index,
+            self.generation,
+            log.into(),
+        );
+        self
+    }
+}
+
+/// Catch-all expectation for calls to any method of a contract.
+///
+/// Created by [`Contract::expect_any_method`]. Unlike [`Expectation`], it is
+/// not tied to a method signature, so predicates receive raw calldata
+/// (including the selector) and return values are raw ABI-encoded bytes.
+///
+/// Catch-all expectations are evaluated in FIFO order after expectations
+/// of the called method, and they follow the same rules regarding
+/// [call counts], [sequences] and modification after use.
+///
+/// [call counts]: Expectation::times
+/// [sequences]: Expectation::in_sequence
+pub struct AnyMethodExpectation {
+    transport: details::MockTransport,
+    address: Address,
+    index: usize,
+    generation: usize,
+}
+
+// See comment on `Expectation` implementation.
+#[allow(unknown_lints)]
+#[allow(clippy::return_self_not_must_use)]
+impl AnyMethodExpectation {
+    fn update(self, update: impl FnOnce(&mut details::AnyExpectation)) -> Self {
+        self.transport
+            .any_expectation(self.address, self.index, self.generation, update);
+        self
+    }
+
+    /// Specifies how many times this expectation can be called.
+    ///
+    /// See [`Expectation::times`] for more info.
+    pub fn times(self, times: impl Into<TimesRange>) -> Self {
+        let times = times.into();
+        self.update(|expectation| expectation.counter.set_times(times))
+    }
+
+    /// Indicates that this expectation can be called exactly zero times.
+    pub fn never(self) -> Self {
+        self.times(0)
+    }
+
+    /// Indicates that this expectation can be called exactly one time.
+    pub fn once(self) -> Self {
+        self.times(1)
+    }
+
+    /// Adds this expectation to a sequence.
+    ///
+    /// See [`Expectation::in_sequence`] for more info.
+    pub fn in_sequence(self, sequence: &mut mockall::Sequence) -> Self {
+        self.update(|expectation| expectation.counter.set_sequence(sequence))
+    }
+
+    /// Sets number of blocks that should be mined on top of the transaction
+    /// block.
+    ///
+    /// See [`Expectation::confirmations`] for more info.
+    pub fn confirmations(self, confirmations: u64) -> Self {
+        self.update(|expectation| expectation.confirmations = confirmations)
+    }
+
+    /// Sets amount of gas consumed by transactions matching
+    /// this expectation.
+    ///
+    /// See [`Expectation::consumes_gas`] for more info.
+    pub fn consumes_gas(self, gas: u64) -> Self {
+        self.update(|expectation| expectation.gas = Some(gas))
+    }
+
+    /// Only match calls to the method with the given selector.
+    ///
+    /// This is useful for methods that are not a part of contract's ABI.
+    pub fn selector(self, selector: H32) -> Self {
+        self.update(|expectation| expectation.selector = Some(selector))
+    }
+
+    /// Adds a predicate on raw calldata, including the method selector.
+    ///
+    /// This method will overwrite any predicate that was set before.
+    pub fn predicate_fn(self, pred: impl Fn(&[u8]) -> bool + Send + 'static) -> Self {
+        self.update(|expectation| {
+            expectation.predicate = details::AnyPredicate::Function(Box::new(pred))
+        })
+    }
+
+    /// Adds a predicate on call context and raw calldata.
+    ///
+    /// This method will overwrite any predicate that was set before.
+    pub fn predicate_fn_ctx(
+        self,
+        pred: impl Fn(&CallContext, &[u8]) -> bool + Send + 'static,
+    ) -> Self {
+        self.update(|expectation| {
+            expectation.predicate = details::AnyPredicate::TxFunction(Box::new(pred))
+        })
+    }
+
+    /// Indicates that this expectation only applies to view calls.
+    ///
+    /// See [`Expectation::allow_calls`] for more info.
+    pub fn allow_calls(self, allow_calls: bool) -> Self {
+        self.update(|expectation| expectation.allow_calls = allow_calls)
+    }
+
+    /// Indicates that this expectation only applies to transactions.
+    ///
+    /// See [`Expectation::allow_transactions`] for more info.
+    pub fn allow_transactions(self, allow_transactions: bool) -> Self {
+        self.update(|expectation| expectation.allow_transactions = allow_transactions)
+    }
+
+    /// Sets raw ABI-encoded return data for calls matching this expectation.
+    ///
+    /// By default, calls return empty data.
+    ///
+    /// This method will overwrite any return value or callback
+    /// that was set before.
+    pub fn returns(self, returns: Vec<u8>) -> Self {
+        self.update(|expectation| expectation.returns = details::AnyReturns::Const(returns))
+    }
+
+    /// Sets callback function that will be used to calculate raw return
+    /// data from raw calldata.
+    ///
+    /// This method will overwrite any return value or callback
+    /// that was set before.
+    pub fn returns_fn(
+        self,
+        returns: impl Fn(&[u8]) -> Result<Vec<u8>, String> + Send + 'static,
+    ) -> Self {
+        self.update(|expectation| {
+            expectation.returns = details::AnyReturns::Function(Box::new(returns))
+        })
+    }
+
+    /// Sets callback function that will be used to calculate raw return
+    /// data from call context and raw calldata.
+    ///
+    /// This method will overwrite any return value or callback
+    /// that was set before.
+    pub fn returns_fn_ctx(
+        self,
+        returns: impl Fn(&CallContext, &[u8]) -> Result<Vec<u8>, String> + Send + 'static,
+    ) -> Self {
+        self.update(|expectation| {
+            expectation.returns = details::AnyReturns::TxFunction(Box::new(returns))
+        })
+    }
+
+    /// Sets return value to an error, meaning that calls to this
+    /// expectation result in reverted transaction.
+    ///
+    /// This method will overwrite any return value or callback
+    /// that was set before.
+    pub fn returns_error(self, error: String) -> Self {
+        self.update(|expectation| expectation.returns = details::AnyReturns::Error(error))
+    }
+
+    /// Sets return value to empty data.
+    ///
+    /// This method will overwrite any return value or callback
+    /// that was set before.
+    pub fn returns_default(self) -> Self {
+        self.update(|expectation| expectation.returns = details::AnyReturns::Default)
+    }
+}
+
+impl ExpectationHandle for AnyMethodExpectation {
+    fn times(self, times: impl Into<TimesRange>) -> Self {
+        AnyMethodExpectation::times(self, times)
+    }
+
+    fn in_sequence(self, sequence: &mut mockall::Sequence) -> Self {
+        AnyMethodExpectation::in_sequence(self, sequence)
+    }
+}
+
+/// Common configuration shared by all kinds of expectations.
+///
+/// This trait allows writing helpers and test DSLs that configure call
+/// counts and ordering without depending on a concrete expectation type.
+/// Custom expectation types can implement it on top of
+/// [`sequence::CallCounter`].
+///
+/// # Examples
+///
+/// ```
+/// # include!("test/doctest/common.rs");
+/// # use ethcontract_mock::ExpectationHandle;
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # let contract = contract();
+/// # let signature = signature();
+/// fn strictly_once<E: ExpectationHandle>(
+///     expectation: E,
+///     sequence: &mut mockall::Sequence,
+/// ) -> E {
+///     expectation.once().in_sequence(sequence)
+/// }
+///
+/// let mut sequence = mockall::Sequence::new();
+/// strictly_once(contract.expect(signature), &mut sequence);
+/// # contract.instance().view_method(signature, (0, 0))?.call().await?;
+/// # Ok(())
+/// # }
+/// ```
+pub trait ExpectationHandle: Sized {
+    /// Specifies how many times this expectation can be called.
+    ///
+    /// See [`Expectation::times`] for more info.
+    fn times(self, times: impl Into<TimesRange>) -> Self;
+
+    /// Adds this expectation to a sequence.
+    ///
+    /// See [`Expectation::in_sequence`] for more info.
+    fn in_sequence(self, sequence: &mut mockall::Sequence) -> Self;
+
+    /// Indicates that this expectation can be called exactly zero times.
+    fn never(self) -> Self {
+        self.times(0)
+    }
+
+    /// Indicates that this expectation can be called exactly one time.
+    fn once(self) -> Self {
+        self.times(1)
+    }
+}
+
+impl<P: Tokenize + Send + 'static, R: Tokenize + Send + 'static> ExpectationHandle
+    for Expectation<P, R>
+{
+    fn times(self, times: impl Into<TimesRange>) -> Self {
+        Expectation::times(self, times)
+    }
+
+    fn in_sequence(self, sequence: &mut mockall::Sequence) -> Self {
+        Expectation::in_sequence(self, sequence)
+    }
 }
 
 /// Information about method call that's being processed.
+#[derive(Clone, Debug)]
 pub struct CallContext {
     /// If `true`, this is a view call, otherwise this is a transaction.
     pub is_view_call: bool,
 
+    /// Number of the block this operation is executed in.
+    ///
+    /// For view calls, this is the block the call was made at, which is the
+    /// latest block unless a historical block was requested. For transactions,
+    /// this is the block that the transaction gets mined in.
+    pub block: u64,
+
     /// Account that issued a view call or a transaction.
     ///
     /// Can be zero in case of a view call.
@@ -971,3 +1657,46 @@ pub struct CallContext {
     /// This value is only non-zero if the method is payable.
     pub value: U256,
 }
+
+/// A call or transaction to a mocked contract that doesn't match any
+/// expectation. See [`Mock::on_unexpected`].
+#[derive(Clone, Debug)]
+pub struct UnexpectedCall {
+    /// Address of the called contract.
+    pub address: Address,
+
+    /// Selector of the called method.
+    pub selector: H32,
+
+    /// ABI signature of the called method, or `None` if the contract doesn't
+    /// have a method with the called selector.
+    pub signature: Option<String>,
+
+    /// Decoded arguments of the call, or `None` if the contract doesn't have
+    /// a method with the called selector.
+    pub params: Option<Vec<Token>>,
+
+    /// Raw call data, including the selector.
+    pub data: Vec<u8>,
+
+    /// Context of the call.
+    pub context: CallContext,
+}
+
+impl std::fmt::Display for UnexpectedCall {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.signature {
+            Some(signature) => write!(
+                f,
+                "unexpected call to {:?} on contract {:#x}",
+                signature, self.address
+            ),
+            None => write!(
+                f,
+                "contract {:#x} doesn't have method with signature 0x{}",
+                self.address,
+                hex::encode(self.selector)
+            ),
+        }
+    }
+}
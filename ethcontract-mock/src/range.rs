@@ -1,7 +1,12 @@
 //! Helpers for working with rust's ranges.
 //!
-//! Note: contents of this module are meant to be used via the [`Into`] trait.
-//! They are not a part of public API.
+//! Contents of this module are usually used via the [`Into`] trait, e.g.
+//! when passing a range to [`Expectation::times`]. [`TimesRange`] can also
+//! be used directly when building custom expectation types, see
+//! [`sequence`] module.
+//!
+//! [`Expectation::times`]: crate::Expectation::times
+//! [`sequence`]: crate::sequence
 
 use std::ops::{Range, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive};
 
@@ -15,6 +20,17 @@ use std::ops::{Range, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToIncl
 pub struct TimesRange(usize, usize);
 
 impl TimesRange {
+    /// Creates a new range with an inclusive lower bound and an exclusive
+    /// upper bound.
+    ///
+    /// # Panics
+    ///
+    /// If the range is empty.
+    pub fn new(lower: usize, upper: usize) -> Self {
+        assert!(upper > lower, "backwards range");
+        TimesRange(lower, upper)
+    }
+
     /// Checks if expectation can be called if it was already called
     /// this number of times.
     pub fn can_call(&self, x: usize) -> bool {
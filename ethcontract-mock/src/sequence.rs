@@ -0,0 +1,174 @@
+//! Call counting and sequencing logic used by mock expectations.
+//!
+//! This module exposes the bookkeeping that backs [`Expectation::times`]
+//! and [`Expectation::in_sequence`], so that custom expectation types and
+//! higher-level test DSLs can enforce the same call count limits and
+//! ordering guarantees as contract method expectations.
+//!
+//! [`Expectation::times`]: crate::Expectation::times
+//! [`Expectation::in_sequence`]: crate::Expectation::in_sequence
+
+use crate::range::TimesRange;
+
+/// Tracks how many times an expectation was called and checks that
+/// calls happen in the order imposed by a [`mockall::Sequence`].
+///
+/// # Examples
+///
+/// ```
+/// # use ethcontract_mock::sequence::CallCounter;
+/// let mut sequence = mockall::Sequence::new();
+///
+/// let mut first = CallCounter::new();
+/// first.set_times(1.into());
+/// first.set_sequence(&mut sequence);
+///
+/// let mut second = CallCounter::new();
+/// second.set_times(2.into());
+/// second.set_sequence(&mut sequence);
+///
+/// first.record_call("first");
+/// second.record_call("second");
+/// second.record_call("second");
+///
+/// assert!(first.verify("first").is_ok());
+/// assert!(second.verify("second").is_ok());
+/// ```
+pub struct CallCounter {
+    /// How many times should the expectation be called.
+    times: TimesRange,
+
+    /// How many times was it actually called.
+    used: usize,
+
+    /// Handle for when the expectation belongs to a sequence.
+    sequence: Option<mockall::SeqHandle>,
+}
+
+impl CallCounter {
+    /// Creates a new counter that allows any number of calls and does not
+    /// belong to a sequence.
+    pub fn new() -> Self {
+        CallCounter {
+            times: TimesRange::default(),
+            used: 0,
+            sequence: None,
+        }
+    }
+
+    /// Returns the range of allowed number of calls.
+    pub fn times(&self) -> &TimesRange {
+        &self.times
+    }
+
+    /// Returns how many calls were recorded so far.
+    pub fn used(&self) -> usize {
+        self.used
+    }
+
+    /// Returns `true` if this counter belongs to a sequence.
+    pub fn in_sequence(&self) -> bool {
+        self.sequence.is_some()
+    }
+
+    /// Sets the range of allowed number of calls.
+    ///
+    /// # Panics
+    ///
+    /// If this counter belongs to a sequence and the given range is not
+    /// an exact number of calls greater than zero.
+    pub fn set_times(&mut self, times: TimesRange) {
+        if self.sequence.is_some() && !times.is_exact() {
+            panic!("only expectations with an exact call count can be in a sequences")
+        }
+        if self.sequence.is_some() && times.lower_bound() == 0 {
+            panic!("expectation in a sequences should be called at least once")
+        }
+
+        self.times = times;
+    }
+
+    /// Adds this counter to a sequence.
+    ///
+    /// # Panics
+    ///
+    /// If this counter is already in a sequence, or if its call count
+    /// is not an exact number greater than zero.
+    pub fn set_sequence(&mut self, sequence: &mut mockall::Sequence) {
+        if !self.times.is_exact() {
+            panic!("only expectations with an exact call count can be in a sequences")
+        }
+        if self.times.lower_bound() == 0 {
+            panic!("expectation in a sequences should be called at least once")
+        }
+        if self.sequence.is_some() {
+            panic!("expectation can't be in multiple sequences")
+        }
+
+        self.sequence = Some(sequence.next_handle());
+    }
+
+    /// Checks if the expectation can accept one more call.
+    pub fn is_active(&self) -> bool {
+        self.times.can_call(self.used)
+    }
+
+    /// Records a call to the expectation.
+    ///
+    /// # Panics
+    ///
+    /// If the call happened out of order with respect to the sequence
+    /// this counter belongs to.
+    pub fn record_call(&mut self, description: &str) {
+        self.used += 1;
+        if let Some(sequence) = &self.sequence {
+            sequence.verify(description);
+
+            if self.used == self.times.lower_bound() {
+                sequence.satisfy();
+            }
+        }
+    }
+
+    /// Verifies that the number of recorded calls is within the allowed
+    /// range, returning a human-readable error message if it is not.
+    pub fn verify(&self, description: &str) -> Result<(), String> {
+        if !self.times.contains(self.used) {
+            return Err(format!(
+                "{} was called {} {}, but it was expected to be called {} {} {}",
+                description,
+                self.used,
+                if self.used == 1 { "time" } else { "times" },
+                if self.times.is_exact() {
+                    "exactly"
+                } else {
+                    "at least"
+                },
+                self.times.lower_bound(),
+                if self.times.lower_bound() == 1 {
+                    "time"
+                } else {
+                    "times"
+                }
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for CallCounter {
+    fn default() -> Self {
+        CallCounter::new()
+    }
+}
+
+impl std::fmt::Debug for CallCounter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CallCounter")
+            .field("times", &self.times)
+            .field("used", &self.used)
+            .field("in_sequence", &self.sequence.is_some())
+            .finish()
+    }
+}
@@ -0,0 +1,128 @@
+use super::*;
+
+#[tokio::test]
+async fn valid_for_blocks() -> Result {
+    let (_mock, _, contract, instance) = setup();
+
+    contract
+        .expect(ERC20::signatures().balance_of())
+        .valid_for_blocks(1)
+        .returns(U256::from(1));
+    contract
+        .expect(ERC20::signatures().balance_of())
+        .returns(U256::from(2));
+    contract
+        .expect(ERC20::signatures().transfer())
+        .returns(true);
+
+    assert_eq!(
+        instance.balance_of(address_for("Bob")).call().await?,
+        1.into()
+    );
+
+    instance
+        .transfer(address_for("Bob"), U256::from(100))
+        .send()
+        .await?;
+    assert_eq!(
+        instance.balance_of(address_for("Bob")).call().await?,
+        1.into()
+    );
+
+    instance
+        .transfer(address_for("Bob"), U256::from(100))
+        .send()
+        .await?;
+    assert_eq!(
+        instance.balance_of(address_for("Bob")).call().await?,
+        2.into()
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn valid_until_block_for_transactions() -> Result {
+    let (_mock, _, contract, instance) = setup();
+
+    contract
+        .expect(ERC20::signatures().transfer())
+        .once()
+        .valid_until_block(1)
+        .returns_fn_ctx(|ctx, _| {
+            assert_eq!(ctx.block, 1);
+            Ok(true)
+        });
+    contract
+        .expect(ERC20::signatures().transfer())
+        .once()
+        .returns_fn_ctx(|ctx, _| {
+            assert_eq!(ctx.block, 2);
+            Ok(true)
+        });
+
+    for _ in 0..2 {
+        instance
+            .transfer(address_for("Bob"), U256::from(100))
+            .send()
+            .await?;
+    }
+
+    contract.checkpoint();
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn historical_block_calls() -> Result {
+    let (_mock, _, contract, instance) = setup();
+
+    contract
+        .expect(ERC20::signatures().balance_of())
+        .valid_until_block(1)
+        .returns(U256::from(1));
+    contract
+        .expect(ERC20::signatures().balance_of())
+        .returns_fn_ctx(|ctx, _| Ok(U256::from(ctx.block * 10)));
+    contract
+        .expect(ERC20::signatures().transfer())
+        .returns(true);
+
+    for _ in 0..3 {
+        instance
+            .transfer(address_for("Bob"), U256::from(100))
+            .send()
+            .await?;
+    }
+
+    let balance_at = |block: BlockNumber| {
+        instance
+            .balance_of(address_for("Bob"))
+            .block(block.into())
+            .call()
+    };
+    assert_eq!(balance_at(BlockNumber::Earliest).await?, 1.into());
+    assert_eq!(balance_at(BlockNumber::Number(1.into())).await?, 1.into());
+    assert_eq!(balance_at(BlockNumber::Number(2.into())).await?, 20.into());
+    assert_eq!(balance_at(BlockNumber::Latest).await?, 30.into());
+    assert_eq!(balance_at(BlockNumber::Pending).await?, 30.into());
+
+    Ok(())
+}
+
+#[tokio::test]
+#[should_panic(expected = "mock node does not support executing methods on future blocks")]
+async fn future_block_calls_are_not_supported() {
+    let (_mock, _, contract, instance) = setup();
+
+    contract
+        .expect(ERC20::signatures().balance_of())
+        .returns(U256::from(1));
+
+    instance
+        .balance_of(address_for("Bob"))
+        .block(BlockNumber::Number(1.into()).into())
+        .call()
+        .await
+        .unwrap();
+}
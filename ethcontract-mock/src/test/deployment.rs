@@ -0,0 +1,127 @@
+use super::*;
+use ethcontract::common::Bytecode;
+use ethcontract::contract::{DeployBuilder, LinkedDeployer, Linker};
+use ethcontract::dyns::{DynInstance, DynTransport};
+use struct_constructor::structs::{Fee, Order};
+
+ethcontract::contract!("examples/truffle/build/contracts/StructConstructor.json");
+
+fn fee(amount: u64) -> Fee {
+    Fee {
+        amount: amount.into(),
+    }
+}
+
+fn params() -> (String, String) {
+    ("Token".to_string(), "TKN".to_string())
+}
+
+fn linker() -> Linker {
+    let mut contract = ERC20::raw_contract().clone();
+    contract.bytecode = Bytecode::from_hex_str("0x6080604052").unwrap();
+    Linker::new(contract)
+}
+
+#[tokio::test]
+async fn deploy_contract() -> Result {
+    let mock = Mock::new(1234);
+    let contract = mock.expect_deployment(ERC20::raw_contract().interface.abi.clone());
+    contract
+        .expect_call(ERC20::signatures().total_supply())
+        .returns(U256::from(42));
+
+    let instance: DynInstance = DeployBuilder::new(mock.web3(), linker(), params())?
+        .from(account_for("Alice"))
+        .deploy()
+        .await?;
+    assert_eq!(instance.address(), contract.address());
+
+    let token = ERC20::at(&mock.web3(), instance.address());
+    assert_eq!(token.total_supply().call().await?, 42.into());
+
+    Ok(())
+}
+
+#[tokio::test]
+#[should_panic(expected = "unexpected contract deployment")]
+async fn unexpected_deployment() {
+    let mock = Mock::new(1234);
+
+    DeployBuilder::<DynTransport, DynInstance>::new(mock.web3(), linker(), params())
+        .unwrap()
+        .from(account_for("Alice"))
+        .deploy()
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+#[should_panic(expected = "was never performed")]
+async fn missing_deployment() {
+    let mock = Mock::new(1234);
+    mock.expect_deployment(ERC20::raw_contract().interface.abi.clone());
+
+    mock.checkpoint();
+}
+
+#[tokio::test]
+async fn deploy_contract_with_struct_arguments() -> Result {
+    let mock = Mock::new(1234);
+    let contract = mock.expect_deployment(StructConstructor::raw_contract().interface.abi.clone());
+    let order = Order {
+        owner: address_for("Alice"),
+        fees: vec![fee(1), fee(2)],
+    };
+    let extra_fees = vec![fee(3)];
+
+    let builder = StructConstructor::builder(&mock.web3(), order.clone(), extra_fees.clone())
+        .from(account_for("Alice"));
+    let data = builder.clone().into_inner().data.unwrap();
+    assert_eq!(
+        StructConstructor::decode_constructor(&data.0)?,
+        (order, extra_fees),
+    );
+
+    let instance = builder.deploy().await?;
+    assert_eq!(instance.address(), contract.address());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn deploy_contract_with_linked_libraries() -> Result {
+    let mock = Mock::new(1234);
+    let library = |name: &str, dependencies: &[&str]| {
+        let code = dependencies
+            .iter()
+            .map(|dependency| format!("73__{:_<38}", dependency))
+            .collect::<String>();
+        ethcontract::Contract {
+            name: name.to_owned(),
+            bytecode: Bytecode::from_hex_str(&format!("6080{}", code)).unwrap(),
+            ..ethcontract::Contract::empty()
+        }
+    };
+    let mut contract = library("Token", &["Math", "Strings"]);
+    contract.interface = ERC20::raw_contract().interface.clone();
+
+    let strings = mock.expect_deployment(Default::default());
+    let math = mock.expect_deployment(Default::default());
+    let token = mock.expect_deployment(ERC20::raw_contract().interface.abi.clone());
+
+    let deployment = LinkedDeployer::new(mock.web3(), contract)
+        .library(library("Math", &["Strings"]))
+        .library(library("Strings", &[]))
+        .from(account_for("Alice"))
+        .deploy(params())
+        .await?;
+
+    assert_eq!(deployment.instance.address(), token.address());
+    assert_eq!(deployment.libraries.len(), 2);
+    assert_eq!(deployment.libraries["Math"], math.address());
+    assert_eq!(deployment.libraries["Strings"], strings.address());
+
+    mock.checkpoint();
+
+    Ok(())
+}
@@ -0,0 +1,345 @@
+use super::*;
+use ethcontract::transaction::ResolveCondition;
+use ethcontract::web3::types::FilterBuilder;
+use ethcontract::RawLog;
+use ethcontract_core::abi::{self, Token};
+
+#[tokio::test]
+async fn transaction_emits_events() -> Result {
+    let (_, web3, contract, instance) = setup();
+
+    contract
+        .expect_transaction(ERC20::signatures().transfer())
+        .emits_event(
+            "Transfer",
+            (address_for("Alice"), address_for("Bob"), U256::from(100)),
+        )
+        .returns(true);
+
+    let receipt = instance
+        .transfer(address_for("Bob"), 100.into())
+        .send()
+        .await?
+        .as_receipt()
+        .unwrap()
+        .clone();
+    assert_eq!(receipt.logs.len(), 1);
+    assert_eq!(receipt.logs[0].address, contract.address());
+    assert_eq!(
+        receipt.logs[0].transaction_hash,
+        Some(receipt.transaction_hash)
+    );
+    assert_eq!(receipt.logs[0].block_hash, receipt.block_hash);
+
+    let events = instance.events().transfer().query().await?;
+    assert_eq!(events.len(), 1);
+    let transfer = &events[0].data;
+    assert_eq!(transfer.from, address_for("Alice"));
+    assert_eq!(transfer.to, address_for("Bob"));
+    assert_eq!(transfer.value, U256::from(100));
+
+    let logs = web3
+        .eth()
+        .logs(
+            FilterBuilder::default()
+                .address(vec![contract.address()])
+                .build(),
+        )
+        .await?;
+    assert_eq!(logs, receipt.logs);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn parse_logs_from_receipt() -> Result {
+    let (_, _, contract, instance) = setup();
+
+    contract
+        .expect_transaction(ERC20::signatures().transfer())
+        .emits_raw_log(RawLog {
+            topics: vec![H256::repeat_byte(1)],
+            data: Vec::new(),
+        })
+        .emits_event(
+            "Transfer",
+            (address_for("Alice"), address_for("Bob"), U256::from(100)),
+        )
+        .returns(true);
+
+    let receipt = instance
+        .transfer(address_for("Bob"), 100.into())
+        .send()
+        .await?
+        .as_receipt()
+        .unwrap()
+        .clone();
+
+    let events = instance.parse_logs(&receipt)?;
+    assert_eq!(events.len(), 1);
+    match &events[0].data {
+        erc20::Event::Transfer(transfer) => {
+            assert_eq!(transfer.from, address_for("Alice"));
+            assert_eq!(transfer.to, address_for("Bob"));
+            assert_eq!(transfer.value, U256::from(100));
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+    assert_eq!(events[0].meta.as_ref().unwrap().log_index, 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn parse_logs_fails_on_invalid_known_event() -> Result {
+    let (_, _, contract, instance) = setup();
+
+    let transfer = ERC20::raw_contract()
+        .interface
+        .abi
+        .event("Transfer")?
+        .signature();
+    contract
+        .expect_transaction(ERC20::signatures().transfer())
+        .emits_raw_log(RawLog {
+            topics: vec![transfer],
+            data: Vec::new(),
+        })
+        .returns(true);
+
+    let receipt = instance
+        .transfer(address_for("Bob"), 100.into())
+        .send()
+        .await?
+        .as_receipt()
+        .unwrap()
+        .clone();
+
+    assert!(instance.parse_logs(&receipt).is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn send_and_decode_events() -> Result {
+    let (_, _, contract, instance) = setup();
+
+    contract
+        .expect_transaction(ERC20::signatures().transfer())
+        .emits_event(
+            "Transfer",
+            (address_for("Alice"), address_for("Bob"), U256::from(100)),
+        )
+        .returns(true);
+
+    let mut method = instance.transfer(address_for("Bob"), 100.into());
+    method.tx = method.tx.resolve(ResolveCondition::Pending);
+    let (receipt, events) = method.send_and_decode_events::<erc20::Event>().await?;
+
+    assert_eq!(receipt.logs.len(), 1);
+    assert_eq!(events.len(), 1);
+    assert_eq!(
+        events[0].meta.as_ref().unwrap().transaction_hash,
+        receipt.transaction_hash
+    );
+    assert!(
+        matches!(&events[0].data, erc20::Event::Transfer(transfer) if transfer.value == U256::from(100))
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn transaction_emits_raw_logs() -> Result {
+    let (_, web3, contract, instance) = setup();
+
+    let log = RawLog {
+        topics: vec![H256::repeat_byte(1)],
+        data: abi::encode(&[Token::Uint(42.into())]),
+    };
+    contract
+        .expect_transaction(ERC20::signatures().approve())
+        .emits_raw_log(log.clone())
+        .emits_raw_log(log.clone())
+        .returns(true);
+
+    instance
+        .approve(address_for("Bob"), 100.into())
+        .send()
+        .await?;
+
+    let logs = web3
+        .eth()
+        .logs(
+            FilterBuilder::default()
+                .topics(Some(vec![H256::repeat_byte(1)]), None, None, None)
+                .build(),
+        )
+        .await?;
+    assert_eq!(logs.len(), 2);
+    assert_eq!(logs[0].topics, log.topics);
+    assert_eq!(logs[0].data.0, log.data);
+    assert_eq!(logs[1].log_index, Some(1.into()));
+
+    let logs = web3
+        .eth()
+        .logs(
+            FilterBuilder::default()
+                .topics(Some(vec![H256::repeat_byte(2)]), None, None, None)
+                .build(),
+        )
+        .await?;
+    assert!(logs.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn logs_are_filtered_by_block() -> Result {
+    let (_, web3, contract, instance) = setup();
+
+    contract
+        .expect_transaction(ERC20::signatures().transfer())
+        .emits_event(
+            "Transfer",
+            (address_for("Alice"), address_for("Bob"), U256::from(100)),
+        )
+        .returns(true);
+
+    instance
+        .transfer(address_for("Bob"), 100.into())
+        .send()
+        .await?;
+    instance
+        .transfer(address_for("Bob"), 100.into())
+        .send()
+        .await?;
+
+    let logs = web3
+        .eth()
+        .logs(
+            FilterBuilder::default()
+                .from_block(BlockNumber::Earliest)
+                .to_block(BlockNumber::Number(1.into()))
+                .build(),
+        )
+        .await?;
+    assert_eq!(logs.len(), 1);
+    assert_eq!(logs[0].block_number, Some(1.into()));
+
+    let logs = web3
+        .eth()
+        .logs(
+            FilterBuilder::default()
+                .from_block(BlockNumber::Earliest)
+                .build(),
+        )
+        .await?;
+    assert_eq!(logs.len(), 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn view_calls_and_reverted_transactions_do_not_emit_events() -> Result {
+    let (_, web3, contract, instance) = setup();
+
+    contract
+        .expect(ERC20::signatures().transfer())
+        .emits_event(
+            "Transfer",
+            (address_for("Alice"), address_for("Bob"), U256::from(100)),
+        )
+        .returns(true);
+    contract
+        .expect_transaction(ERC20::signatures().approve())
+        .emits_event(
+            "Approval",
+            (address_for("Alice"), address_for("Bob"), U256::from(100)),
+        )
+        .returns_error("reverted".into());
+
+    instance
+        .transfer(address_for("Bob"), 100.into())
+        .call()
+        .await?;
+    assert!(instance
+        .approve(address_for("Bob"), 100.into())
+        .send()
+        .await
+        .is_err());
+
+    let logs = web3
+        .eth()
+        .logs(
+            FilterBuilder::default()
+                .from_block(BlockNumber::Earliest)
+                .build(),
+        )
+        .await?;
+    assert!(logs.is_empty());
+
+    Ok(())
+}
+
+#[test]
+#[should_panic(expected = "doesn't have event \"Swap\"")]
+fn unknown_event() {
+    let (_, _, contract, _) = setup();
+
+    contract
+        .expect_transaction(ERC20::signatures().transfer())
+        .emits_event("Swap", ());
+}
+
+#[test]
+#[should_panic(expected = "parameter \"value\" of event \"Transfer\" should have type uint256")]
+fn event_parameter_type_mismatch() {
+    let (_, _, contract, _) = setup();
+
+    contract
+        .expect_transaction(ERC20::signatures().transfer())
+        .emits_event("Transfer", (address_for("Alice"), address_for("Bob"), true));
+}
+
+ethcontract::contract!(
+    "examples/truffle/build/contracts/ERC20.json",
+    mod = non_exhaustive_erc20,
+    contract = ERC20 as NonExhaustiveERC20,
+    non_exhaustive_events = true,
+    event_derives (serde::Deserialize, serde::Serialize),
+);
+
+#[test]
+fn non_exhaustive_events_only_parse_unknown_topics_as_unknown() {
+    use ethcontract::contract::ParseLog;
+    use non_exhaustive_erc20::{event_data, Event};
+
+    let unknown = RawLog {
+        topics: vec![H256::repeat_byte(0x42)],
+        data: vec![],
+    };
+    assert_eq!(
+        Event::parse_log(unknown.clone()).unwrap(),
+        Event::Unknown(unknown)
+    );
+
+    // a transfer log without indexed addresses or a value
+    let malformed = RawLog {
+        topics: vec![event_data::Transfer::signature()],
+        data: vec![],
+    };
+    assert!(Event::parse_log(malformed).is_err());
+}
+
+#[test]
+fn non_exhaustive_events_with_serde_derives() {
+    use non_exhaustive_erc20::Event;
+
+    let unknown = Event::Unknown(RawLog {
+        topics: vec![H256::repeat_byte(0x42)],
+        data: vec![1, 2, 3],
+    });
+    let json = serde_json::to_string(&unknown).unwrap();
+    assert_eq!(serde_json::from_str::<Event>(&json).unwrap(), unknown);
+}
@@ -137,3 +137,117 @@ async fn estimate_gas_is_not_supported_for_earliest_block() {
         .await
         .unwrap();
 }
+
+#[tokio::test]
+async fn estimate_gas_returns_consumed_gas() -> Result {
+    let (_, _, contract, instance) = setup();
+
+    contract
+        .expect(ERC20::signatures().transfer())
+        .once()
+        .predicate((predicate::eq(address_for("Bob")), predicate::always()))
+        .consumes_gas(50_000);
+    contract
+        .expect(ERC20::signatures().transfer())
+        .consumes_gas(30_000);
+
+    let gas = instance
+        .transfer(address_for("Bob"), 100.into())
+        .into_inner()
+        .estimate_gas()
+        .await?;
+    assert_eq!(gas, 50_000.into());
+
+    let gas = instance
+        .transfer(address_for("Alice"), 100.into())
+        .into_inner()
+        .estimate_gas()
+        .await?;
+    assert_eq!(gas, 30_000.into());
+
+    // Estimation doesn't count as a call, so the first expectation
+    // is still active.
+    let gas = instance
+        .transfer(address_for("Bob"), 100.into())
+        .into_inner()
+        .estimate_gas()
+        .await?;
+    assert_eq!(gas, 50_000.into());
+
+    instance
+        .transfer(address_for("Bob"), 100.into())
+        .send()
+        .await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn estimate_gas_ignores_call_expectations() -> Result {
+    let (_, _, contract, instance) = setup();
+
+    contract
+        .expect_call(ERC20::signatures().transfer())
+        .consumes_gas(50_000);
+
+    let gas = instance
+        .transfer(address_for("Bob"), 100.into())
+        .into_inner()
+        .estimate_gas()
+        .await?;
+    assert_eq!(gas, 1.into());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn estimate_gas_for_any_method() -> Result {
+    let (_, _, contract, instance) = setup();
+
+    contract.expect_any_method().consumes_gas(21_000);
+
+    let gas = instance
+        .transfer(address_for("Bob"), 100.into())
+        .into_inner()
+        .estimate_gas()
+        .await?;
+    assert_eq!(gas, 21_000.into());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn receipt_reports_consumed_gas() -> Result {
+    let (_, web3, contract, instance) = setup();
+
+    contract
+        .expect(ERC20::signatures().transfer())
+        .once()
+        .consumes_gas(50_000);
+    contract.expect(ERC20::signatures().approve()).once();
+
+    let result = instance
+        .transfer(address_for("Bob"), 100.into())
+        .send()
+        .await?;
+    let receipt = web3
+        .eth()
+        .transaction_receipt(result.hash())
+        .await?
+        .unwrap();
+    assert_eq!(receipt.gas_used, Some(50_000.into()));
+    assert_eq!(receipt.cumulative_gas_used, 50_000.into());
+
+    let result = instance
+        .approve(address_for("Bob"), 100.into())
+        .send()
+        .await?;
+    let receipt = web3
+        .eth()
+        .transaction_receipt(result.hash())
+        .await?
+        .unwrap();
+    assert_eq!(receipt.gas_used, None);
+
+    Ok(())
+}
@@ -0,0 +1,107 @@
+use super::*;
+use ethcontract::web3::Transport as _;
+
+#[tokio::test]
+async fn fee_history_defaults_to_gas_price() -> Result {
+    let mock = Mock::new(1234);
+    let web3 = mock.web3();
+
+    mock.update_gas_price(10);
+
+    let history = web3
+        .eth()
+        .fee_history(10.into(), BlockNumber::Latest, Some(vec![50.0]))
+        .await?;
+
+    assert_eq!(history.oldest_block, BlockNumber::Number(0.into()));
+    assert_eq!(history.base_fee_per_gas, vec![10.into(), 10.into()]);
+    assert_eq!(history.gas_used_ratio.len(), 1);
+    assert_eq!(history.reward, Some(vec![vec![0.into()]]));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn fee_history_reports_recent_blocks() -> Result {
+    let (mock, web3, contract, instance) = setup();
+
+    contract
+        .expect(ERC20::signatures().transfer())
+        .confirmations(5);
+    instance
+        .transfer(address_for("Alice"), 100.into())
+        .send()
+        .await?;
+
+    mock.set_fee_history([(100, 1), (110, 3), (120, 2)]);
+
+    let history = web3
+        .eth()
+        .fee_history(4.into(), BlockNumber::Latest, Some(vec![25.0, 75.0]))
+        .await?;
+
+    assert_eq!(history.oldest_block, BlockNumber::Number(3.into()));
+    assert_eq!(
+        history.base_fee_per_gas,
+        vec![100.into(), 100.into(), 110.into(), 120.into(), 120.into()]
+    );
+    assert_eq!(history.gas_used_ratio.len(), 4);
+    assert_eq!(
+        history.reward,
+        Some(vec![
+            vec![1.into(), 1.into()],
+            vec![1.into(), 1.into()],
+            vec![3.into(), 3.into()],
+            vec![2.into(), 2.into()],
+        ])
+    );
+
+    let history = web3
+        .eth()
+        .fee_history(2.into(), BlockNumber::Number(4.into()), None)
+        .await?;
+
+    assert_eq!(history.oldest_block, BlockNumber::Number(3.into()));
+    assert_eq!(history.reward, None);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn fee_estimation_is_deterministic() -> Result {
+    let mock = Mock::new(1234);
+    let web3 = mock.web3();
+
+    mock.set_fee_history([(100, 1), (110, 3), (120, 2)]);
+
+    assert_eq!(
+        GasPrice::estimate_eip1559(&web3).await?,
+        GasPrice::Eip1559 {
+            max_fee_per_gas: 242.into(),
+            max_priority_fee_per_gas: 2.into(),
+        }
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn max_priority_fee_per_gas() -> Result {
+    let mock = Mock::new(1234);
+    let web3 = mock.web3();
+
+    let max_priority_fee_per_gas = || async {
+        web3.transport()
+            .execute("eth_maxPriorityFeePerGas", vec![])
+            .await
+            .map(|value| ethcontract::json::from_value::<U256>(value).unwrap())
+    };
+
+    assert_eq!(max_priority_fee_per_gas().await?, 1.into());
+
+    mock.update_max_priority_fee_per_gas(10);
+
+    assert_eq!(max_priority_fee_per_gas().await?, 10.into());
+
+    Ok(())
+}
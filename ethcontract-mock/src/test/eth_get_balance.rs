@@ -0,0 +1,211 @@
+use super::*;
+use ethcontract::web3::types::{Bytes, TransactionRequest};
+
+#[tokio::test]
+async fn balance_initially_zero() -> Result {
+    let web3 = Mock::new(1234).web3();
+
+    assert_eq!(
+        web3.eth().balance(address_for("Alice"), None).await?,
+        0.into()
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn set_balance() -> Result {
+    let mock = Mock::new(1234);
+    let web3 = mock.web3();
+
+    mock.set_balance(address_for("Alice"), 100.into());
+
+    assert_eq!(
+        web3.eth().balance(address_for("Alice"), None).await?,
+        100.into()
+    );
+    assert_eq!(
+        web3.eth()
+            .balance(address_for("Alice"), Some(BlockNumber::Pending))
+            .await?,
+        100.into()
+    );
+    assert_eq!(
+        web3.eth()
+            .balance(address_for("Alice"), Some(BlockNumber::Number(0.into())))
+            .await?,
+        100.into()
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+#[should_panic(expected = "mock node does not support returning balance for specific block number")]
+async fn balance_is_not_supported_for_custom_block() {
+    let web3 = Mock::new(1234).web3();
+
+    web3.eth()
+        .balance(address_for("Alice"), Some(BlockNumber::Number(1.into())))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn transaction_charges_gas_fees() -> Result {
+    let (mock, web3, contract, instance) = setup();
+
+    mock.set_balance(address_for("Alice"), 1_000_000.into());
+    mock.update_gas_price(3);
+    contract
+        .expect(ERC20::signatures().transfer())
+        .consumes_gas(1000);
+
+    instance
+        .transfer(address_for("Bob"), 100.into())
+        .send()
+        .await?;
+
+    assert_eq!(
+        web3.eth().balance(address_for("Alice"), None).await?,
+        997_000.into()
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn gas_fees_are_charged_up_to_balance() -> Result {
+    let (mock, web3, contract, instance) = setup();
+
+    mock.set_balance(address_for("Alice"), 1.into());
+    mock.update_gas_price(3);
+    contract.expect(ERC20::signatures().transfer());
+
+    instance
+        .transfer(address_for("Bob"), 100.into())
+        .send()
+        .await?;
+
+    assert_eq!(
+        web3.eth().balance(address_for("Alice"), None).await?,
+        0.into()
+    );
+
+    Ok(())
+}
+
+fn payable_call(contract: &Contract, value: u64) -> TransactionRequest {
+    TransactionRequest {
+        from: address_for("Alice"),
+        to: Some(contract.address()),
+        gas_price: Some(1.into()),
+        value: Some(value.into()),
+        data: Some(Bytes(vec![1, 2, 3, 4])),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn transaction_transfers_value() -> Result {
+    let (mock, web3, contract, _) = setup();
+
+    mock.add_account(private_key_for("Alice"));
+    mock.set_balance(address_for("Alice"), 1000.into());
+    contract.expect_any_method().consumes_gas(10);
+
+    web3.eth()
+        .send_transaction(payable_call(&contract, 100))
+        .await?;
+
+    assert_eq!(
+        web3.eth().balance(address_for("Alice"), None).await?,
+        890.into()
+    );
+    assert_eq!(
+        web3.eth().balance(contract.address(), None).await?,
+        100.into()
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn reverted_transaction_does_not_transfer_value() -> Result {
+    let (mock, web3, contract, _) = setup();
+
+    mock.add_account(private_key_for("Alice"));
+    mock.set_balance(address_for("Alice"), 1000.into());
+    contract
+        .expect_any_method()
+        .consumes_gas(10)
+        .returns_error("reverted".into());
+
+    web3.eth()
+        .send_transaction(payable_call(&contract, 100))
+        .await?;
+
+    assert_eq!(
+        web3.eth().balance(address_for("Alice"), None).await?,
+        990.into()
+    );
+    assert_eq!(
+        web3.eth().balance(contract.address(), None).await?,
+        0.into()
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn transaction_with_insufficient_funds() -> Result {
+    let (mock, web3, contract, _) = setup();
+
+    mock.add_account(private_key_for("Alice"));
+    mock.set_balance(address_for("Alice"), 99.into());
+    contract.expect_any_method().never();
+
+    let err = web3
+        .eth()
+        .send_transaction(payable_call(&contract, 100))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("insufficient funds"));
+
+    // The rejected transaction does not use up the sender's nonce.
+    assert_eq!(
+        web3.eth()
+            .transaction_count(address_for("Alice"), None)
+            .await?,
+        0.into()
+    );
+    assert_eq!(
+        web3.eth().balance(address_for("Alice"), None).await?,
+        99.into()
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn unfunded_account_can_send_value() -> Result {
+    let (mock, web3, contract, _) = setup();
+
+    mock.add_account(private_key_for("Alice"));
+    contract.expect_any_method();
+
+    web3.eth()
+        .send_transaction(payable_call(&contract, 100))
+        .await?;
+
+    assert_eq!(
+        web3.eth().balance(address_for("Alice"), None).await?,
+        0.into()
+    );
+    assert_eq!(
+        web3.eth().balance(contract.address(), None).await?,
+        100.into()
+    );
+
+    Ok(())
+}
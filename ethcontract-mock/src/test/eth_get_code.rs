@@ -0,0 +1,90 @@
+use super::*;
+use ethcontract::common::Bytecode;
+use ethcontract::contract::{DeployBuilder, Linker};
+use ethcontract::dyns::DynInstance;
+use ethcontract::web3::types::Bytes;
+
+#[tokio::test]
+async fn code_for_deployed_contract() -> Result {
+    let (_, web3, contract, _) = setup();
+
+    assert_eq!(
+        web3.eth().code(contract.address(), None).await?,
+        Bytes(vec![0xfe])
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn code_for_unknown_address() -> Result {
+    let web3 = Mock::new(1234).web3();
+
+    assert_eq!(
+        web3.eth().code(address_for("Alice"), None).await?,
+        Bytes(vec![])
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn set_code() -> Result {
+    let (_, web3, contract, _) = setup();
+
+    contract.set_code(vec![0x60, 0x80, 0x60, 0x40]);
+
+    assert_eq!(
+        web3.eth().code(contract.address(), None).await?,
+        Bytes(vec![0x60, 0x80, 0x60, 0x40])
+    );
+    assert_eq!(
+        web3.eth()
+            .code(contract.address(), Some(BlockNumber::Number(0.into())))
+            .await?,
+        Bytes(vec![0x60, 0x80, 0x60, 0x40])
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn no_code_before_deployment() -> Result {
+    let mock = Mock::new(1234);
+    let web3 = mock.web3();
+    let contract = mock.expect_deployment(ERC20::raw_contract().interface.abi.clone());
+
+    assert_eq!(
+        web3.eth().code(contract.address(), None).await?,
+        Bytes(vec![])
+    );
+
+    let mut linker = ERC20::raw_contract().clone();
+    linker.bytecode = Bytecode::from_hex_str("0x6080604052").unwrap();
+    let _: DynInstance = DeployBuilder::new(
+        web3.clone(),
+        Linker::new(linker),
+        ("Token".to_string(), "TKN".to_string()),
+    )?
+    .from(account_for("Alice"))
+    .deploy()
+    .await?;
+
+    assert_eq!(
+        web3.eth().code(contract.address(), None).await?,
+        Bytes(vec![0xfe])
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+#[should_panic(expected = "mock node does not support returning code for specific block number")]
+async fn code_is_not_supported_for_custom_block() {
+    let (_, web3, contract, _) = setup();
+
+    web3.eth()
+        .code(contract.address(), Some(BlockNumber::Number(1.into())))
+        .await
+        .unwrap();
+}
@@ -1,13 +1,18 @@
-use crate::Mock;
-use ethcontract::web3::types::TransactionRequest;
+use super::*;
+use ethcontract::common::abi::Token;
+use ethcontract::web3::types::{Bytes, TransactionParameters, TransactionRequest};
 
-#[tokio::test]
-#[should_panic(expected = "mock node can't sign transactions")]
-async fn send_transaction() {
-    // When we implement `send_transaction`, we should add same tests as for
-    // send_raw_transaction (expect for raw transaction format/signing)
-    // and also a test that checks that returned transaction hash is correct.
+fn setup_local() -> (Mock, DynWeb3, Contract, ERC20) {
+    let (mock, web3, contract, mut instance) = setup();
+    let address = mock.add_account(private_key_for("Alice"));
+    instance.defaults_mut().from = Some(Account::Local(address, None));
+
+    (mock, web3, contract, instance)
+}
 
+#[tokio::test]
+#[should_panic(expected = "mock node can't sign transactions for account")]
+async fn send_transaction_from_unknown_account() {
     let web3 = Mock::new(1234).web3();
 
     web3.eth()
@@ -15,3 +20,160 @@ async fn send_transaction() {
         .await
         .unwrap();
 }
+
+#[tokio::test]
+async fn add_account_returns_address() -> Result {
+    let mock = Mock::new(1234);
+
+    assert_eq!(
+        mock.add_account(private_key_for("Alice")),
+        address_for("Alice")
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn accounts_returns_added_accounts() -> Result {
+    let mock = Mock::new(1234);
+    let web3 = mock.web3();
+
+    assert!(web3.eth().accounts().await?.is_empty());
+
+    mock.add_account(private_key_for("Bob"));
+    mock.add_account(private_key_for("Alice"));
+    mock.add_account(private_key_for("Bob"));
+
+    assert_eq!(
+        web3.eth().accounts().await?,
+        vec![address_for("Bob"), address_for("Alice")]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn send_transaction() -> Result {
+    let (_, web3, contract, instance) = setup_local();
+
+    contract
+        .expect_transaction(ERC20::signatures().transfer())
+        .once()
+        .predicate_fn_ctx(|ctx, _| {
+            ctx.from == address_for("Alice") && ctx.nonce == 0.into() && ctx.gas_price == 1.into()
+        })
+        .returns(true);
+
+    let result = instance
+        .transfer(address_for("Bob"), 100.into())
+        .send()
+        .await?;
+
+    let receipt = web3
+        .eth()
+        .transaction_receipt(result.hash())
+        .await?
+        .unwrap();
+    assert_eq!(receipt.from, address_for("Alice"));
+    assert_eq!(receipt.to, Some(contract.address()));
+    assert_eq!(
+        web3.eth()
+            .transaction_count(address_for("Alice"), None)
+            .await?,
+        1.into()
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn send_transaction_defaults_to_first_account() -> Result {
+    let (mock, web3, contract, mut instance) = setup();
+    mock.add_account(private_key_for("Bob"));
+    instance.defaults_mut().from = None;
+
+    contract
+        .expect_transaction(ERC20::signatures().transfer())
+        .once()
+        .predicate_fn_ctx(|ctx, _| ctx.from == address_for("Bob"));
+
+    instance
+        .transfer(address_for("Alice"), 100.into())
+        .send()
+        .await?;
+
+    assert_eq!(
+        web3.eth()
+            .transaction_count(address_for("Bob"), None)
+            .await?,
+        1.into()
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn send_transaction_hash_matches_offline_signing() -> Result {
+    let (mock, web3, contract, _) = setup();
+    let address = mock.add_account(private_key_for("Alice"));
+
+    contract.expect_transaction(ERC20::signatures().transfer());
+
+    let request = TransactionRequest {
+        from: address,
+        to: Some(contract.address()),
+        gas: Some(100_000.into()),
+        gas_price: Some(2.into()),
+        value: Some(0.into()),
+        nonce: Some(0.into()),
+        data: Some(Bytes(
+            ERC20::raw_contract()
+                .interface
+                .abi
+                .function("transfer")?
+                .encode_input(&[Token::Address(address_for("Bob")), Token::Uint(100.into())])?,
+        )),
+        ..Default::default()
+    };
+
+    let signed = web3
+        .accounts()
+        .sign_transaction(
+            TransactionParameters {
+                nonce: request.nonce,
+                to: request.to,
+                gas: request.gas.unwrap(),
+                gas_price: request.gas_price,
+                value: request.value.unwrap(),
+                data: request.data.clone().unwrap(),
+                chain_id: Some(1234),
+                ..Default::default()
+            },
+            &private_key_for("Alice"),
+        )
+        .await?;
+
+    let hash = web3.eth().send_transaction(request).await?;
+    assert_eq!(hash, signed.transaction_hash);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[should_panic(expected = "nonce mismatch")]
+async fn send_transaction_nonce_mismatch() {
+    let (mock, web3, contract, _) = setup();
+    let address = mock.add_account(private_key_for("Alice"));
+
+    contract.expect_transaction(ERC20::signatures().transfer());
+
+    web3.eth()
+        .send_transaction(TransactionRequest {
+            from: address,
+            to: Some(contract.address()),
+            nonce: Some(1.into()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+}
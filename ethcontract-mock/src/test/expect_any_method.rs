@@ -0,0 +1,128 @@
+use super::*;
+use ethcontract::errors::ExecutionError;
+use ethcontract_core::abi::Token;
+
+#[tokio::test]
+async fn matches_calls_without_method_expectations() -> Result {
+    let (_, _, contract, instance) = setup();
+
+    contract
+        .expect_any_method()
+        .once()
+        .returns(ethcontract_core::abi::encode(&[Token::Uint(100.into())]));
+
+    assert_eq!(instance.total_supply().call().await?, 100.into());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn method_expectations_take_priority() -> Result {
+    let (_, _, contract, instance) = setup();
+
+    contract
+        .expect(ERC20::signatures().total_supply())
+        .once()
+        .returns(1.into());
+    contract
+        .expect_any_method()
+        .once()
+        .returns(ethcontract_core::abi::encode(&[Token::Uint(2.into())]));
+
+    assert_eq!(instance.total_supply().call().await?, 1.into());
+    assert_eq!(instance.total_supply().call().await?, 2.into());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn reverts_all_calls() -> Result {
+    let (_, _, contract, instance) = setup();
+
+    contract
+        .expect_any_method()
+        .times(2)
+        .returns_error("paused".to_string());
+
+    let err = instance.total_supply().call().await.unwrap_err();
+    assert!(err.to_string().contains("paused"), "{}", err);
+    let err = instance
+        .transfer(address_for("Bob"), 100.into())
+        .send()
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err.inner, ExecutionError::Failure(_)),
+        "{:?}",
+        err.inner
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn selector_and_predicate() -> Result {
+    let (_, _, contract, instance) = setup();
+
+    let selector = ERC20::signatures().balance_of().into_inner();
+    contract
+        .expect_any_method()
+        .selector(selector)
+        .predicate_fn(|data| data.len() == 36)
+        .returns_fn(|data| {
+            assert_eq!(&data[16..36], address_for("Bob").as_bytes());
+            Ok(ethcontract_core::abi::encode(&[Token::Uint(42.into())]))
+        });
+
+    assert_eq!(
+        instance.balance_of(address_for("Bob")).call().await?,
+        42.into()
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+#[should_panic(expected = "unexpected call to")]
+async fn selector_mismatch() {
+    let (_, _, contract, instance) = setup();
+
+    contract
+        .expect_any_method()
+        .selector(ERC20::signatures().balance_of().into_inner());
+
+    instance.total_supply().call().await.unwrap();
+}
+
+#[tokio::test]
+#[should_panic(expected = "unexpected call to")]
+async fn never_called() {
+    let (_, _, contract, instance) = setup();
+
+    contract.expect_any_method().never();
+
+    instance.total_supply().call().await.unwrap();
+}
+
+#[tokio::test]
+#[should_panic(
+    expected = "any method on contract 0x0000000000000000000000000000000000000001 \
+                was called 0 times, but it was expected to be called exactly 1 time"
+)]
+async fn verify_any_method() {
+    let (mock, _, contract, _) = setup();
+
+    contract.expect_any_method().once();
+
+    mock.checkpoint();
+}
+
+#[tokio::test]
+#[should_panic(expected = "old expectations are not valid after checkpoint")]
+async fn checkpoint_invalidates() {
+    let (_, _, contract, _) = setup();
+
+    let expectation = contract.expect_any_method();
+    contract.checkpoint();
+    expectation.once();
+}
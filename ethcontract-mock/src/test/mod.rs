@@ -69,15 +69,26 @@ use ethcontract::prelude::*;
 use predicates::prelude::*;
 
 mod batch;
+mod block_validity;
+mod deployment;
+mod emits_event;
 mod eth_block_number;
 mod eth_chain_id;
 mod eth_estimate_gas;
+mod eth_fee_history;
 mod eth_gas_price;
+mod eth_get_balance;
+mod eth_get_code;
 mod eth_get_transaction_receipt;
 mod eth_send_transaction;
 mod eth_transaction_count;
+mod expect_any_method;
 mod net_version;
+mod on_unexpected;
 mod returns;
+mod send_if;
+mod sequence;
+mod verify;
 
 type Result = std::result::Result<(), Box<dyn std::error::Error>>;
 
@@ -0,0 +1,98 @@
+use super::*;
+use crate::UnexpectedCall;
+use ethcontract::web3::types::{Bytes, CallRequest};
+use ethcontract_core::abi::Token;
+use std::sync::{Arc, Mutex};
+
+fn record(mock: &Mock) -> Arc<Mutex<Vec<UnexpectedCall>>> {
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    mock.on_unexpected({
+        let calls = calls.clone();
+        move |call| calls.lock().unwrap().push(call.clone())
+    });
+    calls
+}
+
+#[tokio::test]
+async fn handler_receives_decoded_call() {
+    let (mock, _, contract, instance) = setup();
+    mock.set_panic_free(true);
+    let calls = record(&mock);
+
+    instance
+        .balance_of(address_for("Bob"))
+        .call()
+        .await
+        .unwrap_err();
+
+    let calls = calls.lock().unwrap();
+    assert_eq!(calls.len(), 1);
+    let call = &calls[0];
+    assert_eq!(call.address, contract.address());
+    assert_eq!(call.selector, ERC20::signatures().balance_of().into_inner());
+    assert_eq!(call.signature.as_deref(), Some("balanceOf(address)"));
+    assert_eq!(call.params, Some(vec![Token::Address(address_for("Bob"))]));
+    assert!(call.context.is_view_call);
+    assert_eq!(call.context.from, address_for("Alice"));
+
+    std::panic::catch_unwind(|| mock.verify()).unwrap_err();
+}
+
+#[tokio::test]
+async fn handler_receives_unknown_selector() {
+    let (mock, web3, contract, _) = setup();
+    mock.set_panic_free(true);
+    let calls = record(&mock);
+
+    let data = vec![0xde, 0xad, 0xbe, 0xef, 0x01];
+    web3.eth()
+        .call(
+            CallRequest {
+                to: Some(contract.address()),
+                data: Some(Bytes(data.clone())),
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap_err();
+
+    let calls = calls.lock().unwrap();
+    assert_eq!(calls[0].selector, [0xde, 0xad, 0xbe, 0xef]);
+    assert_eq!(calls[0].signature, None);
+    assert_eq!(calls[0].params, None);
+    assert_eq!(calls[0].data, data);
+
+    std::panic::catch_unwind(|| mock.verify()).unwrap_err();
+}
+
+#[tokio::test]
+async fn handler_panic_is_reported() {
+    let (mock, _, _, instance) = setup();
+    mock.set_panic_free(true);
+    mock.on_unexpected(|call| panic!("no expectation for {}", call.signature.as_ref().unwrap()));
+
+    let err = instance
+        .balance_of(address_for("Bob"))
+        .call()
+        .await
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("no expectation for balanceOf(address)"));
+
+    std::panic::catch_unwind(|| mock.verify()).unwrap_err();
+}
+
+#[tokio::test]
+#[should_panic(expected = "unexpected call to \"transfer(address,uint256)\"")]
+async fn unexpected_transaction_panics_after_handler() {
+    let (mock, _, _, instance) = setup();
+    mock.on_unexpected(|_| ());
+
+    instance
+        .transfer(address_for("Bob"), 100.into())
+        .send()
+        .await
+        .unwrap();
+}
@@ -2,6 +2,10 @@ use super::*;
 
 ethcontract::contract!("examples/truffle/build/contracts/AbiTypes.json");
 
+fn s(u0: u8, u1: u16) -> abi_types::structs::S {
+    abi_types::structs::S { u_0: u0, u_1: u1 }
+}
+
 #[tokio::test]
 async fn returns_default() -> Result {
     let contract = Mock::new(1234).deploy(AbiTypes::raw_contract().interface.abi.clone());
@@ -17,10 +21,10 @@ async fn returns_default() -> Result {
 
     instance.get_void().call().await?;
     assert_eq!(instance.get_u8().call().await?, 0);
-    assert_eq!(instance.abiv_2_struct((1, 2)).call().await?, (0, 0));
+    assert_eq!(instance.abiv_2_struct(s(1, 2)).call().await?, s(0, 0));
     assert_eq!(
         instance
-            .abiv_2_array_of_struct(vec![(1, 2), (3, 4)])
+            .abiv_2_array_of_struct(vec![s(1, 2), s(3, 4)])
             .call()
             .await?,
         vec![]
@@ -28,7 +32,7 @@ async fn returns_default() -> Result {
     assert_eq!(instance.multiple_results().call().await?, (0, 0, 0));
     assert_eq!(
         instance.multiple_results_struct().call().await?,
-        ((0, 0), (0, 0))
+        (s(0, 0), s(0, 0))
     );
 
     Ok(())
@@ -44,33 +48,33 @@ async fn returns_const() -> Result {
     contract.expect(AbiTypes::signatures().get_u8()).returns(42);
     contract
         .expect(AbiTypes::signatures().abiv_2_struct())
-        .returns((1, 2));
+        .returns(s(1, 2));
     contract
         .expect(AbiTypes::signatures().abiv_2_array_of_struct())
-        .returns(vec![(1, 2), (3, 4)]);
+        .returns(vec![s(1, 2), s(3, 4)]);
     contract
         .expect(AbiTypes::signatures().multiple_results())
         .returns((1, 2, 3));
     contract
         .expect(AbiTypes::signatures().multiple_results_struct())
-        .returns(((1, 2), (3, 4)));
+        .returns((s(1, 2), s(3, 4)));
 
     let instance = AbiTypes::at(&contract.web3(), contract.address);
 
     instance.get_void().call().await?;
     assert_eq!(instance.get_u8().call().await?, 42);
-    assert_eq!(instance.abiv_2_struct((1, 2)).call().await?, (1, 2));
+    assert_eq!(instance.abiv_2_struct(s(1, 2)).call().await?, s(1, 2));
     assert_eq!(
         instance
-            .abiv_2_array_of_struct(vec![(1, 2), (3, 4)])
+            .abiv_2_array_of_struct(vec![s(1, 2), s(3, 4)])
             .call()
             .await?,
-        vec![(1, 2), (3, 4)]
+        vec![s(1, 2), s(3, 4)]
     );
     assert_eq!(instance.multiple_results().call().await?, (1, 2, 3));
     assert_eq!(
         instance.multiple_results_struct().call().await?,
-        ((1, 2), (3, 4))
+        (s(1, 2), s(3, 4))
     );
 
     Ok(())
@@ -97,24 +101,24 @@ async fn returns_fn() -> Result {
         .returns_fn(|_| Ok((1, 2, 3)));
     contract
         .expect(AbiTypes::signatures().multiple_results_struct())
-        .returns_fn(|_| Ok(((1, 2), (3, 4))));
+        .returns_fn(|_| Ok((s(1, 2), s(3, 4))));
 
     let instance = AbiTypes::at(&contract.web3(), contract.address);
 
     instance.get_void().call().await?;
     assert_eq!(instance.get_u8().call().await?, 42);
-    assert_eq!(instance.abiv_2_struct((1, 2)).call().await?, (1, 2));
+    assert_eq!(instance.abiv_2_struct(s(1, 2)).call().await?, s(1, 2));
     assert_eq!(
         instance
-            .abiv_2_array_of_struct(vec![(1, 2), (3, 4)])
+            .abiv_2_array_of_struct(vec![s(1, 2), s(3, 4)])
             .call()
             .await?,
-        vec![(1, 2), (3, 4)]
+        vec![s(1, 2), s(3, 4)]
     );
     assert_eq!(instance.multiple_results().call().await?, (1, 2, 3));
     assert_eq!(
         instance.multiple_results_struct().call().await?,
-        ((1, 2), (3, 4))
+        (s(1, 2), s(3, 4))
     );
 
     Ok(())
@@ -141,24 +145,24 @@ async fn returns_fn_ctx() -> Result {
         .returns_fn_ctx(|_, _| Ok((1, 2, 3)));
     contract
         .expect(AbiTypes::signatures().multiple_results_struct())
-        .returns_fn_ctx(|_, _| Ok(((1, 2), (3, 4))));
+        .returns_fn_ctx(|_, _| Ok((s(1, 2), s(3, 4))));
 
     let instance = AbiTypes::at(&contract.web3(), contract.address);
 
     instance.get_void().call().await?;
     assert_eq!(instance.get_u8().call().await?, 42);
-    assert_eq!(instance.abiv_2_struct((1, 2)).call().await?, (1, 2));
+    assert_eq!(instance.abiv_2_struct(s(1, 2)).call().await?, s(1, 2));
     assert_eq!(
         instance
-            .abiv_2_array_of_struct(vec![(1, 2), (3, 4)])
+            .abiv_2_array_of_struct(vec![s(1, 2), s(3, 4)])
             .call()
             .await?,
-        vec![(1, 2), (3, 4)]
+        vec![s(1, 2), s(3, 4)]
     );
     assert_eq!(instance.multiple_results().call().await?, (1, 2, 3));
     assert_eq!(
         instance.multiple_results_struct().call().await?,
-        ((1, 2), (3, 4))
+        (s(1, 2), s(3, 4))
     );
 
     Ok(())
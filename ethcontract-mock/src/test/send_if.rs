@@ -0,0 +1,76 @@
+use super::*;
+
+#[tokio::test]
+async fn sends_when_predicate_holds() -> Result {
+    let (_mock, _, contract, instance) = setup();
+
+    contract
+        .expect_call(ERC20::signatures().balance_of())
+        .once()
+        .returns_fn_ctx(|ctx, _| {
+            assert_eq!(ctx.block, 0);
+            Ok(U256::from(100))
+        });
+    contract
+        .expect_transaction(ERC20::signatures().transfer())
+        .once()
+        .returns(true);
+
+    let result = instance
+        .transfer(address_for("Bob"), U256::from(100))
+        .send_if(instance.balance_of(address_for("Alice")), |balance| {
+            *balance >= U256::from(100)
+        })
+        .await?;
+    assert!(result.is_some());
+
+    contract.checkpoint();
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn skips_when_predicate_fails() -> Result {
+    let (_mock, _, contract, instance) = setup();
+
+    contract
+        .expect_call(ERC20::signatures().balance_of())
+        .once()
+        .returns(U256::from(99));
+    contract
+        .expect_transaction(ERC20::signatures().transfer())
+        .never();
+
+    let result = instance
+        .transfer(address_for("Bob"), U256::from(100))
+        .send_if(instance.balance_of(address_for("Alice")), |balance| {
+            *balance >= U256::from(100)
+        })
+        .await?;
+    assert!(result.is_none());
+
+    contract.checkpoint();
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn condition_errors_are_returned() {
+    let (_mock, _, contract, instance) = setup();
+
+    contract
+        .expect_call(ERC20::signatures().balance_of())
+        .returns_error("paused".into());
+    contract
+        .expect_transaction(ERC20::signatures().transfer())
+        .never();
+
+    let err = instance
+        .transfer(address_for("Bob"), U256::from(100))
+        .send_if(instance.balance_of(address_for("Alice")), |_| true)
+        .await
+        .unwrap_err();
+    assert!(err.signature.starts_with("balanceOf"));
+
+    contract.checkpoint();
+}
@@ -0,0 +1,93 @@
+use super::*;
+use crate::sequence::CallCounter;
+use crate::{ExpectationHandle, TimesRange};
+
+#[test]
+fn times_range_new() {
+    let range = TimesRange::new(1, 3);
+    assert_eq!(range, (1..3).into());
+    assert!(range.contains(2));
+    assert!(!range.contains(3));
+}
+
+#[test]
+#[should_panic(expected = "backwards range")]
+fn times_range_new_empty() {
+    TimesRange::new(2, 2);
+}
+
+#[test]
+fn call_counter_limits_calls() {
+    let mut counter = CallCounter::new();
+    counter.set_times(TimesRange::from(1..=2));
+    assert!(counter.verify("foo").is_err());
+
+    counter.record_call("foo");
+    assert!(counter.is_active());
+    assert!(counter.verify("foo").is_ok());
+
+    counter.record_call("foo");
+    assert!(!counter.is_active());
+    assert_eq!(counter.used(), 2);
+    assert!(counter.verify("foo").is_ok());
+}
+
+#[test]
+fn call_counter_verify_message() {
+    let mut counter = CallCounter::new();
+    counter.set_times(2.into());
+    counter.record_call("foo");
+    assert_eq!(
+        counter.verify("foo").unwrap_err(),
+        "foo was called 1 time, but it was expected to be called exactly 2 times",
+    );
+}
+
+#[test]
+#[should_panic(expected = "foo: Method sequence violation")]
+fn call_counter_sequence_out_of_order() {
+    let mut sequence = mockall::Sequence::new();
+
+    let mut first = CallCounter::new();
+    first.set_times(1.into());
+    first.set_sequence(&mut sequence);
+
+    let mut second = CallCounter::new();
+    second.set_times(1.into());
+    second.set_sequence(&mut sequence);
+    assert!(second.in_sequence());
+
+    second.record_call("foo");
+}
+
+#[test]
+#[should_panic(expected = "only expectations with an exact call count can be in a sequences")]
+fn call_counter_sequence_requires_exact_times() {
+    let mut sequence = mockall::Sequence::new();
+    let mut counter = CallCounter::new();
+    counter.set_sequence(&mut sequence);
+}
+
+#[tokio::test]
+async fn expectation_handle() -> Result {
+    fn strictly_once<E: ExpectationHandle>(expectation: E, sequence: &mut mockall::Sequence) -> E {
+        expectation.once().in_sequence(sequence)
+    }
+
+    let (_, _, contract, instance) = setup();
+
+    let mut sequence = mockall::Sequence::new();
+    strictly_once(
+        contract.expect(ERC20::signatures().balance_of()),
+        &mut sequence,
+    );
+    strictly_once(
+        contract.expect(ERC20::signatures().total_supply()),
+        &mut sequence,
+    );
+
+    instance.balance_of(address_for("Bob")).call().await?;
+    instance.total_supply().call().await?;
+
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use super::*;
+
+#[tokio::test]
+async fn unsupported_method_returns_error() {
+    let mock = Mock::new(1234);
+    mock.set_panic_free(true);
+
+    let err = mock.web3().eth().coinbase().await.unwrap_err();
+    assert!(err.to_string().contains("does not support rpc method"));
+
+    mock.checkpoint();
+    let result = std::panic::catch_unwind(|| mock.verify());
+    assert!(result.is_err());
+
+    // Violations are cleared after verification.
+    mock.verify();
+}
+
+#[tokio::test]
+async fn unexpected_call_returns_error() {
+    let (mock, _, _, instance) = setup();
+    mock.set_panic_free(true);
+
+    let err = instance
+        .balance_of(address_for("Bob"))
+        .call()
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("unexpected call to"));
+
+    // Mock node is still usable after a violation.
+    assert_eq!(mock.web3().eth().chain_id().await.unwrap(), 1234.into());
+
+    std::panic::catch_unwind(|| mock.verify()).unwrap_err();
+}
+
+#[tokio::test]
+async fn checkpoint_records_unsatisfied_expectations() {
+    let (mock, _, contract, _) = setup();
+    mock.set_panic_free(true);
+
+    contract.expect(ERC20::signatures().balance_of()).once();
+    contract.expect(ERC20::signatures().transfer()).once();
+    mock.checkpoint();
+
+    let report = std::panic::catch_unwind(|| mock.verify())
+        .unwrap_err()
+        .downcast::<String>()
+        .unwrap();
+    assert!(report.starts_with("mock node recorded 2 violations:"));
+    assert!(report.contains("balanceOf(address)"));
+    assert!(report.contains("transfer(address,uint256)"));
+}
+
+#[tokio::test]
+async fn verify_passes() -> Result {
+    let (mock, _, contract, instance) = setup();
+    mock.set_panic_free(true);
+
+    contract.expect(ERC20::signatures().balance_of()).once();
+    instance.balance_of(address_for("Bob")).call().await?;
+
+    mock.verify();
+
+    Ok(())
+}
+
+#[tokio::test]
+#[should_panic(expected = "was called 0 times, but it was expected to be called exactly 1 time")]
+async fn verify_panics_on_unsatisfied_expectations() {
+    let (mock, _, contract, _) = setup();
+
+    contract.expect(ERC20::signatures().balance_of()).once();
+
+    mock.verify();
+}
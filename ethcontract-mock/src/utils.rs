@@ -1,6 +1,9 @@
 //! Convenience utilities for tests.
 
-use ethcontract::{Account, Address, PrivateKey};
+#[cfg(feature = "ethcontract")]
+use ethcontract::{Account, PrivateKey};
+use web3::signing::{keccak256, Key, SecretKey, SecretKeyRef};
+use web3::types::Address;
 
 /// Generate public address by hashing the given string.
 ///
@@ -16,7 +19,7 @@ use ethcontract::{Account, Address, PrivateKey};
 /// # assert_eq!(address, "0xbf0b5a4099f0bf6c8bc4252ebec548bae95602ea".parse().unwrap());
 /// ```
 pub fn address_for(who: &str) -> Address {
-    account_for(who).address()
+    SecretKeyRef::new(&secret_key_for(who)).address()
 }
 
 /// Shortcut for [`address_for`]`("Alice")`.
@@ -45,12 +48,60 @@ pub fn address() -> Address {
 /// let account = account_for("Bob");
 /// # assert_eq!(account.address(), "0x4dba461ca9342f4a6cf942abd7eacf8ae259108c".parse().unwrap());
 /// ```
+#[cfg(feature = "ethcontract")]
 pub fn account_for(who: &str) -> Account {
-    use ethcontract::web3::signing::keccak256;
-    Account::Offline(
-        PrivateKey::from_raw(keccak256(who.as_bytes())).unwrap(),
-        None,
-    )
+    Account::Offline(private_key_for(who), None)
+}
+
+/// Generate a private key by hashing the given string, without
+/// wrapping it into an [`Account`].
+///
+/// This is useful for registering deterministic accounts
+/// with [`Mock::add_account`].
+///
+/// # Safety
+///
+/// This function is intended for tests and should not be used in production.
+///
+/// # Examples
+///
+/// ```
+/// # use ethcontract_mock::Mock;
+/// # use ethcontract_mock::utils::{address_for, private_key_for};
+/// let mock = Mock::new(1234);
+/// let address = mock.add_account(private_key_for("Bob"));
+/// # assert_eq!(address, address_for("Bob"));
+/// ```
+///
+/// [`Mock::add_account`]: crate::Mock::add_account
+#[cfg(feature = "ethcontract")]
+pub fn private_key_for(who: &str) -> PrivateKey {
+    PrivateKey::from_raw(keccak256(who.as_bytes())).unwrap()
+}
+
+/// Generate a `web3` secret key by hashing the given string.
+///
+/// This is the same key as the one returned by [`private_key_for`], for
+/// registering deterministic accounts with [`Mock::add_secret_key`]
+/// without the `ethcontract` feature.
+///
+/// # Safety
+///
+/// This function is intended for tests and should not be used in production.
+///
+/// # Examples
+///
+/// ```
+/// # use ethcontract_mock::Mock;
+/// # use ethcontract_mock::utils::{address_for, secret_key_for};
+/// let mock = Mock::new(1234);
+/// let address = mock.add_secret_key(secret_key_for("Bob"));
+/// # assert_eq!(address, address_for("Bob"));
+/// ```
+///
+/// [`Mock::add_secret_key`]: crate::Mock::add_secret_key
+pub fn secret_key_for(who: &str) -> SecretKey {
+    SecretKey::from_slice(&keccak256(who.as_bytes())).unwrap()
 }
 
 /// Shortcut for [`account_for`]`("Alice")`.
@@ -62,6 +113,7 @@ pub fn account_for(who: &str) -> Account {
 /// let account = account();
 /// # assert_eq!(account.address(), "0xbf0b5a4099f0bf6c8bc4252ebec548bae95602ea".parse().unwrap());
 /// ```
+#[cfg(feature = "ethcontract")]
 pub fn account() -> Account {
     account_for("Alice")
 }
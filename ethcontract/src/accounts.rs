@@ -0,0 +1,230 @@
+//! Batched queries of account state, for services managing many signer
+//! accounts.
+//!
+//! A [`snapshot`] fetches the nonces and balances of a set of accounts in a
+//! single JSON-RPC batch request, which is useful for preflight checks before
+//! dispatching many transactions at once.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # async fn run(web3: ethcontract::web3::Web3<ethcontract::dyns::DynTransport>) {
+//! use ethcontract::accounts;
+//! use ethcontract::prelude::*;
+//!
+//! let signers = [Address::repeat_byte(1), Address::repeat_byte(2)];
+//! let snapshot = accounts::snapshot(&web3, &signers).await.unwrap();
+//! for account in snapshot.with_balance_below(U256::exp10(17)) {
+//!     println!("{:?} needs funding", account.address);
+//! }
+//! # }
+//! ```
+
+use crate::errors::ExecutionError;
+use web3::api::Web3;
+use web3::error::Error as Web3Error;
+use web3::helpers;
+use web3::types::{Address, BlockNumber, U256};
+use web3::BatchTransport;
+
+/// The state of a single account in a snapshot.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct AccountState {
+    /// The address of the account.
+    pub address: Address,
+    /// The transaction count of the account, which is the nonce of the next
+    /// transaction sent from it.
+    pub nonce: U256,
+    /// The balance of the account in wei.
+    pub balance: U256,
+}
+
+/// The states of a set of accounts, fetched at the same block.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct AccountsSnapshot {
+    /// The account states, in the order the addresses were specified in.
+    pub accounts: Vec<AccountState>,
+}
+
+impl AccountsSnapshot {
+    /// Returns the state of the account with the specified address, if it is
+    /// part of the snapshot.
+    pub fn get(&self, address: Address) -> Option<&AccountState> {
+        self.accounts
+            .iter()
+            .find(|account| account.address == address)
+    }
+
+    /// Returns the total balance of all accounts in the snapshot.
+    pub fn total_balance(&self) -> U256 {
+        self.accounts.iter().fold(U256::zero(), |total, account| {
+            total.saturating_add(account.balance)
+        })
+    }
+
+    /// Returns the accounts with a balance lower than the specified minimum,
+    /// for example the funds required for the transactions about to be sent.
+    pub fn with_balance_below(&self, minimum: U256) -> impl Iterator<Item = &AccountState> {
+        self.accounts
+            .iter()
+            .filter(move |account| account.balance < minimum)
+    }
+}
+
+/// Fetches the nonces and balances of the specified accounts in a single
+/// batch request.
+///
+/// State is queried at the pending block, so that nonces account for
+/// transactions that are already in the mempool of the node.
+pub async fn snapshot<T: BatchTransport>(
+    web3: &Web3<T>,
+    addresses: &[Address],
+) -> Result<AccountsSnapshot, ExecutionError> {
+    snapshot_at(web3, addresses, BlockNumber::Pending).await
+}
+
+/// Fetches the nonces and balances of the specified accounts at the specified
+/// block in a single batch request.
+pub async fn snapshot_at<T: BatchTransport>(
+    web3: &Web3<T>,
+    addresses: &[Address],
+    block: BlockNumber,
+) -> Result<AccountsSnapshot, ExecutionError> {
+    if addresses.is_empty() {
+        return Ok(AccountsSnapshot::default());
+    }
+
+    let transport = web3.transport();
+    let block = helpers::serialize(&block);
+    let requests = addresses
+        .iter()
+        .flat_map(|address| {
+            let address = helpers::serialize(address);
+            [
+                transport.prepare(
+                    "eth_getTransactionCount",
+                    vec![address.clone(), block.clone()],
+                ),
+                transport.prepare("eth_getBalance", vec![address, block.clone()]),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    let results = transport.send_batch(requests).await?;
+    if results.len() != addresses.len() * 2 {
+        return Err(
+            Web3Error::Decoder("Batch result did not contain enough responses".to_owned()).into(),
+        );
+    }
+
+    let mut results = results.into_iter();
+    let mut decode_next = || -> Result<U256, Web3Error> {
+        // NOTE: The length of the results was checked above.
+        helpers::decode(results.next().unwrap()?)
+    };
+
+    let accounts = addresses
+        .iter()
+        .map(|&address| {
+            Ok(AccountState {
+                address,
+                nonce: decode_next()?,
+                balance: decode_next()?,
+            })
+        })
+        .collect::<Result<_, Web3Error>>()?;
+
+    Ok(AccountsSnapshot { accounts })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::prelude::*;
+
+    #[test]
+    fn snapshot_batches_requests() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let alice = Address::repeat_byte(1);
+        let bob = Address::repeat_byte(2);
+
+        transport.add_response(json!(["0x1", "0x64", "0x0", "0x2a"]));
+        let snapshot = snapshot(&web3, &[alice, bob]).immediate().unwrap();
+
+        transport.assert_request("eth_getTransactionCount", &[json!(alice), json!("pending")]);
+        transport.assert_request("eth_getBalance", &[json!(alice), json!("pending")]);
+        transport.assert_request("eth_getTransactionCount", &[json!(bob), json!("pending")]);
+        transport.assert_request("eth_getBalance", &[json!(bob), json!("pending")]);
+        transport.assert_no_more_requests();
+
+        assert_eq!(
+            snapshot.accounts,
+            vec![
+                AccountState {
+                    address: alice,
+                    nonce: 1.into(),
+                    balance: 100.into(),
+                },
+                AccountState {
+                    address: bob,
+                    nonce: 0.into(),
+                    balance: 42.into(),
+                },
+            ]
+        );
+        assert_eq!(snapshot.get(bob).unwrap().balance, 42.into());
+        assert_eq!(snapshot.get(Address::repeat_byte(3)), None);
+        assert_eq!(snapshot.total_balance(), 142.into());
+        assert_eq!(
+            snapshot
+                .with_balance_below(50.into())
+                .map(|account| account.address)
+                .collect::<Vec<_>>(),
+            vec![bob]
+        );
+    }
+
+    #[test]
+    fn snapshot_at_block() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let alice = Address::repeat_byte(1);
+
+        transport.add_response(json!(["0x1", "0x64"]));
+        snapshot_at(&web3, &[alice], BlockNumber::Number(42.into()))
+            .immediate()
+            .unwrap();
+
+        transport.assert_request("eth_getTransactionCount", &[json!(alice), json!("0x2a")]);
+        transport.assert_request("eth_getBalance", &[json!(alice), json!("0x2a")]);
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn snapshot_without_accounts() {
+        let transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let snapshot = snapshot(&web3, &[]).immediate().unwrap();
+
+        assert!(snapshot.accounts.is_empty());
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn snapshot_fails_on_missing_responses() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        transport.add_response(json!(["0x1"]));
+        let result = snapshot(&web3, &[Address::repeat_byte(1)]).immediate();
+
+        assert!(matches!(
+            result,
+            Err(ExecutionError::Web3(Web3Error::Decoder(_)))
+        ));
+    }
+}
@@ -1,12 +1,22 @@
 //! Module containing components to batch multiple contract calls
 //! into a single request to the Node.
 
+mod multicall;
+mod multisend;
+
+pub use self::multicall::{Multicall, MulticallVersion, MULTICALL3_ADDRESS};
+pub use self::multisend::{multisend, multisend_payload, MULTISEND_CALL_ONLY_ADDRESS};
+
+use crate::contract::{StateOverrides, ViewMethodBuilder};
+use crate::errors::MethodError;
+use crate::tokens::Tokenize;
 use futures::channel::oneshot::{channel, Sender};
+use std::future::Future;
 use web3::{
     error::{Error as Web3Error, TransportError},
     helpers::{self},
-    types::{BlockId, BlockNumber, Bytes, CallRequest},
-    BatchTransport as Web3BatchTransport,
+    types::{Address, BlockId, BlockNumber, Bytes, CallRequest, U256},
+    BatchTransport as Web3BatchTransport, Transport,
 };
 
 /// Struct allowing to batch multiple calls into a single Node request
@@ -15,7 +25,44 @@ pub struct CallBatch<T: Web3BatchTransport> {
     requests: Vec<(Request, CompletionHandler)>,
 }
 
-type Request = (CallRequest, Option<BlockId>);
+/// Overrides for the context of a single view method call in a batch, so that
+/// calls in the same batch can be executed for different senders or blocks.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CallOverrides {
+    /// The sender of the call.
+    pub from: Option<Address>,
+    /// The block at which the call is executed.
+    pub block: Option<BlockId>,
+    /// The amount of ETH sent with the call.
+    pub value: Option<U256>,
+}
+
+impl CallOverrides {
+    /// Creates empty overrides, leaving the call as is.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the sender of the call.
+    pub fn from(mut self, value: Address) -> Self {
+        self.from = Some(value);
+        self
+    }
+
+    /// Sets the block at which the call is executed.
+    pub fn block(mut self, value: BlockId) -> Self {
+        self.block = Some(value);
+        self
+    }
+
+    /// Sets the amount of ETH sent with the call.
+    pub fn value(mut self, value: U256) -> Self {
+        self.value = Some(value);
+        self
+    }
+}
+
+type Request = (CallRequest, Option<BlockId>, Option<StateOverrides>);
 type CompletionHandler = Sender<Result<Bytes, Web3Error>>;
 
 impl<T: Web3BatchTransport> CallBatch<T> {
@@ -37,9 +84,20 @@ impl<T: Web3BatchTransport> CallBatch<T> {
         &mut self,
         call: CallRequest,
         block: Option<BlockId>,
+    ) -> impl std::future::Future<Output = Result<Bytes, Web3Error>> {
+        self.push_with_state_overrides(call, block, None)
+    }
+
+    /// Adds a call request with optional state overrides to the current
+    /// batch. See [`CallBatch::push`] for details.
+    pub(crate) fn push_with_state_overrides(
+        &mut self,
+        call: CallRequest,
+        block: Option<BlockId>,
+        state_overrides: Option<StateOverrides>,
     ) -> impl std::future::Future<Output = Result<Bytes, Web3Error>> {
         let (tx, rx) = channel();
-        self.requests.push(((call, block), tx));
+        self.requests.push(((call, block, state_overrides), tx));
         async move {
             rx.await.unwrap_or_else(|_| {
                 Err(Web3Error::Transport(TransportError::Message(
@@ -49,6 +107,52 @@ impl<T: Web3BatchTransport> CallBatch<T> {
         }
     }
 
+    /// Adds a view method call to the batch. The resulting future resolves to
+    /// the decoded return value of the method once the batch has been
+    /// executed. This is equivalent to calling
+    /// [`ViewMethodBuilder::batch_call`] with this batch.
+    /// Panics, if the batch is dropped before executing.
+    pub fn add<M, R>(
+        &mut self,
+        method: ViewMethodBuilder<M, R>,
+    ) -> impl Future<Output = Result<R, MethodError>>
+    where
+        M: Transport,
+        R: Tokenize,
+    {
+        method.batch_call(self)
+    }
+
+    /// Adds a view method call to the batch with the specified overrides
+    /// applied on top of the method's own parameters. This allows, for
+    /// example, checking allowances for several owners in the same batch.
+    /// Panics, if the batch is dropped before executing.
+    pub fn add_with<M, R>(
+        &mut self,
+        mut method: ViewMethodBuilder<M, R>,
+        overrides: CallOverrides,
+    ) -> impl Future<Output = Result<R, MethodError>>
+    where
+        M: Transport,
+        R: Tokenize,
+    {
+        if let Some(from) = overrides.from {
+            method = method.from(from);
+        }
+        if let Some(block) = overrides.block {
+            method = method.block(block);
+        }
+        if let Some(value) = overrides.value {
+            method = method.value(value);
+        }
+        method.batch_call(self)
+    }
+
+    /// Execute and resolve all enqueued calls in a single batched RPC call.
+    pub async fn execute(self) {
+        self.execute_all(usize::MAX).await
+    }
+
     /// Execute and resolve all enqueued CallRequests in a batched RPC call, `chunk_size` requests per roundtrip.
     /// Top level request failures will be forwarded to the individual requests.
     pub async fn execute_all(self, batch_size: usize) {
@@ -59,11 +163,15 @@ impl<T: Web3BatchTransport> CallBatch<T> {
 
             // Send requests in a single call
             let batch_result = inner
-                .send_batch(requests.iter().map(|(request, block)| {
+                .send_batch(requests.iter().map(|(request, block, state_overrides)| {
                     let req = helpers::serialize(request);
                     let block =
                         helpers::serialize(&block.unwrap_or_else(|| BlockNumber::Latest.into()));
-                    let (id, request) = inner.prepare("eth_call", vec![req, block]);
+                    let mut params = vec![req, block];
+                    if let Some(state_overrides) = state_overrides {
+                        params.push(helpers::serialize(state_overrides));
+                    }
+                    let (id, request) = inner.prepare("eth_call", params);
                     (id, request)
                 }))
                 .await;
@@ -95,8 +203,11 @@ mod tests {
     use serde_json::json;
 
     use super::*;
-    use crate::test::prelude::FutureTestExt;
+    use crate::contract::{MethodBuilder, StateOverride};
+    use crate::test::prelude::{function, FutureTestExt};
     use crate::test::transport::TestTransport;
+    use ethcontract_common::abi::ParamType;
+    use web3::Web3;
 
     #[test]
     fn batches_calls() {
@@ -117,6 +228,136 @@ mod tests {
         assert_eq!(results[1].clone().unwrap().0, vec![2u8]);
     }
 
+    #[test]
+    fn batches_view_methods() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let function = function("test", &[], &[ParamType::Uint(256)]);
+        let method = || {
+            ViewMethodBuilder::<_, U256>::from_method(MethodBuilder::new(
+                web3.clone(),
+                function.clone(),
+                Address::repeat_byte(0x42),
+                Bytes(function.encode_input(&[]).unwrap()),
+            ))
+        };
+
+        let mut batch = CallBatch::new(transport.clone());
+        let first = batch.add(method());
+        let second = batch.add(method());
+
+        transport.add_response(json!([
+            json!("0x000000000000000000000000000000000000000000000000000000000000002a"),
+            json!("0x"),
+        ]));
+        batch.execute().immediate();
+
+        assert_eq!(first.immediate().unwrap(), U256::from(42));
+        assert!(second.immediate().is_err());
+    }
+
+    #[test]
+    fn batches_view_methods_with_overrides() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let function = function("test", &[], &[ParamType::Uint(256)]);
+        let address = Address::repeat_byte(0x42);
+        let data = Bytes(function.encode_input(&[]).unwrap());
+        let method = || {
+            ViewMethodBuilder::<_, U256>::from_method(MethodBuilder::new(
+                web3.clone(),
+                function.clone(),
+                address,
+                data.clone(),
+            ))
+            .from(Address::repeat_byte(1))
+        };
+
+        let mut batch = CallBatch::new(transport.clone());
+        let first = batch.add_with(method(), CallOverrides::new());
+        let second = batch.add_with(
+            method(),
+            CallOverrides::new()
+                .from(Address::repeat_byte(2))
+                .block(BlockNumber::Number(42.into()).into())
+                .value(1.into()),
+        );
+
+        transport.add_response(json!([
+            json!("0x0000000000000000000000000000000000000000000000000000000000000001"),
+            json!("0x0000000000000000000000000000000000000000000000000000000000000002"),
+        ]));
+        batch.execute().immediate();
+
+        assert_eq!(first.immediate().unwrap(), U256::from(1));
+        assert_eq!(second.immediate().unwrap(), U256::from(2));
+        transport.assert_request(
+            "eth_call",
+            &[
+                json!({ "from": Address::repeat_byte(1), "to": address, "data": data }),
+                json!("latest"),
+            ],
+        );
+        transport.assert_request(
+            "eth_call",
+            &[
+                json!({
+                    "from": Address::repeat_byte(2),
+                    "to": address,
+                    "value": "0x1",
+                    "data": data,
+                }),
+                json!("0x2a"),
+            ],
+        );
+    }
+
+    #[test]
+    fn batches_view_methods_with_state_overrides() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let function = function("test", &[], &[ParamType::Uint(256)]);
+        let address = Address::repeat_byte(0x42);
+        let data = Bytes(function.encode_input(&[]).unwrap());
+        let method = || {
+            ViewMethodBuilder::<_, U256>::from_method(MethodBuilder::new(
+                web3.clone(),
+                function.clone(),
+                address,
+                data.clone(),
+            ))
+        };
+
+        let mut batch = CallBatch::new(transport.clone());
+        let first = batch.add(method());
+        let second =
+            batch.add(method().state_override(address, StateOverride::new().balance(1_000.into())));
+
+        transport.add_response(json!([
+            json!("0x0000000000000000000000000000000000000000000000000000000000000001"),
+            json!("0x0000000000000000000000000000000000000000000000000000000000000002"),
+        ]));
+        batch.execute().immediate();
+
+        assert_eq!(first.immediate().unwrap(), U256::from(1));
+        assert_eq!(second.immediate().unwrap(), U256::from(2));
+        transport.assert_request(
+            "eth_call",
+            &[json!({ "to": address, "data": data }), json!("latest")],
+        );
+        transport.assert_request(
+            "eth_call",
+            &[
+                json!({ "to": address, "data": data }),
+                json!("latest"),
+                json!({ format!("{:?}", address): { "balance": "0x3e8" } }),
+            ],
+        );
+    }
+
     #[test]
     fn resolves_calls_to_error_if_dropped() {
         let future = {
@@ -0,0 +1,390 @@
+//! Module implements aggregating contract calls into a single call to a
+//! deployed `Multicall2` or `Multicall3` contract.
+
+use crate::errors::ExecutionError;
+use ethcontract_common::abi::{self, ParamType, Token};
+use ethcontract_common::hash::{self, H32};
+use futures::channel::oneshot::{channel, Sender};
+use web3::api::Web3;
+use web3::error::{Error as Web3Error, TransportError};
+use web3::types::{Address, BlockId, Bytes, CallRequest, H160};
+use web3::Transport;
+
+/// The address of the `Multicall3` contract, which is deployed at the same
+/// address on most EVM chains.
+pub const MULTICALL3_ADDRESS: Address = H160([
+    0xca, 0x11, 0xbd, 0xe0, 0x59, 0x77, 0xb3, 0x63, 0x11, 0x67, 0x02, 0x88, 0x62, 0xbe, 0x2a, 0x17,
+    0x39, 0x76, 0xca, 0x11,
+]);
+
+/// The version of a deployed multicall contract.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MulticallVersion {
+    /// The `Multicall2` contract, aggregating calls with `tryAggregate`.
+    Multicall2,
+    /// The `Multicall3` contract, aggregating calls with `aggregate3`.
+    Multicall3,
+}
+
+/// Struct allowing to aggregate multiple view method calls into a single
+/// `eth_call` to a multicall contract.
+///
+/// Unlike [`CallBatch`](super::CallBatch), which sends multiple `eth_call`
+/// requests in a single JSON RPC batch, this executes all calls in a single
+/// `eth_call` and therefore works with any transport. All calls are executed
+/// at the same block.
+pub struct Multicall<T: Transport> {
+    web3: Web3<T>,
+    version: MulticallVersion,
+    address: Address,
+    block: Option<BlockId>,
+    calls: Vec<(Address, Bytes, CompletionHandler)>,
+}
+
+type CompletionHandler = Sender<Result<Bytes, ExecutionError>>;
+
+impl<T: Transport> Multicall<T> {
+    /// Creates a new multicall using the `Multicall3` contract deployed at its
+    /// canonical address.
+    pub fn new(web3: Web3<T>) -> Self {
+        Multicall::at(web3, MulticallVersion::Multicall3, MULTICALL3_ADDRESS)
+    }
+
+    /// Creates a new multicall using the multicall contract of the specified
+    /// version deployed at the specified address.
+    pub fn at(web3: Web3<T>, version: MulticallVersion, address: Address) -> Self {
+        Multicall {
+            web3,
+            version,
+            address,
+            block: None,
+            calls: Vec::new(),
+        }
+    }
+
+    /// Specify the block height at which all calls are executed. If not
+    /// specified, the latest mined block will be used. Note that the blocks of
+    /// individual view methods are ignored.
+    pub fn block(mut self, value: BlockId) -> Self {
+        self.block = Some(value);
+        self
+    }
+
+    /// Returns the number of calls in the multicall.
+    pub fn len(&self) -> usize {
+        self.calls.len()
+    }
+
+    /// Returns `true` if no calls were added to the multicall.
+    pub fn is_empty(&self) -> bool {
+        self.calls.is_empty()
+    }
+
+    /// Adds a call request to the multicall. Only the target address and call
+    /// data of the request are used. The resulting future can only resolve
+    /// after the multicall has been executed with `aggregate` or
+    /// `try_aggregate`.
+    ///
+    /// Panics, if the multicall is dropped before executing.
+    pub fn push(
+        &mut self,
+        call: CallRequest,
+    ) -> impl std::future::Future<Output = Result<Bytes, ExecutionError>> {
+        let (tx, rx) = channel();
+        self.calls.push((
+            call.to.unwrap_or_default(),
+            call.data.unwrap_or_default(),
+            tx,
+        ));
+        async move {
+            rx.await.unwrap_or_else(|_| {
+                Err(ExecutionError::Web3(Web3Error::Transport(
+                    TransportError::Message(
+                        "Multicall has been dropped without executing".to_owned(),
+                    ),
+                )))
+            })
+        }
+    }
+
+    /// Executes all calls, requiring every call to succeed. If any call
+    /// fails, the whole multicall reverts and all calls resolve to an error.
+    pub async fn aggregate(self) {
+        self.execute(false).await
+    }
+
+    /// Executes all calls, allowing individual calls to fail. Failed calls
+    /// resolve to an error decoded from their revert data, while the results
+    /// of successful calls are still returned.
+    pub async fn try_aggregate(self) {
+        self.execute(true).await
+    }
+
+    async fn execute(self, allow_failure: bool) {
+        let Self {
+            web3,
+            version,
+            address,
+            block,
+            calls,
+        } = self;
+        if calls.is_empty() {
+            return;
+        }
+
+        let (targets, senders): (Vec<_>, Vec<_>) = calls
+            .into_iter()
+            .map(|(target, data, sender)| ((target, data), sender))
+            .unzip();
+        let request = CallRequest {
+            to: Some(address),
+            data: Some(encode_calls(version, allow_failure, targets)),
+            ..Default::default()
+        };
+
+        let results = web3
+            .eth()
+            .call(request, block)
+            .await
+            .and_then(|bytes| decode_results(&bytes.0, senders.len()));
+        match results {
+            Ok(results) => {
+                for (sender, (success, data)) in senders.into_iter().zip(results) {
+                    let _ = sender.send(if success {
+                        Ok(Bytes(data))
+                    } else {
+                        Err(ExecutionError::from_revert_data(data))
+                    });
+                }
+            }
+            Err(err) => {
+                for sender in senders {
+                    let _ = sender.send(Err(err.clone().into()));
+                }
+            }
+        }
+    }
+}
+
+/// Encodes the call data for aggregating calls with a multicall contract.
+fn encode_calls(
+    version: MulticallVersion,
+    allow_failure: bool,
+    calls: Vec<(Address, Bytes)>,
+) -> Bytes {
+    let (selector, params) = match version {
+        MulticallVersion::Multicall2 => (
+            hash::function_selector("tryAggregate(bool,(address,bytes)[])"),
+            vec![
+                Token::Bool(!allow_failure),
+                Token::Array(
+                    calls
+                        .into_iter()
+                        .map(|(target, data)| {
+                            Token::Tuple(vec![Token::Address(target), Token::Bytes(data.0)])
+                        })
+                        .collect(),
+                ),
+            ],
+        ),
+        MulticallVersion::Multicall3 => (
+            hash::function_selector("aggregate3((address,bool,bytes)[])"),
+            vec![Token::Array(
+                calls
+                    .into_iter()
+                    .map(|(target, data)| {
+                        Token::Tuple(vec![
+                            Token::Address(target),
+                            Token::Bool(allow_failure),
+                            Token::Bytes(data.0),
+                        ])
+                    })
+                    .collect(),
+            )],
+        ),
+    };
+
+    Bytes(encode_with_selector(selector, &params))
+}
+
+fn encode_with_selector(selector: H32, params: &[Token]) -> Vec<u8> {
+    let mut data = selector.to_vec();
+    data.extend(abi::encode(params));
+    data
+}
+
+/// Decodes the `(bool success, bytes returnData)[]` results of a multicall.
+fn decode_results(data: &[u8], len: usize) -> Result<Vec<(bool, Vec<u8>)>, Web3Error> {
+    let kind = ParamType::Array(Box::new(ParamType::Tuple(vec![
+        ParamType::Bool,
+        ParamType::Bytes,
+    ])));
+    let results = match abi::decode(&[kind], data)
+        .map_err(|err| Web3Error::Decoder(err.to_string()))?
+        .pop()
+    {
+        Some(Token::Array(results)) => results,
+        _ => unreachable!("decoded multicall results do not match ABI"),
+    };
+    if results.len() != len {
+        return Err(Web3Error::Decoder(
+            "Multicall result did not contain enough results".to_owned(),
+        ));
+    }
+
+    Ok(results
+        .into_iter()
+        .map(|result| match result {
+            Token::Tuple(mut result) => match (result.pop(), result.pop()) {
+                (Some(Token::Bytes(data)), Some(Token::Bool(success))) => (success, data),
+                _ => unreachable!("decoded multicall result does not match ABI"),
+            },
+            _ => unreachable!("decoded multicall result does not match ABI"),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::revert;
+    use crate::test::prelude::*;
+    use futures::future::join_all;
+    use serde_json::Value;
+    use web3::types::BlockNumber;
+
+    fn results(results: &[(bool, Vec<u8>)]) -> Value {
+        let data = abi::encode(&[Token::Array(
+            results
+                .iter()
+                .map(|(success, data)| {
+                    Token::Tuple(vec![Token::Bool(*success), Token::Bytes(data.clone())])
+                })
+                .collect(),
+        )]);
+        json!(Bytes(data))
+    }
+
+    fn call(target: u8, data: &[u8]) -> CallRequest {
+        CallRequest {
+            to: Some(Address::repeat_byte(target)),
+            data: Some(Bytes(data.to_vec())),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn aggregates_calls() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let mut multicall = Multicall::new(web3);
+        let calls = vec![multicall.push(call(1, &[1])), multicall.push(call(2, &[2]))];
+
+        transport.add_response(results(&[(true, vec![3]), (true, vec![4])]));
+        multicall.aggregate().immediate();
+
+        let results = join_all(calls).immediate();
+        assert_eq!(results[0].as_ref().unwrap().0, [3]);
+        assert_eq!(results[1].as_ref().unwrap().0, [4]);
+
+        let data = encode_with_selector(
+            hash::function_selector("aggregate3((address,bool,bytes)[])"),
+            &[Token::Array(vec![
+                Token::Tuple(vec![
+                    Token::Address(Address::repeat_byte(1)),
+                    Token::Bool(false),
+                    Token::Bytes(vec![1]),
+                ]),
+                Token::Tuple(vec![
+                    Token::Address(Address::repeat_byte(2)),
+                    Token::Bool(false),
+                    Token::Bytes(vec![2]),
+                ]),
+            ])],
+        );
+        transport.assert_request(
+            "eth_call",
+            &[
+                json!({ "to": MULTICALL3_ADDRESS, "data": Bytes(data) }),
+                json!("latest"),
+            ],
+        );
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn try_aggregate_with_multicall2() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let address = Address::repeat_byte(0x42);
+        let mut multicall = Multicall::at(web3, MulticallVersion::Multicall2, address)
+            .block(BlockNumber::Number(42.into()).into());
+        let ok = multicall.push(call(1, &[1]));
+        let failed = multicall.push(call(2, &[2]));
+
+        transport.add_response(results(&[
+            (true, vec![3]),
+            (false, revert::encode_reason("failed")),
+        ]));
+        multicall.try_aggregate().immediate();
+
+        assert_eq!(ok.immediate().unwrap().0, [3]);
+        assert_eq!(
+            failed.immediate().unwrap_err().revert_reason(),
+            Some("failed")
+        );
+
+        let data = encode_with_selector(
+            hash::function_selector("tryAggregate(bool,(address,bytes)[])"),
+            &[
+                Token::Bool(false),
+                Token::Array(vec![
+                    Token::Tuple(vec![
+                        Token::Address(Address::repeat_byte(1)),
+                        Token::Bytes(vec![1]),
+                    ]),
+                    Token::Tuple(vec![
+                        Token::Address(Address::repeat_byte(2)),
+                        Token::Bytes(vec![2]),
+                    ]),
+                ]),
+            ],
+        );
+        transport.assert_request(
+            "eth_call",
+            &[json!({ "to": address, "data": Bytes(data) }), json!("0x2a")],
+        );
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn fails_all_calls_if_multicall_fails() {
+        let transport = TestTransport::new();
+        let web3 = Web3::new(transport);
+
+        let mut multicall = Multicall::new(web3);
+        let call = multicall.push(call(1, &[1]));
+
+        multicall.aggregate().immediate();
+        assert!(matches!(
+            call.immediate().unwrap_err(),
+            ExecutionError::Web3(_)
+        ));
+    }
+
+    #[test]
+    fn resolves_calls_to_error_if_dropped() {
+        let future = {
+            let web3 = Web3::new(TestTransport::new());
+            let mut multicall = Multicall::new(web3);
+            multicall.push(call(1, &[1]))
+        };
+
+        assert!(matches!(
+            future.immediate().unwrap_err(),
+            ExecutionError::Web3(Web3Error::Transport(_))
+        ));
+    }
+}
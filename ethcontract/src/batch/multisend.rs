@@ -0,0 +1,100 @@
+//! Module implements encoding transactions into a single transaction to a
+//! deployed Gnosis Safe `MultiSendCallOnly` contract.
+
+use ethcontract_common::abi::{self, Token};
+use ethcontract_common::hash;
+use web3::types::{Address, Bytes, H160, U256};
+
+/// The address of the Gnosis Safe v1.3.0 `MultiSendCallOnly` contract, which
+/// is deployed at the same address on most EVM chains.
+pub const MULTISEND_CALL_ONLY_ADDRESS: Address = H160([
+    0x40, 0xa2, 0xac, 0xcb, 0xd9, 0x2b, 0xca, 0x93, 0x8b, 0x02, 0x01, 0x0e, 0x17, 0xa5, 0xb8, 0x92,
+    0x9b, 0x49, 0x13, 0x0d,
+]);
+
+/// Encodes transactions into the packed payload accepted by the `multiSend`
+/// method of a multisend contract.
+///
+/// Each transaction is a `(to, value, calldata)` tuple, for example as
+/// returned by [`MethodBuilder::into_inner_tx`], and is encoded as a call
+/// operation. Delegate calls are not supported, so the payload can be
+/// executed by the `MultiSendCallOnly` contract.
+///
+/// [`MethodBuilder::into_inner_tx`]: crate::contract::MethodBuilder::into_inner_tx
+pub fn multisend_payload(transactions: impl IntoIterator<Item = (Address, U256, Bytes)>) -> Bytes {
+    let mut payload = Vec::new();
+    for (to, value, data) in transactions {
+        // NOTE: Operation `0` is a call, as opposed to `1` for delegate call.
+        payload.push(0);
+        payload.extend_from_slice(to.as_bytes());
+        payload.extend_from_slice(&u256_bytes(value));
+        payload.extend_from_slice(&u256_bytes(data.0.len().into()));
+        payload.extend_from_slice(&data.0);
+    }
+
+    Bytes(payload)
+}
+
+/// Encodes transactions into the calldata for calling `multiSend` on a
+/// multisend contract, so that they are executed atomically in a single
+/// transaction. See [`multisend_payload`] for how transactions are encoded.
+///
+/// The resulting calldata should be sent to, or proposed as a delegate call
+/// from a Gnosis Safe to, a `MultiSendCallOnly` contract, for example the one
+/// deployed at [`MULTISEND_CALL_ONLY_ADDRESS`].
+pub fn multisend(transactions: impl IntoIterator<Item = (Address, U256, Bytes)>) -> Bytes {
+    let mut data = hash::function_selector("multiSend(bytes)").to_vec();
+    data.extend(abi::encode(&[Token::Bytes(
+        multisend_payload(transactions).0,
+    )]));
+
+    Bytes(data)
+}
+
+fn u256_bytes(value: U256) -> [u8; 32] {
+    let mut bytes = [0; 32];
+    value.to_big_endian(&mut bytes);
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_payload() {
+        let payload = multisend_payload(vec![
+            (Address::repeat_byte(1), 2.into(), Bytes(vec![3, 4])),
+            (Address::repeat_byte(5), 0.into(), Bytes(vec![])),
+        ]);
+
+        let mut expected = vec![0];
+        expected.extend([1; 20]);
+        expected.extend([0; 31]);
+        expected.push(2);
+        expected.extend([0; 31]);
+        expected.push(2);
+        expected.extend([3, 4]);
+        expected.push(0);
+        expected.extend([5; 20]);
+        expected.extend([0; 64]);
+        assert_eq!(payload.0, expected);
+    }
+
+    #[test]
+    fn encodes_empty_payload() {
+        assert!(multisend_payload(vec![]).0.is_empty());
+    }
+
+    #[test]
+    fn encodes_multisend_call() {
+        let transactions = vec![(Address::repeat_byte(1), 2.into(), Bytes(vec![3, 4]))];
+        let data = multisend(transactions.clone());
+
+        assert_eq!(data.0[..4], [0x8d, 0x80, 0xff, 0x0a]);
+        assert_eq!(
+            abi::decode(&[abi::ParamType::Bytes], &data.0[4..]).unwrap(),
+            [Token::Bytes(multisend_payload(transactions).0)],
+        );
+    }
+}
@@ -2,61 +2,47 @@
 //! for sending transactions to contracts as well as querying current contract
 //! state.
 
+mod call;
 mod deploy;
 mod event;
+mod linked;
 mod method;
+mod multichain;
+mod state_override;
+mod summary;
+mod traces;
 
 use crate::{
-    errors::{DeployError, LinkError},
+    errors::{DeployError, ExecutionError, LinkError},
     tokens::Tokenize,
+    transaction::GasPriceStrategy,
 };
-use ethcontract_common::hash::H32;
 use ethcontract_common::{
     abi::{encode, Error as AbiError, Result as AbiResult},
     contract::Interface,
 };
-use ethcontract_common::{Abi, Bytecode, Contract, DeploymentInformation};
-use std::hash::Hash;
+use ethcontract_common::{Abi, Bytecode, BytecodeMetadata, Contract, DeploymentInformation};
 use std::sync::Arc;
 use web3::api::Web3;
 use web3::types::{Address, Bytes, H256};
 use web3::Transport;
 
-pub use self::deploy::{Deploy, DeployBuilder};
+pub use self::call::{transaction_call, ParseCall, TransactionCall};
+pub use self::deploy::{
+    constructor_calldata, create2_address, decode_constructor_args, Deploy, DeployBuilder,
+    RetryPolicy, CREATE2_DEPLOYER,
+};
 pub use self::event::{
-    AllEventsBuilder, Event, EventBuilder, EventMetadata, EventStatus, ParseLog, RawLog,
-    StreamEvent, Topic,
+    topic_hash, AllEventsBuilder, Event, EventBuilder, EventMetadata, EventStatus, EventSubset,
+    Hashed, ParseLog, RawLog, StreamEvent, Topic,
 };
+pub use self::linked::{LinkedDeployer, LinkedDeployment};
 pub use self::method::{MethodBuilder, MethodDefaults, ViewMethodBuilder};
-use std::marker::PhantomData;
-
-/// Method signature with additional info about method's input and output types.
-///
-/// Additional type parameters are used to help with type inference
-/// for instance's [`method`] and [`view_method`] functions.
-///
-/// [`method`]: `Instance::method`
-/// [`view_method`]: `Instance::view_method`
-#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
-pub struct Signature<P, R>(pub H32, pub std::marker::PhantomData<(P, R)>);
-
-impl<P, R> Signature<P, R> {
-    /// Wraps raw signature.
-    pub fn new(signature: H32) -> Self {
-        Signature(signature, PhantomData)
-    }
-
-    /// Unwraps raw signature.
-    pub fn into_inner(self) -> H32 {
-        self.0
-    }
-}
-
-impl<P, R> From<H32> for Signature<P, R> {
-    fn from(signature: H32) -> Self {
-        Signature::new(signature)
-    }
-}
+pub use self::multichain::MultichainInstance;
+pub use self::state_override::{StateOverride, StateOverrides};
+pub use self::summary::{MethodSummary, ParamSummary};
+pub use self::traces::{InternalCall, TracesBuilder};
+pub use ethcontract_core::Signature;
 
 /// Represents a contract instance at an address. Provides methods for
 /// contract interaction.
@@ -68,6 +54,10 @@ pub struct Instance<T: Transport> {
     /// Default method parameters to use when sending method transactions or
     /// querying method calls.
     pub defaults: MethodDefaults,
+    /// Optional strategy for determining the gas price of method transactions
+    /// at the moment they are sent. Takes precedence over the default gas
+    /// price.
+    pub gas_price_strategy: Option<GasPriceStrategy<T>>,
     interface: Arc<Interface>,
 }
 
@@ -101,6 +91,7 @@ impl<T: Transport> Instance<T> {
             address,
             deployment_information,
             defaults: Default::default(),
+            gas_price_strategy: None,
         }
     }
 
@@ -179,6 +170,19 @@ impl<T: Transport> Instance<T> {
         self.deployment_information
     }
 
+    /// Retrieves the runtime bytecode of the contract with `eth_getCode` and
+    /// extracts the `solc` metadata appended to it. This includes the hash of
+    /// the contract's metadata file, which can be used to look up its sources,
+    /// and the compiler version.
+    ///
+    /// Returns `None` if the deployed bytecode does not contain metadata, for
+    /// example because it was compiled with `--metadata-hash none` or is not a
+    /// Solidity contract.
+    pub async fn bytecode_metadata(&self) -> Result<Option<BytecodeMetadata>, ExecutionError> {
+        let code = self.web3.eth().code(self.address, None).await?;
+        Ok(BytecodeMetadata::from_runtime_code(&code.0).ok())
+    }
+
     /// Returns a method builder to setup a call or transaction on a smart
     /// contract method. Note that calls just get evaluated on a node but do not
     /// actually commit anything to the block chain.
@@ -211,10 +215,13 @@ impl<T: Transport> Instance<T> {
         let function = function.clone();
         let data = Bytes(data);
 
-        Ok(
-            MethodBuilder::new(self.web3(), function, self.address, data)
-                .with_defaults(&self.defaults),
-        )
+        let method = MethodBuilder::new(self.web3(), function, self.address, data)
+            .interface(self.interface.clone())
+            .with_defaults(&self.defaults);
+        Ok(match &self.gas_price_strategy {
+            Some(strategy) => method.gas_price_strategy(strategy.clone()),
+            None => method,
+        })
     }
 
     /// Returns a view method builder to setup a call to a smart contract. View
@@ -277,6 +284,13 @@ impl<T: Transport> Instance<T> {
     pub fn all_events(&self) -> AllEventsBuilder<T, RawLog> {
         AllEventsBuilder::new(self.web3(), self.address(), self.deployment_information())
     }
+
+    /// Returns a builder for retrieving the calls made to this contract
+    /// instance, including internal calls made by other contracts, with
+    /// `trace_filter`. This requires a node that supports the trace APIs.
+    pub fn traces(&self) -> TracesBuilder<T> {
+        TracesBuilder::new(self.web3(), self.interface.clone(), self.address)
+    }
 }
 
 /// Builder for specifying linking options for a contract.
@@ -417,4 +431,27 @@ mod tests {
             err
         );
     }
+
+    #[test]
+    fn bytecode_metadata() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let address = addr!("0x0102030405060708091011121314151617181920");
+        let instance = Instance::at(web3, Arc::new(Abi::default().into()), address);
+
+        transport.add_response(json!("0x6080604052a164736f6c6343000813000a")); // eth_getCode response
+        let metadata = instance
+            .bytecode_metadata()
+            .immediate()
+            .expect("failed to get bytecode metadata")
+            .expect("missing bytecode metadata");
+        assert_eq!(metadata.solc.unwrap().to_string(), "0.8.19");
+
+        transport.assert_request("eth_getCode", &[json!(address), json!("latest")]);
+        transport.assert_no_more_requests();
+
+        transport.add_response(json!("0x")); // eth_getCode response
+        assert_eq!(instance.bytecode_metadata().immediate().unwrap(), None);
+    }
 }
@@ -0,0 +1,154 @@
+//! Module for retrieving transactions and decoding their calldata into typed
+//! contract method calls.
+
+use crate::errors::ExecutionError;
+use web3::api::Web3;
+use web3::types::{Transaction, TransactionId, H256};
+use web3::Transport;
+
+/// A trait for parsing transaction calldata into a typed contract method
+/// call. This is implemented by the `Call` enum of generated contracts.
+pub trait ParseCall: Sized {
+    /// Parses ABI encoded calldata starting with the 4-byte method selector.
+    fn parse_call(data: &[u8]) -> Result<Self, ExecutionError>;
+}
+
+/// A transaction along with its calldata decoded as a contract method call.
+#[derive(Clone, Debug)]
+pub struct TransactionCall<C> {
+    /// The transaction as returned by the node.
+    pub transaction: Transaction,
+    /// The decoded contract method call.
+    pub call: C,
+}
+
+impl<C> TransactionCall<C> {
+    /// Returns the hash of the transaction.
+    pub fn hash(&self) -> H256 {
+        self.transaction.hash
+    }
+
+    /// Returns true if the transaction is still pending and has not yet been
+    /// included in a block.
+    pub fn is_pending(&self) -> bool {
+        self.transaction.block_number.is_none()
+    }
+}
+
+/// Retrieves a transaction by hash with `eth_getTransactionByHash` and decodes
+/// its calldata as a contract method call. Returns `None` if the node does not
+/// know about the transaction.
+///
+/// Note that the transaction's recipient is not checked, so it is up to the
+/// caller to verify that the transaction was sent to the expected contract.
+pub async fn transaction_call<T, C>(
+    web3: &Web3<T>,
+    hash: H256,
+) -> Result<Option<TransactionCall<C>>, ExecutionError>
+where
+    T: Transport,
+    C: ParseCall,
+{
+    let transaction = match web3.eth().transaction(TransactionId::Hash(hash)).await? {
+        Some(transaction) => transaction,
+        None => return Ok(None),
+    };
+    let call = C::parse_call(&transaction.input.0)?;
+
+    Ok(Some(TransactionCall { transaction, call }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::prelude::*;
+    use ethcontract_common::abi::ParamType;
+    use web3::types::{Address, U256};
+
+    #[derive(Debug, Eq, PartialEq)]
+    struct Transfer(Address, U256);
+
+    impl ParseCall for Transfer {
+        fn parse_call(data: &[u8]) -> Result<Self, ExecutionError> {
+            let abi = abi(vec![function(
+                "transfer",
+                &[("to", ParamType::Address), ("value", ParamType::Uint(256))],
+                &[],
+            )]);
+
+            crate::private::decode_call::<(Address, U256)>(&abi, data)
+                .map(|(to, value)| Transfer(to, value))
+        }
+    }
+
+    fn transaction(input: &str, block_number: Option<&str>) -> serde_json::Value {
+        json!({
+            "hash": H256::repeat_byte(0x11),
+            "nonce": "0x0",
+            "blockHash": block_number.map(|_| H256::repeat_byte(0x22)),
+            "blockNumber": block_number,
+            "transactionIndex": block_number.map(|_| "0x0"),
+            "from": Address::repeat_byte(0x01),
+            "to": Address::repeat_byte(0x02),
+            "value": "0x0",
+            "gasPrice": "0x1",
+            "gas": "0x5208",
+            "input": input,
+        })
+    }
+
+    #[test]
+    fn fetch_and_decode_transaction_call() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+        let hash = H256::repeat_byte(0x11);
+
+        let input = format!(
+            "0xa9059cbb{:0>64}{:0>64}",
+            "42".repeat(20),
+            format!("{:x}", 1337),
+        );
+        transport.add_response(transaction(&input, None));
+        let call = transaction_call::<_, Transfer>(&web3, hash)
+            .immediate()
+            .unwrap()
+            .unwrap();
+
+        transport.assert_request("eth_getTransactionByHash", &[json!(hash)]);
+        transport.assert_no_more_requests();
+        assert_eq!(call.hash(), hash);
+        assert!(call.is_pending());
+        assert_eq!(call.call, Transfer(Address::repeat_byte(0x42), 1337.into()));
+
+        transport.add_response(transaction(&input, Some("0x1")));
+        let call = transaction_call::<_, Transfer>(&web3, hash)
+            .immediate()
+            .unwrap()
+            .unwrap();
+        assert!(!call.is_pending());
+    }
+
+    #[test]
+    fn missing_transaction_call() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        transport.add_response(json!(null));
+        let call = transaction_call::<_, Transfer>(&web3, H256::repeat_byte(0x11))
+            .immediate()
+            .unwrap();
+        assert!(call.is_none());
+    }
+
+    #[test]
+    fn invalid_transaction_calldata() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        transport.add_response(transaction("0xdeadbeef", None));
+        let err = transaction_call::<_, Transfer>(&web3, H256::repeat_byte(0x11))
+            .immediate()
+            .unwrap_err();
+        assert!(matches!(err, ExecutionError::AbiDecode(_)));
+    }
+}
@@ -3,14 +3,30 @@
 
 use crate::errors::{DeployError, ExecutionError};
 use crate::tokens::Tokenize;
-use crate::transaction::{Account, GasPrice, TransactionBuilder, TransactionResult};
+use crate::transaction::confirm::{self, ReplacementPolicy, DEFAULT_REPLACEMENT_GAS_PRICE_FACTOR};
+use crate::transaction::{
+    Account, GasPrice, GasPriceStrategy, NonceManager, TransactionBuilder, TransactionResult,
+};
 use ethcontract_common::abi::Error as AbiError;
+use ethcontract_common::hash::keccak256;
 use ethcontract_common::{Abi, Bytecode};
+use std::future::Future;
 use std::marker::PhantomData;
+use std::sync::Arc;
+use std::time::Duration;
 use web3::api::Web3;
-use web3::types::{Address, Bytes, H256, U256};
+use web3::types::{Address, Bytes, TransactionId, H160, H256, U256};
 use web3::Transport;
 
+/// The address of the deterministic deployment proxy, a `CREATE2` deployer
+/// contract that is deployed at the same address on most EVM chains.
+///
+/// See <https://github.com/Arachnid/deterministic-deployment-proxy>.
+pub const CREATE2_DEPLOYER: Address = H160([
+    0x4e, 0x59, 0xb4, 0x48, 0x47, 0xb3, 0x79, 0x57, 0x85, 0x88, 0x92, 0x0c, 0xa7, 0x8f, 0xbf, 0x26,
+    0xc0, 0xb4, 0x95, 0x6c,
+]);
+
 /// a factory trait for deployable contract instances. this traits provides
 /// functionality for building a deployment and creating instances of a
 /// contract type at a given address.
@@ -50,6 +66,10 @@ where
     context: I::Context,
     /// The underlying transaction used t
     tx: TransactionBuilder<T>,
+    /// The salt and deployer contract for `CREATE2` deployments.
+    create2: Option<(H256, Address)>,
+    /// The policy for retrying failed or stuck deployments.
+    retry: Option<RetryPolicy>,
     _instance: PhantomData<I>,
 }
 
@@ -68,26 +88,14 @@ where
         //   `rust-web3` code so that we can add things like signing support;
         //   luckily most of complicated bits can be reused from the tx code
 
-        let bytecode = I::bytecode(&context);
-        if bytecode.is_empty() {
-            return Err(DeployError::EmptyBytecode);
-        }
-
-        let code = bytecode.to_bytes()?;
-        let params = match params.into_token() {
-            ethcontract_common::abi::Token::Tuple(tokens) => tokens,
-            _ => unreachable!("function arguments are always tuples"),
-        };
-        let data = match (I::abi(&context).constructor(), params.is_empty()) {
-            (None, false) => return Err(AbiError::InvalidData.into()),
-            (None, true) => code,
-            (Some(ctor), _) => Bytes(ctor.encode_input(code.0, &params)?),
-        };
+        let data = constructor_calldata(I::abi(&context), I::bytecode(&context), params)?;
 
         Ok(DeployBuilder {
             web3: web3.clone(),
             context,
             tx: TransactionBuilder::new(web3).data(data).confirmations(0),
+            create2: None,
+            retry: None,
             _instance: PhantomData,
         })
     }
@@ -113,6 +121,24 @@ where
         self
     }
 
+    /// Specify a strategy for determining the gas price at the moment the
+    /// transaction is sent, replacing any previously specified gas price.
+    pub fn gas_price_strategy(mut self, value: GasPriceStrategy<T>) -> Self {
+        self.tx = self.tx.gas_price_strategy(value);
+        self
+    }
+
+    /// Specify an async closure for determining the gas price at the moment
+    /// the transaction is sent, replacing any previously specified gas price.
+    pub fn gas_price_with<F, Fut>(mut self, strategy: F) -> Self
+    where
+        F: Fn(Web3<T>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<GasPrice, ExecutionError>> + Send + 'static,
+    {
+        self.tx = self.tx.gas_price_with(strategy);
+        self
+    }
+
     /// Specify what how much ETH to transfer with the transaction, if not
     /// specified then no ETH will be sent.
     pub fn value(mut self, value: U256) -> Self {
@@ -127,6 +153,13 @@ where
         self
     }
 
+    /// Specify a nonce manager for assigning a nonce to the transaction when
+    /// it is sent, if no nonce was specified.
+    pub fn nonce_manager(mut self, value: Arc<NonceManager>) -> Self {
+        self.tx = self.tx.nonce_manager(value);
+        self
+    }
+
     /// Specify the number of confirmations to wait for when confirming the
     /// transaction, if not specified will wait for the transaction to be mined
     /// without any extra confirmations.
@@ -135,23 +168,146 @@ where
         self
     }
 
+    /// Specify the policy for replacing the transaction with one with a higher
+    /// gas price if it does not get mined in time.
+    pub fn replacement(mut self, value: ReplacementPolicy) -> Self {
+        self.tx = self.tx.replacement(value);
+        self
+    }
+
+    /// Specify the policy for retrying the deployment if sending it fails or
+    /// if it does not get mined before the confirmation times out, if not
+    /// specified then the deployment is attempted once.
+    ///
+    /// The nonce of the deployment is resolved before it is first sent and
+    /// every retry reuses it, so that a retry can only ever replace a
+    /// deployment transaction that did reach the node instead of deploying
+    /// the contract a second time. Each retry increases the gas price
+    /// according to the policy. Deployments that get mined but revert are
+    /// never retried.
+    pub fn retry(mut self, value: RetryPolicy) -> Self {
+        self.retry = Some(value);
+        self
+    }
+
+    /// Specify the maximum size of the transaction calldata in bytes, if not
+    /// specified then calldata of any size will be sent to the node. Note
+    /// that the calldata of a deployment includes the contract bytecode.
+    pub fn max_calldata_size(mut self, value: usize) -> Self {
+        self.tx = self.tx.max_calldata_size(value);
+        self
+    }
+
+    /// Deploy the contract with `CREATE2` through a deployer contract, so
+    /// that its address only depends on the deployer address, the salt and the
+    /// contract's creation code (that is, its bytecode and constructor
+    /// arguments).
+    ///
+    /// The deployer is called with the salt followed by the creation code as
+    /// calldata, which is the calling convention of the deterministic
+    /// deployment proxy at [`CREATE2_DEPLOYER`].
+    pub fn create2(mut self, salt: H256, factory: Address) -> Self {
+        self.create2 = Some((salt, factory));
+        self
+    }
+
+    /// Returns the address the contract will be deployed to for `CREATE2`
+    /// deployments, or `None` if the contract is deployed with a regular
+    /// contract creation transaction.
+    pub fn deterministic_address(&self) -> Option<Address> {
+        let (salt, factory) = self.create2?;
+        let code = self
+            .tx
+            .data
+            .as_ref()
+            .map(|data| &data.0[..])
+            .unwrap_or_default();
+        Some(create2_address(factory, salt, code))
+    }
+
     /// Extract inner `TransactionBuilder` from this `DeployBuilder`. This
     /// exposes `TransactionBuilder` only APIs.
     pub fn into_inner(self) -> TransactionBuilder<T> {
-        self.tx
+        create2_transaction(self.tx, self.create2)
     }
 
     /// Sign (if required) and execute the transaction. Returns the transaction
     /// hash that can be used to retrieve transaction information.
     pub async fn deploy(self) -> Result<I, DeployError> {
-        let tx = match self.tx.send().await? {
-            TransactionResult::Receipt(tx) => tx,
-            TransactionResult::Hash(tx) => return Err(DeployError::Pending(tx)),
+        let deterministic_address = self.deterministic_address();
+        let mut tx = create2_transaction(self.tx, self.create2);
+        let policy = self.retry.unwrap_or_else(RetryPolicy::none);
+
+        // Pin the nonce when retrying, as a failed send does not guarantee
+        // that the transaction did not reach the node.
+        let managed_nonce = match (&tx.nonce_manager, &tx.from, tx.nonce) {
+            (Some(manager), Some(from), None) if policy.max_retries > 0 => {
+                Some((manager.clone(), from.address()))
+            }
+            _ => None,
+        };
+        if policy.max_retries > 0 && tx.nonce.is_none() {
+            let nonce = match &managed_nonce {
+                Some((manager, address)) => manager.next(&self.web3, *address).await?,
+                None => tx.pending_nonce(&self.web3).await?,
+            };
+            tx = tx.nonce(nonce);
+        }
+
+        let mut attempt = 0;
+        let tx = loop {
+            let err = match tx.clone().send().await {
+                Ok(TransactionResult::Receipt(tx)) => break tx,
+                Ok(TransactionResult::Hash(tx)) => return Err(DeployError::Pending(tx)),
+                Err(err) if attempt < policy.max_retries => err,
+                Err(err) => {
+                    if let Some((manager, address)) = managed_nonce {
+                        manager.reset(address).await;
+                    }
+                    return Err(err.into());
+                }
+            };
+
+            tx = match err {
+                // The transaction may or may not have reached the node, so
+                // resubmit it with the same nonce, which at worst replaces it.
+                ExecutionError::Web3(_) => {
+                    let gas_price = current_gas_price(&self.web3, &tx).await?;
+                    tx.gas_price(policy.bump(gas_price))
+                }
+                // The transaction is stuck, so replace it using its nonce.
+                ExecutionError::ConfirmTimeout(result) if result.is_hash() => {
+                    let pending = self
+                        .web3
+                        .eth()
+                        .transaction(TransactionId::Hash(result.hash()))
+                        .await?;
+                    match pending {
+                        Some(pending) => {
+                            let gas_price = match sent_gas_price(&pending) {
+                                Some(gas_price) => gas_price,
+                                None => current_gas_price(&self.web3, &tx).await?,
+                            };
+                            tx.nonce(pending.nonce).gas_price(policy.bump(gas_price))
+                        }
+                        // The transaction was dropped by the node, so it can
+                        // be resubmitted as if sending it failed.
+                        None => {
+                            let gas_price = current_gas_price(&self.web3, &tx).await?;
+                            tx.gas_price(policy.bump(gas_price))
+                        }
+                    }
+                }
+                err => return Err(err.into()),
+            };
+
+            confirm::delay(policy.delay_for(attempt)).await;
+            attempt += 1;
         };
 
         let transaction_hash = tx.transaction_hash;
-        let address = tx
-            .contract_address
+        let address = deterministic_address
+            .or(tx.contract_address)
             .ok_or_else(|| ExecutionError::Failure(Box::new(tx)))?;
 
         Ok(I::from_deployment(
@@ -163,6 +319,265 @@ where
     }
 }
 
+/// A policy for retrying deployments that fail to be sent or that get stuck
+/// on congested networks, with exponential backoff between attempts.
+///
+/// Each retry waits for `delay` multiplied by `backoff_factor` for every
+/// previous retry, up to `max_delay`, and increases the gas price by
+/// `gas_price_factor`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RetryPolicy {
+    /// The maximum number of times to retry the deployment.
+    pub max_retries: usize,
+    /// The delay before the first retry.
+    pub delay: Duration,
+    /// The factor by which the delay is increased for each retry.
+    pub backoff_factor: f64,
+    /// The maximum delay between retries.
+    pub max_delay: Duration,
+    /// The factor by which the gas price is increased for each retry.
+    pub gas_price_factor: f64,
+    /// The maximum gas price, or maximum fee per gas for EIP-1559
+    /// transactions, to bump to.
+    pub max_gas_price: Option<U256>,
+}
+
+/// The default maximum number of deployment retries.
+const DEFAULT_MAX_RETRIES: usize = 3;
+
+/// The default delay before the first deployment retry.
+const DEFAULT_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// The default factor by which the delay between retries is increased.
+const DEFAULT_RETRY_BACKOFF_FACTOR: f64 = 2.0;
+
+/// The default maximum delay between deployment retries.
+const DEFAULT_MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+impl RetryPolicy {
+    /// A policy that never retries.
+    fn none() -> Self {
+        RetryPolicy {
+            max_retries: 0,
+            ..Default::default()
+        }
+    }
+
+    /// Set new value for [`max_retries`].
+    ///
+    /// [`max_retries`]: #structfield.max_retries
+    #[inline]
+    pub fn max_retries(mut self, max_retries: usize) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Set new value for [`delay`].
+    ///
+    /// [`delay`]: #structfield.delay
+    #[inline]
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Set new value for [`backoff_factor`].
+    ///
+    /// [`backoff_factor`]: #structfield.backoff_factor
+    #[inline]
+    pub fn backoff_factor(mut self, backoff_factor: f64) -> Self {
+        self.backoff_factor = backoff_factor;
+        self
+    }
+
+    /// Set new value for [`max_delay`].
+    ///
+    /// [`max_delay`]: #structfield.max_delay
+    #[inline]
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Set new value for [`gas_price_factor`].
+    ///
+    /// [`gas_price_factor`]: #structfield.gas_price_factor
+    #[inline]
+    pub fn gas_price_factor(mut self, gas_price_factor: f64) -> Self {
+        self.gas_price_factor = gas_price_factor;
+        self
+    }
+
+    /// Set new value for [`max_gas_price`].
+    ///
+    /// [`max_gas_price`]: #structfield.max_gas_price
+    #[inline]
+    pub fn max_gas_price(mut self, max_gas_price: Option<U256>) -> Self {
+        self.max_gas_price = max_gas_price;
+        self
+    }
+
+    /// Returns the delay before the retry with the specified zero-based
+    /// index.
+    pub fn delay_for(&self, retry: usize) -> Duration {
+        let factor = self
+            .backoff_factor
+            .powi(retry.min(i32::MAX as usize) as i32);
+        let delay = self.delay.as_secs_f64() * factor;
+        if delay < self.max_delay.as_secs_f64() {
+            Duration::from_secs_f64(delay)
+        } else {
+            self.max_delay
+        }
+    }
+
+    /// Returns the increased gas price for a retry. If the gas price can't be
+    /// increased because of the maximum gas price, it is returned unchanged.
+    pub fn bump(&self, gas_price: GasPrice) -> GasPrice {
+        ReplacementPolicy::default()
+            .gas_price_factor(self.gas_price_factor)
+            .max_gas_price(self.max_gas_price)
+            .bump(gas_price)
+            .unwrap_or(gas_price)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_retries: DEFAULT_MAX_RETRIES,
+            delay: DEFAULT_RETRY_DELAY,
+            backoff_factor: DEFAULT_RETRY_BACKOFF_FACTOR,
+            max_delay: DEFAULT_MAX_RETRY_DELAY,
+            gas_price_factor: DEFAULT_REPLACEMENT_GAS_PRICE_FACTOR,
+            max_gas_price: None,
+        }
+    }
+}
+
+/// Returns the gas price a deployment would currently be sent with.
+async fn current_gas_price<T: Transport>(
+    web3: &Web3<T>,
+    tx: &TransactionBuilder<T>,
+) -> Result<GasPrice, ExecutionError> {
+    if let Some(gas_price) = tx.gas_price {
+        return Ok(gas_price);
+    }
+    match &tx.gas_price_strategy {
+        Some(strategy) => strategy.gas_price(web3).await,
+        None => Ok(GasPrice::Legacy(web3.eth().gas_price().await?)),
+    }
+}
+
+/// Returns the gas price a pending transaction was sent with.
+fn sent_gas_price(tx: &web3::types::Transaction) -> Option<GasPrice> {
+    match (tx.max_fee_per_gas, tx.max_priority_fee_per_gas) {
+        (Some(max_fee_per_gas), Some(max_priority_fee_per_gas)) => Some(GasPrice::Eip1559 {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+        }),
+        _ => tx.gas_price.map(GasPrice::Legacy),
+    }
+}
+
+/// Routes a contract creation transaction through a `CREATE2` deployer
+/// contract if a salt and deployer were specified.
+fn create2_transaction<T: Transport>(
+    tx: TransactionBuilder<T>,
+    create2: Option<(H256, Address)>,
+) -> TransactionBuilder<T> {
+    match create2 {
+        Some((salt, factory)) => {
+            let code = tx.data.clone().unwrap_or_default();
+            let data = [salt.as_bytes(), &code.0].concat();
+            tx.to(factory).data(Bytes(data))
+        }
+        None => tx,
+    }
+}
+
+/// Computes the address of a contract deployed with `CREATE2` by the specified
+/// deployer with a salt and creation code.
+pub fn create2_address(deployer: Address, salt: H256, creation_code: &[u8]) -> Address {
+    let hash = keccak256(
+        [
+            &[0xff],
+            deployer.as_bytes(),
+            salt.as_bytes(),
+            &keccak256(creation_code),
+        ]
+        .concat(),
+    );
+    Address::from_slice(&hash[12..])
+}
+
+/// Encodes the input data of a contract creation transaction, that is the
+/// contract bytecode followed by the ABI encoded constructor arguments.
+///
+/// This allows deploying contracts with external deployers, such as `CREATE2`
+/// factories or multisig wallets. Note that the bytecode must be linked.
+pub fn constructor_calldata<P>(
+    abi: &Abi,
+    bytecode: &Bytecode,
+    params: P,
+) -> Result<Bytes, DeployError>
+where
+    P: Tokenize,
+{
+    if bytecode.is_empty() {
+        return Err(DeployError::EmptyBytecode);
+    }
+
+    let code = bytecode.to_bytes()?;
+    let params = match params.into_token() {
+        ethcontract_common::abi::Token::Tuple(tokens) => tokens,
+        _ => unreachable!("function arguments are always tuples"),
+    };
+    match (abi.constructor(), params.is_empty()) {
+        (None, false) => Err(AbiError::InvalidData.into()),
+        (None, true) => Ok(code),
+        (Some(ctor), _) => Ok(Bytes(ctor.encode_input(code.0, &params)?)),
+    }
+}
+
+/// Decodes the constructor arguments from the input data of a contract
+/// creation transaction.
+///
+/// The creation input is expected to be the contract bytecode followed by the
+/// ABI encoded constructor arguments. Note that only the length of the
+/// bytecode is used for locating the arguments, so the bytecode does not need
+/// to be linked.
+pub fn decode_constructor_args<P>(
+    abi: &Abi,
+    bytecode: &Bytecode,
+    input: &[u8],
+) -> Result<P, DeployError>
+where
+    P: Tokenize,
+{
+    if bytecode.is_empty() {
+        return Err(DeployError::EmptyBytecode);
+    }
+
+    let args = input.get(bytecode.len()..).ok_or(AbiError::InvalidData)?;
+    let tokens = match abi.constructor() {
+        Some(ctor) => {
+            let params = ctor
+                .inputs
+                .iter()
+                .map(|input| input.kind.clone())
+                .collect::<Vec<_>>();
+            ethcontract_common::abi::decode(&params, args)?
+        }
+        None if args.is_empty() => Vec::new(),
+        None => return Err(AbiError::InvalidData.into()),
+    };
+
+    let params = P::from_token(ethcontract_common::abi::Token::Tuple(tokens))
+        .map_err(ExecutionError::from)?;
+    Ok(params)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -209,6 +624,262 @@ mod tests {
         //   on github
     }
 
+    #[test]
+    fn deploy_with_create2() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let from = addr!("0x9876543210987654321098765432109876543210");
+        let salt = H256::repeat_byte(0x42);
+        let bytecode = Bytecode::from_hex_str("0x6080").unwrap();
+        let contract = Contract {
+            bytecode,
+            ..Contract::empty()
+        };
+        let builder = InstanceDeployBuilder::new(web3, Linker::new(contract), ())
+            .expect("error creating deploy builder")
+            .from(Account::Local(from, None))
+            .gas(0x1337.into())
+            .create2(salt, CREATE2_DEPLOYER);
+
+        let address = create2_address(CREATE2_DEPLOYER, salt, &[0x60, 0x80]);
+        assert_eq!(builder.deterministic_address(), Some(address));
+
+        let tx_hash = H256::repeat_byte(0x11);
+        transport.add_response(json!(tx_hash));
+        transport.add_response(json!("0x1"));
+        transport.add_response(json!({
+            "transactionHash": tx_hash,
+            "transactionIndex": "0x0",
+            "blockNumber": "0x1",
+            "blockHash": H256::repeat_byte(1),
+            "cumulativeGasUsed": "0x1337",
+            "gasUsed": "0x1337",
+            "logsBloom": web3::types::H2048::zero(),
+            "logs": [],
+            "status": "0x1",
+        }));
+        let instance = builder.deploy().immediate().expect("deployment failed");
+
+        assert_eq!(instance.address(), address);
+        transport.assert_request(
+            "eth_sendTransaction",
+            &[json!({
+                "from": from,
+                "to": CREATE2_DEPLOYER,
+                "gas": "0x1337",
+                "data": format!("0x{}6080", "42".repeat(32)),
+            })],
+        );
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_getTransactionReceipt", &[json!(tx_hash)]);
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn deploy_retries_failed_send() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let from = addr!("0x9876543210987654321098765432109876543210");
+        let contract = Contract {
+            bytecode: Bytecode::from_hex_str("0x6080").unwrap(),
+            ..Contract::empty()
+        };
+        let builder = InstanceDeployBuilder::new(web3, Linker::new(contract), ())
+            .expect("error creating deploy builder")
+            .from(Account::Local(from, None))
+            .gas(0x1337.into())
+            .nonce(42.into())
+            .retry(RetryPolicy::default().delay(Duration::from_secs(0)));
+
+        let tx_hash = H256::repeat_byte(0x11);
+        transport.add_error(web3::Error::Unreachable);
+        transport.add_response(json!("0x64"));
+        transport.add_response(json!(tx_hash));
+        transport.add_response(json!("0x1"));
+        transport.add_response(json!({
+            "transactionHash": tx_hash,
+            "transactionIndex": "0x0",
+            "blockNumber": "0x1",
+            "blockHash": H256::repeat_byte(1),
+            "cumulativeGasUsed": "0x1337",
+            "gasUsed": "0x1337",
+            "contractAddress": addr!("0x0123456789012345678901234567890123456789"),
+            "logsBloom": web3::types::H2048::zero(),
+            "logs": [],
+            "status": "0x1",
+        }));
+        let instance = builder.deploy().immediate().expect("deployment failed");
+
+        assert_eq!(
+            instance.address(),
+            addr!("0x0123456789012345678901234567890123456789"),
+        );
+        transport.assert_request(
+            "eth_sendTransaction",
+            &[json!({
+                "from": from,
+                "gas": "0x1337",
+                "nonce": "0x2a",
+                "data": "0x6080",
+            })],
+        );
+        transport.assert_request("eth_gasPrice", &[]);
+        // The retry uses a bumped gas price and keeps the explicit nonce.
+        transport.assert_request(
+            "eth_sendTransaction",
+            &[json!({
+                "from": from,
+                "gas": "0x1337",
+                "gasPrice": "0x70",
+                "nonce": "0x2a",
+                "data": "0x6080",
+            })],
+        );
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_getTransactionReceipt", &[json!(tx_hash)]);
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn deploy_retries_with_pinned_nonce() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let from = addr!("0x9876543210987654321098765432109876543210");
+        let contract = Contract {
+            bytecode: Bytecode::from_hex_str("0x6080").unwrap(),
+            ..Contract::empty()
+        };
+        let builder = InstanceDeployBuilder::new(web3, Linker::new(contract), ())
+            .expect("error creating deploy builder")
+            .from(Account::Local(from, None))
+            .gas(0x1337.into())
+            .retry(RetryPolicy::default().delay(Duration::from_secs(0)));
+
+        let tx_hash = H256::repeat_byte(0x11);
+        transport.add_response(json!("0x7"));
+        transport.add_error(web3::Error::Unreachable);
+        transport.add_response(json!("0x64"));
+        transport.add_response(json!(tx_hash));
+        transport.add_response(json!("0x1"));
+        transport.add_response(json!({
+            "transactionHash": tx_hash,
+            "transactionIndex": "0x0",
+            "blockNumber": "0x1",
+            "blockHash": H256::repeat_byte(1),
+            "cumulativeGasUsed": "0x1337",
+            "gasUsed": "0x1337",
+            "contractAddress": addr!("0x0123456789012345678901234567890123456789"),
+            "logsBloom": web3::types::H2048::zero(),
+            "logs": [],
+            "status": "0x1",
+        }));
+        builder.deploy().immediate().expect("deployment failed");
+
+        transport.assert_request("eth_getTransactionCount", &[json!(from), json!("pending")]);
+        transport.assert_request(
+            "eth_sendTransaction",
+            &[json!({
+                "from": from,
+                "gas": "0x1337",
+                "nonce": "0x7",
+                "data": "0x6080",
+            })],
+        );
+        transport.assert_request("eth_gasPrice", &[]);
+        // The retry reuses the nonce of the failed send, so it can only
+        // replace the first transaction if it did reach the node.
+        transport.assert_request(
+            "eth_sendTransaction",
+            &[json!({
+                "from": from,
+                "gas": "0x1337",
+                "gasPrice": "0x70",
+                "nonce": "0x7",
+                "data": "0x6080",
+            })],
+        );
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_getTransactionReceipt", &[json!(tx_hash)]);
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn deploy_does_not_retry_without_policy() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let from = addr!("0x9876543210987654321098765432109876543210");
+        let contract = Contract {
+            bytecode: Bytecode::from_hex_str("0x6080").unwrap(),
+            ..Contract::empty()
+        };
+        let builder = InstanceDeployBuilder::new(web3, Linker::new(contract), ())
+            .expect("error creating deploy builder")
+            .from(Account::Local(from, None))
+            .gas(0x1337.into());
+
+        transport.add_error(web3::Error::Unreachable);
+        let result = builder.deploy().immediate();
+
+        assert!(matches!(
+            result,
+            Err(DeployError::Tx(ExecutionError::Web3(_)))
+        ));
+        transport.assert_request(
+            "eth_sendTransaction",
+            &[json!({
+                "from": from,
+                "gas": "0x1337",
+                "data": "0x6080",
+            })],
+        );
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn retry_policy_backoff() {
+        let policy = RetryPolicy::default()
+            .delay(Duration::from_secs(1))
+            .backoff_factor(2.0)
+            .max_delay(Duration::from_secs(5));
+
+        assert_eq!(policy.delay_for(0), Duration::from_secs(1));
+        assert_eq!(policy.delay_for(1), Duration::from_secs(2));
+        assert_eq!(policy.delay_for(2), Duration::from_secs(4));
+        assert_eq!(policy.delay_for(3), Duration::from_secs(5));
+        assert_eq!(policy.delay_for(usize::MAX), Duration::from_secs(5));
+
+        let policy = policy.max_gas_price(Some(105.into()));
+        assert_eq!(
+            policy.bump(GasPrice::Legacy(100.into())),
+            GasPrice::Legacy(105.into()),
+        );
+        assert_eq!(
+            policy.bump(GasPrice::Legacy(110.into())),
+            GasPrice::Legacy(110.into()),
+        );
+    }
+
+    #[test]
+    fn create2_address_test_vectors() {
+        // test vectors from EIP-1014
+        assert_eq!(
+            create2_address(Address::zero(), H256::zero(), &[0x00]),
+            addr!("0x4D1A2e2bB4F88F0250f26Ffff098B0b30B26BF38"),
+        );
+        assert_eq!(
+            create2_address(
+                addr!("0x00000000000000000000000000000000deadbeef"),
+                H256::from_low_u64_be(0xcafebabe),
+                &hex::decode("deadbeef").unwrap(),
+            ),
+            addr!("0x60f3f640a8508fC6a86d45DF051962668E1e8AC7"),
+        );
+    }
+
     #[test]
     fn deploy_fails_on_empty_bytecode() {
         let transport = TestTransport::new();
@@ -221,4 +892,52 @@ mod tests {
         assert_eq!(error.to_string(), DeployError::EmptyBytecode.to_string());
         transport.assert_no_more_requests();
     }
+
+    #[test]
+    fn decode_constructor_args_from_creation_input() {
+        use ethcontract_common::abi::{Constructor, Param, ParamType, Token};
+
+        let abi = Abi {
+            constructor: Some(Constructor {
+                inputs: vec![
+                    Param {
+                        name: "owner".into(),
+                        kind: ParamType::Address,
+                        internal_type: None,
+                    },
+                    Param {
+                        name: "supply".into(),
+                        kind: ParamType::Uint(256),
+                        internal_type: None,
+                    },
+                ],
+            }),
+            ..Default::default()
+        };
+        let bytecode = Bytecode::from_hex_str("0x6080604052").unwrap();
+        let owner = addr!("0x9876543210987654321098765432109876543210");
+        let input = abi
+            .constructor()
+            .unwrap()
+            .encode_input(
+                bytecode.to_bytes().unwrap().0,
+                &[Token::Address(owner), Token::Uint(42.into())],
+            )
+            .unwrap();
+
+        let calldata = constructor_calldata(&abi, &bytecode, (owner, U256::from(42))).unwrap();
+        assert_eq!(calldata.0, input);
+
+        let args: (Address, U256) = decode_constructor_args(&abi, &bytecode, &input).unwrap();
+        assert_eq!(args, (owner, 42.into()));
+
+        assert!(matches!(
+            decode_constructor_args::<(Address, U256)>(&abi, &bytecode, &input[..4]),
+            Err(DeployError::Abi(_))
+        ));
+        assert!(matches!(
+            decode_constructor_args::<()>(&Abi::default(), &bytecode, &input),
+            Err(DeployError::Abi(_))
+        ));
+    }
 }
@@ -2,10 +2,14 @@
 //! detokenization of the data included in the log.
 
 mod data;
+mod hashed;
 
-pub use self::data::{Event, EventMetadata, EventStatus, ParseLog, RawLog, StreamEvent};
+pub use self::data::{
+    Event, EventMetadata, EventStatus, EventSubset, ParseLog, RawLog, StreamEvent,
+};
+pub use self::hashed::{topic_hash, Hashed};
 use crate::errors::{EventError, ExecutionError};
-use crate::log::LogFilterBuilder;
+use crate::log::{LogFilterBuilder, ProviderProfile};
 use crate::tokens::Tokenize;
 pub use ethcontract_common::abi::Topic;
 use ethcontract_common::{
@@ -19,7 +23,7 @@ use std::marker::PhantomData;
 use std::time::Duration;
 use web3::api::Web3;
 use web3::types::{Address, BlockNumber, H256};
-use web3::Transport;
+use web3::{DuplexTransport, Transport};
 
 /// A builder for creating a filtered stream of contract events that are
 #[derive(Debug)]
@@ -55,6 +59,15 @@ impl<T: Transport, E: Tokenize> EventBuilder<T, E> {
         self
     }
 
+    /// Resumes retrieving events after the specified position, typically
+    /// the block number and log index of the last event processed before a
+    /// restart. Events at or before `log_index` in `block` are filtered out.
+    #[allow(clippy::wrong_self_convention)]
+    pub fn from_position(mut self, block: u64, log_index: u64) -> Self {
+        self.filter = self.filter.from_position(block, log_index);
+        self
+    }
+
     /// Sets the last block from which to stream logs for.
     ///
     /// If left unset defaults to the streaming until the end of days.
@@ -119,9 +132,8 @@ impl<T: Transport, E: Tokenize> EventBuilder<T, E> {
             ..
         } = self;
 
-        filter.topics = event
-            .filter(topics)
-            .map_err(|err| EventError::new(&event, err))?;
+        filter.topics =
+            hashed::topic_filter(&event, topics).map_err(|err| EventError::new(&event, err))?;
 
         Ok((event, filter))
     }
@@ -155,6 +167,22 @@ impl<T: Transport, E: Tokenize> EventBuilder<T, E> {
     }
 }
 
+impl<T: DuplexTransport, E: Tokenize> EventBuilder<T, E> {
+    /// Creates an event stream from the current event builder that emits new
+    /// events pushed by the node over an `eth_subscribe` subscription, instead
+    /// of polling a log filter. See [`LogFilterBuilder::subscribe`] for more
+    /// details.
+    pub fn subscribe(self) -> impl Stream<Item = Result<StreamEvent<E>, EventError>> {
+        future::ready(self.into_inner().map(|(event, filter)| {
+            filter.subscribe().map(move |log| {
+                log.and_then(|log| Event::from_streamed_log(log, |raw| raw.decode(&event)))
+                    .map_err(|err| EventError::new(&event, err))
+            })
+        }))
+        .try_flatten_stream()
+    }
+}
+
 /// Converts a tokenizable topic into a raw topic for filtering.
 fn tokenize_topic<P>(topic: Topic<P>) -> Topic<Token>
 where
@@ -204,6 +232,15 @@ impl<T: Transport, E: ParseLog> AllEventsBuilder<T, E> {
         self
     }
 
+    /// Resumes retrieving events after the specified position, typically
+    /// the block number and log index of the last event processed before a
+    /// restart. Events at or before `log_index` in `block` are filtered out.
+    #[allow(clippy::wrong_self_convention)]
+    pub fn from_position(mut self, block: u64, log_index: u64) -> Self {
+        self.filter = self.filter.from_position(block, log_index);
+        self
+    }
+
     /// Sets the last block from which to stream logs for.
     ///
     /// If left unset defaults to the streaming until the end of days.
@@ -262,6 +299,13 @@ impl<T: Transport, E: ParseLog> AllEventsBuilder<T, E> {
         self
     }
 
+    /// The profile of the node provider, used for picking a safe block page
+    /// size for paginated queries when none is specified.
+    pub fn provider_profile(mut self, profile: ProviderProfile) -> Self {
+        self.filter = self.filter.provider_profile(profile);
+        self
+    }
+
     /// The polling interval. This is used as the interval between consecutive
     /// `eth_getLogs` calls to get log updates.
     pub fn poll_interval(mut self, value: Duration) -> Self {
@@ -325,6 +369,16 @@ impl<T: Transport, E: ParseLog> AllEventsBuilder<T, E> {
     }
 }
 
+impl<T: DuplexTransport, E: ParseLog> AllEventsBuilder<T, E> {
+    /// Creates an event stream from the current event builder that emits new
+    /// events pushed by the node over an `eth_subscribe` subscription.
+    pub fn subscribe(self) -> impl Stream<Item = Result<StreamEvent<E>, ExecutionError>> {
+        self.filter
+            .subscribe()
+            .and_then(|log| async { Event::from_streamed_log(log, E::parse_log) })
+    }
+}
+
 /// Retrieves a block number for the specified transaction hash.
 async fn block_number_from_transaction_hash<T: Transport>(
     web3: Web3<T>,
@@ -393,6 +447,76 @@ mod tests {
         (event, log)
     }
 
+    #[test]
+    fn events_from_receipt() {
+        #[derive(Debug, PartialEq)]
+        struct Transfer(Address, Address, U256);
+
+        impl ParseLog for Transfer {
+            fn parse_log(log: RawLog) -> Result<Self, ExecutionError> {
+                let (from, to, amount) = log.decode(&test_abi_event().0)?;
+                Ok(Transfer(from, to, amount))
+            }
+
+            fn is_known_topic(topic: &H256) -> bool {
+                *topic == test_abi_event().0.signature()
+            }
+        }
+
+        let (_, log) = test_abi_event();
+        let mut other = log.clone();
+        other["topics"] = json!([H256::repeat_byte(0xee)]);
+        let receipt = web3::types::TransactionReceipt {
+            logs: serde_json::from_value(json!([other, log])).unwrap(),
+            ..Default::default()
+        };
+
+        let events = Event::<Transfer>::from_receipt(&receipt).unwrap();
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            events[0].data,
+            Transfer(
+                Address::repeat_byte(0xf0),
+                Address::repeat_byte(0x70),
+                42.into()
+            )
+        );
+        assert_eq!(events[0].meta.as_ref().unwrap().log_index, 0);
+
+        // Logs of known events that fail to decode are not skipped.
+        let (event, mut invalid) = test_abi_event();
+        invalid["topics"] = json!([event.signature()]);
+        let receipt = web3::types::TransactionReceipt {
+            logs: serde_json::from_value(json!([invalid])).unwrap(),
+            ..Default::default()
+        };
+        assert!(Event::<Transfer>::from_receipt(&receipt).is_err());
+    }
+
+    #[test]
+    fn construct_event_metadata() {
+        let (_, log) = test_abi_event();
+        let event = Event::<RawLog>::from_log(&serde_json::from_value(log).unwrap()).unwrap();
+
+        assert_eq!(
+            event,
+            Event::new(
+                event.data.clone(),
+                Some(
+                    EventMetadata::new(Address::zero())
+                        .block_hash(H256::zero())
+                        .block_number(0)
+                        .transaction_hash(H256::zero())
+                        .transaction_index(0)
+                        .log_index(0)
+                        .transaction_log_index(0)
+                        .log_type("")
+                ),
+            )
+        );
+    }
+
     #[test]
     fn event_query() {
         let mut transport = TestTransport::new();
@@ -609,6 +733,41 @@ mod tests {
         transport.assert_no_more_requests();
     }
 
+    #[test]
+    fn event_subscribe_next_event() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+        let (event, log) = test_abi_event();
+
+        // subscription created
+        transport.add_response(json!("0x1"));
+        transport.add_notification(json!(log));
+
+        let address = Address::repeat_byte(0x01);
+        let signature = event.signature();
+        let event = EventBuilder::<_, (Address, Address, U256)>::new(web3, event, address)
+            .subscribe()
+            .boxed()
+            .next()
+            .wait()
+            .expect("log subscription did not produce any logs")
+            .expect("failed to get log from log subscription");
+
+        assert!(event.is_added());
+        assert_eq!(event.inner_data().2, U256::from(42));
+        transport.assert_request(
+            "eth_subscribe",
+            &[
+                json!("logs"),
+                json!({
+                    "address": address,
+                    "topics": [signature],
+                }),
+            ],
+        );
+        transport.assert_no_more_requests();
+    }
+
     #[test]
     fn all_events_stream_next_event() {
         let mut transport = TestTransport::new();
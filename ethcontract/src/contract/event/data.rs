@@ -1,7 +1,8 @@
 //! Module contains code for parsing and manipulating event data.
 use crate::{errors::ExecutionError, tokens::Tokenize};
-use ethcontract_common::abi::{Event as AbiEvent, RawLog as AbiRawLog, Token};
-use web3::types::{Log, H160, H256};
+use ethcontract_common::abi::{Event as AbiEvent, RawLog as AbiRawLog, Token, Topic};
+use serde::{Deserialize, Serialize};
+use web3::types::{Log, TransactionReceipt, H160, H256};
 
 /// A contract event
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -32,6 +33,12 @@ pub enum EventStatus<T> {
 }
 
 impl<T> Event<T> {
+    /// Creates an event from its data and metadata, for example for testing
+    /// event handling code without a node.
+    pub fn new(data: T, meta: Option<EventMetadata>) -> Self {
+        Event { data, meta }
+    }
+
     /// Creates an event from a log given a mapping function.
     pub(crate) fn from_past_log<E, F>(log: Log, f: F) -> Result<Self, ExecutionError>
     where
@@ -50,6 +57,41 @@ impl<T> Event<T> {
     }
 }
 
+impl<T: ParseLog> Event<T> {
+    /// Decodes an event from a log.
+    pub fn from_log(log: &Log) -> Result<Self, ExecutionError> {
+        Self::from_past_log(log.clone(), T::parse_log)
+    }
+
+    /// Decodes the events emitted by a transaction from its receipt, without
+    /// querying the node for its logs.
+    ///
+    /// See [`Event::from_logs`] for how logs of other events are handled.
+    pub fn from_receipt(receipt: &TransactionReceipt) -> Result<Vec<Self>, ExecutionError> {
+        Self::from_logs(&receipt.logs)
+    }
+
+    /// Decodes events from logs.
+    ///
+    /// Logs that can't be parsed and whose first topic is not known to the
+    /// event type, for example because they were emitted by other contracts,
+    /// are skipped. Logs of known events that fail to parse are errors.
+    pub fn from_logs<'a, I>(logs: I) -> Result<Vec<Self>, ExecutionError>
+    where
+        I: IntoIterator<Item = &'a Log>,
+    {
+        logs.into_iter()
+            .filter_map(|log| match Self::from_log(log) {
+                Ok(event) => Some(Ok(event)),
+                Err(err) => match log.topics.first() {
+                    Some(topic) if T::is_known_topic(topic) => Some(Err(err)),
+                    _ => None,
+                },
+            })
+            .collect()
+    }
+}
+
 impl<T> Event<EventStatus<T>> {
     /// Creates an event from a log given a mapping function.
     pub(crate) fn from_streamed_log<E, F>(log: Log, f: F) -> Result<Self, ExecutionError>
@@ -146,6 +188,59 @@ pub struct EventMetadata {
 }
 
 impl EventMetadata {
+    /// Creates metadata for an event emitted by the specified contract, with
+    /// all other fields set to their defaults. The remaining fields can be set
+    /// with the builder methods, for example for testing event handling code
+    /// without a node.
+    pub fn new(address: H160) -> Self {
+        EventMetadata {
+            address,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the hash of the block where the log was produced.
+    pub fn block_hash(mut self, value: H256) -> Self {
+        self.block_hash = value;
+        self
+    }
+
+    /// Sets the number of the block where the log was produced.
+    pub fn block_number(mut self, value: u64) -> Self {
+        self.block_number = value;
+        self
+    }
+
+    /// Sets the hash of the transaction the log belongs to.
+    pub fn transaction_hash(mut self, value: H256) -> Self {
+        self.transaction_hash = value;
+        self
+    }
+
+    /// Sets the block index of the transaction the log belongs to.
+    pub fn transaction_index(mut self, value: usize) -> Self {
+        self.transaction_index = value;
+        self
+    }
+
+    /// Sets the index of the log in the block.
+    pub fn log_index(mut self, value: usize) -> Self {
+        self.log_index = value;
+        self
+    }
+
+    /// Sets the index of the log in the transaction.
+    pub fn transaction_log_index(mut self, value: usize) -> Self {
+        self.transaction_log_index = Some(value);
+        self
+    }
+
+    /// Sets the log type.
+    pub fn log_type(mut self, value: impl Into<String>) -> Self {
+        self.log_type = Some(value.into());
+        self
+    }
+
     fn from_log(log: &Log) -> Option<Self> {
         Some(EventMetadata {
             address: log.address,
@@ -161,7 +256,7 @@ impl EventMetadata {
 }
 
 /// Raw log topics and data for a contract event.
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct RawLog {
     /// The raw 32-byte topics.
     pub topics: Vec<H256>,
@@ -191,6 +286,15 @@ impl RawLog {
     }
 }
 
+impl From<RawLog> for AbiRawLog {
+    fn from(log: RawLog) -> Self {
+        AbiRawLog {
+            topics: log.topics,
+            data: log.data,
+        }
+    }
+}
+
 impl From<Log> for RawLog {
     fn from(log: Log) -> Self {
         RawLog {
@@ -205,6 +309,23 @@ impl From<Log> for RawLog {
 pub trait ParseLog: Sized + Send + Sync {
     /// Create a new instance by parsing raw log data.
     fn parse_log(log: RawLog) -> Result<Self, ExecutionError>;
+
+    /// Returns whether a log with the specified first topic is of an event
+    /// known to this type, in which case failing to parse it is an error
+    /// instead of a sign that the log is of another event. Defaults to
+    /// `false`, so that logs that fail to parse are considered unknown.
+    fn is_known_topic(_topic: &H256) -> bool {
+        false
+    }
+}
+
+/// Trait for a subset of a contract's events, allowing logs to be filtered to
+/// only the selected events before they are parsed.
+pub trait EventSubset: ParseLog {
+    /// Returns the filter for the first log topic that matches the selected
+    /// events. This is `Topic::Any` if the subset contains anonymous events,
+    /// since they can't be filtered by topic.
+    fn topic0() -> Topic<H256>;
 }
 
 impl ParseLog for RawLog {
@@ -0,0 +1,338 @@
+//! Module implements support for indexed event parameters of dynamic types,
+//! whose values are replaced by their hash in log topics.
+
+use crate::tokens::{Error as TokenError, Tokenize};
+use ethcontract_common::abi::{
+    self, Error as AbiError, Event as AbiEvent, ParamType, RawTopicFilter, Token, Topic,
+    TopicFilter,
+};
+use ethcontract_common::hash;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use web3::types::H256;
+
+/// The value of an indexed event parameter of a dynamic type, such as a
+/// `string`, `bytes`, array or struct.
+///
+/// Logs only contain the Keccak-256 hash of these values in their topics, so
+/// the original value can't be recovered. Instead, the hash can be compared
+/// against candidate values with [`Hashed::matches`].
+pub struct Hashed<T> {
+    hash: H256,
+    _value: PhantomData<fn() -> T>,
+}
+
+impl<T> Hashed<T> {
+    /// Creates a hashed value from the hash included in the log topic.
+    pub fn from_hash(hash: H256) -> Self {
+        Hashed {
+            hash,
+            _value: PhantomData,
+        }
+    }
+
+    /// Returns the topic hash of the value.
+    pub fn hash(&self) -> H256 {
+        self.hash
+    }
+}
+
+impl<T: Tokenize> Hashed<T> {
+    /// Computes the hashed value for the specified value, for example for use
+    /// as a topic filter.
+    pub fn of(value: T) -> Self {
+        Self::from_hash(topic_hash(&value.into_token()))
+    }
+
+    /// Returns `true` if the hash was computed from the specified value.
+    pub fn matches(&self, value: T) -> bool {
+        self.hash == topic_hash(&value.into_token())
+    }
+}
+
+impl<T> Clone for Hashed<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Hashed<T> {}
+
+impl<T> Debug for Hashed<T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_tuple("Hashed").field(&self.hash).finish()
+    }
+}
+
+impl<T> Default for Hashed<T> {
+    fn default() -> Self {
+        Self::from_hash(H256::default())
+    }
+}
+
+impl<T> PartialEq for Hashed<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.hash == other.hash
+    }
+}
+
+impl<T> Eq for Hashed<T> {}
+
+impl<T> Hash for Hashed<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.hash.hash(state)
+    }
+}
+
+impl<T> Serialize for Hashed<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.hash.serialize(serializer)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Hashed<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        H256::deserialize(deserializer).map(Self::from_hash)
+    }
+}
+
+impl<T> Tokenize for Hashed<T> {
+    fn from_token(token: Token) -> Result<Self, TokenError> {
+        H256::from_token(token).map(Self::from_hash)
+    }
+
+    fn into_token(self) -> Token {
+        self.hash.into_token()
+    }
+}
+
+/// Computes the topic of an indexed event parameter value.
+///
+/// Values of dynamic types are hashed as specified by the Solidity ABI: the
+/// hash of the raw bytes for `string` and `bytes`, and the hash of the
+/// concatenated, padded element encodings for arrays and structs.
+pub fn topic_hash(token: &Token) -> H256 {
+    match token {
+        Token::Bytes(bytes) => H256(hash::keccak256(bytes)),
+        Token::String(string) => H256(hash::keccak256(string)),
+        Token::Array(_) | Token::FixedArray(_) | Token::Tuple(_) => {
+            let mut preimage = Vec::new();
+            encode_in_place(token, &mut preimage);
+            H256(hash::keccak256(preimage))
+        }
+        _ => H256::from_slice(&abi::encode(std::slice::from_ref(token))),
+    }
+}
+
+/// Encodes a token as part of the preimage of an indexed parameter topic.
+fn encode_in_place(token: &Token, preimage: &mut Vec<u8>) {
+    match token {
+        Token::Bytes(bytes) => extend_padded(preimage, bytes),
+        Token::String(string) => extend_padded(preimage, string.as_bytes()),
+        Token::Array(tokens) | Token::FixedArray(tokens) | Token::Tuple(tokens) => {
+            for token in tokens {
+                encode_in_place(token, preimage);
+            }
+        }
+        _ => preimage.extend(abi::encode(std::slice::from_ref(token))),
+    }
+}
+
+/// Appends bytes right-padded with zeros to a multiple of 32 bytes.
+fn extend_padded(preimage: &mut Vec<u8>, bytes: &[u8]) {
+    preimage.extend_from_slice(bytes);
+    let padding = (32 - bytes.len() % 32) % 32;
+    preimage.resize(preimage.len() + padding, 0);
+}
+
+/// Converts a raw topic filter with tokens into a topic filter for the
+/// specified event.
+///
+/// Unlike `ethabi::Event::filter`, this correctly hashes values of indexed
+/// parameters with dynamic types, and accepts topic hashes for them directly.
+pub(crate) fn topic_filter(event: &AbiEvent, raw: RawTopicFilter) -> Result<TopicFilter, AbiError> {
+    let kinds = event
+        .inputs
+        .iter()
+        .filter(|input| input.indexed)
+        .map(|input| &input.kind)
+        .collect::<Vec<_>>();
+
+    let convert = |topic: Topic<Token>, index: usize| -> Result<Topic<H256>, AbiError> {
+        let kind = || kinds.get(index).copied().ok_or(AbiError::InvalidData);
+        match topic {
+            Topic::Any => Ok(Topic::Any),
+            Topic::OneOf(tokens) => {
+                let kind = kind()?;
+                tokens
+                    .into_iter()
+                    .map(|token| convert_token(token, kind))
+                    .collect::<Result<_, _>>()
+                    .map(Topic::OneOf)
+            }
+            Topic::This(token) => convert_token(token, kind()?).map(Topic::This),
+        }
+    };
+
+    Ok(if event.anonymous {
+        TopicFilter {
+            topic0: convert(raw.topic0, 0)?,
+            topic1: convert(raw.topic1, 1)?,
+            topic2: convert(raw.topic2, 2)?,
+            topic3: Topic::Any,
+        }
+    } else {
+        TopicFilter {
+            topic0: Topic::This(event.signature()),
+            topic1: convert(raw.topic0, 0)?,
+            topic2: convert(raw.topic1, 1)?,
+            topic3: convert(raw.topic2, 2)?,
+        }
+    })
+}
+
+/// Converts a single token into a topic for an indexed parameter.
+fn convert_token(token: Token, kind: &ParamType) -> Result<H256, AbiError> {
+    let is_dynamic = matches!(
+        kind,
+        ParamType::Array(_)
+            | ParamType::Bytes
+            | ParamType::FixedArray(..)
+            | ParamType::String
+            | ParamType::Tuple(_)
+    );
+    match token {
+        Token::FixedBytes(bytes) if is_dynamic && bytes.len() == 32 => Ok(H256::from_slice(&bytes)),
+        token if token.type_check(kind) => Ok(topic_hash(&token)),
+        _ => Err(AbiError::InvalidData),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethcontract_common::abi::EventParam;
+
+    #[test]
+    fn topic_hash_of_dynamic_values() {
+        assert_eq!(
+            topic_hash(&Token::String("hello".into())),
+            H256(hash::keccak256("hello"))
+        );
+        assert_eq!(
+            topic_hash(&Token::Bytes(vec![1, 2, 3])),
+            H256(hash::keccak256([1, 2, 3]))
+        );
+
+        let mut preimage = [0u8; 96];
+        preimage[31] = 42;
+        preimage[32..37].copy_from_slice(b"hello");
+        preimage[64..69].copy_from_slice(b"world");
+        assert_eq!(
+            topic_hash(&Token::Tuple(vec![
+                Token::Uint(42.into()),
+                Token::Array(vec![
+                    Token::String("hello".into()),
+                    Token::String("world".into()),
+                ]),
+            ])),
+            H256(hash::keccak256(preimage))
+        );
+    }
+
+    #[test]
+    fn topic_hash_of_static_values() {
+        assert_eq!(
+            topic_hash(&Token::Uint(42.into())),
+            H256::from_low_u64_be(42)
+        );
+    }
+
+    #[test]
+    fn hashed_value_matches_preimage() {
+        let hashed =
+            Hashed::<String>::from_token(Token::FixedBytes(hash::keccak256("hello").to_vec()))
+                .unwrap();
+
+        assert_eq!(hashed, Hashed::of("hello".to_owned()));
+        assert!(hashed.matches("hello".to_owned()));
+        assert!(!hashed.matches("world".to_owned()));
+    }
+
+    #[test]
+    fn topic_filter_for_dynamic_parameters() {
+        let event = AbiEvent {
+            name: "Named".into(),
+            inputs: vec![
+                EventParam {
+                    name: "name".into(),
+                    kind: ParamType::String,
+                    indexed: true,
+                },
+                EventParam {
+                    name: "value".into(),
+                    kind: ParamType::Uint(256),
+                    indexed: true,
+                },
+            ],
+            anonymous: false,
+        };
+        let hash = H256(hash::keccak256("hello"));
+
+        let filter = topic_filter(
+            &event,
+            RawTopicFilter {
+                topic0: Topic::OneOf(vec![
+                    Token::String("hello".into()),
+                    Hashed::<String>::from_hash(hash).into_token(),
+                ]),
+                topic1: Topic::This(Token::Uint(42.into())),
+                topic2: Topic::Any,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(filter.topic0, Topic::This(event.signature()));
+        assert_eq!(filter.topic1, Topic::OneOf(vec![hash, hash]));
+        assert_eq!(filter.topic2, Topic::This(H256::from_low_u64_be(42)));
+        assert_eq!(filter.topic3, Topic::Any);
+    }
+
+    #[test]
+    fn topic_filter_rejects_mismatched_tokens() {
+        let event = AbiEvent {
+            name: "Named".into(),
+            inputs: vec![EventParam {
+                name: "value".into(),
+                kind: ParamType::Uint(256),
+                indexed: true,
+            }],
+            anonymous: false,
+        };
+
+        assert!(topic_filter(
+            &event,
+            RawTopicFilter {
+                topic0: Topic::This(Token::Bool(true)),
+                ..Default::default()
+            },
+        )
+        .is_err());
+        assert!(topic_filter(
+            &event,
+            RawTopicFilter {
+                topic1: Topic::This(Token::Uint(42.into())),
+                ..Default::default()
+            },
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,258 @@
+//! Deployment of contracts together with the libraries they link against.
+
+use crate::contract::{DeployBuilder, Instance, Linker};
+use crate::errors::DeployError;
+use crate::tokens::Tokenize;
+use crate::transaction::Account;
+use ethcontract_common::errors::LinkError;
+use ethcontract_common::{Bytecode, Contract};
+use std::collections::{HashMap, HashSet};
+use web3::api::Web3;
+use web3::types::Address;
+use web3::Transport;
+
+/// Deploys a contract along with the libraries it links against.
+///
+/// Libraries are deployed in dependency order, so that libraries that link
+/// against other libraries are only deployed once their dependencies are, and
+/// are then linked into the contract bytecode. Only the libraries required by
+/// the contract are deployed, and libraries that were already deployed can be
+/// specified by address to be linked as is.
+#[derive(Debug, Clone)]
+#[must_use = "linked deployers do nothing unless you `.deploy()` them"]
+pub struct LinkedDeployer<T: Transport> {
+    web3: Web3<T>,
+    contract: Contract,
+    libraries: HashMap<String, Contract>,
+    addresses: HashMap<String, Address>,
+    from: Option<Account>,
+}
+
+/// The result of a [`LinkedDeployer`] deployment.
+#[derive(Debug, Clone)]
+pub struct LinkedDeployment<T: Transport> {
+    /// The deployed contract instance.
+    pub instance: Instance<T>,
+    /// The addresses of all libraries linked into the contract, including
+    /// its transitive dependencies, by library name.
+    pub libraries: HashMap<String, Address>,
+}
+
+impl<T: Transport> LinkedDeployer<T> {
+    /// Creates a new deployer for a contract.
+    pub fn new(web3: Web3<T>, contract: Contract) -> Self {
+        LinkedDeployer {
+            web3,
+            contract,
+            libraries: HashMap::new(),
+            addresses: HashMap::new(),
+            from: None,
+        }
+    }
+
+    /// Adds a library contract to deploy if the contract or one of its other
+    /// libraries links against it. The library is identified by the name of
+    /// the contract.
+    pub fn library(mut self, library: Contract) -> Self {
+        self.libraries.insert(library.name.clone(), library);
+        self
+    }
+
+    /// Specifies the address of an already deployed library, which is linked
+    /// instead of deploying a new instance of the library.
+    pub fn library_at(mut self, name: impl Into<String>, address: Address) -> Self {
+        self.addresses.insert(name.into(), address);
+        self
+    }
+
+    /// Specifies the account used to deploy the libraries and the contract.
+    pub fn from(mut self, value: Account) -> Self {
+        self.from = Some(value);
+        self
+    }
+
+    /// Returns the names of the libraries that need to be deployed, in the
+    /// order in which they are deployed.
+    ///
+    /// Returns an error if a required library was neither added nor has an
+    /// address, or if libraries depend on each other cyclically.
+    pub fn deployment_order(&self) -> Result<Vec<String>, LinkError> {
+        let mut order = Vec::new();
+        let mut visiting = HashSet::new();
+        for name in self.contract.bytecode.undefined_libraries() {
+            self.visit(name, &mut visiting, &mut order)?;
+        }
+        Ok(order)
+    }
+
+    /// Visits a library in depth first order, adding it to the deployment
+    /// order after its dependencies.
+    fn visit(
+        &self,
+        name: &str,
+        visiting: &mut HashSet<String>,
+        order: &mut Vec<String>,
+    ) -> Result<(), LinkError> {
+        if self.addresses.contains_key(name) || order.iter().any(|library| library == name) {
+            return Ok(());
+        }
+        let library = match self.libraries.get(name) {
+            Some(library) if visiting.insert(name.to_owned()) => library,
+            _ => return Err(LinkError::UndefinedLibrary(name.to_owned())),
+        };
+        for dependency in library.bytecode.undefined_libraries() {
+            self.visit(dependency, visiting, order)?;
+        }
+        visiting.remove(name);
+        order.push(name.to_owned());
+        Ok(())
+    }
+
+    /// Deploys the required libraries and then the contract with the
+    /// specified constructor parameters.
+    pub async fn deploy<P>(self, params: P) -> Result<LinkedDeployment<T>, DeployError>
+    where
+        P: Tokenize,
+    {
+        let order = self.deployment_order()?;
+        let LinkedDeployer {
+            web3,
+            contract,
+            mut libraries,
+            mut addresses,
+            from,
+        } = self;
+
+        for name in order {
+            let library = libraries
+                .remove(&name)
+                .expect("library in deployment order");
+            let mut builder = DeployBuilder::with_libraries(web3.clone(), library, &addresses, ())?;
+            if let Some(from) = &from {
+                builder = builder.from(from.clone());
+            }
+            let instance = builder.deploy().await?;
+            addresses.insert(name, instance.address());
+        }
+
+        let mut builder = DeployBuilder::with_libraries(web3, contract, &addresses, params)?;
+        if let Some(from) = from {
+            builder = builder.from(from);
+        }
+        let instance = builder.deploy().await?;
+
+        Ok(LinkedDeployment {
+            instance,
+            libraries: addresses,
+        })
+    }
+}
+
+impl<T: Transport> DeployBuilder<T, Instance<T>> {
+    /// Creates a new deploy builder for a contract, linking the libraries with
+    /// the specified addresses into its bytecode. Libraries that the contract
+    /// does not link against are ignored.
+    pub fn with_libraries<P>(
+        web3: Web3<T>,
+        contract: Contract,
+        libraries: &HashMap<String, Address>,
+        params: P,
+    ) -> Result<Self, DeployError>
+    where
+        P: Tokenize,
+    {
+        let names = undefined_libraries(&contract.bytecode);
+        let mut linker = Linker::new(contract);
+        for name in names {
+            if let Some(address) = libraries.get(&name) {
+                linker = linker.library(name, *address)?;
+            }
+        }
+        linker.deploy(web3, params)
+    }
+}
+
+/// Returns the owned names of the undefined libraries of a bytecode.
+fn undefined_libraries(bytecode: &Bytecode) -> Vec<String> {
+    bytecode.undefined_libraries().map(str::to_owned).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::prelude::*;
+
+    fn contract(name: &str, libraries: &[&str]) -> Contract {
+        let code = libraries
+            .iter()
+            .map(|library| format!("73__{:_<38}", library))
+            .collect::<String>();
+        Contract {
+            name: name.to_owned(),
+            bytecode: Bytecode::from_hex_str(&format!("6080{}00", code)).unwrap(),
+            ..Contract::empty()
+        }
+    }
+
+    fn deployer(libraries: &[Contract]) -> LinkedDeployer<TestTransport> {
+        let web3 = Web3::new(TestTransport::new());
+        libraries.iter().fold(
+            LinkedDeployer::new(web3, contract("Main", &["A", "B"])),
+            |deployer, library| deployer.library(library.clone()),
+        )
+    }
+
+    #[test]
+    fn deploys_dependencies_first() {
+        let deployer = deployer(&[
+            contract("A", &["C"]),
+            contract("B", &["A", "C"]),
+            contract("C", &[]),
+            contract("Unused", &[]),
+        ]);
+
+        assert_eq!(deployer.deployment_order().unwrap(), ["C", "A", "B"]);
+    }
+
+    #[test]
+    fn skips_deployed_libraries() {
+        let deployer = deployer(&[contract("A", &["C"]), contract("C", &[])])
+            .library_at("B", Address::repeat_byte(0x0b))
+            .library_at("C", Address::repeat_byte(0x0c));
+
+        assert_eq!(deployer.deployment_order().unwrap(), ["A"]);
+    }
+
+    #[test]
+    fn missing_and_cyclic_libraries() {
+        let missing = deployer(&[contract("A", &[])]).deployment_order();
+        assert!(matches!(missing, Err(LinkError::UndefinedLibrary(name)) if name == "B"));
+
+        let cyclic = deployer(&[contract("A", &["B"]), contract("B", &["A"])]).deployment_order();
+        assert!(matches!(cyclic, Err(LinkError::UndefinedLibrary(_))));
+    }
+
+    #[test]
+    fn links_libraries() {
+        let web3 = Web3::new(TestTransport::new());
+        let libraries = [
+            ("A".to_owned(), Address::repeat_byte(0x0a)),
+            ("B".to_owned(), Address::repeat_byte(0x0b)),
+            ("Unused".to_owned(), Address::repeat_byte(0xff)),
+        ]
+        .into_iter()
+        .collect();
+
+        let tx = DeployBuilder::with_libraries(web3, contract("Main", &["A", "B"]), &libraries, ())
+            .unwrap()
+            .into_inner();
+
+        let mut code = vec![0x60, 0x80];
+        for byte in [0x0a, 0x0b] {
+            code.push(0x73);
+            code.extend([byte; 20]);
+        }
+        code.push(0x00);
+        assert_eq!(tx.data.unwrap().0, code);
+    }
+}
@@ -2,11 +2,25 @@
 //! intended to be used directly but to be used by a contract `Instance` with
 //! [Instance::method](ethcontract::contract::Instance::method).
 
-use crate::transaction::{Account, GasPrice, TransactionBuilder, TransactionResult};
-use crate::{batch::CallBatch, errors::MethodError, tokens::Tokenize};
+use crate::batch::{CallBatch, Multicall};
+use crate::contract::event::{Event, ParseLog};
+use crate::contract::state_override::{call_with_state_overrides, StateOverride, StateOverrides};
+use crate::contract::summary::{self, MethodSummary};
+use crate::errors::{ExecutionError, MethodError};
+use crate::tokens::Tokenize;
+use crate::transaction::confirm::ReplacementPolicy;
+use crate::transaction::{
+    Account, CallTrace, GasPrice, GasPriceStrategy, IntoAccessList, NonceManager, ResolveCondition,
+    Tracer, TransactionBuilder, TransactionResult,
+};
 use ethcontract_common::abi::{Function, Token};
+use ethcontract_common::abiext::FunctionExt as _;
+use ethcontract_common::contract::Interface;
+use futures::future;
+use std::future::Future;
 use std::marker::PhantomData;
-use web3::types::{AccessList, Address, BlockId, Bytes, CallRequest, U256};
+use std::sync::Arc;
+use web3::types::{Address, BlockId, BlockNumber, Bytes, CallRequest, TransactionReceipt, U256};
 use web3::Transport;
 use web3::{api::Web3, BatchTransport};
 
@@ -19,6 +33,8 @@ pub struct MethodDefaults {
     pub gas: Option<U256>,
     /// Default gas price to use for transaction.
     pub gas_price: Option<GasPrice>,
+    /// Default maximum size of the transaction calldata in bytes.
+    pub max_calldata_size: Option<usize>,
 }
 
 /// Data used for building a contract method call or transaction. The method
@@ -29,6 +45,7 @@ pub struct MethodDefaults {
 pub struct MethodBuilder<T: Transport, R: Tokenize> {
     web3: Web3<T>,
     function: Function,
+    interface: Option<Arc<Interface>>,
     /// transaction parameters
     pub tx: TransactionBuilder<T>,
     _result: PhantomData<R>,
@@ -58,6 +75,7 @@ impl<T: Transport, R: Tokenize> MethodBuilder<T, R> {
         MethodBuilder {
             web3: web3.clone(),
             function,
+            interface: None,
             tx: TransactionBuilder::new(web3).to(address).data(data),
             _result: PhantomData,
         }
@@ -68,6 +86,14 @@ impl<T: Transport, R: Tokenize> MethodBuilder<T, R> {
         self.tx.from = self.tx.from.or_else(|| defaults.from.clone());
         self.tx.gas = self.tx.gas.or(defaults.gas);
         self.tx.gas_price = self.tx.gas_price.or(defaults.gas_price);
+        self.tx.max_calldata_size = self.tx.max_calldata_size.or(defaults.max_calldata_size);
+        self
+    }
+
+    /// Specify the contract interface used for decoding custom errors when
+    /// the method call reverts.
+    pub fn interface(mut self, value: Arc<Interface>) -> Self {
+        self.interface = Some(value);
         self
     }
 
@@ -97,6 +123,24 @@ impl<T: Transport, R: Tokenize> MethodBuilder<T, R> {
         self
     }
 
+    /// Specify a strategy for determining the gas price at the moment the
+    /// transaction is sent, replacing any previously specified gas price.
+    pub fn gas_price_strategy(mut self, value: GasPriceStrategy<T>) -> Self {
+        self.tx = self.tx.gas_price_strategy(value);
+        self
+    }
+
+    /// Specify an async closure for determining the gas price at the moment
+    /// the transaction is sent, replacing any previously specified gas price.
+    pub fn gas_price_with<F, Fut>(mut self, strategy: F) -> Self
+    where
+        F: Fn(Web3<T>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<GasPrice, ExecutionError>> + Send + 'static,
+    {
+        self.tx = self.tx.gas_price_with(strategy);
+        self
+    }
+
     /// Specify what how much ETH to transfer with the transaction, if not
     /// specified then no ETH will be sent.
     pub fn value(mut self, value: U256) -> Self {
@@ -111,6 +155,13 @@ impl<T: Transport, R: Tokenize> MethodBuilder<T, R> {
         self
     }
 
+    /// Specify a nonce manager for assigning a nonce to the transaction when
+    /// it is sent, if no nonce was specified.
+    pub fn nonce_manager(mut self, value: Arc<NonceManager>) -> Self {
+        self.tx = self.tx.nonce_manager(value);
+        self
+    }
+
     /// Specify the number of confirmations to wait for when confirming the
     /// transaction, if not specified will wait for the transaction to be mined
     /// without any extra confirmations.
@@ -119,24 +170,186 @@ impl<T: Transport, R: Tokenize> MethodBuilder<T, R> {
         self
     }
 
+    /// Specify the policy for replacing the transaction with one with a higher
+    /// gas price if it does not get mined in time.
+    pub fn replacement(mut self, value: ReplacementPolicy) -> Self {
+        self.tx = self.tx.replacement(value);
+        self
+    }
+
+    /// Specify the maximum size of the transaction calldata in bytes, if not
+    /// specified then calldata of any size will be sent to the node.
+    pub fn max_calldata_size(mut self, value: usize) -> Self {
+        self.tx = self.tx.max_calldata_size(value);
+        self
+    }
+
     /// Specify the access list for the transaction, if not specified no access list will be used.
-    pub fn access_list(mut self, value: AccessList) -> Self {
+    pub fn access_list(mut self, value: impl IntoAccessList) -> Self {
         self.tx = self.tx.access_list(value);
         self
     }
 
+    /// Specify whether to create an access list with `eth_createAccessList`
+    /// when sending the transaction, if no access list was specified.
+    pub fn populate_access_list(mut self, value: bool) -> Self {
+        self.tx = self.tx.populate_access_list(value);
+        self
+    }
+
     /// Extract inner `TransactionBuilder` from this `SendBuilder`. This exposes
     /// `TransactionBuilder` only APIs.
     pub fn into_inner(self) -> TransactionBuilder<T> {
         self.tx
     }
 
+    /// Extracts the target address, value and calldata of the method call
+    /// transaction, for example for batching multiple method calls into a
+    /// single transaction with [`multisend`](crate::batch::multisend).
+    pub fn into_inner_tx(self) -> (Address, U256, Bytes) {
+        (
+            self.tx.to.unwrap_or_default(),
+            self.tx.value.unwrap_or_default(),
+            self.tx.data.unwrap_or_default(),
+        )
+    }
+
     /// Sign (if required) and send the method call transaction.
     pub async fn send(self) -> Result<TransactionResult, MethodError> {
-        let Self { function, tx, .. } = self;
+        let Self {
+            function,
+            interface,
+            tx,
+            ..
+        } = self;
         tx.send()
             .await
-            .map_err(|err| MethodError::new(&function, err))
+            .map_err(|err| method_error(&function, interface.as_deref(), err))
+    }
+
+    /// Sign (if required) and send the method call transaction, wait for it to
+    /// be confirmed, and decode the events emitted by the contract from the
+    /// transaction receipt.
+    ///
+    /// The transaction is always waited for, even if it was configured to
+    /// resolve once it is pending. Logs emitted by other contracts are
+    /// skipped, and other logs are decoded with [`Event::from_logs`].
+    pub async fn send_and_decode_events<E: ParseLog>(
+        mut self,
+    ) -> Result<(TransactionReceipt, Vec<Event<E>>), MethodError> {
+        if let Some(ResolveCondition::Pending) = self.tx.resolve {
+            self.tx.resolve = Some(ResolveCondition::default());
+        }
+        let address = self.tx.to.unwrap_or_default();
+        let signature = self.function.signature();
+
+        let receipt = match self.send().await? {
+            TransactionResult::Receipt(receipt) => receipt,
+            TransactionResult::Hash(_) => unreachable!("confirmed transactions have receipts"),
+        };
+        let events = Event::from_logs(receipt.logs.iter().filter(|log| log.address == address))
+            .map_err(|err| MethodError::from_parts(signature, err))?;
+
+        Ok((receipt, events))
+    }
+
+    /// Sign (if required) and send the method call transaction only if the
+    /// result of a view method call satisfies a predicate, returning `None`
+    /// without sending anything otherwise.
+    ///
+    /// This allows skipping transactions that are known to be wasted, for
+    /// example settling an order that was already settled by someone else.
+    /// The condition is checked against the pending block, unless a block was
+    /// set on the view method, right before the transaction is sent. Note
+    /// that this is not atomic, the state can still change before the
+    /// transaction gets mined.
+    ///
+    /// The condition is executed with `call`, so any state overrides set on
+    /// it are applied when checking the condition.
+    pub async fn send_if<C, F>(
+        self,
+        condition: ViewMethodBuilder<T, C>,
+        predicate: F,
+    ) -> Result<Option<TransactionResult>, MethodError>
+    where
+        C: Tokenize,
+        F: FnOnce(&C) -> bool,
+    {
+        let condition = match condition.block {
+            Some(_) => condition,
+            None => condition.block(BlockNumber::Pending.into()),
+        };
+        if !predicate(&condition.call().await?) {
+            return Ok(None);
+        }
+        self.send().await.map(Some)
+    }
+
+    /// Returns a human-readable summary of the method call transaction, with
+    /// its decoded parameters and fee settings, that can be serialized into
+    /// audit logs or approval systems before the transaction is sent.
+    ///
+    /// The chain ID is taken from the signing account if it specifies one,
+    /// and queried from the node otherwise.
+    pub async fn summary(&self) -> Result<MethodSummary, MethodError> {
+        let error = |err: ExecutionError| method_error(&self.function, None, err);
+
+        let data = self.tx.data.as_ref().map(|data| &data.0[..]);
+        let params = summary::decode_params(&self.function, data.unwrap_or_default())
+            .map_err(|err| error(err.into()))?;
+        let chain_id = match &self.tx.from {
+            Some(Account::Offline(_, Some(chain_id)))
+            | Some(Account::Threshold(_, Some(chain_id))) => *chain_id,
+            #[cfg(feature = "aws-kms")]
+            Some(Account::Kms(_, Some(chain_id))) => *chain_id,
+            _ => self
+                .web3
+                .eth()
+                .chain_id()
+                .await
+                .map_err(|err| error(err.into()))?
+                .as_u64(),
+        };
+        let fees = self
+            .tx
+            .gas_price
+            .map(|gas_price| gas_price.resolve_for_transaction())
+            .unwrap_or_default();
+
+        Ok(MethodSummary {
+            method: self.function.name.clone(),
+            signature: self.function.abi_signature(),
+            params,
+            from: self.tx.from.as_ref().map(Account::address),
+            to: self.tx.to,
+            value: self.tx.value.unwrap_or_default(),
+            gas: self.tx.gas,
+            gas_price: fees.gas_price,
+            max_fee_per_gas: fees.max_fee_per_gas,
+            max_priority_fee_per_gas: fees.max_priority_fee_per_gas,
+            nonce: self.tx.nonce,
+            chain_id,
+        })
+    }
+
+    /// Simulates the method call transaction against the latest block without
+    /// sending it, and returns a trace of the calls it made. See
+    /// [`TransactionBuilder::simulate`] for more details.
+    pub async fn simulate(self) -> Result<CallTrace, MethodError> {
+        self.simulate_with_tracer(Tracer::default()).await
+    }
+
+    /// Simulates the method call transaction with the specified tracer.
+    pub async fn simulate_with_tracer(self, tracer: Tracer) -> Result<CallTrace, MethodError> {
+        let Self {
+            function,
+            interface,
+            tx,
+            ..
+        } = self;
+        tx.simulate_with_tracer(tracer)
+            .await
+            .map_err(|err| method_error(&function, interface.as_deref(), err))
     }
 
     /// Demotes a `MethodBuilder` into a `ViewMethodBuilder` which has a more
@@ -154,6 +367,12 @@ impl<T: Transport, R: Tokenize> MethodBuilder<T, R> {
     }
 }
 
+impl<T: Transport, R: Tokenize> From<MethodBuilder<T, R>> for TransactionBuilder<T> {
+    fn from(method: MethodBuilder<T, R>) -> Self {
+        method.into_inner()
+    }
+}
+
 /// Data used for building a contract method call. The view method builder can't
 /// directly send transactions and is for read only method calls.
 #[derive(Debug, Clone)]
@@ -163,6 +382,8 @@ pub struct ViewMethodBuilder<T: Transport, R: Tokenize> {
     pub m: MethodBuilder<T, R>,
     /// optional block number
     pub block: Option<BlockId>,
+    /// optional state overrides
+    pub state_overrides: Option<StateOverrides>,
 }
 
 impl<T: Transport, R: Tokenize> ViewMethodBuilder<T, R> {
@@ -171,6 +392,7 @@ impl<T: Transport, R: Tokenize> ViewMethodBuilder<T, R> {
         ViewMethodBuilder {
             m: method,
             block: None,
+            state_overrides: None,
         }
     }
 
@@ -220,7 +442,7 @@ impl<T: Transport, R: Tokenize> ViewMethodBuilder<T, R> {
     }
 
     /// Specify the access list for the transaction, if not specified no access list will be used.
-    pub fn access_list(mut self, value: AccessList) -> Self {
+    pub fn access_list(mut self, value: impl IntoAccessList) -> Self {
         self.m = self.m.access_list(value);
         self
     }
@@ -231,16 +453,82 @@ impl<T: Transport, R: Tokenize> ViewMethodBuilder<T, R> {
         self.block = Some(value);
         self
     }
+
+    /// Specify state overrides for the call, allowing it to be simulated as
+    /// if accounts had different balances, code or storage.
+    ///
+    /// State overrides are applied to direct and batched calls. Multicalls
+    /// can't apply them to individual calls, so adding a call with state
+    /// overrides to a multicall resolves to an
+    /// [`ExecutionError::UnsupportedStateOverrides`] error.
+    pub fn state_overrides(mut self, value: StateOverrides) -> Self {
+        self.state_overrides = Some(value);
+        self
+    }
+
+    /// Adds a state override for a single account to the call.
+    pub fn state_override(mut self, address: Address, value: StateOverride) -> Self {
+        self.state_overrides
+            .get_or_insert_with(StateOverrides::new)
+            .insert(address, value);
+        self
+    }
 }
 
 impl<T: Transport, R: Tokenize> ViewMethodBuilder<T, R> {
     /// Call a contract method. Contract calls do not modify the blockchain and
     /// as such do not require gas or signing.
     pub async fn call(self) -> Result<R, MethodError> {
-        let eth = &self.m.web3.eth();
-        let (function, call, block) = self.decompose();
-        let future = eth.call(call, block);
-        convert_response::<_, R>(future, function).await
+        let (function, interface, result) = self.execute().await;
+        convert_response::<_, _, R>(future::ready(result), function, interface).await
+    }
+
+    /// Call a contract method that may not be implemented by the contract,
+    /// returning `None` instead of an error if it isn't.
+    ///
+    /// A method is considered to not be implemented if the call reverts
+    /// without any revert data, or if it returns no data even though the
+    /// method has return values. This is the case for contracts that don't
+    /// have the method and either have no fallback function or a fallback
+    /// function that doesn't revert, as well as for accounts without code.
+    /// This is useful for optional interfaces, such as the `symbol()` and
+    /// `decimals()` metadata getters of ERC-20 tokens.
+    pub async fn try_call(self) -> Result<Option<R>, MethodError> {
+        let (function, interface, result) = self.execute().await;
+        match &result {
+            Ok(bytes) if bytes.0.is_empty() && !function.outputs.is_empty() => return Ok(None),
+            Err(ExecutionError::Revert(None)) => return Ok(None),
+            _ => {}
+        }
+        convert_response::<_, _, R>(future::ready(result), function, interface)
+            .await
+            .map(Some)
+    }
+
+    /// Executes the `eth_call` for this view method, returning the raw result
+    /// along with what is needed to decode it.
+    async fn execute(
+        self,
+    ) -> (
+        Function,
+        Option<Arc<Interface>>,
+        Result<Bytes, ExecutionError>,
+    ) {
+        let web3 = self.m.web3.clone();
+        let state_overrides = self.state_overrides.clone();
+        let (function, interface, call, block) = self.decompose();
+        let result = match state_overrides {
+            Some(overrides) => call_with_state_overrides(web3.transport(), call, block, &overrides)
+                .await
+                .map_err(ExecutionError::from),
+            None => web3
+                .eth()
+                .call(call, block)
+                .await
+                .map_err(ExecutionError::from),
+        };
+
+        (function, interface, result)
     }
 
     /// Adds this view method to a batch. Allows execution with other contract calls in one roundtrip
@@ -250,12 +538,45 @@ impl<T: Transport, R: Tokenize> ViewMethodBuilder<T, R> {
         self,
         batch: &mut CallBatch<B>,
     ) -> impl std::future::Future<Output = Result<R, MethodError>> {
-        let (function, call, block) = self.decompose();
-        let future = batch.push(call, block);
-        async move { convert_response::<_, R>(future, function).await }
+        let state_overrides = self.state_overrides.clone();
+        let (function, interface, call, block) = self.decompose();
+        let future = batch.push_with_state_overrides(call, block, state_overrides);
+        async move { convert_response::<_, _, R>(future, function, interface).await }
+    }
+
+    /// Adds this view method to a multicall, allowing it to be aggregated
+    /// with other contract calls into a single `eth_call`. The returned
+    /// future only resolves once `multicall` is executed. Panics, if
+    /// `multicall` is dropped before executing.
+    ///
+    /// Calls with state overrides are not added to the multicall and resolve
+    /// to an [`ExecutionError::UnsupportedStateOverrides`] error instead.
+    pub fn multicall(
+        self,
+        multicall: &mut Multicall<T>,
+    ) -> impl std::future::Future<Output = Result<R, MethodError>> {
+        let has_state_overrides = self.state_overrides.is_some();
+        let (function, interface, call, _) = self.decompose();
+        let future = (!has_state_overrides).then(|| multicall.push(call));
+        async move {
+            match future {
+                Some(future) => convert_response::<_, _, R>(future, function, interface).await,
+                None => Err(MethodError::new(
+                    &function,
+                    ExecutionError::UnsupportedStateOverrides,
+                )),
+            }
+        }
     }
 
-    fn decompose(self) -> (Function, CallRequest, Option<BlockId>) {
+    fn decompose(
+        self,
+    ) -> (
+        Function,
+        Option<Arc<Interface>>,
+        CallRequest,
+        Option<BlockId>,
+    ) {
         let resolved_gas_price = self
             .m
             .tx
@@ -264,6 +585,7 @@ impl<T: Transport, R: Tokenize> ViewMethodBuilder<T, R> {
             .unwrap_or_default();
         (
             self.m.function,
+            self.m.interface,
             CallRequest {
                 from: self.m.tx.from.map(|account| account.address()),
                 to: self.m.tx.to,
@@ -282,15 +604,17 @@ impl<T: Transport, R: Tokenize> ViewMethodBuilder<T, R> {
 }
 
 async fn convert_response<
-    F: std::future::Future<Output = Result<Bytes, web3::Error>>,
+    F: std::future::Future<Output = Result<Bytes, E>>,
+    E: Into<ExecutionError>,
     R: Tokenize,
 >(
     future: F,
     function: Function,
+    interface: Option<Arc<Interface>>,
 ) -> Result<R, MethodError> {
     let bytes = future
         .await
-        .map_err(|err| MethodError::new(&function, err))?;
+        .map_err(|err| method_error(&function, interface.as_deref(), err.into()))?;
     let tokens = function
         .decode_output(&bytes.0)
         .map_err(|err| MethodError::new(&function, err))?;
@@ -306,6 +630,20 @@ async fn convert_response<
     Ok(result)
 }
 
+/// Creates a method error, decoding custom errors with the contract interface
+/// when it is known.
+fn method_error(
+    function: &Function,
+    interface: Option<&Interface>,
+    err: ExecutionError,
+) -> MethodError {
+    let err = match interface {
+        Some(interface) => err.resolve_custom_error(&interface.abi),
+        None => err,
+    };
+    MethodError::new(function, err)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -333,6 +671,21 @@ mod tests {
         (function, Bytes(data))
     }
 
+    #[test]
+    fn method_into_inner_tx() {
+        let transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let address = addr!("0x0123456789012345678901234567890123456789");
+        let (function, data) = test_abi_function();
+        let tx = MethodBuilder::<_, U256>::new(web3, function, address, data.clone())
+            .value(42.into())
+            .into_inner_tx();
+
+        assert_eq!(tx, (address, 42.into(), data));
+        transport.assert_no_more_requests();
+    }
+
     #[test]
     fn method_tx_options() {
         let transport = TestTransport::new();
@@ -361,6 +714,69 @@ mod tests {
         transport.assert_no_more_requests();
     }
 
+    #[test]
+    fn method_summary() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let function = function(
+            "transfer",
+            &[("to", ParamType::Address), ("amount", ParamType::Uint(256))],
+            &[ParamType::Bool],
+        );
+        let address = addr!("0x0123456789012345678901234567890123456789");
+        let recipient = addr!("0x9876543210987654321098765432109876543210");
+        let data = function
+            .encode_input(&[Token::Address(recipient), Token::Uint(1_000.into())])
+            .unwrap();
+        let method = MethodBuilder::<_, bool>::new(web3, function, address, Bytes(data))
+            .gas(21_000.into())
+            .gas_price(GasPrice::Eip1559 {
+                max_fee_per_gas: 2.into(),
+                max_priority_fee_per_gas: 1.into(),
+            });
+
+        transport.add_response(json!("0x1"));
+        let summary = method.summary().immediate().expect("summary error");
+        transport.assert_request("eth_chainId", &[]);
+        transport.assert_no_more_requests();
+
+        assert_eq!(
+            serde_json::to_value(&summary).unwrap(),
+            json!({
+                "method": "transfer",
+                "signature": "transfer(address,uint256)",
+                "params": [
+                    { "name": "to", "type": "address", "value": format!("{:?}", recipient) },
+                    { "name": "amount", "type": "uint256", "value": "1000" },
+                ],
+                "from": null,
+                "to": address,
+                "value": "0x0",
+                "gas": "0x5208",
+                "gasPrice": null,
+                "maxFeePerGas": "0x2",
+                "maxPriorityFeePerGas": "0x1",
+                "nonce": null,
+                "chainId": 1,
+            })
+        );
+
+        let key = key!("0x0102030405060708091011121314151617181920212223242526272829303132");
+        let from = key.public_address();
+        let summary = method
+            .from(Account::Offline(key, Some(100)))
+            .nonce(7.into())
+            .summary()
+            .immediate()
+            .expect("summary error");
+        transport.assert_no_more_requests();
+
+        assert_eq!(summary.from, Some(from));
+        assert_eq!(summary.nonce, Some(7.into()));
+        assert_eq!(summary.chain_id, 100);
+    }
+
     #[test]
     fn view_method_call() {
         let mut transport = TestTransport::new();
@@ -404,6 +820,137 @@ mod tests {
         transport.assert_no_more_requests();
     }
 
+    #[test]
+    fn view_method_call_at_pending_block() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let address = addr!("0x0123456789012345678901234567890123456789");
+        let (function, data) = test_abi_function();
+        let tx = MethodBuilder::<_, U256>::new(web3, function, address, data.clone())
+            .view()
+            .block(BlockNumber::Pending.into());
+
+        transport.add_response(json!(
+            "0x000000000000000000000000000000000000000000000000000000000000002a"
+        ));
+        let result = tx.call().immediate().expect("call error");
+
+        assert_eq!(result, 42.into());
+        transport.assert_request(
+            "eth_call",
+            &[json!({ "to": address, "data": data }), json!("pending")],
+        );
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn view_method_call_with_state_overrides() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let address = addr!("0x0123456789012345678901234567890123456789");
+        let account = addr!("0x9876543210987654321098765432109876543210");
+        let (function, data) = test_abi_function();
+        let tx = ViewMethodBuilder::<_, U256>::from_method(MethodBuilder::new(
+            web3,
+            function,
+            address,
+            data.clone(),
+        ))
+        .state_override(account, StateOverride::new().balance(1_000.into()))
+        .state_override(address, StateOverride::new().code(vec![0x60, 0x00]));
+
+        transport.add_response(json!(
+            "0x000000000000000000000000000000000000000000000000000000000000002a"
+        ));
+        let result = tx.call().immediate().expect("call error");
+
+        assert_eq!(result, 42.into());
+        transport.assert_request(
+            "eth_call",
+            &[
+                json!({
+                    "to": address,
+                    "data": data,
+                }),
+                json!("latest"),
+                json!({
+                    format!("{:?}", account): { "balance": "0x3e8" },
+                    format!("{:?}", address): { "code": "0x6000" },
+                }),
+            ],
+        );
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn view_method_multicall_rejects_state_overrides() {
+        let transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let address = addr!("0x0123456789012345678901234567890123456789");
+        let (function, data) = test_abi_function();
+        let mut multicall = Multicall::new(web3.clone());
+        let call = ViewMethodBuilder::<_, U256>::from_method(MethodBuilder::new(
+            web3, function, address, data,
+        ))
+        .state_override(address, StateOverride::new().balance(1_000.into()))
+        .multicall(&mut multicall);
+
+        assert!(multicall.is_empty());
+        assert!(matches!(
+            call.immediate().unwrap_err().inner,
+            ExecutionError::UnsupportedStateOverrides
+        ));
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn view_method_try_call() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let address = addr!("0x0123456789012345678901234567890123456789");
+        let (function, data) = test_abi_function();
+        let tx = || {
+            ViewMethodBuilder::<_, U256>::from_method(MethodBuilder::new(
+                web3.clone(),
+                function.clone(),
+                address,
+                data.clone(),
+            ))
+        };
+
+        transport.add_response(json!(
+            "0x000000000000000000000000000000000000000000000000000000000000002a"
+        ));
+        assert_eq!(tx().try_call().immediate().unwrap(), Some(42.into()));
+
+        // no code or a fallback function that doesn't revert
+        transport.add_response(json!("0x"));
+        assert_eq!(tx().try_call().immediate().unwrap(), None);
+
+        // no fallback function
+        transport.add_error(web3::Error::Rpc(jsonrpc_core::Error {
+            code: 3.into(),
+            message: "execution reverted".to_owned(),
+            data: None,
+        }));
+        assert_eq!(tx().try_call().immediate().unwrap(), None);
+
+        transport.add_error(web3::Error::Rpc(jsonrpc_core::Error {
+            code: 3.into(),
+            message: "execution reverted: nope".to_owned(),
+            data: Some(json!(crate::errors::revert::encode_reason_hex("nope"))),
+        }));
+        let err = tx().try_call().immediate().unwrap_err();
+        assert_eq!(err.inner.revert_reason(), Some("nope"));
+
+        transport.add_response(json!("0x2a"));
+        assert!(tx().try_call().immediate().is_err());
+    }
+
     #[test]
     fn method_to_view_method_preserves_options() {
         let mut transport = TestTransport::new();
@@ -447,12 +994,90 @@ mod tests {
                 from: Some(Account::Local(from, None)),
                 gas: Some(1.into()),
                 gas_price: Some(2.0.into()),
+                max_calldata_size: Some(1024),
             })
             .into_inner();
 
         assert_eq!(tx.from.map(|a| a.address()), Some(from));
         assert_eq!(tx.gas, Some(1.into()));
         assert_eq!(tx.gas_price, Some(2.0.into()));
+        assert_eq!(tx.max_calldata_size, Some(1024));
         transport.assert_no_more_requests();
     }
+
+    #[test]
+    fn view_method_multicall() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let address = addr!("0x0123456789012345678901234567890123456789");
+        let (function, data) = test_abi_function();
+        let mut multicall = Multicall::new(web3.clone());
+        let results = [true, false].map(|_| {
+            ViewMethodBuilder::<_, U256>::from_method(MethodBuilder::new(
+                web3.clone(),
+                function.clone(),
+                address,
+                data.clone(),
+            ))
+            .multicall(&mut multicall)
+        });
+
+        transport.add_response(json!(Bytes(ethcontract_common::abi::encode(&[
+            Token::Array(vec![
+                Token::Tuple(vec![
+                    Token::Bool(true),
+                    Token::Bytes(ethcontract_common::abi::encode(&[Token::Uint(42.into())])),
+                ]),
+                Token::Tuple(vec![Token::Bool(false), Token::Bytes(vec![])]),
+            ]),
+        ]))));
+        multicall.try_aggregate().immediate();
+
+        let [ok, failed] = results;
+        assert_eq!(ok.immediate().expect("call error"), U256::from(42));
+        assert!(failed.immediate().expect_err("call succeeded").is_revert());
+    }
+
+    #[test]
+    fn method_call_decodes_custom_error() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let address = addr!("0x0123456789012345678901234567890123456789");
+        let (function, data) = test_abi_function();
+        let error = ethcontract_common::abi::AbiError {
+            name: "Unauthorized".to_owned(),
+            inputs: vec![Param {
+                name: "caller".to_owned(),
+                kind: ParamType::Address,
+                internal_type: None,
+            }],
+        };
+        let revert_data = error
+            .encode(&[Token::Address(Address::repeat_byte(1))])
+            .unwrap();
+        let mut abi = ethcontract_common::Abi::default();
+        abi.errors.insert(error.name.clone(), vec![error]);
+
+        transport.add_error(web3::Error::Rpc(jsonrpc_core::Error {
+            code: 3.into(),
+            message: "execution reverted".to_owned(),
+            data: Some(json!(format!("0x{}", hex::encode(&revert_data)))),
+        }));
+        let err = MethodBuilder::<_, U256>::new(web3, function, address, data)
+            .interface(Arc::new(abi.into()))
+            .call()
+            .immediate()
+            .unwrap_err();
+
+        assert!(err.is_revert());
+        assert_eq!(err.revert_data(), Some(revert_data));
+        let custom = err.custom_error().unwrap();
+        assert_eq!(custom.name(), Some("Unauthorized"));
+        assert_eq!(
+            custom.decoded.as_ref().unwrap().1,
+            [Token::Address(Address::repeat_byte(1))],
+        );
+    }
 }
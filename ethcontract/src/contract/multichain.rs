@@ -0,0 +1,184 @@
+//! Implementation of a registry of contract instances deployed on multiple
+//! chains.
+
+use crate::contract::Instance;
+use crate::errors::DeployError;
+use ethcontract_common::contract::Network;
+use ethcontract_common::Contract;
+use std::collections::{BTreeMap, HashMap};
+use web3::api::Web3;
+use web3::Transport;
+
+/// A registry of instances of the same contract deployed on multiple chains.
+///
+/// Calls are routed to an instance by explicitly specifying the chain ID
+/// with [`on`](MultichainInstance::on). The instance type is generic, so it
+/// can hold dynamic [`Instance`]s as well as generated contract types.
+#[derive(Clone, Debug)]
+pub struct MultichainInstance<C> {
+    instances: BTreeMap<u64, C>,
+}
+
+impl<C> MultichainInstance<C> {
+    /// Creates a new empty registry.
+    pub fn new() -> Self {
+        MultichainInstance {
+            instances: BTreeMap::new(),
+        }
+    }
+
+    /// Creates a registry with an instance for each of the provided per-chain
+    /// `web3` providers, using the contract deployments from `networks`.
+    ///
+    /// The `at` function is used to create an instance from a `web3`
+    /// provider and a deployment. Returns an error if there is no deployment
+    /// for one of the chains.
+    pub fn from_networks<W, F>(
+        networks: &HashMap<String, Network>,
+        web3s: impl IntoIterator<Item = (u64, W)>,
+        mut at: F,
+    ) -> Result<Self, DeployError>
+    where
+        F: FnMut(W, &Network) -> C,
+    {
+        let mut registry = MultichainInstance::new();
+        for (chain_id, web3) in web3s {
+            let network = networks
+                .get(&chain_id.to_string())
+                .ok_or_else(|| DeployError::NotFound(chain_id.to_string()))?;
+            registry.insert(chain_id, at(web3, network));
+        }
+
+        Ok(registry)
+    }
+
+    /// Adds an instance for the specified chain, returning the instance that
+    /// was previously registered for it.
+    pub fn insert(&mut self, chain_id: u64, instance: C) -> Option<C> {
+        self.instances.insert(chain_id, instance)
+    }
+
+    /// Removes the instance for the specified chain.
+    pub fn remove(&mut self, chain_id: u64) -> Option<C> {
+        self.instances.remove(&chain_id)
+    }
+
+    /// Returns the instance for the specified chain, if there is one.
+    pub fn get(&self, chain_id: u64) -> Option<&C> {
+        self.instances.get(&chain_id)
+    }
+
+    /// Returns a mutable reference to the instance for the specified chain,
+    /// if there is one.
+    pub fn get_mut(&mut self, chain_id: u64) -> Option<&mut C> {
+        self.instances.get_mut(&chain_id)
+    }
+
+    /// Returns the instance for the specified chain, or an error if the
+    /// contract is not registered for this chain.
+    pub fn on(&self, chain_id: u64) -> Result<&C, DeployError> {
+        self.get(chain_id)
+            .ok_or_else(|| DeployError::NotFound(chain_id.to_string()))
+    }
+
+    /// Returns the IDs of all chains with a registered instance, in ascending
+    /// order.
+    pub fn chain_ids(&self) -> impl Iterator<Item = u64> + '_ {
+        self.instances.keys().copied()
+    }
+
+    /// Returns an iterator over all registered instances and their chain IDs.
+    pub fn iter(&self) -> impl Iterator<Item = (u64, &C)> + '_ {
+        self.instances
+            .iter()
+            .map(|(chain_id, instance)| (*chain_id, instance))
+    }
+}
+
+impl<T: Transport> MultichainInstance<Instance<T>> {
+    /// Creates a registry of dynamic instances for each of the provided
+    /// per-chain `web3` providers, using the deployments from the contract
+    /// artifact's networks.
+    ///
+    /// Note that, unlike [`Instance::deployed`], this does not query the
+    /// providers for their chain IDs.
+    pub fn deployed(
+        contract: &Contract,
+        web3s: impl IntoIterator<Item = (u64, Web3<T>)>,
+    ) -> Result<Self, DeployError> {
+        MultichainInstance::from_networks(&contract.networks, web3s, |web3, network| {
+            Instance::with_deployment_info(
+                web3,
+                contract.interface.clone(),
+                network.address,
+                network.deployment_information,
+            )
+        })
+    }
+}
+
+impl<C> Default for MultichainInstance<C> {
+    fn default() -> Self {
+        MultichainInstance::new()
+    }
+}
+
+impl<C> FromIterator<(u64, C)> for MultichainInstance<C> {
+    fn from_iter<I: IntoIterator<Item = (u64, C)>>(iter: I) -> Self {
+        MultichainInstance {
+            instances: iter.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::prelude::*;
+    use web3::types::Address;
+
+    fn contract() -> Contract {
+        let mut contract = Contract::empty();
+        for (chain_id, address) in [("1", 0x01), ("100", 0x64)] {
+            contract.networks.insert(
+                chain_id.to_string(),
+                Network {
+                    address: Address::from_low_u64_be(address),
+                    deployment_information: None,
+                },
+            );
+        }
+        contract
+    }
+
+    #[test]
+    fn routes_by_chain_id() {
+        let transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let registry =
+            MultichainInstance::deployed(&contract(), [(1, web3.clone()), (100, web3)]).unwrap();
+
+        assert_eq!(registry.chain_ids().collect::<Vec<_>>(), [1, 100]);
+        assert_eq!(
+            registry.on(100).unwrap().address(),
+            Address::from_low_u64_be(0x64)
+        );
+        assert!(matches!(
+            registry.on(5),
+            Err(DeployError::NotFound(chain_id)) if chain_id == "5"
+        ));
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn missing_deployment() {
+        let web3 = Web3::new(TestTransport::new());
+
+        let result = MultichainInstance::deployed(&contract(), [(1, web3.clone()), (5, web3)]);
+        assert!(matches!(
+            result,
+            Err(DeployError::NotFound(chain_id)) if chain_id == "5"
+        ));
+    }
+}
@@ -0,0 +1,122 @@
+//! State overrides for simulating `eth_call`s against modified account state.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use web3::helpers::{self, CallFuture};
+use web3::types::{Address, BlockId, BlockNumber, Bytes, CallRequest, H256, U256};
+use web3::Transport;
+
+/// State overrides per account for an `eth_call`, as supported by Geth and
+/// most other node implementations as the optional third `eth_call`
+/// parameter.
+pub type StateOverrides = HashMap<Address, StateOverride>;
+
+/// Overrides of the state of a single account for the duration of an
+/// `eth_call`.
+///
+/// Note that `state` and `state_diff` are mutually exclusive: `state` replaces
+/// the entire storage of the account, while `state_diff` only replaces the
+/// specified storage slots.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateOverride {
+    /// Fake balance to set for the account.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub balance: Option<U256>,
+    /// Fake nonce to set for the account.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<U256>,
+    /// Fake code to set for the account.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<Bytes>,
+    /// Fake storage to replace the entire storage of the account with.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<HashMap<H256, H256>>,
+    /// Fake storage slots to override in the storage of the account.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state_diff: Option<HashMap<H256, H256>>,
+}
+
+impl StateOverride {
+    /// Creates a new empty state override.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Overrides the balance of the account.
+    pub fn balance(mut self, value: U256) -> Self {
+        self.balance = Some(value);
+        self
+    }
+
+    /// Overrides the nonce of the account.
+    pub fn nonce(mut self, value: U256) -> Self {
+        self.nonce = Some(value);
+        self
+    }
+
+    /// Overrides the code of the account.
+    pub fn code(mut self, value: impl Into<Bytes>) -> Self {
+        self.code = Some(value.into());
+        self
+    }
+
+    /// Replaces the entire storage of the account.
+    pub fn state(mut self, value: HashMap<H256, H256>) -> Self {
+        self.state = Some(value);
+        self
+    }
+
+    /// Overrides a single storage slot of the account, keeping the rest of
+    /// its storage.
+    pub fn storage_slot(mut self, slot: H256, value: H256) -> Self {
+        self.state_diff
+            .get_or_insert_with(HashMap::new)
+            .insert(slot, value);
+        self
+    }
+}
+
+/// Performs an `eth_call` with state overrides. Defaults to the latest block
+/// if no block is specified, since the block parameter can't be omitted when
+/// passing state overrides.
+pub(crate) fn call_with_state_overrides<T: Transport>(
+    transport: &T,
+    call: CallRequest,
+    block: Option<BlockId>,
+    overrides: &StateOverrides,
+) -> CallFuture<Bytes, T::Out> {
+    let call = helpers::serialize(&call);
+    let block = helpers::serialize(&block.unwrap_or_else(|| BlockNumber::Latest.into()));
+    let overrides = helpers::serialize(overrides);
+    CallFuture::new(transport.execute("eth_call", vec![call, block, overrides]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn serialize_state_override() {
+        let slot = H256::repeat_byte(1);
+        let value = H256::repeat_byte(2);
+        let overrides = StateOverride::new()
+            .balance(1_000.into())
+            .code(vec![0x60, 0x00])
+            .storage_slot(slot, value);
+
+        assert_eq!(
+            serde_json::to_value(&overrides).unwrap(),
+            json!({
+                "balance": "0x3e8",
+                "code": "0x6000",
+                "stateDiff": { format!("{:?}", slot): value },
+            }),
+        );
+        assert_eq!(
+            serde_json::to_value(StateOverride::new()).unwrap(),
+            json!({})
+        );
+    }
+}
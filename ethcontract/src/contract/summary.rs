@@ -0,0 +1,118 @@
+//! Human-readable summaries of contract method transactions, for reviewing
+//! transactions in audit logs and approval workflows before they are sent.
+
+use crate::I256;
+use ethcontract_common::abi::{Function, Token};
+use serde::Serialize;
+use web3::types::{Address, U256};
+
+/// A structured summary of a contract method transaction that can be
+/// serialized for audit logs or approval systems. See
+/// [`MethodBuilder::summary`](crate::contract::MethodBuilder::summary).
+///
+/// Transaction parameters that were not explicitly set, and will only be
+/// filled in when the transaction is built, are `None`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MethodSummary {
+    /// The name of the method.
+    pub method: String,
+    /// The signature of the method, for example `transfer(address,uint256)`.
+    pub signature: String,
+    /// The decoded method parameters.
+    pub params: Vec<ParamSummary>,
+    /// The sender of the transaction.
+    pub from: Option<Address>,
+    /// The contract the transaction is sent to.
+    pub to: Option<Address>,
+    /// The amount of ETH sent with the transaction.
+    pub value: U256,
+    /// The gas limit of the transaction.
+    pub gas: Option<U256>,
+    /// The gas price of a legacy transaction.
+    pub gas_price: Option<U256>,
+    /// The maximum fee per gas of an EIP-1559 transaction.
+    pub max_fee_per_gas: Option<U256>,
+    /// The maximum priority fee per gas of an EIP-1559 transaction.
+    pub max_priority_fee_per_gas: Option<U256>,
+    /// The nonce of the transaction.
+    pub nonce: Option<U256>,
+    /// The chain ID of the network the transaction is sent to.
+    pub chain_id: u64,
+}
+
+/// A decoded method parameter of a [`MethodSummary`].
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct ParamSummary {
+    /// The name of the parameter, which is empty for unnamed parameters.
+    pub name: String,
+    /// The Solidity type of the parameter, for example `uint256`.
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// The value of the parameter, formatted for humans. Integers are
+    /// formatted as decimal numbers, and addresses and bytes as `0x`-prefixed
+    /// hexadecimal strings.
+    pub value: String,
+}
+
+/// Decodes the parameters of a method call from its calldata.
+pub(super) fn decode_params(
+    function: &Function,
+    data: &[u8],
+) -> Result<Vec<ParamSummary>, ethcontract_common::abi::Error> {
+    let tokens = function.decode_input(data.get(4..).unwrap_or_default())?;
+    Ok(function
+        .inputs
+        .iter()
+        .zip(&tokens)
+        .map(|(param, token)| ParamSummary {
+            name: param.name.clone(),
+            kind: param.kind.to_string(),
+            value: format_token(token),
+        })
+        .collect())
+}
+
+/// Formats a token for humans.
+fn format_token(token: &Token) -> String {
+    let join = |tokens: &[Token]| {
+        tokens
+            .iter()
+            .map(format_token)
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    match token {
+        Token::Address(address) => format!("{:?}", address),
+        Token::FixedBytes(bytes) | Token::Bytes(bytes) => format!("0x{}", hex::encode(bytes)),
+        Token::Int(value) => I256::from_raw(*value).to_string(),
+        Token::Uint(value) => value.to_string(),
+        Token::Bool(value) => value.to_string(),
+        Token::String(value) => value.clone(),
+        Token::FixedArray(tokens) | Token::Array(tokens) => format!("[{}]", join(tokens)),
+        Token::Tuple(tokens) => format!("({})", join(tokens)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_tokens() {
+        assert_eq!(
+            format_token(&Token::Address(Address::repeat_byte(0x42))),
+            "0x4242424242424242424242424242424242424242"
+        );
+        assert_eq!(format_token(&Token::Bytes(vec![0xde, 0xad])), "0xdead");
+        assert_eq!(format_token(&Token::Int(U256::MAX)), "-1");
+        assert_eq!(format_token(&Token::Uint(1_000.into())), "1000");
+        assert_eq!(
+            format_token(&Token::Array(vec![
+                Token::Bool(true),
+                Token::Tuple(vec![Token::String("a".into()), Token::Uint(1.into())]),
+            ])),
+            "[true, (a, 1)]"
+        );
+    }
+}
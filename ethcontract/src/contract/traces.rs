@@ -0,0 +1,292 @@
+//! Module for retrieving the internal calls made to a contract with the
+//! `trace_filter` API.
+//!
+//! This complements event streams for contracts that change state without
+//! emitting logs, as internal calls made by other contracts are otherwise not
+//! visible without re-executing transactions.
+
+use crate::contract::ParseCall;
+use crate::errors::ExecutionError;
+use ethcontract_common::contract::Interface;
+use ethcontract_common::hash::H32;
+use std::sync::Arc;
+use web3::api::Web3;
+use web3::types::{
+    Action, Address, BlockNumber, Bytes, CallType, Res, TraceFilterBuilder, H256, U256,
+};
+use web3::Transport;
+
+/// A call made to a contract, either directly by a transaction or internally
+/// by another contract.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InternalCall {
+    /// The caller.
+    pub from: Address,
+    /// The contract that was called.
+    pub to: Address,
+    /// The ETH value sent with the call.
+    pub value: U256,
+    /// The calldata of the call.
+    pub input: Bytes,
+    /// The return data of the call, or `None` if the call failed.
+    pub output: Option<Bytes>,
+    /// The kind of call.
+    pub call_type: CallType,
+    /// The error if the call failed, for example `Reverted`.
+    pub error: Option<String>,
+    /// The name of the called contract method, or `None` if the calldata does
+    /// not match any method of the contract ABI (for example, for calls to
+    /// the fallback function).
+    pub function: Option<String>,
+    /// The number of the block containing the transaction.
+    pub block_number: u64,
+    /// The hash of the block containing the transaction.
+    pub block_hash: H256,
+    /// The hash of the transaction that made the call.
+    pub transaction_hash: Option<H256>,
+    /// The position of the call in the call tree of the transaction. This is
+    /// empty for calls made directly by the transaction.
+    pub trace_address: Vec<usize>,
+}
+
+impl InternalCall {
+    /// Returns true if the call failed.
+    pub fn is_reverted(&self) -> bool {
+        self.error.is_some()
+    }
+
+    /// Returns true if the call was made directly by the transaction rather
+    /// than by another contract.
+    pub fn is_top_level(&self) -> bool {
+        self.trace_address.is_empty()
+    }
+
+    /// Decodes the calldata of the call as a typed contract method call, such
+    /// as the `Call` enum of a generated contract.
+    pub fn decode<C: ParseCall>(&self) -> Result<C, ExecutionError> {
+        C::parse_call(&self.input.0)
+    }
+}
+
+/// A builder for retrieving the calls made to a contract with `trace_filter`.
+///
+/// Note that `trace_filter` is only supported by nodes implementing the
+/// OpenEthereum-style trace APIs, such as Erigon, Nethermind and Reth, and
+/// usually requires an archive node for historic blocks.
+#[derive(Debug)]
+#[must_use = "traces builders do nothing unless you `query` them"]
+pub struct TracesBuilder<T: Transport> {
+    web3: Web3<T>,
+    interface: Arc<Interface>,
+    address: Address,
+    /// The underlying trace filter.
+    pub filter: TraceFilterBuilder,
+}
+
+impl<T: Transport> TracesBuilder<T> {
+    /// Creates a new traces builder for the contract at the specified address.
+    pub fn new(web3: Web3<T>, interface: Arc<Interface>, address: Address) -> Self {
+        TracesBuilder {
+            web3,
+            interface,
+            address,
+            filter: TraceFilterBuilder::default().to_address(vec![address]),
+        }
+    }
+
+    /// Sets the first block from which to retrieve calls.
+    #[allow(clippy::wrong_self_convention)]
+    pub fn from_block(mut self, block: BlockNumber) -> Self {
+        self.filter = self.filter.from_block(block);
+        self
+    }
+
+    /// Sets the last block from which to retrieve calls.
+    #[allow(clippy::wrong_self_convention)]
+    pub fn to_block(mut self, block: BlockNumber) -> Self {
+        self.filter = self.filter.to_block(block);
+        self
+    }
+
+    /// Only retrieve calls made by one of the specified callers.
+    #[allow(clippy::wrong_self_convention)]
+    pub fn from_address(mut self, callers: Vec<Address>) -> Self {
+        self.filter = self.filter.from_address(callers);
+        self
+    }
+
+    /// Skips the specified number of traces, for paginating results.
+    pub fn after(mut self, offset: usize) -> Self {
+        self.filter = self.filter.after(offset);
+        self
+    }
+
+    /// Limits the number of traces to retrieve, for paginating results.
+    pub fn count(mut self, count: usize) -> Self {
+        self.filter = self.filter.count(count);
+        self
+    }
+
+    /// Retrieves the calls made to the contract with `trace_filter`, in the
+    /// order in which they were made.
+    ///
+    /// Note that pagination with `after` and `count` applies to the traces
+    /// returned by the node, which may include traces other than calls, such
+    /// as self-destructs refunding the contract.
+    pub async fn query(self) -> Result<Vec<InternalCall>, ExecutionError> {
+        let traces = self.web3.trace().filter(self.filter.build()).await?;
+
+        let calls = traces
+            .into_iter()
+            .filter_map(|trace| {
+                let call = match trace.action {
+                    Action::Call(call) if call.to == self.address => call,
+                    _ => return None,
+                };
+                let output = match trace.result {
+                    Some(Res::Call(result)) => Some(result.output),
+                    _ => None,
+                };
+                let function = call
+                    .input
+                    .0
+                    .get(..4)
+                    .and_then(|selector| {
+                        let mut signature = H32::default();
+                        signature.copy_from_slice(selector);
+                        self.interface.methods.get(&signature)
+                    })
+                    .map(|(name, _)| name.clone());
+
+                Some(InternalCall {
+                    from: call.from,
+                    to: call.to,
+                    value: call.value,
+                    input: call.input,
+                    output,
+                    call_type: call.call_type,
+                    error: trace.error,
+                    function,
+                    block_number: trace.block_number,
+                    block_hash: trace.block_hash,
+                    transaction_hash: trace.transaction_hash,
+                    trace_address: trace.trace_address,
+                })
+            })
+            .collect();
+
+        Ok(calls)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::prelude::*;
+    use ethcontract_common::abi::{ParamType, Token};
+
+    fn interface() -> Arc<Interface> {
+        let poke = function("poke", &[("value", ParamType::Uint(256))], &[]);
+        Arc::new(abi(vec![poke]).into())
+    }
+
+    fn poke(value: u64) -> Vec<u8> {
+        interface()
+            .abi
+            .function("poke")
+            .unwrap()
+            .encode_input(&[Token::Uint(value.into())])
+            .unwrap()
+    }
+
+    fn trace(to: Address, input: &str, trace_address: Vec<usize>) -> serde_json::Value {
+        json!({
+            "action": {
+                "callType": "call",
+                "from": Address::repeat_byte(1),
+                "to": to,
+                "gas": "0x1000",
+                "input": input,
+                "value": "0x0",
+            },
+            "result": { "gasUsed": "0x100", "output": "0x" },
+            "subtraces": 0,
+            "traceAddress": trace_address,
+            "transactionHash": H256::repeat_byte(4),
+            "transactionPosition": 0,
+            "blockHash": H256::repeat_byte(5),
+            "blockNumber": 42,
+            "type": "call",
+        })
+    }
+
+    #[test]
+    fn query_internal_calls() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let address = Address::repeat_byte(2);
+        transport.add_response(json!([
+            trace(address, &format!("0x{}", hex::encode(poke(1337))), vec![0]),
+            trace(Address::repeat_byte(3), "0x", vec![1]),
+            trace(address, "0x", vec![]),
+        ]));
+
+        let calls = TracesBuilder::new(web3, interface(), address)
+            .from_block(BlockNumber::Number(40.into()))
+            .count(10)
+            .query()
+            .immediate()
+            .expect("query failed");
+
+        transport.assert_request(
+            "trace_filter",
+            &[json!({
+                "fromBlock": "0x28",
+                "toAddress": [address],
+                "count": 10,
+            })],
+        );
+        transport.assert_no_more_requests();
+
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].function.as_deref(), Some("poke"));
+        assert!(!calls[0].is_top_level());
+        assert!(!calls[0].is_reverted());
+        assert_eq!(calls[0].block_number, 42);
+        assert_eq!(calls[0].transaction_hash, Some(H256::repeat_byte(4)));
+        assert_eq!(calls[1].function, None);
+        assert!(calls[1].is_top_level());
+    }
+
+    #[test]
+    fn decode_internal_call() {
+        struct Poke(U256);
+        impl ParseCall for Poke {
+            fn parse_call(data: &[u8]) -> Result<Self, ExecutionError> {
+                let function = interface().abi.function("poke").unwrap().clone();
+                match function.decode_input(&data[4..])?.as_slice() {
+                    [Token::Uint(value)] => Ok(Poke(*value)),
+                    _ => unreachable!(),
+                }
+            }
+        }
+
+        let call = InternalCall {
+            from: Address::repeat_byte(1),
+            to: Address::repeat_byte(2),
+            value: U256::zero(),
+            input: Bytes(poke(1337)),
+            output: None,
+            call_type: CallType::Call,
+            error: None,
+            function: Some("poke".into()),
+            block_number: 42,
+            block_hash: H256::repeat_byte(5),
+            transaction_hash: None,
+            trace_address: vec![],
+        };
+
+        assert_eq!(call.decode::<Poke>().unwrap().0, 1337.into());
+    }
+}
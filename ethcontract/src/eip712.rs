@@ -0,0 +1,508 @@
+//! EIP-712 typed structured data hashing and signing.
+//!
+//! EIP-712 specifies how to hash and sign structured data, so that users can
+//! sign messages that are both readable and unambiguous. This is used for
+//! meta-transactions and off-chain order signing, where a contract verifies
+//! signatures over typed messages such as orders or permits.
+//!
+//! Message values are specified as ABI tokens, with structs as tuples whose
+//! elements are in the order of the struct members.
+//!
+//! # Examples
+//!
+//! ```
+//! use ethcontract::eip712::{Domain, TypedData};
+//! use ethcontract::prelude::*;
+//! use ethcontract::tokens::Tokenize;
+//!
+//! let domain = Domain::new("Ether Mail", "1")
+//!     .chain_id(1)
+//!     .verifying_contract(Address::repeat_byte(0xcc));
+//! let data = TypedData::new(
+//!     domain,
+//!     "Mail",
+//!     (Address::repeat_byte(0xbb), "Hello, Bob!".to_owned()).into_token(),
+//! )
+//! .add_type("Mail", &[("to", "address"), ("contents", "string")]);
+//!
+//! let hash = data.signing_hash().unwrap();
+//! ```
+
+use crate::transaction::signer::{self, Signer};
+use crate::transaction::{Account, Signature};
+use ethcontract_common::abi::{self, param_type::Reader, ParamType, Token};
+use ethcontract_common::hash;
+use std::collections::{BTreeMap, BTreeSet};
+use web3::types::{Address, H256, U256};
+
+/// The name of the EIP-712 domain type.
+const DOMAIN_TYPE: &str = "EIP712Domain";
+
+/// An EIP-712 domain, used to separate signatures of messages for different
+/// applications, versions, chains and contracts.
+///
+/// Only the specified domain fields are included in the domain separator.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Domain {
+    /// The user readable name of the signing domain, for example the name of
+    /// the protocol.
+    pub name: Option<String>,
+    /// The current major version of the signing domain.
+    pub version: Option<String>,
+    /// The chain ID of the network the signatures are valid on.
+    pub chain_id: Option<U256>,
+    /// The address of the contract that will verify the signatures.
+    pub verifying_contract: Option<Address>,
+    /// A disambiguating salt for the protocol.
+    pub salt: Option<H256>,
+}
+
+impl Domain {
+    /// Creates a new domain with the specified name and version.
+    pub fn new<S: Into<String>>(name: S, version: S) -> Self {
+        Domain {
+            name: Some(name.into()),
+            version: Some(version.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the chain ID of the domain.
+    pub fn chain_id<C: Into<U256>>(mut self, value: C) -> Self {
+        self.chain_id = Some(value.into());
+        self
+    }
+
+    /// Sets the verifying contract of the domain.
+    pub fn verifying_contract(mut self, value: Address) -> Self {
+        self.verifying_contract = Some(value);
+        self
+    }
+
+    /// Sets the salt of the domain.
+    pub fn salt(mut self, value: H256) -> Self {
+        self.salt = Some(value);
+        self
+    }
+
+    /// Computes the domain separator, which is the struct hash of the domain.
+    pub fn separator(&self) -> H256 {
+        let mut members = Vec::new();
+        let mut encoded = Vec::new();
+        if let Some(name) = &self.name {
+            members.push("string name");
+            encoded.extend_from_slice(&hash::keccak256(name));
+        }
+        if let Some(version) = &self.version {
+            members.push("string version");
+            encoded.extend_from_slice(&hash::keccak256(version));
+        }
+        if let Some(chain_id) = self.chain_id {
+            members.push("uint256 chainId");
+            encoded.extend(abi::encode(&[Token::Uint(chain_id)]));
+        }
+        if let Some(verifying_contract) = self.verifying_contract {
+            members.push("address verifyingContract");
+            encoded.extend(abi::encode(&[Token::Address(verifying_contract)]));
+        }
+        if let Some(salt) = self.salt {
+            members.push("bytes32 salt");
+            encoded.extend_from_slice(salt.as_bytes());
+        }
+
+        let type_hash = hash::keccak256(format!("{}({})", DOMAIN_TYPE, members.join(",")));
+        H256(hash::keccak256([&type_hash[..], &encoded].concat()))
+    }
+}
+
+/// A member of an EIP-712 struct type.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Member {
+    /// The name of the member.
+    pub name: String,
+    /// The type of the member, for example `uint256`, `Person` or `Person[]`.
+    pub kind: String,
+}
+
+/// Typed structured data to be hashed and signed as specified by EIP-712.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TypedData {
+    /// The signing domain.
+    pub domain: Domain,
+    /// The struct type definitions, by type name.
+    pub types: BTreeMap<String, Vec<Member>>,
+    /// The name of the type of the message.
+    pub primary_type: String,
+    /// The message value.
+    pub message: Token,
+}
+
+impl TypedData {
+    /// Creates new typed data for a message of the specified primary type.
+    ///
+    /// The primary type and all struct types it references must be added
+    /// with [`TypedData::add_type`].
+    pub fn new<S: Into<String>>(domain: Domain, primary_type: S, message: Token) -> Self {
+        TypedData {
+            domain,
+            types: BTreeMap::new(),
+            primary_type: primary_type.into(),
+            message,
+        }
+    }
+
+    /// Adds a struct type definition with the specified member names and
+    /// types.
+    pub fn add_type<S: Into<String>>(mut self, name: S, members: &[(&str, &str)]) -> Self {
+        let members = members
+            .iter()
+            .map(|(name, kind)| Member {
+                name: (*name).to_owned(),
+                kind: (*kind).to_owned(),
+            })
+            .collect();
+        self.types.insert(name.into(), members);
+        self
+    }
+
+    /// Encodes a struct type as a string, for example
+    /// `Mail(Person from,Person to,string contents)Person(string name,address wallet)`.
+    pub fn encode_type(&self, name: &str) -> Result<String, Error> {
+        let mut dependencies = BTreeSet::new();
+        self.collect_dependencies(name, &mut dependencies)?;
+        dependencies.remove(name);
+
+        std::iter::once(name)
+            .chain(dependencies.iter().map(String::as_str))
+            .map(|name| {
+                let members = self.members(name)?;
+                let members = members
+                    .iter()
+                    .map(|member| format!("{} {}", member.kind, member.name))
+                    .collect::<Vec<_>>();
+                Ok(format!("{}({})", name, members.join(",")))
+            })
+            .collect()
+    }
+
+    /// Computes the type hash of a struct type.
+    pub fn type_hash(&self, name: &str) -> Result<H256, Error> {
+        Ok(H256(hash::keccak256(self.encode_type(name)?)))
+    }
+
+    /// Computes the hash of a struct value of the specified type.
+    pub fn hash_struct(&self, name: &str, value: &Token) -> Result<H256, Error> {
+        let members = self.members(name)?;
+        let values = match value {
+            Token::Tuple(values) if values.len() == members.len() => values,
+            _ => return Err(Error::InvalidValue(name.to_owned())),
+        };
+
+        let mut encoded = self.type_hash(name)?.as_bytes().to_vec();
+        for (member, value) in members.iter().zip(values) {
+            encoded.extend_from_slice(self.encode_value(&member.kind, value)?.as_bytes());
+        }
+
+        Ok(H256(hash::keccak256(encoded)))
+    }
+
+    /// Computes the hash of the message, which is the struct hash of the
+    /// message value for the primary type.
+    pub fn message_hash(&self) -> Result<H256, Error> {
+        self.hash_struct(&self.primary_type, &self.message)
+    }
+
+    /// Computes the hash to be signed for the typed data.
+    pub fn signing_hash(&self) -> Result<H256, Error> {
+        Ok(signing_hash(self.domain.separator(), self.message_hash()?))
+    }
+
+    /// Signs the typed data with the specified account.
+    ///
+    /// Only accounts that sign locally or with a signing backend are
+    /// supported, accounts managed by the node are not. The `v` value of the
+    /// returned signature is either `27` or `28`.
+    pub async fn sign(&self, account: &Account) -> Result<Signature, Error> {
+        let domain_separator = self.domain.separator();
+        let message_hash = self.message_hash()?;
+        let signature = match account {
+            Account::Offline(key, _) => {
+                Signer::sign_typed_data(key, domain_separator, message_hash).await?
+            }
+            #[cfg(feature = "aws-kms")]
+            Account::Kms(account, _) => {
+                Signer::sign_typed_data(account, domain_separator, message_hash).await?
+            }
+            Account::Threshold(account, _) => {
+                Signer::sign_typed_data(account, domain_separator, message_hash).await?
+            }
+            Account::Signer(signer) => {
+                signer
+                    .sign_typed_data(domain_separator, message_hash)
+                    .await?
+            }
+            Account::Local(..) | Account::Locked(..) => return Err(Error::UnsupportedAccount),
+        };
+
+        Ok(signature)
+    }
+
+    /// Returns the members of a struct type.
+    fn members(&self, name: &str) -> Result<&[Member], Error> {
+        self.types
+            .get(name)
+            .map(Vec::as_slice)
+            .ok_or_else(|| Error::UnknownType(name.to_owned()))
+    }
+
+    /// Collects the names of all struct types referenced by a struct type,
+    /// including itself.
+    fn collect_dependencies(&self, name: &str, found: &mut BTreeSet<String>) -> Result<(), Error> {
+        if !found.insert(name.to_owned()) {
+            return Ok(());
+        }
+        for member in self.members(name)? {
+            let base = base_type(&member.kind);
+            if self.types.contains_key(base) {
+                self.collect_dependencies(base, found)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Encodes a value of the specified type into a single 32-byte word.
+    fn encode_value(&self, kind: &str, value: &Token) -> Result<H256, Error> {
+        let invalid_value = || Error::InvalidValue(kind.to_owned());
+
+        if let Some((element, len)) = array_type(kind) {
+            let elements = match value {
+                Token::Array(elements) if len.is_none() => elements,
+                Token::FixedArray(elements) if len.is_none() || len == Some(elements.len()) => {
+                    elements
+                }
+                _ => return Err(invalid_value()),
+            };
+            let mut encoded = Vec::with_capacity(elements.len() * 32);
+            for element_value in elements {
+                encoded.extend_from_slice(self.encode_value(element, element_value)?.as_bytes());
+            }
+            return Ok(H256(hash::keccak256(encoded)));
+        }
+
+        if self.types.contains_key(kind) {
+            return self.hash_struct(kind, value);
+        }
+
+        let param = Reader::read(kind).map_err(|_| Error::UnknownType(kind.to_owned()))?;
+        match (&param, value) {
+            (ParamType::String, Token::String(value)) => Ok(H256(hash::keccak256(value))),
+            (ParamType::Bytes, Token::Bytes(value)) => Ok(H256(hash::keccak256(value))),
+            (ParamType::Address, _)
+            | (ParamType::Bool, _)
+            | (ParamType::FixedBytes(_), _)
+            | (ParamType::Int(_), _)
+            | (ParamType::Uint(_), _)
+                if value.type_check(&param) =>
+            {
+                Ok(H256::from_slice(&abi::encode(std::slice::from_ref(value))))
+            }
+            _ => Err(invalid_value()),
+        }
+    }
+}
+
+/// Computes the EIP-712 hash to be signed from a domain separator and the
+/// struct hash of a message.
+pub fn signing_hash(domain_separator: H256, message_hash: H256) -> H256 {
+    H256(hash::keccak256(
+        [
+            &b"\x19\x01"[..],
+            domain_separator.as_bytes(),
+            message_hash.as_bytes(),
+        ]
+        .concat(),
+    ))
+}
+
+/// Returns the element type and the optional fixed length of an array type,
+/// or `None` if the type is not an array type.
+fn array_type(kind: &str) -> Option<(&str, Option<usize>)> {
+    let kind = kind.strip_suffix(']')?;
+    let (element, len) = kind.rsplit_once('[')?;
+    if len.is_empty() {
+        Some((element, None))
+    } else {
+        Some((element, Some(len.parse().ok()?)))
+    }
+}
+
+/// Returns the base type of a type with any array suffixes removed.
+fn base_type(kind: &str) -> &str {
+    kind.split('[').next().unwrap_or(kind)
+}
+
+/// Error type for when hashing or signing typed data fails.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// A type is neither a defined struct type nor a valid atomic type.
+    #[error("unknown EIP-712 type `{0}`")]
+    UnknownType(String),
+    /// A value does not match its type.
+    #[error("value does not match EIP-712 type `{0}`")]
+    InvalidValue(String),
+    /// The account can't be used to sign typed data.
+    #[error("account does not support signing typed data")]
+    UnsupportedAccount,
+    /// Signing the typed data failed.
+    #[error(transparent)]
+    Signer(#[from] signer::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secret::PrivateKey;
+    use crate::test::prelude::*;
+
+    /// The `Mail` example from the EIP-712 specification.
+    fn mail() -> TypedData {
+        let person = |name: &str, wallet: &str| {
+            Token::Tuple(vec![
+                Token::String(name.into()),
+                Token::Address(wallet.parse().unwrap()),
+            ])
+        };
+        let domain = Domain::new("Ether Mail", "1")
+            .chain_id(1)
+            .verifying_contract(addr!("0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"));
+        let message = Token::Tuple(vec![
+            person("Cow", "CD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"),
+            person("Bob", "bBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB"),
+            Token::String("Hello, Bob!".into()),
+        ]);
+
+        TypedData::new(domain, "Mail", message)
+            .add_type("Person", &[("name", "string"), ("wallet", "address")])
+            .add_type(
+                "Mail",
+                &[("from", "Person"), ("to", "Person"), ("contents", "string")],
+            )
+    }
+
+    #[test]
+    fn encode_mail_type() {
+        let data = mail();
+
+        assert_eq!(
+            data.encode_type("Mail").unwrap(),
+            "Mail(Person from,Person to,string contents)Person(string name,address wallet)"
+        );
+        assert_eq!(
+            data.type_hash("Mail").unwrap(),
+            "a0cedeb2dc280ba39b857546d74f5549c3a1d7bdc2dd96bf881f76108e23dac2"
+                .parse()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn hash_mail() {
+        let data = mail();
+
+        assert_eq!(
+            data.domain.separator(),
+            "f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f"
+                .parse()
+                .unwrap()
+        );
+        assert_eq!(
+            data.message_hash().unwrap(),
+            "c52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e"
+                .parse()
+                .unwrap()
+        );
+        assert_eq!(
+            data.signing_hash().unwrap(),
+            "be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2"
+                .parse()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn sign_mail() {
+        let key = PrivateKey::from_raw(hash::keccak256("cow")).unwrap();
+        let signature = mail()
+            .sign(&Account::Offline(key, None))
+            .immediate()
+            .unwrap();
+
+        assert_eq!(signature.v, 28);
+        assert_eq!(
+            signature.r,
+            "4355c47d63924e8a72e509b65029052eb6c299d53a04e167c5775fd466751c9d"
+                .parse()
+                .unwrap()
+        );
+        assert_eq!(
+            signature.s,
+            "07299936d304c153f6443dfa05f40ff007d72911b6f72307f996231605b91562"
+                .parse()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn sign_with_local_account_is_unsupported() {
+        let result = mail()
+            .sign(&Account::Local(Address::repeat_byte(1), None))
+            .immediate();
+
+        assert!(matches!(result, Err(Error::UnsupportedAccount)));
+    }
+
+    #[test]
+    fn hash_arrays() {
+        let data = TypedData::new(
+            Domain::default(),
+            "Batch",
+            Token::Tuple(vec![
+                Token::Array(vec![Token::Uint(1.into()), Token::Uint(2.into())]),
+                Token::FixedArray(vec![Token::Bytes(vec![1]), Token::Bytes(vec![2])]),
+            ]),
+        )
+        .add_type("Batch", &[("ids", "uint256[]"), ("blobs", "bytes[2]")]);
+
+        let mut encoded = data.type_hash("Batch").unwrap().as_bytes().to_vec();
+        encoded.extend_from_slice(&hash::keccak256(abi::encode(&[
+            Token::Uint(1.into()),
+            Token::Uint(2.into()),
+        ])));
+        encoded.extend_from_slice(&hash::keccak256(
+            [hash::keccak256([1]), hash::keccak256([2])].concat(),
+        ));
+
+        assert_eq!(data.message_hash().unwrap(), H256(hash::keccak256(encoded)));
+    }
+
+    #[test]
+    fn invalid_values() {
+        let data = mail();
+        assert!(matches!(
+            data.hash_struct("Person", &Token::Tuple(vec![Token::Bool(true)])),
+            Err(Error::InvalidValue(_))
+        ));
+        assert!(matches!(
+            data.hash_struct(
+                "Person",
+                &Token::Tuple(vec![Token::Bool(true), Token::Bool(true)])
+            ),
+            Err(Error::InvalidValue(_))
+        ));
+        assert!(matches!(
+            data.hash_struct("Unknown", &Token::Tuple(vec![])),
+            Err(Error::UnknownType(_))
+        ));
+    }
+}
@@ -0,0 +1,175 @@
+//! ERC-165 standard interface detection.
+//!
+//! ERC-165 contracts implement `supportsInterface(bytes4)`, which reports
+//! whether they implement an interface identified by the XOR of the selectors
+//! of its functions. This allows checking that a contract supports optional
+//! functionality before calling methods that would otherwise revert.
+
+use crate::contract::{Instance, MethodBuilder, ViewMethodBuilder};
+use crate::errors::MethodError;
+use ethcontract_common::abi::{Function, Param, ParamType, StateMutability, Token};
+use ethcontract_common::abiext::AbiExt;
+use ethcontract_common::hash::H32;
+use web3::api::Web3;
+use web3::types::{Address, Bytes};
+use web3::Transport;
+
+/// The interface ID of ERC-165 itself, that is the selector of
+/// `supportsInterface(bytes4)`.
+pub const ERC165_INTERFACE_ID: H32 = [0x01, 0xff, 0xc9, 0xa7];
+
+/// An interface ID that ERC-165 contracts must report as unsupported.
+const INVALID_INTERFACE_ID: H32 = [0xff; 4];
+
+/// Checks whether the contract at the specified address supports an
+/// interface, following the detection procedure specified by ERC-165.
+///
+/// Contracts that don't implement ERC-165, including accounts without code,
+/// are reported as not supporting the interface instead of returning an
+/// error.
+pub async fn supports_interface<T: Transport>(
+    web3: &Web3<T>,
+    address: Address,
+    interface_id: H32,
+) -> Result<bool, MethodError> {
+    for (id, expected) in [(ERC165_INTERFACE_ID, true), (INVALID_INTERFACE_ID, false)] {
+        if call_supports_interface(web3, address, id).await? != Some(expected) {
+            return Ok(false);
+        }
+    }
+
+    Ok(call_supports_interface(web3, address, interface_id)
+        .await?
+        .unwrap_or(false))
+}
+
+/// Checks whether a contract instance supports the interface of its ABI, see
+/// [`AbiExt::interface_id`].
+pub async fn probe<T: Transport>(instance: &Instance<T>) -> Result<bool, MethodError> {
+    supports_interface(
+        &instance.web3(),
+        instance.address(),
+        instance.abi().interface_id(),
+    )
+    .await
+}
+
+/// Calls `supportsInterface(bytes4)`, returning `None` if the contract does
+/// not implement it.
+async fn call_supports_interface<T: Transport>(
+    web3: &Web3<T>,
+    address: Address,
+    interface_id: H32,
+) -> Result<Option<bool>, MethodError> {
+    let function = supports_interface_function();
+    let data = function
+        .encode_input(&[Token::FixedBytes(interface_id.to_vec())])
+        .expect("valid supportsInterface input");
+
+    ViewMethodBuilder::from_method(MethodBuilder::new(
+        web3.clone(),
+        function,
+        address,
+        Bytes(data),
+    ))
+    .try_call()
+    .await
+}
+
+/// Returns the ABI of the ERC-165 `supportsInterface(bytes4)` function.
+fn supports_interface_function() -> Function {
+    #[allow(deprecated)]
+    Function {
+        name: "supportsInterface".to_owned(),
+        inputs: vec![Param {
+            name: "interfaceId".to_owned(),
+            kind: ParamType::FixedBytes(4),
+            internal_type: None,
+        }],
+        outputs: vec![Param {
+            name: "".to_owned(),
+            kind: ParamType::Bool,
+            internal_type: None,
+        }],
+        constant: None,
+        state_mutability: StateMutability::View,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::prelude::*;
+    use ethcontract_common::abiext::FunctionExt;
+
+    fn bool_response(value: bool) -> serde_json::Value {
+        json!(Bytes(ethcontract_common::abi::encode(&[Token::Bool(
+            value
+        )])))
+    }
+
+    #[test]
+    fn erc165_interface_id() {
+        assert_eq!(
+            supports_interface_function().selector(),
+            ERC165_INTERFACE_ID
+        );
+    }
+
+    #[test]
+    fn detects_supported_interface() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+        let address = Address::repeat_byte(0x42);
+
+        transport.add_response(bool_response(true));
+        transport.add_response(bool_response(false));
+        transport.add_response(bool_response(true));
+        let supported = supports_interface(&web3, address, [1, 2, 3, 4])
+            .immediate()
+            .unwrap();
+
+        assert!(supported);
+        for id in ["0x01ffc9a7", "0xffffffff", "0x01020304"] {
+            let function = supports_interface_function();
+            let data = function
+                .encode_input(&[Token::FixedBytes(hex::decode(&id[2..]).unwrap())])
+                .unwrap();
+            transport.assert_request(
+                "eth_call",
+                &[
+                    json!({ "to": address, "data": Bytes(data) }),
+                    json!("latest"),
+                ],
+            );
+        }
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn contracts_without_erc165_support_nothing() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        transport.add_response(json!("0x"));
+        let supported = supports_interface(&web3, Address::repeat_byte(0x42), [1, 2, 3, 4])
+            .immediate()
+            .unwrap();
+
+        assert!(!supported);
+    }
+
+    #[test]
+    fn contracts_that_support_everything_support_nothing() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        transport.add_response(bool_response(true));
+        transport.add_response(bool_response(true));
+        let supported = supports_interface(&web3, Address::repeat_byte(0x42), [1, 2, 3, 4])
+            .immediate()
+            .unwrap();
+
+        assert!(!supported);
+    }
+}
@@ -1,5 +1,13 @@
 //! Module with common error types.
-
+//!
+//! All error types share a set of stable [`ErrorCode`]s for classifying
+//! errors, and expose helpers such as `is_revert` and `is_network_error` for
+//! the most common checks. The JSON RPC error returned by the node, when it
+//! could not be decoded into a more specific error, is available through
+//! [`ExecutionError::rpc_error`].
+
+mod code;
+mod custom;
 mod ganache;
 mod geth;
 mod hardhat;
@@ -7,14 +15,21 @@ mod nethermind;
 mod parity;
 pub(crate) mod revert;
 
+pub use self::code::ErrorCode;
+pub(crate) use self::custom::find_error;
+pub use self::custom::{CustomError, DecodeCustomError};
 use crate::transaction::TransactionResult;
-use ethcontract_common::abi::{Error as AbiError, Event, Function};
+use ethcontract_common::abi::{self, Error as AbiError, Event, Function, Token};
 use ethcontract_common::abiext::EventExt;
 pub use ethcontract_common::errors::*;
+use ethcontract_common::hash;
+use ethcontract_common::Abi;
+pub use ethcontract_core::errors::*;
+use jsonrpc_core::Error as JsonrpcError;
 use secp256k1::Error as Secp256k1Error;
+use serde_json::Value;
 use std::num::ParseIntError;
 use thiserror::Error;
-use uint::FromDecStrErr;
 use web3::error::Error as Web3Error;
 use web3::types::{Log, TransactionReceipt, H256};
 
@@ -53,11 +68,45 @@ pub enum DeployError {
     Pending(H256),
 }
 
+impl DeployError {
+    /// Returns the machine-readable code for this error.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            DeployError::Web3(err) => web3_error_code(err),
+            DeployError::NotFound(_) => ErrorCode::NotDeployed,
+            DeployError::Link(_) => ErrorCode::Link,
+            DeployError::EmptyBytecode => ErrorCode::EmptyBytecode,
+            DeployError::Abi(_) => ErrorCode::Abi,
+            DeployError::Tx(err) => err.code(),
+            DeployError::Pending(_) => ErrorCode::PendingTransaction,
+        }
+    }
+
+    /// Returns the execution error of the deployment transaction, if this
+    /// error was caused by one.
+    pub fn execution_error(&self) -> Option<&ExecutionError> {
+        match self {
+            DeployError::Tx(err) => Some(err),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the deployment transaction reverted.
+    pub fn is_revert(&self) -> bool {
+        self.code() == ErrorCode::Revert
+    }
+
+    /// Returns `true` if the error was caused by failing to reach the node.
+    pub fn is_network_error(&self) -> bool {
+        self.code() == ErrorCode::Network
+    }
+}
+
 /// Error that can occur while executing a contract call or transaction.
 #[derive(Debug, Error)]
 pub enum ExecutionError {
     /// An error occured while performing a web3 call.
-    #[error("web3 error: {0}")]
+    #[error(transparent)]
     Web3(Web3Error),
 
     /// An error occured while ABI decoding the result of a contract method
@@ -78,6 +127,10 @@ pub enum ExecutionError {
     #[error("contract call reverted with message: {0:?}")]
     Revert(Option<String>),
 
+    /// A contract call reverted with a Solidity custom error.
+    #[error("contract call reverted with custom error: {0}")]
+    CustomError(Box<CustomError>),
+
     /// A contract call executed an invalid opcode.
     #[error("contract call executed an invalid opcode")]
     InvalidOpcode,
@@ -114,11 +167,41 @@ pub enum ExecutionError {
     /// Unexpected transaction hash
     #[error("transaction hash returned from node when sending raw transaction does not match expected hash")]
     UnexpectedTransactionHash,
+
+    /// Invalid parameters for a blob transaction.
+    #[error("invalid blob transaction: {0}")]
+    Blob(#[from] crate::transaction::BlobError),
+
+    /// The calldata of a transaction exceeds the configured maximum size.
+    #[error("transaction calldata of {0} bytes exceeds the maximum of {1} bytes")]
+    CalldataTooLarge(usize, usize),
+
+    /// The transaction hash can't be computed before sending because the
+    /// transaction is signed by the node.
+    #[error("transaction hash can not be precomputed for transactions signed by the node")]
+    NodeSignedTransaction,
+
+    /// A view method call with state overrides was added to a multicall. The
+    /// calls of a multicall are executed in a single `eth_call`, so state
+    /// overrides can't be applied to individual calls.
+    #[error("state overrides are not supported for calls aggregated in a multicall")]
+    UnsupportedStateOverrides,
+
+    /// A signer failed to sign a transaction or message.
+    #[error(transparent)]
+    Signer(crate::transaction::signer::Error),
 }
 
 impl From<Web3Error> for ExecutionError {
     fn from(err: Web3Error) -> Self {
         if let Web3Error::Rpc(jsonrpc_err) = &err {
+            if jsonrpc_err.message.contains("revert") {
+                if let Some(err) =
+                    rpc_error_data(jsonrpc_err).and_then(CustomError::from_revert_data)
+                {
+                    return ExecutionError::CustomError(Box::new(err));
+                }
+            }
             if let Some(err) = ganache::get_encoded_error(jsonrpc_err) {
                 return err;
             }
@@ -140,6 +223,134 @@ impl From<Web3Error> for ExecutionError {
     }
 }
 
+impl ExecutionError {
+    /// Returns the machine-readable code for this error.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            ExecutionError::Web3(err) => web3_error_code(err),
+            ExecutionError::AbiDecode(_) => ErrorCode::Abi,
+            ExecutionError::Parse(_) => ErrorCode::Parse,
+            ExecutionError::NoLocalAccounts => ErrorCode::NoLocalAccounts,
+            ExecutionError::Revert(_) => ErrorCode::Revert,
+            ExecutionError::CustomError(_) => ErrorCode::Revert,
+            ExecutionError::InvalidOpcode => ErrorCode::InvalidOpcode,
+            ExecutionError::ConfirmTimeout(_) => ErrorCode::ConfirmTimeout,
+            ExecutionError::Failure(_) => ErrorCode::TransactionFailure,
+            ExecutionError::MissingTransaction(_) => ErrorCode::MissingTransaction,
+            ExecutionError::PendingTransaction(_) => ErrorCode::PendingTransaction,
+            ExecutionError::RemovedLog(_) => ErrorCode::RemovedLog,
+            ExecutionError::StreamEndedUnexpectedly => ErrorCode::StreamEnded,
+            ExecutionError::Tokenization(_) => ErrorCode::Tokenization,
+            ExecutionError::UnexpectedTransactionHash => ErrorCode::UnexpectedTransactionHash,
+            ExecutionError::Blob(_) => ErrorCode::InvalidBlobTransaction,
+            ExecutionError::CalldataTooLarge(_, _) => ErrorCode::CalldataTooLarge,
+            ExecutionError::NodeSignedTransaction => ErrorCode::NodeSignedTransaction,
+            ExecutionError::UnsupportedStateOverrides => ErrorCode::UnsupportedStateOverrides,
+            ExecutionError::Signer(_) => ErrorCode::Signer,
+        }
+    }
+
+    /// Returns `true` if a contract call reverted.
+    ///
+    /// Note that this does not include mined transactions that failed, see
+    /// [`ExecutionError::Failure`].
+    pub fn is_revert(&self) -> bool {
+        self.code() == ErrorCode::Revert
+    }
+
+    /// Returns `true` if the error was caused by failing to reach the node.
+    pub fn is_network_error(&self) -> bool {
+        self.code() == ErrorCode::Network
+    }
+
+    /// Creates an error for a contract call that reverted with the specified
+    /// ABI encoded revert data, such as a call that failed in a multicall.
+    pub fn from_revert_data(data: Vec<u8>) -> Self {
+        if let Some(reason) = revert::decode_reason(&data) {
+            return ExecutionError::Revert(Some(reason));
+        }
+        match CustomError::from_revert_data(data) {
+            Some(err) => ExecutionError::CustomError(Box::new(err)),
+            None => ExecutionError::Revert(None),
+        }
+    }
+
+    /// Returns the revert reason string, if the contract call reverted with
+    /// one.
+    pub fn revert_reason(&self) -> Option<&str> {
+        match self {
+            ExecutionError::Revert(reason) => reason.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Returns the ABI encoded revert data of a reverted contract call, if
+    /// it is known.
+    ///
+    /// For reverts with a reason string, this is the `Error(string)` encoded
+    /// reason. Otherwise, the data is taken from the JSON RPC error returned
+    /// by the node.
+    pub fn revert_data(&self) -> Option<Vec<u8>> {
+        match self {
+            ExecutionError::Revert(Some(reason)) => {
+                let mut data = hash::function_selector("Error(string)").to_vec();
+                data.extend(abi::encode(&[Token::String(reason.clone())]));
+                Some(data)
+            }
+            ExecutionError::CustomError(err) => Some(err.data.clone()),
+            ExecutionError::Web3(Web3Error::Rpc(err)) => rpc_error_data(err),
+            _ => None,
+        }
+    }
+
+    /// Returns the custom error of a reverted contract call, if it reverted
+    /// with one.
+    pub fn custom_error(&self) -> Option<&CustomError> {
+        match self {
+            ExecutionError::CustomError(err) => Some(err),
+            _ => None,
+        }
+    }
+
+    /// Decodes custom errors with the errors defined in a contract ABI.
+    pub(crate) fn resolve_custom_error(mut self, abi: &Abi) -> Self {
+        if let ExecutionError::CustomError(err) = &mut self {
+            err.resolve(abi);
+        }
+        self
+    }
+
+    /// Returns the JSON RPC error returned by the node, if this error was
+    /// caused by one that could not be decoded into a more specific error.
+    pub fn rpc_error(&self) -> Option<&JsonrpcError> {
+        match self {
+            ExecutionError::Web3(Web3Error::Rpc(err)) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// Returns the error code for a web3 error.
+fn web3_error_code(err: &Web3Error) -> ErrorCode {
+    match err {
+        Web3Error::Unreachable | Web3Error::Transport(_) | Web3Error::Io(_) => ErrorCode::Network,
+        Web3Error::Rpc(_) => ErrorCode::Rpc,
+        Web3Error::Decoder(_) | Web3Error::InvalidResponse(_) => ErrorCode::InvalidResponse,
+        _ => ErrorCode::Web3,
+    }
+}
+
+/// Extracts hex encoded data from a JSON RPC error. Nodes either return the
+/// data directly, or nested in an object.
+fn rpc_error_data(err: &JsonrpcError) -> Option<Vec<u8>> {
+    let data = match err.data.as_ref()? {
+        Value::String(data) => data,
+        Value::Object(object) => object.get("data")?.as_str()?,
+        _ => return None,
+    };
+    hex::decode(data.strip_prefix("0x")?).ok()
+}
+
 /// Error that can occur while executing a contract call or transaction.
 #[derive(Debug, Error)]
 #[error("method '{signature}' failure: {inner}")]
@@ -163,6 +374,38 @@ impl MethodError {
     pub fn from_parts(signature: String, inner: ExecutionError) -> Self {
         MethodError { signature, inner }
     }
+
+    /// Returns the machine-readable code for this error.
+    pub fn code(&self) -> ErrorCode {
+        self.inner.code()
+    }
+
+    /// Returns `true` if the method call reverted.
+    pub fn is_revert(&self) -> bool {
+        self.inner.is_revert()
+    }
+
+    /// Returns `true` if the error was caused by failing to reach the node.
+    pub fn is_network_error(&self) -> bool {
+        self.inner.is_network_error()
+    }
+
+    /// Returns the ABI encoded revert data of the method call, if it is
+    /// known. See [`ExecutionError::revert_data`] for more details.
+    pub fn revert_data(&self) -> Option<Vec<u8>> {
+        self.inner.revert_data()
+    }
+
+    /// Returns the custom error of the method call, if it reverted with one.
+    pub fn custom_error(&self) -> Option<&CustomError> {
+        self.inner.custom_error()
+    }
+
+    /// Decodes the custom error of the method call into a typed error, such
+    /// as the `ContractError` enum generated for contracts with custom errors.
+    pub fn decode_custom_error<E: DecodeCustomError>(&self) -> Option<E> {
+        self.custom_error()?.decode()
+    }
 }
 
 /// Error that can occur while streaming contract events.
@@ -188,6 +431,16 @@ impl EventError {
     pub fn from_parts(signature: String, inner: ExecutionError) -> Self {
         EventError { signature, inner }
     }
+
+    /// Returns the machine-readable code for this error.
+    pub fn code(&self) -> ErrorCode {
+        self.inner.code()
+    }
+
+    /// Returns `true` if the error was caused by failing to reach the node.
+    pub fn is_network_error(&self) -> bool {
+        self.inner.is_network_error()
+    }
 }
 
 /// An error indicating an invalid private key. Private keys for secp256k1 must
@@ -211,37 +464,16 @@ impl From<Secp256k1Error> for InvalidPrivateKey {
     }
 }
 
-/// The error type that is returned when conversion to or from a 256-bit integer
-/// fails.
-#[derive(Clone, Copy, Debug, Error)]
-#[error("output of range integer conversion attempted")]
-pub struct TryFromBigIntError;
-
-/// The error type that is returned when parsing a 256-bit signed integer.
-#[derive(Clone, Copy, Debug, Error)]
-pub enum ParseI256Error {
-    /// Error that occurs when an invalid digit is encountered while parsing.
-    #[error("invalid digit found in string")]
-    InvalidDigit,
-
-    /// Error that occurs when the number is too large or too small (negative)
-    /// and does not fit in a 256-bit signed integer.
-    #[error("number does not fit in 256-bit integer")]
-    IntegerOverflow,
-}
-
-impl From<FromDecStrErr> for ParseI256Error {
-    fn from(err: FromDecStrErr) -> Self {
-        match err {
-            FromDecStrErr::InvalidCharacter => ParseI256Error::InvalidDigit,
-            FromDecStrErr::InvalidLength => ParseI256Error::IntegerOverflow,
-        }
-    }
-}
+/// An error parsing a contract method or event from a string that is not the
+/// canonical ABI signature of any of the contract's methods or events.
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+#[error("unknown signature `{0}`")]
+pub struct UnknownSignature(pub String);
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serde_json::json;
     use std::error::Error;
 
     #[test]
@@ -268,6 +500,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn error_codes() {
+        let err = ExecutionError::from(Web3Error::Rpc(ganache::rpc_error("revert", None)));
+        assert_eq!(err.code(), ErrorCode::Revert);
+        assert!(err.is_revert());
+
+        let err = DeployError::Tx(ExecutionError::Web3(Web3Error::Unreachable));
+        assert_eq!(err.code().as_str(), "network");
+        assert!(err.is_network_error());
+        assert!(!err.is_revert());
+    }
+
+    #[test]
+    fn revert_data() {
+        let reason = ExecutionError::Revert(Some("reason".to_owned()));
+        assert_eq!(reason.revert_data(), Some(revert::encode_reason("reason")),);
+        assert_eq!(reason.revert_reason(), Some("reason"));
+
+        let err = ExecutionError::from(Web3Error::Rpc(JsonrpcError {
+            code: 3.into(),
+            message: "custom error".to_owned(),
+            data: Some(json!({ "data": "0x01020304" })),
+        }));
+        assert_eq!(err.code(), ErrorCode::Rpc);
+        assert_eq!(err.revert_data(), Some(vec![1, 2, 3, 4]));
+        assert_eq!(err.rpc_error().unwrap().message, "custom error");
+        assert_eq!(
+            err.source().unwrap().to_string(),
+            err.rpc_error().unwrap().to_string(),
+        );
+    }
+
     #[test]
     fn all_errors_are_boxable_errors() {
         fn assert_boxable_error<T: Error + Send + Sync + 'static>() {}
@@ -0,0 +1,108 @@
+//! Stable machine-readable error codes shared by all error types.
+
+use std::fmt::{self, Display, Formatter};
+
+/// A stable, machine-readable code identifying the kind of an error.
+///
+/// Codes are shared by [`ExecutionError`](super::ExecutionError),
+/// [`MethodError`](super::MethodError), [`EventError`](super::EventError) and
+/// [`DeployError`](super::DeployError), so that errors can be classified the
+/// same way regardless of which operation produced them. The string
+/// representation of a code never changes and is suitable for use in logs
+/// and metrics.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum ErrorCode {
+    /// The node could not be reached, for example because of a connection
+    /// or I/O error.
+    Network,
+    /// The node responded with a JSON RPC error.
+    Rpc,
+    /// The node responded with an invalid response.
+    InvalidResponse,
+    /// Any other error in the web3 transport.
+    Web3,
+    /// A contract call reverted.
+    Revert,
+    /// A contract call executed an invalid opcode.
+    InvalidOpcode,
+    /// A transaction was mined but failed.
+    TransactionFailure,
+    /// A transaction failed to confirm in time.
+    ConfirmTimeout,
+    /// A transaction could not be found.
+    MissingTransaction,
+    /// A transaction is pending and not yet part of a block.
+    PendingTransaction,
+    /// The hash of a sent transaction did not match the expected hash.
+    UnexpectedTransactionHash,
+    /// No local accounts were available for signing.
+    NoLocalAccounts,
+    /// An error ABI encoding or decoding data.
+    Abi,
+    /// An error converting between ABI tokens and Rust types.
+    Tokenization,
+    /// An error parsing a value returned by the node.
+    Parse,
+    /// A removed log was received when querying past logs.
+    RemovedLog,
+    /// A log stream ended unexpectedly.
+    StreamEnded,
+    /// No deployed contract could be found for the network.
+    NotDeployed,
+    /// A contract could not be linked with a library.
+    Link,
+    /// Attempted to deploy a contract with empty bytecode.
+    EmptyBytecode,
+    /// A blob transaction had invalid parameters.
+    InvalidBlobTransaction,
+    /// A transaction's calldata exceeded the configured maximum size.
+    CalldataTooLarge,
+    /// A transaction hash was requested before sending a transaction that is
+    /// signed by the node.
+    NodeSignedTransaction,
+    /// A call with state overrides was aggregated with a multicall, which
+    /// can't apply overrides to individual calls.
+    UnsupportedStateOverrides,
+    /// A signer failed to sign a transaction or message.
+    Signer,
+}
+
+impl ErrorCode {
+    /// Returns the stable string representation of the error code.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::Network => "network",
+            ErrorCode::Rpc => "rpc",
+            ErrorCode::InvalidResponse => "invalid_response",
+            ErrorCode::Web3 => "web3",
+            ErrorCode::Revert => "revert",
+            ErrorCode::InvalidOpcode => "invalid_opcode",
+            ErrorCode::TransactionFailure => "transaction_failure",
+            ErrorCode::ConfirmTimeout => "confirm_timeout",
+            ErrorCode::MissingTransaction => "missing_transaction",
+            ErrorCode::PendingTransaction => "pending_transaction",
+            ErrorCode::UnexpectedTransactionHash => "unexpected_transaction_hash",
+            ErrorCode::NoLocalAccounts => "no_local_accounts",
+            ErrorCode::Abi => "abi",
+            ErrorCode::Tokenization => "tokenization",
+            ErrorCode::Parse => "parse",
+            ErrorCode::RemovedLog => "removed_log",
+            ErrorCode::StreamEnded => "stream_ended",
+            ErrorCode::NotDeployed => "not_deployed",
+            ErrorCode::Link => "link",
+            ErrorCode::EmptyBytecode => "empty_bytecode",
+            ErrorCode::InvalidBlobTransaction => "invalid_blob_transaction",
+            ErrorCode::CalldataTooLarge => "calldata_too_large",
+            ErrorCode::NodeSignedTransaction => "node_signed_transaction",
+            ErrorCode::UnsupportedStateOverrides => "unsupported_state_overrides",
+            ErrorCode::Signer => "signer",
+        }
+    }
+}
+
+impl Display for ErrorCode {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
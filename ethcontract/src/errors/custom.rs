@@ -0,0 +1,156 @@
+//! Module implements decoding Solidity custom errors from revert data.
+
+use ethcontract_common::abi::{AbiError as AbiErrorType, Token};
+use ethcontract_common::hash::{self, H32};
+use ethcontract_common::Abi;
+use lazy_static::lazy_static;
+use std::fmt::{self, Display, Formatter};
+
+lazy_static! {
+    /// The ABI function selector for identifying encoded revert reasons.
+    static ref ERROR_SELECTOR: H32 = hash::function_selector("Error(string)");
+
+    /// The ABI function selector for identifying encoded Solidity panics.
+    static ref PANIC_SELECTOR: H32 = hash::function_selector("Panic(uint256)");
+}
+
+/// A contract call that reverted with a Solidity custom error, for example
+/// `revert InsufficientBalance(available, required)`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CustomError {
+    /// The ABI encoded revert data, starting with the 4-byte error selector.
+    pub data: Vec<u8>,
+    /// The name and parameters of the error, if it was decoded with the ABI
+    /// of the contract that was called.
+    pub decoded: Option<(String, Vec<Token>)>,
+}
+
+impl CustomError {
+    /// Creates a custom error from ABI encoded revert data. Returns `None`
+    /// if the data does not represent a custom error, i.e. it is too short to
+    /// contain a selector or is an encoded revert reason or panic.
+    pub fn from_revert_data(data: Vec<u8>) -> Option<Self> {
+        let selector = data.get(..4)?;
+        if selector == &ERROR_SELECTOR[..] || selector == &PANIC_SELECTOR[..] {
+            return None;
+        }
+
+        Some(CustomError {
+            data,
+            decoded: None,
+        })
+    }
+
+    /// Returns the 4-byte selector identifying the error.
+    pub fn selector(&self) -> H32 {
+        let mut selector = H32::default();
+        selector.copy_from_slice(&self.data[..4]);
+        selector
+    }
+
+    /// Returns the name of the error, if it was decoded.
+    pub fn name(&self) -> Option<&str> {
+        self.decoded.as_ref().map(|(name, _)| name.as_str())
+    }
+
+    /// Decodes the error into a typed error, such as the `ContractError` enum
+    /// generated for contracts with custom errors.
+    pub fn decode<E: DecodeCustomError>(&self) -> Option<E> {
+        E::decode_custom_error(self)
+    }
+
+    /// Decodes the error parameters with the errors defined in a contract ABI.
+    /// This does nothing if the error is not part of the ABI.
+    pub(crate) fn resolve(&mut self, abi: &Abi) {
+        if self.decoded.is_some() {
+            return;
+        }
+        if let Some(error) = find_error(abi, self.selector()) {
+            if let Ok(params) = error.decode(&self.data[4..]) {
+                self.decoded = Some((error.name.clone(), params));
+            }
+        }
+    }
+}
+
+impl Display for CustomError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match &self.decoded {
+            Some((name, params)) => {
+                write!(f, "{}(", name)?;
+                for (i, param) in params.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}", param)?;
+                }
+                f.write_str(")")
+            }
+            None => write!(f, "unknown error 0x{}", hex::encode(self.selector())),
+        }
+    }
+}
+
+/// A typed representation of the custom errors of a contract that can be
+/// decoded from a [`CustomError`].
+pub trait DecodeCustomError: Sized {
+    /// Decodes a custom error, returning `None` if it is not one of the errors
+    /// represented by this type.
+    fn decode_custom_error(error: &CustomError) -> Option<Self>;
+}
+
+/// Finds the error with the specified selector in a contract ABI.
+pub(crate) fn find_error(abi: &Abi, selector: H32) -> Option<&AbiErrorType> {
+    abi.errors()
+        .find(|error| error.signature()[..4] == selector[..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethcontract_common::abi::{Param, ParamType};
+    use web3::types::U256;
+
+    fn abi() -> Abi {
+        let error = AbiErrorType {
+            name: "InsufficientBalance".into(),
+            inputs: vec![
+                Param {
+                    name: "available".into(),
+                    kind: ParamType::Uint(256),
+                    internal_type: None,
+                },
+                Param {
+                    name: "required".into(),
+                    kind: ParamType::Uint(256),
+                    internal_type: None,
+                },
+            ],
+        };
+        let mut abi = Abi::default();
+        abi.errors.insert(error.name.clone(), vec![error]);
+        abi
+    }
+
+    #[test]
+    fn decode_custom_error() {
+        let abi = abi();
+        let data = abi.errors["InsufficientBalance"][0]
+            .encode(&[Token::Uint(1.into()), Token::Uint(U256::from(2))])
+            .unwrap();
+
+        let mut error = CustomError::from_revert_data(data).unwrap();
+        assert_eq!(error.to_string(), "unknown error 0xcf479181");
+
+        error.resolve(&abi);
+        assert_eq!(error.name(), Some("InsufficientBalance"));
+        assert_eq!(error.to_string(), "InsufficientBalance(1, 2)");
+    }
+
+    #[test]
+    fn revert_reasons_are_not_custom_errors() {
+        let reason = crate::errors::revert::encode_reason("reason");
+        assert!(CustomError::from_revert_data(reason).is_none());
+        assert!(CustomError::from_revert_data(vec![1, 2, 3]).is_none());
+    }
+}
@@ -0,0 +1,251 @@
+//! Lookup of contract deployment information from block explorers.
+//!
+//! Contract artifacts often lack deployment information, which makes
+//! paginated event queries start from the genesis block. This module provides
+//! an [`Explorer`] client that retrieves the contract creation transaction
+//! from an Etherscan compatible API instead.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! use ethcontract::explorer::Explorer;
+//! use ethcontract::prelude::*;
+//! use ethcontract::Instance;
+//!
+//! let web3 = Web3::new(Http::new("http://localhost:8545")?);
+//! let address: Address = "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2".parse()?;
+//!
+//! let explorer = Explorer::etherscan(1).with_api_key("MY_API_KEY");
+//! let deployment = explorer.deployment_information(address).await?;
+//! # let interface = Default::default();
+//! let instance = Instance::with_deployment_info(web3, interface, address, Some(deployment));
+//! # Ok(())
+//! # }
+//! ```
+
+use ethcontract_common::DeploymentInformation;
+use serde::Deserialize;
+use serde_json::Value;
+use thiserror::Error;
+use web3::types::{Address, H256};
+
+/// The base URL of the unified Etherscan API, which supports all chains
+/// indexed by Etherscan and its sister explorers.
+pub const ETHERSCAN_API_URL: &str = "https://api.etherscan.io/v2/api";
+
+/// A client for an Etherscan compatible block explorer API.
+#[derive(Clone, Debug)]
+pub struct Explorer {
+    client: reqwest::Client,
+    url: String,
+    chain_id: Option<u64>,
+    api_key: Option<String>,
+}
+
+impl Explorer {
+    /// Creates a client for an Etherscan compatible API at the specified URL,
+    /// for example a Blockscout instance.
+    pub fn new(url: impl Into<String>) -> Self {
+        Explorer {
+            client: reqwest::Client::new(),
+            url: url.into(),
+            chain_id: None,
+            api_key: None,
+        }
+    }
+
+    /// Creates a client for the Etherscan API for the specified chain.
+    ///
+    /// Note that Etherscan rate-limits requests without an API key, use
+    /// [`with_api_key`](Self::with_api_key) to specify one.
+    pub fn etherscan(chain_id: u64) -> Self {
+        Explorer {
+            chain_id: Some(chain_id),
+            ..Explorer::new(ETHERSCAN_API_URL)
+        }
+    }
+
+    /// Sets the API key used for requests.
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// Sets the HTTP client used for requests.
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Retrieves the deployment information for the contract at the specified
+    /// address.
+    ///
+    /// This returns the block number of the deployment if the explorer
+    /// reports it and the hash of the creation transaction otherwise.
+    pub async fn deployment_information(
+        &self,
+        address: Address,
+    ) -> Result<DeploymentInformation, Error> {
+        let mut query = vec![
+            ("module", "contract".to_string()),
+            ("action", "getcontractcreation".to_string()),
+            ("contractaddresses", format!("{:?}", address)),
+        ];
+        if let Some(chain_id) = self.chain_id {
+            query.push(("chainid", chain_id.to_string()));
+        }
+        if let Some(api_key) = &self.api_key {
+            query.push(("apikey", api_key.clone()));
+        }
+
+        let body = self
+            .client
+            .get(&self.url)
+            .query(&query)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+
+        parse_contract_creation(&body, address)
+    }
+}
+
+/// An error retrieving deployment information from a block explorer.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// An error occurred performing the HTTP request.
+    #[error("explorer request failed: {0}")]
+    Http(#[from] reqwest::Error),
+
+    /// The explorer API returned an error.
+    #[error("explorer API error: {0}")]
+    Api(String),
+
+    /// The explorer API returned a response that could not be parsed.
+    #[error("invalid explorer response: {0}")]
+    InvalidResponse(#[from] serde_json::Error),
+
+    /// The explorer does not know the creation of the contract, for example
+    /// because the address is not a contract.
+    #[error("no contract creation found for {0:?}")]
+    NotFound(Address),
+}
+
+/// Response of the `getcontractcreation` API.
+#[derive(Deserialize)]
+struct Response {
+    status: String,
+    message: String,
+    result: Value,
+}
+
+/// A contract creation entry.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ContractCreation {
+    contract_address: Address,
+    tx_hash: H256,
+    #[serde(default)]
+    block_number: Option<String>,
+}
+
+/// Parses a `getcontractcreation` response body into deployment information.
+fn parse_contract_creation(body: &str, address: Address) -> Result<DeploymentInformation, Error> {
+    let response = serde_json::from_str::<Response>(body)?;
+    if response.status != "1" {
+        return match response.result {
+            Value::Null => Err(Error::NotFound(address)),
+            Value::String(message) if !message.is_empty() => Err(Error::Api(message)),
+            _ if response.message.starts_with("No data found") => Err(Error::NotFound(address)),
+            _ => Err(Error::Api(response.message)),
+        };
+    }
+
+    let creations = serde_json::from_value::<Vec<ContractCreation>>(response.result)?;
+    let creation = creations
+        .into_iter()
+        .find(|creation| creation.contract_address == address)
+        .ok_or(Error::NotFound(address))?;
+
+    let block_number = creation.block_number.and_then(|number| number.parse().ok());
+    Ok(match block_number {
+        Some(block_number) => DeploymentInformation::BlockNumber(block_number),
+        None => DeploymentInformation::TransactionHash(creation.tx_hash),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn address() -> Address {
+        "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2"
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn parse_creation_transaction() {
+        let tx_hash = H256::repeat_byte(0x42);
+        let body = json!({
+            "status": "1",
+            "message": "OK",
+            "result": [{
+                "contractAddress": address(),
+                "contractCreator": Address::repeat_byte(1),
+                "txHash": tx_hash,
+            }],
+        });
+
+        assert_eq!(
+            parse_contract_creation(&body.to_string(), address()).unwrap(),
+            DeploymentInformation::TransactionHash(tx_hash),
+        );
+    }
+
+    #[test]
+    fn parse_creation_block() {
+        let body = json!({
+            "status": "1",
+            "message": "OK",
+            "result": [{
+                "contractAddress": address(),
+                "contractCreator": Address::repeat_byte(1),
+                "txHash": H256::repeat_byte(0x42),
+                "blockNumber": "4719568",
+            }],
+        });
+
+        assert_eq!(
+            parse_contract_creation(&body.to_string(), address()).unwrap(),
+            DeploymentInformation::BlockNumber(4_719_568),
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        let not_found = json!({
+            "status": "0",
+            "message": "No data found",
+            "result": null,
+        });
+        assert!(matches!(
+            parse_contract_creation(&not_found.to_string(), address()),
+            Err(Error::NotFound(a)) if a == address(),
+        ));
+
+        let rate_limited = json!({
+            "status": "0",
+            "message": "NOTOK",
+            "result": "Max rate limit reached",
+        });
+        assert!(matches!(
+            parse_contract_creation(&rate_limited.to_string(), address()),
+            Err(Error::Api(message)) if message == "Max rate limit reached",
+        ));
+    }
+}
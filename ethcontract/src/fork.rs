@@ -0,0 +1,366 @@
+//! Detection of deep chain reorganizations and chain swaps for long-lived
+//! services such as indexers.
+//!
+//! A [`ForkWatcher`] periodically samples block hashes at fixed depths below
+//! the chain head and compares them with the hashes sampled in the previous
+//! check. A changed hash means that a reorganization replaced a block that
+//! was already buried under at least the smallest sampled depth, at which
+//! point indexed data derived from it is likely stale. Changes of the chain
+//! ID, for example when an RPC endpoint is pointed at a different network,
+//! are detected as well.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # async fn run(web3: ethcontract::web3::Web3<ethcontract::dyns::DynTransport>) {
+//! use ethcontract::fork::{ForkEvent, ForkWatcher};
+//! use futures::StreamExt;
+//!
+//! let mut events = Box::pin(ForkWatcher::new(web3).depths(vec![16, 64]).stream());
+//! while let Some(event) = events.next().await {
+//!     match event {
+//!         Ok(ForkEvent::Reorg { block_number, .. }) => {
+//!             println!("resyncing from block {}", block_number);
+//!         }
+//!         Ok(ForkEvent::ChainChanged { .. }) => println!("resyncing from scratch"),
+//!         Err(err) => println!("failed to check for forks: {}", err),
+//!     }
+//! }
+//! # }
+//! ```
+
+use crate::errors::ExecutionError;
+use crate::transaction::confirm;
+use futures::stream::{self, Stream};
+use std::collections::BTreeMap;
+use std::time::Duration;
+use web3::api::Web3;
+use web3::types::{BlockId, BlockNumber, H256, U256};
+use web3::Transport;
+
+/// The default depths below the chain head at which block hashes are sampled.
+pub const DEFAULT_DEPTHS: [u64; 2] = [16, 64];
+
+/// The default poll interval for checking for forks.
+#[cfg(not(test))]
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(30);
+/// The default poll interval to be used in tests.
+#[cfg(test)]
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(0);
+
+/// A change of the chain detected by a [`ForkWatcher`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ForkEvent {
+    /// A previously sampled block was replaced by a chain reorganization.
+    Reorg {
+        /// The number of the replaced block. This is the deepest replaced
+        /// block among the sampled ones, so data derived from blocks at or
+        /// after this number should be considered stale.
+        block_number: u64,
+        /// The depth of the replaced block below the current chain head.
+        depth: u64,
+        /// The previously sampled hash of the block.
+        previous_hash: H256,
+        /// The current hash of the block, or `None` if the chain is no longer
+        /// as long as the block number.
+        current_hash: Option<H256>,
+    },
+    /// The node reports a different chain ID than in the previous check.
+    ChainChanged {
+        /// The previously reported chain ID.
+        previous_chain_id: U256,
+        /// The currently reported chain ID.
+        chain_id: U256,
+    },
+}
+
+/// Watches the chain for deep reorganizations and chain swaps by sampling
+/// block hashes at fixed depths below the chain head.
+///
+/// Reorganizations that only replace blocks shallower than the smallest
+/// sampled depth are part of normal chain operation and are not reported.
+#[derive(Debug)]
+#[must_use = "fork watchers do nothing unless you check or stream them"]
+pub struct ForkWatcher<T: Transport> {
+    web3: Web3<T>,
+    depths: Vec<u64>,
+    poll_interval: Duration,
+    chain_id: Option<U256>,
+    checkpoints: BTreeMap<u64, H256>,
+}
+
+impl<T: Transport> ForkWatcher<T> {
+    /// Creates a new fork watcher with default settings.
+    pub fn new(web3: Web3<T>) -> Self {
+        ForkWatcher {
+            web3,
+            depths: DEFAULT_DEPTHS.to_vec(),
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            chain_id: None,
+            checkpoints: BTreeMap::new(),
+        }
+    }
+
+    /// Sets the depths below the chain head at which block hashes are
+    /// sampled. If not specified, [`DEFAULT_DEPTHS`] are used.
+    pub fn depths(mut self, value: Vec<u64>) -> Self {
+        self.depths = value;
+        self
+    }
+
+    /// Sets the interval between checks when streaming fork events. If not
+    /// specified, [`DEFAULT_POLL_INTERVAL`] is used.
+    pub fn poll_interval(mut self, value: Duration) -> Self {
+        self.poll_interval = value;
+        self
+    }
+
+    /// Samples block hashes and compares them with the ones sampled in the
+    /// previous check, returning the detected change of the chain if any.
+    ///
+    /// The first check only records samples. After a change is detected,
+    /// the samples are reset, so each change is only reported once.
+    pub async fn check(&mut self) -> Result<Option<ForkEvent>, ExecutionError> {
+        let mut event = None;
+
+        let chain_id = self.web3.eth().chain_id().await?;
+        if let Some(previous_chain_id) = self.chain_id.replace(chain_id) {
+            if previous_chain_id != chain_id {
+                self.checkpoints.clear();
+                event = Some(ForkEvent::ChainChanged {
+                    previous_chain_id,
+                    chain_id,
+                });
+            }
+        }
+
+        let head = self.web3.eth().block_number().await?.as_u64();
+
+        // NOTE: Checkpoints are ordered by block number, so the first
+        //   replaced block is the deepest one.
+        let mut hashes = BTreeMap::new();
+        for (&block_number, &previous_hash) in &self.checkpoints {
+            let current_hash = self.block_hash(block_number).await?;
+            hashes.insert(block_number, current_hash);
+            if current_hash != Some(previous_hash) {
+                event = Some(ForkEvent::Reorg {
+                    block_number,
+                    depth: head.saturating_sub(block_number),
+                    previous_hash,
+                    current_hash,
+                });
+                break;
+            }
+        }
+
+        let mut checkpoints = BTreeMap::new();
+        for block_number in self
+            .depths
+            .iter()
+            .filter_map(|depth| head.checked_sub(*depth))
+        {
+            let hash = match hashes.get(&block_number) {
+                Some(hash) => *hash,
+                None => self.block_hash(block_number).await?,
+            };
+            if let Some(hash) = hash {
+                checkpoints.insert(block_number, hash);
+            }
+        }
+        self.checkpoints = checkpoints;
+
+        Ok(event)
+    }
+
+    /// Creates a stream that periodically checks for changes of the chain
+    /// and yields them as they are detected.
+    ///
+    /// Errors encountered while checking are yielded as well, and watching
+    /// continues with the next check.
+    pub fn stream(self) -> impl Stream<Item = Result<ForkEvent, ExecutionError>> {
+        stream::unfold(self, |mut watcher| async move {
+            loop {
+                match watcher.check().await {
+                    Ok(Some(event)) => return Some((Ok(event), watcher)),
+                    Ok(None) => confirm::delay(watcher.poll_interval).await,
+                    Err(err) => return Some((Err(err), watcher)),
+                }
+            }
+        })
+    }
+
+    async fn block_hash(&self, block_number: u64) -> Result<Option<H256>, ExecutionError> {
+        let block = self
+            .web3
+            .eth()
+            .block(BlockId::Number(BlockNumber::Number(block_number.into())))
+            .await?;
+
+        Ok(block.and_then(|block| block.hash))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::prelude::*;
+    use futures::StreamExt;
+    use serde_json::Value;
+    use web3::types::Block;
+
+    fn block(hash: u8) -> Value {
+        json!(Block::<H256> {
+            hash: Some(H256::repeat_byte(hash)),
+            ..Default::default()
+        })
+    }
+
+    fn add_check(transport: &mut TestTransport, chain_id: &str, head: &str, blocks: &[Value]) {
+        transport.add_response(json!(chain_id));
+        transport.add_response(json!(head));
+        for block in blocks {
+            transport.add_response(block.clone());
+        }
+    }
+
+    #[test]
+    fn no_fork() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+        let mut watcher = ForkWatcher::new(web3).depths(vec![1, 2]);
+
+        add_check(&mut transport, "0x1", "0xa", &[block(9), block(8)]);
+        assert_eq!(watcher.check().immediate().unwrap(), None);
+        transport.assert_request("eth_chainId", &[]);
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_getBlockByNumber", &[json!("0x9"), json!(false)]);
+        transport.assert_request("eth_getBlockByNumber", &[json!("0x8"), json!(false)]);
+        transport.assert_no_more_requests();
+
+        // NOTE: Block 9 was already fetched when verifying checkpoints.
+        add_check(
+            &mut transport,
+            "0x1",
+            "0xb",
+            &[block(8), block(9), block(10)],
+        );
+        assert_eq!(watcher.check().immediate().unwrap(), None);
+        transport.assert_request("eth_chainId", &[]);
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_getBlockByNumber", &[json!("0x8"), json!(false)]);
+        transport.assert_request("eth_getBlockByNumber", &[json!("0x9"), json!(false)]);
+        transport.assert_request("eth_getBlockByNumber", &[json!("0xa"), json!(false)]);
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn detects_reorg() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+        let mut watcher = ForkWatcher::new(web3).depths(vec![1, 2]);
+
+        add_check(&mut transport, "0x1", "0xa", &[block(9), block(8)]);
+        watcher.check().immediate().unwrap();
+
+        add_check(&mut transport, "0x1", "0xa", &[block(8), block(0x99)]);
+        assert_eq!(
+            watcher.check().immediate().unwrap(),
+            Some(ForkEvent::Reorg {
+                block_number: 9,
+                depth: 1,
+                previous_hash: H256::repeat_byte(9),
+                current_hash: Some(H256::repeat_byte(0x99)),
+            })
+        );
+
+        // NOTE: Samples are reset after a reorg, so it is reported once.
+        add_check(&mut transport, "0x1", "0xa", &[block(8), block(0x99)]);
+        assert_eq!(watcher.check().immediate().unwrap(), None);
+    }
+
+    #[test]
+    fn detects_missing_block() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+        let mut watcher = ForkWatcher::new(web3).depths(vec![0]);
+
+        add_check(&mut transport, "0x1", "0xa", &[block(10)]);
+        watcher.check().immediate().unwrap();
+
+        add_check(&mut transport, "0x1", "0x5", &[json!(null), block(5)]);
+        assert_eq!(
+            watcher.check().immediate().unwrap(),
+            Some(ForkEvent::Reorg {
+                block_number: 10,
+                depth: 0,
+                previous_hash: H256::repeat_byte(10),
+                current_hash: None,
+            })
+        );
+    }
+
+    #[test]
+    fn detects_chain_change() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+        let mut watcher = ForkWatcher::new(web3).depths(vec![0]);
+
+        add_check(&mut transport, "0x1", "0xa", &[block(10)]);
+        watcher.check().immediate().unwrap();
+
+        add_check(&mut transport, "0x64", "0x3", &[block(3)]);
+        assert_eq!(
+            watcher.check().immediate().unwrap(),
+            Some(ForkEvent::ChainChanged {
+                previous_chain_id: 1.into(),
+                chain_id: 100.into(),
+            })
+        );
+        transport.assert_request("eth_chainId", &[]);
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_getBlockByNumber", &[json!("0xa"), json!(false)]);
+        transport.assert_request("eth_chainId", &[]);
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_getBlockByNumber", &[json!("0x3"), json!(false)]);
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn skips_depths_below_genesis() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+        let mut watcher = ForkWatcher::new(web3).depths(vec![1, 64]);
+
+        add_check(&mut transport, "0x1", "0x2", &[block(1)]);
+        assert_eq!(watcher.check().immediate().unwrap(), None);
+        transport.assert_request("eth_chainId", &[]);
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_getBlockByNumber", &[json!("0x1"), json!(false)]);
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn stream_yields_events_and_errors() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+        let mut stream = Box::pin(ForkWatcher::new(web3).depths(vec![0]).stream());
+
+        add_check(&mut transport, "0x1", "0xa", &[block(10)]);
+        add_check(&mut transport, "0x1", "0xa", &[block(0x99)]);
+        transport.add_error(web3::Error::Unreachable);
+        add_check(&mut transport, "0x2", "0xa", &[block(0x99)]);
+
+        assert!(matches!(
+            stream.next().immediate(),
+            Some(Ok(ForkEvent::Reorg {
+                block_number: 10,
+                ..
+            }))
+        ));
+        assert!(matches!(stream.next().immediate(), Some(Err(_))));
+        assert!(matches!(
+            stream.next().immediate(),
+            Some(Ok(ForkEvent::ChainChanged { .. }))
+        ));
+    }
+}
@@ -0,0 +1,268 @@
+//! Gas usage snapshots for catching unexpected gas changes in tests.
+//!
+//! A [`GasSnapshot`] estimates the gas used by contract methods, typically
+//! against a forked node such as `anvil --fork-url ...` with fixture
+//! arguments, and compares the results with a snapshot file from a previous
+//! run. This makes gas changes visible when upgrading target contracts.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # async fn run(token: ethcontract::Instance<ethcontract::dyns::DynTransport>) {
+//! use ethcontract::gas_snapshot::GasSnapshot;
+//! use ethcontract::prelude::*;
+//!
+//! let mut snapshot = GasSnapshot::load("tests/gas-snapshot.json").unwrap();
+//! let recipient = Address::repeat_byte(0x42);
+//! let transfer = token
+//!     .method::<_, bool>([0xa9, 0x05, 0x9c, 0xbb], (recipient, U256::from(1)))
+//!     .unwrap();
+//! snapshot.measure("transfer", transfer).await.unwrap();
+//!
+//! if std::env::var("UPDATE_GAS_SNAPSHOT").is_ok() {
+//!     snapshot.save().unwrap();
+//! } else {
+//!     snapshot.assert_unchanged();
+//! }
+//! # }
+//! ```
+
+use crate::contract::MethodBuilder;
+use crate::errors::MethodError;
+use crate::tokens::Tokenize;
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use web3::Transport;
+
+/// Gas usage of contract methods recorded in a test run, along with the gas
+/// usage from the snapshot file of a previous run.
+#[derive(Clone, Debug)]
+pub struct GasSnapshot {
+    path: PathBuf,
+    previous: BTreeMap<String, u64>,
+    current: BTreeMap<String, u64>,
+}
+
+impl GasSnapshot {
+    /// Loads the snapshot file at the specified path. A missing file is
+    /// treated as an empty snapshot.
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self, GasSnapshotError> {
+        let path = path.into();
+        let previous = match fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json)?,
+            Err(err) if err.kind() == ErrorKind::NotFound => BTreeMap::new(),
+            Err(err) => return Err(err.into()),
+        };
+
+        Ok(GasSnapshot {
+            path,
+            previous,
+            current: BTreeMap::new(),
+        })
+    }
+
+    /// Returns the path of the snapshot file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Estimates the gas used by a contract method call and records it under
+    /// the specified name.
+    pub async fn measure<T, R>(
+        &mut self,
+        name: impl Into<String>,
+        method: MethodBuilder<T, R>,
+    ) -> Result<u64, MethodError>
+    where
+        T: Transport,
+        R: Tokenize,
+    {
+        let function = method.function().clone();
+        let gas = method
+            .into_inner()
+            .estimate_gas()
+            .await
+            .map_err(|err| MethodError::new(&function, err))?;
+
+        let gas = gas.low_u64();
+        self.record(name, gas);
+        Ok(gas)
+    }
+
+    /// Records the gas used by an operation under the specified name.
+    pub fn record(&mut self, name: impl Into<String>, gas: u64) {
+        self.current.insert(name.into(), gas);
+    }
+
+    /// Returns the gas used by the operation with the specified name in the
+    /// current run, if it was recorded.
+    pub fn get(&self, name: &str) -> Option<u64> {
+        self.current.get(name).copied()
+    }
+
+    /// Returns the gas usage changes of operations recorded in the current run
+    /// compared to the snapshot file, sorted by name.
+    ///
+    /// Operations from the snapshot file that were not recorded in this run
+    /// are not considered changes, so that a test can measure a subset of
+    /// the operations in a snapshot.
+    pub fn deltas(&self) -> Vec<GasDelta> {
+        self.current
+            .iter()
+            .filter_map(|(name, &current)| {
+                let previous = self.previous.get(name).copied();
+                (previous != Some(current)).then(|| GasDelta {
+                    name: name.clone(),
+                    previous,
+                    current,
+                })
+            })
+            .collect()
+    }
+
+    /// Writes the snapshot file with the gas usage recorded in this run.
+    /// Entries that were not recorded in this run are kept.
+    pub fn save(&self) -> Result<(), GasSnapshotError> {
+        let mut snapshot = self.previous.clone();
+        snapshot.extend(self.current.iter().map(|(name, gas)| (name.clone(), *gas)));
+
+        let mut json = serde_json::to_string_pretty(&snapshot)?;
+        json.push('\n');
+        fs::write(&self.path, json)?;
+        Ok(())
+    }
+
+    /// Asserts that the gas usage recorded in this run matches the snapshot
+    /// file.
+    ///
+    /// # Panics
+    ///
+    /// Panics with a report of all changes if the gas usage of any recorded
+    /// operation changed or is missing from the snapshot file.
+    pub fn assert_unchanged(&self) {
+        let deltas = self.deltas();
+        if !deltas.is_empty() {
+            let report = deltas
+                .iter()
+                .map(|delta| format!("  {}", delta))
+                .collect::<Vec<_>>()
+                .join("\n");
+            panic!(
+                "gas usage changed compared to snapshot '{}':\n{}",
+                self.path.display(),
+                report,
+            );
+        }
+    }
+}
+
+/// A change in gas usage of an operation compared to the snapshot file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GasDelta {
+    /// The name of the operation.
+    pub name: String,
+    /// The gas used according to the snapshot file, or `None` if the
+    /// operation is new.
+    pub previous: Option<u64>,
+    /// The gas used in the current run.
+    pub current: u64,
+}
+
+impl GasDelta {
+    /// Returns the difference in gas usage, or `None` if the operation is
+    /// new.
+    pub fn difference(&self) -> Option<i128> {
+        self.previous
+            .map(|previous| self.current as i128 - previous as i128)
+    }
+}
+
+impl Display for GasDelta {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self.difference() {
+            Some(difference) => write!(
+                f,
+                "{}: {} -> {} ({:+})",
+                self.name,
+                self.previous.unwrap_or_default(),
+                self.current,
+                difference,
+            ),
+            None => write!(f, "{}: {} (new)", self.name, self.current),
+        }
+    }
+}
+
+/// An error reading or writing a gas snapshot file.
+#[derive(Debug, Error)]
+pub enum GasSnapshotError {
+    /// An IO error occurred reading or writing the snapshot file.
+    #[error("failed to access gas snapshot file: {0}")]
+    Io(#[from] io::Error),
+
+    /// The snapshot file contains invalid JSON.
+    #[error("failed to parse gas snapshot file: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::prelude::*;
+    use crate::Instance;
+    use ethcontract_common::hash;
+    use std::sync::Arc;
+    use web3::types::Address;
+
+    fn snapshot(previous: &[(&str, u64)]) -> GasSnapshot {
+        GasSnapshot {
+            path: PathBuf::from("gas-snapshot.json"),
+            previous: previous
+                .iter()
+                .map(|(name, gas)| (name.to_string(), *gas))
+                .collect(),
+            current: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn measure_method_gas() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let abi = abi(vec![function("transfer", &[], &[])]);
+        let instance = Instance::at(web3, Arc::new(abi.into()), Address::repeat_byte(1));
+        let method = instance
+            .method::<_, ()>(hash::function_selector("transfer()"), ())
+            .unwrap();
+
+        transport.add_response(json!("0x5208"));
+        let mut snapshot = snapshot(&[("transfer", 21_000)]);
+        let gas = snapshot.measure("transfer", method).immediate().unwrap();
+
+        assert_eq!(gas, 21_000);
+        assert!(snapshot.deltas().is_empty());
+        snapshot.assert_unchanged();
+    }
+
+    #[test]
+    fn gas_deltas() {
+        let mut snapshot = snapshot(&[("approve", 46_000), ("transfer", 51_000), ("old", 1)]);
+        snapshot.record("approve", 46_000);
+        snapshot.record("transfer", 50_500);
+        snapshot.record("permit", 70_000);
+
+        let deltas = snapshot.deltas();
+        assert_eq!(
+            deltas
+                .iter()
+                .map(|delta| delta.to_string())
+                .collect::<Vec<_>>(),
+            ["permit: 70000 (new)", "transfer: 51000 -> 50500 (-500)"],
+        );
+    }
+}
@@ -93,12 +93,21 @@
 #[path = "test/macros.rs"]
 mod test_macros;
 
+pub mod accounts;
 pub mod batch;
 pub mod contract;
+pub mod eip712;
+pub mod erc165;
 pub mod errors;
-mod int;
+#[cfg(feature = "http")]
+pub mod explorer;
+pub mod fork;
+pub mod gas_snapshot;
 pub mod log;
+mod macros;
 pub mod secret;
+pub mod serialization;
+pub mod snapshot;
 pub mod tokens;
 pub mod transaction;
 pub mod transport;
@@ -108,6 +117,7 @@ pub use crate::prelude::*;
 #[cfg(feature = "aws-kms")]
 pub use aws_config;
 pub use ethcontract_common as common;
+pub use ethcontract_common::abiext::{AbiExt, EventExt, FunctionExt};
 pub use ethcontract_common::contract::Contract;
 #[cfg(feature = "derive")]
 pub use ethcontract_derive::contract;
@@ -120,16 +130,18 @@ pub mod prelude {
     //! A prelude module for importing commonly used types when interacting with
     //! generated contracts.
 
-    pub use crate::contract::{Event, EventMetadata, EventStatus, RawLog, StreamEvent, Topic};
-    pub use crate::int::I256;
+    pub use crate::contract::{
+        Event, EventMetadata, EventStatus, Hashed, RawLog, StreamEvent, Topic,
+    };
     pub use crate::secret::{Password, PrivateKey};
     pub use crate::tokens::Bytes;
-    pub use crate::transaction::{Account, GasPrice};
+    pub use crate::transaction::{Account, GasPrice, Signature};
+    pub use crate::types::{Address, BlockId, BlockNumber, TransactionCondition, H160, H256, U256};
     pub use ethcontract_common::TransactionHash;
+    pub use ethcontract_core::I256;
     pub use web3::api::Web3;
     #[cfg(feature = "http")]
     pub use web3::transports::Http;
-    pub use web3::types::{Address, BlockId, BlockNumber, TransactionCondition, H160, H256, U256};
 }
 
 pub mod dyns {
@@ -165,13 +177,120 @@ pub mod dyns {
     pub type DynAllEventsBuilder<E> = AllEventsBuilder<DynTransport, E>;
 }
 
+pub mod types {
+    //! Re-exports of the `web3` types that appear in the public `ethcontract`
+    //! API.
+    //!
+    //! Downstream crates should prefer importing these types from here rather
+    //! than depending on `web3` directly, so that upgrading the `web3` version
+    //! used by `ethcontract` does not require matching changes downstream.
+
+    pub use web3::types::{
+        AccessList, AccessListItem, Address, BlockId, BlockNumber, Bytes, CallRequest, Filter,
+        FilterBuilder, Log, SignedTransaction, TransactionCondition, TransactionReceipt, H160,
+        H2048, H256, U256, U64,
+    };
+}
+
 #[doc(hidden)]
 pub mod private {
     // Private definitions that are needed by the generated contract code or
     // but do not appear in public interfaces. No documentation is generated
     // for these definitions.
+    //
+    // The generic helpers here keep generated method and event bindings down
+    // to a single call, and the event macros expand the boilerplate that is
+    // shared by all generated event types, so that the code generator only
+    // emits what is specific to each contract.
+
+    use crate::dyns::{DynEventBuilder, DynInstance, DynMethodBuilder, DynViewMethodBuilder};
+    use crate::errors::ExecutionError;
+    use crate::tokens::Tokenize;
+    use crate::types::Bytes;
+    use crate::H256;
+    use ethcontract_common::abi::{encode, Error as AbiError, Token};
+    use ethcontract_common::hash::H32;
+    use ethcontract_common::Abi;
 
+    pub use crate::{
+        __ethcontract_event_builder as event_builder, __ethcontract_event_data as event_data,
+    };
     pub use lazy_static::lazy_static;
+
+    /// Creates a method builder for a generated contract method.
+    pub fn method<P, R>(instance: &DynInstance, selector: H32, params: P) -> DynMethodBuilder<R>
+    where
+        P: Tokenize,
+        R: Tokenize,
+    {
+        instance.method(selector, params).expect("generated call")
+    }
+
+    /// Creates a view method builder for a generated contract method.
+    pub fn view_method<P, R>(
+        instance: &DynInstance,
+        selector: H32,
+        params: P,
+    ) -> DynViewMethodBuilder<R>
+    where
+        P: Tokenize,
+        R: Tokenize,
+    {
+        instance
+            .view_method(selector, params)
+            .expect("generated call")
+    }
+
+    /// Encodes the calldata for a call to a generated contract method.
+    pub fn calldata<P>(selector: H32, params: P) -> Bytes
+    where
+        P: Tokenize,
+    {
+        let tokens = match params.into_token() {
+            Token::Tuple(tokens) => tokens,
+            _ => unreachable!("function arguments are always tuples"),
+        };
+        Bytes(selector.iter().copied().chain(encode(&tokens)).collect())
+    }
+
+    /// Creates an event builder for a generated contract event.
+    pub fn event<E>(instance: &DynInstance, signature: H256) -> DynEventBuilder<E>
+    where
+        E: Tokenize,
+    {
+        instance.event(signature).expect("generated event filter")
+    }
+
+    /// Decodes the parameters of a custom error defined in a contract ABI
+    /// from revert data.
+    pub fn decode_error<P>(abi: &Abi, data: &[u8]) -> Option<P>
+    where
+        P: Tokenize,
+    {
+        let mut selector = H32::default();
+        selector.copy_from_slice(data.get(..4)?);
+        let params = crate::errors::find_error(abi, selector)?
+            .decode(&data[4..])
+            .ok()?;
+        P::from_token(Token::Tuple(params)).ok()
+    }
+
+    /// Decodes the parameters of a contract method call from calldata
+    /// starting with the 4-byte method selector.
+    pub fn decode_call<P>(abi: &Abi, data: &[u8]) -> Result<P, ExecutionError>
+    where
+        P: Tokenize,
+    {
+        let function = data
+            .get(..4)
+            .and_then(|selector| {
+                abi.functions()
+                    .find(|function| function.short_signature() == selector)
+            })
+            .ok_or(AbiError::InvalidData)?;
+        let params = function.decode_input(&data[4..])?;
+        Ok(P::from_token(Token::Tuple(params))?)
+    }
 }
 
 #[cfg(test)]
@@ -1,6 +1,10 @@
 //! This module implements event builders and streams for retrieving events
 //! emitted by a contract.
 
+mod profile;
+
+pub use self::profile::{is_limit_error, ProviderProfile};
+
 use crate::errors::ExecutionError;
 use ethcontract_common::abi::{Topic, TopicFilter};
 use futures::future::{self, TryFutureExt};
@@ -10,7 +14,7 @@ use std::time::Duration;
 use web3::api::Web3;
 use web3::error::Error as Web3Error;
 use web3::types::{Address, BlockId, BlockNumber, Filter, FilterBuilder, Log, H256};
-use web3::Transport;
+use web3::{DuplexTransport, Transport};
 
 /// The default poll interval to use for polling logs from the block chain.
 #[cfg(not(test))]
@@ -39,6 +43,9 @@ pub struct LogFilterBuilder<T: Transport> {
     pub to_block: Option<BlockNumber>,
     /// Block hash, mutually exclusive with pair `from_block` / `to_block`.
     pub block_hash: Option<H256>,
+    /// The block number and log index of the last log that was already
+    /// processed. Logs at or before this position are filtered out.
+    pub from_position: Option<(u64, u64)>,
     /// The contract addresses to filter logs for.
     pub address: Vec<Address>,
     /// Topic filters used for filtering logs based on indexed topics.
@@ -52,6 +59,9 @@ pub struct LogFilterBuilder<T: Transport> {
     /// logs. This provides no guarantee in how many logs will be returned per
     /// page, but used to limit the block range for the query.
     pub block_page_size: Option<NonZeroU64>,
+    /// The profile of the node provider, used for picking a default block
+    /// page size when none is specified.
+    pub provider_profile: Option<ProviderProfile>,
     /// The polling interval for querying the node for more logs.
     pub poll_interval: Option<Duration>,
 }
@@ -67,8 +77,10 @@ impl<T: Transport> LogFilterBuilder<T> {
             topics: TopicFilter::default(),
             limit: None,
             block_page_size: None,
+            provider_profile: None,
             poll_interval: None,
             block_hash: None,
+            from_position: None,
         }
     }
 
@@ -90,6 +102,19 @@ impl<T: Transport> LogFilterBuilder<T> {
         self
     }
 
+    /// Resumes retrieving logs after the specified position, typically the
+    /// block number and log index of the last log processed before a restart.
+    ///
+    /// This sets the starting block to `block` and filters out logs at or
+    /// before `log_index` in that block, so that each log is only retrieved
+    /// once across restarts.
+    #[allow(clippy::wrong_self_convention)]
+    pub fn from_position(mut self, block: u64, log_index: u64) -> Self {
+        self.from_block = Some(BlockNumber::Number(block.into()));
+        self.from_position = Some((block, log_index));
+        self
+    }
+
     /// Sets `block_hash`. The field `block_hash` and the pair `from_block` and
     /// `to_block` are mutually exclusive.
     pub fn block_hash(mut self, hash: H256) -> Self {
@@ -150,6 +175,14 @@ impl<T: Transport> LogFilterBuilder<T> {
         self
     }
 
+    /// The profile of the node provider, used for picking a safe block page
+    /// size for paginated queries on past events when no explicit block page
+    /// size is specified.
+    pub fn provider_profile(mut self, profile: ProviderProfile) -> Self {
+        self.provider_profile = Some(profile);
+        self
+    }
+
     /// The polling interval. This is used as the interval between consecutive
     /// `eth_getLogs` calls to get log updates.
     pub fn poll_interval(mut self, value: Duration) -> Self {
@@ -192,20 +225,31 @@ impl<T: Transport> LogFilterBuilder<T> {
     /// use the `past_logs_pages` method instead.
     pub async fn past_logs(self) -> Result<Vec<Log>, ExecutionError> {
         let web3 = self.web3.clone();
+        let position = self.from_position;
         let filter = self.into_filter();
-        let logs = web3.eth().logs(filter.build()).await?;
+        let mut logs = web3.eth().logs(filter.build()).await?;
+        logs.retain(|log| is_after(log, position));
 
         Ok(logs)
     }
 
     /// Returns a stream that resolves into a page of logs matching the filter
     /// builder's parameters.
+    ///
+    /// If the node rejects a page because its block range or result set is
+    /// too large, the page is retried with half the block range and the
+    /// reduced block page size is used for all following pages.
     pub fn past_logs_pages(mut self) -> impl Stream<Item = Result<Vec<Log>, ExecutionError>> {
         // NOTE: Ignore the `limit` option when doing paginated queries as it
         //   can interfere.
         self.limit = None;
+        let position = self.from_position;
 
         stream::try_unfold(PastLogsStream::Init(self), PastLogsStream::next)
+            .map_ok(move |mut logs| {
+                logs.retain(|log| is_after(log, position));
+                logs
+            })
             .try_filter(|logs| future::ready(!logs.is_empty()))
     }
 
@@ -213,6 +257,7 @@ impl<T: Transport> LogFilterBuilder<T> {
     pub fn stream(self) -> impl Stream<Item = Result<Log, ExecutionError>> {
         let web3 = self.web3.clone();
         let poll_interval = self.poll_interval.unwrap_or(DEFAULT_POLL_INTERVAL);
+        let position = self.from_position;
         let filter = self.into_filter();
 
         async move {
@@ -223,7 +268,36 @@ impl<T: Transport> LogFilterBuilder<T> {
                 .map_err(ExecutionError::from)?;
             let stream = eth_filter
                 .stream(poll_interval)
-                .map_err(ExecutionError::from);
+                .map_err(ExecutionError::from)
+                .try_filter(move |log| future::ready(is_after(log, position)));
+
+            Ok(stream)
+        }
+        .try_flatten_stream()
+    }
+}
+
+impl<T: DuplexTransport> LogFilterBuilder<T> {
+    /// Creates a subscription-based log stream that emits new logs as they are
+    /// pushed by the node with `eth_subscribe("logs")`, instead of polling for
+    /// filter changes.
+    ///
+    /// Note that nodes only consider the address and topic filters for log
+    /// subscriptions, so the block range and limit are ignored.
+    pub fn subscribe(self) -> impl Stream<Item = Result<Log, ExecutionError>> {
+        let web3 = self.web3.clone();
+        let position = self.from_position;
+        let filter = self.into_filter();
+
+        async move {
+            let stream = web3
+                .eth_subscribe()
+                .subscribe_logs(filter.build())
+                .await
+                .map_err(ExecutionError::from)?;
+            let stream = stream
+                .map_err(ExecutionError::from)
+                .try_filter(move |log| future::ready(is_after(log, position)));
 
             Ok(stream)
         }
@@ -231,6 +305,18 @@ impl<T: Transport> LogFilterBuilder<T> {
     }
 }
 
+/// Returns whether a log comes after the specified block number and log
+/// index. Logs without a block number or log index, such as pending logs, are
+/// always considered to come after it.
+fn is_after(log: &Log, position: Option<(u64, u64)>) -> bool {
+    match (position, log.block_number, log.log_index) {
+        (Some((block, log_index)), Some(log_block), Some(log_log_index)) => {
+            (log_block.as_u64(), log_log_index) > (block, log_index.into())
+        }
+        _ => true,
+    }
+}
+
 /// Converts a `Topic` to an equivalent `Option<Vec<T>>`, suitable for `FilterBuilder::topics`
 fn topic_to_option(topic: Topic<H256>) -> Option<Vec<H256>> {
     match topic {
@@ -294,10 +380,11 @@ impl<T: Transport> PastLogsStream<T> {
         let to_block = builder.to_block.unwrap_or(BlockNumber::Latest);
 
         let web3 = builder.web3.clone();
+        let profile = builder.provider_profile.unwrap_or_default();
         let block_page_size = builder
             .block_page_size
             .map(|size| size.get())
-            .unwrap_or(DEFAULT_BLOCK_PAGE_SIZE);
+            .unwrap_or_else(|| profile.block_page_size());
         let filter = builder.into_filter();
 
         let start_block = match from_block {
@@ -320,6 +407,7 @@ impl<T: Transport> PastLogsStream<T> {
             (Some(page_block), Some(end_block)) => PastLogsStream::Paging(PastLogsPager {
                 web3,
                 to_block,
+                profile,
                 block_page_size,
                 filter,
                 page_block,
@@ -338,6 +426,8 @@ struct PastLogsPager<T: Transport> {
 
     /// The `to_block` specified by the log filter.
     to_block: BlockNumber,
+    /// The node provider profile, refined when the node rejects queries.
+    profile: ProviderProfile,
     /// The block page size being used for queries.
     block_page_size: u64,
     /// The web3 filter used for retrieving the logs.
@@ -373,7 +463,7 @@ impl<T: Transport> PastLogsPager<T> {
                 self.to_block
             };
 
-            let page = self
+            let page = match self
                 .web3
                 .eth()
                 .logs(
@@ -383,7 +473,24 @@ impl<T: Transport> PastLogsPager<T> {
                         .to_block(page_to_block)
                         .build(),
                 )
-                .await?;
+                .await
+            {
+                Ok(page) => page,
+                Err(err) if is_limit_error(&err) => {
+                    // NOTE: The node rejected the query because of its limits,
+                    //   so retry the page with a smaller block range.
+                    let page_size = page_end.min(self.end_block) - self.page_block + 1;
+                    match self.profile.refine(page_size) {
+                        Some(profile) => {
+                            self.profile = profile;
+                            self.block_page_size = profile.block_page_size();
+                            continue;
+                        }
+                        None => return Err(err.into()),
+                    }
+                }
+                Err(err) => return Err(err.into()),
+            };
 
             self.page_block = page_end + 1;
             if page.is_empty() {
@@ -544,6 +651,165 @@ mod tests {
         transport.assert_no_more_requests();
     }
 
+    #[test]
+    fn past_log_stream_refines_block_range() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let log = generate_log("awesome");
+
+        // get latest block
+        transport.add_response(json!(U64::from(1_999)));
+        // first page exceeds the provider limits, so it gets split
+        transport.add_error(Web3Error::Rpc(jsonrpc_core::Error {
+            code: jsonrpc_core::ErrorCode::ServerError(-32005),
+            message: "query returned more than 10000 results".to_string(),
+            data: None,
+        }));
+        transport.add_response(json!([log]));
+        transport.add_response(json!([log, log]));
+
+        let pages = LogFilterBuilder::new(web3)
+            .from_block(0.into())
+            .provider_profile(ProviderProfile::ALCHEMY)
+            .past_logs_pages()
+            .try_collect::<Vec<_>>()
+            .immediate()
+            .expect("failed to get past logs");
+
+        assert_eq!(
+            pages.iter().map(|page| page.len()).collect::<Vec<_>>(),
+            [1, 2],
+        );
+
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request(
+            "eth_getLogs",
+            &[json!({
+                "fromBlock": U64::from(0),
+                "toBlock": "latest",
+            })],
+        );
+        transport.assert_request(
+            "eth_getLogs",
+            &[json!({
+                "fromBlock": U64::from(0),
+                "toBlock": U64::from(999),
+            })],
+        );
+        transport.assert_request(
+            "eth_getLogs",
+            &[json!({
+                "fromBlock": U64::from(1_000),
+                "toBlock": "latest",
+            })],
+        );
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn past_logs_from_position() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let log = |block: u64, log_index: u64, kind: &str| {
+            let mut log = generate_log(kind);
+            log["blockNumber"] = json!(U64::from(block));
+            log["logIndex"] = json!(U64::from(log_index));
+            log
+        };
+
+        // get logs
+        transport.add_response(json!([
+            log(10, 2, "processed"),
+            log(10, 3, "processed"),
+            log(10, 4, "next"),
+            log(11, 0, "later"),
+        ]));
+
+        let logs = LogFilterBuilder::new(web3)
+            .from_position(10, 3)
+            .to_block(20.into())
+            .past_logs()
+            .immediate()
+            .expect("failed to get past logs");
+
+        assert_eq!(
+            logs.iter()
+                .map(|log| log.log_type.as_deref().unwrap())
+                .collect::<Vec<_>>(),
+            ["next", "later"],
+        );
+        transport.assert_request(
+            "eth_getLogs",
+            &[json!({
+                "fromBlock": U64::from(10),
+                "toBlock": U64::from(20),
+            })],
+        );
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn log_stream_from_position() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let mut processed = generate_log("processed");
+        processed["blockNumber"] = json!("0x5");
+        let mut next = generate_log("next");
+        next["blockNumber"] = json!("0x5");
+        next["logIndex"] = json!("0x1");
+
+        // filter created
+        transport.add_response(json!("0xf0"));
+        // get logs filter
+        transport.add_response(json!([processed, next]));
+
+        let log = LogFilterBuilder::new(web3)
+            .from_position(5, 0)
+            .stream()
+            .boxed()
+            .next()
+            .wait()
+            .expect("log stream did not produce any logs")
+            .expect("failed to get log from log stream");
+
+        assert_eq!(log.log_type.as_deref(), Some("next"));
+    }
+
+    #[test]
+    fn log_subscription() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let address = Address::repeat_byte(0x42);
+
+        // subscription created
+        transport.add_response(json!("0x1"));
+        transport.add_notification(generate_log("first"));
+        transport.add_notification(generate_log("second"));
+
+        let logs = LogFilterBuilder::new(web3)
+            .address(vec![address])
+            .subscribe()
+            .try_collect::<Vec<_>>()
+            .wait()
+            .expect("failed to get logs from subscription");
+
+        assert_eq!(
+            logs.iter()
+                .map(|log| log.log_type.as_deref().unwrap())
+                .collect::<Vec<_>>(),
+            ["first", "second"],
+        );
+        transport.assert_request(
+            "eth_subscribe",
+            &[json!("logs"), json!({ "address": address })],
+        );
+        transport.assert_no_more_requests();
+    }
+
     #[test]
     fn log_stream_next_log() {
         let mut transport = TestTransport::new();
@@ -0,0 +1,201 @@
+//! Known `eth_getLogs` limits of popular node providers, used for picking safe
+//! block page sizes when paging through past logs.
+
+use crate::log::DEFAULT_BLOCK_PAGE_SIZE;
+use web3::error::Error as Web3Error;
+
+/// The `eth_getLogs` limits of a node provider.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ProviderProfile {
+    /// A human readable name for the provider.
+    pub name: &'static str,
+    /// The maximum number of blocks that can be queried in a single
+    /// `eth_getLogs` request, if the provider limits it.
+    pub max_block_range: Option<u64>,
+    /// The maximum number of logs that can be returned by a single
+    /// `eth_getLogs` request, if the provider limits it.
+    pub max_results: Option<usize>,
+}
+
+/// Known provider profiles along with the host name suffix used to detect
+/// them from a node URL.
+const PROFILES: &[(&str, ProviderProfile)] = &[
+    (".infura.io", ProviderProfile::INFURA),
+    (".alchemy.com", ProviderProfile::ALCHEMY),
+    (".alchemyapi.io", ProviderProfile::ALCHEMY),
+    (".quiknode.pro", ProviderProfile::QUICKNODE),
+    ("cloudflare-eth.com", ProviderProfile::CLOUDFLARE),
+];
+
+impl ProviderProfile {
+    /// Profile for nodes with unknown limits.
+    pub const GENERIC: ProviderProfile = ProviderProfile {
+        name: "generic",
+        max_block_range: None,
+        max_results: None,
+    };
+
+    /// Profile for Infura nodes, which limit the number of returned logs.
+    pub const INFURA: ProviderProfile = ProviderProfile {
+        name: "Infura",
+        max_block_range: None,
+        max_results: Some(10_000),
+    };
+
+    /// Profile for Alchemy nodes, which only allow unlimited responses for
+    /// queries of up to 2000 blocks.
+    pub const ALCHEMY: ProviderProfile = ProviderProfile {
+        name: "Alchemy",
+        max_block_range: Some(2_000),
+        max_results: None,
+    };
+
+    /// Profile for QuickNode nodes.
+    pub const QUICKNODE: ProviderProfile = ProviderProfile {
+        name: "QuickNode",
+        max_block_range: Some(10_000),
+        max_results: None,
+    };
+
+    /// Profile for the Cloudflare Ethereum gateway.
+    pub const CLOUDFLARE: ProviderProfile = ProviderProfile {
+        name: "Cloudflare",
+        max_block_range: Some(800),
+        max_results: None,
+    };
+
+    /// Detects the provider profile from a node URL, falling back to the
+    /// generic profile for unknown providers.
+    pub fn from_url(url: &str) -> Self {
+        let host = url
+            .split_once("://")
+            .map(|(_, rest)| rest)
+            .unwrap_or(url)
+            .split(['/', ':', '?'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        PROFILES
+            .iter()
+            .find(|(suffix, _)| {
+                host.ends_with(suffix) || Some(&host[..]) == suffix.strip_prefix('.')
+            })
+            .map(|(_, profile)| *profile)
+            .unwrap_or(ProviderProfile::GENERIC)
+    }
+
+    /// Returns a safe default block page size for paging through past logs
+    /// with this provider.
+    pub fn block_page_size(&self) -> u64 {
+        self.max_block_range
+            .unwrap_or(DEFAULT_BLOCK_PAGE_SIZE)
+            .clamp(1, DEFAULT_BLOCK_PAGE_SIZE)
+    }
+
+    /// Returns a refined profile after a query of `block_page_size` blocks
+    /// failed because of provider limits, or `None` if the block range can't
+    /// be reduced any further.
+    pub fn refine(&self, block_page_size: u64) -> Option<Self> {
+        if block_page_size <= 1 {
+            return None;
+        }
+
+        Some(ProviderProfile {
+            max_block_range: Some(block_page_size / 2),
+            ..*self
+        })
+    }
+}
+
+impl Default for ProviderProfile {
+    fn default() -> Self {
+        ProviderProfile::GENERIC
+    }
+}
+
+/// Returns `true` if the error is a node provider rejecting an `eth_getLogs`
+/// query because its block range or result set is too large.
+pub fn is_limit_error(err: &Web3Error) -> bool {
+    const LIMIT_EXCEEDED: i64 = -32005;
+    const PATTERNS: &[&str] = &[
+        "block range",
+        "more than",
+        "response size",
+        "too many",
+        "limit exceeded",
+        "limited to",
+        "exceed maximum",
+        "query timeout",
+    ];
+
+    match err {
+        Web3Error::Rpc(err) => {
+            let message = err.message.to_lowercase();
+            err.code.code() == LIMIT_EXCEEDED
+                || PATTERNS.iter().any(|pattern| message.contains(pattern))
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonrpc_core::{Error as RpcError, ErrorCode};
+
+    #[test]
+    fn profile_from_url() {
+        for (url, profile) in [
+            (
+                "https://mainnet.infura.io/v3/abcdef",
+                ProviderProfile::INFURA,
+            ),
+            (
+                "wss://eth-mainnet.g.alchemy.com/v2/key",
+                ProviderProfile::ALCHEMY,
+            ),
+            ("https://cloudflare-eth.com", ProviderProfile::CLOUDFLARE),
+            ("http://localhost:8545", ProviderProfile::GENERIC),
+            ("https://notinfura.io", ProviderProfile::GENERIC),
+        ] {
+            assert_eq!(ProviderProfile::from_url(url), profile, "{}", url);
+        }
+    }
+
+    #[test]
+    fn profile_block_page_size() {
+        assert_eq!(ProviderProfile::GENERIC.block_page_size(), 10_000);
+        assert_eq!(ProviderProfile::ALCHEMY.block_page_size(), 2_000);
+
+        let refined = ProviderProfile::ALCHEMY.refine(2_000).unwrap();
+        assert_eq!(refined.block_page_size(), 1_000);
+        assert_eq!(ProviderProfile::ALCHEMY.refine(1), None);
+    }
+
+    #[test]
+    fn limit_errors() {
+        let rpc = |code: i64, message: &str| {
+            Web3Error::Rpc(RpcError {
+                code: ErrorCode::from(code),
+                message: message.to_string(),
+                data: None,
+            })
+        };
+
+        assert!(is_limit_error(&rpc(
+            -32005,
+            "query returned more than 10000 results"
+        )));
+        assert!(is_limit_error(&rpc(
+            -32602,
+            "Log response size exceeded. You can make eth_getLogs requests with up to a 2K block range"
+        )));
+        assert!(is_limit_error(&rpc(
+            -32000,
+            "exceed maximum block range: 5000"
+        )));
+        assert!(!is_limit_error(&rpc(-32000, "header not found")));
+        assert!(!is_limit_error(&Web3Error::Unreachable));
+    }
+}
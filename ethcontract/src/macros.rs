@@ -0,0 +1,131 @@
+//! Macros expanding the boilerplate shared by all generated event types, so
+//! that the code generator only emits what is specific to each event.
+//!
+//! These are used by the generated contract code through the re-exports in
+//! `ethcontract::private` and are not part of the public API.
+
+/// Implements the signature accessors and `Tokenize` for a generated event
+/// data type from the event's topic hash, ABI signature and parameter names.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __ethcontract_event_data {
+    (
+        $name:ident,
+        $signature:expr,
+        $abi_signature:literal,
+        ($($param:ident),*) => $construction:expr $(,)?
+    ) => {
+        impl $name {
+            /// Retrieves the signature for the event this data corresponds to.
+            /// This signature is the Keccak-256 hash of the ABI signature of
+            /// this event, see [`abi_signature`](Self::abi_signature).
+            pub fn signature() -> $crate::H256 {
+                $signature
+            }
+
+            /// Retrieves the ABI signature for the event this data corresponds
+            /// to. For this event the value should always be:
+            ///
+            #[doc = concat!("`", $abi_signature, "`")]
+            pub fn abi_signature() -> &'static str {
+                $abi_signature
+            }
+        }
+
+        impl $crate::tokens::Tokenize for $name {
+            fn from_token(
+                token: $crate::common::abi::Token,
+            ) -> ::std::result::Result<Self, $crate::tokens::Error> {
+                let ($($param,)*) = $crate::tokens::Tokenize::from_token(token)?;
+                Ok($construction)
+            }
+
+            fn into_token(self) -> $crate::common::abi::Token {
+                unimplemented!("events are only decoded, not encoded")
+            }
+        }
+    };
+}
+
+/// Defines a generated event builder type wrapping a `DynEventBuilder` for
+/// the specified event data type, along with the filter and query methods
+/// that are common to all events. Topic filter methods for the indexed event
+/// parameters are generated separately.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __ethcontract_event_builder {
+    ($(#[$attr:meta])* $builder:ident($event:ty) $(;)?) => {
+        $(#[$attr])*
+        pub struct $builder(
+            /// The inner event builder.
+            pub $crate::dyns::DynEventBuilder<$event>,
+        );
+
+        impl $builder {
+            /// Sets the starting block from which to stream logs for.
+            ///
+            /// If left unset defaults to the latest block.
+            #[allow(clippy::wrong_self_convention)]
+            pub fn from_block(mut self, block: $crate::BlockNumber) -> Self {
+                self.0 = (self.0).from_block(block);
+                self
+            }
+
+            /// Resumes retrieving events after the specified position,
+            /// typically the block number and log index of the last event
+            /// processed before a restart.
+            #[allow(clippy::wrong_self_convention)]
+            pub fn from_position(mut self, block: u64, log_index: u64) -> Self {
+                self.0 = (self.0).from_position(block, log_index);
+                self
+            }
+
+            /// Sets the last block from which to stream logs for.
+            ///
+            /// If left unset defaults to the streaming until the end of days.
+            #[allow(clippy::wrong_self_convention)]
+            pub fn to_block(mut self, block: $crate::BlockNumber) -> Self {
+                self.0 = (self.0).to_block(block);
+                self
+            }
+
+            /// Limits the number of events that can be retrieved by this filter.
+            ///
+            /// Note that this parameter is non-standard.
+            pub fn limit(mut self, value: usize) -> Self {
+                self.0 = (self.0).limit(value);
+                self
+            }
+
+            /// Sets the polling interval. This is used as the interval between
+            /// consecutive `eth_getFilterChanges` calls to get filter updates.
+            pub fn poll_interval(mut self, value: ::std::time::Duration) -> Self {
+                self.0 = (self.0).poll_interval(value);
+                self
+            }
+
+            /// Returns a future that resolves with a collection of all existing
+            /// logs matching the builder parameters.
+            pub async fn query(
+                self,
+            ) -> ::std::result::Result<
+                ::std::vec::Vec<$crate::Event<$event>>,
+                $crate::errors::EventError,
+            > {
+                (self.0).query().await
+            }
+
+            /// Creates an event stream from the current event builder.
+            pub fn stream(
+                self,
+            ) -> impl $crate::futures::stream::Stream<
+                Item = ::std::result::Result<
+                    $crate::StreamEvent<$event>,
+                    $crate::errors::EventError,
+                >,
+            > {
+                (self.0).stream()
+            }
+        }
+    };
+}
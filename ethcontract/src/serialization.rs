@@ -0,0 +1,183 @@
+//! Serde adapters for 256-bit integers, for use with `#[serde(with = ...)]`.
+//!
+//! Generated event data types apply these adapters to their `U256` and `I256`
+//! fields when a number format is configured for code generation, so that
+//! event data serializes into a representation that is useful downstream.
+//!
+//! # Examples
+//!
+//! ```
+//! use ethcontract::U256;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Deserialize, Serialize)]
+//! struct Transfer {
+//!     #[serde(with = "ethcontract::serialization::decimal")]
+//!     value: U256,
+//! }
+//!
+//! let json = serde_json::to_string(&Transfer { value: 1_000_000.into() }).unwrap();
+//! assert_eq!(json, r#"{"value":"1000000"}"#);
+//! ```
+
+use crate::I256;
+use primitive_types::U256;
+
+/// A 256-bit integer type that can be serialized as a hexadecimal or decimal
+/// string.
+pub trait Number: Sized {
+    /// Formats the number as a `0x`-prefixed hexadecimal string.
+    fn to_hex_string(&self) -> String;
+
+    /// Formats the number as a decimal string.
+    fn to_decimal_string(&self) -> String;
+
+    /// Parses a number from a `0x`-prefixed hexadecimal string.
+    fn from_hex_string(value: &str) -> Result<Self, String>;
+
+    /// Parses a number from a decimal string.
+    fn from_decimal_string(value: &str) -> Result<Self, String>;
+}
+
+impl Number for U256 {
+    fn to_hex_string(&self) -> String {
+        format!("{:#x}", self)
+    }
+
+    fn to_decimal_string(&self) -> String {
+        self.to_string()
+    }
+
+    fn from_hex_string(value: &str) -> Result<Self, String> {
+        let digits = strip_hex_prefix(value)?;
+        U256::from_str_radix(digits, 16).map_err(|err| err.to_string())
+    }
+
+    fn from_decimal_string(value: &str) -> Result<Self, String> {
+        U256::from_dec_str(value).map_err(|err| err.to_string())
+    }
+}
+
+impl Number for I256 {
+    fn to_hex_string(&self) -> String {
+        let hex = format!("{:x}", self);
+        match hex.strip_prefix('-') {
+            Some(abs) => format!("-0x{}", abs),
+            None => format!("0x{}", hex),
+        }
+    }
+
+    fn to_decimal_string(&self) -> String {
+        self.to_string()
+    }
+
+    fn from_hex_string(value: &str) -> Result<Self, String> {
+        let (sign, value) = match value.strip_prefix('-') {
+            Some(value) => ("-", value),
+            None => ("", value),
+        };
+        let digits = strip_hex_prefix(value)?;
+        I256::from_hex_str(&format!("{}{}", sign, digits)).map_err(|err| err.to_string())
+    }
+
+    fn from_decimal_string(value: &str) -> Result<Self, String> {
+        I256::from_dec_str(value).map_err(|err| err.to_string())
+    }
+}
+
+fn strip_hex_prefix(value: &str) -> Result<&str, String> {
+    value
+        .strip_prefix("0x")
+        .ok_or_else(|| format!("hexadecimal number '{}' is missing the 0x prefix", value))
+}
+
+/// Serializes numbers as `0x`-prefixed hexadecimal strings, for example
+/// `"0x2a"`.
+pub mod hex {
+    use super::Number;
+    use serde::de::Error as _;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    /// Serializes a number as a hexadecimal string.
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Number,
+        S: Serializer,
+    {
+        serializer.serialize_str(&value.to_hex_string())
+    }
+
+    /// Deserializes a number from a hexadecimal string.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: Number,
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        T::from_hex_string(&value).map_err(D::Error::custom)
+    }
+}
+
+/// Serializes numbers as decimal strings, for example `"42"`.
+pub mod decimal {
+    use super::Number;
+    use serde::de::Error as _;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    /// Serializes a number as a decimal string.
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Number,
+        S: Serializer,
+    {
+        serializer.serialize_str(&value.to_decimal_string())
+    }
+
+    /// Deserializes a number from a decimal string.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: Number,
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        T::from_decimal_string(&value).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+
+    #[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+    struct Data {
+        #[serde(with = "hex")]
+        hex: I256,
+        #[serde(with = "decimal")]
+        decimal: U256,
+    }
+
+    #[test]
+    fn roundtrip() {
+        let data = Data {
+            hex: I256::from(-42),
+            decimal: U256::MAX,
+        };
+        let json = json!({
+            "hex": "-0x2a",
+            "decimal": U256::MAX.to_string(),
+        });
+
+        assert_eq!(serde_json::to_value(&data).unwrap(), json);
+        assert_eq!(serde_json::from_value::<Data>(json).unwrap(), data);
+    }
+
+    #[test]
+    fn invalid_numbers() {
+        assert!(U256::from_hex_string("2a").is_err());
+        assert!(U256::from_decimal_string("0x2a").is_err());
+        assert!(I256::from_decimal_string(&U256::MAX.to_string()).is_err());
+        assert_eq!(U256::from_hex_string("0x2a"), Ok(U256::from(42)));
+    }
+}
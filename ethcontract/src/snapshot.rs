@@ -0,0 +1,322 @@
+//! Consistent snapshots of contract state at a single block.
+//!
+//! A [`Snapshot`] collects view method calls across any number of contracts,
+//! executes them as a single batch pinned to one block, and returns the
+//! results keyed by name. This is useful for indexers that need to read a
+//! consistent view of several contracts at a block boundary.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # async fn run(
+//! #     web3: ethcontract::Web3<ethcontract::dyns::DynTransport>,
+//! #     token: ethcontract::Instance<ethcontract::dyns::DynTransport>,
+//! # ) {
+//! use ethcontract::prelude::*;
+//! use ethcontract::snapshot::Snapshot;
+//!
+//! let owner = Address::repeat_byte(0x42);
+//! let mut snapshot = Snapshot::new(web3, BlockNumber::Number(17_000_000.into()).into());
+//! snapshot
+//!     .add_method::<_, _, U256>("supply", &token, [0x18, 0x16, 0x0d, 0xdd], ())
+//!     .unwrap();
+//! snapshot
+//!     .add_method::<_, _, U256>("balance", &token, [0x70, 0xa0, 0x82, 0x31], (owner,))
+//!     .unwrap();
+//!
+//! let values = snapshot.execute().await.unwrap();
+//! let supply: U256 = values.get("supply").unwrap();
+//! # }
+//! ```
+
+use crate::batch::CallBatch;
+use crate::contract::{Instance, Signature, ViewMethodBuilder};
+use crate::errors::MethodError;
+use crate::tokens::Tokenize;
+use ethcontract_common::abi::{Result as AbiResult, Token};
+use futures::future::{self, BoxFuture};
+use futures::FutureExt as _;
+use std::collections::BTreeMap;
+use web3::api::Web3;
+use web3::types::BlockId;
+use web3::{BatchTransport, Transport};
+
+/// A view method call that is added to the batch when the snapshot is
+/// executed, returning the future of its tokenized result.
+type QueuedCall<T> =
+    Box<dyn FnOnce(&mut CallBatch<T>) -> BoxFuture<'static, Result<Token, MethodError>>>;
+
+/// A set of view method calls to execute as a single batch at one block.
+#[must_use = "snapshots do nothing unless you `.execute()` them"]
+pub struct Snapshot<T: BatchTransport> {
+    transport: T,
+    block: BlockId,
+    batch_size: usize,
+    calls: Vec<(String, QueuedCall<T>)>,
+}
+
+impl<T: BatchTransport + 'static> Snapshot<T> {
+    /// Creates an empty snapshot of contract state at the specified block.
+    ///
+    /// Note that the block should be specified by number or hash for the
+    /// snapshot to be consistent, as the state at tags such as `latest` may
+    /// change between batches or while the node processes the batch.
+    pub fn new(web3: Web3<T>, block: BlockId) -> Self {
+        Snapshot {
+            transport: web3.transport().clone(),
+            block,
+            batch_size: usize::MAX,
+            calls: Vec::new(),
+        }
+    }
+
+    /// Returns the block the snapshot is taken at.
+    pub fn block(&self) -> BlockId {
+        self.block
+    }
+
+    /// Sets the maximum number of calls sent to the node in a single batch
+    /// request, for nodes that limit the size of batches. By default, all
+    /// calls are sent in one batch.
+    pub fn batch_size(mut self, value: usize) -> Self {
+        self.batch_size = value;
+        self
+    }
+
+    /// Adds a view method call to the snapshot under the specified key. The
+    /// call is executed at the snapshot's block, regardless of the block that
+    /// was set on the method. Adding a call with an existing key replaces the
+    /// previous call.
+    pub fn add<M, R>(&mut self, key: impl Into<String>, method: ViewMethodBuilder<M, R>)
+    where
+        M: Transport + 'static,
+        R: Tokenize + Send + 'static,
+    {
+        let key = key.into();
+        self.calls.retain(|(existing, _)| *existing != key);
+        let method = method.block(self.block);
+        self.calls.push((
+            key,
+            Box::new(move |batch| {
+                method
+                    .batch_call(batch)
+                    .map(|result| result.map(R::into_token))
+                    .boxed()
+            }),
+        ));
+    }
+
+    /// Adds a call to a view method of a contract instance, identified by its
+    /// signature, to the snapshot under the specified key.
+    pub fn add_method<M, P, R>(
+        &mut self,
+        key: impl Into<String>,
+        instance: &Instance<M>,
+        signature: impl Into<Signature<P, R>>,
+        params: P,
+    ) -> AbiResult<()>
+    where
+        M: Transport + 'static,
+        P: Tokenize,
+        R: Tokenize + Send + 'static,
+    {
+        let method = instance.view_method(signature, params)?;
+        self.add(key, method);
+        Ok(())
+    }
+
+    /// Returns the number of calls in the snapshot.
+    pub fn len(&self) -> usize {
+        self.calls.len()
+    }
+
+    /// Returns `true` if the snapshot has no calls.
+    pub fn is_empty(&self) -> bool {
+        self.calls.is_empty()
+    }
+
+    /// Executes all calls of the snapshot, returning their results keyed by
+    /// name. Fails with the error of the first failed call, so that a
+    /// successful snapshot always contains every value.
+    pub async fn execute(self) -> Result<SnapshotValues, MethodError> {
+        let Snapshot {
+            transport,
+            block,
+            batch_size,
+            calls,
+        } = self;
+        let mut batch = CallBatch::new(transport);
+        let (keys, calls): (Vec<_>, Vec<_>) = calls
+            .into_iter()
+            .map(|(key, call)| (key, call(&mut batch)))
+            .unzip();
+
+        batch.execute_all(batch_size).await;
+        let values = future::join_all(calls)
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(SnapshotValues {
+            block,
+            values: keys.into_iter().zip(values).collect(),
+        })
+    }
+}
+
+/// The results of the calls of an executed [`Snapshot`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct SnapshotValues {
+    block: BlockId,
+    values: BTreeMap<String, Token>,
+}
+
+impl SnapshotValues {
+    /// Returns the block the snapshot was taken at.
+    pub fn block(&self) -> BlockId {
+        self.block
+    }
+
+    /// Returns the value of the call with the specified key, or `None` if
+    /// there is no such call or its value is not of the requested type.
+    pub fn get<R: Tokenize>(&self, key: &str) -> Option<R> {
+        R::from_token(self.token(key)?.clone()).ok()
+    }
+
+    /// Returns the raw token of the call with the specified key.
+    pub fn token(&self, key: &str) -> Option<&Token> {
+        self.values.get(key)
+    }
+
+    /// Returns an iterator over the keys and raw tokens of all calls, ordered
+    /// by key.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Token)> {
+        self.values.iter().map(|(key, token)| (key.as_str(), token))
+    }
+
+    /// Returns the number of values in the snapshot.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns `true` if the snapshot has no values.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::MethodBuilder;
+    use crate::test::prelude::*;
+    use ethcontract_common::abi::ParamType;
+    use web3::types::{Address, BlockNumber, Bytes, U256};
+
+    fn method<T: Transport, R: Tokenize>(
+        web3: &Web3<T>,
+        address: Address,
+        kind: ParamType,
+    ) -> ViewMethodBuilder<T, R> {
+        MethodBuilder::new(
+            web3.clone(),
+            function("value", &[], &[kind]),
+            address,
+            Bytes(vec![0x3f, 0xa4, 0xf2, 0x45]),
+        )
+        .view()
+    }
+
+    #[test]
+    fn snapshots_calls_at_block() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+        let block = BlockId::Number(BlockNumber::Number(42.into()));
+
+        let mut snapshot = Snapshot::new(web3.clone(), block);
+        snapshot.add(
+            "supply",
+            method::<_, U256>(&web3, Address::repeat_byte(1), ParamType::Uint(256))
+                .block(BlockNumber::Latest.into()),
+        );
+        snapshot.add(
+            "paused",
+            method::<_, bool>(&web3, Address::repeat_byte(2), ParamType::Bool),
+        );
+        assert_eq!(snapshot.len(), 2);
+
+        transport.add_response(json!([
+            "0x000000000000000000000000000000000000000000000000000000000000002a",
+            "0x0000000000000000000000000000000000000000000000000000000000000001",
+        ]));
+        let values = snapshot.execute().immediate().unwrap();
+
+        for address in [Address::repeat_byte(1), Address::repeat_byte(2)] {
+            transport.assert_request(
+                "eth_call",
+                &[
+                    json!({ "to": address, "data": "0x3fa4f245" }),
+                    json!("0x2a"),
+                ],
+            );
+        }
+        transport.assert_no_more_requests();
+
+        assert_eq!(values.block(), block);
+        assert_eq!(values.get::<U256>("supply"), Some(42.into()));
+        assert_eq!(values.get::<bool>("paused"), Some(true));
+        assert_eq!(values.get::<bool>("supply"), None);
+        assert_eq!(values.get::<U256>("missing"), None);
+        assert_eq!(
+            values.iter().map(|(key, _)| key).collect::<Vec<_>>(),
+            ["paused", "supply"],
+        );
+    }
+
+    #[test]
+    fn replaced_calls_are_not_executed() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let mut snapshot = Snapshot::new(web3.clone(), BlockNumber::Number(1.into()).into());
+        snapshot.add(
+            "value",
+            method::<_, U256>(&web3, Address::repeat_byte(1), ParamType::Uint(256)),
+        );
+        snapshot.add(
+            "value",
+            method::<_, bool>(&web3, Address::repeat_byte(2), ParamType::Bool),
+        );
+        assert_eq!(snapshot.len(), 1);
+
+        transport.add_response(json!([
+            "0x0000000000000000000000000000000000000000000000000000000000000001",
+        ]));
+        let values = snapshot.execute().immediate().unwrap();
+
+        transport.assert_request(
+            "eth_call",
+            &[
+                json!({ "to": Address::repeat_byte(2), "data": "0x3fa4f245" }),
+                json!("0x1"),
+            ],
+        );
+        transport.assert_no_more_requests();
+        assert_eq!(values.get::<bool>("value"), Some(true));
+    }
+
+    #[test]
+    fn failed_calls_fail_snapshot() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let mut snapshot = Snapshot::new(web3.clone(), BlockNumber::Number(1.into()).into());
+        snapshot.add(
+            "supply",
+            method::<_, U256>(&web3, Address::repeat_byte(1), ParamType::Uint(256)),
+        );
+
+        transport.add_response(json!(["0x"]));
+        assert!(snapshot.execute().immediate().is_err());
+    }
+}
@@ -1,6 +1,8 @@
 //! Prelude module with common types used for unit tests.
 
 pub use crate::test::transport::TestTransport;
+use ethcontract_common::abi::{Function, Param, ParamType, StateMutability};
+use ethcontract_common::Abi;
 use futures::future::FutureExt;
 pub use serde_json::json;
 use std::future::Future;
@@ -24,3 +26,37 @@ impl<F: Future + Sized> FutureTestExt for F {
             .expect("future did not resolve immediately")
     }
 }
+
+/// Creates a non-payable function with the specified named inputs and
+/// unnamed outputs.
+pub fn function(name: &str, inputs: &[(&str, ParamType)], outputs: &[ParamType]) -> Function {
+    let param = |name: &str, kind: &ParamType| Param {
+        name: name.to_owned(),
+        kind: kind.clone(),
+        internal_type: None,
+    };
+
+    #[allow(deprecated)]
+    Function {
+        name: name.to_owned(),
+        inputs: inputs
+            .iter()
+            .map(|(name, kind)| param(name, kind))
+            .collect(),
+        outputs: outputs.iter().map(|kind| param("", kind)).collect(),
+        constant: None,
+        state_mutability: StateMutability::NonPayable,
+    }
+}
+
+/// Creates an ABI with the specified functions.
+pub fn abi(functions: Vec<Function>) -> Abi {
+    let mut abi = Abi::default();
+    for function in functions {
+        abi.functions
+            .entry(function.name.clone())
+            .or_default()
+            .push(function);
+    }
+    abi
+}
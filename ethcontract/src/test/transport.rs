@@ -4,9 +4,11 @@
 use jsonrpc_core::{Call, Value};
 use std::collections::VecDeque;
 use std::sync::{Arc, Mutex};
+use web3::api::SubscriptionId;
 use web3::futures::future::{self, Ready};
+use web3::futures::stream::{self, Iter};
 use web3::helpers;
-use web3::{error::Error, BatchTransport};
+use web3::{error::Error, BatchTransport, DuplexTransport};
 use web3::{RequestId, Transport};
 
 /// Type alias for request method and value pairs
@@ -16,7 +18,8 @@ type Requests = Vec<(String, Vec<Value>)>;
 struct Inner {
     asserted: usize,
     requests: Requests,
-    responses: VecDeque<Value>,
+    responses: VecDeque<Result<Value, Error>>,
+    notifications: Vec<Value>,
 }
 
 /// Test transport
@@ -38,7 +41,7 @@ impl Transport for TestTransport {
     fn send(&self, id: RequestId, request: Call) -> Self::Out {
         let response = self.inner.lock().unwrap().responses.pop_front();
         match response {
-            Some(response) => future::ok(response),
+            Some(response) => future::ready(response),
             None => {
                 println!("Unexpected request (id: {:?}): {:?}", id, request);
                 future::err(Error::Unreachable)
@@ -78,6 +81,19 @@ impl BatchTransport for TestTransport {
     }
 }
 
+impl DuplexTransport for TestTransport {
+    type NotificationStream = Iter<std::vec::IntoIter<Value>>;
+
+    fn subscribe(&self, _: SubscriptionId) -> Result<Self::NotificationStream, Error> {
+        let notifications = std::mem::take(&mut self.inner.lock().unwrap().notifications);
+        Ok(stream::iter(notifications))
+    }
+
+    fn unsubscribe(&self, _: SubscriptionId) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
 impl TestTransport {
     /// Create a new test transport instance.
     pub fn new() -> Self {
@@ -87,7 +103,19 @@ impl TestTransport {
     /// Add a response to an eventual request.
     pub fn add_response(&mut self, value: Value) {
         let mut inner = self.inner.lock().unwrap();
-        inner.responses.push_back(value);
+        inner.responses.push_back(Ok(value));
+    }
+
+    /// Add a notification for the next subscription.
+    pub fn add_notification(&mut self, value: Value) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.notifications.push(value);
+    }
+
+    /// Add an error response to an eventual request.
+    pub fn add_error(&mut self, error: Error) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.responses.push_back(Err(error));
     }
 
     /// Assert that a request was made.
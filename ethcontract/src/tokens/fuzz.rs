@@ -0,0 +1,298 @@
+//! Generation of arbitrary tokens for ABI parameter types, allowing code that
+//! encodes and decodes contract method parameters, for example tests against
+//! `ethcontract-mock` contracts, to be property tested with inputs derived
+//! from a contract's ABI.
+//!
+//! Generated tokens are always valid for their parameter type, that is
+//! integers fit in their number of bits, and fixed bytes and fixed arrays have
+//! their exact length. Strategies for `proptest` are available with the
+//! `proptest` feature, and generators for `quickcheck` with the
+//! `quickcheck` feature.
+
+use ethcontract_common::abi::{Function, ParamType, Token};
+use web3::types::U256;
+
+/// Size bounds for generated tokens of dynamically sized types.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct FuzzConfig {
+    /// The maximum length of generated dynamic arrays.
+    pub max_array_len: usize,
+    /// The maximum length of generated dynamic byte arrays.
+    pub max_bytes_len: usize,
+    /// The maximum length of generated strings, in characters.
+    pub max_string_len: usize,
+}
+
+impl FuzzConfig {
+    /// Sets the maximum length of generated dynamic arrays.
+    pub fn max_array_len(mut self, value: usize) -> Self {
+        self.max_array_len = value;
+        self
+    }
+
+    /// Sets the maximum length of generated dynamic byte arrays.
+    pub fn max_bytes_len(mut self, value: usize) -> Self {
+        self.max_bytes_len = value;
+        self
+    }
+
+    /// Sets the maximum length of generated strings, in characters.
+    pub fn max_string_len(mut self, value: usize) -> Self {
+        self.max_string_len = value;
+        self
+    }
+}
+
+impl Default for FuzzConfig {
+    fn default() -> Self {
+        FuzzConfig {
+            max_array_len: 4,
+            max_bytes_len: 64,
+            max_string_len: 32,
+        }
+    }
+}
+
+/// Returns the parameter types of the inputs of a function.
+fn input_kinds(function: &Function) -> Vec<ParamType> {
+    function
+        .inputs
+        .iter()
+        .map(|param| param.kind.clone())
+        .collect()
+}
+
+/// Converts 32 arbitrary bytes into an integer value that fits in the
+/// specified number of bits. Signed values are sign extended to 256 bits.
+fn int_value(bytes: [u8; 32], bits: usize, signed: bool) -> U256 {
+    let value = U256::from_big_endian(&bytes);
+    if bits >= 256 {
+        return value;
+    }
+
+    let mask = (U256::one() << bits) - 1;
+    let value = value & mask;
+    if signed && value.bit(bits - 1) {
+        value | !mask
+    } else {
+        value
+    }
+}
+
+/// Returns a `proptest` strategy generating tokens for a parameter type.
+#[cfg(any(feature = "proptest", test))]
+pub fn token_strategy(
+    kind: &ParamType,
+    config: &FuzzConfig,
+) -> proptest::strategy::BoxedStrategy<Token> {
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    match kind {
+        ParamType::Address => any::<[u8; 20]>()
+            .prop_map(|bytes| Token::Address(bytes.into()))
+            .boxed(),
+        ParamType::Bytes => vec(any::<u8>(), 0..=config.max_bytes_len)
+            .prop_map(Token::Bytes)
+            .boxed(),
+        ParamType::Int(bits) => {
+            let bits = *bits;
+            any::<[u8; 32]>()
+                .prop_map(move |bytes| Token::Int(int_value(bytes, bits, true)))
+                .boxed()
+        }
+        ParamType::Uint(bits) => {
+            let bits = *bits;
+            any::<[u8; 32]>()
+                .prop_map(move |bytes| Token::Uint(int_value(bytes, bits, false)))
+                .boxed()
+        }
+        ParamType::Bool => any::<bool>().prop_map(Token::Bool).boxed(),
+        ParamType::String => vec(any::<char>(), 0..=config.max_string_len)
+            .prop_map(|chars| Token::String(chars.into_iter().collect()))
+            .boxed(),
+        ParamType::Array(kind) => vec(token_strategy(kind, config), 0..=config.max_array_len)
+            .prop_map(Token::Array)
+            .boxed(),
+        ParamType::FixedBytes(len) => vec(any::<u8>(), *len).prop_map(Token::FixedBytes).boxed(),
+        ParamType::FixedArray(kind, len) => vec(token_strategy(kind, config), *len)
+            .prop_map(Token::FixedArray)
+            .boxed(),
+        ParamType::Tuple(kinds) => tokens_strategy(kinds, config)
+            .prop_map(Token::Tuple)
+            .boxed(),
+    }
+}
+
+/// Returns a `proptest` strategy generating tokens for a list of parameter
+/// types.
+#[cfg(any(feature = "proptest", test))]
+pub fn tokens_strategy(
+    kinds: &[ParamType],
+    config: &FuzzConfig,
+) -> proptest::strategy::BoxedStrategy<Vec<Token>> {
+    use proptest::strategy::Strategy as _;
+
+    kinds
+        .iter()
+        .map(|kind| token_strategy(kind, config))
+        .collect::<Vec<_>>()
+        .boxed()
+}
+
+/// Returns a `proptest` strategy generating the input tokens of a function.
+#[cfg(any(feature = "proptest", test))]
+pub fn inputs_strategy(
+    function: &Function,
+    config: &FuzzConfig,
+) -> proptest::strategy::BoxedStrategy<Vec<Token>> {
+    tokens_strategy(&input_kinds(function), config)
+}
+
+/// Generates an arbitrary token for a parameter type with `quickcheck`.
+#[cfg(any(feature = "quickcheck", test))]
+pub fn arbitrary_token(kind: &ParamType, config: &FuzzConfig, g: &mut quickcheck::Gen) -> Token {
+    use quickcheck::Arbitrary;
+
+    fn bytes<const N: usize>(g: &mut quickcheck::Gen) -> [u8; N] {
+        let mut bytes = [0; N];
+        for byte in &mut bytes {
+            *byte = u8::arbitrary(g);
+        }
+        bytes
+    }
+
+    fn len(g: &mut quickcheck::Gen, max: usize) -> usize {
+        usize::arbitrary(g) % max.saturating_add(1)
+    }
+
+    match kind {
+        ParamType::Address => Token::Address(bytes::<20>(g).into()),
+        ParamType::Bytes => {
+            let len = len(g, config.max_bytes_len);
+            Token::Bytes((0..len).map(|_| u8::arbitrary(g)).collect())
+        }
+        ParamType::Int(bits) => Token::Int(int_value(bytes(g), *bits, true)),
+        ParamType::Uint(bits) => Token::Uint(int_value(bytes(g), *bits, false)),
+        ParamType::Bool => Token::Bool(bool::arbitrary(g)),
+        ParamType::String => {
+            let len = len(g, config.max_string_len);
+            Token::String((0..len).map(|_| char::arbitrary(g)).collect())
+        }
+        ParamType::Array(kind) => {
+            let len = len(g, config.max_array_len);
+            Token::Array((0..len).map(|_| arbitrary_token(kind, config, g)).collect())
+        }
+        ParamType::FixedBytes(len) => {
+            Token::FixedBytes((0..*len).map(|_| u8::arbitrary(g)).collect())
+        }
+        ParamType::FixedArray(kind, len) => Token::FixedArray(
+            (0..*len)
+                .map(|_| arbitrary_token(kind, config, g))
+                .collect(),
+        ),
+        ParamType::Tuple(kinds) => Token::Tuple(arbitrary_tokens(kinds, config, g)),
+    }
+}
+
+/// Generates arbitrary tokens for a list of parameter types with
+/// `quickcheck`.
+#[cfg(any(feature = "quickcheck", test))]
+pub fn arbitrary_tokens(
+    kinds: &[ParamType],
+    config: &FuzzConfig,
+    g: &mut quickcheck::Gen,
+) -> Vec<Token> {
+    kinds
+        .iter()
+        .map(|kind| arbitrary_token(kind, config, g))
+        .collect()
+}
+
+/// Generates arbitrary input tokens of a function with `quickcheck`.
+#[cfg(any(feature = "quickcheck", test))]
+pub fn arbitrary_inputs(
+    function: &Function,
+    config: &FuzzConfig,
+    g: &mut quickcheck::Gen,
+) -> Vec<Token> {
+    arbitrary_tokens(&input_kinds(function), config, g)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::prelude::function as fixture;
+    use proptest::prelude::*;
+
+    fn function() -> Function {
+        let inputs = [
+            ParamType::Address,
+            ParamType::Bytes,
+            ParamType::Int(24),
+            ParamType::Uint(8),
+            ParamType::String,
+            ParamType::Array(Box::new(ParamType::Tuple(vec![
+                ParamType::Bool,
+                ParamType::FixedBytes(3),
+            ]))),
+            ParamType::FixedArray(Box::new(ParamType::Int(256)), 2),
+        ]
+        .map(|kind| ("", kind));
+
+        fixture("fuzz", &inputs, &[])
+    }
+
+    fn assert_valid_inputs(function: &Function, config: &FuzzConfig, tokens: &[Token]) {
+        assert!(Token::types_check(tokens, &input_kinds(function)));
+        assert_eq!(
+            function
+                .decode_input(&ethcontract_common::abi::encode(tokens))
+                .unwrap(),
+            tokens
+        );
+
+        for token in tokens {
+            match token {
+                Token::Bytes(bytes) => assert!(bytes.len() <= config.max_bytes_len),
+                Token::String(string) => {
+                    assert!(string.chars().count() <= config.max_string_len)
+                }
+                Token::Array(tokens) => assert!(tokens.len() <= config.max_array_len),
+                _ => {}
+            }
+        }
+    }
+
+    #[test]
+    fn int_values_fit_in_bits() {
+        assert_eq!(int_value([0xff; 32], 8, false), U256::from(0xff));
+        assert_eq!(int_value([0xff; 32], 8, true), U256::MAX);
+        assert_eq!(int_value([0x7f; 32], 8, true), U256::from(0x7f));
+        assert_eq!(int_value([0x80; 32], 16, true), !U256::from(0x7f7f));
+        assert_eq!(int_value([0xff; 32], 256, false), U256::MAX);
+    }
+
+    proptest! {
+        #[test]
+        fn proptest_inputs_are_valid(
+            tokens in inputs_strategy(&function(), &FuzzConfig::default()),
+        ) {
+            assert_valid_inputs(&function(), &FuzzConfig::default(), &tokens);
+        }
+    }
+
+    #[test]
+    fn quickcheck_inputs_are_valid() {
+        let function = function();
+        let config = FuzzConfig::default()
+            .max_array_len(2)
+            .max_bytes_len(8)
+            .max_string_len(4);
+        let mut g = quickcheck::Gen::new(100);
+        for _ in 0..100 {
+            let tokens = arbitrary_inputs(&function, &config, &mut g);
+            assert_valid_inputs(&function, &config, &tokens);
+        }
+    }
+}
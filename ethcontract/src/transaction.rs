@@ -1,23 +1,46 @@
 //! Implementation for setting up, signing, estimating gas and sending
 //! transactions on the Ethereum network.
 
+mod access_list;
+pub mod blob;
 mod build;
 pub mod confirm;
 pub mod gas_price;
+mod kind;
 #[cfg(feature = "aws-kms")]
 pub mod kms;
+mod nonce;
 mod send;
+mod signature;
+pub mod signer;
+mod signing;
+pub mod threshold;
+pub mod trace;
 
+pub use self::access_list::{CreatedAccessList, IntoAccessList};
+pub use self::blob::{BlobError, BlobSidecar};
 pub use self::build::Transaction;
-use self::confirm::ConfirmParams;
-pub use self::gas_price::GasPrice;
+use self::confirm::{ConfirmParams, ReplacementPolicy};
+pub use self::gas_price::{GasPrice, GasPriceStrategy};
+pub use self::kind::TransactionType;
+pub use self::nonce::NonceManager;
 pub use self::send::TransactionResult;
+pub use self::signature::Signature;
+pub use self::signer::Signer;
+pub use self::trace::{simulate_bundle, CallTrace, Tracer};
 use crate::errors::ExecutionError;
 use crate::secret::{Password, PrivateKey};
+use std::future::Future;
+use std::sync::Arc;
 use web3::api::Web3;
-use web3::types::{AccessList, Address, Bytes, CallRequest, TransactionCondition, U256};
+use web3::types::{AccessList, Address, Bytes, CallRequest, TransactionCondition, H520, U256};
 use web3::Transport;
 
+/// The default maximum size of a transaction accepted into the transaction
+/// pool of Geth and most other node implementations. Transactions with
+/// calldata close to or above this size are rejected by most providers.
+pub const TX_POOL_MAX_SIZE: usize = 128 * 1024;
+
 /// The account type used for signing the transaction.
 #[derive(Clone, Debug)]
 pub enum Account {
@@ -31,6 +54,12 @@ pub enum Account {
     /// Sign using AWS KMS account and optionally specified chain ID.
     #[cfg(feature = "aws-kms")]
     Kms(kms::Account, Option<u64>),
+    /// Sign using a threshold (t-of-n) signer set and optionally specified
+    /// chain ID.
+    Threshold(threshold::Account, Option<u64>),
+    /// Sign using a custom signer implementation, such as a hardware wallet
+    /// or a remote signing service. The chain ID is queried from the node.
+    Signer(Arc<dyn Signer>),
 }
 
 impl Account {
@@ -42,8 +71,58 @@ impl Account {
             Account::Offline(key, _) => key.public_address(),
             #[cfg(feature = "aws-kms")]
             Account::Kms(kms, _) => kms.public_address(),
+            Account::Threshold(threshold, _) => threshold.public_address(),
+            Account::Signer(signer) => signer.address(),
         }
     }
+
+    /// Signs a message prefixed with `"\x19Ethereum Signed Message:\n"` and
+    /// its length as specified by EIP-191.
+    ///
+    /// Local accounts are signed for by the node with `eth_sign`, and locked
+    /// accounts with `personal_sign` using their password. All other accounts
+    /// sign the message without interacting with the node. The `v` value of
+    /// the returned signature is either `27` or `28`.
+    pub async fn sign_message<T: Transport>(
+        &self,
+        web3: &Web3<T>,
+        message: &[u8],
+    ) -> Result<Signature, ExecutionError> {
+        let signature = match self {
+            Account::Local(address, _) => {
+                let signature = web3.eth().sign(*address, Bytes(message.to_vec())).await?;
+                node_signature(signature)
+            }
+            Account::Locked(address, password, _) => {
+                let signature = web3
+                    .personal()
+                    .sign(Bytes(message.to_vec()), *address, password)
+                    .await?;
+                node_signature(signature)
+            }
+            Account::Offline(key, _) => Signer::sign_message(key, message).await?,
+            #[cfg(feature = "aws-kms")]
+            Account::Kms(account, _) => Signer::sign_message(account, message).await?,
+            Account::Threshold(account, _) => Signer::sign_message(account, message).await?,
+            Account::Signer(signer) => signer.sign_message(message).await?,
+        };
+
+        Ok(signature)
+    }
+}
+
+/// Converts a 65-byte signature returned by the node, normalizing `v` to
+/// either `27` or `28` as some nodes return the recovery ID instead.
+fn node_signature(signature: H520) -> Signature {
+    let signature = Signature::from(signature);
+    Signature {
+        v: if signature.v < 27 {
+            signature.v + 27
+        } else {
+            signature.v
+        },
+        ..signature
+    }
 }
 
 /// The condition on which a transaction's `SendFuture` gets resolved.
@@ -85,6 +164,9 @@ pub struct TransactionBuilder<T: Transport> {
     pub gas: Option<U256>,
     /// Optional gas price to use for transaction. Defaults to None.
     pub gas_price: Option<GasPrice>,
+    /// Optional strategy for determining the gas price when the transaction
+    /// is sent. Takes precedence over `gas_price`.
+    pub gas_price_strategy: Option<GasPriceStrategy<T>>,
     /// The ETH value to send with the transaction. Defaults to 0.
     pub value: Option<U256>,
     /// The data for the transaction. Defaults to empty data.
@@ -92,11 +174,27 @@ pub struct TransactionBuilder<T: Transport> {
     /// Optional nonce to use. Defaults to the signing account's current
     /// transaction count.
     pub nonce: Option<U256>,
+    /// Optional nonce manager shared between transactions for assigning
+    /// nonces when no explicit nonce was specified.
+    pub nonce_manager: Option<Arc<NonceManager>>,
     /// Optional resolve conditions. Defaults to waiting the transaction to be
     /// mined without any extra confirmation blocks.
     pub resolve: Option<ResolveCondition>,
     /// Access list
     pub access_list: Option<AccessList>,
+    /// Whether to create an access list with `eth_createAccessList` when
+    /// building the transaction if none was specified. Defaults to false.
+    pub populate_access_list: bool,
+    /// Optional maximum fee per blob gas for an EIP-4844 blob transaction.
+    /// Defaults to twice the current blob base fee.
+    pub max_fee_per_blob_gas: Option<U256>,
+    /// Optional blob sidecar. Specifying blobs makes this an EIP-4844 blob
+    /// transaction.
+    pub blob_sidecar: Option<BlobSidecar>,
+    /// Optional maximum size of the transaction calldata in bytes. Building
+    /// a transaction with larger calldata fails before any requests are sent
+    /// to the node. Defaults to no limit.
+    pub max_calldata_size: Option<usize>,
 }
 
 impl<T: Transport> TransactionBuilder<T> {
@@ -108,11 +206,17 @@ impl<T: Transport> TransactionBuilder<T> {
             to: None,
             gas: None,
             gas_price: None,
+            gas_price_strategy: None,
             value: None,
             data: None,
             nonce: None,
+            nonce_manager: None,
             resolve: None,
             access_list: None,
+            populate_access_list: false,
+            max_fee_per_blob_gas: None,
+            blob_sidecar: None,
+            max_calldata_size: None,
         }
     }
 
@@ -141,6 +245,55 @@ impl<T: Transport> TransactionBuilder<T> {
     /// price will be used.
     pub fn gas_price(mut self, value: GasPrice) -> Self {
         self.gas_price = Some(value);
+        self.gas_price_strategy = None;
+        self
+    }
+
+    /// Specify a strategy for determining the gas price at the moment the
+    /// transaction is sent, replacing any previously specified gas price.
+    ///
+    /// The strategy is consulted again before sending each replacement
+    /// transaction, in which case the replacement uses the higher of the
+    /// strategy's gas price and the gas price bumped according to the
+    /// replacement policy.
+    pub fn gas_price_strategy(mut self, value: GasPriceStrategy<T>) -> Self {
+        self.gas_price_strategy = Some(value);
+        self
+    }
+
+    /// Specify an async closure for determining the gas price at the moment
+    /// the transaction is sent. See [`gas_price_strategy`] for more details.
+    ///
+    /// [`gas_price_strategy`]: Self::gas_price_strategy
+    pub fn gas_price_with<F, Fut>(self, strategy: F) -> Self
+    where
+        F: Fn(Web3<T>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<GasPrice, ExecutionError>> + Send + 'static,
+    {
+        self.gas_price_strategy(GasPriceStrategy::new(strategy))
+    }
+
+    /// Specify the maximum fee per gas for an EIP-1559 transaction. If a
+    /// legacy gas price or no gas price was specified, then the maximum
+    /// priority fee per gas defaults to the same value.
+    pub fn max_fee_per_gas(mut self, value: U256) -> Self {
+        self.gas_price = Some(match self.gas_price {
+            Some(gas_price) => gas_price.with_max_fee_per_gas(value),
+            None => (value, value).into(),
+        });
+        self.gas_price_strategy = None;
+        self
+    }
+
+    /// Specify the maximum priority fee per gas for an EIP-1559 transaction.
+    /// If a legacy gas price or no gas price was specified, then the maximum
+    /// fee per gas defaults to the same value.
+    pub fn max_priority_fee_per_gas(mut self, value: U256) -> Self {
+        self.gas_price = Some(match self.gas_price {
+            Some(gas_price) => gas_price.with_max_priority_fee_per_gas(value),
+            None => (value, value).into(),
+        });
+        self.gas_price_strategy = None;
         self
     }
 
@@ -165,6 +318,15 @@ impl<T: Transport> TransactionBuilder<T> {
         self
     }
 
+    /// Specify a nonce manager for assigning a nonce to the transaction when
+    /// it is sent, if no nonce was specified. This prevents nonce collisions
+    /// when concurrently sending transactions from the same account. Note
+    /// that the nonce manager is only used when a `from` account is specified.
+    pub fn nonce_manager(mut self, value: Arc<NonceManager>) -> Self {
+        self.nonce_manager = Some(value);
+        self
+    }
+
     /// Specify the resolve condition, if not specified will default to waiting
     /// for the transaction to be mined (but not confirmed by any extra blocks).
     pub fn resolve(mut self, value: ResolveCondition) -> Self {
@@ -173,8 +335,44 @@ impl<T: Transport> TransactionBuilder<T> {
     }
 
     /// Specify the access list for the transaction, if not specified no access list will be used.
-    pub fn access_list(mut self, value: AccessList) -> Self {
-        self.access_list = Some(value);
+    pub fn access_list(mut self, value: impl IntoAccessList) -> Self {
+        self.access_list = Some(value.into_access_list());
+        self
+    }
+
+    /// Specify whether to create an access list with `eth_createAccessList`
+    /// when building the transaction, if no access list was specified.
+    pub fn populate_access_list(mut self, value: bool) -> Self {
+        self.populate_access_list = value;
+        self
+    }
+
+    /// Specify the maximum fee per blob gas for an EIP-4844 blob transaction,
+    /// if not specified then twice the current blob base fee will be used.
+    pub fn max_fee_per_blob_gas(mut self, value: U256) -> Self {
+        self.max_fee_per_blob_gas = Some(value);
+        self
+    }
+
+    /// Specify the blobs to attach to the transaction, making it an EIP-4844
+    /// blob transaction.
+    ///
+    /// Blob transactions must have a recipient and are always sent as raw
+    /// transactions, so they can only be signed by offline, AWS KMS or
+    /// threshold accounts.
+    pub fn blob_sidecar(mut self, value: BlobSidecar) -> Self {
+        self.blob_sidecar = Some(value);
+        self
+    }
+
+    /// Specify the maximum size of the transaction calldata in bytes, if not
+    /// specified then calldata of any size will be sent to the node.
+    ///
+    /// Providers often reject or silently drop transactions with large
+    /// calldata, so this allows failing early with a clear error instead.
+    /// [`TX_POOL_MAX_SIZE`] is the limit used by most node transaction pools.
+    pub fn max_calldata_size(mut self, value: usize) -> Self {
+        self.max_calldata_size = Some(value);
         self
     }
 
@@ -195,33 +393,76 @@ impl<T: Transport> TransactionBuilder<T> {
         self
     }
 
+    /// Specify the policy for replacing the transaction with one with a higher
+    /// gas price if it does not get mined in time. This is a utility method
+    /// for specifying the resolve condition.
+    pub fn replacement(mut self, value: ReplacementPolicy) -> Self {
+        let params = match self.resolve {
+            Some(ResolveCondition::Confirmed(params)) => params,
+            _ => ConfirmParams::default(),
+        };
+        self.resolve = Some(ResolveCondition::Confirmed(params.replacement(Some(value))));
+        self
+    }
+
     /// Estimate the gas required for this transaction.
     pub async fn estimate_gas(self) -> Result<U256, ExecutionError> {
-        let from = self.from.map(|account| account.address());
-        let resolved_gas_price = self
-            .gas_price
-            .map(|gas_price| gas_price.resolve_for_transaction())
-            .unwrap_or_default();
+        self.check_calldata_size()?;
         self.web3
             .eth()
-            .estimate_gas(
-                CallRequest {
-                    from,
-                    to: self.to,
-                    gas: None,
-                    gas_price: resolved_gas_price.gas_price,
-                    value: self.value,
-                    data: self.data.clone(),
-                    transaction_type: resolved_gas_price.transaction_type,
-                    access_list: self.access_list,
-                    max_fee_per_gas: resolved_gas_price.max_fee_per_gas,
-                    max_priority_fee_per_gas: resolved_gas_price.max_priority_fee_per_gas,
-                },
-                None,
-            )
+            .estimate_gas(self.call_request(), None)
             .await
             .map_err(From::from)
     }
+
+    /// Create an access list for this transaction with `eth_createAccessList`.
+    pub async fn create_access_list(&self) -> Result<CreatedAccessList, ExecutionError> {
+        access_list::create_access_list(&self.web3, self.call_request()).await
+    }
+
+    /// Resolves the gas price with the gas price strategy, if one was
+    /// specified. The strategy is removed from the builder, so that it is only
+    /// consulted once.
+    async fn resolve_gas_price_strategy(&mut self) -> Result<(), ExecutionError> {
+        if let Some(strategy) = self.gas_price_strategy.take() {
+            self.gas_price = Some(strategy.gas_price(&self.web3).await?);
+        }
+        Ok(())
+    }
+
+    /// Verifies that the transaction calldata does not exceed the configured
+    /// maximum size.
+    fn check_calldata_size(&self) -> Result<(), ExecutionError> {
+        let size = self
+            .data
+            .as_ref()
+            .map(|data| data.0.len())
+            .unwrap_or_default();
+        match self.max_calldata_size {
+            Some(limit) if size > limit => Err(ExecutionError::CalldataTooLarge(size, limit)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns a call request with the transaction parameters.
+    fn call_request(&self) -> CallRequest {
+        let resolved_gas_price = self
+            .gas_price
+            .map(|gas_price| gas_price.resolve_for_transaction())
+            .unwrap_or_default();
+        CallRequest {
+            from: self.from.as_ref().map(|account| account.address()),
+            to: self.to,
+            gas: None,
+            gas_price: resolved_gas_price.gas_price,
+            value: self.value,
+            data: self.data.clone(),
+            transaction_type: resolved_gas_price.transaction_type,
+            access_list: self.access_list.clone(),
+            max_fee_per_gas: resolved_gas_price.max_fee_per_gas,
+            max_priority_fee_per_gas: resolved_gas_price.max_priority_fee_per_gas,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -231,6 +472,59 @@ mod tests {
     use hex_literal::hex;
     use web3::types::{AccessListItem, H2048, H256};
 
+    #[test]
+    fn account_sign_message_offline() {
+        let transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let key = key!("0x0102030405060708091011121314151617181920212223242526272829303132");
+        let signature = Account::Offline(key.clone(), None)
+            .sign_message(&web3, b"hello")
+            .immediate()
+            .expect("signing failed");
+
+        assert_eq!(
+            signature,
+            Signer::sign_message(&key, b"hello").immediate().unwrap()
+        );
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn account_sign_message_with_node() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let address = addr!("0x9876543210987654321098765432109876543210");
+        let r = H256::repeat_byte(0x11);
+        let s = H256::repeat_byte(0x22);
+        let node_signature = |v: u8| {
+            let mut bytes = [r.as_bytes(), s.as_bytes()].concat();
+            bytes.push(v);
+            json!(Bytes(bytes))
+        };
+
+        transport.add_response(node_signature(0x1c));
+        let signature = Account::Local(address, None)
+            .sign_message(&web3, b"hello")
+            .immediate()
+            .expect("signing failed");
+        assert_eq!(signature, Signature { r, s, v: 28 });
+        transport.assert_request("eth_sign", &[json!(address), json!("0x68656c6c6f")]);
+
+        transport.add_response(node_signature(0));
+        let signature = Account::Locked(address, "hunter2".into(), None)
+            .sign_message(&web3, b"hello")
+            .immediate()
+            .expect("signing failed");
+        assert_eq!(signature, Signature { r, s, v: 27 });
+        transport.assert_request(
+            "personal_sign",
+            &[json!("0x68656c6c6f"), json!(address), json!("hunter2")],
+        );
+        transport.assert_no_more_requests();
+    }
+
     #[test]
     fn tx_builder_estimate_gas() {
         let mut transport = TestTransport::new();
@@ -306,6 +600,149 @@ mod tests {
         assert_eq!(tx.hash(), hash);
     }
 
+    #[test]
+    fn tx_send_with_nonce_manager() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let from = addr!("0x9876543210987654321098765432109876543210");
+        let hash = hash!("0x4242424242424242424242424242424242424242424242424242424242424242");
+        let manager = Arc::new(NonceManager::new());
+        let send = |web3| {
+            TransactionBuilder::new(web3)
+                .from(Account::Local(from, None))
+                .gas(1.into())
+                .gas_price(2.0.into())
+                .nonce_manager(manager.clone())
+                .resolve(ResolveCondition::Pending)
+                .send()
+                .immediate()
+        };
+
+        transport.add_response(json!("0x2a")); // transaction count
+        transport.add_response(json!(hash)); // transaction hash
+        send(web3.clone()).expect("transaction success");
+        transport.add_error(web3::Error::Unreachable); // transaction error
+        send(web3.clone()).expect_err("transaction failure");
+
+        transport.assert_request("eth_getTransactionCount", &[json!(from), json!("pending")]);
+        for nonce in ["0x2a", "0x2b"] {
+            transport.assert_request(
+                "eth_sendTransaction",
+                &[json!({
+                    "from": from,
+                    "gas": "0x1",
+                    "gasPrice": "0x2",
+                    "nonce": nonce,
+                })],
+            );
+        }
+        transport.assert_no_more_requests();
+
+        // the nonce is queried again after a failure
+        assert_eq!(manager.peek(from).immediate(), None);
+    }
+
+    #[test]
+    fn tx_send_oversize_calldata() {
+        let transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let tx = TransactionBuilder::new(web3)
+            .from(Account::Local(Address::repeat_byte(1), None))
+            .data(Bytes(vec![0; 33]))
+            .max_calldata_size(32);
+
+        let err = tx.clone().send().immediate().unwrap_err();
+        assert!(matches!(err, ExecutionError::CalldataTooLarge(33, 32)));
+        assert_eq!(err.code(), crate::errors::ErrorCode::CalldataTooLarge);
+        assert!(matches!(
+            tx.clone().build().immediate(),
+            Err(ExecutionError::CalldataTooLarge(33, 32))
+        ));
+        assert!(matches!(
+            tx.estimate_gas().immediate(),
+            Err(ExecutionError::CalldataTooLarge(33, 32))
+        ));
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn tx_send_local_eip1559() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let from = addr!("0x9876543210987654321098765432109876543210");
+        let to = addr!("0x0123456789012345678901234567890123456789");
+        let hash = hash!("0x4242424242424242424242424242424242424242424242424242424242424242");
+
+        transport.add_response(json!(hash)); // tansaction hash
+        TransactionBuilder::new(web3)
+            .from(Account::Local(from, None))
+            .to(to)
+            .gas(1.into())
+            .max_fee_per_gas(100.into())
+            .max_priority_fee_per_gas(2.into())
+            .resolve(ResolveCondition::Pending)
+            .send()
+            .immediate()
+            .expect("transaction success");
+
+        transport.assert_request(
+            "eth_sendTransaction",
+            &[json!({
+                "from": from,
+                "to": to,
+                "gas": "0x1",
+                "type": "0x2",
+                "maxFeePerGas": "0x64",
+                "maxPriorityFeePerGas": "0x2",
+            })],
+        );
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn tx_send_local_populated_access_list() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let from = addr!("0x9876543210987654321098765432109876543210");
+        let to = addr!("0x0123456789012345678901234567890123456789");
+        let key = H256::repeat_byte(1);
+        let hash = hash!("0x4242424242424242424242424242424242424242424242424242424242424242");
+
+        transport.add_response(json!({
+            "accessList": [{ "address": to, "storageKeys": [key] }],
+            "gasUsed": "0x5208",
+        }));
+        transport.add_response(json!(hash)); // tansaction hash
+        TransactionBuilder::new(web3)
+            .from(Account::Local(from, None))
+            .to(to)
+            .gas(1.into())
+            .populate_access_list(true)
+            .resolve(ResolveCondition::Pending)
+            .send()
+            .immediate()
+            .expect("transaction success");
+
+        transport.assert_request(
+            "eth_createAccessList",
+            &[json!({ "from": from, "to": to }), json!("latest")],
+        );
+        transport.assert_request(
+            "eth_sendTransaction",
+            &[json!({
+                "from": from,
+                "to": to,
+                "gas": "0x1",
+                "accessList": [{ "address": to, "storageKeys": [key] }],
+            })],
+        );
+        transport.assert_no_more_requests();
+    }
+
     #[test]
     fn tx_send_with_confirmations() {
         let mut transport = TestTransport::new();
@@ -364,6 +801,272 @@ mod tests {
         transport.assert_no_more_requests();
     }
 
+    #[test]
+    fn tx_send_with_replacement() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let key = key!("0x0102030405060708091011121314151617181920212223242526272829303132");
+        let policy = ReplacementPolicy::default().blocks(1).max_replacements(1);
+        let builder = TransactionBuilder::new(web3)
+            .from(Account::Offline(key, Some(77777)))
+            .to(Address::zero())
+            .gas(0x1337.into())
+            .gas_price(GasPrice::Legacy(100.into()))
+            .nonce(0x42.into())
+            .replacement(policy);
+
+        let raw_tx = |builder: TransactionBuilder<_>| match builder.build().wait().unwrap() {
+            Transaction::Raw { bytes, hash } => (bytes, hash),
+            _ => unreachable!(),
+        };
+        let (tx1, hash1) = raw_tx(builder.clone());
+        let (tx2, hash2) = raw_tx(builder.clone().gas_price(GasPrice::Legacy(112.into())));
+
+        // initial transaction is not mined after 1 block
+        transport.add_response(json!(hash1));
+        transport.add_response(json!("0x1"));
+        transport.add_response(json!(null));
+        transport.add_response(json!("0x2"));
+        transport.add_response(json!(null));
+        // replacement transaction is mined
+        transport.add_response(json!(hash2));
+        transport.add_response(json!("0x3"));
+        transport.add_response(json!(null));
+        transport.add_response(json!({
+            "transactionHash": hash2,
+            "transactionIndex": "0x1",
+            "blockNumber": "0x3",
+            "blockHash": H256::repeat_byte(3),
+            "cumulativeGasUsed": "0x1337",
+            "gasUsed": "0x1337",
+            "logsBloom": H2048::zero(),
+            "logs": [],
+            "status": "0x1",
+            "effectiveGasPrice": "0x70",
+        }));
+
+        let result = builder.send().wait().expect("send with replacement failed");
+
+        assert_eq!(result.hash(), hash2);
+        transport.assert_request("eth_sendRawTransaction", &[json!(tx1)]);
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_getTransactionReceipt", &[json!(hash1)]);
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_getTransactionReceipt", &[json!(hash1)]);
+        transport.assert_request("eth_sendRawTransaction", &[json!(tx2)]);
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_getTransactionReceipt", &[json!(hash1)]);
+        transport.assert_request("eth_getTransactionReceipt", &[json!(hash2)]);
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn tx_send_with_replacement_uses_pending_nonce() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let from = addr!("0x9876543210987654321098765432109876543210");
+        let tx_hash = H256::repeat_byte(0x42);
+        let policy = ReplacementPolicy::default().blocks(1).max_replacements(1);
+
+        // the account has pending transactions with nonces 0x2a and 0x2b,
+        // so the latest transaction count is 0x2a and the pending one 0x2c
+        transport.add_response(json!("0x2c"));
+        transport.add_response(json!(tx_hash));
+        transport.add_response(json!("0x1"));
+        transport.add_response(json!({
+            "transactionHash": tx_hash,
+            "transactionIndex": "0x0",
+            "blockNumber": "0x1",
+            "blockHash": H256::repeat_byte(1),
+            "cumulativeGasUsed": "0x1337",
+            "gasUsed": "0x1337",
+            "logsBloom": H2048::zero(),
+            "logs": [],
+            "status": "0x1",
+        }));
+
+        let result = TransactionBuilder::new(web3)
+            .from(Account::Local(from, None))
+            .to(Address::zero())
+            .gas(0x1337.into())
+            .gas_price(GasPrice::Legacy(100.into()))
+            .replacement(policy)
+            .send()
+            .wait()
+            .expect("send with replacement failed");
+
+        assert_eq!(result.hash(), tx_hash);
+        transport.assert_request("eth_getTransactionCount", &[json!(from), json!("pending")]);
+        transport.assert_request(
+            "eth_sendTransaction",
+            &[json!({
+                "from": from,
+                "to": Address::zero(),
+                "gas": "0x1337",
+                "gasPrice": "0x64",
+                "nonce": "0x2c",
+            })],
+        );
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_getTransactionReceipt", &[json!(tx_hash)]);
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn tx_send_with_gas_price_strategy() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let from = addr!("0x9876543210987654321098765432109876543210");
+        let hash = hash!("0x4242424242424242424242424242424242424242424242424242424242424242");
+
+        transport.add_response(json!("0x10")); // gas price
+        transport.add_response(json!(hash)); // transaction hash
+        let tx = TransactionBuilder::new(web3)
+            .from(Account::Local(from, None))
+            .gas(1.into())
+            .gas_price_with(|web3| async move {
+                let gas_price = web3.eth().gas_price().await?;
+                Ok(GasPrice::Legacy(gas_price * 2))
+            })
+            .resolve(ResolveCondition::Pending)
+            .send()
+            .immediate()
+            .expect("transaction success");
+
+        assert_eq!(tx.hash(), hash);
+        transport.assert_request("eth_gasPrice", &[]);
+        transport.assert_request(
+            "eth_sendTransaction",
+            &[json!({
+                "from": from,
+                "gas": "0x1",
+                "gasPrice": "0x20",
+            })],
+        );
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn tx_send_with_gas_price_strategy_replacement() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let key = key!("0x0102030405060708091011121314151617181920212223242526272829303132");
+        let policy = ReplacementPolicy::default().blocks(1).max_replacements(1);
+        let gas_prices = Arc::new(std::sync::Mutex::new(vec![150, 100]));
+        let builder = TransactionBuilder::new(web3)
+            .from(Account::Offline(key, Some(77777)))
+            .to(Address::zero())
+            .gas(0x1337.into())
+            .nonce(0x42.into())
+            .replacement(policy);
+
+        let raw_tx = |builder: TransactionBuilder<_>| match builder.build().wait().unwrap() {
+            Transaction::Raw { bytes, hash } => (bytes, hash),
+            _ => unreachable!(),
+        };
+        let (tx1, hash1) = raw_tx(builder.clone().gas_price(GasPrice::Legacy(100.into())));
+        let (tx2, hash2) = raw_tx(builder.clone().gas_price(GasPrice::Legacy(150.into())));
+
+        // initial transaction is not mined after 1 block
+        transport.add_response(json!(hash1));
+        transport.add_response(json!("0x1"));
+        transport.add_response(json!(null));
+        transport.add_response(json!("0x2"));
+        transport.add_response(json!(null));
+        // replacement transaction with the strategy's gas price is mined
+        transport.add_response(json!(hash2));
+        transport.add_response(json!("0x3"));
+        transport.add_response(json!(null));
+        transport.add_response(json!({
+            "transactionHash": hash2,
+            "transactionIndex": "0x1",
+            "blockNumber": "0x3",
+            "blockHash": H256::repeat_byte(3),
+            "cumulativeGasUsed": "0x1337",
+            "gasUsed": "0x1337",
+            "logsBloom": H2048::zero(),
+            "logs": [],
+            "status": "0x1",
+            "effectiveGasPrice": "0x96",
+        }));
+
+        let result = builder
+            .gas_price_with(move |_| {
+                let gas_price = gas_prices.lock().unwrap().pop().unwrap();
+                async move { Ok(GasPrice::Legacy(gas_price.into())) }
+            })
+            .send()
+            .wait()
+            .expect("send with replacement failed");
+
+        assert_eq!(result.hash(), hash2);
+        transport.assert_request("eth_sendRawTransaction", &[json!(tx1)]);
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_getTransactionReceipt", &[json!(hash1)]);
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_getTransactionReceipt", &[json!(hash1)]);
+        transport.assert_request("eth_sendRawTransaction", &[json!(tx2)]);
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_getTransactionReceipt", &[json!(hash1)]);
+        transport.assert_request("eth_getTransactionReceipt", &[json!(hash2)]);
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn tx_send_with_chain_specific_receipt() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let from = addr!("0x9876543210987654321098765432109876543210");
+        let tx_hash = H256::repeat_byte(0x42);
+
+        transport.add_response(json!(tx_hash));
+        transport.add_response(json!("0x1"));
+        transport.add_response(json!({
+            "transactionHash": tx_hash,
+            "transactionIndex": "0x0",
+            "blockNumber": "0x1",
+            "blockHash": H256::repeat_byte(1),
+            "cumulativeGasUsed": "0x1337",
+            "gasUsed": "0x1337",
+            "logsBloom": H2048::zero(),
+            "logs": [],
+            "status": "0x1",
+            "type": "0x7e",
+            "depositNonce": "0x2a",
+            "l1Fee": "0x10",
+        }));
+
+        let result = TransactionBuilder::new(web3)
+            .from(Account::Local(from, None))
+            .to(Address::zero())
+            .gas(0x1337.into())
+            .send()
+            .immediate()
+            .expect("transaction success");
+
+        assert_eq!(result.hash(), tx_hash);
+        assert_eq!(
+            result.transaction_type(),
+            Some(TransactionType::Other(0x7e))
+        );
+        transport.assert_request(
+            "eth_sendTransaction",
+            &[json!({
+                "from": from,
+                "to": Address::zero(),
+                "gas": "0x1337",
+            })],
+        );
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_getTransactionReceipt", &[json!(tx_hash)]);
+        transport.assert_no_more_requests();
+    }
+
     #[test]
     fn tx_failure() {
         let mut transport = TestTransport::new();
@@ -0,0 +1,82 @@
+//! Helpers for specifying EIP-2930 access lists and creating them with
+//! `eth_createAccessList`.
+
+use crate::errors::ExecutionError;
+use serde::Deserialize;
+use web3::api::Web3;
+use web3::helpers::{self, CallFuture};
+use web3::types::{AccessList, AccessListItem, Address, BlockNumber, CallRequest, H256, U256};
+use web3::Transport;
+
+/// A type that can be converted into an EIP-2930 access list.
+///
+/// This is implemented for `AccessList` as well as lists of addresses with
+/// their accessed storage keys, so that access lists can be specified without
+/// constructing `AccessListItem`s.
+pub trait IntoAccessList {
+    /// Converts the value into an access list.
+    fn into_access_list(self) -> AccessList;
+}
+
+impl IntoAccessList for AccessList {
+    fn into_access_list(self) -> AccessList {
+        self
+    }
+}
+
+impl IntoAccessList for Vec<(Address, Vec<H256>)> {
+    fn into_access_list(self) -> AccessList {
+        self.into_iter()
+            .map(|(address, storage_keys)| AccessListItem {
+                address,
+                storage_keys,
+            })
+            .collect()
+    }
+}
+
+/// An access list created by the node for a transaction, along with the gas
+/// used by the transaction when using the access list.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedAccessList {
+    /// The created access list.
+    pub access_list: AccessList,
+    /// The gas used by the transaction with the access list.
+    pub gas_used: U256,
+}
+
+/// Creates an access list for a transaction with `eth_createAccessList` on the
+/// latest block.
+pub(crate) async fn create_access_list<T: Transport>(
+    web3: &Web3<T>,
+    request: CallRequest,
+) -> Result<CreatedAccessList, ExecutionError> {
+    let request = helpers::serialize(&request);
+    let block = helpers::serialize(&BlockNumber::Latest);
+    let created = CallFuture::new(
+        web3.transport()
+            .execute("eth_createAccessList", vec![request, block]),
+    )
+    .await?;
+    Ok(created)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn access_list_from_storage_keys() {
+        let address = Address::repeat_byte(1);
+        let key = H256::repeat_byte(2);
+
+        assert_eq!(
+            vec![(address, vec![key])].into_access_list(),
+            vec![AccessListItem {
+                address,
+                storage_keys: vec![key],
+            }],
+        );
+    }
+}
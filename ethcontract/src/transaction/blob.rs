@@ -0,0 +1,332 @@
+//! Implementation of EIP-4844 blob transactions.
+//!
+//! Blob transactions carry a sidecar of blobs along with their KZG commitments
+//! and proofs. Only the versioned hashes of the commitments are part of the
+//! signed transaction, while the sidecar is included in the network
+//! representation of the transaction that is sent to the node.
+//!
+//! Note that computing KZG commitments and proofs requires a trusted setup
+//! and is out of scope for this crate. They should be computed with a KZG
+//! library such as `c-kzg` and attached to the transaction with a
+//! [`BlobSidecar`].
+
+use ethcontract_common::hash::keccak256;
+use rlp::RlpStream;
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use web3::signing::Signature;
+use web3::types::{AccessList, Address, Bytes, H256, U256};
+
+/// The number of bytes in a blob.
+pub const BYTES_PER_BLOB: usize = 131_072;
+
+/// The number of bytes in a KZG commitment or proof.
+pub const BYTES_PER_COMMITMENT: usize = 48;
+
+/// The version byte of versioned hashes for KZG commitments.
+pub const VERSIONED_HASH_VERSION_KZG: u8 = 0x01;
+
+/// The EIP-2718 transaction type of blob transactions.
+const BLOB_TX_TYPE: u8 = 0x03;
+
+/// The blobs of a blob transaction along with their KZG commitments and
+/// proofs.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct BlobSidecar {
+    /// The blobs, each exactly [`BYTES_PER_BLOB`] long.
+    pub blobs: Vec<Bytes>,
+    /// The KZG commitments of the blobs.
+    pub commitments: Vec<Bytes>,
+    /// The KZG proofs of the blobs.
+    pub proofs: Vec<Bytes>,
+}
+
+impl BlobSidecar {
+    /// Creates a new empty blob sidecar.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a blob with its KZG commitment and proof to the sidecar.
+    pub fn with_blob(
+        mut self,
+        blob: impl Into<Bytes>,
+        commitment: impl Into<Bytes>,
+        proof: impl Into<Bytes>,
+    ) -> Self {
+        self.blobs.push(blob.into());
+        self.commitments.push(commitment.into());
+        self.proofs.push(proof.into());
+        self
+    }
+
+    /// Returns the number of blobs in the sidecar.
+    pub fn len(&self) -> usize {
+        self.blobs.len()
+    }
+
+    /// Returns true if the sidecar does not contain any blobs.
+    pub fn is_empty(&self) -> bool {
+        self.blobs.is_empty()
+    }
+
+    /// Returns the versioned hashes of the blob commitments, as they are
+    /// included in the signed transaction.
+    pub fn versioned_hashes(&self) -> Vec<H256> {
+        self.commitments
+            .iter()
+            .map(|commitment| kzg_to_versioned_hash(&commitment.0))
+            .collect()
+    }
+
+    /// Verifies that the sidecar is well formed, that is, it contains at least
+    /// one blob and each blob has a commitment and a proof of the correct
+    /// length.
+    pub fn validate(&self) -> Result<(), BlobError> {
+        if self.is_empty() {
+            return Err(BlobError::EmptySidecar);
+        }
+        if self.commitments.len() != self.blobs.len() || self.proofs.len() != self.blobs.len() {
+            return Err(BlobError::MismatchedSidecar);
+        }
+        for (index, blob) in self.blobs.iter().enumerate() {
+            if blob.0.len() != BYTES_PER_BLOB {
+                return Err(BlobError::InvalidBlobLength(index, blob.0.len()));
+            }
+        }
+        for (index, item) in self.commitments.iter().chain(&self.proofs).enumerate() {
+            if item.0.len() != BYTES_PER_COMMITMENT {
+                let index = index % self.blobs.len();
+                return Err(BlobError::InvalidCommitmentLength(index, item.0.len()));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Computes the versioned hash of a KZG commitment.
+pub fn kzg_to_versioned_hash(commitment: &[u8]) -> H256 {
+    let mut hash = H256::from_slice(&Sha256::digest(commitment));
+    hash.0[0] = VERSIONED_HASH_VERSION_KZG;
+    hash
+}
+
+/// An error with the parameters of a blob transaction.
+#[derive(Debug, Error)]
+pub enum BlobError {
+    /// The blob sidecar does not contain any blobs.
+    #[error("blob sidecar does not contain any blobs")]
+    EmptySidecar,
+
+    /// The blob sidecar has different numbers of blobs, commitments and
+    /// proofs.
+    #[error("blob sidecar has mismatched numbers of blobs, commitments and proofs")]
+    MismatchedSidecar,
+
+    /// A blob in the sidecar has an invalid length.
+    #[error("blob {0} has invalid length {1}")]
+    InvalidBlobLength(usize, usize),
+
+    /// A KZG commitment or proof in the sidecar has an invalid length.
+    #[error("KZG commitment or proof for blob {0} has invalid length {1}")]
+    InvalidCommitmentLength(usize, usize),
+
+    /// Blob transactions can't be used to deploy contracts.
+    #[error("blob transactions require a recipient")]
+    MissingRecipient,
+
+    /// Blob transactions can only be signed by accounts that sign offline.
+    #[error("blob transactions can't be signed by the node")]
+    UnsupportedAccount,
+}
+
+/// A fully specified, unsigned blob transaction.
+#[derive(Clone, Debug)]
+pub(crate) struct BlobTransaction {
+    pub chain_id: u64,
+    pub nonce: U256,
+    pub max_priority_fee_per_gas: U256,
+    pub max_fee_per_gas: U256,
+    pub gas: U256,
+    pub to: Address,
+    pub value: U256,
+    pub data: Bytes,
+    pub access_list: AccessList,
+    pub max_fee_per_blob_gas: U256,
+    pub sidecar: BlobSidecar,
+}
+
+impl BlobTransaction {
+    /// Returns the EIP-2718 signing payload of this transaction, that is the
+    /// transaction type followed by the RLP encoded transaction fields.
+    pub fn signing_payload(&self) -> Bytes {
+        let mut stream = RlpStream::new_list(11);
+        self.append_fields(&mut stream);
+        Bytes([&[BLOB_TX_TYPE], stream.as_raw()].concat())
+    }
+
+    /// Returns the message hash to sign for this transaction.
+    pub fn signing_hash(&self) -> [u8; 32] {
+        keccak256(&self.signing_payload().0)
+    }
+
+    /// Encodes the transaction with the specified signature. Returns the
+    /// network representation of the transaction including the blob sidecar
+    /// and the transaction hash.
+    pub fn encode(&self, signature: &Signature) -> (Bytes, H256) {
+        let mut stream = RlpStream::new_list(14);
+        self.append_fields(&mut stream);
+        stream.append(&signature.v);
+        stream.append(&U256::from_big_endian(signature.r.as_bytes()));
+        stream.append(&U256::from_big_endian(signature.s.as_bytes()));
+        let payload = stream.out();
+        let hash = H256(keccak256([&[BLOB_TX_TYPE], &payload[..]].concat()));
+
+        let mut stream = RlpStream::new_list(4);
+        stream.append_raw(&payload, 1);
+        for items in [
+            &self.sidecar.blobs,
+            &self.sidecar.commitments,
+            &self.sidecar.proofs,
+        ] {
+            stream.begin_list(items.len());
+            for item in items {
+                stream.append(&item.0);
+            }
+        }
+        let raw = Bytes([&[BLOB_TX_TYPE], &stream.out()[..]].concat());
+
+        (raw, hash)
+    }
+
+    fn append_fields(&self, stream: &mut RlpStream) {
+        stream.append(&self.chain_id);
+        stream.append(&self.nonce);
+        stream.append(&self.max_priority_fee_per_gas);
+        stream.append(&self.max_fee_per_gas);
+        stream.append(&self.gas);
+        stream.append(&self.to);
+        stream.append(&self.value);
+        stream.append(&self.data.0);
+        stream.begin_list(self.access_list.len());
+        for item in &self.access_list {
+            stream.begin_list(2);
+            stream.append(&item.address);
+            stream.append_list(&item.storage_keys);
+        }
+        stream.append(&self.max_fee_per_blob_gas);
+        stream.append_list(&self.sidecar.versioned_hashes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    fn sidecar() -> BlobSidecar {
+        BlobSidecar::new().with_blob(
+            vec![0; BYTES_PER_BLOB],
+            vec![0xc0; BYTES_PER_COMMITMENT],
+            vec![0; BYTES_PER_COMMITMENT],
+        )
+    }
+
+    #[test]
+    fn versioned_hash() {
+        // commitment of the zero blob
+        let mut commitment = [0; BYTES_PER_COMMITMENT];
+        commitment[0] = 0xc0;
+        assert_eq!(
+            kzg_to_versioned_hash(&commitment),
+            H256(hex!(
+                "010657f37554c781402a22917dee2f75def7ab966d7b770905398eba3c444014"
+            )),
+        );
+    }
+
+    #[test]
+    fn validate_sidecar() {
+        assert!(sidecar().validate().is_ok());
+        assert!(matches!(
+            BlobSidecar::new().validate(),
+            Err(BlobError::EmptySidecar)
+        ));
+        assert!(matches!(
+            BlobSidecar {
+                proofs: vec![],
+                ..sidecar()
+            }
+            .validate(),
+            Err(BlobError::MismatchedSidecar)
+        ));
+        assert!(matches!(
+            BlobSidecar::new()
+                .with_blob(vec![0; 32], vec![0; 48], vec![0; 48])
+                .validate(),
+            Err(BlobError::InvalidBlobLength(0, 32))
+        ));
+        assert!(matches!(
+            BlobSidecar::new()
+                .with_blob(vec![0; BYTES_PER_BLOB], vec![0; 48], vec![0; 47])
+                .validate(),
+            Err(BlobError::InvalidCommitmentLength(0, 47))
+        ));
+    }
+
+    #[test]
+    fn encode_network_representation() {
+        let tx = BlobTransaction {
+            chain_id: 1,
+            nonce: 0.into(),
+            max_priority_fee_per_gas: 1.into(),
+            max_fee_per_gas: 2.into(),
+            gas: 21_000.into(),
+            to: Address::repeat_byte(0x42),
+            value: 0.into(),
+            data: Bytes::default(),
+            access_list: vec![],
+            max_fee_per_blob_gas: 3.into(),
+            sidecar: sidecar(),
+        };
+        let signature = Signature {
+            v: 1,
+            r: H256::repeat_byte(0x11),
+            s: H256::repeat_byte(0x22),
+        };
+
+        let (raw, hash) = tx.encode(&signature);
+        assert_eq!(raw.0[0], BLOB_TX_TYPE);
+
+        let wrapper = rlp::Rlp::new(&raw.0[1..]);
+        assert_eq!(wrapper.item_count().unwrap(), 4);
+        let payload = wrapper.at(0).unwrap();
+        assert_eq!(payload.item_count().unwrap(), 14);
+        assert_eq!(
+            payload.at(10).unwrap().as_list::<H256>().unwrap(),
+            tx.sidecar.versioned_hashes(),
+        );
+        assert_eq!(payload.val_at::<u64>(11).unwrap(), 1);
+        assert_eq!(
+            hash,
+            H256(keccak256([&[BLOB_TX_TYPE], payload.as_raw()].concat())),
+        );
+        assert_eq!(
+            wrapper.at(1).unwrap().at(0).unwrap().data().unwrap().len(),
+            BYTES_PER_BLOB,
+        );
+
+        // the signing hash does not depend on the signature
+        let unsigned = payload
+            .iter()
+            .take(11)
+            .fold(RlpStream::new_list(11), |mut s, item| {
+                s.append_raw(item.as_raw(), 1);
+                s
+            });
+        assert_eq!(
+            tx.signing_hash(),
+            keccak256([&[BLOB_TX_TYPE], unsigned.as_raw()].concat()),
+        );
+    }
+}
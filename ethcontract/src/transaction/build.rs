@@ -6,16 +6,19 @@
 
 use crate::errors::ExecutionError;
 use crate::secret::{Password, PrivateKey};
+use crate::transaction::blob::{BlobError, BlobSidecar, BlobTransaction};
 use crate::transaction::gas_price::GasPrice;
 #[cfg(feature = "aws-kms")]
 use crate::transaction::kms;
-use crate::transaction::{Account, TransactionBuilder};
+use crate::transaction::{signer, Account, TransactionBuilder};
 use web3::api::Web3;
+use web3::error::Error as Web3Error;
+use web3::signing::Key as _;
 use web3::types::{
     AccessList, Address, Bytes, CallRequest, RawTransaction, SignedTransaction,
     TransactionCondition, TransactionParameters, TransactionRequest, H256, U256,
 };
-use web3::Transport;
+use web3::{helpers, Transport};
 
 impl<T: Transport> TransactionBuilder<T> {
     /// Build a prepared transaction that is ready to send.
@@ -23,7 +26,13 @@ impl<T: Transport> TransactionBuilder<T> {
     /// Can resolve into either a `TransactionRequest` for sending locally
     /// signed transactions or raw signed transaction `Bytes` when sending a raw
     /// transaction.
-    pub async fn build(self) -> Result<Transaction, ExecutionError> {
+    pub async fn build(mut self) -> Result<Transaction, ExecutionError> {
+        self.check_calldata_size()?;
+        self.resolve_gas_price_strategy().await?;
+        if self.populate_access_list && self.access_list.is_none() {
+            self.access_list = Some(self.create_access_list().await?.access_list);
+        }
+
         let options = TransactionOptions {
             to: self.to,
             gas: self.gas,
@@ -34,6 +43,17 @@ impl<T: Transport> TransactionBuilder<T> {
             access_list: self.access_list,
         };
 
+        if let Some(sidecar) = self.blob_sidecar {
+            return build_blob_transaction(
+                self.web3,
+                self.from,
+                options,
+                self.max_fee_per_blob_gas,
+                sidecar,
+            )
+            .await;
+        }
+
         let tx = match self.from {
             None => Transaction::Request(
                 build_transaction_request_for_local_signing(
@@ -81,10 +101,43 @@ impl<T: Transport> TransactionBuilder<T> {
                         hash: signed.transaction_hash,
                     })?
             }
+            Some(Account::Threshold(account, chain_id)) => {
+                build_signer_signed_transaction(self.web3, &account, chain_id, options)
+                    .await
+                    .map(|signed| Transaction::Raw {
+                        bytes: signed.raw_transaction,
+                        hash: signed.transaction_hash,
+                    })?
+            }
+            Some(Account::Signer(signer)) => {
+                build_signer_signed_transaction(self.web3, &*signer, None, options)
+                    .await
+                    .map(|signed| Transaction::Raw {
+                        bytes: signed.raw_transaction,
+                        hash: signed.transaction_hash,
+                    })?
+            }
         };
 
         Ok(tx)
     }
+
+    /// Builds and signs the transaction without sending it, returning its
+    /// hash along with the signed transaction.
+    ///
+    /// This allows the transaction hash to be logged or watched for before
+    /// the transaction is submitted with [`Transaction::send`]. Note that the
+    /// returned transaction should be sent as is instead of building it again,
+    /// since some signers produce different signatures for the same
+    /// transaction.
+    ///
+    /// Returns an error for transactions that are signed by the node, as their
+    /// hash is only known once they are sent.
+    pub async fn precomputed_hash(self) -> Result<(H256, Transaction), ExecutionError> {
+        let tx = self.build().await?;
+        let hash = tx.hash().ok_or(ExecutionError::NodeSignedTransaction)?;
+        Ok((hash, tx))
+    }
 }
 
 /// Represents a prepared and optionally signed transaction that is ready for
@@ -121,6 +174,15 @@ impl Transaction {
             _ => None,
         }
     }
+
+    /// Returns the hash of a signed raw transaction, or None if it is a
+    /// transaction request whose hash is only known once the node signs it.
+    pub fn hash(&self) -> Option<H256> {
+        match self {
+            Transaction::Raw { hash, .. } => Some(*hash),
+            _ => None,
+        }
+    }
 }
 
 /// Shared transaction options that are used when finalizing transactions into
@@ -288,6 +350,143 @@ async fn build_kms_signed_transaction<T: Transport>(
     Ok(signed)
 }
 
+/// Build a transaction signed by a threshold signer set or a custom signer.
+///
+/// Note that all transaction parameters must be finalized before signing. This
+/// means that things like account nonce, gas and gas price estimates, as well
+/// as chain ID must be queried from the node if not provided before signing.
+async fn build_signer_signed_transaction<T: Transport>(
+    web3: Web3<T>,
+    signer: &dyn signer::Signer,
+    chain_id: Option<u64>,
+    options: TransactionOptions,
+) -> Result<SignedTransaction, ExecutionError> {
+    let gas = resolve_gas_limit(&web3, signer.address(), &options).await?;
+    let resolved_gas_price = options
+        .gas_price
+        .map(|gas_price| gas_price.resolve_for_transaction())
+        .unwrap_or_default();
+    let signed = signer::sign_transaction(
+        web3,
+        signer,
+        TransactionParameters {
+            nonce: options.nonce,
+            gas_price: resolved_gas_price.gas_price,
+            gas,
+            to: options.to,
+            value: options.value.unwrap_or_default(),
+            data: options.data.unwrap_or_default(),
+            chain_id,
+            transaction_type: resolved_gas_price.transaction_type,
+            access_list: options.access_list,
+            max_fee_per_gas: resolved_gas_price.max_fee_per_gas,
+            max_priority_fee_per_gas: resolved_gas_price.max_priority_fee_per_gas,
+        },
+    )
+    .await?;
+
+    Ok(signed)
+}
+
+/// Build an EIP-4844 blob transaction signed by an offline, AWS KMS,
+/// threshold or custom signer account.
+///
+/// Note that all transaction parameters must be finalized before signing. This
+/// means that things like account nonce, gas and gas price estimates, as well
+/// as chain ID must be queried from the node if not provided before signing.
+async fn build_blob_transaction<T: Transport>(
+    web3: Web3<T>,
+    account: Option<Account>,
+    options: TransactionOptions,
+    max_fee_per_blob_gas: Option<U256>,
+    sidecar: BlobSidecar,
+) -> Result<Transaction, ExecutionError> {
+    sidecar.validate()?;
+    let to = options.to.ok_or(BlobError::MissingRecipient)?;
+    let (from, chain_id) = match &account {
+        Some(Account::Offline(key, chain_id)) => (key.public_address(), *chain_id),
+        #[cfg(feature = "aws-kms")]
+        Some(Account::Kms(account, chain_id)) => (account.public_address(), *chain_id),
+        Some(Account::Threshold(account, chain_id)) => (account.public_address(), *chain_id),
+        Some(Account::Signer(signer)) => (signer.address(), None),
+        _ => return Err(BlobError::UnsupportedAccount.into()),
+    };
+
+    let gas = resolve_gas_limit(&web3, from, &options).await?;
+    let nonce = match options.nonce {
+        Some(nonce) => nonce,
+        None => web3.eth().transaction_count(from, None).await?,
+    };
+    let resolved_gas_price = options
+        .gas_price
+        .map(|gas_price| gas_price.resolve_for_transaction())
+        .unwrap_or_default();
+    let (max_fee_per_gas, max_priority_fee_per_gas) = match (
+        resolved_gas_price.max_fee_per_gas,
+        resolved_gas_price.max_priority_fee_per_gas,
+    ) {
+        (Some(max_fee_per_gas), Some(max_priority_fee_per_gas)) => {
+            (max_fee_per_gas, max_priority_fee_per_gas)
+        }
+        _ => {
+            let gas_price = match resolved_gas_price.gas_price {
+                Some(gas_price) => gas_price,
+                None => web3.eth().gas_price().await?,
+            };
+            (gas_price, gas_price)
+        }
+    };
+    let chain_id = match chain_id {
+        Some(chain_id) => chain_id,
+        None => web3.eth().chain_id().await?.as_u64(),
+    };
+    let max_fee_per_blob_gas = match max_fee_per_blob_gas {
+        Some(max_fee_per_blob_gas) => max_fee_per_blob_gas,
+        None => {
+            let blob_base_fee = web3.transport().execute("eth_blobBaseFee", vec![]).await?;
+            helpers::decode::<U256>(blob_base_fee)?.saturating_mul(2.into())
+        }
+    };
+
+    let tx = BlobTransaction {
+        chain_id,
+        nonce,
+        max_priority_fee_per_gas,
+        max_fee_per_gas,
+        gas,
+        to,
+        value: options.value.unwrap_or_default(),
+        data: options.data.unwrap_or_default(),
+        access_list: options.access_list.unwrap_or_default(),
+        max_fee_per_blob_gas,
+        sidecar,
+    };
+    let message = tx.signing_hash();
+    let unsigned = || signer::UnsignedTransaction {
+        payload: tx.signing_payload(),
+        signing_hash: H256(message),
+    };
+    let signature = match account {
+        Some(Account::Offline(key, _)) => key
+            .sign_message(&message)
+            .map_err(|_| Web3Error::Internal)?,
+        #[cfg(feature = "aws-kms")]
+        Some(Account::Kms(account, _)) => account.sign(message).await?,
+        Some(Account::Threshold(account, _)) => {
+            signer::sign_unsigned_transaction(&account, &unsigned())
+                .await?
+                .into()
+        }
+        Some(Account::Signer(signer)) => signer::sign_unsigned_transaction(&*signer, &unsigned())
+            .await?
+            .into(),
+        _ => unreachable!("blob transaction account already checked"),
+    };
+    let (bytes, hash) = tx.encode(&signature);
+
+    Ok(Transaction::Raw { bytes, hash })
+}
+
 async fn resolve_gas_limit<T: Transport>(
     web3: &Web3<T>,
     from: Address,
@@ -602,4 +801,165 @@ mod tests {
         // check that if we sign with same values we get same results
         assert_eq!(tx1, tx2);
     }
+
+    #[test]
+    fn tx_precomputed_hash() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let key = key!("0x0102030405060708091011121314151617181920212223242526272829303132");
+        let builder = TransactionBuilder::new(web3.clone())
+            .from(Account::Offline(key, Some(77777)))
+            .to(addr!("0x0000000000000000000000000000000000000000"))
+            .gas(uint!("0x9a5"))
+            .gas_price(uint!("0x1ce").into())
+            .nonce(uint!("0x42"));
+
+        let (hash, tx) = builder
+            .clone()
+            .precomputed_hash()
+            .immediate()
+            .expect("failed to precompute transaction hash");
+        transport.assert_no_more_requests();
+
+        assert_eq!(tx.hash(), Some(hash));
+        assert_eq!(tx, builder.build().immediate().unwrap());
+
+        let bytes = tx.clone().raw().unwrap();
+        transport.add_response(json!(hash));
+        let sent = tx
+            .send(&web3)
+            .immediate()
+            .expect("failed to send transaction");
+        transport.assert_request("eth_sendRawTransaction", &[json!(bytes)]);
+        transport.assert_no_more_requests();
+
+        assert_eq!(sent, hash);
+    }
+
+    #[test]
+    fn tx_precomputed_hash_node_signed() {
+        let transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let err = TransactionBuilder::new(web3)
+            .from(Account::Local(Address::repeat_byte(0x42), None))
+            .to(Address::zero())
+            .gas(uint!("0x9a5"))
+            .gas_price(uint!("0x1ce").into())
+            .nonce(uint!("0x42"))
+            .precomputed_hash()
+            .immediate()
+            .expect_err("unexpected success precomputing transaction hash");
+        transport.assert_no_more_requests();
+
+        assert!(matches!(err, ExecutionError::NodeSignedTransaction));
+    }
+
+    #[test]
+    fn tx_build_blob() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let key = key!("0x0102030405060708091011121314151617181920212223242526272829303132");
+        let from: Address = key.public_address();
+        let to = addr!("0x4242424242424242424242424242424242424242");
+        let sidecar = BlobSidecar::new().with_blob(
+            vec![0; crate::transaction::blob::BYTES_PER_BLOB],
+            vec![0xc0; crate::transaction::blob::BYTES_PER_COMMITMENT],
+            vec![0; crate::transaction::blob::BYTES_PER_COMMITMENT],
+        );
+
+        transport.add_response(json!("0x5208")); // gas estimate
+        transport.add_response(json!("0x42")); // nonce
+        transport.add_response(json!("0x1")); // chain ID
+        transport.add_response(json!("0x7")); // blob base fee
+        let tx = TransactionBuilder::new(web3.clone())
+            .from(Account::Offline(key.clone(), None))
+            .to(to)
+            .max_fee_per_gas(2.into())
+            .max_priority_fee_per_gas(1.into())
+            .blob_sidecar(sidecar.clone())
+            .build()
+            .immediate()
+            .expect("failed to build blob transaction");
+
+        transport.assert_request(
+            "eth_estimateGas",
+            &[json!({
+                "from": from,
+                "to": to,
+                "maxFeePerGas": "0x2",
+                "maxPriorityFeePerGas": "0x1",
+                "type": "0x2",
+            })],
+        );
+        transport.assert_request("eth_getTransactionCount", &[json!(from), json!("latest")]);
+        transport.assert_request("eth_chainId", &[]);
+        transport.assert_request("eth_blobBaseFee", &[]);
+        transport.assert_no_more_requests();
+
+        let (bytes, hash) = match tx {
+            Transaction::Raw { bytes, hash } => (bytes, hash),
+            _ => panic!("expected raw transaction"),
+        };
+        assert_eq!(bytes.0[0], 0x03);
+        let payload = rlp::Rlp::new(&bytes.0[1..]).at(0).unwrap();
+        assert_eq!(payload.val_at::<U256>(1).unwrap(), 0x42.into());
+        assert_eq!(payload.val_at::<U256>(9).unwrap(), 14.into());
+
+        // the signature recovers to the signing account
+        let unsigned = BlobTransaction {
+            chain_id: 1,
+            nonce: 0x42.into(),
+            max_priority_fee_per_gas: 1.into(),
+            max_fee_per_gas: 2.into(),
+            gas: 0x5208.into(),
+            to,
+            value: 0.into(),
+            data: Bytes::default(),
+            access_list: vec![],
+            max_fee_per_blob_gas: 14.into(),
+            sidecar,
+        };
+        let signature = [
+            payload.val_at::<H256>(12).unwrap().0,
+            payload.val_at::<H256>(13).unwrap().0,
+        ]
+        .concat();
+        let recovery_id = payload.val_at::<u64>(11).unwrap() as i32;
+        assert_eq!(
+            web3::signing::recover(&unsigned.signing_hash(), &signature, recovery_id).unwrap(),
+            from,
+        );
+        assert_eq!(
+            unsigned
+                .encode(&key.sign_message(&unsigned.signing_hash()).unwrap())
+                .1,
+            hash
+        );
+    }
+
+    #[test]
+    fn tx_build_blob_requires_offline_signing() {
+        let transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+        let sidecar = BlobSidecar::new().with_blob(
+            vec![0; crate::transaction::blob::BYTES_PER_BLOB],
+            vec![0; crate::transaction::blob::BYTES_PER_COMMITMENT],
+            vec![0; crate::transaction::blob::BYTES_PER_COMMITMENT],
+        );
+
+        let err = TransactionBuilder::new(web3)
+            .to(Address::repeat_byte(0x42))
+            .blob_sidecar(sidecar)
+            .build()
+            .immediate()
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            ExecutionError::Blob(BlobError::UnsupportedAccount)
+        ));
+        transport.assert_no_more_requests();
+    }
 }
@@ -7,12 +7,13 @@
 //! some of this can move upstream into the `web3` crate.
 
 use crate::errors::ExecutionError;
-use crate::transaction::TransactionResult;
+use crate::transaction::{GasPrice, TransactionResult};
 use futures_timer::Delay;
 use std::cmp::min;
 use std::time::Duration;
 use web3::api::Web3;
-use web3::types::{TransactionReceipt, H256, U64};
+use web3::error::Error as Web3Error;
+use web3::types::{BlockId, BlockNumber, TransactionReceipt, H256, U256, U64};
 use web3::Transport;
 
 /// A struct with the confirmation parameters.
@@ -38,6 +39,145 @@ pub struct ConfirmParams {
     pub poll_interval_factor: f32,
     /// The maximum number of blocks to wait for a transaction to get confirmed.
     pub block_timeout: Option<usize>,
+    /// The block tag that the transaction's block must be included in for the
+    /// transaction to be confirmed. When set, the transaction is confirmed
+    /// once the block with this tag is at or above the transaction's block
+    /// instead of counting `confirmations`.
+    ///
+    /// For nodes that do not support the block tag, confirmation falls back to
+    /// counting `confirmations`.
+    pub finality: Option<Finality>,
+    /// The policy for replacing the transaction with one with a higher gas
+    /// price if it does not get mined in time. Defaults to no replacement.
+    pub replacement: Option<ReplacementPolicy>,
+}
+
+/// A policy for replacing a transaction that is not getting mined with a
+/// transaction with the same nonce and a higher gas price.
+///
+/// When a transaction is not mined within `blocks` blocks, it is rebroadcast
+/// with its gas price multiplied by `gas_price_factor`. All sent transactions
+/// are tracked until one of them is confirmed, since any of them may end up
+/// getting mined.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ReplacementPolicy {
+    /// The number of blocks to wait for a transaction to be mined before
+    /// replacing it.
+    pub blocks: usize,
+    /// The factor by which the gas price is increased for each replacement.
+    /// Nodes usually require replacement transactions to increase the gas
+    /// price by at least 10%.
+    pub gas_price_factor: f64,
+    /// The maximum number of replacement transactions to send.
+    pub max_replacements: usize,
+    /// The maximum gas price, or maximum fee per gas for EIP-1559
+    /// transactions, to bump to.
+    pub max_gas_price: Option<U256>,
+}
+
+/// The default number of blocks to wait before replacing a transaction.
+pub const DEFAULT_REPLACEMENT_BLOCKS: usize = 5;
+
+/// The default factor by which gas prices are increased for replacements.
+pub const DEFAULT_REPLACEMENT_GAS_PRICE_FACTOR: f64 = 1.125;
+
+/// The default maximum number of replacement transactions.
+pub const DEFAULT_MAX_REPLACEMENTS: usize = 3;
+
+impl ReplacementPolicy {
+    /// Set new value for [`blocks`].
+    ///
+    /// [`blocks`]: #structfield.blocks
+    #[inline]
+    pub fn blocks(mut self, blocks: usize) -> Self {
+        self.blocks = blocks;
+        self
+    }
+
+    /// Set new value for [`gas_price_factor`].
+    ///
+    /// [`gas_price_factor`]: #structfield.gas_price_factor
+    #[inline]
+    pub fn gas_price_factor(mut self, gas_price_factor: f64) -> Self {
+        self.gas_price_factor = gas_price_factor;
+        self
+    }
+
+    /// Set new value for [`max_replacements`].
+    ///
+    /// [`max_replacements`]: #structfield.max_replacements
+    #[inline]
+    pub fn max_replacements(mut self, max_replacements: usize) -> Self {
+        self.max_replacements = max_replacements;
+        self
+    }
+
+    /// Set new value for [`max_gas_price`].
+    ///
+    /// [`max_gas_price`]: #structfield.max_gas_price
+    #[inline]
+    pub fn max_gas_price(mut self, max_gas_price: Option<U256>) -> Self {
+        self.max_gas_price = max_gas_price;
+        self
+    }
+
+    /// Returns the bumped gas price for a replacement transaction, or `None`
+    /// if the gas price can't be increased because of the maximum gas price.
+    pub fn bump(&self, gas_price: GasPrice) -> Option<GasPrice> {
+        let bump = |value: U256| {
+            let bumped = U256::from_f64_lossy(value.to_f64_lossy() * self.gas_price_factor)
+                .max(value.saturating_add(1.into()));
+            match self.max_gas_price {
+                Some(max_gas_price) => bumped.min(max_gas_price.max(value)),
+                None => bumped,
+            }
+        };
+
+        let bumped = match gas_price {
+            GasPrice::Legacy(value) => GasPrice::Legacy(bump(value)),
+            GasPrice::Eip1559 {
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+            } => {
+                let max_fee_per_gas = bump(max_fee_per_gas);
+                GasPrice::Eip1559 {
+                    max_fee_per_gas,
+                    max_priority_fee_per_gas: bump(max_priority_fee_per_gas).min(max_fee_per_gas),
+                }
+            }
+        };
+        (bumped != gas_price).then_some(bumped)
+    }
+}
+
+impl Default for ReplacementPolicy {
+    fn default() -> Self {
+        ReplacementPolicy {
+            blocks: DEFAULT_REPLACEMENT_BLOCKS,
+            gas_price_factor: DEFAULT_REPLACEMENT_GAS_PRICE_FACTOR,
+            max_replacements: DEFAULT_MAX_REPLACEMENTS,
+            max_gas_price: None,
+        }
+    }
+}
+
+/// A block tag for waiting until a transaction's block is final.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Finality {
+    /// The `safe` block tag, for blocks that are unlikely to be re-orged.
+    Safe,
+    /// The `finalized` block tag, for blocks that can't be re-orged without
+    /// violating consensus rules.
+    Finalized,
+}
+
+impl From<Finality> for BlockNumber {
+    fn from(finality: Finality) -> Self {
+        match finality {
+            Finality::Safe => BlockNumber::Safe,
+            Finality::Finalized => BlockNumber::Finalized,
+        }
+    }
 }
 
 /// Default minimal delay between polling the node for transaction confirmation.
@@ -61,6 +201,16 @@ const DEFAULT_POLL_INTERVAL_FACTOR: f32 = 0.0;
 /// The default block timeout to use for confirming transactions.
 pub const DEFAULT_BLOCK_TIMEOUT: Option<usize> = Some(25);
 
+/// The default number of confirmations to fall back to when waiting for a
+/// `safe` or `finalized` block on nodes that do not support these block tags.
+/// This is roughly two epochs on Ethereum mainnet.
+pub const DEFAULT_FINALITY_CONFIRMATIONS: usize = 64;
+
+/// The default block timeout to use for waiting for a `safe` or `finalized`
+/// block. This is larger than [`DEFAULT_BLOCK_TIMEOUT`] since finalization
+/// takes several epochs.
+pub const DEFAULT_FINALITY_BLOCK_TIMEOUT: Option<usize> = Some(256);
+
 impl ConfirmParams {
     /// Create new confirmation parameters for just confirming that the
     /// transaction was mined but not confirmed with any extra blocks.
@@ -77,6 +227,34 @@ impl ConfirmParams {
             poll_interval_max: DEFAULT_POLL_INTERVAL_MAX,
             poll_interval_factor: DEFAULT_POLL_INTERVAL_FACTOR,
             block_timeout: DEFAULT_BLOCK_TIMEOUT,
+            finality: None,
+            replacement: None,
+        }
+    }
+
+    /// Create new confirmation parameters for waiting until the transaction's
+    /// block is finalized, as reported by the `finalized` block tag.
+    ///
+    /// For nodes that do not support the `finalized` block tag, this falls
+    /// back to waiting for [`DEFAULT_FINALITY_CONFIRMATIONS`] confirmations.
+    pub fn finalized() -> Self {
+        ConfirmParams::with_finality(Finality::Finalized)
+    }
+
+    /// Create new confirmation parameters for waiting until the transaction's
+    /// block is safe, as reported by the `safe` block tag.
+    ///
+    /// For nodes that do not support the `safe` block tag, this falls back to
+    /// waiting for [`DEFAULT_FINALITY_CONFIRMATIONS`] confirmations.
+    pub fn safe() -> Self {
+        ConfirmParams::with_finality(Finality::Safe)
+    }
+
+    fn with_finality(finality: Finality) -> Self {
+        ConfirmParams {
+            block_timeout: DEFAULT_FINALITY_BLOCK_TIMEOUT,
+            finality: Some(finality),
+            ..ConfirmParams::with_confirmations(DEFAULT_FINALITY_CONFIRMATIONS)
         }
     }
 
@@ -133,6 +311,24 @@ impl ConfirmParams {
         self.block_timeout = block_timeout;
         self
     }
+
+    /// Set new value for [`finality`].
+    ///
+    /// [`finality`]: #structfield.finality
+    #[inline]
+    pub fn finality(mut self, finality: Option<Finality>) -> Self {
+        self.finality = finality;
+        self
+    }
+
+    /// Set new value for [`replacement`].
+    ///
+    /// [`replacement`]: #structfield.replacement
+    #[inline]
+    pub fn replacement(mut self, replacement: Option<ReplacementPolicy>) -> Self {
+        self.replacement = replacement;
+        self
+    }
 }
 
 impl Default for ConfirmParams {
@@ -146,13 +342,27 @@ pub async fn wait_for_confirmation<T: Transport>(
     web3: &Web3<T>,
     tx: H256,
     params: ConfirmParams,
+) -> Result<TransactionReceipt, ExecutionError> {
+    wait_for_any_confirmation(web3, &[tx], params).await
+}
+
+/// Waits for any one of several transactions to be confirmed, for example
+/// when a transaction was replaced with one using the same nonce.
+///
+/// On timeout, the error contains the last of the transactions if none of them
+/// were mined.
+pub async fn wait_for_any_confirmation<T: Transport>(
+    web3: &Web3<T>,
+    txs: &[H256],
+    params: ConfirmParams,
 ) -> Result<TransactionReceipt, ExecutionError> {
     let mut latest_block = None;
     let mut context = ConfirmationContext {
         web3,
-        tx,
+        txs,
         params,
         starting_block: None,
+        finality_supported: true,
     };
 
     loop {
@@ -169,14 +379,19 @@ pub async fn wait_for_confirmation<T: Transport>(
 #[derive(Debug)]
 struct ConfirmationContext<'a, T: Transport> {
     web3: &'a Web3<T>,
-    /// The transaction hash that is being confirmed.
-    tx: H256,
+    /// The hashes of the transactions that are being confirmed, any one of
+    /// which confirming is sufficient.
+    txs: &'a [H256],
     /// The confirmation parameters (like number of confirming blocks to wait
     /// for and polling interval).
     params: ConfirmParams,
     /// The current block number when confirmation started. This is used for
     /// timeouts.
     starting_block: Option<U64>,
+    /// Whether the node supports the finality block tag. This is set to
+    /// `false` once the node fails to return a block for the tag, in which
+    /// case confirmation falls back to counting confirmations.
+    finality_supported: bool,
 }
 
 impl<T: Transport> ConfirmationContext<'_, T> {
@@ -189,30 +404,50 @@ impl<T: Transport> ConfirmationContext<'_, T> {
             Some(value) => value,
             None => self.web3.eth().block_number().await?,
         };
-        let tx = self.web3.eth().transaction_receipt(self.tx).await?;
+        let mut tx = None;
+        for hash in self.txs {
+            tx = self.web3.eth().transaction_receipt(*hash).await?;
+            if tx.is_some() {
+                break;
+            }
+        }
 
         let (target_block, tx_result) = match tx.and_then(|tx| Some((tx.block_number?, tx))) {
-            Some((tx_block, tx)) => {
-                let target_block = tx_block + self.params.confirmations;
-
-                // This happens in two cases:
-                // - we don't need additional confirmation, transaction receipt is enough,
-                // - the transaction was mined before we queried `latest_block`, thus
-                //   `latest_block >= tx_block`.
-                if latest_block >= target_block || self.params.confirmations == 0 {
+            Some((tx_block, tx)) => match self.finality_block().await? {
+                Some(finality_block) if finality_block >= tx_block => {
                     return Ok(Check::Confirmed(tx));
                 }
-
-                (target_block, TransactionResult::Receipt(tx))
-            }
+                // The final block does not advance at a fixed distance from
+                // the latest block, so check again on the next block.
+                Some(_) => (latest_block + 1, TransactionResult::Receipt(tx)),
+                None => {
+                    let target_block = tx_block + self.params.confirmations;
+
+                    // This happens in two cases:
+                    // - we don't need additional confirmation, transaction receipt is enough,
+                    // - the transaction was mined before we queried `latest_block`, thus
+                    //   `latest_block >= tx_block`.
+                    if latest_block >= target_block || self.params.confirmations == 0 {
+                        return Ok(Check::Confirmed(tx));
+                    }
+
+                    (target_block, TransactionResult::Receipt(tx))
+                }
+            },
             None => {
                 // We know that transaction was not mined at block `latest_block` because
                 // we've fetched `latest_block` before we've fetched transaction receipt.
                 // Thus, we need to wait at least one block after the `latest_block`,
-                // and then `self.params.confirmations` blocks on top of that.
+                // and then `self.params.confirmations` blocks on top of that, unless
+                // we are waiting for a final block.
+                let confirmations = if self.waits_for_finality() {
+                    0
+                } else {
+                    self.params.confirmations
+                };
                 (
-                    latest_block + self.params.confirmations + 1,
-                    TransactionResult::Hash(self.tx),
+                    latest_block + confirmations + 1,
+                    TransactionResult::Hash(self.txs.last().copied().unwrap_or_default()),
                 )
             }
         };
@@ -229,6 +464,41 @@ impl<T: Transport> ConfirmationContext<'_, T> {
         Ok(Check::Pending(target_block))
     }
 
+    /// Returns `true` if confirmation waits for a final block instead of
+    /// counting confirmations.
+    fn waits_for_finality(&self) -> bool {
+        self.params.finality.is_some() && self.finality_supported
+    }
+
+    /// Retrieves the number of the block with the configured finality tag.
+    ///
+    /// Returns `None` if no finality is configured or the node does not
+    /// support the block tag.
+    async fn finality_block(&mut self) -> Result<Option<U64>, ExecutionError> {
+        let finality = match self.params.finality {
+            Some(finality) if self.finality_supported => finality,
+            _ => return Ok(None),
+        };
+
+        let block = match self
+            .web3
+            .eth()
+            .block(BlockId::Number(finality.into()))
+            .await
+        {
+            Ok(block) => block.and_then(|block| block.number),
+            // Nodes that do not know the block tag reject it as an invalid
+            // parameter.
+            Err(Web3Error::Rpc(_)) => None,
+            Err(err) => return Err(err.into()),
+        };
+        if block.is_none() {
+            self.finality_supported = false;
+        }
+
+        Ok(block)
+    }
+
     /// Waits for blocks to be mined. This method polls the latest block number
     /// and waits till the target block number is reached.
     ///
@@ -274,7 +544,7 @@ enum Check {
 /// This method is used so that unit tests resolve immediately, as the `Delay`
 /// future always returns `Poll::Pending` at least once, even with a delay or
 /// zero.
-async fn delay(duration: Duration) {
+pub(crate) async fn delay(duration: Duration) {
     const ZERO_DURATION: Duration = Duration::from_secs(0);
 
     if duration != ZERO_DURATION {
@@ -287,7 +557,7 @@ mod tests {
     use super::*;
     use crate::test::prelude::*;
     use serde_json::Value;
-    use web3::types::H2048;
+    use web3::types::{Block, H2048};
 
     fn generate_tx_receipt<U: Into<U64>>(hash: H256, block_num: U) -> Value {
         json!({
@@ -303,6 +573,13 @@ mod tests {
         })
     }
 
+    fn generate_block<U: Into<U64>>(block_num: U) -> Value {
+        json!(Block::<H256> {
+            number: Some(block_num.into()),
+            ..Default::default()
+        })
+    }
+
     #[test]
     fn confirm_mined_transaction() {
         let mut transport = TestTransport::new();
@@ -560,6 +837,48 @@ mod tests {
         transport.assert_no_more_requests();
     }
 
+    #[test]
+    fn replacement_policy_bumps_gas_price() {
+        let policy = ReplacementPolicy::default();
+        assert_eq!(
+            policy.bump(GasPrice::Legacy(100.into())),
+            Some(GasPrice::Legacy(112.into())),
+        );
+        assert_eq!(
+            policy.bump(GasPrice::Legacy(1.into())),
+            Some(GasPrice::Legacy(2.into())),
+        );
+        assert_eq!(policy.bump((200.0, 8.0).into()), Some((225.0, 9.0).into()),);
+
+        let capped = policy.max_gas_price(Some(120.into()));
+        assert_eq!(
+            capped.bump(GasPrice::Legacy(110.into())),
+            Some(GasPrice::Legacy(120.into())),
+        );
+        assert_eq!(capped.bump(GasPrice::Legacy(120.into())), None);
+    }
+
+    #[test]
+    fn confirm_any_transaction() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let hashes = [H256::repeat_byte(1), H256::repeat_byte(2)];
+
+        transport.add_response(json!("0x1"));
+        transport.add_response(json!(null));
+        transport.add_response(generate_tx_receipt(hashes[1], 1));
+        let confirm = wait_for_any_confirmation(&web3, &hashes, ConfirmParams::mined())
+            .immediate()
+            .expect("transaction confirmation failed");
+
+        assert_eq!(confirm.transaction_hash, hashes[1]);
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_getTransactionReceipt", &[json!(hashes[0])]);
+        transport.assert_request("eth_getTransactionReceipt", &[json!(hashes[1])]);
+        transport.assert_no_more_requests();
+    }
+
     #[test]
     fn confirmation_timeout() {
         let mut transport = TestTransport::new();
@@ -603,4 +922,65 @@ mod tests {
         transport.assert_request("eth_getTransactionReceipt", &[json!(hash)]);
         transport.assert_no_more_requests();
     }
+
+    #[test]
+    fn confirm_finalized_transaction() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let hash = H256::repeat_byte(0xff);
+
+        // transaction mined on block 3, but finalized block is 2
+        transport.add_response(json!("0x5"));
+        transport.add_response(generate_tx_receipt(hash, 3));
+        transport.add_response(generate_block(2));
+        // poll for one block
+        transport.add_response(json!("0x6"));
+        // finalized block reached the transaction's block
+        transport.add_response(generate_tx_receipt(hash, 3));
+        transport.add_response(generate_block(3));
+
+        let confirm = wait_for_confirmation(&web3, hash, ConfirmParams::finalized())
+            .immediate()
+            .expect("transaction confirmation failed");
+
+        assert_eq!(confirm.transaction_hash, hash);
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_getTransactionReceipt", &[json!(hash)]);
+        transport.assert_request("eth_getBlockByNumber", &[json!("finalized"), json!(false)]);
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_getTransactionReceipt", &[json!(hash)]);
+        transport.assert_request("eth_getBlockByNumber", &[json!("finalized"), json!(false)]);
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn finality_falls_back_to_confirmations() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let hash = H256::repeat_byte(0xff);
+
+        // node does not support the `safe` block tag
+        transport.add_response(json!("0x1"));
+        transport.add_response(generate_tx_receipt(hash, 1));
+        transport.add_error(Web3Error::Rpc(jsonrpc_core::Error::invalid_params(
+            "unknown block tag",
+        )));
+        // wait for one confirmation instead
+        transport.add_response(json!("0x2"));
+        transport.add_response(generate_tx_receipt(hash, 1));
+
+        let confirm = wait_for_confirmation(&web3, hash, ConfirmParams::safe().confirmations(1))
+            .immediate()
+            .expect("transaction confirmation failed");
+
+        assert_eq!(confirm.transaction_hash, hash);
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_getTransactionReceipt", &[json!(hash)]);
+        transport.assert_request("eth_getBlockByNumber", &[json!("safe"), json!(false)]);
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_getTransactionReceipt", &[json!(hash)]);
+        transport.assert_no_more_requests();
+    }
 }
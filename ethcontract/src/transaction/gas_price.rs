@@ -1,7 +1,21 @@
 //! Implementation of gas price estimation.
 
+use crate::errors::ExecutionError;
+use futures::future::{BoxFuture, FutureExt};
 use primitive_types::U256;
-use web3::types::U64;
+use std::fmt::{self, Debug, Formatter};
+use std::future::Future;
+use std::sync::Arc;
+use web3::api::Web3;
+use web3::types::{BlockNumber, U64};
+use web3::Transport;
+
+/// The number of recent blocks to consider when estimating EIP-1559 fees.
+pub const FEE_HISTORY_BLOCK_COUNT: u64 = 10;
+
+/// The percentile of effective priority fees paid in recent blocks to use as
+/// the estimated priority fee.
+pub const FEE_HISTORY_REWARD_PERCENTILE: f64 = 50.0;
 
 #[derive(Debug, Default, Eq, PartialEq)]
 /// Data related to gas price, prepared for populating the transaction object.
@@ -54,6 +68,121 @@ impl GasPrice {
     }
 }
 
+impl GasPrice {
+    /// Estimates EIP-1559 fees from the fee history of recent blocks with
+    /// `eth_feeHistory`.
+    ///
+    /// The priority fee is the median of the priority fees paid at the
+    /// [`FEE_HISTORY_REWARD_PERCENTILE`] over the last
+    /// [`FEE_HISTORY_BLOCK_COUNT`] blocks, and the maximum fee allows the base
+    /// fee of the next block to double before the transaction is priced out.
+    pub async fn estimate_eip1559<T: Transport>(web3: &Web3<T>) -> Result<Self, ExecutionError> {
+        let history = web3
+            .eth()
+            .fee_history(
+                FEE_HISTORY_BLOCK_COUNT.into(),
+                BlockNumber::Latest,
+                Some(vec![FEE_HISTORY_REWARD_PERCENTILE]),
+            )
+            .await?;
+
+        // The fee history includes the base fee of the next block after the
+        // newest block of the returned range, which is what we are pricing.
+        let base_fee = history.base_fee_per_gas.last().copied().unwrap_or_default();
+
+        let mut rewards = history
+            .reward
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|rewards| rewards.first().copied())
+            .collect::<Vec<_>>();
+        rewards.sort_unstable();
+        let max_priority_fee_per_gas = rewards.get(rewards.len() / 2).copied().unwrap_or_default();
+
+        Ok(GasPrice::Eip1559 {
+            max_fee_per_gas: base_fee
+                .saturating_mul(2.into())
+                .saturating_add(max_priority_fee_per_gas),
+            max_priority_fee_per_gas,
+        })
+    }
+
+    /// Returns the gas price with the specified maximum fee per gas. A legacy
+    /// gas price is converted to an EIP-1559 one, with the priority fee
+    /// defaulting to the maximum fee.
+    pub fn with_max_fee_per_gas(self, value: U256) -> Self {
+        match self {
+            GasPrice::Eip1559 {
+                max_priority_fee_per_gas,
+                ..
+            } => GasPrice::Eip1559 {
+                max_fee_per_gas: value,
+                max_priority_fee_per_gas,
+            },
+            GasPrice::Legacy(_) => GasPrice::Eip1559 {
+                max_fee_per_gas: value,
+                max_priority_fee_per_gas: value,
+            },
+        }
+    }
+
+    /// Returns the gas price with the specified maximum priority fee per gas.
+    /// A legacy gas price is converted to an EIP-1559 one, with the maximum
+    /// fee defaulting to the priority fee.
+    pub fn with_max_priority_fee_per_gas(self, value: U256) -> Self {
+        match self {
+            GasPrice::Eip1559 {
+                max_fee_per_gas, ..
+            } => GasPrice::Eip1559 {
+                max_fee_per_gas,
+                max_priority_fee_per_gas: value,
+            },
+            GasPrice::Legacy(_) => GasPrice::Eip1559 {
+                max_fee_per_gas: value,
+                max_priority_fee_per_gas: value,
+            },
+        }
+    }
+}
+
+type GasPriceFn<T> =
+    dyn Fn(Web3<T>) -> BoxFuture<'static, Result<GasPrice, ExecutionError>> + Send + Sync;
+
+/// A strategy for determining the gas price of a transaction at the moment it
+/// is sent, for example by querying a custom gas price oracle.
+///
+/// Transactions with a replacement policy consult the strategy again before
+/// sending each replacement transaction.
+pub struct GasPriceStrategy<T: Transport>(Arc<GasPriceFn<T>>);
+
+impl<T: Transport> GasPriceStrategy<T> {
+    /// Creates a new gas price strategy from an async closure.
+    pub fn new<F, Fut>(strategy: F) -> Self
+    where
+        F: Fn(Web3<T>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<GasPrice, ExecutionError>> + Send + 'static,
+    {
+        GasPriceStrategy(Arc::new(move |web3| strategy(web3).boxed()))
+    }
+
+    /// Determines the gas price to use for a transaction.
+    pub async fn gas_price(&self, web3: &Web3<T>) -> Result<GasPrice, ExecutionError> {
+        (self.0)(web3.clone()).await
+    }
+}
+
+impl<T: Transport> Clone for GasPriceStrategy<T> {
+    fn clone(&self) -> Self {
+        GasPriceStrategy(self.0.clone())
+    }
+}
+
+impl<T: Transport> Debug for GasPriceStrategy<T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("GasPriceStrategy").finish_non_exhaustive()
+    }
+}
+
 impl From<U256> for GasPrice {
     fn from(value: U256) -> Self {
         GasPrice::Legacy(value)
@@ -84,6 +213,7 @@ impl From<(f64, f64)> for GasPrice {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test::prelude::*;
 
     #[test]
     fn resolve_for_transaction_legacy() {
@@ -139,4 +269,54 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn with_eip1559_fees() {
+        let gas_price = GasPrice::Legacy(100.into())
+            .with_max_fee_per_gas(200.into())
+            .with_max_priority_fee_per_gas(2.into());
+        assert_eq!(
+            gas_price,
+            GasPrice::Eip1559 {
+                max_fee_per_gas: 200.into(),
+                max_priority_fee_per_gas: 2.into(),
+            }
+        );
+
+        let gas_price = GasPrice::Legacy(100.into()).with_max_priority_fee_per_gas(2.into());
+        assert_eq!(
+            gas_price,
+            GasPrice::Eip1559 {
+                max_fee_per_gas: 2.into(),
+                max_priority_fee_per_gas: 2.into(),
+            }
+        );
+    }
+
+    #[test]
+    fn estimate_eip1559_fees() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        transport.add_response(json!({
+            "oldestBlock": "0x10",
+            "baseFeePerGas": ["0x64", "0x6e", "0x78", "0x82"],
+            "gasUsedRatio": [0.5, 0.9, 0.7],
+            "reward": [["0x3"], ["0x1"], ["0x2"]],
+        }));
+        let gas_price = GasPrice::estimate_eip1559(&web3).immediate().unwrap();
+
+        transport.assert_request(
+            "eth_feeHistory",
+            &[json!("0xa"), json!("latest"), json!([50.0])],
+        );
+        transport.assert_no_more_requests();
+        assert_eq!(
+            gas_price,
+            GasPrice::Eip1559 {
+                max_fee_per_gas: (130 * 2 + 2).into(),
+                max_priority_fee_per_gas: 2.into(),
+            }
+        );
+    }
 }
@@ -0,0 +1,114 @@
+//! Classification of EIP-2718 typed transaction envelopes.
+
+use web3::types::{Transaction, TransactionReceipt, U64};
+
+/// The type of a transaction envelope as specified in EIP-2718.
+///
+/// Nodes for L2 chains add their own transaction envelopes, such as deposit
+/// transactions on OP stack chains or the various Arbitrum transaction
+/// types. These are represented by the `Other` variant with the raw type byte
+/// so that transactions and receipts of any type can be handled.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum TransactionType {
+    /// A legacy, untyped transaction.
+    Legacy,
+    /// An EIP-2930 access list transaction.
+    AccessList,
+    /// An EIP-1559 dynamic fee transaction.
+    Eip1559,
+    /// An EIP-4844 blob transaction.
+    Blob,
+    /// An EIP-7702 set code transaction.
+    SetCode,
+    /// A chain specific transaction type, for example `0x7e` for OP stack
+    /// deposit transactions.
+    Other(u8),
+}
+
+impl TransactionType {
+    /// Returns the transaction type for an optional `type` field of a
+    /// transaction or receipt returned by a node. A missing type is a legacy
+    /// transaction, and `None` is returned for values that don't fit into a
+    /// type byte.
+    pub fn from_field(value: Option<U64>) -> Option<Self> {
+        match value {
+            None => Some(TransactionType::Legacy),
+            Some(value) => u8::try_from(value.as_u64()).ok().map(Self::from),
+        }
+    }
+
+    /// Returns the transaction type of a transaction.
+    pub fn of_transaction(tx: &Transaction) -> Option<Self> {
+        Self::from_field(tx.transaction_type)
+    }
+
+    /// Returns the transaction type of a transaction receipt.
+    pub fn of_receipt(receipt: &TransactionReceipt) -> Option<Self> {
+        Self::from_field(receipt.transaction_type)
+    }
+
+    /// Returns the raw type byte.
+    pub fn as_u8(&self) -> u8 {
+        match self {
+            TransactionType::Legacy => 0,
+            TransactionType::AccessList => 1,
+            TransactionType::Eip1559 => 2,
+            TransactionType::Blob => 3,
+            TransactionType::SetCode => 4,
+            TransactionType::Other(value) => *value,
+        }
+    }
+
+    /// Returns true if the transaction type is one of the types specified for
+    /// Ethereum mainnet.
+    pub fn is_standard(&self) -> bool {
+        !matches!(self, TransactionType::Other(_))
+    }
+}
+
+impl From<u8> for TransactionType {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => TransactionType::Legacy,
+            1 => TransactionType::AccessList,
+            2 => TransactionType::Eip1559,
+            3 => TransactionType::Blob,
+            4 => TransactionType::SetCode,
+            value => TransactionType::Other(value),
+        }
+    }
+}
+
+impl From<TransactionType> for U64 {
+    fn from(value: TransactionType) -> Self {
+        value.as_u8().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transaction_type_roundtrip() {
+        for value in 0..=u8::MAX {
+            assert_eq!(TransactionType::from(value).as_u8(), value);
+        }
+        assert_eq!(TransactionType::from(0x7e), TransactionType::Other(0x7e));
+        assert!(!TransactionType::Other(0x7e).is_standard());
+        assert!(TransactionType::Eip1559.is_standard());
+    }
+
+    #[test]
+    fn transaction_type_from_field() {
+        assert_eq!(
+            TransactionType::from_field(None),
+            Some(TransactionType::Legacy)
+        );
+        assert_eq!(
+            TransactionType::from_field(Some(0x64.into())),
+            Some(TransactionType::Other(0x64))
+        );
+        assert_eq!(TransactionType::from_field(Some(0x100.into())), None);
+    }
+}
@@ -0,0 +1,129 @@
+//! Implementation of a nonce manager for sending concurrent transactions from
+//! the same account.
+
+use crate::errors::ExecutionError;
+use futures::lock::Mutex;
+use std::collections::HashMap;
+use web3::api::Web3;
+use web3::types::{Address, BlockNumber, U256};
+use web3::Transport;
+
+/// A nonce manager that caches and atomically increments nonces per account.
+///
+/// Without a nonce manager, each transaction queries the node for the signing
+/// account's transaction count, so concurrently sending multiple transactions
+/// from the same account can produce nonce collisions. A nonce manager can be
+/// shared between transaction builders with an `Arc` so that each transaction
+/// is assigned a unique nonce.
+///
+/// The first nonce for an account is the account's pending transaction count
+/// as reported by the node. If sending a transaction fails, the cached nonce
+/// for the account is reset so that it is queried again for the next
+/// transaction.
+#[derive(Debug, Default)]
+pub struct NonceManager {
+    nonces: Mutex<HashMap<Address, U256>>,
+}
+
+impl NonceManager {
+    /// Creates a new nonce manager without any cached nonces.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the next nonce to use for the specified account and increments
+    /// the cached nonce. The nonce is queried from the node if it is not yet
+    /// cached.
+    pub async fn next<T: Transport>(
+        &self,
+        web3: &Web3<T>,
+        address: Address,
+    ) -> Result<U256, ExecutionError> {
+        let mut nonces = self.nonces.lock().await;
+        let nonce = match nonces.get(&address) {
+            Some(nonce) => *nonce,
+            None => {
+                web3.eth()
+                    .transaction_count(address, Some(BlockNumber::Pending))
+                    .await?
+            }
+        };
+        nonces.insert(address, nonce + 1);
+        Ok(nonce)
+    }
+
+    /// Returns the cached next nonce for the specified account, if any.
+    pub async fn peek(&self, address: Address) -> Option<U256> {
+        self.nonces.lock().await.get(&address).copied()
+    }
+
+    /// Sets the next nonce to use for the specified account.
+    pub async fn set(&self, address: Address, nonce: U256) {
+        self.nonces.lock().await.insert(address, nonce);
+    }
+
+    /// Clears the cached nonce for the specified account, so that it is
+    /// queried from the node for the next transaction.
+    pub async fn reset(&self, address: Address) {
+        self.nonces.lock().await.remove(&address);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::prelude::*;
+    use futures::future;
+
+    #[test]
+    fn caches_and_increments_nonces() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+        let manager = NonceManager::new();
+
+        let first = Address::repeat_byte(1);
+        let second = Address::repeat_byte(2);
+
+        transport.add_response(json!("0x2a"));
+        transport.add_response(json!("0x0"));
+        let nonces = future::try_join_all([
+            manager.next(&web3, first),
+            manager.next(&web3, first),
+            manager.next(&web3, second),
+            manager.next(&web3, first),
+        ])
+        .immediate()
+        .unwrap();
+
+        assert_eq!(nonces, [42.into(), 43.into(), 0.into(), 44.into()]);
+        transport.assert_request("eth_getTransactionCount", &[json!(first), json!("pending")]);
+        transport.assert_request(
+            "eth_getTransactionCount",
+            &[json!(second), json!("pending")],
+        );
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn reset_queries_nonce_again() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+        let manager = NonceManager::new();
+        let address = Address::repeat_byte(1);
+
+        manager.set(address, 5.into()).immediate();
+        assert_eq!(manager.next(&web3, address).immediate().unwrap(), 5.into());
+        assert_eq!(manager.peek(address).immediate(), Some(6.into()));
+
+        manager.reset(address).immediate();
+        assert_eq!(manager.peek(address).immediate(), None);
+
+        transport.add_response(json!("0x7"));
+        assert_eq!(manager.next(&web3, address).immediate().unwrap(), 7.into());
+        transport.assert_request(
+            "eth_getTransactionCount",
+            &[json!(address), json!("pending")],
+        );
+        transport.assert_no_more_requests();
+    }
+}
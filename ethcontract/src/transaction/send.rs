@@ -2,33 +2,57 @@
 //! confirmation.
 
 use crate::errors::ExecutionError;
-use crate::transaction::confirm;
-use crate::transaction::{ResolveCondition, Transaction, TransactionBuilder};
-use web3::types::{TransactionReceipt, H256, U64};
+use crate::transaction::confirm::{self, ConfirmParams};
+use crate::transaction::{
+    GasPrice, GasPriceStrategy, ResolveCondition, Transaction, TransactionBuilder, TransactionType,
+};
+use web3::api::Web3;
+use web3::types::{BlockNumber, TransactionReceipt, H256, U256, U64};
 use web3::Transport;
 
 impl<T: Transport> TransactionBuilder<T> {
     /// Sign (if required) and send the transaction. Returns the transaction
     /// hash that can be used to retrieve transaction information.
     pub async fn send(mut self) -> Result<TransactionResult, ExecutionError> {
+        self.check_calldata_size()?;
         let web3 = self.web3.clone();
         let resolve = self.resolve.take().unwrap_or_default();
+        let gas_price_strategy = self.gas_price_strategy.clone();
+        self.resolve_gas_price_strategy().await?;
 
-        let tx = self.build().await?;
-        let tx_hash = match tx {
-            Transaction::Request(tx) => web3.eth().send_transaction(tx).await?,
-            Transaction::Raw { bytes, hash } => {
-                let node_hash = web3.eth().send_raw_transaction(bytes).await?;
-                if node_hash != hash {
-                    return Err(ExecutionError::UnexpectedTransactionHash);
+        let managed_nonce = match (&self.nonce_manager, &self.from, self.nonce) {
+            (Some(manager), Some(from), None) => Some((manager.clone(), from.address())),
+            _ => None,
+        };
+        if let Some((manager, address)) = &managed_nonce {
+            self.nonce = Some(manager.next(&web3, *address).await?);
+        }
+
+        let replaceable = matches!(
+            &resolve,
+            ResolveCondition::Confirmed(ConfirmParams {
+                replacement: Some(_),
+                ..
+            })
+        );
+        let (tx_hash, replacement) = match self.send_replaceable(&web3, replaceable).await {
+            Ok(sent) => sent,
+            Err(err) => {
+                if let Some((manager, address)) = managed_nonce {
+                    manager.reset(address).await;
                 }
-                hash
+                return Err(err);
             }
         };
 
-        let tx_receipt = match resolve {
-            ResolveCondition::Pending => return Ok(TransactionResult::Hash(tx_hash)),
-            ResolveCondition::Confirmed(params) => {
+        let tx_receipt = match (resolve, replacement) {
+            (ResolveCondition::Pending, _) => return Ok(TransactionResult::Hash(tx_hash)),
+            (ResolveCondition::Confirmed(params), Some(replacement)) => {
+                replacement
+                    .confirm_with_replacement(&web3, tx_hash, params, gas_price_strategy)
+                    .await
+            }
+            (ResolveCondition::Confirmed(params), None) => {
                 confirm::wait_for_confirmation(&web3, tx_hash, params).await
             }
         }?;
@@ -38,6 +62,164 @@ impl<T: Transport> TransactionBuilder<T> {
             _ => Err(ExecutionError::Failure(Box::new(tx_receipt))),
         }
     }
+
+    /// Builds and sends the transaction, returning its hash. If the
+    /// transaction should be replaceable, then its nonce and gas price are
+    /// resolved before sending and a copy of the builder for sending
+    /// replacement transactions is returned along with the hash.
+    async fn send_replaceable(
+        mut self,
+        web3: &Web3<T>,
+        replaceable: bool,
+    ) -> Result<(H256, Option<Self>), ExecutionError> {
+        if !replaceable {
+            return Ok((self.build_and_send(web3).await?, None));
+        }
+
+        if self.nonce.is_none() {
+            self.nonce = Some(self.pending_nonce(web3).await?);
+        }
+        if self.gas_price.is_none() {
+            self.gas_price = Some(GasPrice::Legacy(web3.eth().gas_price().await?));
+        }
+
+        let replacement = self.clone();
+        Ok((self.build_and_send(web3).await?, Some(replacement)))
+    }
+
+    /// Returns the nonce of the next transaction of the sender, counting
+    /// pending transactions. The sender is the first account of the node if
+    /// no account was specified.
+    pub(crate) async fn pending_nonce(&self, web3: &Web3<T>) -> Result<U256, ExecutionError> {
+        let from = match &self.from {
+            Some(account) => account.address(),
+            None => *web3
+                .eth()
+                .accounts()
+                .await?
+                .first()
+                .ok_or(ExecutionError::NoLocalAccounts)?,
+        };
+        // NOTE: Use the pending transaction count, as the latest one is
+        //   the nonce of a pending transaction of the account if it has
+        //   any, which would then be replaced.
+        Ok(web3
+            .eth()
+            .transaction_count(from, Some(BlockNumber::Pending))
+            .await?)
+    }
+
+    /// Waits for a transaction to be confirmed, replacing it with transactions
+    /// with bumped gas prices according to the replacement policy of the
+    /// confirmation parameters if it does not get mined in time. If a gas
+    /// price strategy is specified, then replacements use its gas price
+    /// instead when it is higher than the bumped gas price.
+    async fn confirm_with_replacement(
+        self,
+        web3: &Web3<T>,
+        tx_hash: H256,
+        params: ConfirmParams,
+        gas_price_strategy: Option<GasPriceStrategy<T>>,
+    ) -> Result<TransactionReceipt, ExecutionError> {
+        let policy = params.replacement.unwrap_or_default();
+        let mut gas_price = self.gas_price.unwrap_or(GasPrice::Legacy(0.into()));
+        let mut tx_hashes = vec![tx_hash];
+
+        for _ in 0..policy.max_replacements {
+            // Only wait for one of the transactions to be mined, as once it
+            // is it can no longer be replaced.
+            let mined = ConfirmParams {
+                confirmations: 0,
+                finality: None,
+                block_timeout: Some(policy.blocks),
+                ..params.clone()
+            };
+            match confirm::wait_for_any_confirmation(web3, &tx_hashes, mined).await {
+                Ok(tx_receipt) if params.confirmations == 0 && params.finality.is_none() => {
+                    return Ok(tx_receipt);
+                }
+                Ok(tx_receipt) => {
+                    return confirm::wait_for_confirmation(
+                        web3,
+                        tx_receipt.transaction_hash,
+                        params,
+                    )
+                    .await;
+                }
+                Err(ExecutionError::ConfirmTimeout(_)) => (),
+                Err(err) => return Err(err),
+            }
+
+            gas_price = match policy.bump(gas_price) {
+                Some(gas_price) => gas_price,
+                None => break,
+            };
+            // A failing strategy should not prevent the transaction from
+            // being replaced, so fall back to the bumped gas price.
+            if let Some(strategy) = &gas_price_strategy {
+                if let Ok(strategy_gas_price) = strategy.gas_price(web3).await {
+                    gas_price = max_gas_price(gas_price, strategy_gas_price);
+                }
+            }
+            // Sending the replacement fails if one of the previous
+            // transactions was mined in the meantime, in which case we keep
+            // waiting for the existing transactions.
+            match self.clone().gas_price(gas_price).build_and_send(web3).await {
+                Ok(tx_hash) => tx_hashes.push(tx_hash),
+                Err(_) => break,
+            }
+        }
+
+        confirm::wait_for_any_confirmation(web3, &tx_hashes, params).await
+    }
+
+    /// Builds and sends the transaction, returning its hash.
+    async fn build_and_send(self, web3: &Web3<T>) -> Result<H256, ExecutionError> {
+        self.build().await?.send(web3).await
+    }
+}
+
+impl Transaction {
+    /// Sends a prepared transaction, returning its hash. For signed raw
+    /// transactions, the hash returned by the node is checked against the
+    /// precomputed one.
+    pub async fn send<T: Transport>(self, web3: &Web3<T>) -> Result<H256, ExecutionError> {
+        match self {
+            Transaction::Request(tx) => Ok(web3.eth().send_transaction(tx).await?),
+            Transaction::Raw { bytes, hash } => {
+                let node_hash = web3.eth().send_raw_transaction(bytes).await?;
+                if node_hash != hash {
+                    return Err(ExecutionError::UnexpectedTransactionHash);
+                }
+                Ok(hash)
+            }
+        }
+    }
+}
+
+/// Returns the higher of two gas prices, so that a replacement transaction
+/// pays at least as much as either gas price.
+fn max_gas_price(a: GasPrice, b: GasPrice) -> GasPrice {
+    let fees = |gas_price| match gas_price {
+        GasPrice::Legacy(value) => (value, value),
+        GasPrice::Eip1559 {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+        } => (max_fee_per_gas, max_priority_fee_per_gas),
+    };
+
+    match (a, b) {
+        (GasPrice::Legacy(a), GasPrice::Legacy(b)) => GasPrice::Legacy(a.max(b)),
+        (a, b) => {
+            let (a_max_fee, a_priority_fee) = fees(a);
+            let (b_max_fee, b_priority_fee) = fees(b);
+            let max_fee_per_gas = a_max_fee.max(b_max_fee);
+            GasPrice::Eip1559 {
+                max_fee_per_gas,
+                max_priority_fee_per_gas: a_priority_fee.max(b_priority_fee).min(max_fee_per_gas),
+            }
+        }
+    }
 }
 
 /// Represents the result of a sent transaction that can either be a transaction
@@ -89,4 +271,11 @@ impl TransactionResult {
             _ => None,
         }
     }
+
+    /// Returns the type of the transaction envelope from the receipt. This
+    /// will return `None` if the result is only a hash, or if the node
+    /// reported a type that doesn't fit into a type byte.
+    pub fn transaction_type(&self) -> Option<TransactionType> {
+        self.as_receipt().and_then(TransactionType::of_receipt)
+    }
 }
@@ -0,0 +1,87 @@
+//! Module containing the ECDSA signature type returned when signing messages.
+
+use web3::types::{H256, H520};
+
+/// An ECDSA signature over a message.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Signature {
+    /// The R component of the signature.
+    pub r: H256,
+    /// The S component of the signature.
+    pub s: H256,
+    /// The V component of the signature.
+    pub v: u64,
+}
+
+impl Signature {
+    /// Creates a signature from its 65-byte `r || s || v` encoding, as
+    /// returned by the `eth_sign` and `personal_sign` RPC methods.
+    pub fn from_bytes(bytes: [u8; 65]) -> Self {
+        Signature {
+            r: H256::from_slice(&bytes[..32]),
+            s: H256::from_slice(&bytes[32..64]),
+            v: bytes[64] as _,
+        }
+    }
+
+    /// Encodes the signature into its 65-byte `r || s || v` encoding.
+    ///
+    /// Note that `v` is truncated to a single byte, so signatures with
+    /// EIP-155 replay protection can't be encoded this way.
+    pub fn to_bytes(&self) -> [u8; 65] {
+        let mut bytes = [0; 65];
+        bytes[..32].copy_from_slice(self.r.as_bytes());
+        bytes[32..64].copy_from_slice(self.s.as_bytes());
+        bytes[64] = self.v as _;
+        bytes
+    }
+}
+
+impl From<H520> for Signature {
+    fn from(bytes: H520) -> Self {
+        Signature::from_bytes(bytes.0)
+    }
+}
+
+impl From<web3::signing::Signature> for Signature {
+    fn from(signature: web3::signing::Signature) -> Self {
+        Signature {
+            r: signature.r,
+            s: signature.s,
+            v: signature.v,
+        }
+    }
+}
+
+impl From<Signature> for web3::signing::Signature {
+    fn from(signature: Signature) -> Self {
+        web3::signing::Signature {
+            r: signature.r,
+            s: signature.s,
+            v: signature.v,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signature_bytes_roundtrip() {
+        let mut bytes = [0x11; 65];
+        bytes[32..64].fill(0x22);
+        bytes[64] = 27;
+
+        let signature = Signature::from_bytes(bytes);
+        assert_eq!(
+            signature,
+            Signature {
+                r: H256::repeat_byte(0x11),
+                s: H256::repeat_byte(0x22),
+                v: 27,
+            }
+        );
+        assert_eq!(signature.to_bytes(), bytes);
+    }
+}
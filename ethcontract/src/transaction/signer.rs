@@ -0,0 +1,368 @@
+//! Pluggable signer implementation.
+//!
+//! The `Signer` trait allows transactions to be signed by arbitrary signing
+//! backends, such as hardware wallets or remote signing services, without
+//! requiring built-in support for them. Signers only need to produce ECDSA
+//! signatures for 32-byte digests; building and encoding the transaction is
+//! taken care of by the `TransactionBuilder`. Signers that need to inspect
+//! the transaction they sign can override `Signer::sign_transaction` to get
+//! its RLP encoded signing payload.
+
+use crate::eip712;
+use crate::errors::ExecutionError;
+use crate::secret::PrivateKey;
+#[cfg(feature = "aws-kms")]
+use crate::transaction::kms;
+use crate::transaction::signing::{self, DigestKey};
+use crate::transaction::threshold;
+use crate::transaction::Signature;
+use futures::future::{self, BoxFuture, FutureExt as _};
+use std::fmt::Debug;
+use web3::signing::Key;
+use web3::types::{Address, Bytes, SignedTransaction, TransactionParameters, H256};
+use web3::{Transport, Web3};
+
+/// A signer for an account.
+pub trait Signer: Debug + Send + Sync {
+    /// Returns the public address of the account the signer signs for.
+    fn address(&self) -> Address;
+
+    /// Signs a 32-byte digest.
+    ///
+    /// The `v` value of the returned signature is the recovery ID, that is
+    /// either `0` or `1`.
+    fn sign_digest(&self, digest: [u8; 32]) -> BoxFuture<'_, Result<Signature, Error>>;
+
+    /// Signs a transaction with all of its parameters resolved.
+    ///
+    /// The default implementation signs the signing hash of the transaction
+    /// with `sign_digest`. Signers that need to inspect the transaction, such
+    /// as hardware wallets that display it for confirmation, can override
+    /// this to sign its payload instead.
+    ///
+    /// The `v` value of the returned signature is the recovery ID, that is
+    /// either `0` or `1`. Replay protection is applied when the signature is
+    /// included in the transaction.
+    fn sign_transaction<'a>(
+        &'a self,
+        transaction: &'a UnsignedTransaction,
+    ) -> BoxFuture<'a, Result<Signature, Error>> {
+        self.sign_digest(transaction.signing_hash.0)
+    }
+
+    /// Signs a message prefixed with `"\x19Ethereum Signed Message:\n"` and its
+    /// length as specified by EIP-191, matching the `personal_sign` RPC
+    /// method.
+    ///
+    /// The default implementation signs the EIP-191 message hash with
+    /// `sign_digest`.
+    ///
+    /// The `v` value of the returned signature is either `27` or `28`.
+    fn sign_message<'a>(&'a self, message: &'a [u8]) -> BoxFuture<'a, Result<Signature, Error>> {
+        let digest = web3::signing::hash_message(message);
+        self.sign_digest(digest.0)
+            .map(|signature| signature.map(with_legacy_v))
+            .boxed()
+    }
+
+    /// Signs EIP-712 typed data with the specified domain separator and
+    /// message struct hash.
+    ///
+    /// The default implementation signs the EIP-712 signing hash computed
+    /// from them. Signers that need to distinguish typed data from other
+    /// digests, such as hardware wallets, can override this.
+    ///
+    /// The `v` value of the returned signature is either `27` or `28`.
+    fn sign_typed_data(
+        &self,
+        domain_separator: H256,
+        message_hash: H256,
+    ) -> BoxFuture<'_, Result<Signature, Error>> {
+        let digest = eip712::signing_hash(domain_separator, message_hash);
+        self.sign_digest(digest.0)
+            .map(|signature| signature.map(with_legacy_v))
+            .boxed()
+    }
+}
+
+impl Signer for PrivateKey {
+    fn address(&self) -> Address {
+        self.public_address()
+    }
+
+    fn sign_digest(&self, digest: [u8; 32]) -> BoxFuture<'_, Result<Signature, Error>> {
+        future::ready(sign_digest(self, digest)).boxed()
+    }
+}
+
+impl Signer for threshold::Account {
+    fn address(&self) -> Address {
+        self.public_address()
+    }
+
+    fn sign_digest(&self, digest: [u8; 32]) -> BoxFuture<'_, Result<Signature, Error>> {
+        async move {
+            let signature = self.sign(digest).await.map_err(Error::backend)?;
+            Ok(signature.into())
+        }
+        .boxed()
+    }
+}
+
+#[cfg(feature = "aws-kms")]
+impl Signer for kms::Account {
+    fn address(&self) -> Address {
+        self.public_address()
+    }
+
+    fn sign_digest(&self, digest: [u8; 32]) -> BoxFuture<'_, Result<Signature, Error>> {
+        async move {
+            let signature = self.sign(digest).await.map_err(Error::backend)?;
+            Ok(signature.into())
+        }
+        .boxed()
+    }
+}
+
+/// An unsigned transaction with all of its parameters resolved, as passed to
+/// `Signer::sign_transaction`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UnsignedTransaction {
+    /// The signing payload of the transaction. For typed transactions, this
+    /// is the EIP-2718 transaction type followed by the RLP encoded
+    /// transaction fields, and for legacy transactions the EIP-155 RLP
+    /// encoding including the chain ID.
+    pub payload: Bytes,
+    /// The hash to sign, that is the Keccak-256 hash of the payload.
+    pub signing_hash: H256,
+}
+
+/// Signs a transaction with a signer.
+pub(crate) async fn sign_transaction<T>(
+    web3: Web3<T>,
+    signer: &dyn Signer,
+    params: TransactionParameters,
+) -> Result<SignedTransaction, Error>
+where
+    T: Transport,
+{
+    let address = signer.address();
+    let transaction = web3
+        .accounts()
+        .sign_transaction(params, DigestKey(address))
+        .await?;
+    let unsigned = UnsignedTransaction {
+        payload: signing::signing_payload(&transaction)?,
+        signing_hash: transaction.message_hash,
+    };
+    let signature = sign_unsigned_transaction(signer, &unsigned).await?;
+
+    Ok(signing::replace_signature(transaction, signature.into())?)
+}
+
+/// Signs an unsigned transaction with a signer, verifying that the signature
+/// was produced by the signer's account.
+pub(crate) async fn sign_unsigned_transaction(
+    signer: &dyn Signer,
+    transaction: &UnsignedTransaction,
+) -> Result<Signature, Error> {
+    let signature = signer.sign_transaction(transaction).await?;
+    let recovered = web3::signing::recover(
+        transaction.signing_hash.as_bytes(),
+        &[signature.r.as_bytes(), signature.s.as_bytes()].concat(),
+        signature.v as _,
+    );
+    if recovered.ok() != Some(signer.address()) {
+        return Err(Error::InvalidSignature);
+    }
+
+    Ok(signature)
+}
+
+/// Signs a 32-byte digest with a private key.
+fn sign_digest(key: &PrivateKey, message: [u8; 32]) -> Result<Signature, Error> {
+    Key::sign_message(&key, &message)
+        .map(Signature::from)
+        .map_err(|_| Error::InvalidSignature)
+}
+
+/// Converts the recovery ID of a signature into the `27` or `28` `v` value
+/// used for signed messages.
+fn with_legacy_v(signature: Signature) -> Signature {
+    Signature {
+        v: signature.v + 27,
+        ..signature
+    }
+}
+
+/// Error type for when signing with a `Signer` fails.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// Error reported by the signing backend.
+    #[error("signer backend error: {0}")]
+    Backend(Box<dyn std::error::Error + Send + Sync>),
+    /// The signature was not produced by the signer's account.
+    #[error("invalid signature")]
+    InvalidSignature,
+    /// Error related to the Web3 interactions needed for signing
+    #[error(transparent)]
+    Web3(#[from] web3::error::Error),
+    /// Error related to decoding the transaction object
+    #[error(transparent)]
+    Rlp(#[from] rlp::DecoderError),
+}
+
+impl Error {
+    /// Creates a backend error from any error type.
+    pub fn backend<E>(err: E) -> Self
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        Error::Backend(Box::new(err))
+    }
+}
+
+impl From<Error> for ExecutionError {
+    fn from(err: Error) -> Self {
+        match err {
+            Error::Web3(err) => err.into(),
+            err => ExecutionError::Signer(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::prelude::*;
+    use crate::transaction::{Account, TransactionBuilder};
+    use ethcontract_common::hash::keccak256;
+    use std::sync::Arc;
+
+    /// A signer that delegates to a private key, standing in for an external
+    /// signing service that hashes the transaction payload itself.
+    #[derive(Debug)]
+    struct RemoteSigner(PrivateKey);
+
+    impl Signer for RemoteSigner {
+        fn address(&self) -> Address {
+            self.0.public_address()
+        }
+
+        fn sign_digest(&self, _: [u8; 32]) -> BoxFuture<'_, Result<Signature, Error>> {
+            unreachable!("remote signer only signs transaction payloads")
+        }
+
+        fn sign_transaction<'a>(
+            &'a self,
+            transaction: &'a UnsignedTransaction,
+        ) -> BoxFuture<'a, Result<Signature, Error>> {
+            let digest = keccak256(&transaction.payload.0);
+            assert_eq!(digest, transaction.signing_hash.0);
+            self.0.sign_digest(digest)
+        }
+    }
+
+    /// A signer that signs with a different key than its address.
+    #[derive(Debug)]
+    struct ImpostorSigner(PrivateKey);
+
+    impl Signer for ImpostorSigner {
+        fn address(&self) -> Address {
+            Address::repeat_byte(0x42)
+        }
+
+        fn sign_digest(&self, digest: [u8; 32]) -> BoxFuture<'_, Result<Signature, Error>> {
+            self.0.sign_digest(digest)
+        }
+    }
+
+    fn builder(web3: Web3<TestTransport>) -> TransactionBuilder<TestTransport> {
+        TransactionBuilder::new(web3)
+            .to(Address::repeat_byte(0x42))
+            .gas(0x1337.into())
+            .gas_price((1337.0, 42.0).into())
+            .nonce(0x42.into())
+    }
+
+    #[test]
+    fn signs_same_transaction_as_private_key() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let key = key!("0x0102030405060708091011121314151617181920212223242526272829303132");
+        let offline = builder(web3.clone())
+            .from(Account::Offline(key.clone(), Some(77777)))
+            .build()
+            .immediate()
+            .unwrap();
+
+        transport.add_response(json!("0x12fd1"));
+        let signed = builder(web3)
+            .from(Account::Signer(Arc::new(RemoteSigner(key))))
+            .build()
+            .immediate()
+            .unwrap();
+
+        transport.assert_request("eth_chainId", &[]);
+        transport.assert_no_more_requests();
+        assert_eq!(signed, offline);
+    }
+
+    #[test]
+    fn signs_same_legacy_transaction_as_private_key() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let key = key!("0x0102030405060708091011121314151617181920212223242526272829303132");
+        let offline = builder(web3.clone())
+            .gas_price(1337.0.into())
+            .from(Account::Offline(key.clone(), Some(77777)))
+            .build()
+            .immediate()
+            .unwrap();
+
+        transport.add_response(json!("0x12fd1"));
+        let signed = builder(web3)
+            .gas_price(1337.0.into())
+            .from(Account::Signer(Arc::new(RemoteSigner(key))))
+            .build()
+            .immediate()
+            .unwrap();
+
+        assert_eq!(signed, offline);
+    }
+
+    #[test]
+    fn rejects_signature_from_other_account() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let key = key!("0x0102030405060708091011121314151617181920212223242526272829303132");
+        transport.add_response(json!("0x12fd1"));
+        let result = builder(web3)
+            .from(Account::Signer(Arc::new(ImpostorSigner(key))))
+            .build()
+            .immediate();
+
+        assert!(matches!(
+            result,
+            Err(ExecutionError::Signer(Error::InvalidSignature))
+        ));
+    }
+
+    #[test]
+    fn sign_message_with_prefix() {
+        let key = key!("0x0102030405060708091011121314151617181920212223242526272829303132");
+        let signature = Signer::sign_message(&key, b"hello").immediate().unwrap();
+
+        assert!(signature.v == 27 || signature.v == 28);
+        let recovered = web3::signing::recover(
+            web3::signing::hash_message(b"hello").as_bytes(),
+            &[signature.r.as_bytes(), signature.s.as_bytes()].concat(),
+            (signature.v - 27) as _,
+        )
+        .unwrap();
+        assert_eq!(recovered, key.public_address());
+    }
+}
@@ -0,0 +1,173 @@
+//! Shared helpers for accounts that sign transaction digests asynchronously.
+//!
+//! The `web3` crate has utility methods for building and RLP encoding signed
+//! transactions that we want to reuse. Unfortunately it expects `sign`-ing to
+//! **not** be asynchronous. To work around this, we sign transactions with a
+//! `DigestKey` that returns dummy signatures including the all-important
+//! signing message, sign that message externally (AWS KMS, threshold signer
+//! sets, ...) and then adjust the returned `SignedTransaction` result with
+//! `replace_signature`.
+
+use ethcontract_common::hash::keccak256;
+use primitive_types::U256;
+use rlp::{Rlp, RlpStream};
+use secp256k1::constants::CURVE_ORDER;
+use web3::signing::{self, Signature};
+use web3::types::{Address, Bytes, SignedTransaction, H256};
+
+/// A web3 signing key adapter that produces a dummy signature for the account
+/// with the specified address.
+pub(crate) struct DigestKey(pub Address);
+
+impl web3::signing::Key for DigestKey {
+    fn sign(
+        &self,
+        message: &[u8],
+        chain_id: Option<u64>,
+    ) -> Result<Signature, web3::signing::SigningError> {
+        let signature = self.sign_message(message)?;
+        // apply EIP155
+        Ok(Signature {
+            v: if let Some(chain_id) = chain_id {
+                signature.v + 35 + chain_id * 2
+            } else {
+                signature.v + 27
+            },
+            ..signature
+        })
+    }
+
+    fn sign_message(&self, _: &[u8]) -> Result<Signature, web3::signing::SigningError> {
+        Ok(Signature {
+            r: H256::default(),
+            s: H256::default(),
+            v: 0,
+        })
+    }
+
+    fn address(&self) -> Address {
+        self.0
+    }
+}
+
+/// Converts a compact `r || s` ECDSA signature over `message` into a
+/// recoverable Ethereum signature for `address`.
+///
+/// The `s` value is normalized to the lower half of the curve order (EIP-2)
+/// and the recovery ID is determined by checking which of the two candidate
+/// public keys recovers to the expected address. Returns `None` if the
+/// signature was not produced by `address`.
+pub(crate) fn recoverable_signature(
+    message: &[u8; 32],
+    compact: &[u8; 64],
+    address: Address,
+) -> Option<Signature> {
+    let r = H256::from_slice(&compact[..32]);
+
+    // If `s` happens to be "on the dark side of the curve", we need to invert it (EIP-2)
+    let mut s_tentative: U256 = U256::from_big_endian(&compact[32..]);
+    let secp256k1_n = U256::from_big_endian(&CURVE_ORDER);
+    if s_tentative > secp256k1_n / 2 {
+        s_tentative = secp256k1_n - s_tentative;
+    }
+    let mut s = H256::default();
+    s_tentative.to_big_endian(&mut s.0);
+
+    // Recover correct v by trying which of the two options recovers to our public key
+    let signature = [r.as_bytes(), s.as_bytes()].concat();
+    let v = (0..=1).find(|&recovery_id| {
+        signing::recover(message, &signature, recovery_id).ok() == Some(address)
+    })?;
+
+    Some(Signature { v: v as _, r, s })
+}
+
+/// Reconstructs the signing payload of a transaction signed with a
+/// `DigestKey`. For typed transactions, this is the EIP-2718 transaction type
+/// followed by the RLP encoded transaction fields, and for legacy transactions
+/// the EIP-155 RLP encoding including the chain ID.
+pub(crate) fn signing_payload(transaction: &SignedTransaction) -> Result<Bytes, rlp::DecoderError> {
+    let (id, raw, len) = split_transaction(transaction)?;
+
+    // Legacy transactions with replay protection sign the chain ID followed
+    // by two empty values in place of the signature (EIP-155). Since the
+    // dummy signature has a 0 parity, the chain ID can be recovered from `v`.
+    let replay_protection = match id {
+        None if transaction.v >= 35 => Some((transaction.v - 35) / 2),
+        _ => None,
+    };
+    let mut encoder = RlpStream::new_list(len + if replay_protection.is_some() { 3 } else { 0 });
+    for item in Rlp::new(raw).iter().take(len) {
+        encoder.append_raw(item.as_raw(), 1);
+    }
+    if let Some(chain_id) = replay_protection {
+        encoder.append(&chain_id);
+        encoder.append(&0_u8);
+        encoder.append(&0_u8);
+    }
+
+    Ok(Bytes(match id {
+        Some(id) => [&[id], encoder.as_raw()].concat(),
+        None => encoder.out().to_vec(),
+    }))
+}
+
+/// Replaces the dummy signature of a transaction signed with a `DigestKey`
+/// with the actual signature of its signing message.
+pub(crate) fn replace_signature(
+    transaction: SignedTransaction,
+    signature: Signature,
+) -> Result<SignedTransaction, rlp::DecoderError> {
+    // transaction.v has the EIP155 value w/ 0 parity, signature.v has the parity bit, together they from the correct v
+    let v = transaction.v + signature.v;
+
+    // Re-encode the transaction, replacing the last 3 list values.
+    let (id, raw, len) = split_transaction(&transaction)?;
+    let mut encoder = RlpStream::new_list(len + 3);
+    for item in Rlp::new(raw).iter().take(len) {
+        encoder.append_raw(item.as_raw(), 1);
+    }
+    encoder.append(&v);
+    // RLP encoding doesn't allow leading zeros for s & r, yet default H256 RLP encoding preserves leading 0s
+    // By converting and encoding U256, we get rid of the leading zeros.
+    encoder.append(&U256::from_big_endian(signature.r.as_bytes()));
+    encoder.append(&U256::from_big_endian(signature.s.as_bytes()));
+
+    let raw_transaction = Bytes(match id {
+        Some(id) => [&[id], encoder.as_raw()].concat(),
+        None => encoder.out().to_vec(),
+    });
+    let transaction_hash = H256(keccak256(&raw_transaction.0));
+
+    Ok(SignedTransaction {
+        message_hash: transaction.message_hash,
+        v,
+        r: signature.r,
+        s: signature.s,
+        raw_transaction,
+        transaction_hash,
+    })
+}
+
+/// Splits a raw signed transaction into its EIP-2718 transaction type, its
+/// RLP encoded form and the number of transaction fields before the
+/// signature.
+fn split_transaction(
+    transaction: &SignedTransaction,
+) -> Result<(Option<u8>, &[u8], usize), rlp::DecoderError> {
+    let (id, raw) = match transaction.raw_transaction.0.first().copied() {
+        Some(x) if x < 0x80 => (Some(x), &transaction.raw_transaction.0[1..]),
+        _ => (None, &transaction.raw_transaction.0[..]),
+    };
+
+    // Fortunately for us, raw transactions always RLP append the signature,
+    // meaning the last 3 list values are `v`, `r`, and `s` respectively.
+    let len = match Rlp::new(raw).prototype()? {
+        rlp::Prototype::List(len) => len
+            .checked_sub(3)
+            .ok_or(rlp::DecoderError::Custom("transaction fields too short"))?,
+        _ => return Err(rlp::DecoderError::RlpExpectedToBeList),
+    };
+
+    Ok((id, raw, len))
+}
@@ -0,0 +1,284 @@
+//! Threshold (t-of-n) account implementation.
+//!
+//! A threshold account is controlled by a set of `n` signers, any `t` of which
+//! can jointly produce a signature for the account. This is the model used by
+//! MPC and TSS signing backends, where each party contributes a partial
+//! signature (a share) for a message digest and the shares get combined into
+//! a single standard ECDSA signature that is indistinguishable from one
+//! produced by a regular private key.
+//!
+//! The share format and combination logic are specific to the signing
+//! backend, so they are provided through the `ShareSigner` and `Aggregator`
+//! traits. The threshold account itself implements
+//! [`Signer`](crate::transaction::Signer), so it signs transactions, messages
+//! and typed data like any other signer.
+
+use crate::transaction::signing;
+use futures::future::BoxFuture;
+use futures::stream::{FuturesUnordered, StreamExt};
+use std::fmt::Debug;
+use std::sync::Arc;
+use web3::{signing::Signature, types::Address};
+
+/// A single party of a threshold signer set.
+///
+/// Unlike a [`Signer`](crate::transaction::Signer), a party only produces a
+/// share of the signature that is meaningless on its own.
+pub trait ShareSigner: Debug + Send + Sync {
+    /// Produces this party's signature share for a 32-byte message digest.
+    fn sign_share(&self, message: [u8; 32]) -> BoxFuture<'_, Result<Vec<u8>, Error>>;
+}
+
+/// Combines signature shares into a standard ECDSA signature.
+pub trait Aggregator: Debug + Send + Sync {
+    /// Aggregates exactly `threshold` signature shares for a message digest
+    /// into a compact 64-byte `r || s` ECDSA signature.
+    fn aggregate(&self, message: [u8; 32], shares: &[SignatureShare]) -> Result<[u8; 64], Error>;
+}
+
+/// A partial signature produced by one of the signers of a threshold account.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SignatureShare {
+    /// The index of the signer that produced the share in the account's
+    /// signer set.
+    pub index: usize,
+    /// The backend specific share data.
+    pub data: Vec<u8>,
+}
+
+/// A threshold account abstraction.
+#[derive(Clone, Debug)]
+pub struct Account {
+    address: Address,
+    threshold: usize,
+    signers: Vec<Arc<dyn ShareSigner>>,
+    aggregator: Arc<dyn Aggregator>,
+}
+
+impl Account {
+    /// Creates a new threshold account for the specified address where any
+    /// `threshold` of the `signers` can sign for the account.
+    pub fn new(
+        address: Address,
+        threshold: usize,
+        signers: Vec<Arc<dyn ShareSigner>>,
+        aggregator: Arc<dyn Aggregator>,
+    ) -> Result<Self, Error> {
+        if threshold == 0 || threshold > signers.len() {
+            return Err(Error::InvalidThreshold {
+                threshold,
+                signers: signers.len(),
+            });
+        }
+
+        Ok(Self {
+            address,
+            threshold,
+            signers,
+            aggregator,
+        })
+    }
+
+    /// Returns the public address of the threshold account.
+    pub fn public_address(&self) -> Address {
+        self.address
+    }
+
+    /// Returns the number of signature shares required to sign.
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// Signs a message.
+    ///
+    /// Signature shares are requested from all signers concurrently, and the
+    /// first `threshold` shares to be produced are aggregated into the final
+    /// signature. Failing signers are tolerated as long as enough shares can
+    /// be collected.
+    pub async fn sign(&self, message: [u8; 32]) -> Result<Signature, Error> {
+        let mut pending = self
+            .signers
+            .iter()
+            .enumerate()
+            .map(|(index, signer)| async move { (index, signer.sign_share(message).await) })
+            .collect::<FuturesUnordered<_>>();
+
+        let mut shares = Vec::with_capacity(self.threshold);
+        while let Some((index, result)) = pending.next().await {
+            if let Ok(data) = result {
+                shares.push(SignatureShare { index, data });
+                if shares.len() == self.threshold {
+                    break;
+                }
+            }
+        }
+        if shares.len() < self.threshold {
+            return Err(Error::InsufficientShares {
+                threshold: self.threshold,
+                received: shares.len(),
+            });
+        }
+
+        let compact = self.aggregator.aggregate(message, &shares)?;
+        signing::recoverable_signature(&message, &compact, self.address)
+            .ok_or(Error::InvalidSignature)
+    }
+}
+
+/// Error type for when threshold signing fails.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// The threshold is not satisfiable by the signer set.
+    #[error("invalid threshold {threshold} for {signers} signers")]
+    InvalidThreshold {
+        /// The requested threshold.
+        threshold: usize,
+        /// The number of signers in the set.
+        signers: usize,
+    },
+    /// Not enough signers produced a signature share.
+    #[error("collected {received} of {threshold} required signature shares")]
+    InsufficientShares {
+        /// The number of required signature shares.
+        threshold: usize,
+        /// The number of signature shares that were collected.
+        received: usize,
+    },
+    /// Error reported by a signer or aggregator backend.
+    #[error("threshold signing backend error: {0}")]
+    Backend(Box<dyn std::error::Error + Send + Sync>),
+    /// The aggregated signature was not produced by the account's key.
+    #[error("invalid signature")]
+    InvalidSignature,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secret::PrivateKey;
+    use crate::test::prelude::*;
+    use crate::transaction::{Account as TransactionAccount, TransactionBuilder};
+    use futures::FutureExt as _;
+    use secp256k1::{Message, Secp256k1};
+
+    /// A trivial "scheme" where every share is the full compact signature of
+    /// a shared key. Good enough to exercise share collection.
+    #[derive(Debug)]
+    struct KeySigner(Option<PrivateKey>);
+
+    impl ShareSigner for KeySigner {
+        fn sign_share(&self, message: [u8; 32]) -> BoxFuture<'_, Result<Vec<u8>, Error>> {
+            let result = match &self.0 {
+                Some(key) => {
+                    let message = Message::from_slice(&message).unwrap();
+                    let signature = Secp256k1::signing_only().sign_ecdsa(&message, key);
+                    Ok(signature.serialize_compact().to_vec())
+                }
+                None => Err(Error::Backend("offline".into())),
+            };
+            async move { result }.boxed()
+        }
+    }
+
+    #[derive(Debug)]
+    struct FirstShare;
+
+    impl Aggregator for FirstShare {
+        fn aggregate(&self, _: [u8; 32], shares: &[SignatureShare]) -> Result<[u8; 64], Error> {
+            assert!(shares.windows(2).all(|pair| pair[0].data == pair[1].data));
+            let mut compact = [0; 64];
+            compact.copy_from_slice(&shares[0].data);
+            Ok(compact)
+        }
+    }
+
+    fn account(key: &PrivateKey, signers: &[bool], threshold: usize) -> Account {
+        Account::new(
+            key.public_address(),
+            threshold,
+            signers
+                .iter()
+                .map(|&online| {
+                    Arc::new(KeySigner(online.then(|| key.clone()))) as Arc<dyn ShareSigner>
+                })
+                .collect(),
+            Arc::new(FirstShare),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn invalid_threshold() {
+        let key = key!("0x0102030405060708091011121314151617181920212223242526272829303132");
+        for threshold in [0, 3] {
+            let result = Account::new(
+                key.public_address(),
+                threshold,
+                vec![],
+                Arc::new(FirstShare),
+            );
+            assert!(matches!(result, Err(Error::InvalidThreshold { .. })));
+        }
+    }
+
+    #[test]
+    fn tolerates_failing_signers() {
+        let key = key!("0x0102030405060708091011121314151617181920212223242526272829303132");
+        let account = account(&key, &[false, true, true], 2);
+
+        let signature = account.sign([0x42; 32]).immediate().unwrap();
+        let recovered = web3::signing::recover(
+            &[0x42; 32],
+            &[signature.r.as_bytes(), signature.s.as_bytes()].concat(),
+            signature.v as _,
+        )
+        .unwrap();
+        assert_eq!(recovered, key.public_address());
+    }
+
+    #[test]
+    fn insufficient_shares() {
+        let key = key!("0x0102030405060708091011121314151617181920212223242526272829303132");
+        let account = account(&key, &[false, false, true], 2);
+
+        let result = account.sign([0x42; 32]).immediate();
+        assert!(matches!(
+            result,
+            Err(Error::InsufficientShares {
+                threshold: 2,
+                received: 1
+            })
+        ));
+    }
+
+    #[test]
+    fn signs_same_transaction_as_private_key() {
+        let transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let key = key!("0x0102030405060708091011121314151617181920212223242526272829303132");
+        let builder = TransactionBuilder::new(web3)
+            .to(Address::repeat_byte(0x42))
+            .gas(0x1337.into())
+            .gas_price((1337.0, 42.0).into())
+            .nonce(0x42.into());
+
+        let offline = builder
+            .clone()
+            .from(TransactionAccount::Offline(key.clone(), Some(77777)))
+            .build()
+            .immediate()
+            .unwrap();
+        let threshold = builder
+            .from(TransactionAccount::Threshold(
+                account(&key, &[true, true, true], 2),
+                Some(77777),
+            ))
+            .build()
+            .immediate()
+            .unwrap();
+
+        assert_eq!(threshold, offline);
+        transport.assert_no_more_requests();
+    }
+}
@@ -0,0 +1,516 @@
+//! Simulation of transactions with `debug_traceCall` or `trace_call`.
+//!
+//! Simulating a transaction executes it against the latest block without
+//! sending it, and returns a trace of all the calls it made. This allows
+//! validating complex transactions off-chain before paying gas for them.
+
+use crate::errors::ExecutionError;
+use crate::transaction::TransactionBuilder;
+use serde::Deserialize;
+use serde_json::json;
+use web3::api::Web3;
+use web3::error::Error as Web3Error;
+use web3::helpers::{self, CallFuture};
+use web3::types::{Address, BlockNumber, Bytes, CallRequest, U256};
+use web3::Transport;
+
+/// The tracer to use for simulating transactions.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Tracer {
+    /// Use Geth's built-in `callTracer` with `debug_traceCall`. This is
+    /// supported by Geth, Erigon, Anvil and most hosted node providers.
+    #[default]
+    CallTracer,
+    /// Use `trace_call` with the `trace` trace type. This is supported by
+    /// OpenEthereum-style nodes such as Nethermind, Erigon and Reth.
+    ///
+    /// Note that the gas used by the root call of these traces does not
+    /// include the intrinsic gas of the transaction.
+    ParityTrace,
+}
+
+/// A trace of a call made while executing a transaction, including all the
+/// nested calls it made.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CallTrace {
+    /// The kind of call, for example `CALL`, `DELEGATECALL`, `STATICCALL` or
+    /// `CREATE`.
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// The caller.
+    pub from: Address,
+    /// The callee, or the created contract for successful `CREATE` calls.
+    #[serde(default)]
+    pub to: Option<Address>,
+    /// The ETH value sent with the call.
+    #[serde(default)]
+    pub value: Option<U256>,
+    /// The gas provided for the call.
+    #[serde(default)]
+    pub gas: U256,
+    /// The gas used by the call.
+    pub gas_used: U256,
+    /// The calldata of the call.
+    #[serde(default)]
+    pub input: Bytes,
+    /// The return data of the call, or the revert data if the call reverted.
+    #[serde(default)]
+    pub output: Option<Bytes>,
+    /// The error if the call failed, for example `execution reverted`.
+    #[serde(default)]
+    pub error: Option<String>,
+    /// The calls made by this call.
+    #[serde(default)]
+    pub calls: Vec<CallTrace>,
+}
+
+impl CallTrace {
+    /// Returns true if the call failed.
+    pub fn is_reverted(&self) -> bool {
+        self.error.is_some()
+    }
+
+    /// Returns the return data of the call, or the revert data if the call
+    /// reverted.
+    pub fn return_data(&self) -> &[u8] {
+        self.output
+            .as_ref()
+            .map(|output| &output.0[..])
+            .unwrap_or_default()
+    }
+
+    /// Returns the error the call reverted with, decoded from its revert data,
+    /// or `None` if the call did not revert.
+    pub fn revert_error(&self) -> Option<ExecutionError> {
+        self.is_reverted()
+            .then(|| ExecutionError::from_revert_data(self.return_data().to_vec()))
+    }
+
+    /// Returns this call and all nested calls that failed, in the order in
+    /// which they were made.
+    pub fn reverted_calls(&self) -> Vec<&CallTrace> {
+        let mut reverted = Vec::new();
+        self.visit(&mut |trace| {
+            if trace.is_reverted() {
+                reverted.push(trace);
+            }
+        });
+        reverted
+    }
+
+    fn visit<'a>(&'a self, f: &mut impl FnMut(&'a CallTrace)) {
+        f(self);
+        for call in &self.calls {
+            call.visit(f);
+        }
+    }
+}
+
+impl<T: Transport> TransactionBuilder<T> {
+    /// Simulates the transaction against the latest block with Geth's
+    /// `callTracer` and returns a trace of the calls it made. The simulation
+    /// succeeds even if the transaction reverts, which can be checked with
+    /// [`CallTrace::is_reverted`].
+    pub async fn simulate(self) -> Result<CallTrace, ExecutionError> {
+        self.simulate_with_tracer(Tracer::default()).await
+    }
+
+    /// Simulates the transaction against the latest block with the specified
+    /// tracer and returns a trace of the calls it made.
+    pub async fn simulate_with_tracer(self, tracer: Tracer) -> Result<CallTrace, ExecutionError> {
+        self.check_calldata_size()?;
+        let request = CallRequest {
+            gas: self.gas,
+            ..self.call_request()
+        };
+        trace_call(self.web3.transport(), request, tracer).await
+    }
+}
+
+/// Simulates a bundle of transactions in sequence against the pending block
+/// with `trace_callMany`, so that each transaction sees the state changes of
+/// the transactions before it, and returns a trace for each transaction.
+///
+/// This allows validating dependent transactions, such as an approval
+/// followed by a transfer, before sending them. Method builders can be
+/// simulated by converting them into transaction builders. Like with
+/// [`Tracer::ParityTrace`], this is supported by OpenEthereum-style nodes
+/// such as Nethermind, Erigon and Reth, and the gas used by the traces does
+/// not include the intrinsic gas of the transactions.
+pub async fn simulate_bundle<T, I>(
+    web3: &Web3<T>,
+    bundle: I,
+) -> Result<Vec<CallTrace>, ExecutionError>
+where
+    T: Transport,
+    I: IntoIterator,
+    I::Item: Into<TransactionBuilder<T>>,
+{
+    let calls = bundle
+        .into_iter()
+        .map(|tx| {
+            let tx = tx.into();
+            tx.check_calldata_size()?;
+            let request = CallRequest {
+                gas: tx.gas,
+                ..tx.call_request()
+            };
+            Ok(json!([request, ["trace"]]))
+        })
+        .collect::<Result<Vec<_>, ExecutionError>>()?;
+    let block = helpers::serialize(&BlockNumber::Pending);
+
+    let results: Vec<TraceResults> = CallFuture::new(
+        web3.transport()
+            .execute("trace_callMany", vec![json!(calls), block]),
+    )
+    .await?;
+    results
+        .into_iter()
+        .map(TraceResults::into_call_trace)
+        .collect()
+}
+
+/// Traces a call against the latest block with the specified tracer.
+async fn trace_call<T: Transport>(
+    transport: &T,
+    request: CallRequest,
+    tracer: Tracer,
+) -> Result<CallTrace, ExecutionError> {
+    let request = helpers::serialize(&request);
+    let block = helpers::serialize(&BlockNumber::Latest);
+    match tracer {
+        Tracer::CallTracer => {
+            let trace = CallFuture::new(transport.execute(
+                "debug_traceCall",
+                vec![request, block, json!({ "tracer": "callTracer" })],
+            ))
+            .await?;
+            Ok(trace)
+        }
+        Tracer::ParityTrace => {
+            let results: TraceResults = CallFuture::new(
+                transport.execute("trace_call", vec![request, json!(["trace"]), block]),
+            )
+            .await?;
+            results.into_call_trace()
+        }
+    }
+}
+
+/// The results of a `trace_call` with the `trace` trace type.
+#[derive(Deserialize)]
+struct TraceResults {
+    #[serde(default)]
+    output: Bytes,
+    trace: Vec<Trace>,
+}
+
+/// A flattened trace as returned by OpenEthereum-style trace APIs. Traces are
+/// returned in the order in which the calls were made.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Trace {
+    action: TraceAction,
+    #[serde(default)]
+    result: Option<TraceResult>,
+    #[serde(default)]
+    error: Option<String>,
+    subtraces: usize,
+    #[serde(rename = "type")]
+    kind: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TraceAction {
+    #[serde(default)]
+    call_type: Option<String>,
+    #[serde(default)]
+    from: Option<Address>,
+    #[serde(default)]
+    to: Option<Address>,
+    #[serde(default)]
+    value: Option<U256>,
+    #[serde(default)]
+    gas: U256,
+    #[serde(default)]
+    input: Option<Bytes>,
+    #[serde(default)]
+    init: Option<Bytes>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TraceResult {
+    #[serde(default)]
+    gas_used: U256,
+    #[serde(default)]
+    output: Option<Bytes>,
+    #[serde(default)]
+    address: Option<Address>,
+}
+
+impl TraceResults {
+    fn into_call_trace(self) -> Result<CallTrace, ExecutionError> {
+        let mut traces = self.trace.into_iter();
+        let mut trace = nest_traces(&mut traces)?;
+        trace.output = Some(self.output);
+        Ok(trace)
+    }
+}
+
+/// Converts flattened traces into a call trace, consuming the traces of the
+/// first call and all its nested calls.
+fn nest_traces(traces: &mut impl Iterator<Item = Trace>) -> Result<CallTrace, ExecutionError> {
+    let trace = traces
+        .next()
+        .ok_or_else(|| Web3Error::InvalidResponse("trace is missing nested calls".to_owned()))?;
+
+    let kind = trace.action.call_type.unwrap_or(trace.kind).to_uppercase();
+    let (gas_used, output, created) = match trace.result {
+        Some(result) => (result.gas_used, result.output, result.address),
+        None => (U256::zero(), None, None),
+    };
+    let calls = (0..trace.subtraces)
+        .map(|_| nest_traces(traces))
+        .collect::<Result<_, _>>()?;
+
+    Ok(CallTrace {
+        kind,
+        from: trace.action.from.unwrap_or_default(),
+        to: trace.action.to.or(created),
+        value: trace.action.value,
+        gas: trace.action.gas,
+        gas_used,
+        input: trace.action.input.or(trace.action.init).unwrap_or_default(),
+        output,
+        error: trace.error,
+        calls,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::prelude::*;
+    use crate::transaction::Account;
+
+    fn builder(web3: Web3<TestTransport>) -> TransactionBuilder<TestTransport> {
+        TransactionBuilder::new(web3)
+            .from(Account::Local(Address::repeat_byte(1), None))
+            .to(Address::repeat_byte(2))
+            .gas(100_000.into())
+            .data(Bytes(vec![0x12, 0x34]))
+    }
+
+    #[test]
+    fn simulate_with_call_tracer() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        transport.add_response(json!({
+            "type": "CALL",
+            "from": Address::repeat_byte(1),
+            "to": Address::repeat_byte(2),
+            "value": "0x0",
+            "gas": "0x186a0",
+            "gasUsed": "0x7530",
+            "input": "0x1234",
+            "output": "0x",
+            "error": "execution reverted",
+            "calls": [{
+                "type": "STATICCALL",
+                "from": Address::repeat_byte(2),
+                "to": Address::repeat_byte(3),
+                "gas": "0x1000",
+                "gasUsed": "0x100",
+                "input": "0x",
+                "output": "0x2a",
+            }],
+        }));
+        let trace = builder(web3).simulate().immediate().unwrap();
+
+        transport.assert_request(
+            "debug_traceCall",
+            &[
+                json!({
+                    "from": Address::repeat_byte(1),
+                    "to": Address::repeat_byte(2),
+                    "gas": "0x186a0",
+                    "data": "0x1234",
+                }),
+                json!("latest"),
+                json!({ "tracer": "callTracer" }),
+            ],
+        );
+        transport.assert_no_more_requests();
+
+        assert_eq!(trace.gas_used, 30_000.into());
+        assert!(trace.is_reverted());
+        assert!(matches!(
+            trace.revert_error(),
+            Some(ExecutionError::Revert(None))
+        ));
+        assert_eq!(trace.reverted_calls(), [&trace]);
+        assert_eq!(trace.calls[0].kind, "STATICCALL");
+        assert_eq!(trace.calls[0].return_data(), [0x2a]);
+    }
+
+    #[test]
+    fn simulate_with_parity_trace() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let call = |from: u8, to: u8, subtraces: usize| {
+            json!({
+                "action": {
+                    "callType": "call",
+                    "from": Address::repeat_byte(from),
+                    "to": Address::repeat_byte(to),
+                    "gas": "0x1000",
+                    "input": "0x",
+                    "value": "0x0",
+                },
+                "result": { "gasUsed": "0x100", "output": "0x" },
+                "subtraces": subtraces,
+                "traceAddress": [],
+                "type": "call",
+            })
+        };
+        transport.add_response(json!({
+            "output": "0x2a",
+            "trace": [
+                call(1, 2, 2),
+                call(2, 3, 1),
+                {
+                    "action": {
+                        "callType": "delegatecall",
+                        "from": Address::repeat_byte(3),
+                        "to": Address::repeat_byte(4),
+                        "gas": "0x100",
+                        "input": "0x",
+                        "value": "0x0",
+                    },
+                    "result": null,
+                    "error": "Reverted",
+                    "subtraces": 0,
+                    "traceAddress": [0, 0],
+                    "type": "call",
+                },
+                call(2, 5, 0),
+            ],
+            "stateDiff": null,
+            "vmTrace": null,
+        }));
+        let trace = builder(web3)
+            .simulate_with_tracer(Tracer::ParityTrace)
+            .immediate()
+            .unwrap();
+
+        transport.assert_request(
+            "trace_call",
+            &[
+                json!({
+                    "from": Address::repeat_byte(1),
+                    "to": Address::repeat_byte(2),
+                    "gas": "0x186a0",
+                    "data": "0x1234",
+                }),
+                json!(["trace"]),
+                json!("latest"),
+            ],
+        );
+        transport.assert_no_more_requests();
+
+        assert!(!trace.is_reverted());
+        assert_eq!(trace.return_data(), [0x2a]);
+        assert_eq!(trace.calls.len(), 2);
+        assert_eq!(trace.calls[0].to, Some(Address::repeat_byte(3)));
+        assert_eq!(trace.calls[1].to, Some(Address::repeat_byte(5)));
+
+        let reverted = trace.reverted_calls();
+        assert_eq!(reverted.len(), 1);
+        assert_eq!(reverted[0].kind, "DELEGATECALL");
+        assert_eq!(reverted[0].to, Some(Address::repeat_byte(4)));
+    }
+
+    #[test]
+    fn simulate_bundle_of_transactions() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        let result = |gas_used: &str, output: &str, error: Option<&str>| {
+            json!({
+                "output": output,
+                "trace": [{
+                    "action": {
+                        "callType": "call",
+                        "from": Address::repeat_byte(1),
+                        "to": Address::repeat_byte(2),
+                        "gas": "0x186a0",
+                        "input": "0x1234",
+                        "value": "0x0",
+                    },
+                    "result": { "gasUsed": gas_used, "output": output },
+                    "error": error,
+                    "subtraces": 0,
+                    "traceAddress": [],
+                    "type": "call",
+                }],
+                "stateDiff": null,
+                "vmTrace": null,
+            })
+        };
+        transport.add_response(json!([
+            result("0x5208", "0x01", None),
+            result("0x100", "0x", Some("Reverted")),
+        ]));
+        let traces = simulate_bundle(
+            &web3,
+            [builder(web3.clone()), builder(web3.clone()).value(1.into())],
+        )
+        .immediate()
+        .unwrap();
+
+        let request = json!({
+            "from": Address::repeat_byte(1),
+            "to": Address::repeat_byte(2),
+            "gas": "0x186a0",
+            "data": "0x1234",
+        });
+        let mut with_value = request.clone();
+        with_value["value"] = json!("0x1");
+        transport.assert_request(
+            "trace_callMany",
+            &[
+                json!([[request, ["trace"]], [with_value, ["trace"]]]),
+                json!("pending"),
+            ],
+        );
+        transport.assert_no_more_requests();
+
+        assert_eq!(traces.len(), 2);
+        assert_eq!(traces[0].gas_used, 21_000.into());
+        assert_eq!(traces[0].return_data(), [0x01]);
+        assert!(!traces[0].is_reverted());
+        assert!(traces[1].is_reverted());
+    }
+
+    #[test]
+    fn simulate_parity_trace_with_missing_calls() {
+        let mut transport = TestTransport::new();
+        let web3 = Web3::new(transport.clone());
+
+        transport.add_response(json!({ "output": "0x", "trace": [] }));
+        let err = builder(web3)
+            .simulate_with_tracer(Tracer::ParityTrace)
+            .immediate()
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            ExecutionError::Web3(Web3Error::InvalidResponse(_))
+        ));
+    }
+}
@@ -6,6 +6,23 @@
 //! be generic on the underlying transport (at the small cost of some dynamic
 //! dispatch and extra allocations).
 
+mod fallback;
+#[cfg(feature = "http")]
+mod http;
+
+#[cfg(feature = "jsonrpsee")]
+mod jsonrpsee;
+mod metrics;
+mod retry;
+
+pub use self::fallback::FallbackTransport;
+#[cfg(feature = "http")]
+pub use self::http::HttpBuilder;
+#[cfg(feature = "jsonrpsee")]
+pub use self::jsonrpsee::JsonRpseeTransport;
+pub use self::metrics::{BatchMetrics, RequestMetrics};
+pub use self::retry::RetryTransport;
+
 use futures::future::BoxFuture;
 use futures::FutureExt as _;
 use jsonrpc_core::Call;
@@ -73,6 +90,30 @@ where
     }
 }
 
+/// A `web3` transport that can be used with the `ethcontract` runtime and
+/// generated contract bindings.
+///
+/// This trait is implemented for all transports with `Send` futures and
+/// captures the bounds required for wrapping them in a [`DynTransport`], so
+/// that code which is generic over the transport does not need to spell out
+/// the transport's future types. This keeps such code independent of the
+/// exact trait bounds of the underlying `web3` version.
+pub trait EthTransport: Transport + BatchTransport + Send + Sync + 'static {
+    /// Wraps this transport in a `DynTransport`.
+    fn into_dyn(self) -> DynTransport;
+}
+
+impl<F, B, T> EthTransport for T
+where
+    F: Future<Output = Result<Value, Web3Error>> + Send + 'static,
+    B: Future<Output = Result<Vec<Result<Value, Web3Error>>, Web3Error>> + Send + 'static,
+    T: Transport<Out = F> + BatchTransport<Batch = B> + Send + Sync + 'static,
+{
+    fn into_dyn(self) -> DynTransport {
+        DynTransport::new(self)
+    }
+}
+
 /// Dynamic `Transport` implementation to allow for a generic-free contract API.
 /// This type wraps any `Transport` type and implements `Transport` itself.
 #[derive(Debug)]
@@ -174,6 +215,20 @@ mod tests {
         transport.assert_no_more_requests();
     }
 
+    #[test]
+    fn eth_transport_into_dyn() {
+        fn wrap<T: EthTransport>(transport: T) -> DynTransport {
+            transport.into_dyn()
+        }
+
+        let transport = TestTransport::new();
+        let dyn_transport = wrap(transport.clone());
+        assert!(dyn_transport.downcast::<TestTransport>().is_some());
+
+        let dyn_dyn_transport = wrap(dyn_transport.clone());
+        assert!(Arc::ptr_eq(&dyn_transport.inner, &dyn_dyn_transport.inner));
+    }
+
     #[test]
     #[allow(clippy::redundant_clone)]
     fn dyn_transport_does_not_double_wrap() {
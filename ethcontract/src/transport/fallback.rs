@@ -0,0 +1,459 @@
+//! Transport that fails over between multiple endpoints.
+
+use super::retry::{is_idempotent, is_rejected, is_transient};
+use futures::future::{self, BoxFuture, Either};
+use futures::FutureExt as _;
+use futures_timer::Delay;
+use jsonrpc_core::Call;
+use serde_json::Value;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use web3::error::{Error as Web3Error, TransportError};
+use web3::{BatchTransport, RequestId, Transport};
+
+/// A transport that sends requests to the first of an ordered list of
+/// endpoints, such as several RPC URLs, and fails over to the next one when a
+/// request fails with a transient error or times out.
+///
+/// Endpoints that fail are considered unhealthy for a recovery interval,
+/// during which requests are sent to the remaining healthy endpoints first.
+/// Once the interval elapses, the endpoint is used again according to its
+/// priority. Endpoints can also be checked actively with
+/// [`FallbackTransport::check_health`].
+///
+/// Errors returned by the node for valid requests, such as reverts, are
+/// returned without failing over. For batches, only failures of the batch as
+/// a whole cause a fail over.
+///
+/// Requests that send transactions, and batches containing them, only fail
+/// over if the endpoint rejected them without processing them, for example
+/// because of rate limiting. Other errors, including timeouts, are returned
+/// as is, since the endpoint may have already broadcast the transaction, and
+/// sending it to the next endpoint would broadcast it again.
+#[derive(Debug)]
+pub struct FallbackTransport<T> {
+    endpoints: Arc<Vec<Endpoint<T>>>,
+    timeout: Option<Duration>,
+    recovery_interval: Duration,
+}
+
+/// An endpoint of a `FallbackTransport` with its health status.
+#[derive(Debug)]
+struct Endpoint<T> {
+    transport: T,
+    unhealthy_until: Mutex<Option<Instant>>,
+}
+
+impl<T> FallbackTransport<T> {
+    /// Creates a transport failing over between the specified transports, in
+    /// order of priority. Unhealthy endpoints recover after 30 seconds.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no transports are specified.
+    pub fn new<I>(transports: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let endpoints = transports
+            .into_iter()
+            .map(|transport| Endpoint {
+                transport,
+                unhealthy_until: Mutex::new(None),
+            })
+            .collect::<Vec<_>>();
+        assert!(
+            !endpoints.is_empty(),
+            "fallback transport requires at least one transport"
+        );
+
+        FallbackTransport {
+            endpoints: Arc::new(endpoints),
+            timeout: None,
+            recovery_interval: Duration::from_secs(30),
+        }
+    }
+
+    /// Sets a timeout for requests to a single endpoint, after which the
+    /// request is sent to the next endpoint instead.
+    pub fn timeout(mut self, value: Duration) -> Self {
+        self.timeout = Some(value);
+        self
+    }
+
+    /// Sets the duration for which an endpoint is considered unhealthy after
+    /// a failure.
+    pub fn recovery_interval(mut self, value: Duration) -> Self {
+        self.recovery_interval = value;
+        self
+    }
+
+    /// Returns the transports of all endpoints, in order of priority.
+    pub fn transports(&self) -> impl Iterator<Item = &T> + '_ {
+        self.endpoints.iter().map(|endpoint| &endpoint.transport)
+    }
+
+    /// Returns whether each endpoint is currently considered healthy, in
+    /// order of priority.
+    pub fn health(&self) -> Vec<bool> {
+        let now = Instant::now();
+        self.endpoints
+            .iter()
+            .map(|endpoint| endpoint.is_healthy(now))
+            .collect()
+    }
+
+    /// Returns the endpoints in the order in which they should be tried, that
+    /// is healthy endpoints by priority followed by unhealthy ones.
+    fn ordered_endpoints(&self) -> Vec<&Endpoint<T>> {
+        let now = Instant::now();
+        let (healthy, unhealthy): (Vec<_>, Vec<_>) = self
+            .endpoints
+            .iter()
+            .partition(|endpoint| endpoint.is_healthy(now));
+        healthy.into_iter().chain(unhealthy).collect()
+    }
+
+    /// Runs a request with the specified calls against the endpoints until
+    /// one of them succeeds or fails with a non-transient error.
+    async fn run<'a, F, Fut, R>(
+        &self,
+        calls: impl IntoIterator<Item = &'a Call>,
+        request: F,
+    ) -> Result<R, Web3Error>
+    where
+        F: Fn(&T) -> Fut,
+        Fut: Future<Output = Result<R, Web3Error>>,
+    {
+        let idempotent = is_idempotent(calls);
+        let mut last_err = None;
+        for endpoint in self.ordered_endpoints() {
+            match with_timeout(request(&endpoint.transport), self.timeout).await {
+                Err(err) if is_transient(&err) => {
+                    endpoint.mark_unhealthy(self.recovery_interval);
+                    if !idempotent && !is_rejected(&err) {
+                        return Err(err);
+                    }
+                    last_err = Some(err);
+                }
+                result => {
+                    endpoint.mark_healthy();
+                    return result;
+                }
+            }
+        }
+
+        Err(last_err.expect("fallback transport has at least one endpoint"))
+    }
+}
+
+impl<T: Transport> FallbackTransport<T> {
+    /// Checks the health of all endpoints by requesting the latest block
+    /// number from them, marking endpoints as healthy or unhealthy based on
+    /// the result. Returns whether each endpoint is healthy, in order of
+    /// priority.
+    pub async fn check_health(&self) -> Vec<bool> {
+        let checks = self.endpoints.iter().map(|endpoint| async move {
+            let request = endpoint.transport.execute("eth_blockNumber", vec![]);
+            match with_timeout(request, self.timeout).await {
+                Ok(_) => {
+                    endpoint.mark_healthy();
+                    true
+                }
+                Err(_) => {
+                    endpoint.mark_unhealthy(self.recovery_interval);
+                    false
+                }
+            }
+        });
+
+        future::join_all(checks).await
+    }
+}
+
+impl<T> Endpoint<T> {
+    fn is_healthy(&self, now: Instant) -> bool {
+        match *self.unhealthy_until.lock().unwrap() {
+            Some(until) => until <= now,
+            None => true,
+        }
+    }
+
+    fn mark_healthy(&self) {
+        *self.unhealthy_until.lock().unwrap() = None;
+    }
+
+    fn mark_unhealthy(&self, recovery_interval: Duration) {
+        *self.unhealthy_until.lock().unwrap() = Some(Instant::now() + recovery_interval);
+    }
+}
+
+impl<T> Clone for FallbackTransport<T> {
+    fn clone(&self) -> Self {
+        FallbackTransport {
+            endpoints: self.endpoints.clone(),
+            timeout: self.timeout,
+            recovery_interval: self.recovery_interval,
+        }
+    }
+}
+
+/// Awaits a request, failing with a transport error if it does not complete
+/// within the timeout.
+async fn with_timeout<F, R>(request: F, timeout: Option<Duration>) -> Result<R, Web3Error>
+where
+    F: Future<Output = Result<R, Web3Error>>,
+{
+    let timeout = match timeout {
+        Some(timeout) => timeout,
+        None => return request.await,
+    };
+
+    match future::select(Box::pin(request), Delay::new(timeout)).await {
+        Either::Left((result, _)) => result,
+        Either::Right(_) => Err(Web3Error::Transport(TransportError::Message(
+            "request timed out".to_owned(),
+        ))),
+    }
+}
+
+impl<T> Transport for FallbackTransport<T>
+where
+    T: Transport + Send + Sync + 'static,
+    T::Out: Send,
+{
+    type Out = BoxFuture<'static, Result<Value, Web3Error>>;
+
+    fn prepare(&self, method: &str, params: Vec<Value>) -> (RequestId, Call) {
+        self.endpoints[0].transport.prepare(method, params)
+    }
+
+    fn send(&self, id: RequestId, request: Call) -> Self::Out {
+        let this = self.clone();
+        async move {
+            this.run([&request], |transport| transport.send(id, request.clone()))
+                .await
+        }
+        .boxed()
+    }
+}
+
+impl<T> BatchTransport for FallbackTransport<T>
+where
+    T: BatchTransport + Send + Sync + 'static,
+    T::Out: Send,
+    T::Batch: Send,
+{
+    type Batch = BoxFuture<'static, Result<Vec<Result<Value, Web3Error>>, Web3Error>>;
+
+    fn send_batch<I>(&self, requests: I) -> Self::Batch
+    where
+        I: IntoIterator<Item = (RequestId, Call)>,
+    {
+        let this = self.clone();
+        let requests = requests.into_iter().collect::<Vec<_>>();
+        async move {
+            this.run(requests.iter().map(|(_, call)| call), |transport| {
+                transport.send_batch(requests.clone())
+            })
+            .await
+        }
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::prelude::*;
+    use crate::transport::DynTransport;
+
+    fn rate_limited() -> Web3Error {
+        Web3Error::Rpc(jsonrpc_core::Error {
+            code: (-32005).into(),
+            message: "request rate exceeded".to_owned(),
+            data: None,
+        })
+    }
+
+    #[test]
+    fn fails_over_to_next_endpoint() {
+        let mut primary = TestTransport::new();
+        let mut secondary = TestTransport::new();
+        let fallback = FallbackTransport::new(vec![primary.clone(), secondary.clone()]);
+
+        primary.add_error(Web3Error::Transport(TransportError::Code(503)));
+        secondary.add_response(json!("0x1"));
+        let result = fallback.execute("eth_blockNumber", vec![]).immediate();
+        assert_eq!(result.unwrap(), json!("0x1"));
+        assert_eq!(fallback.health(), vec![false, true]);
+
+        // The unhealthy primary is skipped until it recovers.
+        secondary.add_response(json!("0x2"));
+        let result = fallback.execute("eth_blockNumber", vec![]).immediate();
+        assert_eq!(result.unwrap(), json!("0x2"));
+    }
+
+    #[test]
+    fn recovers_unhealthy_endpoints() {
+        let mut primary = TestTransport::new();
+        let mut secondary = TestTransport::new();
+        let fallback = FallbackTransport::new(vec![primary.clone(), secondary.clone()])
+            .recovery_interval(Duration::ZERO);
+
+        primary.add_error(rate_limited());
+        secondary.add_response(json!("0x1"));
+        fallback
+            .execute("eth_blockNumber", vec![])
+            .immediate()
+            .unwrap();
+
+        primary.add_response(json!("0x2"));
+        let result = fallback.execute("eth_blockNumber", vec![]).immediate();
+        assert_eq!(result.unwrap(), json!("0x2"));
+        assert_eq!(fallback.health(), vec![true, true]);
+    }
+
+    #[test]
+    fn does_not_fail_over_on_node_errors() {
+        let mut primary = TestTransport::new();
+        let mut secondary = TestTransport::new();
+        let fallback = FallbackTransport::new(vec![primary.clone(), secondary.clone()]);
+
+        primary.add_error(Web3Error::Rpc(jsonrpc_core::Error {
+            code: 3.into(),
+            message: "execution reverted".to_owned(),
+            data: None,
+        }));
+        secondary.add_response(json!("0x"));
+        let result = fallback.execute("eth_call", vec![]).immediate();
+
+        assert!(matches!(result, Err(Web3Error::Rpc(_))));
+        assert_eq!(fallback.health(), vec![true, true]);
+    }
+
+    #[test]
+    fn returns_last_error_if_all_endpoints_fail() {
+        let mut primary = TestTransport::new();
+        let mut secondary = TestTransport::new();
+        let fallback = FallbackTransport::new(vec![primary.clone(), secondary.clone()]);
+
+        primary.add_error(Web3Error::Transport(TransportError::Code(503)));
+        secondary.add_error(rate_limited());
+        let result = fallback.execute("eth_blockNumber", vec![]).immediate();
+
+        assert!(matches!(result, Err(Web3Error::Rpc(_))));
+        assert_eq!(fallback.health(), vec![false, false]);
+    }
+
+    #[test]
+    fn fails_over_batches() {
+        let mut primary = TestTransport::new();
+        let mut secondary = TestTransport::new();
+        let fallback = FallbackTransport::new(vec![primary.clone(), secondary.clone()]);
+
+        primary.add_error(Web3Error::Transport(TransportError::Code(502)));
+        secondary.add_response(json!(["0x1", "0x2"]));
+        let requests = vec![
+            fallback.prepare("eth_blockNumber", vec![]),
+            fallback.prepare("eth_chainId", vec![]),
+        ];
+        let results = fallback.send_batch(requests).immediate().unwrap();
+
+        assert_eq!(results.len(), 2);
+    }
+
+    #[test]
+    fn does_not_fail_over_transactions() {
+        let mut primary = TestTransport::new();
+        let mut secondary = TestTransport::new();
+        let fallback = FallbackTransport::new(vec![primary.clone(), secondary.clone()]);
+
+        // the primary may have already broadcast the transaction
+        primary.add_error(Web3Error::Transport(TransportError::Message(
+            "request timed out".to_owned(),
+        )));
+        secondary.add_response(json!("0x42"));
+        let result = fallback
+            .execute("eth_sendTransaction", vec![json!({})])
+            .immediate();
+        assert!(matches!(result, Err(Web3Error::Transport(_))));
+        assert_eq!(fallback.health(), vec![false, true]);
+
+        // the transaction was not sent to the secondary
+        let result = fallback.execute("eth_blockNumber", vec![]).immediate();
+        assert_eq!(result.unwrap(), json!("0x42"));
+    }
+
+    #[test]
+    fn fails_over_rejected_transactions() {
+        let mut primary = TestTransport::new();
+        let mut secondary = TestTransport::new();
+        let fallback = FallbackTransport::new(vec![primary.clone(), secondary.clone()]);
+
+        primary.add_error(rate_limited());
+        secondary.add_response(json!("0x42"));
+        let result = fallback
+            .execute("eth_sendRawTransaction", vec![json!("0x")])
+            .immediate();
+        assert_eq!(result.unwrap(), json!("0x42"));
+    }
+
+    #[test]
+    fn does_not_fail_over_timed_out_transactions() {
+        let fallback = FallbackTransport::new(vec![TestTransport::new(), TestTransport::new()])
+            .timeout(Duration::from_millis(1));
+
+        let result = fallback
+            .run(
+                [&fallback.prepare("eth_sendRawTransaction", vec![]).1],
+                |_| future::pending::<Result<(), Web3Error>>(),
+            )
+            .wait();
+        assert!(matches!(result, Err(ref err) if is_transient(err)));
+        assert_eq!(fallback.health(), vec![false, true]);
+    }
+
+    #[test]
+    fn check_health_of_endpoints() {
+        let mut primary = TestTransport::new();
+        let mut secondary = TestTransport::new();
+        let fallback = FallbackTransport::new(vec![primary.clone(), secondary.clone()]);
+
+        primary.add_error(Web3Error::Unreachable);
+        secondary.add_response(json!("0x1"));
+        assert_eq!(fallback.check_health().immediate(), vec![false, true]);
+        assert_eq!(fallback.health(), vec![false, true]);
+
+        primary.add_response(json!("0x1"));
+        secondary.add_response(json!("0x1"));
+        assert_eq!(fallback.check_health().immediate(), vec![true, true]);
+        assert_eq!(fallback.health(), vec![true, true]);
+    }
+
+    #[test]
+    fn requests_time_out() {
+        let result = with_timeout(
+            future::pending::<Result<(), Web3Error>>(),
+            Some(Duration::from_millis(1)),
+        )
+        .wait();
+
+        assert!(matches!(result, Err(ref err) if is_transient(err)));
+    }
+
+    #[test]
+    fn can_be_used_as_dyn_transport() {
+        let mut primary = TestTransport::new();
+        let dyn_transport = DynTransport::new(FallbackTransport::new(vec![primary.clone()]));
+
+        primary.add_response(json!("0x2a"));
+        let result = dyn_transport
+            .execute("eth_blockNumber", vec![])
+            .immediate()
+            .unwrap();
+
+        assert_eq!(result, json!("0x2a"));
+    }
+}
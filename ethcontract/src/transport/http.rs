@@ -0,0 +1,129 @@
+//! Builder for HTTP transports with tuned connection pooling and compression.
+
+use std::time::Duration;
+use web3::error::{Error as Web3Error, TransportError};
+use web3::transports::Http;
+
+/// A builder for an HTTP transport that exposes the tuning options of the
+/// underlying HTTP client.
+///
+/// [`Http::new`] uses a client with fixed defaults, which is not always ideal
+/// for high-throughput applications such as indexers. This builder allows
+/// configuring connection pooling, timeouts, HTTP/2 and response compression.
+///
+/// # Examples
+///
+/// ```no_run
+/// use ethcontract::transport::HttpBuilder;
+/// use std::time::Duration;
+///
+/// let http = HttpBuilder::new("http://localhost:8545")
+///     .pool_max_idle_per_host(64)
+///     .pool_idle_timeout(Some(Duration::from_secs(30)))
+///     .timeout(Duration::from_secs(10))
+///     .build()
+///     .expect("valid HTTP transport");
+/// ```
+#[derive(Debug)]
+#[must_use = "HTTP builders do nothing unless you build them"]
+pub struct HttpBuilder {
+    url: String,
+    client: reqwest::ClientBuilder,
+}
+
+impl HttpBuilder {
+    /// Creates a new HTTP transport builder for the specified node URL.
+    pub fn new(url: impl Into<String>) -> Self {
+        HttpBuilder {
+            url: url.into(),
+            client: reqwest::Client::builder().user_agent("web3.rs"),
+        }
+    }
+
+    /// Sets the maximum number of idle connections kept alive per host.
+    pub fn pool_max_idle_per_host(mut self, value: usize) -> Self {
+        self.client = self.client.pool_max_idle_per_host(value);
+        self
+    }
+
+    /// Sets the timeout after which idle pooled connections are closed. Use
+    /// `None` to keep idle connections open indefinitely.
+    pub fn pool_idle_timeout(mut self, value: Option<Duration>) -> Self {
+        self.client = self.client.pool_idle_timeout(value);
+        self
+    }
+
+    /// Sets the timeout for entire requests, from connecting until the
+    /// response body has been received.
+    pub fn timeout(mut self, value: Duration) -> Self {
+        self.client = self.client.timeout(value);
+        self
+    }
+
+    /// Sets the timeout for establishing new connections.
+    pub fn connect_timeout(mut self, value: Duration) -> Self {
+        self.client = self.client.connect_timeout(value);
+        self
+    }
+
+    /// Sets the TCP keep-alive interval for connections.
+    pub fn tcp_keepalive(mut self, value: Option<Duration>) -> Self {
+        self.client = self.client.tcp_keepalive(value);
+        self
+    }
+
+    /// Only use HTTP/2 for requests, without negotiating the protocol version
+    /// first. This requires the node to support HTTP/2.
+    pub fn http2_prior_knowledge(mut self) -> Self {
+        self.client = self.client.http2_prior_knowledge();
+        self
+    }
+
+    /// Enables or disables gzip compression of responses.
+    #[cfg(feature = "http-gzip")]
+    pub fn gzip(mut self, enable: bool) -> Self {
+        self.client = self.client.gzip(enable);
+        self
+    }
+
+    /// Enables or disables deflate compression of responses.
+    #[cfg(feature = "http-deflate")]
+    pub fn deflate(mut self, enable: bool) -> Self {
+        self.client = self.client.deflate(enable);
+        self
+    }
+
+    /// Builds the HTTP transport.
+    pub fn build(self) -> Result<Http, Web3Error> {
+        let client = self.client.build().map_err(|err| {
+            Web3Error::Transport(TransportError::Message(format!(
+                "failed to build client: {}",
+                err
+            )))
+        })?;
+        let url = self.url.parse().map_err(|err| {
+            Web3Error::Transport(TransportError::Message(format!(
+                "invalid node URL '{}': {}",
+                self.url, err
+            )))
+        })?;
+
+        Ok(Http::with_client(client, url))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_http_transport() {
+        assert!(HttpBuilder::new("http://localhost:8545")
+            .pool_max_idle_per_host(8)
+            .pool_idle_timeout(None)
+            .connect_timeout(Duration::from_secs(1))
+            .build()
+            .is_ok());
+        assert!(HttpBuilder::new("not a url").build().is_err());
+    }
+}
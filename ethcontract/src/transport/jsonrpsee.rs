@@ -0,0 +1,294 @@
+//! Transport adapter for running the `ethcontract` runtime over a `jsonrpsee`
+//! client.
+
+use futures::future::BoxFuture;
+use futures::FutureExt as _;
+use jsonrpc_core::{Call, MethodCall, Params};
+use jsonrpsee::core::client::{BatchResponse, ClientT, Error as ClientError};
+use jsonrpsee::core::params::BatchRequestBuilder;
+use jsonrpsee::core::traits::ToRpcParams;
+use jsonrpsee::types::ErrorObject;
+use serde_json::value::RawValue;
+use serde_json::Value;
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use web3::error::{Error as Web3Error, TransportError};
+use web3::{helpers, BatchTransport, RequestId, Transport};
+
+/// A `web3` transport that sends requests with a `jsonrpsee` client.
+///
+/// This allows using contract bindings with the connection pooling and
+/// middleware of an existing `jsonrpsee` HTTP or WebSocket client, such as
+/// `jsonrpsee::http_client::HttpClient` or `jsonrpsee::ws_client::WsClient`.
+/// The client is shared between clones of the transport.
+///
+/// # Examples
+///
+/// ```ignore
+/// use ethcontract::transport::JsonRpseeTransport;
+/// use ethcontract::Web3;
+/// use jsonrpsee::http_client::HttpClientBuilder;
+///
+/// let client = HttpClientBuilder::default().build("http://localhost:8545")?;
+/// let web3 = Web3::new(JsonRpseeTransport::new(client));
+/// let instance = MyContract::at(&web3, address);
+/// ```
+#[derive(Debug)]
+pub struct JsonRpseeTransport<C> {
+    client: Arc<C>,
+    id: Arc<AtomicUsize>,
+}
+
+impl<C> JsonRpseeTransport<C> {
+    /// Creates a new transport from a `jsonrpsee` client.
+    pub fn new(client: C) -> Self {
+        JsonRpseeTransport {
+            client: Arc::new(client),
+            id: Default::default(),
+        }
+    }
+
+    /// Returns a reference to the underlying `jsonrpsee` client.
+    pub fn client(&self) -> &C {
+        &self.client
+    }
+}
+
+impl<C> Clone for JsonRpseeTransport<C> {
+    fn clone(&self) -> Self {
+        JsonRpseeTransport {
+            client: self.client.clone(),
+            id: self.id.clone(),
+        }
+    }
+}
+
+impl<C> Transport for JsonRpseeTransport<C>
+where
+    C: ClientT + Debug + Send + Sync + 'static,
+{
+    type Out = BoxFuture<'static, Result<Value, Web3Error>>;
+
+    fn prepare(&self, method: &str, params: Vec<Value>) -> (RequestId, Call) {
+        let id = self.id.fetch_add(1, Ordering::AcqRel);
+        (id, helpers::build_request(id, method, params))
+    }
+
+    fn send(&self, _: RequestId, request: Call) -> Self::Out {
+        let client = self.client.clone();
+        async move {
+            let (method, params) = method_call(request)?;
+            client
+                .request::<Value, _>(&method, RpcParams(params))
+                .await
+                .map_err(client_error)
+        }
+        .boxed()
+    }
+}
+
+impl<C> BatchTransport for JsonRpseeTransport<C>
+where
+    C: ClientT + Debug + Send + Sync + 'static,
+{
+    type Batch = BoxFuture<'static, Result<Vec<Result<Value, Web3Error>>, Web3Error>>;
+
+    fn send_batch<T>(&self, requests: T) -> Self::Batch
+    where
+        T: IntoIterator<Item = (RequestId, Call)>,
+    {
+        let client = self.client.clone();
+        let requests = requests
+            .into_iter()
+            .map(|(_, request)| method_call(request))
+            .collect::<Result<Vec<_>, _>>();
+
+        async move {
+            let requests = requests?;
+            let mut batch = BatchRequestBuilder::new();
+            for (method, params) in &requests {
+                batch
+                    .insert(method.as_str(), RpcParams(params.clone()))
+                    .map_err(|err| Web3Error::Decoder(err.to_string()))?;
+            }
+
+            let responses: BatchResponse<Value> =
+                client.batch_request(batch).await.map_err(client_error)?;
+            Ok(responses
+                .into_iter()
+                .map(|response| response.map_err(rpc_error))
+                .collect())
+        }
+        .boxed()
+    }
+}
+
+/// JSON RPC request parameters that can be sent with a `jsonrpsee` client.
+struct RpcParams(Params);
+
+impl ToRpcParams for RpcParams {
+    fn to_rpc_params(self) -> Result<Option<Box<RawValue>>, serde_json::Error> {
+        match self.0 {
+            Params::None => Ok(None),
+            params => serde_json::value::to_raw_value(&params).map(Some),
+        }
+    }
+}
+
+/// Extracts the method name and parameters from a JSON RPC method call.
+fn method_call(request: Call) -> Result<(String, Params), Web3Error> {
+    match request {
+        Call::MethodCall(MethodCall { method, params, .. }) => Ok((method, params)),
+        _ => Err(Web3Error::Transport(TransportError::Message(
+            "only method calls are supported by the jsonrpsee transport".to_owned(),
+        ))),
+    }
+}
+
+/// Converts a `jsonrpsee` client error into a `web3` error.
+fn client_error(err: ClientError) -> Web3Error {
+    match err {
+        ClientError::Call(err) => rpc_error(err),
+        ClientError::ParseError(err) => Web3Error::InvalidResponse(err.to_string()),
+        err => Web3Error::Transport(TransportError::Message(err.to_string())),
+    }
+}
+
+/// Converts a JSON RPC error object into a `web3` RPC error.
+fn rpc_error(err: ErrorObject) -> Web3Error {
+    Web3Error::Rpc(jsonrpc_core::Error {
+        code: (err.code() as i64).into(),
+        message: err.message().to_owned(),
+        data: err
+            .data()
+            .and_then(|data| serde_json::from_str(data.get()).ok()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::prelude::*;
+    use jsonrpsee::core::client::BatchEntry;
+    use serde::de::DeserializeOwned;
+    use std::fmt;
+    use std::sync::Mutex;
+
+    /// A `jsonrpsee` client that records requests and responds with the
+    /// method name and parameters, or an error for the `fail` method.
+    #[derive(Debug, Default)]
+    struct EchoClient {
+        requests: Mutex<Vec<(String, Option<String>)>>,
+    }
+
+    impl EchoClient {
+        fn respond(
+            &self,
+            method: &str,
+            params: Option<Box<RawValue>>,
+        ) -> BatchEntry<'static, Value> {
+            let params = params.map(|params| params.get().to_owned());
+            self.requests
+                .lock()
+                .unwrap()
+                .push((method.to_owned(), params.clone()));
+
+            if method == "fail" {
+                return Err(ErrorObject::owned(-32000, "execution reverted", Some("0x")));
+            }
+            let params = params.map(|params| serde_json::from_str::<Value>(&params).unwrap());
+            Ok(json!([method, params]))
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl ClientT for EchoClient {
+        async fn notification<Params>(&self, _: &str, _: Params) -> Result<(), ClientError>
+        where
+            Params: ToRpcParams + Send,
+        {
+            unimplemented!()
+        }
+
+        async fn request<R, Params>(&self, method: &str, params: Params) -> Result<R, ClientError>
+        where
+            R: DeserializeOwned,
+            Params: ToRpcParams + Send,
+        {
+            let response = self.respond(method, params.to_rpc_params()?)?;
+            Ok(serde_json::from_value(response)?)
+        }
+
+        async fn batch_request<'a, R>(
+            &self,
+            batch: BatchRequestBuilder<'a>,
+        ) -> Result<BatchResponse<'a, R>, ClientError>
+        where
+            R: DeserializeOwned + fmt::Debug + 'a,
+        {
+            let responses = batch
+                .build()?
+                .into_iter()
+                .map(|(method, params)| {
+                    self.respond(method, params)
+                        .map(|response| serde_json::from_value(response).unwrap())
+                })
+                .collect::<Vec<_>>();
+            let failed = responses
+                .iter()
+                .filter(|response| response.is_err())
+                .count();
+            Ok(BatchResponse::new(
+                responses.len() - failed,
+                responses,
+                failed,
+            ))
+        }
+    }
+
+    #[test]
+    fn send_requests() {
+        let transport = JsonRpseeTransport::new(EchoClient::default());
+
+        let response = transport
+            .execute("eth_blockNumber", vec![])
+            .immediate()
+            .unwrap();
+        assert_eq!(response, json!(["eth_blockNumber", []]));
+
+        let response = transport
+            .execute("eth_getBalance", vec![json!("0x01"), json!("latest")])
+            .immediate()
+            .unwrap();
+        assert_eq!(response, json!(["eth_getBalance", ["0x01", "latest"]]));
+
+        let err = transport.execute("fail", vec![]).immediate().unwrap_err();
+        assert!(matches!(
+            err,
+            Web3Error::Rpc(err) if err.message == "execution reverted" && err.data == Some(json!("0x")),
+        ));
+    }
+
+    #[test]
+    fn send_batch_requests() {
+        let transport = JsonRpseeTransport::new(EchoClient::default());
+
+        let requests = vec![
+            transport.prepare("eth_chainId", vec![]),
+            transport.prepare("fail", vec![json!(42)]),
+        ];
+        let responses = transport.send_batch(requests).immediate().unwrap();
+
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0].as_ref().unwrap(), &json!(["eth_chainId", []]));
+        assert!(matches!(responses[1], Err(Web3Error::Rpc(_))));
+        assert_eq!(
+            *transport.client().requests.lock().unwrap(),
+            [
+                ("eth_chainId".to_owned(), Some("[]".to_owned())),
+                ("fail".to_owned(), Some("[42]".to_owned())),
+            ],
+        );
+    }
+}
@@ -0,0 +1,409 @@
+//! Instrumentation of JSON-RPC requests and batch payload sizes.
+//!
+//! Reporting the method, duration and outcome of every request allows
+//! exporting node metrics and structured logs without implementing a custom
+//! transport.
+//!
+//! Providers commonly limit the size of JSON-RPC batches, both in number of
+//! requests and in bytes. Reporting the serialized sizes of batches allows
+//! tuning batch sizes, such as the block page size used for querying past
+//! events, against these limits.
+
+use super::{BoxedBatch, BoxedFuture, DynTransport, TransportBoxed};
+use futures::FutureExt as _;
+use jsonrpc_core::{Call, Request};
+use serde_json::Value;
+use std::any::Any;
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use web3::RequestId;
+
+/// Metrics of a single JSON-RPC request.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RequestMetrics {
+    /// The JSON-RPC method of the request.
+    pub method: String,
+    /// The time from sending the request until receiving its response. For
+    /// batched requests, this is the duration of the whole batch.
+    pub duration: Duration,
+    /// The size in bytes of the serialized request.
+    pub request_bytes: usize,
+    /// Whether the request was sent as part of a batch.
+    pub batched: bool,
+    /// Whether the request succeeded.
+    pub success: bool,
+}
+
+/// Payload size metrics of a single JSON-RPC batch.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct BatchMetrics {
+    /// The number of requests in the batch.
+    pub requests: usize,
+    /// The size in bytes of the serialized batch request.
+    pub request_bytes: usize,
+    /// The total size in bytes of the serialized results in the batch
+    /// response, or `None` if the batch as a whole failed. Note that this does
+    /// not include the JSON-RPC envelope of each response.
+    pub response_bytes: Option<usize>,
+    /// The number of requests in the batch that returned an error.
+    pub errors: usize,
+}
+
+/// A hook invoked with the metrics of every request sent over a transport.
+type RequestMetricsHook = Arc<dyn Fn(&RequestMetrics) + Send + Sync>;
+
+/// A hook invoked with the metrics of every batch sent over a transport.
+type BatchMetricsHook = Arc<dyn Fn(&BatchMetrics) + Send + Sync>;
+
+impl DynTransport {
+    /// Instruments the transport to invoke the specified hook with the
+    /// metrics of every JSON-RPC request that is sent over it, once its
+    /// response is received.
+    ///
+    /// Requests sent in a batch are reported individually, with the duration
+    /// of the batch. Batch failures are reported as failures of all of its
+    /// requests.
+    pub fn with_metrics<F>(self, hook: F) -> Self
+    where
+        F: Fn(&RequestMetrics) + Send + Sync + 'static,
+    {
+        DynTransport {
+            inner: Arc::new(RequestMetricsTransport {
+                inner: self.inner,
+                hook: Arc::new(hook),
+            }),
+        }
+    }
+
+    /// Instruments the transport to invoke the specified hook with the payload
+    /// size metrics of every JSON-RPC batch that is sent over it.
+    ///
+    /// Single requests are not reported.
+    pub fn with_batch_metrics<F>(self, hook: F) -> Self
+    where
+        F: Fn(&BatchMetrics) + Send + Sync + 'static,
+    {
+        DynTransport {
+            inner: Arc::new(BatchMetricsTransport {
+                inner: self.inner,
+                hook: Arc::new(hook),
+            }),
+        }
+    }
+}
+
+/// A transport layer that reports request metrics.
+struct RequestMetricsTransport {
+    inner: Arc<dyn TransportBoxed>,
+    hook: RequestMetricsHook,
+}
+
+impl Debug for RequestMetricsTransport {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("RequestMetricsTransport")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TransportBoxed for RequestMetricsTransport {
+    fn prepare_boxed(&self, method: &str, params: Vec<Value>) -> (RequestId, Call) {
+        self.inner.prepare_boxed(method, params)
+    }
+
+    fn send_boxed(&self, id: RequestId, request: Call) -> BoxedFuture {
+        let mut metrics = RequestMetrics {
+            method: method_name(&request).to_owned(),
+            duration: Duration::ZERO,
+            request_bytes: serialized_len(&request),
+            batched: false,
+            success: false,
+        };
+
+        let hook = self.hook.clone();
+        let response = self.inner.send_boxed(id, request);
+        async move {
+            let start = Instant::now();
+            let result = response.await;
+            metrics.duration = start.elapsed();
+            metrics.success = result.is_ok();
+            hook(&metrics);
+            result
+        }
+        .boxed()
+    }
+
+    fn execute_boxed(&self, method: &str, params: Vec<Value>) -> BoxedFuture {
+        let (id, request) = self.prepare_boxed(method, params);
+        self.send_boxed(id, request)
+    }
+
+    fn send_batch_boxed(&self, requests: Vec<(RequestId, Call)>) -> BoxedBatch {
+        let mut metrics = requests
+            .iter()
+            .map(|(_, request)| RequestMetrics {
+                method: method_name(request).to_owned(),
+                duration: Duration::ZERO,
+                request_bytes: serialized_len(request),
+                batched: true,
+                success: false,
+            })
+            .collect::<Vec<_>>();
+
+        let hook = self.hook.clone();
+        let responses = self.inner.send_batch_boxed(requests);
+        async move {
+            let start = Instant::now();
+            let result = responses.await;
+            let duration = start.elapsed();
+            for (i, metrics) in metrics.iter_mut().enumerate() {
+                metrics.duration = duration;
+                metrics.success = match &result {
+                    Ok(responses) => matches!(responses.get(i), Some(Ok(_))),
+                    Err(_) => false,
+                };
+                hook(metrics);
+            }
+            result
+        }
+        .boxed()
+    }
+
+    fn inner(&self) -> &(dyn Any + Send + Sync) {
+        self.inner.inner()
+    }
+}
+
+/// A transport layer that reports batch payload size metrics.
+struct BatchMetricsTransport {
+    inner: Arc<dyn TransportBoxed>,
+    hook: BatchMetricsHook,
+}
+
+impl Debug for BatchMetricsTransport {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("BatchMetricsTransport")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TransportBoxed for BatchMetricsTransport {
+    fn prepare_boxed(&self, method: &str, params: Vec<Value>) -> (RequestId, Call) {
+        self.inner.prepare_boxed(method, params)
+    }
+
+    fn send_boxed(&self, id: RequestId, request: Call) -> BoxedFuture {
+        self.inner.send_boxed(id, request)
+    }
+
+    fn execute_boxed(&self, method: &str, params: Vec<Value>) -> BoxedFuture {
+        self.inner.execute_boxed(method, params)
+    }
+
+    fn send_batch_boxed(&self, requests: Vec<(RequestId, Call)>) -> BoxedBatch {
+        let calls = requests
+            .iter()
+            .map(|(_, call)| call.clone())
+            .collect::<Vec<_>>();
+        let mut metrics = BatchMetrics {
+            requests: calls.len(),
+            request_bytes: serialized_len(&Request::Batch(calls)),
+            ..Default::default()
+        };
+
+        let hook = self.hook.clone();
+        self.inner
+            .send_batch_boxed(requests)
+            .inspect(move |result| {
+                if let Ok(responses) = result {
+                    metrics.response_bytes = Some(
+                        responses
+                            .iter()
+                            .filter_map(|response| response.as_ref().ok())
+                            .map(serialized_len)
+                            .sum(),
+                    );
+                    metrics.errors = responses
+                        .iter()
+                        .filter(|response| response.is_err())
+                        .count();
+                }
+                hook(&metrics);
+            })
+            .boxed()
+    }
+
+    fn inner(&self) -> &(dyn Any + Send + Sync) {
+        self.inner.inner()
+    }
+}
+
+/// Returns the JSON-RPC method of a request.
+fn method_name(request: &Call) -> &str {
+    match request {
+        Call::MethodCall(call) => &call.method,
+        Call::Notification(notification) => &notification.method,
+        Call::Invalid { .. } => "",
+    }
+}
+
+/// Returns the length of the JSON serialization of a value.
+fn serialized_len<T: serde::Serialize>(value: &T) -> usize {
+    serde_json::to_vec(value)
+        .map(|bytes| bytes.len())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::prelude::*;
+    use std::sync::Mutex;
+    use web3::{BatchTransport as _, Transport as _};
+
+    fn instrumented(transport: &TestTransport) -> (DynTransport, Arc<Mutex<Vec<BatchMetrics>>>) {
+        let reported = Arc::new(Mutex::new(Vec::new()));
+        let transport = DynTransport::new(transport.clone()).with_batch_metrics({
+            let reported = reported.clone();
+            move |metrics| reported.lock().unwrap().push(*metrics)
+        });
+
+        (transport, reported)
+    }
+
+    fn instrumented_requests(
+        transport: &TestTransport,
+    ) -> (DynTransport, Arc<Mutex<Vec<RequestMetrics>>>) {
+        let reported = Arc::new(Mutex::new(Vec::new()));
+        let transport = DynTransport::new(transport.clone()).with_metrics({
+            let reported = reported.clone();
+            move |metrics| reported.lock().unwrap().push(metrics.clone())
+        });
+
+        (transport, reported)
+    }
+
+    #[test]
+    fn reports_requests() {
+        let mut transport = TestTransport::new();
+        let (dyn_transport, reported) = instrumented_requests(&transport);
+
+        transport.add_response(json!("0x2a"));
+        dyn_transport
+            .execute("eth_blockNumber", vec![])
+            .immediate()
+            .unwrap();
+        dyn_transport
+            .execute("eth_chainId", vec![])
+            .immediate()
+            .unwrap_err();
+
+        let reported = reported.lock().unwrap();
+        assert_eq!(reported.len(), 2);
+        assert_eq!(reported[0].method, "eth_blockNumber");
+        assert_eq!(
+            reported[0].request_bytes,
+            serialized_len(&dyn_transport.prepare("eth_blockNumber", vec![]).1)
+        );
+        assert!(!reported[0].batched);
+        assert!(reported[0].success);
+        assert_eq!(reported[1].method, "eth_chainId");
+        assert!(!reported[1].success);
+    }
+
+    #[test]
+    fn reports_batched_requests() {
+        let mut transport = TestTransport::new();
+        let (dyn_transport, reported) = instrumented_requests(&transport);
+        let batches = Arc::new(Mutex::new(0));
+        let dyn_transport = dyn_transport.with_batch_metrics({
+            let batches = batches.clone();
+            move |_| *batches.lock().unwrap() += 1
+        });
+
+        let requests = vec![
+            dyn_transport.prepare("eth_blockNumber", vec![]),
+            dyn_transport.prepare("eth_chainId", vec![]),
+        ];
+        transport.add_response(json!(["0x2a", "0x1"]));
+        dyn_transport.send_batch(requests).immediate().unwrap();
+
+        let reported = reported.lock().unwrap();
+        assert_eq!(
+            reported
+                .iter()
+                .map(|metrics| (metrics.method.as_str(), metrics.batched, metrics.success))
+                .collect::<Vec<_>>(),
+            vec![("eth_blockNumber", true, true), ("eth_chainId", true, true)]
+        );
+        assert_eq!(reported[0].duration, reported[1].duration);
+        assert_eq!(*batches.lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn reports_batch_sizes() {
+        let mut transport = TestTransport::new();
+        let (dyn_transport, reported) = instrumented(&transport);
+
+        let requests = vec![
+            dyn_transport.prepare("eth_blockNumber", vec![]),
+            dyn_transport.prepare("eth_chainId", vec![]),
+        ];
+        let request_bytes = serde_json::to_vec(&Request::Batch(
+            requests.iter().map(|(_, call)| call.clone()).collect(),
+        ))
+        .unwrap()
+        .len();
+
+        transport.add_response(json!(["0x2a", "0x1"]));
+        dyn_transport.send_batch(requests).immediate().unwrap();
+
+        assert_eq!(
+            *reported.lock().unwrap(),
+            vec![BatchMetrics {
+                requests: 2,
+                request_bytes,
+                response_bytes: Some(br#""0x2a""#.len() + br#""0x1""#.len()),
+                errors: 0,
+            }]
+        );
+    }
+
+    #[test]
+    fn reports_failed_batches() {
+        let transport = TestTransport::new();
+        let (dyn_transport, reported) = instrumented(&transport);
+
+        let requests = vec![dyn_transport.prepare("eth_blockNumber", vec![])];
+        dyn_transport.send_batch(requests).immediate().unwrap_err();
+
+        let reported = reported.lock().unwrap();
+        assert_eq!(reported.len(), 1);
+        assert_eq!(reported[0].requests, 1);
+        assert_eq!(reported[0].response_bytes, None);
+    }
+
+    #[test]
+    fn does_not_report_single_requests() {
+        let mut transport = TestTransport::new();
+        let (dyn_transport, reported) = instrumented(&transport);
+
+        transport.add_response(json!("0x2a"));
+        dyn_transport
+            .execute("eth_blockNumber", vec![])
+            .immediate()
+            .unwrap();
+
+        assert!(reported.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn instrumented_transport_is_downcastable() {
+        let transport = TestTransport::new();
+        let (dyn_transport, _) = instrumented(&transport);
+
+        assert!(dyn_transport.downcast::<TestTransport>().is_some());
+    }
+}
@@ -0,0 +1,486 @@
+//! Transport middleware that retries requests failing with transient errors.
+
+use crate::transaction::confirm::delay;
+use futures::future::BoxFuture;
+use futures::FutureExt as _;
+use jsonrpc_core::Call;
+use serde_json::Value;
+use std::future::Future;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use web3::error::{Error as Web3Error, TransportError};
+use web3::{BatchTransport, RequestId, Transport};
+
+/// The JSON-RPC error code used by nodes and providers for rate limiting.
+const LIMIT_EXCEEDED: i64 = -32005;
+
+/// The HTTP status code for rate limiting.
+const TOO_MANY_REQUESTS: u16 = 429;
+
+/// Methods that have side effects every time a request is processed, such as
+/// broadcasting a transaction, and must not be resent blindly.
+const NON_IDEMPOTENT_METHODS: &[&str] = &[
+    "eth_sendTransaction",
+    "eth_sendRawTransaction",
+    "personal_sendTransaction",
+];
+
+/// A transport that retries requests failing with transient errors, such as
+/// connection failures and rate limiting, with exponential backoff.
+///
+/// Errors returned by the node for valid requests, such as reverts, are never
+/// retried. For batches, only failures of the batch as a whole are retried,
+/// errors of individual requests in a batch are returned as is.
+///
+/// Requests that send transactions, and batches containing them, are only
+/// retried if the node rejected them without processing them, for example
+/// because of rate limiting. Other errors such as timeouts or failures to read
+/// the response can happen after the node already accepted the transaction,
+/// in which case retrying it would broadcast it again, for example with a
+/// different nonce if the node signs it. Retrying these errors for sent
+/// transactions can be enabled with
+/// [`retry_send_transactions`](Self::retry_send_transactions).
+///
+/// The transport can be wrapped in a `DynTransport` to be used with generated
+/// contract bindings.
+#[derive(Clone, Debug)]
+pub struct RetryTransport<T> {
+    inner: Arc<T>,
+    policy: RetryPolicy,
+}
+
+/// The retry configuration of a `RetryTransport`.
+#[derive(Clone, Debug)]
+struct RetryPolicy {
+    max_retries: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    backoff_multiplier: f64,
+    budget: Option<Arc<RetryBudget>>,
+    retry_send_transactions: bool,
+}
+
+/// A budget limiting the number of retries across all requests.
+#[derive(Debug)]
+struct RetryBudget {
+    capacity: u32,
+    tokens: AtomicU32,
+}
+
+impl<T> RetryTransport<T> {
+    /// Wraps a transport, retrying failed requests up to 3 times with an
+    /// initial backoff of 100 milliseconds that doubles with each retry, up to
+    /// 10 seconds.
+    pub fn new(inner: T) -> Self {
+        RetryTransport {
+            inner: Arc::new(inner),
+            policy: RetryPolicy {
+                max_retries: 3,
+                initial_backoff: Duration::from_millis(100),
+                max_backoff: Duration::from_secs(10),
+                backoff_multiplier: 2.0,
+                budget: None,
+                retry_send_transactions: false,
+            },
+        }
+    }
+
+    /// Sets the maximum number of times a single request is retried.
+    pub fn max_retries(mut self, value: u32) -> Self {
+        self.policy.max_retries = value;
+        self
+    }
+
+    /// Sets the delay before the first retry of a request.
+    pub fn initial_backoff(mut self, value: Duration) -> Self {
+        self.policy.initial_backoff = value;
+        self
+    }
+
+    /// Sets the maximum delay between retries of a request.
+    pub fn max_backoff(mut self, value: Duration) -> Self {
+        self.policy.max_backoff = value;
+        self
+    }
+
+    /// Sets the factor by which the delay is multiplied after each retry.
+    pub fn backoff_multiplier(mut self, value: f64) -> Self {
+        self.policy.backoff_multiplier = value;
+        self
+    }
+
+    /// Limits the number of retries across all requests sent over this
+    /// transport and its clones, so that retries don't amplify the load on an
+    /// overloaded node.
+    ///
+    /// Every retry consumes a token from the budget, and every request that
+    /// succeeds without being retried returns one, up to the capacity. Once
+    /// the budget is exhausted, failed requests are no longer retried.
+    pub fn retry_budget(mut self, capacity: u32) -> Self {
+        self.policy.budget = Some(Arc::new(RetryBudget {
+            capacity,
+            tokens: AtomicU32::new(capacity),
+        }));
+        self
+    }
+
+    /// Specifies whether requests sending transactions are retried on all
+    /// transient errors, and not only when the node rejected them. This is
+    /// only safe if sending a transaction twice has no effect, for example
+    /// when only sending signed raw transactions.
+    pub fn retry_send_transactions(mut self, value: bool) -> Self {
+        self.policy.retry_send_transactions = value;
+        self
+    }
+
+    /// Returns a reference to the inner transport.
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl RetryPolicy {
+    /// Runs a request with the specified calls, retrying it while it fails
+    /// with transient errors.
+    async fn run<'a, F, Fut, R>(
+        &self,
+        calls: impl IntoIterator<Item = &'a Call>,
+        mut request: F,
+    ) -> Result<R, Web3Error>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<R, Web3Error>>,
+    {
+        let idempotent = self.retry_send_transactions || is_idempotent(calls);
+        let mut retries = 0;
+        let mut backoff = self.initial_backoff;
+        loop {
+            match request().await {
+                Err(err)
+                    if retries < self.max_retries
+                        && is_transient(&err)
+                        && (idempotent || is_rejected(&err))
+                        && self.withdraw_retry() =>
+                {
+                    delay(backoff).await;
+                    retries += 1;
+                    backoff = Duration::try_from_secs_f64(
+                        backoff.as_secs_f64() * self.backoff_multiplier,
+                    )
+                    .unwrap_or(self.max_backoff)
+                    .min(self.max_backoff);
+                }
+                result => {
+                    if result.is_ok() && retries == 0 {
+                        self.deposit_retry();
+                    }
+                    return result;
+                }
+            }
+        }
+    }
+
+    /// Takes a token from the retry budget, returning `false` if it is
+    /// exhausted.
+    fn withdraw_retry(&self) -> bool {
+        match &self.budget {
+            Some(budget) => budget
+                .tokens
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |tokens| {
+                    tokens.checked_sub(1)
+                })
+                .is_ok(),
+            None => true,
+        }
+    }
+
+    /// Returns a token to the retry budget.
+    fn deposit_retry(&self) {
+        if let Some(budget) = &self.budget {
+            let _ = budget
+                .tokens
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |tokens| {
+                    (tokens < budget.capacity).then_some(tokens + 1)
+                });
+        }
+    }
+}
+
+/// Returns `true` if a request failed with an error that is likely to be
+/// resolved by retrying it.
+pub(super) fn is_transient(err: &Web3Error) -> bool {
+    match err {
+        Web3Error::Unreachable => true,
+        Web3Error::Transport(TransportError::Code(code)) => {
+            matches!(*code, TOO_MANY_REQUESTS | 502 | 503 | 504)
+        }
+        Web3Error::Transport(TransportError::Message(message)) => {
+            let message = message.to_lowercase();
+            [
+                "failed to send request",
+                "failed to read response",
+                "connection reset",
+                "connection closed",
+                "timed out",
+            ]
+            .iter()
+            .any(|needle| message.contains(needle))
+        }
+        Web3Error::Rpc(_) => is_rejected(err),
+        _ => false,
+    }
+}
+
+/// Returns `true` if a request failed with an error indicating that the node
+/// rejected it without processing it, so that it can be safely resent.
+pub(super) fn is_rejected(err: &Web3Error) -> bool {
+    match err {
+        Web3Error::Transport(TransportError::Code(code)) => *code == TOO_MANY_REQUESTS,
+        Web3Error::Rpc(err) => {
+            err.code.code() == LIMIT_EXCEEDED
+                || err.code.code() == TOO_MANY_REQUESTS as i64
+                || err.message.to_lowercase().contains("rate limit")
+        }
+        _ => false,
+    }
+}
+
+/// Returns `true` if none of the calls are to methods with side effects, such
+/// as sending transactions, so that they can be sent more than once.
+pub(super) fn is_idempotent<'a>(calls: impl IntoIterator<Item = &'a Call>) -> bool {
+    calls.into_iter().all(|call| {
+        let method = match call {
+            Call::MethodCall(call) => &call.method,
+            Call::Notification(notification) => &notification.method,
+            Call::Invalid { .. } => return true,
+        };
+        !NON_IDEMPOTENT_METHODS.contains(&method.as_str())
+    })
+}
+
+impl<T> Transport for RetryTransport<T>
+where
+    T: Transport + Send + Sync + 'static,
+    T::Out: Send,
+{
+    type Out = BoxFuture<'static, Result<Value, Web3Error>>;
+
+    fn prepare(&self, method: &str, params: Vec<Value>) -> (RequestId, Call) {
+        self.inner.prepare(method, params)
+    }
+
+    fn send(&self, id: RequestId, request: Call) -> Self::Out {
+        let inner = self.inner.clone();
+        let policy = self.policy.clone();
+        async move {
+            policy
+                .run([&request], || inner.send(id, request.clone()))
+                .await
+        }
+        .boxed()
+    }
+}
+
+impl<T> BatchTransport for RetryTransport<T>
+where
+    T: BatchTransport + Send + Sync + 'static,
+    T::Out: Send,
+    T::Batch: Send,
+{
+    type Batch = BoxFuture<'static, Result<Vec<Result<Value, Web3Error>>, Web3Error>>;
+
+    fn send_batch<I>(&self, requests: I) -> Self::Batch
+    where
+        I: IntoIterator<Item = (RequestId, Call)>,
+    {
+        let inner = self.inner.clone();
+        let policy = self.policy.clone();
+        let requests = requests.into_iter().collect::<Vec<_>>();
+        async move {
+            policy
+                .run(requests.iter().map(|(_, call)| call), || {
+                    inner.send_batch(requests.clone())
+                })
+                .await
+        }
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::prelude::*;
+    use crate::transport::DynTransport;
+
+    fn retrying(transport: &TestTransport) -> RetryTransport<TestTransport> {
+        RetryTransport::new(transport.clone()).initial_backoff(Duration::ZERO)
+    }
+
+    fn rate_limited() -> Web3Error {
+        Web3Error::Rpc(jsonrpc_core::Error {
+            code: LIMIT_EXCEEDED.into(),
+            message: "request rate exceeded".to_owned(),
+            data: None,
+        })
+    }
+
+    #[test]
+    fn retries_transient_errors() {
+        let mut transport = TestTransport::new();
+        let retrying = retrying(&transport);
+
+        transport.add_error(Web3Error::Transport(TransportError::Code(429)));
+        transport.add_error(Web3Error::Transport(TransportError::Message(
+            "failed to send request: connection reset by peer".to_owned(),
+        )));
+        transport.add_error(rate_limited());
+        transport.add_response(json!("0x2a"));
+        let result = retrying
+            .execute("eth_blockNumber", vec![])
+            .immediate()
+            .unwrap();
+
+        assert_eq!(result, json!("0x2a"));
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn does_not_retry_other_errors() {
+        let mut transport = TestTransport::new();
+        let retrying = retrying(&transport);
+
+        transport.add_error(Web3Error::Rpc(jsonrpc_core::Error {
+            code: 3.into(),
+            message: "execution reverted".to_owned(),
+            data: None,
+        }));
+        transport.add_response(json!("0x2a"));
+        let result = retrying.execute("eth_call", vec![]).immediate();
+
+        assert!(matches!(result, Err(Web3Error::Rpc(_))));
+    }
+
+    #[test]
+    fn gives_up_after_max_retries() {
+        let mut transport = TestTransport::new();
+        let retrying = retrying(&transport).max_retries(1);
+
+        transport.add_error(rate_limited());
+        transport.add_error(rate_limited());
+        transport.add_response(json!("0x2a"));
+        let result = retrying.execute("eth_blockNumber", vec![]).immediate();
+
+        assert!(matches!(result, Err(Web3Error::Rpc(_))));
+    }
+
+    #[test]
+    fn retry_budget_is_shared() {
+        let mut transport = TestTransport::new();
+        let retrying = retrying(&transport).retry_budget(1);
+
+        transport.add_error(rate_limited());
+        transport.add_response(json!("0x1"));
+        transport.add_error(rate_limited());
+        transport.add_response(json!("0x2"));
+
+        let first = retrying.clone().execute("eth_blockNumber", vec![]);
+        assert_eq!(first.immediate().unwrap(), json!("0x1"));
+        let second = retrying.execute("eth_blockNumber", vec![]);
+        assert!(second.immediate().is_err());
+    }
+
+    #[test]
+    fn retries_failed_batches() {
+        let mut transport = TestTransport::new();
+        let retrying = retrying(&transport);
+
+        transport.add_error(Web3Error::Transport(TransportError::Code(503)));
+        transport.add_response(json!(["0x1", "0x2"]));
+        let requests = vec![
+            retrying.prepare("eth_blockNumber", vec![]),
+            retrying.prepare("eth_chainId", vec![]),
+        ];
+        let results = retrying.send_batch(requests).immediate().unwrap();
+
+        assert_eq!(results.len(), 2);
+    }
+
+    #[test]
+    fn only_retries_rejected_transactions() {
+        let mut transport = TestTransport::new();
+        let retrying = retrying(&transport);
+
+        // the node may have already broadcast the transaction
+        transport.add_error(Web3Error::Transport(TransportError::Message(
+            "failed to read response: timed out".to_owned(),
+        )));
+        transport.add_response(json!("0x42"));
+        let result = retrying
+            .execute("eth_sendTransaction", vec![json!({})])
+            .immediate();
+        assert!(matches!(result, Err(Web3Error::Transport(_))));
+        transport.assert_request("eth_sendTransaction", &[json!({})]);
+        transport.assert_no_more_requests();
+
+        // the node rejected the transaction without processing it
+        transport.add_error(rate_limited());
+        let result = retrying
+            .execute("eth_sendRawTransaction", vec![json!("0x")])
+            .immediate();
+        assert_eq!(result.unwrap(), json!("0x42"));
+    }
+
+    #[test]
+    fn does_not_retry_batches_with_transactions() {
+        let mut transport = TestTransport::new();
+        let retrying = retrying(&transport);
+
+        transport.add_error(Web3Error::Transport(TransportError::Code(504)));
+        transport.add_response(json!(["0x1", "0x2"]));
+        let requests = vec![
+            retrying.prepare("eth_blockNumber", vec![]),
+            retrying.prepare("eth_sendRawTransaction", vec![json!("0x")]),
+        ];
+        let result = retrying.send_batch(requests).immediate();
+        assert!(result.is_err());
+
+        // the batch was not resent
+        let result = retrying.execute("eth_blockNumber", vec![]).immediate();
+        assert_eq!(result.unwrap(), json!(["0x1", "0x2"]));
+    }
+
+    #[test]
+    fn retries_transactions_if_enabled() {
+        let mut transport = TestTransport::new();
+        let retrying = retrying(&transport).retry_send_transactions(true);
+
+        transport.add_error(Web3Error::Transport(TransportError::Code(502)));
+        transport.add_response(json!("0x42"));
+        let result = retrying
+            .execute("eth_sendRawTransaction", vec![json!("0x")])
+            .immediate();
+
+        assert_eq!(result.unwrap(), json!("0x42"));
+    }
+
+    #[test]
+    fn can_be_used_as_dyn_transport() {
+        let mut transport = TestTransport::new();
+        let dyn_transport = DynTransport::new(retrying(&transport));
+
+        transport.add_error(Web3Error::Unreachable);
+        transport.add_response(json!("0x2a"));
+        let result = dyn_transport
+            .execute("eth_blockNumber", vec![])
+            .immediate()
+            .unwrap();
+
+        assert_eq!(result, json!("0x2a"));
+        assert!(dyn_transport
+            .downcast::<RetryTransport<TestTransport>>()
+            .is_some());
+    }
+}
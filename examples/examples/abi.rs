@@ -61,11 +61,13 @@ async fn calls(instance: &AbiTypes) {
     debug_call!(instance.get_array());
     debug_call!(instance.get_fixed_array());
 
-    let value = (4, 2);
-    let result = instance.abiv_2_struct(value).call().await.unwrap();
+    let s = |u0, u1| abi_types::structs::S { u_0: u0, u_1: u1 };
+
+    let value = s(4, 2);
+    let result = instance.abiv_2_struct(value.clone()).call().await.unwrap();
     assert_eq!(result, value);
 
-    let value = vec![(4, 2), (5, 3)];
+    let value = vec![s(4, 2), s(5, 3)];
     let result = instance
         .abiv_2_array_of_struct(value.clone())
         .call()
@@ -73,7 +75,7 @@ async fn calls(instance: &AbiTypes) {
         .unwrap();
     assert_eq!(result, value);
 
-    let value = [vec![(4, 2)], vec![(5, 3), (6, 4)], vec![]];
+    let value = [vec![s(4, 2)], vec![s(5, 3), s(6, 4)], vec![]];
     let result = instance
         .abiv_2_array_of_array_of_struct(value.clone())
         .call()
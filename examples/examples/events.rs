@@ -19,7 +19,7 @@ async fn main() {
     let mut transfers = instance
         .events()
         .transfer()
-        .from(Topic::This(accounts[0]))
+        .from(accounts[0])
         .stream()
         .boxed();
 
@@ -1,20 +1,21 @@
 use ethcontract_generate::loaders::HardHatLoader;
-use ethcontract_generate::ContractBuilder;
+use ethcontract_generate::{ContractBuilder, ModuleBindings};
 
 fn main() {
     let out_dir = std::env::var("OUT_DIR").unwrap();
-    let dest = std::path::Path::new(&out_dir).join("contracts.rs");
+    let dest = std::path::Path::new(&out_dir).join("contracts");
 
     let artifact = HardHatLoader::new()
         .deny_network_by_name("localhost")
         .load_from_directory("../hardhat/deployments")
         .unwrap();
 
+    let mut bindings = ModuleBindings::new();
     for contract in artifact.iter() {
-        ContractBuilder::new()
-            .generate(contract)
-            .unwrap()
-            .write_to_file(&dest)
-            .unwrap();
+        bindings = bindings.add(
+            contract.name.to_lowercase(),
+            ContractBuilder::new().generate(contract).unwrap(),
+        );
     }
+    bindings.write_to_dir(dest).unwrap();
 }
@@ -1,7 +1,7 @@
 use ethcontract::prelude::*;
 use std::env;
 
-include!(concat!(env!("OUT_DIR"), "/contracts.rs"));
+include!(concat!(env!("OUT_DIR"), "/contracts/mod.rs"));
 
 const RINKEBY_CHAIN_ID: u64 = 4;
 
@@ -0,0 +1,29 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/**
+ * @dev Contract with a constructor taking ABIv2 struct parameters.
+ */
+contract StructConstructor {
+  struct Fee {
+    uint256 amount;
+  }
+
+  struct Order {
+    address owner;
+    Fee[] fees;
+  }
+
+  address public owner;
+  uint256 public totalFees;
+
+  constructor(Order memory order, Fee[] memory extraFees) {
+    owner = order.owner;
+    for (uint256 i = 0; i < order.fees.length; i++) {
+      totalFees += order.fees[i].amount;
+    }
+    for (uint256 i = 0; i < extraFees.length; i++) {
+      totalFees += extraFees[i].amount;
+    }
+  }
+}